@@ -0,0 +1,72 @@
+//! Vector search latency at 10k vectors: linear scan vs. HNSW.
+//!
+//! Run with `cargo bench --bench vector_search`.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use hippos::index::vector::{MemoryVectorIndex, pseudo_random_vectors};
+use hippos::index::{HnswConfig, VectorIndex, VectorMetadata};
+use tokio::runtime::Runtime;
+
+const DIMENSION: usize = 384;
+const VECTOR_COUNT: usize = 10_000;
+const SESSION_ID: &str = "bench_session";
+
+fn build_index(rt: &Runtime, index: MemoryVectorIndex, vectors: &[Vec<f32>]) -> MemoryVectorIndex {
+    rt.block_on(async {
+        for (i, vector) in vectors.iter().enumerate() {
+            let metadata = VectorMetadata {
+                session_id: SESSION_ID.to_string(),
+                turn_id: format!("turn_{}", i),
+                turn_number: i as u64,
+                timestamp: Utc::now(),
+                extra: HashMap::new(),
+            };
+            index
+                .add(&format!("vec_{}", i), vector, metadata)
+                .await
+                .unwrap();
+        }
+    });
+    index
+}
+
+fn bench_vector_search(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let vectors = pseudo_random_vectors(VECTOR_COUNT, DIMENSION, 42);
+    let queries = pseudo_random_vectors(100, DIMENSION, 7);
+
+    let indexes = [
+        (
+            "linear",
+            build_index(&rt, MemoryVectorIndex::new(DIMENSION), &vectors),
+        ),
+        (
+            "hnsw",
+            build_index(
+                &rt,
+                MemoryVectorIndex::with_hnsw(DIMENSION, HnswConfig::default()),
+                &vectors,
+            ),
+        ),
+    ];
+
+    let mut group = c.benchmark_group("vector_search_10k");
+    for (name, index) in &indexes {
+        group.bench_with_input(BenchmarkId::from_parameter(name), index, |b, index| {
+            let mut next = 0;
+            b.iter(|| {
+                let query = &queries[next % queries.len()];
+                next += 1;
+                rt.block_on(index.search(black_box(query), SESSION_ID, 10, None))
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_vector_search);
+criterion_main!(benches);
@@ -1,18 +1,30 @@
-use crate::index::IndexService;
+use crate::config::config::{ApiConfig, PatternQualityThresholds};
+use crate::error::Result;
+use crate::index::{EmbeddingModel, IndexService};
 use crate::mcp::sse_server::ConnectionManager;
 use crate::models::entity_repository::EntityRepositoryImpl;
-use crate::models::memory_repository::MemoryRepositoryImpl;
+use crate::models::memory_repository::MemoryRepository;
 use crate::models::pattern_repository::PatternRepositoryImpl;
 use crate::models::profile_repository::ProfileRepositoryImpl;
+use crate::observability::{AppMetrics, EventBus};
 use crate::security::auth::Authenticator;
 use crate::security::rate_limit::RateLimiter;
 use crate::security::rbac::Authorizer;
 use crate::services::dehydration::DehydrationService;
+pub use crate::services::lazy::{LazyService, ServiceInitializer};
+use crate::services::memory_builder::MemoryBuilder;
+use crate::services::memory_integrator::MemoryIntegrator;
+use crate::services::memory_recall::MemoryRecall;
+use crate::services::pattern_manager::{PatternCache, PatternManager};
+use crate::services::profile::{ProfileService, ProfileServiceImpl};
 use crate::services::retrieval::RetrievalService;
-use crate::services::session::SessionService;
+use crate::services::session::{Pagination, SessionService};
+use crate::services::snapshot::SessionSnapshotServiceImpl;
 use crate::services::turn::TurnService;
-use crate::storage::repository::{SessionRepository, TurnRepository};
+use crate::storage::repository::{SessionStore, TurnStore};
+use crate::storage::stats::StorageStatsService;
 use crate::storage::surrealdb::SurrealPool;
+use std::future::Future;
 use std::sync::Arc;
 
 /// Application state containing all shared services and security components
@@ -21,11 +33,11 @@ pub struct AppState {
     /// Database connection pool
     pub db_pool: SurrealPool,
     /// Session repository for session CRUD operations
-    pub session_repository: Arc<SessionRepository>,
+    pub session_repository: Arc<dyn SessionStore>,
     /// Turn repository for turn CRUD operations
-    pub turn_repository: Arc<TurnRepository>,
+    pub turn_repository: Arc<dyn TurnStore>,
     /// Memory repository for memory CRUD operations
-    pub memory_repository: Arc<MemoryRepositoryImpl>,
+    pub memory_repository: Arc<dyn MemoryRepository + Send + Sync>,
     /// Pattern repository for pattern CRUD operations
     pub pattern_repository: Arc<PatternRepositoryImpl>,
     /// Entity repository for entity and relationship CRUD operations
@@ -36,12 +48,16 @@ pub struct AppState {
     pub session_service: Arc<dyn SessionService>,
     /// Turn service for turn business logic
     pub turn_service: Arc<dyn TurnService>,
-    /// Retrieval service for querying context
-    pub retrieval_service: Arc<dyn RetrievalService>,
-    /// Dehydration service for compressing context
-    pub dehydration_service: Arc<dyn DehydrationService>,
-    /// Index service for search indexing
-    pub index_service: Arc<dyn IndexService>,
+    /// Profile service that keeps profiles in sync with profile memories
+    pub profile_service: Arc<dyn ProfileService>,
+    /// Retrieval service for querying context (created on first use)
+    pub retrieval_service: LazyService<dyn RetrievalService>,
+    /// Dehydration service for compressing context (created on first use)
+    pub dehydration_service: LazyService<dyn DehydrationService>,
+    /// Index service for search indexing (created on first use)
+    pub index_service: LazyService<dyn IndexService>,
+    /// Embedding model for ad-hoc memory embeddings (created on first use)
+    pub embedding_model: LazyService<dyn EmbeddingModel>,
     /// Authenticator for API key and JWT validation
     pub authenticator: Arc<dyn Authenticator>,
     /// Authorizer for RBAC permission checks
@@ -50,23 +66,43 @@ pub struct AppState {
     pub rate_limiter: Arc<RateLimiter>,
     /// Connection manager for SSE MCP server
     pub connection_manager: Option<Arc<ConnectionManager>>,
+    /// Domain event bus forwarded to the connection manager's subscribers
+    pub event_bus: EventBus,
+    /// Serve pattern stats without authentication
+    pub public_stats_enabled: bool,
+    /// Upper bound for `page_size` on list endpoints
+    pub max_page_size: usize,
+    /// Pattern cache shared by every `PatternManager` built from this state
+    pub pattern_cache: PatternCache,
+    /// Thresholds every `PatternManager` built from this state applies
+    pub pattern_quality_thresholds: PatternQualityThresholds,
+    /// Application metrics shared with the observability endpoints
+    pub metrics: Arc<AppMetrics>,
 }
 
 impl std::fmt::Debug for AppState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AppState")
             .field("db_pool", &"SurrealPool")
-            .field("session_repository", &"Arc<SessionRepository>")
-            .field("turn_repository", &"Arc<TurnRepository>")
-            .field("memory_repository", &"Arc<MemoryRepository>")
+            .field("session_repository", &"Arc<dyn SessionStore>")
+            .field("turn_repository", &"Arc<dyn TurnStore>")
+            .field("memory_repository", &"Arc<dyn MemoryRepository>")
             .field("pattern_repository", &"Arc<PatternRepositoryImpl>")
             .field("entity_repository", &"Arc<EntityRepositoryImpl>")
             .field("profile_repository", &"Arc<ProfileRepositoryImpl>")
             .field("session_service", &"Arc<dyn SessionService>")
             .field("turn_service", &"Arc<dyn TurnService>")
-            .field("retrieval_service", &"Arc<dyn RetrievalService>")
-            .field("dehydration_service", &"Arc<dyn DehydrationService>")
-            .field("index_service", &"Arc<dyn IndexService>")
+            .field("profile_service", &"Arc<dyn ProfileService>")
+            .field(
+                "retrieval_service",
+                &self.retrieval_service.is_initialized(),
+            )
+            .field(
+                "dehydration_service",
+                &self.dehydration_service.is_initialized(),
+            )
+            .field("index_service", &self.index_service.is_initialized())
+            .field("embedding_model", &self.embedding_model.is_initialized())
             .field("authenticator", &"Arc<dyn Authenticator>")
             .field("authorizer", &"Arc<dyn Authorizer>")
             .field("rate_limiter", &self.rate_limiter)
@@ -77,6 +113,11 @@ impl std::fmt::Debug for AppState {
                     .as_ref()
                     .map(|_| "Some(ConnectionManager)"),
             )
+            .field("event_bus", &"EventBus")
+            .field("public_stats_enabled", &self.public_stats_enabled)
+            .field("max_page_size", &self.max_page_size)
+            .field("pattern_cache_capacity", &self.pattern_cache.capacity())
+            .field("metrics", &"Arc<AppMetrics>")
             .finish()
     }
 }
@@ -84,58 +125,233 @@ impl std::fmt::Debug for AppState {
 impl AppState {
     pub fn new(
         db_pool: SurrealPool,
-        session_repository: SessionRepository,
-        turn_repository: TurnRepository,
-        memory_repository: MemoryRepositoryImpl,
+        session_repository: Arc<dyn SessionStore>,
+        turn_repository: Arc<dyn TurnStore>,
+        memory_repository: Arc<dyn MemoryRepository + Send + Sync>,
         pattern_repository: PatternRepositoryImpl,
         entity_repository: EntityRepositoryImpl,
         profile_repository: ProfileRepositoryImpl,
         session_service: Box<dyn SessionService>,
         turn_service: Box<dyn TurnService>,
-        retrieval_service: Box<dyn RetrievalService>,
-        dehydration_service: Box<dyn DehydrationService>,
-        index_service: Box<dyn IndexService>,
         authenticator: Box<dyn Authenticator>,
         authorizer: Box<dyn Authorizer>,
         rate_limiter: RateLimiter,
     ) -> Self {
+        let profile_repository = Arc::new(profile_repository);
         Self {
             db_pool,
-            session_repository: Arc::new(session_repository),
-            turn_repository: Arc::new(turn_repository),
-            memory_repository: Arc::new(memory_repository),
+            session_repository,
+            turn_repository,
+            memory_repository,
             pattern_repository: Arc::new(pattern_repository),
             entity_repository: Arc::new(entity_repository),
-            profile_repository: Arc::new(profile_repository),
+            profile_service: Arc::new(ProfileServiceImpl::new(profile_repository.clone())),
+            profile_repository,
             session_service: Arc::from(session_service),
             turn_service: Arc::from(turn_service),
-            retrieval_service: Arc::from(retrieval_service),
-            dehydration_service: Arc::from(dehydration_service),
-            index_service: Arc::from(index_service),
+            retrieval_service: LazyService::unconfigured("retrieval service"),
+            dehydration_service: LazyService::unconfigured("dehydration service"),
+            index_service: LazyService::unconfigured("index service"),
+            embedding_model: LazyService::unconfigured("embedding model"),
             authenticator: Arc::from(authenticator),
             authorizer: Arc::from(authorizer),
             rate_limiter: Arc::from(rate_limiter),
             connection_manager: None,
+            event_bus: EventBus::default(),
+            public_stats_enabled: false,
+            max_page_size: ApiConfig::default().max_page_size,
+            pattern_cache: PatternCache::default(),
+            pattern_quality_thresholds: PatternQualityThresholds::default(),
+            metrics: Arc::new(AppMetrics::default()),
+        }
+    }
+
+    /// Returns this state with a dedicated SSE `ConnectionManager`
+    ///
+    /// The manager broadcasts the events published on this state's event bus,
+    /// so `with_event_bus` must be called first.
+    pub fn with_sse_connection_manager(mut self, max_connections: usize) -> Self {
+        self.connection_manager = Some(Arc::new(ConnectionManager::with_event_bus(
+            max_connections,
+            &self.event_bus,
+        )));
+        self
+    }
+
+    /// Returns this state publishing domain events on the given bus
+    pub fn with_event_bus(mut self, event_bus: EventBus) -> Self {
+        self.event_bus = event_bus;
+        self
+    }
+
+    /// Returns this state sharing the given lazily-initialised index service
+    ///
+    /// Pass the same slot to the session and turn services so that their
+    /// cascading deletes reach the index this state serves searches from.
+    pub fn with_index_service(mut self, index_service: LazyService<dyn IndexService>) -> Self {
+        self.index_service = index_service;
+        self
+    }
+
+    /// Returns this state with a lazily-initialised retrieval service
+    pub fn with_retrieval_service<F, Fut>(mut self, init: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Box<dyn RetrievalService>>> + Send + 'static,
+    {
+        self.retrieval_service = LazyService::new("retrieval service", init);
+        self
+    }
+
+    /// Returns this state with a lazily-initialised dehydration service
+    pub fn with_dehydration_service<F, Fut>(mut self, init: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Box<dyn DehydrationService>>> + Send + 'static,
+    {
+        self.dehydration_service = LazyService::new("dehydration service", init);
+        self
+    }
+
+    /// Returns this state sharing the given lazily-initialised embedding model
+    ///
+    /// Pass the same slot to the index and retrieval initialisers so that the
+    /// model is loaded once for the whole process.
+    pub fn with_embedding_model(
+        mut self,
+        embedding_model: LazyService<dyn EmbeddingModel>,
+    ) -> Self {
+        self.embedding_model = embedding_model;
+        self
+    }
+
+    /// Returns the index service, creating it on first use
+    pub async fn ensure_index_service(&self) -> Result<&dyn IndexService> {
+        self.index_service.get().await
+    }
+
+    /// Returns the retrieval service, creating it on first use
+    pub async fn ensure_retrieval_service(&self) -> Result<&dyn RetrievalService> {
+        self.retrieval_service.get().await
+    }
+
+    /// Returns the dehydration service, creating it on first use
+    pub async fn ensure_dehydration_service(&self) -> Result<&dyn DehydrationService> {
+        self.dehydration_service.get().await
+    }
+
+    /// Builds a `MemoryBuilder` over the shared repositories
+    pub async fn memory_builder(&self) -> Result<MemoryBuilder> {
+        let dehydration_service = self.dehydration_service.get_shared().await?;
+        Ok(MemoryBuilder::new(
+            self.memory_repository.clone(),
+            self.entity_repository.clone(),
+            dehydration_service,
+        )
+        .with_integrator(Arc::new(self.memory_integrator()))
+        .with_metrics(self.metrics.clone())
+        .with_event_bus(self.event_bus.clone())
+        .with_profile_service(self.profile_service.clone()))
+    }
+
+    /// Builds a `MemoryIntegrator` that links memories to their tenant's known entities
+    pub fn memory_integrator(&self) -> MemoryIntegrator {
+        let recall = MemoryRecall::new(
+            self.db_pool.clone(),
+            self.memory_repository.clone(),
+            self.profile_repository.clone(),
+        );
+        MemoryIntegrator::new(self.memory_repository.clone(), Arc::new(recall), None)
+            .with_entity_repo(self.entity_repository.clone())
+    }
+
+    /// Builds a `MemoryRecall` over the shared repositories
+    ///
+    /// The embedding model is attached when it can be loaded; without it only
+    /// memories with stored embeddings take part in similarity search.
+    pub async fn memory_recall(&self) -> MemoryRecall {
+        let recall = MemoryRecall::new(
+            self.db_pool.clone(),
+            self.memory_repository.clone(),
+            self.profile_repository.clone(),
+        )
+        .with_turn_repository(self.turn_repository.clone());
+
+        match self.embedding_model.get_shared().await {
+            Ok(model) => recall.with_embedding_model(model),
+            Err(e) => {
+                tracing::warn!("Embedding model unavailable for memory recall: {}", e);
+                recall
+            }
         }
     }
 
-    pub fn init_sse_connection_manager(&mut self, max_connections: usize) {
-        self.connection_manager = Some(Arc::new(ConnectionManager::new(max_connections)));
+    /// Builds a `PatternManager` backed by the shared pattern cache
+    pub fn pattern_manager(&self) -> PatternManager {
+        PatternManager::new_basic(
+            self.pattern_repository.clone(),
+            self.memory_repository.clone(),
+        )
+        .with_cache(self.pattern_cache.clone())
+        .with_quality_thresholds(self.pattern_quality_thresholds.clone())
+        .with_metrics(self.metrics.clone())
+    }
+
+    /// Builds a `SessionSnapshotServiceImpl` over the shared turn repository
+    pub fn snapshot_service(&self) -> SessionSnapshotServiceImpl {
+        SessionSnapshotServiceImpl::new(self.db_pool.clone(), self.turn_repository.clone())
+    }
+
+    /// Builds a `StorageStatsService` over the shared pool
+    pub fn storage_stats_service(&self) -> StorageStatsService {
+        StorageStatsService::new(self.db_pool.clone())
+    }
+
+    /// Returns this state recording into the given metrics
+    pub fn with_metrics(mut self, metrics: Arc<AppMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Returns this state with the public pattern stats endpoint toggled
+    pub fn with_public_stats_endpoint(mut self, enabled: bool) -> Self {
+        self.public_stats_enabled = enabled;
+        self
+    }
+
+    /// Returns this state with the configured maximum list page size
+    pub fn with_max_page_size(mut self, max_page_size: usize) -> Self {
+        self.max_page_size = max_page_size;
+        self
+    }
+
+    /// Returns this state with a pattern cache of the given capacity
+    pub fn with_pattern_cache_capacity(mut self, capacity: usize) -> Self {
+        self.pattern_cache = PatternCache::new(capacity);
+        self
+    }
+
+    /// Returns this state with the configured pattern quality thresholds
+    pub fn with_pattern_quality_thresholds(mut self, thresholds: PatternQualityThresholds) -> Self {
+        self.pattern_quality_thresholds = thresholds;
+        self
+    }
+
+    /// Pagination for a list request, defaulted and clamped to the configured limits
+    pub fn pagination(&self, page: Option<usize>, page_size: Option<usize>) -> Pagination {
+        Pagination::from_query(page, page_size).clamp(1, self.max_page_size)
     }
 
     pub fn development(
         db_pool: SurrealPool,
-        session_repository: SessionRepository,
-        turn_repository: TurnRepository,
-        memory_repository: MemoryRepositoryImpl,
+        session_repository: Arc<dyn SessionStore>,
+        turn_repository: Arc<dyn TurnStore>,
+        memory_repository: Arc<dyn MemoryRepository + Send + Sync>,
         pattern_repository: PatternRepositoryImpl,
         entity_repository: EntityRepositoryImpl,
         profile_repository: ProfileRepositoryImpl,
         session_service: Box<dyn SessionService>,
         turn_service: Box<dyn TurnService>,
-        retrieval_service: Box<dyn RetrievalService>,
-        dehydration_service: Box<dyn DehydrationService>,
-        index_service: Box<dyn IndexService>,
     ) -> Self {
         use crate::security::auth::CombinedAuthenticator;
         use crate::security::rate_limit::RateLimiter;
@@ -155,12 +371,40 @@ impl AppState {
             profile_repository,
             session_service,
             turn_service,
-            retrieval_service,
-            dehydration_service,
-            index_service,
             authenticator,
             authorizer,
             rate_limiter,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::MockDatabase;
+    use crate::models::pattern::{Pattern, PatternType};
+
+    #[tokio::test]
+    async fn test_pattern_manager_uses_configured_quality_thresholds() {
+        let db = MockDatabase::start().await;
+        let mut pattern = Pattern::new(
+            "user_123",
+            PatternType::BestPractice,
+            "Retry with backoff",
+            "Flaky network",
+            "Retry with exponential backoff",
+        );
+        pattern.confidence = 0.8;
+        pattern.success_count = 9;
+        pattern.failure_count = 1;
+
+        let state = db.app_state();
+        assert!(state.pattern_manager().assess_quality(&pattern));
+
+        let state = state.with_pattern_quality_thresholds(PatternQualityThresholds {
+            min_usage_count: 20,
+            ..Default::default()
+        });
+        assert!(!state.pattern_manager().assess_quality(&pattern));
+    }
+}
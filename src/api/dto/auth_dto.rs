@@ -0,0 +1,40 @@
+//! 认证 DTO
+//!
+//! 定义 API Key 管理相关的请求和响应数据结构。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 创建 API Key 请求
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct CreateApiKeyRequest {
+    /// 有效期（天），为空表示永不过期
+    pub expires_in_days: Option<u32>,
+}
+
+/// 创建 API Key 响应
+///
+/// 明文 key 仅在创建时返回一次，之后无法再次获取。
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    /// Key ID
+    pub key_id: String,
+    /// 明文 API Key
+    pub api_key: String,
+    /// 租户 ID
+    pub tenant_id: String,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+    /// 过期时间
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// 删除 API Key 响应
+#[derive(Debug, Serialize)]
+pub struct DeleteApiKeyResponse {
+    /// Key ID
+    pub key_id: String,
+    /// 消息
+    pub message: String,
+}
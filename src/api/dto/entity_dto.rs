@@ -365,6 +365,9 @@ pub enum RelationshipTypeDto {
     #[serde(rename = "similar_to")]
     SimilarTo,
 
+    #[serde(rename = "related_to")]
+    RelatedTo,
+
     #[serde(rename = "created_by")]
     CreatedBy,
 
@@ -471,6 +474,57 @@ pub struct GraphPathDto {
     pub strength: f32,
 }
 
+/// 全局图谱响应（D3 force graph 的 nodes / links 格式）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GlobalGraphResponse {
+    /// 节点列表
+    pub nodes: Vec<GraphNodeDto>,
+
+    /// 边列表
+    pub links: Vec<GraphLinkDto>,
+}
+
+/// 图谱节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNodeDto {
+    /// 实体 ID
+    pub id: String,
+
+    /// 实体名称
+    pub name: String,
+
+    /// 分组（实体类型）
+    pub group: EntityTypeDto,
+
+    /// 出现频率
+    pub frequency: u32,
+
+    /// 关系数（仅枢纽实体）
+    pub relationship_count: Option<usize>,
+
+    /// 是否为枢纽实体
+    pub hub: bool,
+}
+
+/// 图谱边
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphLinkDto {
+    /// 关系 ID
+    pub id: String,
+
+    /// 源实体 ID
+    pub source: String,
+
+    /// 目标实体 ID
+    pub target: String,
+
+    /// 关系类型
+    pub relationship_type: RelationshipTypeDto,
+
+    /// 关系强度
+    pub strength: f32,
+}
+
 /// 图统计响应
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphStatsResponse {
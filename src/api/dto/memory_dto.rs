@@ -176,6 +176,9 @@ pub struct MemoryResponse {
     /// 更新时间
     pub updated_at: DateTime<Utc>,
 
+    /// 访问次数
+    pub access_count: u32,
+
     /// 相关记忆数
     pub related_count: usize,
 }
@@ -197,6 +200,7 @@ impl From<Memory> for MemoryResponse {
             version: memory.version,
             created_at: memory.created_at,
             updated_at: memory.updated_at,
+            access_count: memory.access_count,
             related_count: memory.related_ids.len(),
         }
     }
@@ -2,6 +2,7 @@
 //!
 //! 数据传输对象，用于 API 请求和响应的序列化。
 
+pub mod auth_dto;
 pub mod entity_dto;
 pub mod memory_dto;
 pub mod pattern_dto;
@@ -10,6 +11,7 @@ pub mod search_dto;
 pub mod session_dto;
 pub mod turn_dto;
 
+pub use auth_dto::*;
 pub use entity_dto::*;
 pub use memory_dto::*;
 pub use pattern_dto::*;
@@ -2,6 +2,7 @@
 //!
 //! 用于 Pattern API 的请求和响应序列化
 
+use crate::models::pattern::PatternStatus;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -143,6 +144,10 @@ pub struct SearchPatternRequest {
     #[serde(default)]
     pub public_only: bool,
 
+    /// 是否包含已弃用和已归档的模式
+    #[serde(default)]
+    pub include_archived: bool,
+
     /// 分页
     #[serde(default = "default_page")]
     pub page: u32,
@@ -202,6 +207,7 @@ pub struct PatternResponse {
     pub is_public: bool,
     pub confidence: f32,
     pub version: u32,
+    pub status: PatternStatus,
 }
 
 /// 模式列表响应
@@ -322,6 +328,19 @@ pub struct PatternStatsResponse {
     pub most_used_pattern: Option<PatternMostUsedDto>,
 }
 
+/// 公开模式统计响应（无需认证，仅包含聚合数据）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicPatternStatsResponse {
+    /// 总模式数
+    pub total_patterns: u64,
+
+    /// 平均成功率
+    pub avg_success_rate: f32,
+
+    /// 最多使用的模式
+    pub most_used_pattern: Option<PatternMostUsedDto>,
+}
+
 /// 最多使用的模式
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PatternMostUsedDto {
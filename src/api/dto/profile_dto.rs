@@ -224,6 +224,19 @@ pub struct ProfileStatsResponse {
     pub category_stats: Vec<ProfileCategoryStat>,
 }
 
+/// 画像记忆同步状态响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSyncStatusResponse {
+    /// 画像 ID
+    pub id: String,
+
+    /// 已同步的 Profile 类型记忆数
+    pub sync_count: u64,
+
+    /// 最后同步时间
+    pub last_synced_at: Option<DateTime<Utc>>,
+}
+
 /// 类别统计
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfileCategoryStat {
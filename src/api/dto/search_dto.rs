@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::security::validation::{Validatable, ValidationResult, validators};
+
 /// 语义搜索请求
 #[derive(Debug, Deserialize)]
 #[serde(default)]
@@ -26,6 +28,12 @@ impl Default for SemanticSearchRequest {
     }
 }
 
+impl Validatable for SemanticSearchRequest {
+    fn validate(&self) -> ValidationResult<()> {
+        validators::validate_search_query(&self.query)
+    }
+}
+
 /// 混合搜索请求
 #[derive(Debug, Deserialize)]
 #[serde(default)]
@@ -45,6 +53,39 @@ impl Default for HybridSearchRequest {
     }
 }
 
+impl Validatable for HybridSearchRequest {
+    fn validate(&self) -> ValidationResult<()> {
+        validators::validate_search_query(&self.query)
+    }
+}
+
+/// 全局搜索请求
+#[derive(Debug, Deserialize)]
+pub struct GlobalSearchRequest {
+    /// 租户 ID
+    pub tenant_id: String,
+    /// 搜索查询
+    pub query: String,
+    /// 返回结果数量
+    pub limit: Option<u32>,
+    /// 是否使用语义检索
+    #[serde(default = "default_true")]
+    pub use_semantic: bool,
+    /// 是否使用全文检索
+    #[serde(default = "default_true")]
+    pub use_full_text: bool,
+}
+
+impl Validatable for GlobalSearchRequest {
+    fn validate(&self) -> ValidationResult<()> {
+        validators::validate_search_query(&self.query)
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
 /// 搜索结果项
 #[derive(Debug, Clone, Serialize)]
 pub struct SearchResultItem {
@@ -87,3 +128,30 @@ pub struct RecentContextResponse {
     /// 总数
     pub total: usize,
 }
+
+/// 全局搜索结果项
+#[derive(Debug, Clone, Serialize)]
+pub struct GlobalSearchResultItem {
+    /// 所属会话 ID
+    pub session_id: String,
+    /// 所属会话名称
+    pub session_name: Option<String>,
+    /// 搜索结果
+    #[serde(flatten)]
+    pub result: SearchResultItem,
+}
+
+/// 全局搜索响应
+#[derive(Debug, Serialize)]
+pub struct GlobalSearchResponse {
+    /// 租户 ID
+    pub tenant_id: String,
+    /// 查询
+    pub query: String,
+    /// 结果列表
+    pub results: Vec<GlobalSearchResultItem>,
+    /// 结果数量
+    pub total_results: usize,
+    /// 耗时（毫秒）
+    pub took_ms: u64,
+}
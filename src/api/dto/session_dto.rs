@@ -5,6 +5,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::api::dto::entity_dto::EntityResponse;
+use crate::api::dto::memory_dto::MemoryResponse;
+use crate::api::dto::turn_dto::TurnResponse;
+use crate::models::session::SessionConfig;
+
 /// 创建会话请求
 #[derive(Debug, Deserialize)]
 #[serde(default)]
@@ -61,6 +66,43 @@ impl Default for UpdateSessionRequest {
     }
 }
 
+/// 更新会话配置请求（未提供的字段保持不变）
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct UpdateSessionConfigRequest {
+    /// 保留的摘要数量
+    pub summary_limit: Option<usize>,
+    /// 索引刷新间隔（毫秒）
+    pub index_refresh_interval: Option<u64>,
+    /// 启用语义搜索
+    pub semantic_search_enabled: Option<bool>,
+    /// 启用自动摘要
+    pub auto_summarize: Option<bool>,
+    /// 最大轮次数（0 表示无限制）
+    pub max_turns: Option<usize>,
+}
+
+impl UpdateSessionConfigRequest {
+    /// 将请求中的字段合并到现有配置
+    pub fn apply_to(self, config: &mut SessionConfig) {
+        if let Some(summary_limit) = self.summary_limit {
+            config.summary_limit = summary_limit;
+        }
+        if let Some(index_refresh_interval) = self.index_refresh_interval {
+            config.index_refresh_interval = index_refresh_interval;
+        }
+        if let Some(semantic_search_enabled) = self.semantic_search_enabled {
+            config.semantic_search_enabled = semantic_search_enabled;
+        }
+        if let Some(auto_summarize) = self.auto_summarize {
+            config.auto_summarize = auto_summarize;
+        }
+        if let Some(max_turns) = self.max_turns {
+            config.max_turns = max_turns;
+        }
+    }
+}
+
 /// 会话配置响应
 #[derive(Debug, Serialize)]
 #[serde(default)]
@@ -87,6 +129,20 @@ pub struct SessionStatsResponse {
     pub storage_size: u64,
     /// 最后索引时间
     pub last_indexed_at: Option<DateTime<Utc>>,
+    /// Token 预算估算（仅在获取单个会话且 `include_token_estimate=true` 时返回）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_estimate: Option<TokenEstimateResponse>,
+}
+
+/// Token 预算估算响应
+#[derive(Debug, Serialize)]
+pub struct TokenEstimateResponse {
+    /// 使用脱水摘要时的 Token 数
+    pub total_gist_tokens: usize,
+    /// 使用原文时的 Token 数
+    pub total_raw_tokens: usize,
+    /// 摘要与原文的 Token 比例
+    pub dehydrated_ratio: f32,
 }
 
 /// 会话响应
@@ -193,3 +249,124 @@ pub struct RestoreSessionResponse {
     /// 消息
     pub message: String,
 }
+
+/// 批量索引请求
+#[derive(Debug, Deserialize)]
+pub struct BatchIndexRequest {
+    /// 需要重新索引的轮次 ID
+    pub turn_ids: Vec<String>,
+}
+
+/// 批量索引响应
+#[derive(Debug, Serialize)]
+pub struct BatchIndexResponse {
+    /// 成功索引的轮次数
+    pub indexed: usize,
+    /// 索引失败的轮次 ID
+    pub failed: Vec<String>,
+    /// 失败原因（与 `failed` 一一对应）
+    pub errors: Vec<String>,
+}
+
+/// 重建会话索引响应
+#[derive(Debug, Serialize)]
+pub struct ReindexSessionResponse {
+    /// 会话 ID
+    pub id: String,
+    /// 删除的索引条目数
+    pub deleted: usize,
+    /// 重新索引的轮次数
+    pub reindexed: usize,
+    /// 重新索引失败的轮次数
+    pub failed: usize,
+    /// 消息
+    pub message: String,
+}
+
+/// 创建会话快照响应
+#[derive(Debug, Serialize)]
+pub struct SessionSnapshotResponse {
+    /// 快照 ID
+    pub snapshot_id: String,
+    /// 会话 ID
+    pub session_id: String,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+    /// 快照包含的轮次数量
+    pub turn_count: u64,
+}
+
+/// 恢复会话快照响应
+#[derive(Debug, Serialize)]
+pub struct RestoreSnapshotResponse {
+    /// 快照 ID
+    pub snapshot_id: String,
+    /// 会话 ID
+    pub session_id: String,
+    /// 重新写入的快照轮次数量
+    pub turns_restored: usize,
+    /// 删除的快照点之后创建的轮次数量
+    pub turns_discarded: usize,
+    /// 消息
+    pub message: String,
+}
+
+/// 会话 Token 估算响应
+#[derive(Debug, Serialize)]
+pub struct SessionTokenEstimateResponse {
+    /// 会话 ID
+    pub id: String,
+    /// Token 估算
+    #[serde(flatten)]
+    pub estimate: TokenEstimateResponse,
+}
+
+/// 压缩会话轮次响应
+#[derive(Debug, Serialize)]
+pub struct CompressSessionResponse {
+    /// 替代原轮次的合成系统轮次
+    pub turn: TurnResponse,
+    /// 被压缩的轮次数
+    pub turns_compressed: usize,
+    /// 删除的原轮次数
+    pub deleted: u64,
+}
+
+/// 会话摘要响应
+#[derive(Debug, Serialize)]
+pub struct SessionSummaryResponse {
+    /// 按轮次顺序拼接的摘要文本
+    pub summary: String,
+    /// 摘要覆盖的轮次数
+    pub turns_covered: usize,
+    /// 摘要的估算 token 数
+    pub tokens_estimated: u64,
+}
+
+/// 会话时间线条目
+#[derive(Debug, Serialize)]
+pub struct TimelineEntry {
+    /// 轮次
+    pub turn: TurnResponse,
+    /// 由该轮次产生的记忆
+    pub memories: Vec<MemoryResponse>,
+    /// 轮次内容中提及的实体
+    pub entities: Vec<EntityResponse>,
+}
+
+/// 上下文窗口响应
+#[derive(Debug, Serialize)]
+pub struct ContextWindowResponse {
+    /// 会话 ID
+    pub session_id: String,
+    /// 目标模型
+    pub model: String,
+    /// 窗口内的轮次（按轮次编号升序）
+    pub turns: Vec<TurnResponse>,
+    /// 窗口内轮次的估算 token 总数
+    pub tokens_used: u64,
+    /// 窗口内的轮次数
+    pub turns_included: usize,
+    /// 因超出预算而未放入窗口的轮次数
+    pub turns_truncated: u64,
+}
@@ -104,6 +104,48 @@ pub struct TurnListResponse {
     pub page_size: usize,
 }
 
+/// 轮次分组响应
+#[derive(Debug, Serialize)]
+pub struct TurnGroupResponse {
+    /// 分组 ID
+    pub group_id: String,
+    /// 起始轮次编号
+    pub start_turn: u64,
+    /// 结束轮次编号
+    pub end_turn: u64,
+    /// 分组类型
+    pub group_type: String,
+    /// 包含的轮次数
+    pub turn_count: usize,
+    /// 包含的轮次 ID（仅在请求 include_turn_ids 时返回）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn_ids: Option<Vec<String>>,
+}
+
+/// 轮次分组列表响应
+#[derive(Debug, Serialize)]
+pub struct TurnGroupListResponse {
+    /// 会话 ID
+    pub session_id: String,
+    /// 分组列表
+    pub groups: Vec<TurnGroupResponse>,
+    /// 分组总数
+    pub total: usize,
+}
+
+/// 对话窗口响应
+#[derive(Debug, Serialize)]
+pub struct ConversationWindowResponse {
+    /// 会话 ID
+    pub session_id: String,
+    /// 窗口内的轮次（按轮次编号升序）
+    pub turns: Vec<TurnResponse>,
+    /// 会话轮次总数
+    pub total_turns: u64,
+    /// 窗口起始轮次编号
+    pub window_start_turn: u64,
+}
+
 /// 创建轮次响应
 #[derive(Debug, Serialize)]
 pub struct CreateTurnResponse {
@@ -124,6 +166,20 @@ pub struct DeleteTurnResponse {
     pub message: String,
 }
 
+/// 批量删除轮次请求
+#[derive(Debug, Deserialize, Default)]
+pub struct DeleteTurnsRequest {
+    /// 删除轮次编号小于该值的轮次（必填，须大于 0）
+    pub before_turn_number: Option<u64>,
+}
+
+/// 批量删除轮次响应
+#[derive(Debug, Serialize)]
+pub struct DeleteTurnsResponse {
+    /// 删除的轮次数量
+    pub deleted: usize,
+}
+
 /// 更新轮次请求
 #[derive(Debug, Deserialize, Default)]
 pub struct UpdateTurnRequest {
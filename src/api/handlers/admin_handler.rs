@@ -0,0 +1,44 @@
+//! Admin API Handlers
+//!
+//! HTTP handlers for operational endpoints restricted to administrators.
+
+use axum::{
+    Json,
+    extract::{Extension, State},
+    response::IntoResponse,
+};
+use tracing::debug;
+
+use crate::{
+    api::app_state::AppState,
+    error::AppError,
+    security::{auth::Claims, rbac::ClaimsExt},
+};
+
+/// Get record counts and estimated sizes for each database table
+///
+/// GET /api/v1/admin/storage-stats
+///
+/// Also refreshes the `table_record_count` gauge exported on `/metrics`.
+pub async fn get_storage_stats(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Getting storage stats for user: {}", claims.sub);
+
+    if !claims.is_admin() {
+        return Err(AppError::Forbidden(
+            "Storage stats require the admin role".to_string(),
+        ));
+    }
+
+    let stats = state.storage_stats_service().gather().await?;
+    state.metrics.set_table_record_counts(
+        stats
+            .tables
+            .iter()
+            .map(|t| (t.table_name.clone(), t.record_count)),
+    );
+
+    Ok(Json(stats))
+}
@@ -0,0 +1,123 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{Duration, Utc};
+use tracing::debug;
+
+use crate::{
+    api::{app_state::AppState, dto::auth_dto::*},
+    error::AppError,
+    security::{
+        auth::{ApiKeyAuth, Claims},
+        rbac::{ActionType, Permission, ResourceType},
+    },
+};
+
+/// 获取 API Key 存储
+fn api_key_store(state: &AppState) -> Result<&ApiKeyAuth, AppError> {
+    state
+        .authenticator
+        .api_key_auth()
+        .ok_or_else(|| AppError::Config("API key authentication is not enabled".to_string()))
+}
+
+/// 仅管理员可管理 API Key
+async fn require_key_management(state: &AppState, claims: &Claims) -> Result<(), AppError> {
+    let permission = Permission::new(ResourceType::System, ActionType::Manage);
+    if !state.authorizer.check_permission(claims, &permission).await {
+        return Err(AppError::Authorization(
+            "Managing API keys requires the admin role".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Creating API key for tenant: {}", claims.tenant_id);
+    require_key_management(&state, &claims).await?;
+
+    let expires_at = request
+        .expires_in_days
+        .map(|days| Utc::now() + Duration::days(days as i64));
+
+    let (api_key, record) = api_key_store(&state)?
+        .create_key(&claims.tenant_id, expires_at)
+        .await?;
+
+    let response = CreateApiKeyResponse {
+        key_id: record.key_id,
+        api_key,
+        tenant_id: record.tenant_id,
+        created_at: record.created_at,
+        expires_at: record.expires_at,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn delete_api_key(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(key_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Deleting API key: {}", key_id);
+    require_key_management(&state, &claims).await?;
+
+    if !api_key_store(&state)?
+        .revoke_key(&claims.tenant_id, &key_id)
+        .await?
+    {
+        return Err(AppError::NotFound(format!("API key not found: {}", key_id)));
+    }
+
+    let response = DeleteApiKeyResponse {
+        key_id,
+        message: "API key deleted successfully".to_string(),
+    };
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{MockDatabase, claims, json_response};
+
+    #[tokio::test]
+    async fn test_create_api_key_requires_admin() {
+        let db = MockDatabase::start().await;
+        let state = db.app_state();
+
+        let denied = create_api_key(
+            State(state.clone()),
+            Extension(claims("tenant_a", "user")),
+            Json(CreateApiKeyRequest {
+                expires_in_days: None,
+            }),
+        )
+        .await;
+        assert!(matches!(denied, Err(AppError::Authorization(_))));
+
+        let (status, body) = json_response(
+            create_api_key(
+                State(state),
+                Extension(claims("tenant_a", "admin")),
+                Json(CreateApiKeyRequest {
+                    expires_in_days: None,
+                }),
+            )
+            .await
+            .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["tenant_id"], "tenant_a");
+    }
+}
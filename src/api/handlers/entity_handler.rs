@@ -9,6 +9,7 @@ use axum::{
     response::IntoResponse,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use tracing::debug;
 
 use crate::{
@@ -165,9 +166,13 @@ pub async fn list_entities(
         claims.sub, params.page, params.page_size
     );
 
-    let page = params.page.unwrap_or(1);
-    let page_size = params.page_size.unwrap_or(20).clamp(1, 100) as usize;
-    let offset = ((page - 1) * page_size as u32) as usize;
+    let pagination = state.pagination(
+        params.page.map(|p| p as usize),
+        params.page_size.map(|s| s as usize),
+    );
+    let page = pagination.page as u32;
+    let page_size = pagination.page_size;
+    let offset = pagination.offset();
 
     let entities = state
         .entity_repository
@@ -533,6 +538,132 @@ pub async fn add_entity_property(
     Ok(Json(response))
 }
 
+/// Default number of hub entities in the global graph
+const DEFAULT_GLOBAL_GRAPH_LIMIT: usize = 20;
+
+/// Maximum BFS depth around hub entities in the global graph
+const MAX_GLOBAL_GRAPH_DEPTH: u32 = 3;
+
+/// Get the global knowledge graph around the tenant's hub entities
+///
+/// GET /api/v1/entities/graph/global?limit=20&depth=1
+pub async fn get_global_graph(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<GlobalGraphParams>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Getting global graph for tenant: {}", claims.tenant_id);
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_GLOBAL_GRAPH_LIMIT)
+        .clamp(1, 100);
+    let depth = params.depth.unwrap_or(1).min(MAX_GLOBAL_GRAPH_DEPTH);
+
+    let hubs = state
+        .entity_repository
+        .get_hub_entities(&claims.tenant_id, limit)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let mut graph = GlobalGraphBuilder::default();
+    let mut frontier = Vec::with_capacity(hubs.len());
+    for (entity, relationship_count) in hubs {
+        frontier.push(entity.id.clone());
+        graph.add_node(entity, Some(relationship_count));
+    }
+
+    // Breadth-first expansion, one layer of neighbours per depth level
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for entity_id in &frontier {
+            let relationships = state
+                .entity_repository
+                .get_entity_relationships(entity_id)
+                .await
+                .map_err(|e| AppError::Database(e.to_string()))?;
+
+            for relationship in relationships {
+                if relationship.tenant_id != claims.tenant_id {
+                    continue;
+                }
+
+                let neighbor_id = if relationship.source_entity_id == *entity_id {
+                    relationship.target_entity_id.clone()
+                } else {
+                    relationship.source_entity_id.clone()
+                };
+
+                if !graph.contains(&neighbor_id) {
+                    let neighbor = state
+                        .entity_repository
+                        .get_entity_by_id(&neighbor_id)
+                        .await
+                        .map_err(|e| AppError::Database(e.to_string()))?;
+                    match neighbor {
+                        Some(neighbor) if neighbor.tenant_id == claims.tenant_id => {
+                            graph.add_node(neighbor, None);
+                            next.push(neighbor_id);
+                        }
+                        _ => continue,
+                    }
+                }
+
+                graph.add_link(relationship);
+            }
+        }
+        frontier = next;
+    }
+
+    Ok(Json(graph.build()))
+}
+
+/// Accumulates nodes and links for the global graph, skipping duplicates
+#[derive(Default)]
+struct GlobalGraphBuilder {
+    node_ids: HashSet<String>,
+    link_ids: HashSet<String>,
+    graph: GlobalGraphResponse,
+}
+
+impl GlobalGraphBuilder {
+    fn contains(&self, entity_id: &str) -> bool {
+        self.node_ids.contains(entity_id)
+    }
+
+    /// Hub entities carry their relationship count, neighbours do not
+    fn add_node(&mut self, entity: Entity, relationship_count: Option<usize>) {
+        if !self.node_ids.insert(entity.id.clone()) {
+            return;
+        }
+        self.graph.nodes.push(GraphNodeDto {
+            id: entity.id,
+            name: entity.name,
+            group: entity.entity_type.into(),
+            frequency: entity.frequency,
+            relationship_count,
+            hub: relationship_count.is_some(),
+        });
+    }
+
+    fn add_link(&mut self, relationship: Relationship) {
+        if !self.link_ids.insert(relationship.id.clone()) {
+            return;
+        }
+        self.graph.links.push(GraphLinkDto {
+            id: relationship.id,
+            source: relationship.source_entity_id,
+            target: relationship.target_entity_id,
+            relationship_type: relationship.relationship_type.into(),
+            strength: relationship.strength,
+        });
+    }
+
+    fn build(self) -> GlobalGraphResponse {
+        self.graph
+    }
+}
+
 /// Discover entities from text
 ///
 /// POST /api/v1/entities/discover
@@ -646,6 +777,7 @@ impl From<RelationshipTypeDto> for RelationshipType {
             RelationshipTypeDto::References => RelationshipType::References,
             RelationshipTypeDto::ConflictsWith => RelationshipType::ConflictsWith,
             RelationshipTypeDto::SimilarTo => RelationshipType::SimilarTo,
+            RelationshipTypeDto::RelatedTo => RelationshipType::RelatedTo,
             RelationshipTypeDto::CreatedBy => RelationshipType::CreatedBy,
             RelationshipTypeDto::Contains => RelationshipType::Contains,
             RelationshipTypeDto::CompetesWith => RelationshipType::CompetesWith,
@@ -673,6 +805,7 @@ impl From<RelationshipType> for RelationshipTypeDto {
             RelationshipType::References => RelationshipTypeDto::References,
             RelationshipType::ConflictsWith => RelationshipTypeDto::ConflictsWith,
             RelationshipType::SimilarTo => RelationshipTypeDto::SimilarTo,
+            RelationshipType::RelatedTo => RelationshipTypeDto::RelatedTo,
             RelationshipType::CreatedBy => RelationshipTypeDto::CreatedBy,
             RelationshipType::Contains => RelationshipTypeDto::Contains,
             RelationshipType::CompetesWith => RelationshipTypeDto::CompetesWith,
@@ -695,6 +828,13 @@ pub struct ListEntitiesParams {
     pub page_size: Option<u32>,
 }
 
+/// Query parameters for the global graph
+#[derive(Debug, Deserialize, Default)]
+pub struct GlobalGraphParams {
+    pub limit: Option<usize>,
+    pub depth: Option<u32>,
+}
+
 /// Response for entity deletion
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeleteEntityResponse {
@@ -715,3 +855,32 @@ pub struct UpdateEntityResponse {
     pub id: String,
     pub message: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global_graph_builder_skips_duplicates() {
+        let rust = Entity::new("Rust", EntityType::Tool);
+        let alice = Entity::new("Alice", EntityType::Person);
+        let uses = Relationship::new(&alice.id, &rust.id, RelationshipType::Uses, "m1");
+
+        let mut builder = GlobalGraphBuilder::default();
+        builder.add_node(rust.clone(), Some(1));
+        builder.add_node(alice.clone(), None);
+        builder.add_node(rust.clone(), None);
+        builder.add_link(uses.clone());
+        builder.add_link(uses.clone());
+
+        assert!(builder.contains(&alice.id));
+        let graph = builder.build();
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(graph.nodes[0].hub);
+        assert_eq!(graph.nodes[0].relationship_count, Some(1));
+        assert!(!graph.nodes[1].hub);
+        assert_eq!(graph.links.len(), 1);
+        assert_eq!(graph.links[0].source, alice.id);
+        assert_eq!(graph.links[0].target, rust.id);
+    }
+}
@@ -9,14 +9,14 @@ use axum::{
     response::IntoResponse,
 };
 use serde::{Deserialize, Serialize};
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::{
     api::{app_state::AppState, dto::memory_dto::*},
     error::AppError,
     models::memory::{Memory, MemoryStatus},
-    models::memory_repository::MemoryRepository,
     security::auth::Claims,
+    services::memory_recall::MemoryRecallService,
 };
 
 /// Create a new memory
@@ -41,6 +41,7 @@ pub async fn create_memory(
     );
 
     let mut memory = memory;
+    memory.tenant_id = claims.tenant_id.clone();
     if let Some(source_id) = request.source_id {
         memory.source_id = Some(source_id);
     }
@@ -53,6 +54,18 @@ pub async fn create_memory(
     for topic in request.topics {
         memory.add_topic(&topic);
     }
+    let extracted_topics = match state.memory_builder().await {
+        Ok(builder) => builder.extract_topics(&request.content, &claims.sub).await,
+        Err(e) => Err(e),
+    };
+    match extracted_topics {
+        Ok(topics) => {
+            for topic in topics {
+                memory.add_topic(&topic);
+            }
+        }
+        Err(e) => warn!("Failed to extract topics for user {}: {}", claims.sub, e),
+    }
     if let Some(expires_at) = request.expires_at {
         memory.expires_at = Some(expires_at);
     }
@@ -62,6 +75,29 @@ pub async fn create_memory(
         .create(&memory)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
+    state
+        .metrics
+        .record_memory_created(&created_memory.memory_type.to_string());
+    if let Err(e) = state
+        .profile_service
+        .sync_from_memory(&created_memory)
+        .await
+    {
+        warn!(
+            "Failed to sync profile from memory {}: {}",
+            created_memory.id, e
+        );
+    }
+    if let Err(e) = state
+        .memory_integrator()
+        .link_memory_to_entities(std::slice::from_ref(&created_memory))
+        .await
+    {
+        warn!(
+            "Failed to link memory {} to entities: {}",
+            created_memory.id, e
+        );
+    }
 
     let response = MemoryResponse::from(created_memory);
 
@@ -109,13 +145,22 @@ pub async fn list_memories(
         claims.sub, params.page, params.page_size
     );
 
-    let page = params.page.unwrap_or(1);
-    let page_size = params.page_size.unwrap_or(20).clamp(1, 100) as usize;
-    let offset = ((page - 1) * page_size as u32) as usize;
+    let pagination = state.pagination(
+        params.page.map(|p| p as usize),
+        params.page_size.map(|s| s as usize),
+    );
+    let page = pagination.page as u32;
+    let page_size = pagination.page_size;
+    let offset = pagination.offset();
 
     let memories = state
         .memory_repository
-        .list_by_user(&claims.sub, params.memory_type.as_deref(), page_size, offset)
+        .list_by_user(
+            &claims.sub,
+            params.memory_type.as_deref(),
+            page_size,
+            offset,
+        )
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -321,9 +366,13 @@ pub async fn get_memories_by_type(
         memory_type, claims.sub
     );
 
-    let page = params.page.unwrap_or(1);
-    let page_size = params.page_size.unwrap_or(20).clamp(1, 100) as usize;
-    let offset = ((page - 1) * page_size as u32) as usize;
+    let pagination = state.pagination(
+        params.page.map(|p| p as usize),
+        params.page_size.map(|s| s as usize),
+    );
+    let page = pagination.page as u32;
+    let page_size = pagination.page_size;
+    let offset = pagination.offset();
 
     let memory_type_str = match memory_type.to_lowercase().as_str() {
         "episodic" => Some("episodic"),
@@ -484,6 +533,125 @@ pub async fn get_memory_version(
     Ok(Json(response))
 }
 
+/// Get the most frequently accessed memories of a user
+///
+/// GET /api/v1/users/:user_id/memories/hot
+pub async fn get_hot_memories(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<String>,
+    Query(params): Query<HotMemoriesParams>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Getting hot memories for user: {}", user_id);
+
+    if user_id != claims.sub {
+        return Err(AppError::Authorization(
+            "Access denied to memories of another user".to_string(),
+        ));
+    }
+
+    let limit = params.limit.unwrap_or(50).clamp(1, 100);
+
+    let memories = state
+        .memory_repository
+        .list_hot(&user_id, limit)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let memory_responses: Vec<MemoryResponse> = memories.into_iter().map(MemoryResponse::from).collect();
+
+    let response = HotMemoriesResponse {
+        user_id,
+        total: memory_responses.len(),
+        memories: memory_responses,
+    };
+
+    Ok(Json(response))
+}
+
+/// Find memories semantically similar to a given memory
+///
+/// GET /api/v1/memories/:id/similar
+pub async fn get_similar_memories(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<SimilarMemoriesParams>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Finding memories similar to: {}", id);
+
+    let limit = params.limit.unwrap_or(10).clamp(1, 100);
+
+    let results = state
+        .memory_recall()
+        .await
+        .find_similar_memories(&id, &claims.sub, limit)
+        .await?;
+
+    let response = SimilarMemoriesResponse {
+        memory_id: id,
+        total: results.len(),
+        results: results
+            .into_iter()
+            .map(|r| SimilarMemoryItem {
+                score: r.combined_score,
+                memory: MemoryResponse::from(r.memory),
+            })
+            .collect(),
+    };
+
+    Ok(Json(response))
+}
+
+/// Consolidate a user's episodic memories about a topic into a semantic memory
+///
+/// POST /api/v1/users/:user_id/memories/consolidate
+pub async fn consolidate_memories(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<String>,
+    Json(request): Json<ConsolidateMemoriesRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Consolidating memories for user: {} topic: {}", user_id, request.topic);
+
+    if user_id != claims.sub {
+        return Err(AppError::Authorization(
+            "Access denied to memories of another user".to_string(),
+        ));
+    }
+
+    if request.topic.trim().is_empty() {
+        return Err(AppError::Validation("Topic cannot be empty".to_string()));
+    }
+
+    let min_memories = request
+        .min_memories
+        .unwrap_or(DEFAULT_MIN_CONSOLIDATION_MEMORIES);
+
+    let consolidated = state
+        .memory_builder()
+        .await?
+        .consolidate_memories(&user_id, request.topic.trim(), min_memories)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let status = if consolidated.is_some() {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+
+    let response = ConsolidateMemoriesResponse {
+        user_id,
+        topic: request.topic,
+        consolidated: consolidated.is_some(),
+        source_count: consolidated.as_ref().map_or(0, |m| m.related_ids.len()),
+        memory: consolidated.map(MemoryResponse::from),
+    };
+
+    Ok((status, Json(response)))
+}
+
 #[derive(Debug, Deserialize, Default)]
 pub struct ListMemoriesParams {
     pub page: Option<u32>,
@@ -500,6 +668,36 @@ pub struct MemoryListResponse {
     pub total_pages: u32,
 }
 
+#[derive(Debug, Deserialize, Default)]
+pub struct HotMemoriesParams {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotMemoriesResponse {
+    pub user_id: String,
+    pub memories: Vec<MemoryResponse>,
+    pub total: usize,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct SimilarMemoriesParams {
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarMemoryItem {
+    pub memory: MemoryResponse,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarMemoriesResponse {
+    pub memory_id: String,
+    pub results: Vec<SimilarMemoryItem>,
+    pub total: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchiveMemoryResponse {
     pub id: String,
@@ -525,3 +723,126 @@ pub struct UpdateMemoryResponse {
     pub id: String,
     pub message: String,
 }
+
+/// Default minimum number of episodic memories required for consolidation
+const DEFAULT_MIN_CONSOLIDATION_MEMORIES: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidateMemoriesRequest {
+    pub topic: String,
+    pub min_memories: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidateMemoriesResponse {
+    pub user_id: String,
+    pub topic: String,
+    pub consolidated: bool,
+    pub source_count: usize,
+    pub memory: Option<MemoryResponse>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{MockDatabase, claims, json_response};
+    use crate::models::entity::{Entity, EntityType};
+    use crate::models::memory::{MemorySource, MemoryType};
+    use crate::models::memory_repository::MemoryRepository;
+    use crate::storage::in_memory::InMemoryMemoryRepository;
+
+    #[tokio::test]
+    async fn test_create_memory_links_mentioned_entities() {
+        let db = MockDatabase::start().await;
+        let mut project = Entity::new("Hippos", EntityType::Project);
+        project.tenant_id = "tenant_a".to_string();
+        let mut person = Entity::new("Alice", EntityType::Person);
+        person.tenant_id = "tenant_a".to_string();
+
+        db.respond("name CONTAINS 'Hippos'", serde_json::json!([project]))
+            .await;
+        db.respond("name CONTAINS 'Alice'", serde_json::json!([person]))
+            .await;
+
+        let request = CreateMemoryRequest {
+            memory_type: MemoryType::Episodic,
+            content: "I worked on the Hippos project with Alice".to_string(),
+            source: MemorySource::Conversation,
+            source_id: None,
+            parent_id: None,
+            tags: Vec::new(),
+            topics: Vec::new(),
+            expires_at: None,
+        };
+        let response = create_memory(
+            State(db.app_state()),
+            Extension(claims("tenant_a", "user")),
+            Json(request),
+        )
+        .await
+        .unwrap()
+        .into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let relationships: Vec<String> = db
+            .queries()
+            .await
+            .into_iter()
+            .filter(|q| q.starts_with("CREATE relationship"))
+            .collect();
+        assert_eq!(relationships.len(), 2);
+        assert!(relationships.iter().any(|q| {
+            q.contains(&format!("target_entity_id = '{}'", project.id))
+                && q.contains("relationship_type = 'part_of'")
+                && q.contains("tenant_id = 'tenant_a'")
+        }));
+        assert!(relationships.iter().any(|q| {
+            q.contains(&format!("target_entity_id = '{}'", person.id))
+                && q.contains("relationship_type = 'related_to'")
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_get_memories_by_type_returns_only_that_type() {
+        let db = MockDatabase::start().await;
+        let repo = InMemoryMemoryRepository::new();
+        let mut episodic_ids = Vec::new();
+        for (user_id, memory_type) in [
+            ("user_1", MemoryType::Episodic),
+            ("user_1", MemoryType::Episodic),
+            ("user_1", MemoryType::Semantic),
+            ("user_2", MemoryType::Episodic),
+        ] {
+            let wanted = user_id == "user_1" && memory_type == MemoryType::Episodic;
+            let memory = Memory::new(user_id, memory_type, "content", MemorySource::Conversation);
+            if wanted {
+                episodic_ids.push(memory.id.clone());
+            }
+            repo.create(&memory).await.unwrap();
+        }
+        let mut state = db.app_state();
+        state.memory_repository = std::sync::Arc::new(repo);
+
+        let response = get_memories_by_type(
+            State(state),
+            Extension(claims("tenant_a", "user")),
+            Path("Episodic".to_string()),
+            Query(ListMemoriesParams::default()),
+        )
+        .await;
+        let (status, body) = json_response(response).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let memories = body["memories"].as_array().unwrap();
+        let mut ids: Vec<&str> = memories.iter().map(|m| m["id"].as_str().unwrap()).collect();
+        ids.sort();
+        episodic_ids.sort();
+        assert_eq!(ids, episodic_ids);
+        let episodic = serde_json::to_value(MemoryType::Episodic).unwrap();
+        assert!(
+            memories
+                .iter()
+                .all(|m| m["memory_type"] == episodic && m["user_id"] == "user_1")
+        );
+    }
+}
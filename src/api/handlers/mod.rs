@@ -2,6 +2,8 @@
 //!
 //! HTTP 请求处理程序。
 
+pub mod admin_handler;
+pub mod auth_handler;
 pub mod entity_handler;
 pub mod memory_handler;
 pub mod pattern_handler;
@@ -10,6 +12,8 @@ pub mod search_handler;
 pub mod session_handler;
 pub mod turn_handler;
 
+pub use admin_handler::*;
+pub use auth_handler::*;
 pub use entity_handler::*;
 pub use memory_handler::*;
 pub use pattern_handler::*;
@@ -5,8 +5,8 @@
 use axum::{
     Json,
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
 };
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
@@ -15,9 +15,12 @@ use tracing::debug;
 use crate::{
     api::{app_state::AppState, dto::pattern_dto::*},
     error::AppError,
-    models::pattern::{Pattern, PatternQuery, PatternType, PatternUsage},
+    models::pattern::{
+        Pattern, PatternQuery, PatternStats, PatternStatus, PatternType, PatternUsage,
+    },
     models::pattern_repository::PatternRepository,
     security::auth::Claims,
+    services::pattern_manager::{FieldChange, PatternManager},
 };
 
 /// Create a new pattern
@@ -74,6 +77,9 @@ pub async fn create_pattern(
         .create(&pattern)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
+    state
+        .metrics
+        .record_pattern_created(&created_pattern.pattern_type.to_string());
 
     let response = PatternResponse::from(created_pattern);
 
@@ -91,8 +97,8 @@ pub async fn get_pattern(
     debug!("Getting pattern: {}", id);
 
     let pattern = state
-        .pattern_repository
-        .get_by_id(&id)
+        .pattern_manager()
+        .get_pattern(&id)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?
         .ok_or_else(|| AppError::NotFound(format!("Pattern not found: {}", id)))?;
@@ -109,6 +115,75 @@ pub async fn get_pattern(
     Ok(Json(response))
 }
 
+/// Export a pattern as JSON or as a Markdown attachment
+///
+/// GET /api/v1/patterns/:id/export
+pub async fn export_pattern(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<ExportPatternParams>,
+) -> Result<Response, AppError> {
+    debug!("Exporting pattern {} as {:?}", id, params.format);
+
+    let pattern = state
+        .pattern_manager()
+        .get_pattern(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Pattern not found: {}", id)))?;
+
+    if !pattern.is_public && pattern.created_by != claims.sub {
+        return Err(AppError::Authorization(
+            "Access denied to pattern of another user".to_string(),
+        ));
+    }
+
+    let response = match params.format {
+        ExportFormat::Json => Json(PatternResponse::from(pattern)).into_response(),
+        ExportFormat::Markdown => {
+            let disposition = format!(
+                "attachment; filename=\"{}.md\"",
+                export_file_name(&pattern.name)
+            );
+            (
+                [
+                    (
+                        header::CONTENT_TYPE,
+                        "text/markdown; charset=utf-8".to_string(),
+                    ),
+                    (header::CONTENT_DISPOSITION, disposition),
+                ],
+                pattern.export_to_markdown(),
+            )
+                .into_response()
+        }
+    };
+
+    Ok(response)
+}
+
+/// Replace characters that are unsafe in a `Content-Disposition` filename
+fn export_file_name(name: &str) -> String {
+    let file_name: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ' ' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if file_name.is_empty() {
+        "pattern".to_string()
+    } else {
+        file_name
+    }
+}
+
 /// List patterns with pagination
 ///
 /// GET /api/v1/patterns
@@ -122,13 +197,17 @@ pub async fn list_patterns(
         claims.sub, params.page, params.page_size
     );
 
-    let page = params.page.unwrap_or(1);
-    let page_size = params.page_size.unwrap_or(20).clamp(1, 100) as usize;
-    let offset = ((page - 1) * page_size as u32) as usize;
+    let pagination = state.pagination(
+        params.page.map(|p| p as usize),
+        params.page_size.map(|s| s as usize),
+    );
+    let page = pagination.page as u32;
+    let page_size = pagination.page_size;
+    let offset = pagination.offset();
 
     let patterns = state
         .pattern_repository
-        .list(page_size, offset)
+        .list(page_size, offset, params.include_archived)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -179,6 +258,7 @@ pub async fn search_patterns(
         keyword: request.keyword.clone(),
         created_by: Some(claims.sub.clone()),
         public_only: request.public_only,
+        include_archived: request.include_archived,
         page: request.page,
         page_size: request.page_size,
     };
@@ -232,6 +312,12 @@ pub async fn update_pattern(
         ));
     }
 
+    state
+        .pattern_repository
+        .save_version(&pattern)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
     if let Some(name) = request.name {
         pattern.name = name;
     }
@@ -253,12 +339,15 @@ pub async fn update_pattern(
     if let Some(explanation) = request.explanation {
         pattern.explanation = Some(explanation);
     }
+    pattern.updated_at = Utc::now();
+    pattern.version += 1;
 
     state
         .pattern_repository
         .update(&id, &pattern)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
+    state.pattern_cache.invalidate(&id);
 
     let response = UpdatePatternResponse {
         id,
@@ -292,8 +381,8 @@ pub async fn delete_pattern(
     }
 
     state
-        .pattern_repository
-        .delete(&id)
+        .pattern_manager()
+        .delete_pattern(&id)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -305,6 +394,67 @@ pub async fn delete_pattern(
     Ok(Json(response))
 }
 
+/// Deprecate a pattern
+///
+/// POST /api/v1/patterns/:id/deprecate
+pub async fn deprecate_pattern(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Deprecating pattern: {}", id);
+
+    change_pattern_status(&state, &claims, id, PatternStatus::Deprecated).await
+}
+
+/// Restore a deprecated or archived pattern
+///
+/// POST /api/v1/patterns/:id/restore
+pub async fn restore_pattern(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Restoring pattern: {}", id);
+
+    change_pattern_status(&state, &claims, id, PatternStatus::Active).await
+}
+
+/// Set the status of a pattern owned by the caller
+async fn change_pattern_status(
+    state: &AppState,
+    claims: &Claims,
+    id: String,
+    status: PatternStatus,
+) -> Result<Json<PatternStatusResponse>, AppError> {
+    let pattern = state
+        .pattern_repository
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Pattern not found: {}", id)))?;
+
+    if pattern.created_by != claims.sub {
+        return Err(AppError::Authorization(
+            "Access denied to pattern of another user".to_string(),
+        ));
+    }
+
+    let manager = state.pattern_manager();
+    match status {
+        PatternStatus::Active => manager.restore_pattern(&id).await,
+        PatternStatus::Deprecated => manager.deprecate_pattern(&id).await,
+        PatternStatus::Archived => manager.delete_pattern(&id).await,
+    }
+    .map_err(|e| AppError::Database(e.to_string()))?;
+
+    Ok(Json(PatternStatusResponse {
+        message: format!("Pattern status set to {}", status),
+        id,
+        status,
+    }))
+}
+
 /// Record pattern usage
 ///
 /// POST /api/v1/patterns/:id/usage
@@ -346,6 +496,7 @@ pub async fn record_usage(
         .record_usage(&id, &usage)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
+    state.pattern_cache.invalidate(&id);
 
     let response = RecordUsageResponse {
         usage_id,
@@ -367,7 +518,7 @@ pub async fn match_patterns(
     debug!("Matching patterns for user: {}", claims.sub);
 
     let patterns = state
-        .pattern_repository
+        .pattern_manager()
         .match_patterns(&request.input, request.max_matches)
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
@@ -416,25 +567,132 @@ pub async fn get_pattern_stats(
         avg_success_rate: stats.avg_success_rate,
         high_quality_count: stats.high_quality_count,
         total_usages: stats.total_usages,
-        most_used_pattern: if !stats.most_used_pattern_id.is_empty() {
-            Some(PatternMostUsedDto {
-                pattern_id: stats.most_used_pattern_id,
-                pattern_name: stats.most_used_pattern_name,
-                usage_count: 0,
-            })
-        } else {
-            None
-        },
+        most_used_pattern: most_used_pattern(stats),
+    };
+
+    Ok(Json(response))
+}
+
+/// Get aggregate pattern statistics without authentication
+///
+/// GET /api/v1/patterns/stats (when `enable_public_stats_endpoint` is set)
+pub async fn get_public_pattern_stats(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Getting public pattern stats");
+
+    let stats = state
+        .pattern_repository
+        .get_stats()
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let response = PublicPatternStatsResponse {
+        total_patterns: stats.total_count,
+        avg_success_rate: stats.avg_success_rate,
+        most_used_pattern: most_used_pattern(stats),
+    };
+
+    Ok(Json(response))
+}
+
+fn most_used_pattern(stats: PatternStats) -> Option<PatternMostUsedDto> {
+    if stats.most_used_pattern_id.is_empty() {
+        return None;
+    }
+
+    Some(PatternMostUsedDto {
+        pattern_id: stats.most_used_pattern_id,
+        pattern_name: stats.most_used_pattern_name,
+        usage_count: 0,
+    })
+}
+
+/// Diff two versions of a pattern
+///
+/// GET /api/v1/patterns/:id/history/:version_a/diff/:version_b
+pub async fn get_pattern_diff(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, version_a, version_b)): Path<(String, u32, u32)>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!(
+        "Diffing pattern {} versions {} -> {}",
+        id, version_a, version_b
+    );
+
+    let current = state
+        .pattern_repository
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Pattern not found: {}", id)))?;
+
+    if !current.is_public && current.created_by != claims.sub {
+        return Err(AppError::Authorization(
+            "Access denied to pattern of another user".to_string(),
+        ));
+    }
+
+    let old = load_pattern_version(&state, &current, version_a).await?;
+    let new = load_pattern_version(&state, &current, version_b).await?;
+    let diff = PatternManager::get_pattern_diff(&old, &new);
+
+    let response = PatternDiffResponse {
+        pattern_id: id,
+        version_a,
+        version_b,
+        changed_fields: diff.changed_fields,
     };
 
     Ok(Json(response))
 }
 
+/// Resolve a pattern version: the current version or a stored snapshot
+async fn load_pattern_version(
+    state: &AppState,
+    current: &Pattern,
+    version: u32,
+) -> Result<Pattern, AppError> {
+    if version == current.version {
+        return Ok(current.clone());
+    }
+
+    state
+        .pattern_repository
+        .get_version(&current.id, version)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "Pattern {} has no version {}",
+                current.id, version
+            ))
+        })
+}
+
 // Query parameters for listing patterns
 #[derive(Debug, Deserialize, Default)]
 pub struct ListPatternsParams {
     pub page: Option<u32>,
     pub page_size: Option<u32>,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+// Query parameters for exporting a pattern
+#[derive(Debug, Deserialize, Default)]
+pub struct ExportPatternParams {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Markdown,
 }
 
 // Response types
@@ -450,6 +708,13 @@ pub struct DeletePatternResponse {
     pub message: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternStatusResponse {
+    pub id: String,
+    pub status: PatternStatus,
+    pub message: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecordUsageResponse {
     pub usage_id: String,
@@ -463,6 +728,14 @@ pub struct MatchPatternsResponse {
     pub input: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternDiffResponse {
+    pub pattern_id: String,
+    pub version_a: u32,
+    pub version_b: u32,
+    pub changed_fields: Vec<FieldChange>,
+}
+
 // From implementations for DTO conversions
 impl From<PatternTypeDto> for PatternType {
     fn from(dto: PatternTypeDto) -> Self {
@@ -527,6 +800,89 @@ impl From<Pattern> for PatternResponse {
             is_public: pattern.is_public,
             confidence: pattern.confidence,
             version: pattern.version,
+            status: pattern.status,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::create_router;
+    use crate::api::test_support::{MockDatabase, claims, json_response};
+    use crate::models::pattern::{Pattern, PatternType};
+    use axum::{body::Body, http::Request, http::StatusCode};
+    use tower::ServiceExt;
+
+    fn stats_request() -> Request<Body> {
+        Request::builder()
+            .uri("/api/v1/patterns/stats")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_public_pattern_stats_served_without_credentials() {
+        let db = MockDatabase::start().await;
+        db.respond(
+            "SELECT count() FROM pattern",
+            serde_json::json!([{ "count": 3 }]),
+        )
+        .await;
+        let app = create_router(db.app_state().with_public_stats_endpoint(true));
+
+        let (status, body) = json_response(app.oneshot(stats_request()).await.unwrap()).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_patterns"], 3);
+        assert!(body.get("workflow_count").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pattern_stats_require_credentials_unless_public() {
+        let db = MockDatabase::start().await;
+        let app = create_router(db.app_state());
+
+        let response = app.oneshot(stats_request()).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(db.queries().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deprecate_pattern_goes_through_pattern_manager() {
+        let db = MockDatabase::start().await;
+        let pattern = Pattern::new(
+            "user_1",
+            PatternType::Skill,
+            "retry",
+            "flaky call",
+            "backoff",
+        );
+        db.respond("SELECT * FROM pattern", serde_json::json!([pattern]))
+            .await;
+        db.respond("UPDATE pattern SET status", serde_json::json!([]))
+            .await;
+        let state = db.app_state();
+        state.pattern_cache.insert(pattern.clone());
+
+        let response = deprecate_pattern(
+            State(state.clone()),
+            Extension(claims("tenant_1", "user")),
+            Path(pattern.id.clone()),
+        )
+        .await
+        .unwrap();
+        let (status, body) = json_response(response).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "deprecated");
+        assert!(
+            db.queries()
+                .await
+                .iter()
+                .any(|q| q.contains("UPDATE pattern SET status = 'deprecated'"))
+        );
+        assert!(state.pattern_cache.get(&pattern.id).is_none());
+    }
+}
@@ -30,11 +30,7 @@ pub async fn create_profile(
     debug!("Creating profile for user: {}", claims.sub);
 
     // Check if profile already exists for this user
-    let existing = state
-        .profile_repository
-        .get_by_user_id(&claims.sub)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+    let existing = state.profile_service.get_by_user_id(&claims.sub).await?;
 
     if existing.is_some() {
         return Err(AppError::Conflict(
@@ -66,11 +62,7 @@ pub async fn create_profile(
         profile.add_interest(&interest);
     }
 
-    let created_profile = state
-        .profile_repository
-        .create(&profile)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+    let created_profile = state.profile_service.create(&profile).await?;
 
     let response = ProfileResponse::from(created_profile);
 
@@ -88,10 +80,9 @@ pub async fn get_profile(
     debug!("Getting profile: {}", id);
 
     let profile = state
-        .profile_repository
+        .profile_service
         .get_by_id(&id)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?
+        .await?
         .ok_or_else(|| AppError::NotFound(format!("Profile not found: {}", id)))?;
 
     // Verify ownership
@@ -116,10 +107,9 @@ pub async fn get_my_profile(
     debug!("Getting profile for user: {}", claims.sub);
 
     let profile = state
-        .profile_repository
+        .profile_service
         .get_by_user_id(&claims.sub)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?
+        .await?
         .ok_or_else(|| AppError::NotFound("Profile not found for current user".to_string()))?;
 
     let response = ProfileResponse::from(profile);
@@ -139,10 +129,9 @@ pub async fn update_profile(
     debug!("Updating profile: {}", id);
 
     let mut profile = state
-        .profile_repository
+        .profile_service
         .get_by_id(&id)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?
+        .await?
         .ok_or_else(|| AppError::NotFound(format!("Profile not found: {}", id)))?;
 
     if profile.user_id != claims.sub {
@@ -176,11 +165,7 @@ pub async fn update_profile(
     profile.updated_at = chrono::Utc::now();
     profile.version += 1;
 
-    state
-        .profile_repository
-        .update(&id, &profile)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+    state.profile_service.update(&profile).await?;
 
     let response = UpdateProfileResponse {
         id,
@@ -203,25 +188,21 @@ pub async fn list_profiles(
         claims.sub, params.page, params.page_size
     );
 
-    let page = params.page.unwrap_or(1);
-    let page_size = params.page_size.unwrap_or(20).clamp(1, 100) as usize;
-    let offset = ((page - 1) * page_size as u32) as usize;
+    let pagination = state.pagination(
+        params.page.map(|p| p as usize),
+        params.page_size.map(|s| s as usize),
+    );
+    let page = pagination.page as u32;
+    let page_size = pagination.page_size;
 
     let profiles = state
-        .profile_repository
-        .list(page_size, offset)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
-
-    // Filter to only include profiles owned by the current user
-    let user_profiles: Vec<Profile> = profiles
-        .into_iter()
-        .filter(|p| p.user_id == claims.sub)
-        .collect();
-
-    let total = user_profiles.len() as u64;
+        .profile_service
+        .list_by_user(&claims.sub, pagination)
+        .await?;
+    let total = state.profile_service.count_by_user(&claims.sub).await?;
 
-    let profile_responses: Vec<ProfileResponse> = user_profiles.into_iter().map(ProfileResponse::from).collect();
+    let profile_responses: Vec<ProfileResponse> =
+        profiles.into_iter().map(ProfileResponse::from).collect();
 
     let response = ListProfilesResponse {
         profiles: profile_responses,
@@ -244,10 +225,9 @@ pub async fn delete_profile(
     debug!("Deleting profile: {}", id);
 
     let profile = state
-        .profile_repository
+        .profile_service
         .get_by_id(&id)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?
+        .await?
         .ok_or_else(|| AppError::NotFound(format!("Profile not found: {}", id)))?;
 
     if profile.user_id != claims.sub {
@@ -256,11 +236,7 @@ pub async fn delete_profile(
         ));
     }
 
-    state
-        .profile_repository
-        .delete(&id)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+    state.profile_service.delete(&id).await?;
 
     let response = DeleteProfileResponse {
         id,
@@ -270,6 +246,39 @@ pub async fn delete_profile(
     Ok(Json(response))
 }
 
+/// Merge a duplicate profile into this one
+///
+/// POST /api/v1/profiles/:id/merge
+pub async fn merge_profiles(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(request): Json<MergeProfileRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Merging profile {} into {}", request.source_profile_id, id);
+
+    for profile_id in [&id, &request.source_profile_id] {
+        let profile = state
+            .profile_service
+            .get_by_id(profile_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Profile not found: {}", profile_id)))?;
+
+        if profile.user_id != claims.sub {
+            return Err(AppError::Authorization(
+                "Access denied to profile of another user".to_string(),
+            ));
+        }
+    }
+
+    let merged = state
+        .profile_service
+        .merge_profiles(&id, &request.source_profile_id)
+        .await?;
+
+    Ok(Json(ProfileResponse::from(merged)))
+}
+
 /// Add a fact to a profile
 ///
 /// POST /api/v1/profiles/:id/facts
@@ -422,6 +431,38 @@ pub async fn get_profile_stats(
     Ok(Json(response))
 }
 
+/// Get when a profile was last synced from profile memories
+///
+/// GET /api/v1/profiles/:id/sync-status
+pub async fn get_profile_sync_status(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Getting profile sync status: {}", id);
+
+    let profile = state
+        .profile_repository
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Profile not found: {}", id)))?;
+
+    if profile.user_id != claims.sub {
+        return Err(AppError::Authorization(
+            "Access denied to profile of another user".to_string(),
+        ));
+    }
+
+    let response = ProfileSyncStatusResponse {
+        id: profile.id,
+        sync_count: profile.sync_count,
+        last_synced_at: profile.last_synced_at,
+    };
+
+    Ok(Json(response))
+}
+
 /// Add a preference to a profile
 ///
 /// POST /api/v1/profiles/:id/preferences
@@ -9,7 +9,10 @@ use tracing::debug;
 use crate::{
     api::{app_state::AppState, dto::search_dto::*},
     error::AppError,
+    index::SearchOptions,
     security::auth::Claims,
+    security::rbac::{ActionType, ClaimsExt, Permission, ResourceType},
+    security::validation::{Validatable, validators},
 };
 
 #[derive(Deserialize)]
@@ -34,9 +37,8 @@ pub async fn semantic_search(
         session_id, request.query
     );
 
-    if request.query.is_empty() {
-        return Err(AppError::Validation("Query cannot be empty".to_string()));
-    }
+    validators::validate_session_id(&session_id)?;
+    request.validate()?;
 
     let session = state
         .session_service
@@ -54,8 +56,14 @@ pub async fn semantic_search(
     let start_time = std::time::Instant::now();
 
     let results = state
-        .retrieval_service
-        .semantic_search(&session_id, &request.query, request.limit.unwrap_or(10))
+        .ensure_retrieval_service()
+        .await?
+        .semantic_search(
+            &session_id,
+            &request.query,
+            request.limit.unwrap_or(10),
+            request.threshold,
+        )
         .await?;
 
     let took_ms = start_time.elapsed().as_millis() as u64;
@@ -90,15 +98,18 @@ pub async fn hybrid_search(
     Path(session_id): Path<String>,
     Query(params): Query<HybridSearchQueryParams>,
 ) -> Result<impl IntoResponse, AppError> {
-    let query = params.q.unwrap_or_default();
+    let request = HybridSearchRequest {
+        query: params.q.unwrap_or_default(),
+        limit: params.limit,
+    };
     debug!(
         "Hybrid search for session: {}, query: {}",
-        session_id, query
+        session_id, request.query
     );
 
-    if query.is_empty() {
-        return Err(AppError::Validation("Query cannot be empty".to_string()));
-    }
+    validators::validate_session_id(&session_id)?;
+    request.validate()?;
+    let query = request.query;
 
     let session = state
         .session_service
@@ -116,8 +127,9 @@ pub async fn hybrid_search(
     let start_time = std::time::Instant::now();
 
     let results = state
-        .retrieval_service
-        .hybrid_search(&session_id, &query, params.limit.unwrap_or(10))
+        .ensure_retrieval_service()
+        .await?
+        .hybrid_search(&session_id, &query, request.limit.unwrap_or(10))
         .await?;
 
     let took_ms = start_time.elapsed().as_millis() as u64;
@@ -154,6 +166,8 @@ pub async fn get_recent_context(
 ) -> Result<impl IntoResponse, AppError> {
     debug!("Getting recent context for session: {}", session_id);
 
+    validators::validate_session_id(&session_id)?;
+
     let session = state
         .session_service
         .get_by_id(&session_id)
@@ -170,7 +184,8 @@ pub async fn get_recent_context(
     let limit = params.limit.unwrap_or(10);
 
     let recent = state
-        .retrieval_service
+        .ensure_retrieval_service()
+        .await?
         .list_recent(&session_id, limit)
         .await?;
 
@@ -194,3 +209,86 @@ pub async fn get_recent_context(
 
     Ok(Json(response))
 }
+
+/// Search across every session of a tenant
+///
+/// POST /api/v1/search/global
+pub async fn global_search(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(request): Json<GlobalSearchRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!(
+        "Global search for tenant: {}, query: {}",
+        request.tenant_id, request.query
+    );
+
+    request.validate()?;
+
+    if !request.use_semantic && !request.use_full_text {
+        return Err(AppError::Validation(
+            "At least one of use_semantic or use_full_text must be enabled".to_string(),
+        ));
+    }
+
+    if !claims.can_access_tenant(&request.tenant_id) {
+        return Err(AppError::Authorization(
+            "Access denied to sessions of another tenant".to_string(),
+        ));
+    }
+
+    let permission = Permission::new(ResourceType::Index, ActionType::Search);
+    if !state
+        .authorizer
+        .check_permission(&claims, &permission)
+        .await
+    {
+        return Err(AppError::Authorization(
+            "Global search requires index search permission".to_string(),
+        ));
+    }
+
+    let options = SearchOptions {
+        limit: request.limit.unwrap_or(10) as usize,
+        use_semantic: request.use_semantic,
+        use_full_text: request.use_full_text,
+        ..Default::default()
+    };
+
+    let start_time = std::time::Instant::now();
+
+    let results = state
+        .ensure_index_service()
+        .await?
+        .search_global(&request.tenant_id, &request.query, options)
+        .await?;
+
+    let took_ms = start_time.elapsed().as_millis() as u64;
+
+    let search_results: Vec<GlobalSearchResultItem> = results
+        .into_iter()
+        .map(|r| GlobalSearchResultItem {
+            session_id: r.session_id,
+            session_name: r.session_name,
+            result: SearchResultItem {
+                turn_id: r.turn_id,
+                gist: r.gist,
+                score: r.score,
+                result_type: format!("{:?}", r.result_type).to_lowercase(),
+                turn_number: r.turn_number,
+                timestamp: r.timestamp.to_rfc3339(),
+                sources: r.sources,
+            },
+        })
+        .collect();
+
+    let response = GlobalSearchResponse {
+        tenant_id: request.tenant_id,
+        query: request.query,
+        total_results: search_results.len(),
+        results: search_results,
+        took_ms,
+    };
+
+    Ok(Json(response))
+}
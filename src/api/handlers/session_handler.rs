@@ -4,16 +4,52 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
+use futures_util::stream::{self, StreamExt};
 use serde::Deserialize;
-use tracing::debug;
+use std::sync::LazyLock;
+use tracing::{debug, warn};
 
 use crate::{
-    api::{app_state::AppState, dto::session_dto::*},
+    api::{
+        app_state::AppState, dto::session_dto::*, handlers::turn_handler::convert_turn_to_response,
+    },
     error::AppError,
+    models::entity::Entity,
+    models::entity_repository::EntityRepository,
+    models::turn::Turn,
     security::auth::Claims,
-    services::session::{Pagination, SessionQuery},
+    security::rbac::{ActionType, Permission, ResourceType},
+    services::dehydration::TokenEstimate,
+    services::session::SessionQuery,
+    services::snapshot::SessionSnapshotService,
+    services::turn::{TruncationStrategy, approximate_tokens},
 };
 
+/// 单次批量索引请求允许的最大轮次数
+const MAX_BATCH_INDEX_TURNS: usize = 200;
+
+/// 批量索引的并发度
+const BATCH_INDEX_CONCURRENCY: usize = 8;
+
+/// 少于该轮次数时摘要直接返回原始内容，不触发脱水
+const MIN_SUMMARY_TURNS: usize = 3;
+
+/// 时间线每页的条目数
+const TIMELINE_PAGE_SIZE: usize = 20;
+
+/// 每个轮次用于检索实体的最大候选词数
+const MAX_TIMELINE_ENTITY_TERMS: usize = 5;
+
+/// 并发构建时间线条目的轮次数
+const TIMELINE_CONCURRENCY: usize = 4;
+
+/// 每个候选词最多检索的实体数
+const TIMELINE_ENTITIES_PER_TERM: usize = 20;
+
+/// 轮次内容中首字母大写的连续单词，视为候选实体名
+static CAPITALIZED_NAME: LazyLock<regex::Regex> =
+    LazyLock::new(|| regex::Regex::new(r"[A-Z][a-z]+(?:\s+[A-Z][a-z]+)*").unwrap());
+
 /// 从请求扩展中提取 tenant_id
 /// 如果没有 claims，使用 "default" 作为默认租户
 fn extract_tenant_id(claims: Option<&Claims>) -> String {
@@ -49,17 +85,17 @@ pub async fn list_sessions(
     Query(params): Query<ListSessionsParams>,
 ) -> Result<impl IntoResponse, AppError> {
     debug!(
-        "Listing sessions: page={:?}, page_size={:?}",
-        params.page, params.page_size
+        "Listing sessions: page={:?}, page_size={:?}, status={:?}",
+        params.page, params.page_size, params.status
     );
 
     let tenant_id = extract_tenant_id(Some(&claims));
-    let page = params.page.unwrap_or(1);
-    let page_size = params.page_size.unwrap_or(20);
+    let pagination = state.pagination(params.page, params.page_size);
+    let (page, page_size) = (pagination.page, pagination.page_size);
 
     let query = SessionQuery {
-        pagination: Pagination::new(page, page_size),
-        status: None,
+        pagination,
+        status: params.status.clone(),
     };
 
     let sessions = state
@@ -70,7 +106,7 @@ pub async fn list_sessions(
 
     let total = state
         .session_service
-        .count(&tenant_id)
+        .count(&tenant_id, params.status.as_deref())
         .await
         .map_err(|e| AppError::Database(e.to_string()))?;
 
@@ -95,6 +131,7 @@ pub async fn list_sessions(
                 total_tokens: s.stats.total_tokens,
                 storage_size: s.stats.storage_size,
                 last_indexed_at: s.stats.last_indexed_at,
+                token_estimate: None,
             },
         })
         .collect();
@@ -113,6 +150,7 @@ pub async fn get_session(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Path(id): Path<String>,
+    Query(params): Query<GetSessionParams>,
 ) -> Result<impl IntoResponse, AppError> {
     debug!("Getting session: {}", id);
 
@@ -130,6 +168,24 @@ pub async fn get_session(
         ));
     }
 
+    // Token 估算需要读取全部轮次，仅在显式请求时计算；失败不影响会话本身的返回
+    let token_estimate = if !params.include_token_estimate {
+        None
+    } else {
+        match state.ensure_dehydration_service().await {
+            Ok(service) => service
+                .estimate_token_count(&session.id)
+                .await
+                .inspect_err(|e| warn!("Failed to estimate tokens for session {}: {}", id, e))
+                .ok()
+                .map(token_estimate_response),
+            Err(e) => {
+                warn!("Dehydration service unavailable: {}", e);
+                None
+            }
+        }
+    };
+
     let response = SessionResponse {
         id: session.id,
         tenant_id: session.tenant_id,
@@ -149,6 +205,7 @@ pub async fn get_session(
             total_tokens: session.stats.total_tokens,
             storage_size: session.stats.storage_size,
             last_indexed_at: session.stats.last_indexed_at,
+            token_estimate,
         },
     };
 
@@ -202,6 +259,42 @@ pub async fn update_session(
     Ok(Json(response))
 }
 
+pub async fn update_session_config(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateSessionConfigRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Updating session config: {}", id);
+
+    let session = state
+        .session_service
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let mut config = session.config;
+    request.apply_to(&mut config);
+
+    let session = state.session_service.update_config(&id, config).await?;
+
+    let response = SessionConfigResponse {
+        summary_limit: session.config.summary_limit,
+        max_turns: session.config.max_turns,
+        semantic_search_enabled: session.config.semantic_search_enabled,
+        auto_summarize: session.config.auto_summarize,
+    };
+
+    Ok(Json(response))
+}
+
 pub async fn delete_session(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
@@ -323,9 +416,903 @@ pub async fn restore_session(
     Ok(Json(response))
 }
 
+/// Rebuild all index entries for a session (admin only)
+///
+/// POST /api/v1/sessions/:id/reindex
+pub async fn reindex_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Reindexing session: {}", id);
+
+    let permission = Permission::new(ResourceType::Index, ActionType::Manage);
+    if !state.authorizer.check_permission(&claims, &permission).await {
+        return Err(AppError::Authorization(
+            "Reindexing requires index management permission".to_string(),
+        ));
+    }
+
+    let session = state
+        .session_service
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let report = state
+        .ensure_index_service()
+        .await?
+        .reindex_session(&id)
+        .await?;
+
+    let response = ReindexSessionResponse {
+        id,
+        deleted: report.deleted,
+        reindexed: report.reindexed,
+        failed: report.failed,
+        message: "Session reindexed".to_string(),
+    };
+
+    Ok(Json(response))
+}
+
+/// Re-index a batch of turns belonging to the session
+///
+/// POST /api/v1/sessions/:id/index/batch
+pub async fn batch_index_turns(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Json(request): Json<BatchIndexRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!(
+        "Batch indexing {} turns of session: {}",
+        request.turn_ids.len(),
+        id
+    );
+
+    if request.turn_ids.len() > MAX_BATCH_INDEX_TURNS {
+        return Err(AppError::Unprocessable(format!(
+            "At most {} turn IDs can be indexed per request, got {}",
+            MAX_BATCH_INDEX_TURNS,
+            request.turn_ids.len()
+        )));
+    }
+
+    let permission = Permission::new(ResourceType::Index, ActionType::Manage);
+    if !state.authorizer.check_permission(&claims, &permission).await {
+        return Err(AppError::Authorization(
+            "Batch indexing requires index management permission".to_string(),
+        ));
+    }
+
+    let session = state
+        .session_service
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let turn_ids: Vec<&str> = request.turn_ids.iter().map(String::as_str).collect();
+    let turns = state.turn_repository.get_turns_by_ids(&turn_ids).await?;
+
+    let mut response = BatchIndexResponse {
+        indexed: 0,
+        failed: Vec::new(),
+        errors: Vec::new(),
+    };
+    let mut pending = Vec::with_capacity(turns.len());
+    for (turn_id, turn) in request.turn_ids.iter().zip(turns) {
+        match turn {
+            Some(turn) if turn.session_id == id => pending.push(turn),
+            _ => {
+                response.failed.push(turn_id.clone());
+                response
+                    .errors
+                    .push(format!("Turn not found in session: {}", turn_id));
+            }
+        }
+    }
+
+    let index_service = state.ensure_index_service().await?;
+    let mut outcomes = stream::iter(pending)
+        .map(|turn| async move {
+            let outcome = index_service.index_turn(&turn).await;
+            (turn.id, outcome)
+        })
+        .buffer_unordered(BATCH_INDEX_CONCURRENCY);
+
+    while let Some((turn_id, outcome)) = outcomes.next().await {
+        match outcome {
+            Ok(_) => response.indexed += 1,
+            Err(e) => {
+                warn!("Failed to index turn {}: {}", turn_id, e);
+                response.failed.push(turn_id);
+                response.errors.push(e.to_string());
+            }
+        }
+    }
+
+    Ok(Json(response))
+}
+
+/// Create an immutable snapshot of the session's current turns
+///
+/// POST /api/v1/sessions/:id/snapshot
+pub async fn create_session_snapshot(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Creating snapshot of session: {}", id);
+
+    let session = state
+        .session_service
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let snapshot = state.snapshot_service().create_snapshot(&id).await?;
+
+    let response = SessionSnapshotResponse {
+        snapshot_id: snapshot.snapshot_id,
+        session_id: snapshot.session_id,
+        created_at: snapshot.created_at,
+        turn_count: snapshot.turn_count,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Restore the session's turns to the state captured in a snapshot
+///
+/// POST /api/v1/sessions/:id/snapshot/:snapshot_id/restore
+pub async fn restore_session_snapshot(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((id, snapshot_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Restoring session {} from snapshot {}", id, snapshot_id);
+
+    let session = state
+        .session_service
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let result = state
+        .snapshot_service()
+        .restore_snapshot(&id, &snapshot_id)
+        .await?;
+
+    let response = RestoreSnapshotResponse {
+        snapshot_id: result.snapshot_id,
+        session_id: result.session_id,
+        turns_restored: result.turns_restored,
+        turns_discarded: result.turns_discarded,
+        message: "Session restored from snapshot".to_string(),
+    };
+
+    Ok(Json(response))
+}
+
+/// Estimate the LLM token budget of a session
+///
+/// GET /api/v1/sessions/:id/token-estimate
+pub async fn get_token_estimate(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Estimating tokens for session: {}", id);
+
+    let session = state
+        .session_service
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let estimate = state
+        .ensure_dehydration_service()
+        .await?
+        .estimate_token_count(&id)
+        .await?;
+
+    let response = SessionTokenEstimateResponse {
+        id,
+        estimate: token_estimate_response(estimate),
+    };
+
+    Ok(Json(response))
+}
+
+/// Build a context window of recent turns that fits a token budget
+///
+/// GET /api/v1/sessions/:id/context_window
+pub async fn get_context_window(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<ContextWindowParams>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!(
+        "Building context window for session {} (max_tokens={}, model={})",
+        id, params.max_tokens, params.model
+    );
+
+    if params.max_tokens == 0 {
+        return Err(AppError::Validation(
+            "max_tokens must be greater than 0".to_string(),
+        ));
+    }
+
+    let session = state
+        .session_service
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let window = state
+        .turn_service
+        .get_context_window(&id, params.max_tokens, params.strategy)
+        .await?;
+
+    let response = ContextWindowResponse {
+        session_id: id,
+        model: params.model,
+        turns_included: window.turns.len(),
+        turns: window
+            .turns
+            .into_iter()
+            .map(convert_turn_to_response)
+            .collect(),
+        tokens_used: window.tokens_used,
+        turns_truncated: window.turns_truncated,
+    };
+
+    Ok(Json(response))
+}
+
+/// Compress the turns numbered below `before_turn` into a single system turn
+///
+/// POST /api/v1/sessions/:id/compress
+pub async fn compress_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<CompressSessionParams>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!(
+        "Compressing turns before {} in session {}",
+        params.before_turn, id
+    );
+
+    let session = state
+        .session_service
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let compressed = state
+        .turn_service
+        .compress_turns_before(&id, params.before_turn)
+        .await?;
+
+    let response = CompressSessionResponse {
+        turn: convert_turn_to_response(compressed.turn),
+        turns_compressed: compressed.turns_compressed,
+        deleted: compressed.deleted,
+    };
+
+    Ok(Json(response))
+}
+
+/// List a session's turns in order, each with the memories it produced and the entities it mentions
+///
+/// GET /api/v1/sessions/:id/timeline
+pub async fn get_session_timeline(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<SessionTimelineParams>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!(
+        "Building timeline for session {} (page={})",
+        id, params.page
+    );
+
+    let session = state
+        .session_service
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let start = params.page.max(1).saturating_sub(1) * TIMELINE_PAGE_SIZE;
+    let turns = state
+        .turn_repository
+        .list_by_session_filtered(&id, None, TIMELINE_PAGE_SIZE, start)
+        .await?;
+
+    let entries: Vec<TimelineEntry> = stream::iter(turns)
+        .map(|turn| timeline_entry(&state, &session.tenant_id, turn))
+        .buffered(TIMELINE_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<_, _>>()?;
+
+    Ok(Json(entries))
+}
+
+/// 查询轮次产生的记忆与提及的实体（仅限会话所属租户）
+async fn timeline_entry(
+    state: &AppState,
+    tenant_id: &str,
+    turn: Turn,
+) -> Result<TimelineEntry, AppError> {
+    let memories = state.memory_repository.list_by_source_id(&turn.id).await?;
+
+    let mut entities: Vec<Entity> = Vec::new();
+    for term in entity_search_terms(&turn) {
+        let candidates = state
+            .entity_repository
+            .search_tenant_entities(tenant_id, &term, TIMELINE_ENTITIES_PER_TERM)
+            .await?;
+        for entity in candidates {
+            if mentions_entity(&turn.raw_content, &entity)
+                && !entities.iter().any(|existing| existing.id == entity.id)
+            {
+                entities.push(entity);
+            }
+        }
+    }
+
+    Ok(TimelineEntry {
+        turn: convert_turn_to_response(turn),
+        memories: memories.into_iter().map(Into::into).collect(),
+        entities: entities.into_iter().map(Into::into).collect(),
+    })
+}
+
+/// 实体检索候选词：优先使用脱水主题，其次是内容中首字母大写的词组
+fn entity_search_terms(turn: &Turn) -> Vec<String> {
+    let mut terms: Vec<String> = turn
+        .dehydrated
+        .as_ref()
+        .map(|dehydrated| dehydrated.topics.clone())
+        .unwrap_or_default();
+
+    terms.extend(
+        CAPITALIZED_NAME
+            .find_iter(&turn.raw_content)
+            .map(|m| m.as_str().to_string())
+            .filter(|name| name.len() > 2),
+    );
+
+    let mut unique: Vec<String> = Vec::new();
+    for term in terms {
+        let term = term.trim().to_string();
+        if !term.is_empty() && !unique.contains(&term) {
+            unique.push(term);
+        }
+    }
+    unique.truncate(MAX_TIMELINE_ENTITY_TERMS);
+    unique
+}
+
+/// 内容中是否出现实体名称或别名（忽略大小写）
+fn mentions_entity(content: &str, entity: &Entity) -> bool {
+    let content = content.to_lowercase();
+    std::iter::once(&entity.name)
+        .chain(entity.aliases.iter())
+        .any(|name| !name.is_empty() && content.contains(&name.to_lowercase()))
+}
+
+/// Summarize the most recent turns of a session from their dehydrated gists
+///
+/// GET /api/v1/sessions/:id/summary
+pub async fn get_session_summary(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<String>,
+    Query(params): Query<SessionSummaryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!(
+        "Building summary for session {} (max_turns={})",
+        id, params.max_turns
+    );
+
+    if params.max_turns == 0 {
+        return Err(AppError::Validation(
+            "max_turns must be greater than 0".to_string(),
+        ));
+    }
+
+    let session = state
+        .session_service
+        .get_by_id(&id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let mut turns = state
+        .turn_repository
+        .list_recent_by_session(&id, params.max_turns)
+        .await?;
+
+    // 只为本次摘要覆盖的轮次脱水，不处理会话中更早的轮次
+    if turns.len() >= MIN_SUMMARY_TURNS && turns.iter().any(|turn| turn.dehydrated.is_none()) {
+        state
+            .ensure_dehydration_service()
+            .await?
+            .dehydrate_turns(&mut turns, state.turn_repository.as_ref())
+            .await?;
+    }
+    turns.sort_by_key(|turn| turn.turn_number);
+
+    let summary = summarize_turns(&turns);
+    let response = SessionSummaryResponse {
+        tokens_estimated: approximate_tokens(&summary),
+        turns_covered: turns.len(),
+        summary,
+    };
+
+    Ok(Json(response))
+}
+
+/// 按轮次顺序拼接摘要文本
+///
+/// 轮次数少于 `MIN_SUMMARY_TURNS` 时直接拼接原始内容；否则使用脱水摘要，
+/// 脱水失败的轮次回退到原始内容。
+fn summarize_turns(turns: &[Turn]) -> String {
+    let use_gist = turns.len() >= MIN_SUMMARY_TURNS;
+    turns
+        .iter()
+        .map(|turn| match &turn.dehydrated {
+            Some(dehydrated) if use_gist => dehydrated.gist.as_str(),
+            _ => turn.raw_content.as_str(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn token_estimate_response(estimate: TokenEstimate) -> TokenEstimateResponse {
+    TokenEstimateResponse {
+        total_gist_tokens: estimate.total_gist_tokens,
+        total_raw_tokens: estimate.total_raw_tokens,
+        dehydrated_ratio: estimate.dehydrated_ratio,
+    }
+}
+
 #[derive(Debug, Deserialize, Default)]
 pub struct ListSessionsParams {
     pub page: Option<usize>,
     pub page_size: Option<usize>,
     pub status: Option<String>,
 }
+
+#[derive(Debug, Default, Deserialize)]
+pub struct GetSessionParams {
+    /// 附带 Token 预算估算（需要读取会话的全部轮次）
+    #[serde(default)]
+    pub include_token_estimate: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompressSessionParams {
+    pub before_turn: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionTimelineParams {
+    #[serde(default = "default_timeline_page")]
+    pub page: usize,
+}
+
+fn default_timeline_page() -> usize {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionSummaryParams {
+    #[serde(default = "default_summary_max_turns")]
+    pub max_turns: usize,
+}
+
+fn default_summary_max_turns() -> usize {
+    20
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContextWindowParams {
+    pub max_tokens: u32,
+    pub model: String,
+    #[serde(default)]
+    pub strategy: TruncationStrategy,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{MockDatabase, claims, json_response};
+    use crate::models::entity::EntityType;
+    use crate::models::memory::{Memory, MemorySource, MemoryType};
+    use crate::models::session::Session;
+    use crate::models::turn::DehydratedData;
+
+    #[test]
+    fn test_entity_search_terms_prefers_topics() {
+        let mut turn = Turn::new(
+            "session_1",
+            1,
+            "We moved the Billing Service to Rust last week.",
+        );
+        turn.dehydrated = Some(DehydratedData {
+            topics: vec!["migration".to_string(), "Rust".to_string()],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            entity_search_terms(&turn),
+            vec!["migration", "Rust", "Billing Service"]
+        );
+    }
+
+    #[test]
+    fn test_mentions_entity_checks_name_and_aliases() {
+        let mut entity = Entity::new("PostgreSQL", EntityType::Tool);
+        entity.aliases.push("postgres".to_string());
+
+        assert!(mentions_entity("we run Postgres in prod", &entity));
+        assert!(mentions_entity("POSTGRESQL 16", &entity));
+        assert!(!mentions_entity("we run MySQL in prod", &entity));
+    }
+
+    #[tokio::test]
+    async fn test_session_timeline_links_turn_memories_and_tenant_entities() {
+        let db = MockDatabase::start().await;
+        let session = Session::new("tenant_a", "timeline");
+        let turn = Turn::new(&session.id, 1, "We deployed Kubernetes yesterday.");
+        let mut memory = Memory::new(
+            "user_1",
+            MemoryType::Episodic,
+            "deployed Kubernetes",
+            MemorySource::Conversation,
+        );
+        memory.source_id = Some(turn.id.clone());
+        let entity = Entity::new("Kubernetes", EntityType::Tool);
+
+        db.respond("FROM session WHERE id", serde_json::json!([session]))
+            .await;
+        db.respond("FROM turn WHERE session_id", serde_json::json!([turn]))
+            .await;
+        db.respond(
+            &format!("FROM memory WHERE source_id = '{}'", turn.id),
+            serde_json::json!([memory]),
+        )
+        .await;
+        db.respond("tenant_id = 'tenant_a'", serde_json::json!([entity]))
+            .await;
+
+        let response = get_session_timeline(
+            State(db.app_state()),
+            Extension(claims("tenant_a", "user")),
+            Path(session.id.clone()),
+            Query(SessionTimelineParams { page: 1 }),
+        )
+        .await;
+        let (status, body) = json_response(response).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["turn"]["id"], turn.id);
+        assert_eq!(body[0]["memories"][0]["id"], memory.id);
+        assert_eq!(body[0]["entities"][0]["name"], "Kubernetes");
+
+        let entity_queries: Vec<String> = db
+            .queries()
+            .await
+            .into_iter()
+            .filter(|query| query.contains("FROM entity"))
+            .collect();
+        assert!(!entity_queries.is_empty());
+        assert!(
+            entity_queries
+                .iter()
+                .all(|query| query.contains("tenant_id = 'tenant_a'"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_timeline_rejects_other_tenant() {
+        let db = MockDatabase::start().await;
+        let session = Session::new("tenant_a", "timeline");
+        db.respond("FROM session WHERE id", serde_json::json!([session]))
+            .await;
+
+        let result = get_session_timeline(
+            State(db.app_state()),
+            Extension(claims("tenant_b", "user")),
+            Path(session.id.clone()),
+            Query(SessionTimelineParams { page: 1 }),
+        )
+        .await;
+
+        assert!(matches!(result, Err(AppError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_session_summary_dehydrates_only_fetched_turns() {
+        use crate::services::dehydration::{DehydrationService, SimpleDehydrationService};
+
+        let db = MockDatabase::start().await;
+        let session = Session::new("tenant_a", "summary");
+        let mut turns: Vec<Turn> = (3..=5)
+            .rev()
+            .map(|n| Turn::new(&session.id, n, &format!("Turn {} covered the rollout.", n)))
+            .collect();
+        turns[0].dehydrated = Some(DehydratedData {
+            gist: "already dehydrated".to_string(),
+            ..Default::default()
+        });
+
+        db.respond("FROM session WHERE id", serde_json::json!([session]))
+            .await;
+        db.respond("turn_number DESC LIMIT 3", serde_json::json!(turns))
+            .await;
+
+        let state = db.app_state().with_dehydration_service(|| async {
+            let service = SimpleDehydrationService::new(100, 5, 10);
+            Ok(Box::new(service) as Box<dyn DehydrationService>)
+        });
+        let response = get_session_summary(
+            State(state),
+            Extension(claims("tenant_a", "user")),
+            Path(session.id.clone()),
+            Query(SessionSummaryParams { max_turns: 3 }),
+        )
+        .await;
+        let (status, body) = json_response(response).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["turns_covered"], 3);
+        let summary = body["summary"].as_str().unwrap();
+        assert!(summary.contains("already dehydrated"));
+
+        let queries = db.queries().await;
+        let updated: Vec<&String> = queries
+            .iter()
+            .filter(|query| query.contains("SET dehydrated"))
+            .collect();
+        assert_eq!(updated.len(), 2);
+        for turn in &turns[1..] {
+            assert!(updated.iter().any(|query| query.contains(&turn.id)));
+        }
+        // 不会分页加载整个会话的轮次
+        let full_scan = queries
+            .iter()
+            .any(|q| q.contains("ORDER BY turn_number ASC"));
+        assert!(!full_scan);
+    }
+
+    #[tokio::test]
+    async fn test_get_session_estimates_tokens_only_on_request() {
+        use crate::services::dehydration::{DehydrationService, SimpleDehydrationService};
+        use crate::storage::repository::TurnRepository;
+
+        let db = MockDatabase::start().await;
+        let session = Session::new("tenant_a", "estimate");
+        let turn = Turn::new(&session.id, 1, "abcdefgh");
+        db.respond("FROM session WHERE id", serde_json::json!([session]))
+            .await;
+        db.respond("FROM turn WHERE session_id", serde_json::json!([turn]))
+            .await;
+
+        let turn_repository = std::sync::Arc::new(TurnRepository::new(db.pool()));
+        let state = db.app_state().with_dehydration_service(move || {
+            let turn_repository = turn_repository.clone();
+            async move {
+                let service =
+                    SimpleDehydrationService::new(100, 5, 10).with_turn_repository(turn_repository);
+                Ok(Box::new(service) as Box<dyn DehydrationService>)
+            }
+        });
+        let get = |include_token_estimate| {
+            get_session(
+                State(state.clone()),
+                Extension(claims("tenant_a", "user")),
+                Path(session.id.clone()),
+                Query(GetSessionParams {
+                    include_token_estimate,
+                }),
+            )
+        };
+
+        let (status, body) = json_response(get(false).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["stats"].get("token_estimate").is_none());
+        let queries = db.queries().await;
+        assert!(!queries.iter().any(|q| q.contains("FROM turn")));
+
+        let (status, body) = json_response(get(true).await).await;
+        assert_eq!(status, StatusCode::OK);
+        let raw_tokens = body["stats"]["token_estimate"]["total_raw_tokens"].as_u64();
+        assert!(raw_tokens.unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_compress_session_replaces_index_entries_and_turn_count() {
+        let db = MockDatabase::start().await;
+        let mut session = Session::new("tenant_a", "compress");
+        session.stats.total_turns = 3;
+        let first = Turn::new(&session.id, 1, "We picked Rust for the billing service.");
+        let second = Turn::new(&session.id, 2, "The migration finished last week.");
+        let kept = Turn::new(&session.id, 3, "What should we do next?");
+
+        db.respond("FROM session WHERE id", serde_json::json!([session]))
+            .await;
+        db.respond(
+            "AND turn_number <= 2",
+            serde_json::json!([first.clone(), second.clone()]),
+        )
+        .await;
+        db.respond(
+            "DELETE FROM turn WHERE id IN",
+            serde_json::json!([first.clone(), second.clone()]),
+        )
+        .await;
+
+        let state = db.app_state();
+        let index_service = state.ensure_index_service().await.unwrap();
+        for turn in [&first, &second, &kept] {
+            index_service.index_turn(turn).await.unwrap();
+        }
+
+        let response = compress_session(
+            State(state.clone()),
+            Extension(claims("tenant_a", "user")),
+            Path(session.id.clone()),
+            Query(CompressSessionParams { before_turn: 3 }),
+        )
+        .await;
+        let (status, body) = json_response(response).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["deleted"], 2);
+        let compressed_id = body["turn"]["id"].as_str().unwrap();
+        let mut indexed: Vec<String> = index_service
+            .list_indices(&session.id, 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|record| record.turn_id)
+            .collect();
+        indexed.sort();
+        let mut expected = vec![compressed_id.to_string(), kept.id.clone()];
+        expected.sort();
+        assert_eq!(indexed, expected);
+
+        let queries = db.queries().await;
+        let update = queries
+            .iter()
+            .find(|query| query.starts_with("UPDATE session"))
+            .unwrap();
+        assert!(update.contains("stats.total_turns = math::max([stats.total_turns + -1, 0])"));
+        assert!(!update.contains("status"));
+    }
+
+    #[tokio::test]
+    async fn test_batch_index_rejects_too_many_ids() {
+        let db = MockDatabase::start().await;
+        let turn_ids = (0..=MAX_BATCH_INDEX_TURNS)
+            .map(|i| format!("turn_{}", i))
+            .collect();
+
+        let result = batch_index_turns(
+            State(db.app_state()),
+            Extension(claims("tenant_a", "admin")),
+            Path("session_1".to_string()),
+            Json(BatchIndexRequest { turn_ids }),
+        )
+        .await;
+
+        assert!(matches!(result, Err(AppError::Unprocessable(_))));
+        assert!(db.queries().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batch_index_skips_turns_of_other_sessions() {
+        let db = MockDatabase::start().await;
+        let session = Session::new("tenant_a", "indexing");
+        let own = Turn::new(&session.id, 1, "index me");
+        let foreign = Turn::new("session_other", 1, "not mine");
+
+        db.respond("FROM session WHERE id", serde_json::json!([session]))
+            .await;
+        db.respond("FROM turn WHERE id IN", serde_json::json!([own, foreign]))
+            .await;
+
+        let response = batch_index_turns(
+            State(db.app_state()),
+            Extension(claims("tenant_a", "admin")),
+            Path(session.id.clone()),
+            Json(BatchIndexRequest {
+                turn_ids: vec![own.id.clone(), foreign.id.clone()],
+            }),
+        )
+        .await;
+        let (status, body) = json_response(response).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["indexed"], 1);
+        assert_eq!(body["failed"], serde_json::json!([foreign.id]));
+
+        let lookup = db
+            .queries()
+            .await
+            .into_iter()
+            .find(|query| query.contains("FROM turn WHERE id IN"))
+            .unwrap();
+        assert!(lookup.contains(&format!("turn:⟨{}⟩", own.id)));
+    }
+}
@@ -12,9 +12,12 @@ use crate::{
     error::AppError,
     models::turn::Turn,
     security::auth::Claims,
-    services::turn::TurnQuery,
+    services::turn::{TurnGroup, TurnQuery},
 };
 
+/// 轮次列表默认每页数量
+const DEFAULT_TURN_PAGE_SIZE: usize = 50;
+
 pub async fn create_turn(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
@@ -76,8 +79,11 @@ pub async fn list_turns(
         ));
     }
 
-    let page = params.page.unwrap_or(1);
-    let page_size = params.page_size.unwrap_or(50);
+    let pagination = state.pagination(
+        params.page,
+        Some(params.page_size.unwrap_or(DEFAULT_TURN_PAGE_SIZE)),
+    );
+    let (page, page_size) = (pagination.page, pagination.page_size);
 
     let query = TurnQuery {
         page,
@@ -88,16 +94,18 @@ pub async fn list_turns(
     let turns = state
         .turn_service
         .list_by_session(&session_id, query)
-        .await
-        .map_err(|e| AppError::Database(e.to_string()))?;
+        .await?;
+
+    let total = state
+        .turn_service
+        .count_by_session_filtered(&session_id, params.message_type.as_deref())
+        .await? as usize;
 
     let turn_responses: Vec<TurnResponse> = turns
         .into_iter()
         .map(|t| convert_turn_to_response(t))
         .collect();
 
-    let total = turn_responses.len();
-
     let response = TurnListResponse {
         turns: turn_responses,
         total,
@@ -130,6 +138,41 @@ pub async fn get_turn(
     Ok(Json(response))
 }
 
+pub async fn get_turn_by_number(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((session_id, turn_number)): Path<(String, u64)>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!(
+        "Getting turn number: {} for session: {}",
+        turn_number, session_id
+    );
+
+    let session = state
+        .session_service
+        .get_by_id(&session_id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", session_id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let turn = state
+        .turn_service
+        .get_by_turn_number(&session_id, turn_number)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Turn number not found: {}", turn_number)))?;
+
+    let response = convert_turn_to_response(turn);
+
+    Ok(Json(response))
+}
+
 pub async fn delete_turn(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
@@ -175,6 +218,52 @@ pub async fn delete_turn(
     Ok(Json(response))
 }
 
+/// Delete the turns numbered below `before_turn_number`, keeping the session
+///
+/// `before_turn_number` is required and must be positive, so a missing body
+/// can never wipe the whole session.
+///
+/// DELETE /api/v1/sessions/:session_id/turns
+pub async fn delete_turns(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(session_id): Path<String>,
+    request: Option<Json<DeleteTurnsRequest>>,
+) -> Result<impl IntoResponse, AppError> {
+    let before_turn = request
+        .and_then(|Json(request)| request.before_turn_number)
+        .filter(|&before_turn| before_turn > 0)
+        .ok_or_else(|| {
+            AppError::Validation(
+                "before_turn_number is required and must be greater than 0".to_string(),
+            )
+        })?;
+    debug!(
+        "Deleting turns before {} for session: {}",
+        before_turn, session_id
+    );
+
+    let session = state
+        .session_service
+        .get_by_id(&session_id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", session_id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let deleted = state
+        .turn_service
+        .delete_turns_before(&session_id, before_turn)
+        .await?;
+
+    Ok(Json(DeleteTurnsResponse { deleted }))
+}
+
 pub async fn update_turn(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
@@ -225,7 +314,111 @@ pub async fn update_turn(
     Ok(Json(response))
 }
 
-fn convert_turn_to_response(turn: Turn) -> TurnResponse {
+/// List the turn groups of a session
+///
+/// GET /api/v1/sessions/:session_id/turns/groups
+pub async fn list_turn_groups(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(session_id): Path<String>,
+    Query(params): Query<TurnGroupsParams>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!("Listing turn groups for session: {}", session_id);
+
+    let session = state
+        .session_service
+        .get_by_id(&session_id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", session_id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let groups: Vec<TurnGroupResponse> = state
+        .turn_service
+        .identify_turn_groups(&session_id)
+        .await?
+        .into_iter()
+        .map(|group| convert_group_to_response(group, params.include_turn_ids))
+        .collect();
+
+    let response = TurnGroupListResponse {
+        session_id,
+        total: groups.len(),
+        groups,
+    };
+
+    Ok(Json(response))
+}
+
+/// Get the most recent turns of a session as a conversation window
+///
+/// GET /api/v1/sessions/:session_id/conversation?window=20
+pub async fn get_conversation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(session_id): Path<String>,
+    Query(params): Query<ConversationParams>,
+) -> Result<impl IntoResponse, AppError> {
+    debug!(
+        "Getting conversation window for session: {} (window: {})",
+        session_id, params.window
+    );
+
+    if params.window == 0 {
+        return Err(AppError::Validation(
+            "window must be greater than 0".to_string(),
+        ));
+    }
+
+    let session = state
+        .session_service
+        .get_by_id(&session_id)
+        .await
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", session_id)))?;
+
+    if session.tenant_id != claims.tenant_id {
+        return Err(AppError::Authorization(
+            "Access denied to session of another tenant".to_string(),
+        ));
+    }
+
+    let window = state
+        .turn_service
+        .get_conversation_window(&session_id, params.window, params.include_system)
+        .await?;
+
+    let response = ConversationWindowResponse {
+        session_id,
+        turns: window
+            .turns
+            .into_iter()
+            .map(convert_turn_to_response)
+            .collect(),
+        total_turns: window.total_turns,
+        window_start_turn: window.window_start_turn,
+    };
+
+    Ok(Json(response))
+}
+
+fn convert_group_to_response(group: TurnGroup, include_turn_ids: bool) -> TurnGroupResponse {
+    TurnGroupResponse {
+        group_id: group.group_id,
+        start_turn: group.start_turn,
+        end_turn: group.end_turn,
+        group_type: format!("{:?}", group.group_type),
+        turn_count: group.turn_ids.len(),
+        turn_ids: include_turn_ids.then_some(group.turn_ids),
+    }
+}
+
+pub(crate) fn convert_turn_to_response(turn: Turn) -> TurnResponse {
     let metadata = TurnMetadataResponse {
         timestamp: turn.metadata.timestamp,
         user_id: turn.metadata.user_id,
@@ -261,3 +454,111 @@ pub struct ListTurnsParams {
     pub page_size: Option<usize>,
     pub message_type: Option<String>,
 }
+
+#[derive(Debug, Deserialize, Default)]
+pub struct TurnGroupsParams {
+    #[serde(default)]
+    pub include_turn_ids: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConversationParams {
+    #[serde(default = "default_conversation_window")]
+    pub window: u32,
+    #[serde(default = "default_include_system")]
+    pub include_system: bool,
+}
+
+fn default_conversation_window() -> u32 {
+    20
+}
+
+fn default_include_system() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test_support::{MockDatabase, claims, json_response};
+    use crate::models::session::Session;
+    use crate::services::turn::TurnGroupType;
+
+    fn group() -> TurnGroup {
+        TurnGroup {
+            group_id: "group_1".to_string(),
+            start_turn: 1,
+            end_turn: 2,
+            group_type: TurnGroupType::User,
+            turn_ids: vec!["turn_1".to_string(), "turn_2".to_string()],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_turns_requires_positive_before_turn_number() {
+        let db = MockDatabase::start().await;
+
+        for request in [
+            None,
+            Some(Json(DeleteTurnsRequest::default())),
+            Some(Json(DeleteTurnsRequest {
+                before_turn_number: Some(0),
+            })),
+        ] {
+            let result = delete_turns(
+                State(db.app_state()),
+                Extension(claims("tenant_a", "user")),
+                Path("session_1".to_string()),
+                request,
+            )
+            .await;
+            assert!(matches!(result, Err(AppError::Validation(_))));
+        }
+        assert!(db.queries().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_turns_defaults_to_fifty_per_page() {
+        let db = MockDatabase::start().await;
+        let session = Session::new("tenant_a", "turns");
+        db.respond("FROM session WHERE id", serde_json::json!([session]))
+            .await;
+
+        let response = list_turns(
+            State(db.app_state()),
+            Extension(claims("tenant_a", "user")),
+            Path(session.id.clone()),
+            Query(ListTurnsParams::default()),
+        )
+        .await;
+        let (status, body) = json_response(response).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["page_size"], 50);
+        let queries = db.queries().await;
+        assert!(
+            queries
+                .iter()
+                .any(|q| q.contains("FROM turn") && q.contains("LIMIT 50 START 0"))
+        );
+    }
+
+    #[test]
+    fn test_group_response_omits_turn_ids_by_default() {
+        let response = convert_group_to_response(group(), false);
+        assert_eq!(response.turn_count, 2);
+        assert_eq!(response.group_type, "User");
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json.get("turn_ids").is_none());
+    }
+
+    #[test]
+    fn test_group_response_includes_turn_ids_on_request() {
+        let response = convert_group_to_response(group(), true);
+        assert_eq!(
+            response.turn_ids,
+            Some(vec!["turn_1".to_string(), "turn_2".to_string()])
+        );
+    }
+}
@@ -8,27 +8,66 @@ pub mod app_state;
 pub mod dto;
 pub mod handlers;
 pub mod routes;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 use crate::api::app_state::AppState;
+use crate::api::handlers::pattern_handler;
 use crate::error::AppError;
-use crate::security::middleware::{auth_middleware, security_headers_middleware};
-use axum::Router;
+use crate::security::middleware::{
+    auth_middleware, rate_limit_middleware, security_headers_middleware,
+};
+use axum::{Router, routing::get};
 
 pub fn create_router(app_state: AppState) -> Router {
     let authenticator = app_state.authenticator.clone();
+    let rate_limiter = app_state.rate_limiter.clone();
+    let public_rate_limiter = app_state.rate_limiter.clone();
+    let public_stats = app_state.public_stats_enabled;
 
-    let api = Router::new()
+    let mut api = Router::new()
         .merge(routes::session_routes::create_session_router())
         .merge(routes::turn_routes::create_turn_router())
-        .merge(routes::search_routes::create_search_router());
+        .merge(routes::search_routes::create_search_router())
+        .merge(routes::memory_routes::create_memory_router())
+        .merge(routes::entity_routes::create_entity_router())
+        .merge(routes::entity_routes::create_relationship_router())
+        .merge(routes::pattern_routes::create_pattern_router())
+        .merge(routes::profile_routes::create_profile_router())
+        .merge(routes::auth_routes::create_auth_router())
+        .merge(routes::admin_routes::create_admin_router());
+    if !public_stats {
+        api = api.route("/patterns/stats", get(pattern_handler::get_pattern_stats));
+    }
 
-    Router::new()
+    let protected = Router::new()
         .nest("/api/v1", api)
         .layer(axum::middleware::from_fn(security_headers_middleware))
+        // 限流层位于认证层之内，以便按认证后的租户与主体计数
         .layer(axum::middleware::from_fn(move |req, next| {
-            auth_middleware(req, next, authenticator.clone())
+            rate_limit_middleware(req, next, rate_limiter.clone())
         }))
-        .with_state(app_state)
+        .layer(axum::middleware::from_fn(move |req, next| {
+            auth_middleware(req, next, authenticator.clone())
+        }));
+
+    // 公开统计路由注册在认证层之外
+    let router = if public_stats {
+        Router::new()
+            .route(
+                "/api/v1/patterns/stats",
+                get(pattern_handler::get_public_pattern_stats),
+            )
+            .layer(axum::middleware::from_fn(security_headers_middleware))
+            .layer(axum::middleware::from_fn(move |req, next| {
+                rate_limit_middleware(req, next, public_rate_limiter.clone())
+            }))
+            .merge(protected)
+    } else {
+        protected
+    };
+
+    router.with_state(app_state)
 }
 
 pub async fn initialize_api(app_state: AppState) -> Result<Router, AppError> {
@@ -0,0 +1,13 @@
+//! Admin Routes
+//!
+//! 定义管理员运维相关的 API 路由。
+
+use crate::api::handlers::admin_handler::*;
+use axum::{routing::get, Router};
+
+use crate::api::app_state::AppState;
+
+/// 创建管理员路由器
+pub fn create_admin_router() -> Router<AppState> {
+    Router::new().route("/admin/storage-stats", get(get_storage_stats))
+}
@@ -0,0 +1,18 @@
+//! Auth Routes
+//!
+//! 定义认证相关的 API 路由。
+
+use crate::api::handlers::auth_handler::*;
+use axum::{
+    routing::{delete, post},
+    Router,
+};
+
+use crate::api::app_state::AppState;
+
+/// 创建认证路由器
+pub fn create_auth_router() -> Router<AppState> {
+    Router::new()
+        .route("/auth/api-keys", post(create_api_key))
+        .route("/auth/api-keys/:key_id", delete(delete_api_key))
+}
@@ -29,6 +29,7 @@ pub fn create_entity_router() -> Router<AppState> {
         // Graph routes
         .route("/entities/graph", post(query_graph))
         .route("/entities/graph/stats", get(get_graph_stats))
+        .route("/entities/graph/global", get(get_global_graph))
 }
 
 /// 创建关系路由器
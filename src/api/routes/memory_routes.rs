@@ -18,6 +18,12 @@ pub fn create_memory_router() -> Router<AppState> {
         .route("/memories/:id", get(get_memory))
         .route("/memories/:id", put(update_memory))
         .route("/memories/:id", delete(delete_memory))
+        .route("/memories/:id/similar", get(get_similar_memories))
         .route("/memories/search", post(search_memories))
         .route("/memories/stats", get(get_memory_stats))
+        .route("/users/:user_id/memories/hot", get(get_hot_memories))
+        .route(
+            "/users/:user_id/memories/consolidate",
+            post(consolidate_memories),
+        )
 }
@@ -2,7 +2,11 @@
 //!
 //! 定义 API 路由。
 
+pub mod admin_routes;
+pub mod auth_routes;
+pub mod entity_routes;
 pub mod memory_routes;
+pub mod pattern_routes;
 pub mod profile_routes;
 pub mod search_routes;
 pub mod session_routes;
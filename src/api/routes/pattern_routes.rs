@@ -11,6 +11,8 @@ use crate::api::app_state::AppState;
 use crate::api::handlers::pattern_handler::*;
 
 /// 创建模式路由器
+///
+/// `/patterns/stats` 由 `create_router` 根据是否公开统计单独注册。
 pub fn create_pattern_router() -> Router<AppState> {
     Router::new()
         .route("/patterns", get(list_patterns))
@@ -20,6 +22,12 @@ pub fn create_pattern_router() -> Router<AppState> {
         .route("/patterns/:id", delete(delete_pattern))
         .route("/patterns/search", post(search_patterns))
         .route("/patterns/:id/usage", post(record_usage))
+        .route("/patterns/:id/deprecate", post(deprecate_pattern))
+        .route("/patterns/:id/restore", post(restore_pattern))
+        .route("/patterns/:id/export", get(export_pattern))
         .route("/patterns/match", post(match_patterns))
-        .route("/patterns/stats", get(get_pattern_stats))
+        .route(
+            "/patterns/:id/history/:version_a/diff/:version_b",
+            get(get_pattern_diff),
+        )
 }
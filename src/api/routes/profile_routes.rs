@@ -20,6 +20,7 @@ pub fn create_profile_router() -> Router<AppState> {
         .route("/profiles/me", get(get_my_profile))
         .route("/profiles/:id", put(update_profile))
         .route("/profiles/:id", delete(delete_profile))
+        .route("/profiles/:id/merge", post(merge_profiles))
         // Profile facts
         .route("/profiles/:id/facts", post(add_fact))
         .route("/profiles/:id/facts/:fact_id/verify", post(verify_fact))
@@ -29,4 +30,6 @@ pub fn create_profile_router() -> Router<AppState> {
         .route("/profiles/:id/working-hours", put(update_working_hours))
         // Profile stats
         .route("/profiles/:id/stats", get(get_profile_stats))
+        // Profile memory sync
+        .route("/profiles/:id/sync-status", get(get_profile_sync_status))
 }
@@ -22,4 +22,5 @@ pub fn create_search_router() -> Router<AppState> {
             "/sessions/:session_id/context/recent",
             get(get_recent_context),
         )
+        .route("/search/global", post(global_search))
 }
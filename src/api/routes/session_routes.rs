@@ -4,7 +4,7 @@
 
 use crate::api::handlers::session_handler::*;
 use axum::{
-    routing::{delete, get, post, put},
+    routing::{delete, get, patch, post, put},
     Router,
 };
 
@@ -18,6 +18,19 @@ pub fn create_session_router() -> Router<AppState> {
         .route("/sessions/:id", get(get_session))
         .route("/sessions/:id", put(update_session))
         .route("/sessions/:id", delete(delete_session))
+        .route("/sessions/:id/config", patch(update_session_config))
         .route("/sessions/:id/archive", post(archive_session))
         .route("/sessions/:id/restore", post(restore_session))
+        .route("/sessions/:id/reindex", post(reindex_session))
+        .route("/sessions/:id/index/batch", post(batch_index_turns))
+        .route("/sessions/:id/snapshot", post(create_session_snapshot))
+        .route(
+            "/sessions/:id/snapshot/:snapshot_id/restore",
+            post(restore_session_snapshot),
+        )
+        .route("/sessions/:id/token-estimate", get(get_token_estimate))
+        .route("/sessions/:id/context_window", get(get_context_window))
+        .route("/sessions/:id/summary", get(get_session_summary))
+        .route("/sessions/:id/timeline", get(get_session_timeline))
+        .route("/sessions/:id/compress", post(compress_session))
 }
@@ -15,7 +15,14 @@ pub fn create_turn_router() -> Router<AppState> {
     Router::new()
         .route("/sessions/:session_id/turns", post(create_turn))
         .route("/sessions/:session_id/turns", get(list_turns))
+        .route("/sessions/:session_id/turns", delete(delete_turns))
+        .route("/sessions/:session_id/turns/groups", get(list_turn_groups))
+        .route("/sessions/:session_id/conversation", get(get_conversation))
         .route("/sessions/:session_id/turns/:turn_id", get(get_turn))
         .route("/sessions/:session_id/turns/:turn_id", put(update_turn))
         .route("/sessions/:session_id/turns/:turn_id", delete(delete_turn))
+        .route(
+            "/sessions/:session_id/turns/by-number/:turn_number",
+            get(get_turn_by_number),
+        )
 }
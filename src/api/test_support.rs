@@ -0,0 +1,138 @@
+//! 处理器测试辅助
+//!
+//! 用 wiremock 模拟 SurrealDB 的 `/sql` HTTP 接口：按查询语句片段返回预设的结果行，
+//! 未匹配的查询返回空结果，并可取回收到的全部查询语句用于断言。
+
+use crate::api::app_state::AppState;
+use crate::config::config::DatabaseConfig;
+use crate::index::embedding::SimpleEmbeddingModel;
+use crate::index::{
+    IndexService, UnifiedIndexService, create_full_text_index, create_vector_index,
+};
+use crate::models::entity_repository::EntityRepositoryImpl;
+use crate::models::memory_repository::MemoryRepositoryImpl;
+use crate::models::pattern_repository::PatternRepositoryImpl;
+use crate::models::profile_repository::ProfileRepositoryImpl;
+use crate::security::auth::Claims;
+use crate::services::lazy::LazyService;
+use crate::services::session::SessionServiceImpl;
+use crate::services::turn::TurnServiceImpl;
+use crate::storage::repository::{SessionRepository, SessionStore, TurnRepository, TurnStore};
+use crate::storage::surrealdb::SurrealPool;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use std::sync::Arc;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// 模拟的 SurrealDB
+pub(crate) struct MockDatabase {
+    server: MockServer,
+}
+
+impl MockDatabase {
+    pub(crate) async fn start() -> Self {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/sql"))
+            .respond_with(statement_response(serde_json::json!([])))
+            .with_priority(u8::MAX)
+            .mount(&server)
+            .await;
+        Self { server }
+    }
+
+    /// 查询语句包含 `fragment` 时返回 `rows`；先注册的优先匹配
+    pub(crate) async fn respond(&self, fragment: &str, rows: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(path("/sql"))
+            .and(body_string_contains(fragment))
+            .respond_with(statement_response(rows))
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// 按接收顺序返回收到的查询语句
+    pub(crate) async fn queries(&self) -> Vec<String> {
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|request| String::from_utf8_lossy(&request.body).into_owned())
+            .collect()
+    }
+
+    /// 连接到该模拟数据库的连接池
+    pub(crate) fn pool(&self) -> SurrealPool {
+        SurrealPool::detached(DatabaseConfig {
+            url: self.server.uri(),
+            ..Default::default()
+        })
+    }
+
+    /// 连接到该模拟数据库的开发模式应用状态，索引服务使用内存索引
+    pub(crate) fn app_state(&self) -> AppState {
+        let pool = self.pool();
+        let session_repository: Arc<dyn SessionStore> =
+            Arc::new(SessionRepository::new(pool.clone()));
+        let turn_repository: Arc<dyn TurnStore> = Arc::new(TurnRepository::new(pool.clone()));
+        let index_service: LazyService<dyn IndexService> =
+            LazyService::new("index service", || async {
+                Ok(Box::new(UnifiedIndexService::new(
+                    create_vector_index(None, None),
+                    create_full_text_index(None, false),
+                    Box::new(SimpleEmbeddingModel::new(384)),
+                )) as Box<dyn IndexService>)
+            });
+        let session_service =
+            SessionServiceImpl::new(session_repository.clone(), turn_repository.clone())
+                .with_index_service(index_service.clone());
+        let turn_service =
+            TurnServiceImpl::new(turn_repository.clone(), session_repository.clone())
+                .with_index_service(index_service.clone());
+
+        AppState::development(
+            pool.clone(),
+            session_repository,
+            turn_repository,
+            Arc::new(MemoryRepositoryImpl::new(pool.clone())),
+            PatternRepositoryImpl::new(pool.clone()),
+            EntityRepositoryImpl::new(pool.clone()),
+            ProfileRepositoryImpl::new(pool),
+            Box::new(session_service),
+            Box::new(turn_service),
+        )
+        .with_index_service(index_service)
+    }
+}
+
+fn statement_response(rows: serde_json::Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!([
+        { "status": "OK", "time": "1ms", "result": rows }
+    ]))
+}
+
+/// 指定租户的用户令牌声明
+pub(crate) fn claims(tenant_id: &str, role: &str) -> Claims {
+    Claims::new(
+        "user_1".to_string(),
+        tenant_id.to_string(),
+        role.to_string(),
+        3600,
+        "hippos".to_string(),
+        "hippos-api".to_string(),
+    )
+}
+
+/// 取出响应的状态码与 JSON 响应体
+pub(crate) async fn json_response(response: impl IntoResponse) -> (StatusCode, serde_json::Value) {
+    let response = response.into_response();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json = serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+    (status, json)
+}
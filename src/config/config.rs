@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use crate::error::{AppError, Result};
+use crate::index::EmbeddingModel;
+use crate::index::HnswConfig;
+use crate::index::embedding::PoolingStrategy;
+use std::time::Duration;
+
 /// 数据库类型
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -43,6 +49,31 @@ pub struct DatabaseConfig {
     pub idle_timeout: u64,
     /// ArangoDB 集合前缀
     pub collection_prefix: String,
+    /// 瞬时连接错误的最大重试次数
+    pub max_retries: u32,
+    /// 重试基础延迟（毫秒），第 n 次重试等待 `base_delay_ms * 2^n`
+    pub base_delay_ms: u64,
+    /// 轮次内容压缩配置
+    pub compression: CompressionConfig,
+}
+
+/// 轮次 `raw_content` 压缩配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompressionConfig {
+    /// 是否启用 gzip 压缩
+    pub enabled: bool,
+    /// 内容超过该字节数时才压缩
+    pub min_size_bytes: usize,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_size_bytes: 8192,
+        }
+    }
 }
 
 /// 向量数据库配置
@@ -61,6 +92,17 @@ pub struct VectorConfig {
     pub pq_m: usize,
     /// 距离计算方式
     pub distance_type: String,
+    /// 是否使用 HNSW 图做近似最近邻检索（关闭时线性扫描）
+    pub hnsw_enabled: bool,
+    /// HNSW 图参数，仅在 `hnsw_enabled` 时生效
+    pub hnsw: HnswConfig,
+}
+
+impl VectorConfig {
+    /// 启用 HNSW 时返回其参数，供 `create_vector_index` 使用
+    pub fn hnsw_config(&self) -> Option<HnswConfig> {
+        self.hnsw_enabled.then_some(self.hnsw)
+    }
 }
 
 /// 服务器配置
@@ -77,6 +119,20 @@ pub struct ServerConfig {
     pub request_timeout: u64,
     /// 最大请求体大小（字节）
     pub max_request_size: usize,
+    /// 是否启用 HTTPS
+    pub tls_enabled: bool,
+    /// TLS 证书配置（启用 HTTPS 时必填）
+    pub tls: Option<TlsConfig>,
+}
+
+/// TLS 证书配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM 证书路径
+    pub cert_path: PathBuf,
+    /// PEM 私钥路径
+    pub key_path: PathBuf,
 }
 
 /// 安全配置
@@ -129,12 +185,169 @@ pub struct EmbeddingConfig {
     pub batch_size: usize,
     /// 是否使用 GPU
     pub use_gpu: bool,
-    /// Embedding 后端类型: "ollama" 或 "simple"
+    /// 推理设备: "cpu"、"cuda" 或 "cuda:<序号>"（为空时根据 use_gpu 决定）
+    pub device: String,
+    /// Embedding 后端类型: "ollama"、"candle" 或 "simple"
     pub backend: String,
     /// Ollama 服务器地址
     pub ollama_url: String,
     /// Ollama 请求超时（秒）
     pub ollama_timeout: u64,
+    /// 句向量池化策略: "mean"、"cls" 或 "max"（Ollama 后端由服务端决定，忽略此项）
+    pub pooling_strategy: PoolingStrategy,
+    /// 远程后端暂时不可用时的重试策略
+    pub retry: RetryConfig,
+}
+
+/// 嵌入模型重试配置（指数退避）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// 最大尝试次数（含首次调用，1 表示不重试）
+    pub max_attempts: u8,
+    /// 首次重试前的等待时间（毫秒），之后每次翻倍
+    pub base_delay_ms: u64,
+    /// 单次等待时间上限（毫秒）
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 200,
+            max_delay_ms: 5_000,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// 第 `attempt` 次失败后的等待时间：`base_delay_ms * 2^(attempt - 1)`，不超过 `max_delay_ms`
+    pub fn delay_for(&self, attempt: u8) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(32);
+        Duration::from_millis(
+            self.base_delay_ms
+                .saturating_mul(factor)
+                .min(self.max_delay_ms),
+        )
+    }
+}
+
+/// 维度校验时编码的探测文本
+const DIMENSION_CHECK_TEXT: &str = "dimension_check";
+
+impl EmbeddingConfig {
+    /// 校验模型实际输出维度与配置的向量维度（`vector.dimension`）一致
+    ///
+    /// 维度不一致时相似度计算会静默出错，因此在模型初始化后立即失败。
+    pub async fn validate_dimension(
+        &self,
+        model: &dyn EmbeddingModel,
+        configured: usize,
+    ) -> Result<()> {
+        let actual = model.encode(DIMENSION_CHECK_TEXT).await?.len();
+        if actual != configured {
+            return Err(AppError::Config(format!(
+                "Embedding dimension mismatch: configured {}, model outputs {}",
+                configured, actual
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// 可观测性配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ObservabilityConfig {
+    /// 指标快照目录（为空时不持久化指标）
+    pub metrics_snapshot_path: Option<PathBuf>,
+}
+
+/// WebSocket 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSocketConfig {
+    /// 服务端发送 Ping 的间隔（秒，0 表示关闭心跳）
+    pub ping_interval_secs: u64,
+    /// 超过该时间未收到 Pong 则断开连接（秒）
+    pub pong_timeout_secs: u64,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        Self {
+            ping_interval_secs: 30,
+            pong_timeout_secs: 90,
+        }
+    }
+}
+
+impl WebSocketConfig {
+    /// Ping 发送间隔
+    pub fn ping_interval(&self) -> Duration {
+        Duration::from_secs(self.ping_interval_secs)
+    }
+
+    /// Pong 超时时间
+    pub fn pong_timeout(&self) -> Duration {
+        Duration::from_secs(self.pong_timeout_secs)
+    }
+}
+
+/// 高质量模式的判定阈值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PatternQualityThresholds {
+    /// 最低置信度
+    pub min_confidence: f32,
+    /// 最低成功率
+    pub min_success_rate: f32,
+    /// 最少使用次数
+    pub min_usage_count: u32,
+}
+
+impl Default for PatternQualityThresholds {
+    fn default() -> Self {
+        Self {
+            min_confidence: 0.7,
+            min_success_rate: 0.7,
+            min_usage_count: 0,
+        }
+    }
+}
+
+/// 模式库配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PatternsConfig {
+    /// 高质量模式判定阈值
+    pub quality_thresholds: PatternQualityThresholds,
+    /// 模式 LRU 缓存容量（按模式 ID 缓存）
+    pub cache_capacity: usize,
+}
+
+impl Default for PatternsConfig {
+    fn default() -> Self {
+        Self {
+            quality_thresholds: PatternQualityThresholds::default(),
+            cache_capacity: 500,
+        }
+    }
+}
+
+/// API 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiConfig {
+    /// 列表接口允许的最大每页数量
+    pub max_page_size: usize,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self { max_page_size: 200 }
+    }
 }
 
 /// 应用配置
@@ -153,6 +366,14 @@ pub struct AppConfig {
     pub logging: LoggingConfig,
     /// 嵌入模型配置
     pub embedding: EmbeddingConfig,
+    /// 可观测性配置
+    pub observability: ObservabilityConfig,
+    /// WebSocket 配置
+    pub websocket: WebSocketConfig,
+    /// 模式库配置
+    pub patterns: PatternsConfig,
+    /// API 配置
+    pub api: ApiConfig,
     /// 应用名称
     pub app_name: String,
     /// 环境
@@ -175,6 +396,9 @@ impl AppConfig {
                 connection_timeout: 30,
                 idle_timeout: 300,
                 collection_prefix: "hippos_".into(),
+                max_retries: 3,
+                base_delay_ms: 100,
+                compression: CompressionConfig::default(),
             },
             vector: VectorConfig {
                 data_dir: PathBuf::from("./data/vector"),
@@ -183,6 +407,8 @@ impl AppConfig {
                 nprobe: 32,
                 pq_m: 8,
                 distance_type: "cosine".into(),
+                hnsw_enabled: false,
+                hnsw: HnswConfig::default(),
             },
             server: ServerConfig {
                 host: "0.0.0.0".into(),
@@ -190,6 +416,8 @@ impl AppConfig {
                 workers: 4,
                 request_timeout: 30,
                 max_request_size: 10 * 1024 * 1024,
+                tls_enabled: false,
+                tls: None,
             },
             security: SecurityConfig {
                 api_key: "dev-api-key-change-in-production".into(),
@@ -213,10 +441,19 @@ impl AppConfig {
                 model_path: None,
                 batch_size: 32,
                 use_gpu: false,
+                device: "cpu".into(),
                 backend: "simple".into(),
                 ollama_url: "http://localhost:11434".into(),
                 ollama_timeout: 60,
+                pooling_strategy: PoolingStrategy::Mean,
+                retry: RetryConfig::default(),
             },
+            observability: ObservabilityConfig {
+                metrics_snapshot_path: Some(PathBuf::from("./data")),
+            },
+            websocket: WebSocketConfig::default(),
+            patterns: PatternsConfig::default(),
+            api: ApiConfig::default(),
             app_name: "hippos".into(),
             environment: "development".into(),
         }
@@ -295,6 +532,13 @@ mod tests {
         assert_eq!(config.dimension, 384);
         assert_eq!(config.nlist, 1024);
         assert_eq!(config.distance_type, "cosine");
+        assert!(config.hnsw_config().is_none());
+
+        let enabled = VectorConfig {
+            hnsw_enabled: true,
+            ..config
+        };
+        assert_eq!(enabled.hnsw_config().map(|hnsw| hnsw.m), Some(16));
     }
 
     #[test]
@@ -303,6 +547,8 @@ mod tests {
         assert_eq!(config.host, "0.0.0.0");
         assert_eq!(config.port, 8080);
         assert_eq!(config.workers, 4);
+        assert!(!config.tls_enabled);
+        assert!(config.tls.is_none());
     }
 
     #[test]
@@ -319,4 +565,62 @@ mod tests {
         assert_eq!(config.backend, "simple");
         assert!(!config.use_gpu);
     }
+
+    #[tokio::test]
+    async fn test_embedding_validate_dimension() {
+        use crate::index::embedding::SimpleEmbeddingModel;
+
+        let config = AppConfig::development().embedding;
+        let model = SimpleEmbeddingModel::new(384);
+        assert!(config.validate_dimension(&model, 384).await.is_ok());
+
+        let err = config.validate_dimension(&model, 768).await.unwrap_err();
+        assert!(matches!(err, AppError::Config(_)));
+        assert!(
+            err.to_string()
+                .contains("configured 768, model outputs 384")
+        );
+    }
+
+    #[test]
+    fn test_websocket_config_defaults() {
+        let config = AppConfig::development().websocket;
+        assert_eq!(config.ping_interval(), Duration::from_secs(30));
+        assert_eq!(config.pong_timeout(), Duration::from_secs(90));
+
+        // 配置文件中只设置部分字段时其余字段使用默认值
+        let config: WebSocketConfig = serde_json::from_str(r#"{"ping_interval_secs": 5}"#).unwrap();
+        assert_eq!(config.ping_interval(), Duration::from_secs(5));
+        assert_eq!(config.pong_timeout(), Duration::from_secs(90));
+    }
+
+    #[test]
+    fn test_patterns_config_partial() {
+        let config: PatternsConfig =
+            serde_json::from_str(r#"{"quality_thresholds": {"min_usage_count": 3}}"#).unwrap();
+        assert_eq!(config.quality_thresholds.min_usage_count, 3);
+        assert_eq!(config.quality_thresholds.min_confidence, 0.7);
+        assert_eq!(config.quality_thresholds.min_success_rate, 0.7);
+        assert_eq!(config.cache_capacity, 500);
+    }
+
+    #[test]
+    fn test_api_config_defaults() {
+        assert_eq!(AppConfig::development().api.max_page_size, 200);
+
+        let config: AppConfig = serde_json::from_str(r#"{"api": {"max_page_size": 50}}"#).unwrap();
+        assert_eq!(config.api.max_page_size, 50);
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_exponentially() {
+        let config = RetryConfig {
+            max_attempts: 5,
+            base_delay_ms: 100,
+            max_delay_ms: 350,
+        };
+        assert_eq!(config.delay_for(1).as_millis(), 100);
+        assert_eq!(config.delay_for(2).as_millis(), 200);
+        assert_eq!(config.delay_for(3).as_millis(), 350);
+    }
 }
@@ -1,52 +1,86 @@
-use crate::config::config::{AppConfig, DatabaseConfig, VectorConfig};
+use crate::config::config::{AppConfig, DatabaseConfig, TlsConfig, VectorConfig};
 use figment::{
     Figment,
-    providers::{Env, Format, Toml},
+    providers::{Format, Serialized, Toml},
+    value::Value,
 };
 use std::path::PathBuf;
 
+/// 环境变量覆盖的前缀
+pub const ENV_PREFIX: &str = "HIPPOS_";
+
+/// 旧版环境变量前缀，已弃用，仍作为 `ENV_PREFIX` 的低优先级后备
+pub const LEGACY_ENV_PREFIX: &str = "EXOCORTEX_";
+
 /// 配置加载器
 pub struct ConfigLoader;
 
 impl ConfigLoader {
     /// 从默认路径加载配置
     ///
-    /// 搜索路径：
-    /// 1. ./config.yaml
-    /// 2. 环境变量
+    /// 优先级（高到低）：
+    /// 1. `HIPPOS_<SECTION>_<FIELD>` 环境变量
+    /// 2. 已弃用的 `EXOCORTEX_<SECTION>_<FIELD>` 环境变量
+    /// 3. ./config.yaml
+    /// 4. 内置默认值
     pub fn load() -> Result<AppConfig, figment::Error> {
-        let figment = Figment::new()
-            .merge(Toml::file("config.yaml"))
-            .merge(Env::prefixed("EXOCORTEX_").split("_").global());
-
-        figment.extract()
+        Self::figment(default_config_path(), std::env::vars()).extract()
     }
 
     /// 从指定路径加载配置
     pub fn load_from(path: PathBuf) -> Result<AppConfig, figment::Error> {
-        let figment = Figment::new()
-            .merge(Toml::file(path))
-            .merge(Env::prefixed("EXOCORTEX_").split("_").global());
-
-        figment.extract()
+        Self::figment(path, std::env::vars()).extract()
     }
 
-    /// 加载数据库配置
+    /// 加载数据库配置（`HIPPOS_DATABASE_*` 覆盖）
     pub fn load_database_config() -> Result<DatabaseConfig, figment::Error> {
-        let figment = Figment::new()
-            .merge(Toml::file("config.yaml"))
-            .merge(Env::prefixed("EXOCORTEX_DB_").split("_").global());
-
-        figment.extract()
+        Self::load().map(|config| config.database)
     }
 
-    /// 加载向量数据库配置
+    /// 加载向量数据库配置（`HIPPOS_VECTOR_*` 覆盖）
     pub fn load_vector_config() -> Result<VectorConfig, figment::Error> {
-        let figment = Figment::new()
-            .merge(Toml::file("config.yaml"))
-            .merge(Env::prefixed("EXOCORTEX_VECTOR_").split("_").global());
+        Self::load().map(|config| config.vector)
+    }
+
+    /// 配置文件叠加环境变量覆盖
+    ///
+    /// 不以 `ENV_PREFIX` / `LEGACY_ENV_PREFIX` 开头或无法对应到配置字段的变量会被忽略；
+    /// 同一字段同时设置两种前缀时以 `ENV_PREFIX` 为准。
+    fn figment(path: PathBuf, vars: impl IntoIterator<Item = (String, String)>) -> Figment {
+        let keys = config_keys();
+        let mut overrides: Vec<(bool, String, String)> = vars
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let lower = name.to_lowercase();
+                let (key, legacy) = match lower.strip_prefix(&ENV_PREFIX.to_lowercase()) {
+                    Some(key) => (key, false),
+                    None => (lower.strip_prefix(&LEGACY_ENV_PREFIX.to_lowercase())?, true),
+                };
+                let path = env_key_path(&keys, key)?;
+                if legacy {
+                    tracing::warn!(
+                        "{} is deprecated, use {}{} instead",
+                        name,
+                        ENV_PREFIX,
+                        key.to_uppercase()
+                    );
+                }
+                Some((!legacy, path, value))
+            })
+            .collect();
+        // 旧前缀先合并，使新前缀的同名覆盖生效
+        overrides.sort_by_key(|(current, _, _)| *current);
 
-        figment.extract()
+        overrides
+            .into_iter()
+            .map(|(_, path, value)| (path, value))
+            .fold(
+                Figment::new().merge(Toml::file(path)),
+                |figment, (path, value)| {
+                    let value: Value = value.parse().expect("infallible");
+                    figment.merge(Serialized::global(&path, value))
+                },
+            )
     }
 
     /// 验证配置
@@ -67,6 +101,41 @@ impl ConfigLoader {
     }
 }
 
+/// 默认配置的字段树，用于把环境变量名解析为配置路径
+///
+/// 可选的配置节默认为 `None`、序列化为 `null`，因此先填入默认值，
+/// 使其字段（如 `server.tls.cert_path`）也能被环境变量覆盖。
+fn config_keys() -> serde_json::Value {
+    let mut config = AppConfig::default();
+    config.server.tls.get_or_insert_with(TlsConfig::default);
+    serde_json::to_value(config).unwrap_or_default()
+}
+
+/// 将 `<section>_<field>`（小写，去掉前缀）解析为点分隔的配置路径
+///
+/// 字段名本身可能包含下划线，因此每一层优先匹配最长的字段名，
+/// 例如 `embedding_retry_max_attempts` 解析为 `embedding.retry.max_attempts`。
+/// 只有解析到叶子字段时才返回路径。
+fn env_key_path(keys: &serde_json::Value, key: &str) -> Option<String> {
+    let segments: Vec<&str> = key.split('_').collect();
+    let mut node = keys;
+    let mut path = Vec::new();
+    let mut start = 0;
+
+    while start < segments.len() {
+        let fields = node.as_object()?;
+        let (end, field) = (start + 1..=segments.len())
+            .rev()
+            .map(|end| (end, segments[start..end].join("_")))
+            .find(|(_, field)| fields.contains_key(field))?;
+        node = &fields[&field];
+        path.push(field);
+        start = end;
+    }
+
+    (!node.is_object()).then(|| path.join("."))
+}
+
 /// 配置验证错误
 #[derive(thiserror::Error, Debug)]
 pub enum ConfigValidationError {
@@ -92,3 +161,108 @@ pub fn default_config_path() -> PathBuf {
 pub fn config_exists() -> bool {
     default_config_path().exists()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_env_key_path() {
+        let keys = config_keys();
+        assert_eq!(
+            env_key_path(&keys, "database_url").as_deref(),
+            Some("database.url")
+        );
+        assert_eq!(
+            env_key_path(&keys, "embedding_model_name").as_deref(),
+            Some("embedding.model_name")
+        );
+        assert_eq!(
+            env_key_path(&keys, "embedding_retry_max_attempts").as_deref(),
+            Some("embedding.retry.max_attempts")
+        );
+        assert_eq!(env_key_path(&keys, "app_name").as_deref(), Some("app_name"));
+        assert_eq!(
+            env_key_path(&keys, "server_tls_cert_path").as_deref(),
+            Some("server.tls.cert_path")
+        );
+        assert_eq!(env_key_path(&keys, "server_tls"), None);
+        // 未知字段与整个配置节都不是可覆盖的叶子字段
+        assert_eq!(env_key_path(&keys, "metrics_buckets"), None);
+        assert_eq!(env_key_path(&keys, "database"), None);
+    }
+
+    #[test]
+    fn test_env_overrides_file_overrides_defaults() {
+        let path =
+            std::env::temp_dir().join(format!("hippos_config_{}.yaml", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "[database]\nurl = \"ws://file:8000\"\nnamespace = \"file_ns\"\n\n[server]\nport = 8080\n",
+        )
+        .unwrap();
+
+        let config: AppConfig = ConfigLoader::figment(
+            path.clone(),
+            vars(&[
+                ("HIPPOS_DATABASE_URL", "ws://env:8000"),
+                ("HIPPOS_SERVER_PORT", "9090"),
+                ("HIPPOS_EMBEDDING_MODEL_NAME", "env-model"),
+                ("HIPPOS_METRICS_BUCKETS", "5,10"),
+                ("OTHER_DATABASE_URL", "ws://ignored:8000"),
+            ]),
+        )
+        .extract()
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // 环境变量优先于配置文件
+        assert_eq!(config.database.url, "ws://env:8000");
+        assert_eq!(config.server.port, 9090);
+        assert_eq!(config.embedding.model_name, "env-model");
+        // 未设置环境变量时使用配置文件
+        assert_eq!(config.database.namespace, "file_ns");
+        // 两者都未设置时使用内置默认值
+        let defaults = AppConfig::default();
+        assert_eq!(config.database.database, defaults.database.database);
+        assert_eq!(
+            config.embedding.retry.max_attempts,
+            defaults.embedding.retry.max_attempts
+        );
+    }
+
+    #[test]
+    fn test_env_sets_tls_paths_and_accepts_legacy_prefix() {
+        let path =
+            std::env::temp_dir().join(format!("hippos_config_{}.yaml", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "[server]\nport = 8080\n").unwrap();
+
+        let config: AppConfig = ConfigLoader::figment(
+            path.clone(),
+            vars(&[
+                ("HIPPOS_SERVER_TLS_CERT_PATH", "/certs/tls.crt"),
+                ("HIPPOS_SERVER_TLS_KEY_PATH", "/certs/tls.key"),
+                ("HIPPOS_SERVER_PORT", "9443"),
+                ("EXOCORTEX_SERVER_PORT", "9090"),
+                ("EXOCORTEX_DATABASE_URL", "ws://legacy:8000"),
+            ]),
+        )
+        .extract()
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let tls = config.server.tls.unwrap();
+        assert_eq!(tls.cert_path, PathBuf::from("/certs/tls.crt"));
+        assert_eq!(tls.key_path, PathBuf::from("/certs/tls.key"));
+        // 新前缀优先于旧前缀，旧前缀仍可覆盖配置文件与默认值
+        assert_eq!(config.server.port, 9443);
+        assert_eq!(config.database.url, "ws://legacy:8000");
+    }
+}
@@ -29,6 +29,14 @@ pub enum AppError {
     #[error("未授权访问: {0}")]
     Authorization(String),
 
+    /// 未认证（缺少或无效的凭证）
+    #[error("未认证: {0}")]
+    Unauthorized(String),
+
+    /// 已认证但无权执行该操作
+    #[error("禁止访问: {0}")]
+    Forbidden(String),
+
     /// 资源不存在
     #[error("资源不存在: {0}")]
     NotFound(String),
@@ -37,10 +45,22 @@ pub enum AppError {
     #[error("参数验证失败: {0}")]
     Validation(String),
 
+    /// 单个字段校验失败，响应体为 `{"field": ..., "error": ..., "detail": ...}`
+    #[error("字段 {field} 校验失败: {detail}")]
+    InvalidField {
+        field: String,
+        error: String,
+        detail: String,
+    },
+
     /// 资源冲突
     #[error("资源冲突: {0}")]
     Conflict(String),
 
+    /// 请求格式正确但无法处理（如批量请求超出上限）
+    #[error("无法处理的请求: {0}")]
+    Unprocessable(String),
+
     /// 速率限制
     #[error("请求过于频繁，请稍后再试")]
     RateLimited,
@@ -65,6 +85,10 @@ pub enum AppError {
     #[error("嵌入模型错误: {0}")]
     Embedding(String),
 
+    /// 外部服务暂时不可用（如远程后端返回 429 / 503），可重试
+    #[error("外部服务暂时不可用: {0}")]
+    ExternalService(String),
+
     /// 内部错误
     #[error("内部错误: {0}")]
     Internal(String),
@@ -110,6 +134,23 @@ impl From<std::string::String> for AppError {
     }
 }
 
+impl From<crate::security::validation::ValidationError> for AppError {
+    fn from(e: crate::security::validation::ValidationError) -> Self {
+        use crate::security::validation::ValidationError;
+
+        let field = e.field().to_string();
+        let detail = match &e {
+            ValidationError::Custom { message, .. } => message.clone(),
+            other => other.to_string(),
+        };
+        AppError::InvalidField {
+            error: format!("invalid_{}", field),
+            field,
+            detail,
+        }
+    }
+}
+
 impl From<regex::Error> for AppError {
     fn from(e: regex::Error) -> Self {
         AppError::Validation(format!("Regex error: {}", e))
@@ -119,6 +160,27 @@ impl From<regex::Error> for AppError {
 /// Axum response implementation for AppError
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        match &self {
+            AppError::Unauthorized(detail) => {
+                return auth_error_response(StatusCode::UNAUTHORIZED, "unauthorized", detail);
+            }
+            AppError::Forbidden(detail) => {
+                return auth_error_response(StatusCode::FORBIDDEN, "forbidden", detail);
+            }
+            AppError::InvalidField {
+                field,
+                error,
+                detail,
+            } => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({ "field": field, "error": error, "detail": detail })),
+                )
+                    .into_response();
+            }
+            _ => {}
+        }
+
         let (status, code) = (&self).into();
         let body = Json(ErrorResponse::new(&code, &self.to_string()));
         (
@@ -129,6 +191,15 @@ impl IntoResponse for AppError {
     }
 }
 
+/// 认证 / 授权失败响应：`{"error": "...", "detail": "..."}`
+fn auth_error_response(status: StatusCode, error: &str, detail: &str) -> Response {
+    (
+        status,
+        Json(serde_json::json!({ "error": error, "detail": detail })),
+    )
+        .into_response()
+}
+
 /// 错误响应
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorResponse {
@@ -173,8 +244,12 @@ impl From<&AppError> for (u16, String) {
             AppError::NotFound(_) => (404, "NOT_FOUND".to_string()),
             AppError::Authentication(_) => (401, "UNAUTHORIZED".to_string()),
             AppError::Authorization(_) => (403, "FORBIDDEN".to_string()),
+            AppError::Unauthorized(_) => (401, "UNAUTHORIZED".to_string()),
+            AppError::Forbidden(_) => (403, "FORBIDDEN".to_string()),
             AppError::Validation(_) => (400, "BAD_REQUEST".to_string()),
+            AppError::InvalidField { .. } => (400, "BAD_REQUEST".to_string()),
             AppError::Conflict(_) => (409, "CONFLICT".to_string()),
+            AppError::Unprocessable(_) => (422, "UNPROCESSABLE_ENTITY".to_string()),
             AppError::RateLimited => (429, "RATE_LIMITED".to_string()),
             AppError::Timeout(_) => (408, "TIMEOUT".to_string()),
             AppError::Connection(_) => (503, "SERVICE_UNAVAILABLE".to_string()),
@@ -183,6 +258,7 @@ impl From<&AppError> for (u16, String) {
             AppError::Serialization(_) => (400, "SERIALIZATION_ERROR".to_string()),
             AppError::VectorIndex(_) => (500, "INDEX_ERROR".to_string()),
             AppError::Embedding(_) => (500, "EMBEDDING_ERROR".to_string()),
+            AppError::ExternalService(_) => (503, "SERVICE_UNAVAILABLE".to_string()),
             AppError::Internal(_) => (500, "INTERNAL_ERROR".to_string()),
             AppError::Io(_) => (500, "IO_ERROR".to_string()),
         }
@@ -191,3 +267,57 @@ impl From<&AppError> for (u16, String) {
 
 /// 结果类型别名
 pub type Result<T> = std::result::Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn into_parts(error: AppError) -> (StatusCode, serde_json::Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized_and_forbidden_responses() {
+        let (status, body) =
+            into_parts(AppError::Unauthorized("Missing credentials".to_string())).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "unauthorized");
+        assert_eq!(body["detail"], "Missing credentials");
+
+        let (status, body) = into_parts(AppError::Forbidden(
+            "Missing permission system:manage".to_string(),
+        ))
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"], "forbidden");
+        assert_eq!(body["detail"], "Missing permission system:manage");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_field_response() {
+        let error = AppError::from(
+            crate::security::validation::validators::validate_search_query("1").unwrap_err(),
+        );
+        let (status, body) = into_parts(error).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["field"], "query");
+        assert_eq!(body["error"], "invalid_query");
+        assert_eq!(
+            body["detail"],
+            "Query must be 2-1000 characters and not purely numeric"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unprocessable_response() {
+        let (status, body) =
+            into_parts(AppError::Unprocessable("Too many turn IDs".to_string())).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["code"], "UNPROCESSABLE_ENTITY");
+    }
+}
@@ -2,21 +2,84 @@
 
 use async_trait::async_trait;
 use reqwest;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::config::config::{EmbeddingConfig, RetryConfig};
+use crate::error::{AppError, Result};
+
+#[cfg(feature = "candle")]
+pub mod candle_embedding;
+
+#[cfg(feature = "candle")]
+pub use candle_embedding::CandleEmbeddingModel;
+
+/// 将 token 级向量聚合为句向量的池化策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PoolingStrategy {
+    /// 按 attention mask 取平均（句向量默认做法）
+    #[default]
+    Mean,
+    /// 取首个 token（CLS）的向量
+    Cls,
+    /// 逐维取最大值
+    Max,
+}
 
-use crate::config::config::EmbeddingConfig;
-use crate::error::Result;
+/// 支持批量推理的后端每次请求编码的文本数
+pub const ENCODE_BATCH_SIZE: usize = 32;
 
 #[async_trait]
 pub trait EmbeddingModel: Send + Sync {
     async fn encode(&self, text: &str) -> Result<Vec<f32>>;
-    async fn encode_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>>;
+
+    /// 批量编码，返回的向量与 `texts` 一一对应
+    ///
+    /// 默认实现逐条调用 `encode`；支持批量推理的后端应覆盖此方法以减少推理调用次数。
+    async fn encode_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for text in texts {
+            embeddings.push(self.encode(text).await?);
+        }
+        Ok(embeddings)
+    }
+
     fn dimension(&self) -> usize;
+
+    /// 使用指定池化策略编码
+    ///
+    /// 默认实现忽略策略直接调用 `encode`（即模型自身的池化方式，通常为 `Mean`）。
+    async fn encode_with_pooling(&self, text: &str, strategy: PoolingStrategy) -> Result<Vec<f32>> {
+        let _ = strategy;
+        self.encode(text).await
+    }
+}
+
+/// 共享的模型实例，使多个服务复用同一个已加载的模型
+#[async_trait]
+impl<T: EmbeddingModel + ?Sized> EmbeddingModel for Arc<T> {
+    async fn encode(&self, text: &str) -> Result<Vec<f32>> {
+        (**self).encode(text).await
+    }
+
+    async fn encode_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        (**self).encode_batch(texts).await
+    }
+
+    fn dimension(&self) -> usize {
+        (**self).dimension()
+    }
+
+    async fn encode_with_pooling(&self, text: &str, strategy: PoolingStrategy) -> Result<Vec<f32>> {
+        (**self).encode_with_pooling(text, strategy).await
+    }
 }
 
 pub struct SimpleEmbeddingModel {
     embeddings: std::collections::HashMap<String, Vec<f32>>,
     dimension: usize,
+    pooling: PoolingStrategy,
 }
 
 impl SimpleEmbeddingModel {
@@ -24,52 +87,76 @@ impl SimpleEmbeddingModel {
         Self {
             embeddings: std::collections::HashMap::new(),
             dimension,
+            pooling: PoolingStrategy::default(),
         }
     }
 
+    /// 设置 `encode` 使用的池化策略
+    pub fn with_pooling(mut self, pooling: PoolingStrategy) -> Self {
+        self.pooling = pooling;
+        self
+    }
+
     pub fn add_word_embedding(&mut self, word: &str, embedding: &[f32]) {
         if embedding.len() == self.dimension {
             self.embeddings.insert(word.to_string(), embedding.to_vec());
         }
     }
-}
 
-#[async_trait]
-impl EmbeddingModel for SimpleEmbeddingModel {
-    async fn encode(&self, text: &str) -> Result<Vec<f32>> {
-        let words: Vec<&str> = text.split_whitespace().collect();
+    /// 查表并按 `strategy` 池化单条文本
+    fn pool(&self, text: &str, strategy: PoolingStrategy) -> Vec<f32> {
+        // 以单词作为 token，未登录词不参与池化
+        let vectors: Vec<&Vec<f32>> = text
+            .split_whitespace()
+            .filter_map(|word| self.embeddings.get(word))
+            .collect();
 
-        if words.is_empty() {
-            return Ok(vec![0.0; self.dimension]);
+        if vectors.is_empty() {
+            return vec![0.0; self.dimension];
         }
 
-        let mut sum = vec![0.0; self.dimension];
-        let mut count = 0;
-
-        for word in words {
-            if let Some(embedding) = self.embeddings.get(word) {
-                for (i, val) in embedding.iter().enumerate() {
-                    sum[i] += val;
+        match strategy {
+            PoolingStrategy::Mean => {
+                let mut sum = vec![0.0; self.dimension];
+                for embedding in &vectors {
+                    for (i, val) in embedding.iter().enumerate() {
+                        sum[i] += val;
+                    }
+                }
+                for val in &mut sum {
+                    *val /= vectors.len() as f32;
                 }
-                count += 1;
+                sum
             }
-        }
-
-        if count > 0 {
-            for val in &mut sum {
-                *val /= count as f32;
+            PoolingStrategy::Cls => vectors[0].clone(),
+            PoolingStrategy::Max => {
+                let mut max = vec![f32::MIN; self.dimension];
+                for embedding in &vectors {
+                    for (i, val) in embedding.iter().enumerate() {
+                        max[i] = max[i].max(*val);
+                    }
+                }
+                max
             }
         }
+    }
+}
+
+#[async_trait]
+impl EmbeddingModel for SimpleEmbeddingModel {
+    async fn encode(&self, text: &str) -> Result<Vec<f32>> {
+        self.encode_with_pooling(text, self.pooling).await
+    }
 
-        Ok(sum)
+    async fn encode_with_pooling(&self, text: &str, strategy: PoolingStrategy) -> Result<Vec<f32>> {
+        Ok(self.pool(text, strategy))
     }
 
     async fn encode_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        // 按批同步查表，避免逐条经过异步调用
         let mut embeddings = Vec::with_capacity(texts.len());
-
-        for text in texts {
-            let embedding = self.encode(text).await?;
-            embeddings.push(embedding);
+        for chunk in texts.chunks(ENCODE_BATCH_SIZE) {
+            embeddings.extend(chunk.iter().map(|text| self.pool(text, self.pooling)));
         }
 
         Ok(embeddings)
@@ -117,11 +204,22 @@ impl OllamaEmbeddingModel {
                 "truncate": true
             }))
             .send()
-            .await?;
+            .await
+            .map_err(|e| {
+                AppError::ExternalService(format!("Ollama embedding request failed: {}", e))
+            })?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            return Err(crate::error::AppError::Embedding(format!(
+            // 429 与 5xx 视为暂时性故障，交由 RetryableEmbeddingModel 重试
+            if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+                return Err(AppError::ExternalService(format!(
+                    "Ollama embedding failed ({}): {}",
+                    status, error_text
+                )));
+            }
+            return Err(AppError::Embedding(format!(
                 "Ollama embedding failed: {}",
                 error_text
             )));
@@ -144,10 +242,9 @@ impl EmbeddingModel for OllamaEmbeddingModel {
 
     async fn encode_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
         // Ollama 支持批量输入，但为了稳定性，分批处理
-        let batch_size = 32;
         let mut all_embeddings = Vec::with_capacity(texts.len());
 
-        for chunk in texts.chunks(batch_size) {
+        for chunk in texts.chunks(ENCODE_BATCH_SIZE) {
             let chunk_vec: Vec<&str> = chunk.to_vec();
             let embeddings = self.embed(chunk_vec).await?;
             all_embeddings.extend(embeddings);
@@ -161,6 +258,68 @@ impl EmbeddingModel for OllamaEmbeddingModel {
     }
 }
 
+/// 为嵌入模型增加指数退避重试的装饰器
+///
+/// 仅重试 `AppError::ExternalService`（远程后端暂时不可用），其余错误立即返回。
+pub struct RetryableEmbeddingModel {
+    inner: Box<dyn EmbeddingModel>,
+    config: RetryConfig,
+}
+
+impl RetryableEmbeddingModel {
+    pub fn new(inner: Box<dyn EmbeddingModel>, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    /// 执行 `call`，遇到 `ExternalService` 错误时按配置退避重试
+    async fn with_retry<T, F, Fut>(&self, call: F) -> Result<T>
+    where
+        F: Fn() -> Fut + Send + Sync,
+        Fut: std::future::Future<Output = Result<T>> + Send,
+        T: Send,
+    {
+        let max_attempts = self.config.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match call().await {
+                Err(AppError::ExternalService(e)) if attempt < max_attempts => {
+                    let delay = self.config.delay_for(attempt);
+                    tracing::warn!(
+                        "Embedding attempt {}/{} failed, retrying in {:?}: {}",
+                        attempt,
+                        max_attempts,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingModel for RetryableEmbeddingModel {
+    async fn encode(&self, text: &str) -> Result<Vec<f32>> {
+        self.with_retry(|| self.inner.encode(text)).await
+    }
+
+    async fn encode_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        self.with_retry(|| self.inner.encode_batch(texts)).await
+    }
+
+    fn dimension(&self) -> usize {
+        self.inner.dimension()
+    }
+
+    async fn encode_with_pooling(&self, text: &str, strategy: PoolingStrategy) -> Result<Vec<f32>> {
+        self.with_retry(|| self.inner.encode_with_pooling(text, strategy))
+            .await
+    }
+}
+
 pub async fn create_embedding_model(
     config: &EmbeddingConfig,
     dimension: usize,
@@ -169,10 +328,30 @@ pub async fn create_embedding_model(
         "ollama" => {
             let model =
                 OllamaEmbeddingModel::new(&config.ollama_url, &config.model_name, dimension)?;
+            Ok(Box::new(RetryableEmbeddingModel::new(
+                Box::new(model),
+                config.retry.clone(),
+            )))
+        }
+        #[cfg(feature = "candle")]
+        "candle" => {
+            let model = CandleEmbeddingModel::from_config(config)?;
+            if model.dimension() != dimension {
+                return Err(crate::error::AppError::Config(format!(
+                    "Embedding model dimension {} does not match vector.dimension {}",
+                    model.dimension(),
+                    dimension
+                )));
+            }
             Ok(Box::new(model))
         }
+        #[cfg(not(feature = "candle"))]
+        "candle" => Err(crate::error::AppError::Config(
+            "embedding.backend = \"candle\" requires building with the `candle` feature"
+                .to_string(),
+        )),
         "simple" | _ => {
-            let model = SimpleEmbeddingModel::new(dimension);
+            let model = SimpleEmbeddingModel::new(dimension).with_pooling(config.pooling_strategy);
             Ok(Box::new(model))
         }
     }
@@ -192,6 +371,20 @@ mod tests {
         assert_eq!(model.dimension(), 384);
     }
 
+    #[tokio::test]
+    async fn test_shared_model_delegates_to_inner() {
+        let mut inner = SimpleEmbeddingModel::new(2);
+        inner.add_word_embedding("hello", &[1.0, 0.0]);
+        let shared: Arc<dyn EmbeddingModel> = Arc::new(inner);
+        let model: Box<dyn EmbeddingModel> = Box::new(shared.clone());
+
+        assert_eq!(model.dimension(), 2);
+        assert_eq!(
+            model.encode("hello").await.unwrap(),
+            shared.encode("hello").await.unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_batch_encoding() {
         let model = SimpleEmbeddingModel::new(384);
@@ -205,4 +398,136 @@ mod tests {
         assert_eq!(results[1].len(), 384);
         assert_eq!(results[2].len(), 384);
     }
+
+    #[tokio::test]
+    async fn test_batch_encoding_matches_encode() {
+        let mut model = SimpleEmbeddingModel::new(2).with_pooling(PoolingStrategy::Max);
+        model.add_word_embedding("hello", &[1.0, 0.0]);
+        model.add_word_embedding("world", &[0.0, 3.0]);
+
+        let texts: Vec<&str> = ["hello", "hello world", "unknown"]
+            .into_iter()
+            .cycle()
+            .take(ENCODE_BATCH_SIZE + 5)
+            .collect();
+        let results = model.encode_batch(&texts).await.unwrap();
+
+        assert_eq!(results.len(), texts.len());
+        for (text, result) in texts.iter().zip(&results) {
+            assert_eq!(*result, model.encode(text).await.unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encode_with_pooling_strategies() {
+        let mut model = SimpleEmbeddingModel::new(2);
+        model.add_word_embedding("hello", &[1.0, 0.0]);
+        model.add_word_embedding("world", &[0.0, 3.0]);
+
+        let sentence = "hello world";
+        let mean = model
+            .encode_with_pooling(sentence, PoolingStrategy::Mean)
+            .await
+            .unwrap();
+        let cls = model
+            .encode_with_pooling(sentence, PoolingStrategy::Cls)
+            .await
+            .unwrap();
+        let max = model
+            .encode_with_pooling(sentence, PoolingStrategy::Max)
+            .await
+            .unwrap();
+
+        assert_eq!(mean, vec![0.5, 1.5]);
+        assert_eq!(cls, vec![1.0, 0.0]);
+        assert_eq!(max, vec![1.0, 3.0]);
+        assert_ne!(cls, mean);
+
+        // encode 使用模型配置的池化策略
+        let model = model.with_pooling(PoolingStrategy::Cls);
+        assert_eq!(model.encode(sentence).await.unwrap(), cls);
+    }
+
+    /// 前 `failures` 次调用返回给定错误的模型
+    struct FlakyModel {
+        failures: usize,
+        error: fn() -> AppError,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EmbeddingModel for FlakyModel {
+        async fn encode(&self, _text: &str) -> Result<Vec<f32>> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if call < self.failures {
+                return Err((self.error)());
+            }
+            Ok(vec![1.0, 0.0])
+        }
+
+        fn dimension(&self) -> usize {
+            2
+        }
+    }
+
+    fn retryable(failures: usize, error: fn() -> AppError) -> RetryableEmbeddingModel {
+        RetryableEmbeddingModel::new(
+            Box::new(FlakyModel {
+                failures,
+                error,
+                calls: std::sync::atomic::AtomicUsize::new(0),
+            }),
+            RetryConfig {
+                max_attempts: 3,
+                base_delay_ms: 1,
+                max_delay_ms: 2,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_retryable_model_retries_external_service_errors() {
+        let unavailable = || AppError::ExternalService("503".to_string());
+
+        let model = retryable(2, unavailable);
+        assert_eq!(model.encode("hello").await.unwrap(), vec![1.0, 0.0]);
+
+        let model = retryable(3, unavailable);
+        assert!(matches!(
+            model.encode("hello").await,
+            Err(AppError::ExternalService(_))
+        ));
+
+        // 其他错误不重试
+        let model = retryable(1, || AppError::Embedding("bad input".to_string()));
+        assert!(matches!(
+            model.encode("hello").await,
+            Err(AppError::Embedding(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_default_encode_batch_calls_encode() {
+        let model = FlakyModel {
+            failures: 0,
+            error: || AppError::Embedding("unused".to_string()),
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        };
+
+        let results = model.encode_batch(&["a", "b", "c"]).await.unwrap();
+        assert_eq!(results, vec![vec![1.0, 0.0]; 3]);
+        assert_eq!(model.calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[cfg(not(feature = "candle"))]
+    #[tokio::test]
+    async fn test_candle_backend_requires_feature() {
+        let config = EmbeddingConfig {
+            backend: "candle".into(),
+            ..Default::default()
+        };
+
+        let result = create_embedding_model(&config, 384).await;
+        assert!(matches!(result, Err(crate::error::AppError::Config(_))));
+    }
 }
@@ -0,0 +1,327 @@
+//! 基于 Candle 的本地 Embedding 模型
+//!
+//! 加载 `sentence-transformers/all-MiniLM-L6-v2` 等 BERT 类模型，在本地完成推理：
+//! 分词 → Transformer 前向 → 池化（默认按 attention mask 平均）→ L2 归一化。
+//!
+//! 模型目录需包含 `config.json`、`tokenizer.json` 和 `model.safetensors`。
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use candle_core::{Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+use serde::Deserialize;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+use super::{EmbeddingModel, PoolingStrategy};
+use crate::config::config::EmbeddingConfig;
+use crate::error::{AppError, Result};
+
+/// 未配置 batch_size 时的默认批大小
+const DEFAULT_BATCH_SIZE: usize = 32;
+
+/// 从 `config.json` 中读取的模型尺寸
+///
+/// `candle_transformers` 的 `Config` 字段不公开，这里单独解析需要的字段。
+#[derive(Debug, Deserialize)]
+struct ModelDimensions {
+    hidden_size: usize,
+    max_position_embeddings: usize,
+}
+
+/// 推理所需的模型状态（在阻塞线程池中使用）
+struct CandleInner {
+    model: BertModel,
+    tokenizer: Tokenizer,
+    device: Device,
+}
+
+/// Candle 本地推理 Embedding 模型
+pub struct CandleEmbeddingModel {
+    inner: Arc<CandleInner>,
+    dimension: usize,
+    batch_size: usize,
+    pooling: PoolingStrategy,
+}
+
+impl CandleEmbeddingModel {
+    /// 根据配置加载模型
+    ///
+    /// `model_path` 可以是模型目录，也可以直接指向目录中的 `.safetensors` 文件。
+    pub fn from_config(config: &EmbeddingConfig) -> Result<Self> {
+        let model_path = config.model_path.as_deref().ok_or_else(|| {
+            AppError::Config("embedding.model_path must be set for the candle backend".to_string())
+        })?;
+        let device = select_device(config)?;
+        let batch_size = if config.batch_size == 0 {
+            DEFAULT_BATCH_SIZE
+        } else {
+            config.batch_size
+        };
+
+        Ok(Self::load(model_path, device, batch_size)?.with_pooling(config.pooling_strategy))
+    }
+
+    /// 设置 `encode` / `encode_batch` 使用的池化策略
+    pub fn with_pooling(mut self, pooling: PoolingStrategy) -> Self {
+        self.pooling = pooling;
+        self
+    }
+
+    /// 从模型目录或权重文件加载
+    pub fn load(model_path: &Path, device: Device, batch_size: usize) -> Result<Self> {
+        let (model_dir, weights_path) = resolve_model_files(model_path)?;
+
+        let config_json = std::fs::read_to_string(model_dir.join("config.json"))?;
+        let dimensions: ModelDimensions = serde_json::from_str(&config_json)?;
+        let bert_config: Config = serde_json::from_str(&config_json)?;
+
+        let mut tokenizer = Tokenizer::from_file(model_dir.join("tokenizer.json"))
+            .map_err(|e| AppError::Embedding(format!("Failed to load tokenizer: {}", e)))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..Default::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: dimensions.max_position_embeddings,
+                ..Default::default()
+            }))
+            .map_err(|e| AppError::Embedding(format!("Failed to configure tokenizer: {}", e)))?;
+
+        // SAFETY: 权重文件在模型生命周期内不会被修改
+        let vb = unsafe { VarBuilder::from_mmaped_safetensors(&[weights_path], DTYPE, &device) }
+            .map_err(candle_error)?;
+        let model = BertModel::load(vb, &bert_config).map_err(candle_error)?;
+
+        tracing::info!(
+            "Loaded candle embedding model from {} (dimension: {}, device: {:?})",
+            model_dir.display(),
+            dimensions.hidden_size,
+            device
+        );
+
+        Ok(Self {
+            inner: Arc::new(CandleInner {
+                model,
+                tokenizer,
+                device,
+            }),
+            dimension: dimensions.hidden_size,
+            batch_size: batch_size.max(1),
+            pooling: PoolingStrategy::default(),
+        })
+    }
+}
+
+impl CandleInner {
+    /// 对一批文本执行前向推理，返回池化并 L2 归一化后的向量
+    fn embed(&self, texts: Vec<String>, pooling: PoolingStrategy) -> Result<Vec<Vec<f32>>> {
+        let encodings = self
+            .tokenizer
+            .encode_batch(texts, true)
+            .map_err(|e| AppError::Embedding(format!("Tokenization failed: {}", e)))?;
+
+        let mut ids = Vec::with_capacity(encodings.len());
+        let mut masks = Vec::with_capacity(encodings.len());
+        for encoding in &encodings {
+            ids.push(Tensor::new(encoding.get_ids(), &self.device).map_err(candle_error)?);
+            masks.push(
+                Tensor::new(encoding.get_attention_mask(), &self.device).map_err(candle_error)?,
+            );
+        }
+
+        let input_ids = Tensor::stack(&ids, 0).map_err(candle_error)?;
+        let attention_mask = Tensor::stack(&masks, 0).map_err(candle_error)?;
+        let token_type_ids = input_ids.zeros_like().map_err(candle_error)?;
+
+        // (batch, seq_len, hidden)
+        let hidden = self
+            .model
+            .forward(&input_ids, &token_type_ids)
+            .map_err(candle_error)?;
+
+        pool_normalize(&hidden, &attention_mask, pooling)
+            .and_then(|pooled| pooled.to_vec2::<f32>())
+            .map_err(candle_error)
+    }
+}
+
+/// 按池化策略聚合隐藏状态并 L2 归一化
+fn pool_normalize(
+    hidden: &Tensor,
+    attention_mask: &Tensor,
+    pooling: PoolingStrategy,
+) -> candle_core::Result<Tensor> {
+    match pooling {
+        PoolingStrategy::Mean => mean_pool_normalize(hidden, attention_mask),
+        // (batch, hidden)
+        PoolingStrategy::Cls => l2_normalize(&hidden.narrow(1, 0, 1)?.squeeze(1)?),
+        PoolingStrategy::Max => {
+            // padding 位置加上极小值，使其不会被选为最大值
+            let mask = attention_mask.to_dtype(hidden.dtype())?.unsqueeze(2)?;
+            let penalty = mask.affine(1e9, -1e9)?;
+            l2_normalize(&hidden.broadcast_add(&penalty)?.max(1)?)
+        }
+    }
+}
+
+/// 按 attention mask 对隐藏状态做平均池化并 L2 归一化
+fn mean_pool_normalize(hidden: &Tensor, attention_mask: &Tensor) -> candle_core::Result<Tensor> {
+    // (batch, seq_len, 1)
+    let mask = attention_mask.to_dtype(hidden.dtype())?.unsqueeze(2)?;
+    let summed = hidden.broadcast_mul(&mask)?.sum(1)?;
+    let counts = mask.sum(1)?.clamp(1e-9, f64::MAX)?;
+    l2_normalize(&summed.broadcast_div(&counts)?)
+}
+
+/// 对 (batch, hidden) 向量逐行做 L2 归一化
+fn l2_normalize(pooled: &Tensor) -> candle_core::Result<Tensor> {
+    let norm = pooled
+        .sqr()?
+        .sum_keepdim(1)?
+        .sqrt()?
+        .clamp(1e-12, f64::MAX)?;
+    pooled.broadcast_div(&norm)
+}
+
+/// 解析模型目录与权重文件路径
+fn resolve_model_files(model_path: &Path) -> Result<(PathBuf, PathBuf)> {
+    match model_path.extension().and_then(|e| e.to_str()) {
+        Some("gguf") => Err(AppError::Config(format!(
+            "GGUF weights are not supported for BERT models, convert {} to safetensors",
+            model_path.display()
+        ))),
+        Some("safetensors") => {
+            let dir = model_path.parent().unwrap_or_else(|| Path::new("."));
+            Ok((dir.to_path_buf(), model_path.to_path_buf()))
+        }
+        _ => Ok((
+            model_path.to_path_buf(),
+            model_path.join("model.safetensors"),
+        )),
+    }
+}
+
+/// 根据 `embedding.device` / `embedding.use_gpu` 选择推理设备
+fn select_device(config: &EmbeddingConfig) -> Result<Device> {
+    let device = config.device.trim().to_lowercase();
+    let ordinal = match device.as_str() {
+        "cpu" => return Ok(Device::Cpu),
+        "" if !config.use_gpu => return Ok(Device::Cpu),
+        "" | "cuda" => 0,
+        other => other
+            .strip_prefix("cuda:")
+            .and_then(|n| n.parse::<usize>().ok())
+            .ok_or_else(|| {
+                AppError::Config(format!("Unsupported embedding device: {}", config.device))
+            })?,
+    };
+
+    Device::new_cuda(ordinal).map_err(candle_error)
+}
+
+fn candle_error(e: candle_core::Error) -> AppError {
+    AppError::Embedding(e.to_string())
+}
+
+impl CandleEmbeddingModel {
+    /// 分批在阻塞线程池中推理
+    async fn encode_batch_with_pooling(
+        &self,
+        texts: &[&str],
+        pooling: PoolingStrategy,
+    ) -> Result<Vec<Vec<f32>>> {
+        let mut all_embeddings = Vec::with_capacity(texts.len());
+
+        for chunk in texts.chunks(self.batch_size) {
+            let inner = self.inner.clone();
+            let chunk: Vec<String> = chunk.iter().map(|t| t.to_string()).collect();
+            let embeddings = tokio::task::spawn_blocking(move || inner.embed(chunk, pooling))
+                .await
+                .map_err(|e| AppError::Embedding(format!("Embedding task failed: {}", e)))??;
+            all_embeddings.extend(embeddings);
+        }
+
+        Ok(all_embeddings)
+    }
+}
+
+#[async_trait]
+impl EmbeddingModel for CandleEmbeddingModel {
+    async fn encode(&self, text: &str) -> Result<Vec<f32>> {
+        self.encode_with_pooling(text, self.pooling).await
+    }
+
+    async fn encode_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        self.encode_batch_with_pooling(texts, self.pooling).await
+    }
+
+    async fn encode_with_pooling(&self, text: &str, strategy: PoolingStrategy) -> Result<Vec<f32>> {
+        let embeddings = self.encode_batch_with_pooling(&[text], strategy).await?;
+        Ok(embeddings
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| vec![0.0; self.dimension]))
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_device() {
+        let mut config = EmbeddingConfig::default();
+        assert!(matches!(select_device(&config), Ok(Device::Cpu)));
+
+        config.device = "CPU".into();
+        config.use_gpu = true;
+        assert!(matches!(select_device(&config), Ok(Device::Cpu)));
+
+        config.device = "tpu".into();
+        assert!(matches!(select_device(&config), Err(AppError::Config(_))));
+    }
+
+    #[test]
+    fn test_mean_pool_normalize_ignores_padding() {
+        // 第二个 token 是 padding，不应参与平均
+        let hidden = Tensor::new(&[[[3.0f32, 4.0], [100.0, 100.0]]], &Device::Cpu).unwrap();
+        let mask = Tensor::new(&[[1u32, 0]], &Device::Cpu).unwrap();
+
+        let pooled = mean_pool_normalize(&hidden, &mask)
+            .unwrap()
+            .to_vec2::<f32>()
+            .unwrap();
+        assert!((pooled[0][0] - 0.6).abs() < 1e-6);
+        assert!((pooled[0][1] - 0.8).abs() < 1e-6);
+    }
+    #[test]
+    fn test_cls_and_max_pooling_differ_from_mean() {
+        // 两个有效 token，无 padding
+        let hidden = Tensor::new(&[[[3.0f32, 0.0], [0.0, 4.0]]], &Device::Cpu).unwrap();
+        let mask = Tensor::new(&[[1u32, 1]], &Device::Cpu).unwrap();
+        let pool = |strategy| {
+            pool_normalize(&hidden, &mask, strategy)
+                .unwrap()
+                .to_vec2::<f32>()
+                .unwrap()
+        };
+
+        let mean = pool(PoolingStrategy::Mean);
+        let cls = pool(PoolingStrategy::Cls);
+        let max = pool(PoolingStrategy::Max);
+
+        assert!((cls[0][0] - 1.0).abs() < 1e-6);
+        assert!(cls[0][1].abs() < 1e-6);
+        assert!((max[0][0] - 0.6).abs() < 1e-6);
+        assert!((max[0][1] - 0.8).abs() < 1e-6);
+        assert_ne!(cls, mean);
+    }
+}
@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::error::Result;
 use surrealdb::{Surreal, engine::any::Any};
@@ -33,89 +34,206 @@ pub trait FullTextIndex: Send + Sync {
     async fn delete(&self, id: &str) -> Result<bool>;
     async fn count(&self, session_id: &str) -> Result<u64>;
     async fn exists(&self, id: &str) -> Result<bool>;
+    /// 按轮次号顺序列出会话的全部文档（不做检索匹配，score 为 0）
+    async fn list_all(
+        &self,
+        session_id: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<FtsResult>>;
+}
+
+/// 全文索引配置（BM25 参数）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FullTextIndexConfig {
+    /// 词频饱和参数
+    pub k1: f32,
+    /// 文档长度归一化参数（0 表示不按长度归一化）
+    pub b: f32,
+}
+
+impl Default for FullTextIndexConfig {
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+/// 已索引的文档
+struct FtsDocument {
+    content: String,
+    metadata: FtsMetadata,
+    /// 词项 -> 词频
+    term_freqs: HashMap<String, u32>,
+    /// 文档长度（词项数）
+    length: u64,
 }
 
 pub struct MemoryFtsIndex {
-    documents: dashmap::DashMap<String, (String, FtsMetadata)>,
+    documents: dashmap::DashMap<String, FtsDocument>,
+    /// 语料总词项数，用于计算平均文档长度
+    total_terms: AtomicU64,
+    config: FullTextIndexConfig,
 }
 
 impl MemoryFtsIndex {
     pub fn new() -> Self {
+        Self::with_config(FullTextIndexConfig::default())
+    }
+
+    /// 使用指定 BM25 参数创建索引
+    pub fn with_config(config: FullTextIndexConfig) -> Self {
         Self {
             documents: dashmap::DashMap::new(),
+            total_terms: AtomicU64::new(0),
+            config,
         }
     }
 
-    fn matches_query(content: &str, query: &str) -> bool {
-        let query_words: Vec<&str> = query.split_whitespace().collect();
-        let content_lower = content.to_lowercase();
+    /// 将文本切分为小写词项
+    fn tokenize(text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    /// 文档是否包含查询的全部词项
+    fn matches_query(term_freqs: &HashMap<String, u32>, query_terms: &[String]) -> bool {
+        query_terms.iter().all(|term| term_freqs.contains_key(term))
+    }
+
+    /// 语料平均文档长度
+    fn average_length(&self) -> f32 {
+        let documents = self.documents.len();
+        if documents == 0 {
+            return 0.0;
+        }
+        self.total_terms.load(Ordering::Relaxed) as f32 / documents as f32
+    }
 
-        query_words
+    /// 计算查询词项的 IDF（`ln(1 + (N - df + 0.5) / (df + 0.5))`）
+    fn idf(&self, query_terms: &[String]) -> Vec<f32> {
+        let total_docs = self.documents.len() as f32;
+        query_terms
             .iter()
-            .all(|word| content_lower.contains(&word.to_lowercase()))
+            .map(|term| {
+                let doc_freq = self
+                    .documents
+                    .iter()
+                    .filter(|doc| doc.term_freqs.contains_key(term))
+                    .count() as f32;
+                (1.0 + (total_docs - doc_freq + 0.5) / (doc_freq + 0.5)).ln()
+            })
+            .collect()
     }
 
-    fn calculate_score(content: &str, query: &str) -> f32 {
-        let query_words: Vec<&str> = query.split_whitespace().collect();
-        let content_lower = content.to_lowercase();
+    /// 归一化的 BM25 分数
+    ///
+    /// 除以查询的理论最大分数（词频趋于无穷时每个词项贡献 `idf * (k1 + 1)`），
+    /// 结果位于 `[0, 1]`，便于与语义分数比较。
+    fn bm25_score(
+        &self,
+        doc: &FtsDocument,
+        query_terms: &[String],
+        idf: &[f32],
+        avg_length: f32,
+    ) -> f32 {
+        let FullTextIndexConfig { k1, b } = self.config;
+        let length_norm = if avg_length > 0.0 {
+            1.0 - b + b * doc.length as f32 / avg_length
+        } else {
+            1.0
+        };
 
         let mut score = 0.0;
-        for word in &query_words {
-            let word_lower = word.to_lowercase();
-            let count = content_lower.matches(&word_lower).count() as f32;
-            score += count / (word.len() as f32 + 1.0);
+        let mut max_score = 0.0;
+        for (term, idf) in query_terms.iter().zip(idf) {
+            let tf = doc.term_freqs.get(term).copied().unwrap_or(0) as f32;
+            score += idf * tf * (k1 + 1.0) / (tf + k1 * length_norm);
+            max_score += idf * (k1 + 1.0);
         }
 
-        score
+        if max_score > 0.0 {
+            (score / max_score).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
     }
 }
 
 #[async_trait]
 impl FullTextIndex for MemoryFtsIndex {
     async fn add(&self, id: &str, content: &str, metadata: FtsMetadata) -> Result<()> {
-        self.documents
-            .insert(id.to_string(), (content.to_string(), metadata));
+        let terms = Self::tokenize(content);
+        let mut term_freqs = HashMap::new();
+        for term in &terms {
+            *term_freqs.entry(term.clone()).or_insert(0) += 1;
+        }
+        let document = FtsDocument {
+            content: content.to_string(),
+            metadata,
+            term_freqs,
+            length: terms.len() as u64,
+        };
+
+        self.total_terms
+            .fetch_add(document.length, Ordering::Relaxed);
+        if let Some(previous) = self.documents.insert(id.to_string(), document) {
+            self.total_terms
+                .fetch_sub(previous.length, Ordering::Relaxed);
+        }
 
         Ok(())
     }
 
     async fn search(&self, query: &str, session_id: &str, limit: usize) -> Result<Vec<FtsResult>> {
+        let query_terms = Self::tokenize(query);
+        if query_terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let idf = self.idf(&query_terms);
+        let avg_length = self.average_length();
+
         let mut results: Vec<_> = self
             .documents
             .iter()
-            .filter(|ref_multi| ref_multi.value().1.session_id == session_id)
-            .filter(|ref_multi| {
-                let (content, _) = ref_multi.value();
-                Self::matches_query(content, query)
-            })
+            .filter(|doc| doc.metadata.session_id == session_id)
+            .filter(|doc| Self::matches_query(&doc.term_freqs, &query_terms))
             .map(|ref_multi| {
-                let (id, (content, meta)) = ref_multi.pair();
-                let score = Self::calculate_score(content, query);
+                let (id, doc) = ref_multi.pair();
                 FtsResult {
                     id: id.clone(),
-                    score,
-                    turn_id: meta.turn_id.clone(),
-                    gist: content.to_string(),
-                    metadata: meta.clone(),
+                    score: self.bm25_score(doc, &query_terms, &idf, avg_length),
+                    turn_id: doc.metadata.turn_id.clone(),
+                    gist: doc.content.clone(),
+                    metadata: doc.metadata.clone(),
                 }
             })
             .collect();
 
-        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
         results.truncate(limit);
 
         Ok(results)
     }
 
     async fn delete(&self, id: &str) -> Result<bool> {
-        Ok(self.documents.remove(id).is_some())
+        match self.documents.remove(id) {
+            Some((_, document)) => {
+                self.total_terms
+                    .fetch_sub(document.length, Ordering::Relaxed);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
     }
 
     async fn count(&self, session_id: &str) -> Result<u64> {
         let count = self
             .documents
             .iter()
-            .filter(|ref_multi| ref_multi.value().1.session_id == session_id)
+            .filter(|doc| doc.metadata.session_id == session_id)
             .count();
         Ok(count as u64)
     }
@@ -123,6 +241,38 @@ impl FullTextIndex for MemoryFtsIndex {
     async fn exists(&self, id: &str) -> Result<bool> {
         Ok(self.documents.contains_key(id))
     }
+
+    async fn list_all(
+        &self,
+        session_id: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<FtsResult>> {
+        let mut results: Vec<_> = self
+            .documents
+            .iter()
+            .filter(|doc| doc.metadata.session_id == session_id)
+            .map(|ref_multi| {
+                let (id, doc) = ref_multi.pair();
+                FtsResult {
+                    id: id.clone(),
+                    score: 0.0,
+                    turn_id: doc.metadata.turn_id.clone(),
+                    gist: doc.content.clone(),
+                    metadata: doc.metadata.clone(),
+                }
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            a.metadata
+                .turn_number
+                .cmp(&b.metadata.turn_number)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+
+        Ok(results.into_iter().skip(offset).take(limit).collect())
+    }
 }
 
 pub fn create_full_text_index(
@@ -203,20 +353,102 @@ mod tests {
         assert_eq!(count, 0);
     }
 
+    #[tokio::test]
+    async fn test_memory_fts_index_list_all() {
+        let index = MemoryFtsIndex::new();
+
+        for turn_number in [2, 1] {
+            let metadata = FtsMetadata {
+                session_id: "session_1".to_string(),
+                turn_id: format!("turn_{}", turn_number),
+                turn_number,
+                timestamp: Utc::now(),
+                extra: HashMap::new(),
+            };
+            let id = format!("doc_{}", turn_number);
+            index.add(&id, "hello world", metadata).await.unwrap();
+        }
+
+        let results = index.list_all("session_1", 10, 0).await.unwrap();
+        let turn_ids: Vec<_> = results.iter().map(|r| r.turn_id.as_str()).collect();
+        assert_eq!(turn_ids, vec!["turn_1", "turn_2"]);
+        assert_eq!(results[0].gist, "hello world");
+
+        assert!(index.list_all("session_2", 10, 0).await.unwrap().is_empty());
+    }
+
     #[test]
     fn test_matches_query() {
-        assert!(MemoryFtsIndex::matches_query("hello world rust", "hello"));
-        assert!(MemoryFtsIndex::matches_query("hello world rust", "world"));
-        assert!(MemoryFtsIndex::matches_query("hello world rust", "rust"));
-        assert!(!MemoryFtsIndex::matches_query("hello world rust", "python"));
+        let doc = |text: &str| {
+            let mut term_freqs = HashMap::new();
+            for term in MemoryFtsIndex::tokenize(text) {
+                *term_freqs.entry(term).or_insert(0) += 1;
+            }
+            term_freqs
+        };
+        let query = |text: &str| MemoryFtsIndex::tokenize(text);
+        let content = doc("Hello, world rust");
+
+        assert!(MemoryFtsIndex::matches_query(&content, &query("hello")));
+        assert!(MemoryFtsIndex::matches_query(&content, &query("World")));
+        assert!(MemoryFtsIndex::matches_query(
+            &content,
+            &query("rust hello")
+        ));
+        assert!(!MemoryFtsIndex::matches_query(&content, &query("python")));
     }
 
-    #[test]
-    fn test_calculate_score() {
-        let content = "hello world rust programming";
-        let score1 = MemoryFtsIndex::calculate_score(content, "hello");
-        let score2 = MemoryFtsIndex::calculate_score(content, "rust programming");
+    #[tokio::test]
+    async fn test_bm25_scoring() {
+        let index = MemoryFtsIndex::new();
+        let contents = [
+            ("doc_1", "rust rust rust memory"),
+            (
+                "doc_2",
+                "rust memory safety without garbage collection in practice",
+            ),
+            ("doc_3", "python scripting"),
+        ];
+        for (turn_number, (id, content)) in contents.into_iter().enumerate() {
+            let metadata = FtsMetadata {
+                session_id: "session_1".to_string(),
+                turn_id: id.to_string(),
+                turn_number: turn_number as u64,
+                timestamp: Utc::now(),
+                extra: HashMap::new(),
+            };
+            index.add(id, content, metadata).await.unwrap();
+        }
+
+        let results = index.search("rust", "session_1", 10).await.unwrap();
+        let ids: Vec<_> = results.iter().map(|r| r.id.as_str()).collect();
+        // 词频更高且文档更短的排在前面
+        assert_eq!(ids, vec!["doc_1", "doc_2"]);
+        assert!(results.iter().all(|r| r.score > 0.0 && r.score <= 1.0));
+        assert!(results[0].score > results[1].score);
+
+        // 平均长度随删除更新
+        assert!(index.delete("doc_2").await.unwrap());
+        assert_eq!(index.average_length(), 3.0);
+    }
+
+    #[tokio::test]
+    async fn test_bm25_length_normalization_configurable() {
+        let config = FullTextIndexConfig { k1: 1.2, b: 0.0 };
+        let index = MemoryFtsIndex::with_config(config);
+        for (id, content) in [("short", "rust"), ("long", "rust with many other words")] {
+            let metadata = FtsMetadata {
+                session_id: "session_1".to_string(),
+                turn_id: id.to_string(),
+                ..Default::default()
+            };
+            index.add(id, content, metadata).await.unwrap();
+        }
 
-        assert!(score2 > score1);
+        // b = 0 时不按文档长度归一化
+        let results = index.search("rust", "session_1", 10).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].score, results[1].score);
+        assert!(index.search("", "session_1", 10).await.unwrap().is_empty());
     }
 }
@@ -5,16 +5,40 @@ pub mod full_text;
 pub mod vector;
 
 pub use embedding::{EmbeddingModel, create_embedding_model};
-pub use full_text::{FtsMetadata, FtsResult, FullTextIndex, create_full_text_index};
-pub use vector::{VectorIndex, VectorMetadata, VectorSearchResult, create_vector_index};
+pub use full_text::{
+    FtsMetadata, FtsResult, FullTextIndex, FullTextIndexConfig, create_full_text_index,
+};
+pub use vector::{
+    HnswConfig, VectorIndex, VectorMetadata, VectorSearchResult, create_vector_index,
+};
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
 use serde::{Deserialize, Serialize};
 
-use crate::error::Result;
+use std::sync::Arc;
+
+use crate::error::{AppError, Result};
 use crate::models::index_record::IndexRecord;
+use crate::models::session::Session;
 use crate::models::turn::Turn;
+use crate::storage::repository::{SessionStore, TurnStore};
+
+/// 重建索引时每页读取的轮次数
+const REINDEX_PAGE_SIZE: usize = 100;
+
+/// 重建索引时列出已有索引条目的上限
+const REINDEX_LIST_LIMIT: usize = 10_000;
+
+/// 重建索引时每批编码的轮次数，避免压垮嵌入后端
+const REINDEX_CHUNK_SIZE: usize = 32;
+
+/// 全局搜索时每页读取的租户会话数
+const GLOBAL_SEARCH_SESSION_PAGE_SIZE: usize = 100;
+
+/// 跨会话搜索按会话内排名做二次 RRF 融合时的常数 k
+const CROSS_SESSION_RRF_K: u64 = 60;
 
 #[derive(Debug, Clone, Default)]
 pub struct SearchOptions {
@@ -42,6 +66,23 @@ pub struct SearchResult {
     pub turn_number: u64,
     pub timestamp: DateTime<Utc>,
     pub sources: Vec<String>,
+    /// 结果所属会话 ID
+    #[serde(default)]
+    pub session_id: String,
+    /// 结果所属会话名称（仅全局搜索时填充）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_name: Option<String>,
+}
+
+/// 会话索引重建报告
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReindexReport {
+    /// 删除的索引条目数
+    pub deleted: usize,
+    /// 重新索引的轮次数
+    pub reindexed: usize,
+    /// 重新索引失败的轮次数
+    pub failed: usize,
 }
 
 #[async_trait]
@@ -60,12 +101,36 @@ pub trait IndexService: Send + Sync {
         options: SearchOptions,
     ) -> Result<Vec<SearchResult>>;
     async fn delete_index(&self, turn_id: &str) -> Result<bool>;
+    /// 删除会话的全部索引条目，返回删除数量
+    async fn delete_session_indices(&self, session_id: &str) -> Result<usize>;
+    /// 删除会话的全部索引条目，并用当前嵌入模型从存储中的轮次重新建立
+    async fn reindex_session(&self, session_id: &str) -> Result<ReindexReport>;
+    /// 在租户的全部会话中搜索，结果附带所属会话 ID 与名称
+    async fn search_global(
+        &self,
+        tenant_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchResult>>;
+    /// 对租户的每个会话并发执行 `search_indices`，再按会话内排名做二次 RRF 融合
+    ///
+    /// 与直接比较原始得分的 `search_global` 不同，各会话的全文得分依赖各自的语料统计，
+    /// 按排名融合可避免个别会话的得分尺度主导结果。返回全局前 `options.limit` 条
+    /// （为 0 时不截断），结果的 `score` 为融合得分。
+    async fn cross_session_search(
+        &self,
+        tenant_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchResult>>;
 }
 
 pub struct UnifiedIndexService {
     vector_index: Box<dyn VectorIndex>,
     full_text_index: Box<dyn FullTextIndex>,
     embedding_model: Box<dyn EmbeddingModel>,
+    turn_repository: Option<Arc<dyn TurnStore>>,
+    session_repository: Option<Arc<dyn SessionStore>>,
 }
 
 impl UnifiedIndexService {
@@ -78,9 +143,224 @@ impl UnifiedIndexService {
             vector_index,
             full_text_index,
             embedding_model,
+            turn_repository: None,
+            session_repository: None,
+        }
+    }
+
+    /// 设置轮次仓储（`reindex_session` 需要，搜索时也用于补全结果摘要）
+    pub fn with_turn_repository(mut self, turn_repository: Arc<dyn TurnStore>) -> Self {
+        self.turn_repository = Some(turn_repository);
+        self
+    }
+
+    /// 设置会话仓储（`search_global` 需要，用于列出租户的会话）
+    pub fn with_session_repository(mut self, session_repository: Arc<dyn SessionStore>) -> Self {
+        self.session_repository = Some(session_repository);
+        self
+    }
+
+    /// 分页读取租户的全部会话
+    async fn fetch_tenant_sessions(&self, tenant_id: &str) -> Result<Vec<Session>> {
+        let session_repository = self.session_repository.as_ref().ok_or_else(|| {
+            AppError::Config("Global search requires a session repository".to_string())
+        })?;
+
+        let mut sessions = Vec::new();
+        let mut start = 0;
+        loop {
+            let page = session_repository
+                .list_by_tenant(tenant_id, GLOBAL_SEARCH_SESSION_PAGE_SIZE, start)
+                .await?;
+            let fetched = page.len();
+            sessions.extend(page);
+
+            if fetched < GLOBAL_SEARCH_SESSION_PAGE_SIZE {
+                break;
+            }
+            start += fetched;
+        }
+
+        Ok(sessions)
+    }
+
+    /// 为缺少摘要的搜索结果批量读取轮次并补全摘要
+    async fn fill_missing_gists(&self, results: &mut [SearchResult]) {
+        let Some(turn_repository) = &self.turn_repository else {
+            return;
+        };
+
+        let missing: Vec<usize> = results
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.gist.is_empty())
+            .map(|(i, _)| i)
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        let ids: Vec<&str> = missing
+            .iter()
+            .map(|&i| results[i].turn_id.as_str())
+            .collect();
+        let turns = match turn_repository.get_turns_by_ids(&ids).await {
+            Ok(turns) => turns,
+            Err(e) => {
+                tracing::warn!("Failed to load turns for search results: {}", e);
+                return;
+            }
+        };
+
+        for (i, turn) in missing.into_iter().zip(turns) {
+            if let Some(turn) = turn {
+                results[i].gist = turn_gist(&turn);
+            }
         }
     }
 
+    /// 读取会话的全部轮次
+    async fn fetch_session_turns(&self, session_id: &str) -> Result<Vec<Turn>> {
+        let turn_repository = self.turn_repository.as_ref().ok_or_else(|| {
+            AppError::Config("Index service has no turn repository configured".to_string())
+        })?;
+
+        let mut turns = Vec::new();
+        loop {
+            let page = turn_repository
+                .list_by_session(session_id, REINDEX_PAGE_SIZE, turns.len())
+                .await?;
+            let page_len = page.len();
+            turns.extend(page);
+            if page_len < REINDEX_PAGE_SIZE {
+                break;
+            }
+        }
+
+        Ok(turns)
+    }
+
+    /// 索引单个轮次；`force` 为 true 时跳过已索引检查并覆盖已有条目
+    async fn index_turn_with(&self, turn: &Turn, force: bool) -> Result<IndexRecord> {
+        let turn_id = &turn.id;
+
+        if force {
+            self.delete_index(turn_id).await?;
+        } else {
+            let vector_id = format!("vec_{}", turn_id);
+
+            let vector_exists = self.vector_index.exists(&vector_id).await?;
+            let fts_exists = self
+                .full_text_index
+                .exists(&format!("doc_{}", turn_id))
+                .await?;
+
+            if vector_exists || fts_exists {
+                return Err(AppError::Validation(format!(
+                    "Turn {} is already indexed",
+                    turn_id
+                )));
+            }
+        }
+
+        let gist = turn_gist(turn);
+
+        let embedding = if let Some(dehydrated) = &turn.dehydrated {
+            if let Some(emb) = &dehydrated.embedding {
+                emb.clone()
+            } else {
+                self.embedding_model.encode(&gist).await?
+            }
+        } else {
+            self.embedding_model.encode(&gist).await?
+        };
+
+        self.add_turn_entries(turn, &gist, &embedding).await
+    }
+
+    /// 使用当前嵌入模型分批（每批 `REINDEX_CHUNK_SIZE` 个）重新编码并写入轮次索引
+    ///
+    /// 不复用脱水数据中已存储的嵌入向量。返回 `(成功数, 失败数)`，
+    /// 某一批编码失败时整批计为失败。
+    async fn reindex_turns(&self, turns: &[Turn]) -> (usize, usize) {
+        let (mut reindexed, mut failed) = (0, 0);
+        for chunk in turns.chunks(REINDEX_CHUNK_SIZE) {
+            let gists: Vec<String> = chunk.iter().map(turn_gist).collect();
+            let texts: Vec<&str> = gists.iter().map(String::as_str).collect();
+            let embeddings = match self.embedding_model.encode_batch(&texts).await {
+                Ok(embeddings) if embeddings.len() == chunk.len() => embeddings,
+                Ok(embeddings) => {
+                    tracing::warn!(
+                        "Embedding model returned {} vectors for {} turns",
+                        embeddings.len(),
+                        chunk.len()
+                    );
+                    failed += chunk.len();
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to encode reindex batch: {}", e);
+                    failed += chunk.len();
+                    continue;
+                }
+            };
+
+            for ((turn, gist), embedding) in chunk.iter().zip(&gists).zip(&embeddings) {
+                match self.add_turn_entries(turn, gist, embedding).await {
+                    Ok(_) => reindexed += 1,
+                    Err(e) => {
+                        tracing::warn!("Failed to reindex turn {}: {}", turn.id, e);
+                        failed += 1;
+                    }
+                }
+            }
+        }
+
+        (reindexed, failed)
+    }
+
+    /// 写入轮次的向量（`vec_{turn_id}`）与全文（`doc_{turn_id}`）索引条目
+    async fn add_turn_entries(
+        &self,
+        turn: &Turn,
+        gist: &str,
+        embedding: &[f32],
+    ) -> Result<IndexRecord> {
+        let record = IndexRecord::new(
+            &turn.id,
+            &turn.session_id,
+            gist,
+            turn.metadata.timestamp,
+            turn.turn_number,
+        );
+
+        let vector_metadata = VectorMetadata {
+            session_id: turn.session_id.clone(),
+            turn_id: turn.id.clone(),
+            turn_number: turn.turn_number,
+            timestamp: turn.metadata.timestamp,
+            extra: std::collections::HashMap::new(),
+        };
+
+        self.vector_index
+            .add(&format!("vec_{}", turn.id), embedding, vector_metadata)
+            .await?;
+
+        let fts_metadata = FtsMetadata {
+            session_id: turn.session_id.clone(),
+            turn_id: turn.id.clone(),
+            turn_number: turn.turn_number,
+            timestamp: turn.metadata.timestamp,
+            extra: std::collections::HashMap::new(),
+        };
+
+        self.full_text_index
+            .add(&format!("doc_{}", turn.id), gist, fts_metadata)
+            .await?;
+
+        Ok(record)
+    }
+
     fn rrf_fusion(
         vector_results: &[VectorSearchResult],
         fts_results: &[FtsResult],
@@ -141,6 +421,17 @@ impl UnifiedIndexService {
                             .map(|r| r.metadata.turn_number)
                     })
                     .unwrap_or(0);
+                let session_id = vector_results
+                    .iter()
+                    .find(|r| r.turn_id == turn_id)
+                    .map(|r| r.metadata.session_id.clone())
+                    .or_else(|| {
+                        fts_results
+                            .iter()
+                            .find(|r| r.turn_id == turn_id)
+                            .map(|r| r.metadata.session_id.clone())
+                    })
+                    .unwrap_or_default();
 
                 SearchResult {
                     turn_id,
@@ -150,6 +441,8 @@ impl UnifiedIndexService {
                     turn_number,
                     timestamp,
                     sources,
+                    session_id,
+                    session_name: None,
                 }
             })
             .collect();
@@ -157,76 +450,202 @@ impl UnifiedIndexService {
         results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
         results
     }
-}
-
-#[async_trait]
-impl IndexService for UnifiedIndexService {
-    async fn index_turn(&self, turn: &Turn) -> Result<IndexRecord> {
-        let turn_id = &turn.id;
-        let vector_id = format!("vec_{}", turn_id);
 
-        let vector_exists = self.vector_index.exists(&vector_id).await?;
-        let fts_exists = self
-            .full_text_index
-            .exists(&format!("doc_{}", turn_id))
-            .await?;
+    /// 丢弃得分低于 `threshold` 的向量与全文结果
+    ///
+    /// 在 RRF 融合之前调用，避免弱相关命中抬高融合得分。
+    fn retain_above_threshold(
+        vector_results: &mut Option<Vec<VectorSearchResult>>,
+        fts_results: &mut Option<Vec<FtsResult>>,
+        threshold: Option<f32>,
+    ) {
+        let Some(threshold) = threshold else {
+            return;
+        };
+        if let Some(results) = vector_results {
+            results.retain(|r| r.score >= threshold);
+        }
+        if let Some(results) = fts_results {
+            results.retain(|r| r.score >= threshold);
+        }
+    }
 
-        if vector_exists || fts_exists {
-            return Err(crate::error::AppError::Validation(format!(
-                "Turn {} is already indexed",
-                turn_id
-            )));
+    /// 合并向量与全文检索结果：两者都有时使用 RRF 融合
+    fn combine_results(
+        vector_results: Option<Vec<VectorSearchResult>>,
+        fts_results: Option<Vec<FtsResult>>,
+    ) -> Vec<SearchResult> {
+        match (vector_results, fts_results) {
+            (Some(vr), None) => vr
+                .into_iter()
+                .map(|r| SearchResult {
+                    turn_id: r.turn_id,
+                    gist: "".to_string(),
+                    score: r.score,
+                    result_type: SearchResultType::Semantic,
+                    turn_number: r.metadata.turn_number,
+                    timestamp: r.metadata.timestamp,
+                    sources: vec!["vector".to_string()],
+                    session_id: r.metadata.session_id,
+                    session_name: None,
+                })
+                .collect(),
+            (None, Some(fr)) => fr
+                .into_iter()
+                .map(|r| SearchResult {
+                    turn_id: r.turn_id,
+                    gist: r.gist,
+                    score: r.score,
+                    result_type: SearchResultType::FullText,
+                    turn_number: r.metadata.turn_number,
+                    timestamp: r.metadata.timestamp,
+                    sources: vec!["full_text".to_string()],
+                    session_id: r.metadata.session_id,
+                    session_name: None,
+                })
+                .collect(),
+            (Some(vr), Some(fr)) => Self::rrf_fusion(&vr, &fr, 60),
+            (None, None) => vec![],
         }
+    }
 
-        let gist = turn
-            .dehydrated
-            .as_ref()
-            .map(|d| d.gist.clone())
-            .unwrap_or_else(|| turn.raw_content.chars().take(100).collect());
+    /// 在给定会话集合中检索并合并结果，为每条结果填充会话名称
+    async fn search_sessions(
+        &self,
+        sessions: &[Session],
+        query: &str,
+        options: &SearchOptions,
+    ) -> Result<Vec<SearchResult>> {
+        let limit = options.limit.max(10);
 
-        let embedding = if let Some(dehydrated) = &turn.dehydrated {
-            if let Some(emb) = &dehydrated.embedding {
-                emb.clone()
-            } else {
-                self.embedding_model.encode(&gist).await?
-            }
+        let query_embedding = if options.use_semantic || options.use_hybrid {
+            Some(self.embedding_model.encode(query).await?)
         } else {
-            self.embedding_model.encode(&gist).await?
+            None
         };
+        let use_full_text = options.use_full_text || options.use_hybrid;
 
-        let record = IndexRecord::new(
-            &turn.id,
-            &turn.session_id,
-            &gist,
-            turn.metadata.timestamp,
-            turn.turn_number,
-        );
+        let mut vector_results = query_embedding.as_ref().map(|_| Vec::new());
+        let mut fts_results = use_full_text.then(Vec::new);
 
-        let vector_metadata = VectorMetadata {
-            session_id: turn.session_id.clone(),
-            turn_id: turn.id.clone(),
-            turn_number: turn.turn_number,
-            timestamp: turn.metadata.timestamp,
-            extra: std::collections::HashMap::new(),
-        };
+        for session in sessions {
+            if let (Some(embedding), Some(results)) = (&query_embedding, &mut vector_results) {
+                results.extend(
+                    self.vector_index
+                        .search(embedding, &session.id, limit, options.threshold)
+                        .await?,
+                );
+            }
+            if let Some(results) = &mut fts_results {
+                results.extend(
+                    self.full_text_index
+                        .search(query, &session.id, limit)
+                        .await?,
+                );
+            }
+        }
 
-        self.vector_index
-            .add(&format!("vec_{}", turn.id), &embedding, vector_metadata)
-            .await?;
+        // 各会话的得分处于同一尺度，合并后重新排序并截断
+        if let Some(results) = &mut vector_results {
+            results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+            results.truncate(limit);
+        }
+        if let Some(results) = &mut fts_results {
+            results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+            results.truncate(limit);
+        }
+        Self::retain_above_threshold(&mut vector_results, &mut fts_results, options.threshold);
 
-        let fts_metadata = FtsMetadata {
-            session_id: turn.session_id.clone(),
-            turn_id: turn.id.clone(),
-            turn_number: turn.turn_number,
-            timestamp: turn.metadata.timestamp,
-            extra: std::collections::HashMap::new(),
-        };
+        let mut results = Self::combine_results(vector_results, fts_results);
+        results.truncate(limit);
 
-        self.full_text_index
-            .add(&format!("doc_{}", turn.id), &gist, fts_metadata)
-            .await?;
+        Self::fill_session_names(&mut results, sessions);
 
-        Ok(record)
+        Ok(results)
+    }
+
+    /// 并发检索每个会话，按会话内排名融合后截断为前 `options.limit` 条
+    async fn search_sessions_fused(
+        &self,
+        sessions: &[Session],
+        query: &str,
+        options: &SearchOptions,
+    ) -> Result<Vec<SearchResult>> {
+        let per_session = join_all(
+            sessions
+                .iter()
+                .map(|session| self.search_indices(&session.id, query, options.clone())),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let mut results = Self::rrf_merge_sessions(per_session, CROSS_SESSION_RRF_K);
+        if options.limit > 0 {
+            results.truncate(options.limit);
+        }
+        Self::fill_session_names(&mut results, sessions);
+
+        Ok(results)
+    }
+
+    /// 按各会话结果列表内的排名做 RRF 融合
+    ///
+    /// 每条结果的得分替换为 `1 / (k + 会话内排名)`，同一轮次出现在多个列表中时得分相加；
+    /// 融合得分相同时按原始得分降序排列。
+    fn rrf_merge_sessions(per_session: Vec<Vec<SearchResult>>, k: u64) -> Vec<SearchResult> {
+        let mut merged: std::collections::HashMap<String, (f32, SearchResult)> =
+            std::collections::HashMap::new();
+
+        for mut results in per_session {
+            results.sort_by(|a, b| b.score.total_cmp(&a.score));
+            for (rank, result) in results.into_iter().enumerate() {
+                let rrf_score = 1.0 / (k + rank as u64) as f32;
+                merged
+                    .entry(result.turn_id.clone())
+                    .and_modify(|(score, _)| *score += rrf_score)
+                    .or_insert((rrf_score, result));
+            }
+        }
+
+        let mut fused: Vec<(f32, SearchResult)> = merged.into_values().collect();
+        fused.sort_by(|a, b| {
+            b.0.total_cmp(&a.0)
+                .then_with(|| b.1.score.total_cmp(&a.1.score))
+                .then_with(|| a.1.turn_id.cmp(&b.1.turn_id))
+        });
+
+        fused
+            .into_iter()
+            .map(|(score, mut result)| {
+                result.score = score;
+                result
+            })
+            .collect()
+    }
+
+    /// 为结果填充所属会话的名称
+    fn fill_session_names(results: &mut [SearchResult], sessions: &[Session]) {
+        let names: std::collections::HashMap<&str, &str> = sessions
+            .iter()
+            .map(|s| (s.id.as_str(), s.name.as_str()))
+            .collect();
+        for result in results {
+            result.session_name = names
+                .get(result.session_id.as_str())
+                .map(|name| name.to_string());
+        }
+    }
+}
+
+#[async_trait]
+impl IndexService for UnifiedIndexService {
+    #[tracing::instrument(
+        skip_all,
+        fields(service.name = "IndexService", session.id = %turn.session_id, turn.id = %turn.id)
+    )]
+    async fn index_turn(&self, turn: &Turn) -> Result<IndexRecord> {
+        self.index_turn_with(turn, false).await
     }
 
     async fn list_indices(
@@ -235,46 +654,38 @@ impl IndexService for UnifiedIndexService {
         limit: usize,
         offset: usize,
     ) -> Result<Vec<IndexRecord>> {
-        let vector_results = self
+        let mut indices: Vec<IndexRecord> = self
             .vector_index
-            .search(&vec![0.0; 384], session_id, limit + offset)
-            .await?;
-
-        let mut indices: Vec<IndexRecord> = Vec::with_capacity(limit);
-        for (i, result) in vector_results.iter().enumerate() {
-            if i >= offset {
-                indices.push(IndexRecord::new(
+            .list_all(session_id, limit, offset)
+            .await?
+            .into_iter()
+            .map(|result| {
+                IndexRecord::new(
                     &result.turn_id,
                     session_id,
                     "",
                     result.metadata.timestamp,
                     result.metadata.turn_number,
-                ));
-                if indices.len() >= limit {
-                    break;
-                }
-            }
-        }
+                )
+            })
+            .collect();
 
         if indices.is_empty() {
-            let fts_results = self
+            indices = self
                 .full_text_index
-                .search("", session_id, limit + offset)
-                .await?;
-            for (i, result) in fts_results.iter().enumerate() {
-                if i >= offset {
-                    indices.push(IndexRecord::new(
+                .list_all(session_id, limit, offset)
+                .await?
+                .into_iter()
+                .map(|result| {
+                    IndexRecord::new(
                         &result.turn_id,
                         session_id,
                         &result.gist,
                         result.metadata.timestamp,
                         result.metadata.turn_number,
-                    ));
-                    if indices.len() >= limit {
-                        break;
-                    }
-                }
-            }
+                    )
+                })
+                .collect();
         }
 
         Ok(indices)
@@ -288,18 +699,18 @@ impl IndexService for UnifiedIndexService {
     ) -> Result<Vec<SearchResult>> {
         let limit = options.limit.max(10);
 
-        let vector_results = if options.use_semantic || options.use_hybrid {
+        let mut vector_results = if options.use_semantic || options.use_hybrid {
             let query_embedding = self.embedding_model.encode(query).await?;
             Some(
                 self.vector_index
-                    .search(&query_embedding, session_id, limit)
+                    .search(&query_embedding, session_id, limit, options.threshold)
                     .await?,
             )
         } else {
             None
         };
 
-        let fts_results = if options.use_full_text || options.use_hybrid {
+        let mut fts_results = if options.use_full_text || options.use_hybrid {
             Some(
                 self.full_text_index
                     .search(query, session_id, limit)
@@ -308,35 +719,12 @@ impl IndexService for UnifiedIndexService {
         } else {
             None
         };
+        Self::retain_above_threshold(&mut vector_results, &mut fts_results, options.threshold);
 
-        match (vector_results, fts_results) {
-            (Some(vr), None) => Ok(vr
-                .into_iter()
-                .map(|r| SearchResult {
-                    turn_id: r.turn_id,
-                    gist: "".to_string(),
-                    score: r.score,
-                    result_type: SearchResultType::Semantic,
-                    turn_number: r.metadata.turn_number,
-                    timestamp: r.metadata.timestamp,
-                    sources: vec!["vector".to_string()],
-                })
-                .collect()),
-            (None, Some(fr)) => Ok(fr
-                .into_iter()
-                .map(|r| SearchResult {
-                    turn_id: r.turn_id,
-                    gist: r.gist,
-                    score: r.score,
-                    result_type: SearchResultType::FullText,
-                    turn_number: r.metadata.turn_number,
-                    timestamp: r.metadata.timestamp,
-                    sources: vec!["full_text".to_string()],
-                })
-                .collect()),
-            (Some(vr), Some(fr)) => Ok(Self::rrf_fusion(&vr, &fr, 60)),
-            (None, None) => Ok(vec![]),
-        }
+        let mut results = Self::combine_results(vector_results, fts_results);
+
+        self.fill_missing_gists(&mut results).await;
+        Ok(results)
     }
 
     async fn delete_index(&self, turn_id: &str) -> Result<bool> {
@@ -350,6 +738,92 @@ impl IndexService for UnifiedIndexService {
             .await?;
         Ok(vector_deleted || fts_deleted)
     }
+
+    async fn delete_session_indices(&self, session_id: &str) -> Result<usize> {
+        let records = self.list_indices(session_id, REINDEX_LIST_LIMIT, 0).await?;
+
+        let mut deleted = 0;
+        for record in &records {
+            if self.delete_index(&record.turn_id).await? {
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    async fn reindex_session(&self, session_id: &str) -> Result<ReindexReport> {
+        // 先读取轮次，读取失败时不删除任何索引
+        let turns = self.fetch_session_turns(session_id).await?;
+
+        let mut turn_ids: Vec<String> = self
+            .list_indices(session_id, REINDEX_LIST_LIMIT, 0)
+            .await?
+            .into_iter()
+            .map(|record| record.turn_id)
+            .collect();
+        for turn in &turns {
+            if !turn_ids.contains(&turn.id) {
+                turn_ids.push(turn.id.clone());
+            }
+        }
+
+        let mut report = ReindexReport::default();
+        for turn_id in &turn_ids {
+            if self.delete_index(turn_id).await? {
+                report.deleted += 1;
+            }
+        }
+
+        (report.reindexed, report.failed) = self.reindex_turns(&turns).await;
+
+        tracing::info!(
+            "Reindexed session {}: {} deleted, {} reindexed, {} failed",
+            session_id,
+            report.deleted,
+            report.reindexed,
+            report.failed
+        );
+
+        Ok(report)
+    }
+
+    async fn search_global(
+        &self,
+        tenant_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchResult>> {
+        let sessions = self.fetch_tenant_sessions(tenant_id).await?;
+        if sessions.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut results = self.search_sessions(&sessions, query, &options).await?;
+        self.fill_missing_gists(&mut results).await;
+        Ok(results)
+    }
+
+    async fn cross_session_search(
+        &self,
+        tenant_id: &str,
+        query: &str,
+        options: SearchOptions,
+    ) -> Result<Vec<SearchResult>> {
+        let sessions = self.fetch_tenant_sessions(tenant_id).await?;
+        if sessions.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.search_sessions_fused(&sessions, query, &options).await
+    }
+}
+
+/// 轮次的索引摘要：优先使用脱水摘要，否则取原文前 100 个字符
+fn turn_gist(turn: &Turn) -> String {
+    turn.dehydrated
+        .as_ref()
+        .map(|d| d.gist.clone())
+        .unwrap_or_else(|| turn.raw_content.chars().take(100).collect())
 }
 
 pub fn create_unified_index_service(
@@ -363,3 +837,456 @@ pub fn create_unified_index_service(
         embedding_model,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::embedding::SimpleEmbeddingModel;
+
+    fn service() -> UnifiedIndexService {
+        UnifiedIndexService::new(
+            create_vector_index(None, None),
+            create_full_text_index(None, false),
+            Box::new(SimpleEmbeddingModel::new(384)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_forced_index_bypasses_idempotency_check() {
+        let service = service();
+        let turn = Turn::new("session_1", 1, "hello reindex");
+
+        service.index_turn(&turn).await.unwrap();
+        assert!(service.index_turn(&turn).await.is_err());
+        assert!(service.index_turn_with(&turn, true).await.is_ok());
+    }
+
+    /// 记录每次批量编码大小的嵌入模型
+    struct BatchRecordingModel {
+        inner: SimpleEmbeddingModel,
+        batch_sizes: Arc<parking_lot::Mutex<Vec<usize>>>,
+    }
+
+    #[async_trait]
+    impl EmbeddingModel for BatchRecordingModel {
+        async fn encode(&self, text: &str) -> Result<Vec<f32>> {
+            self.inner.encode(text).await
+        }
+
+        async fn encode_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            self.batch_sizes.lock().push(texts.len());
+            self.inner.encode_batch(texts).await
+        }
+
+        fn dimension(&self) -> usize {
+            self.inner.dimension()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reindex_turns_encodes_in_chunks() {
+        let batch_sizes = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let service = UnifiedIndexService::new(
+            create_vector_index(None, None),
+            create_full_text_index(None, false),
+            Box::new(BatchRecordingModel {
+                inner: SimpleEmbeddingModel::new(384),
+                batch_sizes: batch_sizes.clone(),
+            }),
+        );
+        let turns: Vec<Turn> = (1..=70)
+            .map(|n| Turn::new("session_1", n, &format!("turn {}", n)))
+            .collect();
+
+        assert_eq!(service.reindex_turns(&turns).await, (70, 0));
+        assert_eq!(*batch_sizes.lock(), vec![32, 32, 6]);
+        assert_eq!(
+            service
+                .list_indices("session_1", 100, 0)
+                .await
+                .unwrap()
+                .len(),
+            70
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_session_indices_leaves_no_orphans() {
+        let service = service();
+        for (session_id, turn_number, content) in [
+            ("session_1", 1, "first turn"),
+            ("session_1", 2, "second turn"),
+            ("session_2", 1, "other session"),
+        ] {
+            let turn = Turn::new(session_id, turn_number, content);
+            service.index_turn(&turn).await.unwrap();
+        }
+
+        assert_eq!(
+            service.delete_session_indices("session_1").await.unwrap(),
+            2
+        );
+        assert!(
+            service
+                .list_indices("session_1", 10, 0)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            service
+                .list_indices("session_2", 10, 0)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    /// 固定返回给定得分的向量索引
+    struct FixedVectorIndex(Vec<f32>);
+
+    #[async_trait]
+    impl VectorIndex for FixedVectorIndex {
+        async fn add(&self, _id: &str, _vector: &[f32], _metadata: VectorMetadata) -> Result<()> {
+            Ok(())
+        }
+
+        async fn search(
+            &self,
+            _query: &[f32],
+            session_id: &str,
+            _limit: usize,
+            _min_score: Option<f32>,
+        ) -> Result<Vec<VectorSearchResult>> {
+            Ok(self
+                .0
+                .iter()
+                .enumerate()
+                .map(|(i, score)| VectorSearchResult {
+                    id: format!("vec_turn_{}", i),
+                    score: *score,
+                    turn_id: format!("turn_{}", i),
+                    metadata: VectorMetadata {
+                        session_id: session_id.to_string(),
+                        turn_id: format!("turn_{}", i),
+                        turn_number: i as u64,
+                        ..Default::default()
+                    },
+                })
+                .collect())
+        }
+
+        async fn delete(&self, _id: &str) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn count(&self, _session_id: &str) -> Result<u64> {
+            Ok(self.0.len() as u64)
+        }
+
+        async fn exists(&self, _id: &str) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn list_all(
+            &self,
+            _session_id: &str,
+            _limit: usize,
+            _offset: usize,
+        ) -> Result<Vec<VectorSearchResult>> {
+            Ok(Vec::new())
+        }
+    }
+
+    /// 固定返回给定得分的全文索引
+    struct FixedFtsIndex(Vec<f32>);
+
+    #[async_trait]
+    impl FullTextIndex for FixedFtsIndex {
+        async fn add(&self, _id: &str, _content: &str, _metadata: FtsMetadata) -> Result<()> {
+            Ok(())
+        }
+
+        async fn search(
+            &self,
+            _query: &str,
+            session_id: &str,
+            _limit: usize,
+        ) -> Result<Vec<FtsResult>> {
+            Ok(self
+                .0
+                .iter()
+                .enumerate()
+                .map(|(i, score)| FtsResult {
+                    id: format!("doc_turn_{}", i),
+                    score: *score,
+                    turn_id: format!("turn_{}", i),
+                    gist: format!("gist {}", i),
+                    metadata: FtsMetadata {
+                        session_id: session_id.to_string(),
+                        turn_id: format!("turn_{}", i),
+                        turn_number: i as u64,
+                        ..Default::default()
+                    },
+                })
+                .collect())
+        }
+
+        async fn delete(&self, _id: &str) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn count(&self, _session_id: &str) -> Result<u64> {
+            Ok(self.0.len() as u64)
+        }
+
+        async fn exists(&self, _id: &str) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn list_all(
+            &self,
+            _session_id: &str,
+            _limit: usize,
+            _offset: usize,
+        ) -> Result<Vec<FtsResult>> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn fixed_score_service(vector_scores: Vec<f32>, fts_scores: Vec<f32>) -> UnifiedIndexService {
+        UnifiedIndexService::new(
+            Box::new(FixedVectorIndex(vector_scores)),
+            Box::new(FixedFtsIndex(fts_scores)),
+            Box::new(SimpleEmbeddingModel::new(384)),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_search_indices_threshold_filters_single_mode() {
+        let service = fixed_score_service(vec![0.9, 0.5, 0.49], vec![0.8, 0.2]);
+
+        let semantic = SearchOptions {
+            use_semantic: true,
+            threshold: Some(0.5),
+            ..Default::default()
+        };
+        let results = service
+            .search_indices("session_1", "query", semantic)
+            .await
+            .unwrap();
+        let scores: Vec<f32> = results.iter().map(|r| r.score).collect();
+        assert_eq!(scores, vec![0.9, 0.5]);
+
+        let full_text = SearchOptions {
+            use_full_text: true,
+            threshold: Some(0.5),
+            ..Default::default()
+        };
+        let results = service
+            .search_indices("session_1", "query", full_text)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].turn_id, "turn_0");
+
+        let unfiltered = SearchOptions {
+            use_semantic: true,
+            ..Default::default()
+        };
+        let results = service
+            .search_indices("session_1", "query", unfiltered)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_search_indices_threshold_applies_before_fusion() {
+        let service = fixed_score_service(vec![0.9, 0.1], vec![0.7, 0.3, 0.6]);
+
+        let options = SearchOptions {
+            use_hybrid: true,
+            threshold: Some(0.5),
+            ..Default::default()
+        };
+        let results = service
+            .search_indices("session_1", "query", options)
+            .await
+            .unwrap();
+
+        let mut turn_ids: Vec<&str> = results.iter().map(|r| r.turn_id.as_str()).collect();
+        turn_ids.sort();
+        assert_eq!(turn_ids, vec!["turn_0", "turn_2"]);
+        let fused = results.iter().find(|r| r.turn_id == "turn_0").unwrap();
+        assert_eq!(fused.sources, vec!["vector", "full_text"]);
+    }
+
+    #[tokio::test]
+    async fn test_reindex_session_replaces_old_entries() {
+        use crate::storage::in_memory::InMemoryRepository;
+        use crate::storage::repository::Repository;
+
+        let turns = InMemoryRepository::<Turn>::new();
+        let service = service().with_turn_repository(Arc::new(turns.clone()));
+
+        let mut edited = Turn::new("session_1", 1, "legacy deployment notes");
+        service.index_turn(&edited).await.unwrap();
+        service
+            .index_turn(&Turn::new("session_1", 2, "deleted turn"))
+            .await
+            .unwrap();
+
+        // 存储中只剩修改过内容的轮次 1 和新轮次 3
+        edited.raw_content = "kubernetes rollout plan".to_string();
+        turns.create(&edited).await.unwrap();
+        let added = turns
+            .create(&Turn::new("session_1", 3, "added later"))
+            .await
+            .unwrap();
+
+        let report = service.reindex_session("session_1").await.unwrap();
+        assert_eq!(
+            report,
+            ReindexReport {
+                deleted: 2,
+                reindexed: 2,
+                failed: 0,
+            }
+        );
+
+        let mut indexed: Vec<String> = service
+            .list_indices("session_1", 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.turn_id)
+            .collect();
+        indexed.sort();
+        let mut expected = vec![edited.id.clone(), added.id.clone()];
+        expected.sort();
+        assert_eq!(indexed, expected);
+
+        // 修改前的内容不再能被搜到
+        let options = SearchOptions {
+            limit: 10,
+            use_full_text: true,
+            ..Default::default()
+        };
+        let search = |query| service.search_indices("session_1", query, options.clone());
+        assert!(search("legacy").await.unwrap().is_empty());
+        let found = search("kubernetes").await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].turn_id, edited.id);
+    }
+
+    #[tokio::test]
+    async fn test_reindex_session_requires_turn_repository() {
+        let result = service().reindex_session("session_1").await;
+        assert!(matches!(result, Err(AppError::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_search_global_requires_session_repository() {
+        let options = SearchOptions {
+            use_full_text: true,
+            ..Default::default()
+        };
+        let result = service().search_global("tenant_1", "hello", options).await;
+        assert!(matches!(result, Err(AppError::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_search_sessions_spans_sessions() {
+        let service = service();
+        for (session_id, content) in [
+            ("session_1", "deploy the rust service"),
+            ("session_2", "rust borrow checker notes"),
+            ("session_3", "rust tips from another tenant"),
+        ] {
+            let turn = Turn::new(session_id, 1, content);
+            service.index_turn(&turn).await.unwrap();
+        }
+
+        let mut sessions = vec![
+            Session::new("tenant_1", "Deploys"),
+            Session::new("tenant_1", "Notes"),
+        ];
+        sessions[0].id = "session_1".to_string();
+        sessions[1].id = "session_2".to_string();
+
+        let options = SearchOptions {
+            limit: 10,
+            use_full_text: true,
+            ..Default::default()
+        };
+        let results = service
+            .search_sessions(&sessions, "rust", &options)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.session_id != "session_3"));
+        let notes = results
+            .iter()
+            .find(|r| r.session_id == "session_2")
+            .unwrap();
+        assert_eq!(notes.session_name.as_deref(), Some("Notes"));
+    }
+
+    #[tokio::test]
+    async fn test_search_sessions_fused_ranks_by_session() {
+        let service = service();
+        for (session_id, number, content) in [
+            ("session_1", 1, "rust rust rust deploy"),
+            ("session_1", 2, "rust once"),
+            ("session_2", 1, "rust notes"),
+            ("session_3", 1, "rust from another tenant"),
+        ] {
+            let turn = Turn::new(session_id, number, content);
+            service.index_turn(&turn).await.unwrap();
+        }
+
+        let mut sessions = vec![
+            Session::new("tenant_1", "Deploys"),
+            Session::new("tenant_1", "Notes"),
+        ];
+        sessions[0].id = "session_1".to_string();
+        sessions[1].id = "session_2".to_string();
+
+        let options = SearchOptions {
+            limit: 2,
+            use_full_text: true,
+            ..Default::default()
+        };
+        let results = service
+            .search_sessions_fused(&sessions, "rust", &options)
+            .await
+            .unwrap();
+
+        // 两个会话的首条结果排名相同，各占一席
+        assert_eq!(results.len(), 2);
+        let mut session_ids: Vec<&str> = results.iter().map(|r| r.session_id.as_str()).collect();
+        session_ids.sort();
+        assert_eq!(session_ids, vec!["session_1", "session_2"]);
+        assert!(results.iter().all(|r| (r.score - 1.0 / 60.0).abs() < 1e-6));
+        let notes = results
+            .iter()
+            .find(|r| r.session_id == "session_2")
+            .unwrap();
+        assert_eq!(notes.session_name.as_deref(), Some("Notes"));
+    }
+
+    #[tokio::test]
+    async fn test_cross_session_search_requires_session_repository() {
+        let options = SearchOptions {
+            use_full_text: true,
+            ..Default::default()
+        };
+        let result = service()
+            .cross_session_search("tenant_1", "hello", options)
+            .await;
+        assert!(matches!(result, Err(AppError::Config(_))));
+    }
+}
@@ -3,7 +3,8 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 use crate::error::Result;
 use surrealdb::{Surreal, engine::any::Any};
@@ -28,20 +29,320 @@ pub struct VectorSearchResult {
 #[async_trait]
 pub trait VectorIndex: Send + Sync {
     async fn add(&self, id: &str, vector: &[f32], metadata: VectorMetadata) -> Result<()>;
+    /// 按余弦相似度检索会话内的向量，`min_score` 为 Some 时过滤掉相似度更低的结果
     async fn search(
         &self,
         query: &[f32],
         session_id: &str,
         limit: usize,
+        min_score: Option<f32>,
     ) -> Result<Vec<VectorSearchResult>>;
     async fn delete(&self, id: &str) -> Result<bool>;
     async fn count(&self, session_id: &str) -> Result<u64>;
     async fn exists(&self, id: &str) -> Result<bool>;
+    /// 按轮次号顺序列出会话的全部向量（不计算相似度，score 为 0）
+    async fn list_all(
+        &self,
+        session_id: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<VectorSearchResult>>;
+}
+
+/// HNSW 近似最近邻索引参数
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HnswConfig {
+    /// 每个节点在上层保留的最大邻居数（第 0 层为 `2 * m`）
+    pub m: usize,
+    /// 插入时搜索邻居的候选队列大小
+    pub ef_construction: usize,
+    /// 检索时的候选队列大小（实际取 `max(ef_search, limit)`）
+    pub ef_search: usize,
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 64,
+        }
+    }
+}
+
+/// 图中的候选节点，按相似度排序
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    similarity: f32,
+    node: usize,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.similarity
+            .total_cmp(&other.similarity)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+struct HnswNode {
+    id: String,
+    vector: Vec<f32>,
+    /// 每层的邻居节点，长度为节点层级 + 1
+    neighbors: Vec<Vec<usize>>,
+    deleted: bool,
+}
+
+/// 单个会话的 HNSW 分层图
+///
+/// 删除的节点保留为墓碑以维持图的连通性：检索时照常经过但不计入结果，
+/// 墓碑超过节点总数一半时用存活节点重建整张图。
+struct HnswGraph {
+    config: HnswConfig,
+    nodes: Vec<HnswNode>,
+    node_ids: HashMap<String, usize>,
+    entry_point: Option<usize>,
+    /// 墓碑节点数量
+    deleted: usize,
+    /// 层级分布参数 `1 / ln(m)`
+    level_multiplier: f64,
+    /// xorshift 随机数状态，用于分配节点层级
+    rng_state: u64,
+}
+
+impl HnswGraph {
+    fn new(config: HnswConfig) -> Self {
+        Self {
+            config,
+            nodes: Vec::new(),
+            node_ids: HashMap::new(),
+            entry_point: None,
+            deleted: 0,
+            level_multiplier: 1.0 / (config.m.max(2) as f64).ln(),
+            rng_state: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    fn max_connections(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.config.m * 2
+        } else {
+            self.config.m
+        }
+    }
+
+    /// 按指数分布随机分配新节点的层级
+    fn random_level(&mut self) -> usize {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng_state = x;
+
+        let uniform = ((x >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        (-uniform.ln() * self.level_multiplier).floor() as usize
+    }
+
+    fn candidate(&self, query: &[f32], node: usize) -> Candidate {
+        Candidate {
+            similarity: MemoryVectorIndex::cosine_similarity(query, &self.nodes[node].vector),
+            node,
+        }
+    }
+
+    fn top_level(&self, node: usize) -> usize {
+        self.nodes[node].neighbors.len() - 1
+    }
+
+    fn insert(&mut self, id: &str, vector: &[f32]) {
+        self.remove(id);
+
+        let level = self.random_level();
+        let node = self.nodes.len();
+        self.nodes.push(HnswNode {
+            id: id.to_string(),
+            vector: vector.to_vec(),
+            neighbors: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.node_ids.insert(id.to_string(), node);
+
+        let Some(entry) = self.entry_point else {
+            self.entry_point = Some(node);
+            return;
+        };
+
+        // 1. 在新节点层级之上贪心下降，找到最近的入口
+        let top_level = self.top_level(entry);
+        let mut entry_points = vec![self.candidate(vector, entry)];
+        for layer in (level + 1..=top_level).rev() {
+            entry_points = self.search_layer(vector, &entry_points, 1, layer, true);
+        }
+
+        // 2. 在新节点所在的每一层连接最近的 m 个邻居，并裁剪邻居的连接数
+        for layer in (0..=level.min(top_level)).rev() {
+            let candidates = self.search_layer(
+                vector,
+                &entry_points,
+                self.config.ef_construction,
+                layer,
+                true,
+            );
+            let neighbors: Vec<usize> = candidates
+                .iter()
+                .take(self.config.m)
+                .map(|c| c.node)
+                .collect();
+            for &neighbor in &neighbors {
+                self.nodes[neighbor].neighbors[layer].push(node);
+                self.prune(neighbor, layer);
+            }
+            self.nodes[node].neighbors[layer] = neighbors;
+            entry_points = candidates;
+        }
+
+        if level > top_level {
+            self.entry_point = Some(node);
+        }
+    }
+
+    /// 连接数超过上限时只保留最相似的邻居
+    fn prune(&mut self, node: usize, layer: usize) {
+        let max_connections = self.max_connections(layer);
+        if self.nodes[node].neighbors[layer].len() <= max_connections {
+            return;
+        }
+
+        let vector = &self.nodes[node].vector;
+        let mut scored: Vec<Candidate> = self.nodes[node].neighbors[layer]
+            .iter()
+            .map(|&neighbor| self.candidate(vector, neighbor))
+            .collect();
+        scored.sort_by(|a, b| b.cmp(a));
+        scored.truncate(max_connections);
+        self.nodes[node].neighbors[layer] = scored.into_iter().map(|c| c.node).collect();
+    }
+
+    fn remove(&mut self, id: &str) -> bool {
+        let Some(node) = self.node_ids.remove(id) else {
+            return false;
+        };
+        self.nodes[node].deleted = true;
+        self.deleted += 1;
+
+        if self.deleted * 2 > self.nodes.len() {
+            self.compact();
+        }
+        true
+    }
+
+    /// 丢弃墓碑，按原插入顺序用存活节点重建图
+    fn compact(&mut self) {
+        let nodes = std::mem::take(&mut self.nodes);
+        let rng_state = self.rng_state;
+        *self = Self::new(self.config);
+        self.rng_state = rng_state;
+
+        for node in nodes.into_iter().filter(|node| !node.deleted) {
+            self.insert(&node.id, &node.vector);
+        }
+    }
+
+    /// 在单层内做 beam search，返回最多 `ef` 个按相似度降序排列的节点
+    ///
+    /// `include_deleted` 为 false 时墓碑节点仍用于遍历，但不进入结果。
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[Candidate],
+        ef: usize,
+        layer: usize,
+        include_deleted: bool,
+    ) -> Vec<Candidate> {
+        let is_result = |c: &Candidate| include_deleted || !self.nodes[c.node].deleted;
+        let mut visited: HashSet<usize> = entry_points.iter().map(|c| c.node).collect();
+        let mut candidates: BinaryHeap<Candidate> = entry_points.iter().copied().collect();
+        let mut results: BinaryHeap<Reverse<Candidate>> = entry_points
+            .iter()
+            .copied()
+            .filter(|c| is_result(c))
+            .map(Reverse)
+            .collect();
+        while results.len() > ef {
+            results.pop();
+        }
+
+        while let Some(candidate) = candidates.pop() {
+            if let Some(Reverse(worst)) = results.peek()
+                && results.len() >= ef
+                && candidate.similarity < worst.similarity
+            {
+                break;
+            }
+
+            for &neighbor in &self.nodes[candidate.node].neighbors[layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let next = self.candidate(query, neighbor);
+                if results.len() < ef
+                    || results
+                        .peek()
+                        .is_some_and(|Reverse(worst)| next.similarity > worst.similarity)
+                {
+                    candidates.push(next);
+                    if !is_result(&next) {
+                        continue;
+                    }
+                    results.push(Reverse(next));
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<Candidate> = results.into_iter().map(|Reverse(c)| c).collect();
+        results.sort_by(|a, b| b.cmp(a));
+        results
+    }
+
+    /// 检索最相似的 `limit` 个未删除节点，返回 (ID, 相似度)
+    fn search(&self, query: &[f32], limit: usize) -> Vec<(String, f32)> {
+        let Some(entry) = self.entry_point else {
+            return Vec::new();
+        };
+
+        let mut entry_points = vec![self.candidate(query, entry)];
+        for layer in (1..=self.top_level(entry)).rev() {
+            entry_points = self.search_layer(query, &entry_points, 1, layer, true);
+        }
+
+        let ef = self.config.ef_search.max(limit);
+        self.search_layer(query, &entry_points, ef, 0, false)
+            .into_iter()
+            .take(limit)
+            .map(|c| (self.nodes[c.node].id.clone(), c.similarity))
+            .collect()
+    }
 }
 
 pub struct MemoryVectorIndex {
     vectors: dashmap::DashMap<String, (Vec<f32>, VectorMetadata)>,
     dimension: usize,
+    /// HNSW 参数（None 时线性扫描）
+    hnsw_config: Option<HnswConfig>,
+    /// 按会话划分的 HNSW 图
+    graphs: dashmap::DashMap<String, HnswGraph>,
 }
 
 impl MemoryVectorIndex {
@@ -49,6 +350,16 @@ impl MemoryVectorIndex {
         Self {
             vectors: dashmap::DashMap::new(),
             dimension,
+            hnsw_config: None,
+            graphs: dashmap::DashMap::new(),
+        }
+    }
+
+    /// 创建使用 HNSW 图做近似最近邻检索的索引
+    pub fn with_hnsw(dimension: usize, config: HnswConfig) -> Self {
+        Self {
+            hnsw_config: Some(config),
+            ..Self::new(dimension)
         }
     }
 
@@ -65,27 +376,15 @@ impl MemoryVectorIndex {
 
         dot_product / (norm_a * norm_b)
     }
-}
-
-#[async_trait]
-impl VectorIndex for MemoryVectorIndex {
-    async fn add(&self, id: &str, vector: &[f32], metadata: VectorMetadata) -> Result<()> {
-        assert_eq!(vector.len(), self.dimension);
-
-        self.vectors
-            .insert(id.to_string(), (vector.to_vec(), metadata));
-
-        Ok(())
-    }
 
-    async fn search(
+    /// 遍历会话内全部向量计算相似度
+    fn search_linear(
         &self,
         query: &[f32],
         session_id: &str,
         limit: usize,
-    ) -> Result<Vec<VectorSearchResult>> {
-        assert_eq!(query.len(), self.dimension);
-
+        min_score: Option<f32>,
+    ) -> Vec<VectorSearchResult> {
         let mut results: Vec<_> = self
             .vectors
             .iter()
@@ -100,16 +399,96 @@ impl VectorIndex for MemoryVectorIndex {
                     metadata: meta.clone(),
                 }
             })
+            .filter(|result| min_score.is_none_or(|min| result.score >= min))
             .collect();
 
         results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
         results.truncate(limit);
+        results
+    }
+
+    /// 遍历会话的 HNSW 图检索近似最近邻
+    fn search_hnsw(
+        &self,
+        query: &[f32],
+        session_id: &str,
+        limit: usize,
+        min_score: Option<f32>,
+    ) -> Vec<VectorSearchResult> {
+        let neighbors = match self.graphs.get(session_id) {
+            Some(graph) => graph.search(query, limit),
+            None => return Vec::new(),
+        };
+
+        neighbors
+            .into_iter()
+            .filter(|(_, score)| min_score.is_none_or(|min| *score >= min))
+            .filter_map(|(id, score)| {
+                let entry = self.vectors.get(&id)?;
+                let meta = &entry.1;
+                Some(VectorSearchResult {
+                    turn_id: meta.turn_id.clone(),
+                    metadata: meta.clone(),
+                    id,
+                    score,
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl VectorIndex for MemoryVectorIndex {
+    async fn add(&self, id: &str, vector: &[f32], metadata: VectorMetadata) -> Result<()> {
+        assert_eq!(vector.len(), self.dimension);
+
+        let session_id = metadata.session_id.clone();
+        let previous = self
+            .vectors
+            .insert(id.to_string(), (vector.to_vec(), metadata));
+
+        if let Some(config) = self.hnsw_config {
+            if let Some((_, old)) = previous
+                && old.session_id != session_id
+                && let Some(mut graph) = self.graphs.get_mut(&old.session_id)
+            {
+                graph.remove(id);
+            }
+            self.graphs
+                .entry(session_id)
+                .or_insert_with(|| HnswGraph::new(config))
+                .insert(id, vector);
+        }
+
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        query: &[f32],
+        session_id: &str,
+        limit: usize,
+        min_score: Option<f32>,
+    ) -> Result<Vec<VectorSearchResult>> {
+        assert_eq!(query.len(), self.dimension);
+
+        let results = if self.hnsw_config.is_some() {
+            self.search_hnsw(query, session_id, limit, min_score)
+        } else {
+            self.search_linear(query, session_id, limit, min_score)
+        };
 
         Ok(results)
     }
 
     async fn delete(&self, id: &str) -> Result<bool> {
-        Ok(self.vectors.remove(id).is_some())
+        let Some((_, (_, meta))) = self.vectors.remove(id) else {
+            return Ok(false);
+        };
+        if let Some(mut graph) = self.graphs.get_mut(&meta.session_id) {
+            graph.remove(id);
+        }
+        Ok(true)
     }
 
     async fn count(&self, session_id: &str) -> Result<u64> {
@@ -124,10 +503,66 @@ impl VectorIndex for MemoryVectorIndex {
     async fn exists(&self, id: &str) -> Result<bool> {
         Ok(self.vectors.contains_key(id))
     }
+
+    async fn list_all(
+        &self,
+        session_id: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<VectorSearchResult>> {
+        let mut results: Vec<_> = self
+            .vectors
+            .iter()
+            .filter(|ref_multi| ref_multi.value().1.session_id == session_id)
+            .map(|ref_multi| {
+                let (id, (_, meta)) = ref_multi.pair();
+                VectorSearchResult {
+                    id: id.clone(),
+                    score: 0.0,
+                    turn_id: meta.turn_id.clone(),
+                    metadata: meta.clone(),
+                }
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            a.metadata
+                .turn_number
+                .cmp(&b.metadata.turn_number)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+
+        Ok(results.into_iter().skip(offset).take(limit).collect())
+    }
+}
+
+/// 创建向量索引，`hnsw` 为 Some 时使用 HNSW 近似检索，否则线性扫描
+pub fn create_vector_index(
+    _db: Option<&Surreal<Any>>,
+    hnsw: Option<HnswConfig>,
+) -> Box<dyn VectorIndex> {
+    match hnsw {
+        Some(config) => Box::new(MemoryVectorIndex::with_hnsw(384, config)),
+        None => Box::new(MemoryVectorIndex::new(384)),
+    }
 }
 
-pub fn create_vector_index(_db: Option<&Surreal<Any>>, _use_hnsw: bool) -> Box<dyn VectorIndex> {
-    Box::new(MemoryVectorIndex::new(384))
+/// 生成确定性的伪随机向量（xorshift），供测试与基准共用，保证多次运行结果可比
+#[doc(hidden)]
+pub fn pseudo_random_vectors(count: usize, dimension: usize, seed: u64) -> Vec<Vec<f32>> {
+    let mut state = seed;
+    (0..count)
+        .map(|_| {
+            (0..dimension)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    (state % 2000) as f32 / 1000.0 - 1.0
+                })
+                .collect()
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -149,11 +584,50 @@ mod tests {
         let vector = vec![0.1; 384];
         index.add("vec_1", &vector, metadata).await.unwrap();
 
-        let results = index.search(&vector, "session_1", 10).await.unwrap();
+        let results = index.search(&vector, "session_1", 10, None).await.unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].turn_id, "turn_1");
     }
 
+    #[tokio::test]
+    async fn test_memory_vector_index_search_min_score() {
+        let index = MemoryVectorIndex::new(3);
+        let query = [1.0, 0.0, 0.0];
+
+        // 与查询向量的余弦相似度依次为 1.0、0.95、0.85、0.6、0.0
+        let vectors = [
+            [1.0, 0.0, 0.0],
+            [0.95, 0.312_25, 0.0],
+            [0.85, 0.526_78, 0.0],
+            [0.6, 0.8, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        for (i, vector) in vectors.iter().enumerate() {
+            let metadata = VectorMetadata {
+                session_id: "session_1".to_string(),
+                turn_id: format!("turn_{}", i),
+                turn_number: i as u64,
+                timestamp: Utc::now(),
+                extra: HashMap::new(),
+            };
+            index
+                .add(&format!("vec_{}", i), vector, metadata)
+                .await
+                .unwrap();
+        }
+
+        let results = index
+            .search(&query, "session_1", 10, Some(0.8))
+            .await
+            .unwrap();
+        let turn_ids: Vec<_> = results.iter().map(|r| r.turn_id.as_str()).collect();
+        assert_eq!(turn_ids, vec!["turn_0", "turn_1", "turn_2"]);
+        assert!(results.iter().all(|r| r.score >= 0.8));
+
+        let all = index.search(&query, "session_1", 10, None).await.unwrap();
+        assert_eq!(all.len(), 5);
+    }
+
     #[tokio::test]
     async fn test_memory_vector_index_delete() {
         let index = MemoryVectorIndex::new(384);
@@ -176,6 +650,190 @@ mod tests {
         assert_eq!(count, 0);
     }
 
+    #[tokio::test]
+    async fn test_memory_vector_index_list_all() {
+        let index = MemoryVectorIndex::new(384);
+
+        for (turn_number, session_id) in [(2, "session_1"), (1, "session_1"), (1, "session_2")] {
+            let metadata = VectorMetadata {
+                session_id: session_id.to_string(),
+                turn_id: format!("turn_{}", turn_number),
+                turn_number,
+                timestamp: Utc::now(),
+                extra: HashMap::new(),
+            };
+            let id = format!("vec_{}_{}", session_id, turn_number);
+            index.add(&id, &vec![0.1; 384], metadata).await.unwrap();
+        }
+
+        let results = index.list_all("session_1", 10, 0).await.unwrap();
+        let turn_ids: Vec<_> = results.iter().map(|r| r.turn_id.as_str()).collect();
+        assert_eq!(turn_ids, vec!["turn_1", "turn_2"]);
+
+        let page = index.list_all("session_1", 1, 1).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].turn_id, "turn_2");
+    }
+
+    fn metadata(session_id: &str, turn_number: u64) -> VectorMetadata {
+        VectorMetadata {
+            session_id: session_id.to_string(),
+            turn_id: format!("turn_{}", turn_number),
+            turn_number,
+            timestamp: Utc::now(),
+            extra: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hnsw_search_recall_against_linear_scan() {
+        let exact = MemoryVectorIndex::new(32);
+        let approximate = MemoryVectorIndex::with_hnsw(32, HnswConfig::default());
+        for (i, vector) in pseudo_random_vectors(1000, 32, 42).iter().enumerate() {
+            let id = format!("vec_{}", i);
+            exact
+                .add(&id, vector, metadata("session_1", i as u64))
+                .await
+                .unwrap();
+            approximate
+                .add(&id, vector, metadata("session_1", i as u64))
+                .await
+                .unwrap();
+        }
+
+        let mut hits = 0;
+        let queries = pseudo_random_vectors(20, 32, 7);
+        for query in &queries {
+            let expected = exact.search(query, "session_1", 10, None).await.unwrap();
+            let actual = approximate
+                .search(query, "session_1", 10, None)
+                .await
+                .unwrap();
+            assert_eq!(actual.len(), 10);
+            assert!(actual.windows(2).all(|w| w[0].score >= w[1].score));
+            hits += actual
+                .iter()
+                .filter(|r| expected.iter().any(|e| e.id == r.id))
+                .count();
+        }
+
+        let recall = hits as f32 / (queries.len() * 10) as f32;
+        assert!(recall >= 0.9, "recall {} below 0.9", recall);
+    }
+
+    #[tokio::test]
+    async fn test_hnsw_delete_and_move_between_sessions() {
+        let index = MemoryVectorIndex::with_hnsw(3, HnswConfig::default());
+        let vectors = [[1.0, 0.0, 0.0], [0.9, 0.1, 0.0], [0.0, 1.0, 0.0]];
+        for (i, vector) in vectors.iter().enumerate() {
+            index
+                .add(
+                    &format!("vec_{}", i),
+                    vector,
+                    metadata("session_1", i as u64),
+                )
+                .await
+                .unwrap();
+        }
+
+        assert!(index.delete("vec_0").await.unwrap());
+        let results = index
+            .search(&vectors[0], "session_1", 10, None)
+            .await
+            .unwrap();
+        let ids: Vec<_> = results.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["vec_1", "vec_2"]);
+
+        index
+            .add("vec_1", &vectors[1], metadata("session_2", 1))
+            .await
+            .unwrap();
+        let results = index
+            .search(&vectors[0], "session_1", 10, None)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "vec_2");
+        let results = index
+            .search(&vectors[0], "session_2", 10, Some(0.5))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "vec_1");
+        assert!(
+            index
+                .search(&vectors[0], "session_3", 10, None)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hnsw_search_skips_tombstones_without_losing_results() {
+        let config = HnswConfig {
+            ef_search: 10,
+            ..HnswConfig::default()
+        };
+        let index = MemoryVectorIndex::with_hnsw(16, config);
+        let vectors = pseudo_random_vectors(200, 16, 42);
+        for (i, vector) in vectors.iter().enumerate() {
+            index
+                .add(
+                    &format!("vec_{}", i),
+                    vector,
+                    metadata("session_1", i as u64),
+                )
+                .await
+                .unwrap();
+        }
+
+        // 删除与查询最相似的一批节点（少于一半，不触发重建）
+        let query = &vectors[0];
+        let nearest = index.search(query, "session_1", 60, None).await.unwrap();
+        for result in &nearest {
+            index.delete(&result.id).await.unwrap();
+        }
+        assert_eq!(index.graphs.get("session_1").unwrap().nodes.len(), 200);
+
+        let results = index.search(query, "session_1", 10, None).await.unwrap();
+        assert_eq!(results.len(), 10);
+        assert!(results.iter().all(|r| nearest.iter().all(|n| n.id != r.id)));
+    }
+
+    #[tokio::test]
+    async fn test_hnsw_compacts_tombstones() {
+        let index = MemoryVectorIndex::with_hnsw(16, HnswConfig::default());
+        let vectors = pseudo_random_vectors(100, 16, 42);
+        for (i, vector) in vectors.iter().enumerate() {
+            index
+                .add(
+                    &format!("vec_{}", i),
+                    vector,
+                    metadata("session_1", i as u64),
+                )
+                .await
+                .unwrap();
+        }
+
+        for i in 0..51 {
+            index.delete(&format!("vec_{}", i)).await.unwrap();
+        }
+        {
+            let graph = index.graphs.get("session_1").unwrap();
+            assert_eq!(graph.nodes.len(), 49);
+            assert_eq!(graph.deleted, 0);
+            assert!(graph.nodes.iter().all(|node| !node.deleted));
+        }
+
+        let results = index
+            .search(&vectors[99], "session_1", 5, None)
+            .await
+            .unwrap();
+        assert_eq!(results[0].id, "vec_99");
+        assert!(results.iter().all(|r| r.id != "vec_0"));
+    }
+
     #[test]
     fn test_cosine_similarity() {
         let a = vec![1.0, 0.0, 0.0];
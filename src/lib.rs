@@ -13,5 +13,6 @@ pub mod models;
 pub mod observability;
 pub mod security;
 pub mod services;
+pub mod startup;
 pub mod storage;
 pub mod websocket;
@@ -1,24 +1,38 @@
 use hippos::api::{self, app_state::AppState};
+use hippos::config::config::{AppConfig, ServerConfig};
 use hippos::config::loader::ConfigLoader;
-use hippos::index::{create_embedding_model, create_unified_index_service};
+use hippos::index::{EmbeddingModel, IndexService, UnifiedIndexService, create_embedding_model};
 use hippos::mcp::sse_server;
+use hippos::models::api_key_repository::ApiKeyRepositoryImpl;
 use hippos::models::entity_repository::EntityRepositoryImpl;
-use hippos::models::memory_repository::MemoryRepositoryImpl;
+use hippos::models::memory_repository::{MemoryRepository, MemoryRepositoryImpl};
 use hippos::models::pattern_repository::PatternRepositoryImpl;
 use hippos::models::profile_repository::ProfileRepositoryImpl;
-use hippos::observability::{ObservabilityState, create_observability_router};
-use hippos::services::{
-    create_dehydration_service, create_retrieval_service, create_session_service,
-    create_turn_service,
+use hippos::observability::{
+    ACTIVE_SESSION_WINDOW_SECS, AppMetrics, EventBus, ObservabilityState,
+    create_observability_router, with_request_metrics,
 };
-use hippos::storage::repository::{SessionRepository, TurnRepository};
+use hippos::security::auth::CombinedAuthenticator;
+use hippos::security::rate_limit::RateLimiter;
+use hippos::security::rbac::SimpleAuthorizer;
+use hippos::security::{ReloadableSecuritySettings, SecuritySettings};
+use hippos::services::LazyService;
+use hippos::services::create_retrieval_service;
+use hippos::services::dehydration::SimpleDehydrationService;
+use hippos::services::session::{SessionService, SessionServiceImpl};
+use hippos::services::turn::TurnServiceImpl;
+use hippos::startup::bind_listener;
+use hippos::storage::repository::{SessionRepository, SessionStore, TurnRepository, TurnStore};
+use hippos::storage::stats::StorageStatsService;
 use hippos::storage::surrealdb::SurrealPool;
+use hippos::websocket;
 use std::sync::Arc;
-use tracing::info;
+use std::time::Duration;
+use tracing::{info, warn};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt::init();
+    let _otel_guard = hippos::observability::init_tracing("hippos");
 
     // Check if we should run in MCP mode
     if std::env::var("HIPPOS_MCP_MODE").is_ok() {
@@ -49,81 +63,208 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Database connection pool initialized");
 
     let session_repository_raw = SessionRepository::new(db_pool.clone());
-    let turn_repository_raw = TurnRepository::new(db_pool.clone().inner().await, db_pool.clone());
-    let memory_repository_raw = hippos::models::memory_repository::MemoryRepositoryImpl::new(db_pool.clone());
+    let turn_repository_raw = TurnRepository::new(db_pool.clone());
+    let memory_repository_raw = MemoryRepositoryImpl::new(db_pool.clone());
     let pattern_repository_raw = PatternRepositoryImpl::new(db_pool.clone());
+    if let Err(e) = pattern_repository_raw.ensure_search_index().await {
+        warn!(
+            "Pattern search index unavailable, using keyword matching: {}",
+            e
+        );
+    }
     let entity_repository_raw = EntityRepositoryImpl::new(db_pool.clone());
     let profile_repository_raw = ProfileRepositoryImpl::new(db_pool.clone());
-    let session_repository = Arc::new(session_repository_raw);
-    let turn_repository = Arc::new(turn_repository_raw);
-    let memory_repository = Arc::new(memory_repository_raw);
+    let session_repository: Arc<dyn SessionStore> = Arc::new(session_repository_raw);
+    let turn_repository: Arc<dyn TurnStore> = Arc::new(turn_repository_raw);
+    let memory_repository: Arc<dyn MemoryRepository + Send + Sync> =
+        Arc::new(memory_repository_raw);
     let pattern_repository = Arc::new(pattern_repository_raw);
     let entity_repository = Arc::new(entity_repository_raw);
     let profile_repository = Arc::new(profile_repository_raw);
     info!("Repositories initialized");
 
-    let embedding_model_for_index =
-        create_embedding_model(&config.embedding, config.vector.dimension).await?;
-    info!(
-        "Embedding model initialized: {} (backend: {})",
-        config.embedding.model_name, config.embedding.backend
-    );
-
-    let embedding_model_for_retrieval =
-        create_embedding_model(&config.embedding, config.vector.dimension).await?;
-
-    let index_service = create_unified_index_service(
-        hippos::index::create_vector_index(None, false),
-        hippos::index::create_full_text_index(None, false),
-        embedding_model_for_index,
+    // 可观测性状态需在服务之前创建，以便服务记录业务指标
+    let observability_state = Arc::new(ObservabilityState::new("0.1.0".to_string()));
+    restore_metrics(&observability_state, &config);
+    let event_bus = EventBus::default();
+
+    let embedding_model = lazy_embedding_model(&config);
+    let index_service = lazy_index_service(
+        &config,
+        embedding_model.clone(),
+        turn_repository.clone(),
+        session_repository.clone(),
     );
-    info!("Index service initialized");
-
-    let retrieval_service =
-        create_retrieval_service(embedding_model_for_retrieval, turn_repository.clone());
-    info!("Retrieval service initialized");
-
-    let dehydration_service = create_dehydration_service(100, 5, 10);
-    info!("Dehydration service initialized");
-
     let session_service =
-        create_session_service(session_repository.clone(), turn_repository.clone());
+        SessionServiceImpl::new(session_repository.clone(), turn_repository.clone())
+            .with_memory_repository(memory_repository.clone())
+            .with_index_service(index_service.clone())
+            .with_metrics(observability_state.metrics.clone())
+            .with_event_bus(event_bus.clone());
     info!("Session service initialized");
 
-    let turn_service = create_turn_service(turn_repository.clone(), session_repository.clone());
+    let turn_service = TurnServiceImpl::new(turn_repository.clone(), session_repository.clone())
+        .with_index_service(index_service.clone())
+        .with_metrics(observability_state.metrics.clone())
+        .with_event_bus(event_bus.clone());
     info!("Turn service initialized");
 
+    let (security_settings, authenticator, authorizer, rate_limiter) =
+        security_components(&db_pool, &config)?;
     let app_state = AppState::new(
         db_pool.clone(),
-        (*session_repository).clone(),
-        (*turn_repository).clone(),
-        (*memory_repository).clone(),
+        session_repository.clone(),
+        turn_repository.clone(),
+        memory_repository.clone(),
         (*pattern_repository).clone(),
         (*entity_repository).clone(),
         (*profile_repository).clone(),
-        session_service as Box<dyn hippos::services::session::SessionService>,
-        turn_service as Box<dyn hippos::services::turn::TurnService>,
-        retrieval_service as Box<dyn hippos::services::retrieval::RetrievalService>,
-        dehydration_service as Box<dyn hippos::services::dehydration::DehydrationService>,
-        index_service as Box<dyn hippos::index::IndexService>,
-        Box::new(hippos::security::auth::CombinedAuthenticator::development()),
-        Box::new(hippos::security::rbac::SimpleAuthorizer::development()),
-        hippos::security::rate_limit::RateLimiter::development(),
+        Box::new(session_service) as Box<dyn hippos::services::session::SessionService>,
+        Box::new(turn_service) as Box<dyn hippos::services::turn::TurnService>,
+        Box::new(authenticator),
+        Box::new(authorizer),
+        rate_limiter,
+    )
+    .with_event_bus(event_bus)
+    .with_public_stats_endpoint(security_settings.enable_public_stats_endpoint)
+    .with_max_page_size(config.api.max_page_size)
+    .with_pattern_cache_capacity(config.patterns.cache_capacity)
+    .with_pattern_quality_thresholds(config.patterns.quality_thresholds.clone())
+    .with_metrics(observability_state.metrics.clone());
+    let app_state = with_lazy_services(
+        app_state,
+        &config,
+        index_service,
+        embedding_model,
+        turn_repository.clone(),
     );
+    validate_embedding_model(&app_state).await?;
     info!("Application state created");
 
-    // 创建可观测性状态并集成路由
-    let observability_state = Arc::new(ObservabilityState::new("0.1.0".to_string()));
+    spawn_active_session_refresh(
+        app_state.session_service.clone(),
+        observability_state.metrics.clone(),
+    );
+    spawn_storage_stats_refresh(
+        app_state.storage_stats_service(),
+        observability_state.metrics.clone(),
+    );
+    spawn_database_health_check(db_pool.clone(), observability_state.clone());
+
+    // 集成可观测性路由
     let api_router = api::create_router(app_state);
-    let router = create_observability_router(observability_state).merge(api_router);
+    let router = create_observability_router(observability_state.clone()).merge(api_router);
+    let router = with_request_metrics(router, observability_state.clone());
     info!("API router created with observability endpoints");
 
-    let addr = format!("{}:{}", config.server.host, config.server.port);
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    info!("Server listening on {}", addr);
+    let listener = bind_listener(&config.server).await?;
+    listener.serve(router, shutdown_signal()).await?;
+
+    persist_metrics(&observability_state, &config);
+
+    Ok(())
+}
+
+/// Builds the embedding model slot shared by `AppState` and the index / retrieval services
+///
+/// The model is checked against `vector.dimension` when it is loaded, so every
+/// service that takes it from this slot gets a validated model.
+fn lazy_embedding_model(config: &AppConfig) -> LazyService<dyn EmbeddingModel> {
+    let config = config.clone();
+    LazyService::new("embedding model", move || {
+        let config = config.clone();
+        async move {
+            let embedding_model =
+                create_embedding_model(&config.embedding, config.vector.dimension).await?;
+            config
+                .embedding
+                .validate_dimension(embedding_model.as_ref(), config.vector.dimension)
+                .await?;
+            info!(
+                "Embedding model initialized: {} (backend: {})",
+                config.embedding.model_name, config.embedding.backend
+            );
+            Ok(embedding_model)
+        }
+    })
+}
 
-    axum::serve(listener, router).await?;
+/// Builds the index service slot shared by `AppState` and the session / turn services
+///
+/// The services only touch the index once it has been created, so sharing the
+/// slot keeps their cascading deletes in sync without loading the embedding model.
+fn lazy_index_service(
+    config: &AppConfig,
+    embedding_model: LazyService<dyn EmbeddingModel>,
+    turn_repository: Arc<dyn TurnStore>,
+    session_repository: Arc<dyn SessionStore>,
+) -> LazyService<dyn IndexService> {
+    let config = config.clone();
+    LazyService::new("index service", move || {
+        let config = config.clone();
+        let embedding_model = embedding_model.clone();
+        let turn_repository = turn_repository.clone();
+        let session_repository = session_repository.clone();
+        async move {
+            let index_service = UnifiedIndexService::new(
+                hippos::index::create_vector_index(None, config.vector.hnsw_config()),
+                hippos::index::create_full_text_index(None, false),
+                Box::new(embedding_model.get_shared().await?),
+            )
+            .with_turn_repository(turn_repository)
+            .with_session_repository(session_repository);
+            Ok(Box::new(index_service) as Box<dyn IndexService>)
+        }
+    })
+}
+
+/// Registers the embedding-backed services so they are created on first use
+///
+/// Building the index and retrieval services is expensive, so deployments that
+/// never hit the search / reindex endpoints (e.g. MCP mode) never pay for it.
+/// Both take their model from the shared embedding model slot, which
+/// `validate_embedding_model` loads at startup to check its dimension.
+fn with_lazy_services(
+    app_state: AppState,
+    config: &AppConfig,
+    index_service: LazyService<dyn IndexService>,
+    embedding_model: LazyService<dyn EmbeddingModel>,
+    turn_repository: Arc<dyn TurnStore>,
+) -> AppState {
+    let retrieval_config = config.clone();
+    let retrieval_turns = turn_repository.clone();
+    let retrieval_model = embedding_model.clone();
+
+    app_state
+        .with_index_service(index_service)
+        .with_embedding_model(embedding_model)
+        .with_retrieval_service(move || {
+            let config = retrieval_config.clone();
+            let turn_repository = retrieval_turns.clone();
+            let embedding_model = retrieval_model.clone();
+            async move {
+                Ok(create_retrieval_service(
+                    Box::new(embedding_model.get_shared().await?),
+                    turn_repository,
+                    config.vector.hnsw_config(),
+                ))
+            }
+        })
+        .with_dehydration_service(move || {
+            let turn_repository = turn_repository.clone();
+            async move {
+                let dehydration_service =
+                    SimpleDehydrationService::new(100, 5, 10).with_turn_repository(turn_repository);
+                Ok(Box::new(dehydration_service) as Box<dyn hippos::services::DehydrationService>)
+            }
+        })
+}
 
+/// Loads the shared embedding model, failing startup when its output dimension
+/// does not match `vector.dimension`
+async fn validate_embedding_model(app_state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
+    app_state.embedding_model.get_shared().await?;
+    info!("Embedding model dimension validated");
     Ok(())
 }
 
@@ -139,97 +280,300 @@ async fn run_combined_server(port: u16) -> Result<(), Box<dyn std::error::Error>
     info!("Database connection pool initialized");
 
     let session_repository_raw = SessionRepository::new(db_pool.clone());
-    let turn_repository_raw = TurnRepository::new(db_pool.clone().inner().await, db_pool.clone());
-    let memory_repository_raw = hippos::models::memory_repository::MemoryRepositoryImpl::new(db_pool.clone());
+    let turn_repository_raw = TurnRepository::new(db_pool.clone());
+    let memory_repository_raw = MemoryRepositoryImpl::new(db_pool.clone());
     let pattern_repository_raw = PatternRepositoryImpl::new(db_pool.clone());
+    if let Err(e) = pattern_repository_raw.ensure_search_index().await {
+        warn!(
+            "Pattern search index unavailable, using keyword matching: {}",
+            e
+        );
+    }
     let entity_repository_raw = EntityRepositoryImpl::new(db_pool.clone());
     let profile_repository_raw = ProfileRepositoryImpl::new(db_pool.clone());
-    let session_repository = Arc::new(session_repository_raw);
-    let turn_repository = Arc::new(turn_repository_raw);
-    let memory_repository = Arc::new(memory_repository_raw);
+    let session_repository: Arc<dyn SessionStore> = Arc::new(session_repository_raw);
+    let turn_repository: Arc<dyn TurnStore> = Arc::new(turn_repository_raw);
+    let memory_repository: Arc<dyn MemoryRepository + Send + Sync> =
+        Arc::new(memory_repository_raw);
     let pattern_repository = Arc::new(pattern_repository_raw);
     let entity_repository = Arc::new(entity_repository_raw);
     let profile_repository = Arc::new(profile_repository_raw);
     info!("Repositories initialized");
 
-    let embedding_model_for_index =
-        create_embedding_model(&config.embedding, config.vector.dimension).await?;
-    info!(
-        "Embedding model initialized: {} (backend: {})",
-        config.embedding.model_name, config.embedding.backend
-    );
-
-    let embedding_model_for_retrieval =
-        create_embedding_model(&config.embedding, config.vector.dimension).await?;
-
-    let index_service = create_unified_index_service(
-        hippos::index::create_vector_index(None, false),
-        hippos::index::create_full_text_index(None, false),
-        embedding_model_for_index,
+    // 可观测性状态需在服务之前创建，以便服务记录业务指标
+    let observability_state = Arc::new(ObservabilityState::new("0.1.0".to_string()));
+    restore_metrics(&observability_state, &config);
+    let event_bus = EventBus::default();
+
+    let embedding_model = lazy_embedding_model(&config);
+    let index_service = lazy_index_service(
+        &config,
+        embedding_model.clone(),
+        turn_repository.clone(),
+        session_repository.clone(),
     );
-    info!("Index service initialized");
-
-    let retrieval_service =
-        create_retrieval_service(embedding_model_for_retrieval, turn_repository.clone());
-    info!("Retrieval service initialized");
-
-    let dehydration_service = create_dehydration_service(100, 5, 10);
-    info!("Dehydration service initialized");
-
     let session_service =
-        create_session_service(session_repository.clone(), turn_repository.clone());
+        SessionServiceImpl::new(session_repository.clone(), turn_repository.clone())
+            .with_memory_repository(memory_repository.clone())
+            .with_index_service(index_service.clone())
+            .with_metrics(observability_state.metrics.clone())
+            .with_event_bus(event_bus.clone());
     info!("Session service initialized");
 
-    let turn_service = create_turn_service(turn_repository.clone(), session_repository.clone());
+    let turn_service = TurnServiceImpl::new(turn_repository.clone(), session_repository.clone())
+        .with_index_service(index_service.clone())
+        .with_metrics(observability_state.metrics.clone())
+        .with_event_bus(event_bus.clone());
     info!("Turn service initialized");
 
     // Create AppState with SSE ConnectionManager
-    let mut app_state = AppState::new(
+    let (security_settings, authenticator, authorizer, rate_limiter) =
+        security_components(&db_pool, &config)?;
+    let app_state = AppState::new(
         db_pool.clone(),
-        (*session_repository).clone(),
-        (*turn_repository).clone(),
-        (*memory_repository).clone(),
+        session_repository.clone(),
+        turn_repository.clone(),
+        memory_repository.clone(),
         (*pattern_repository).clone(),
         (*entity_repository).clone(),
         (*profile_repository).clone(),
-        session_service as Box<dyn hippos::services::session::SessionService>,
-        turn_service as Box<dyn hippos::services::turn::TurnService>,
-        retrieval_service as Box<dyn hippos::services::retrieval::RetrievalService>,
-        dehydration_service as Box<dyn hippos::services::dehydration::DehydrationService>,
-        index_service as Box<dyn hippos::index::IndexService>,
-        Box::new(hippos::security::auth::CombinedAuthenticator::development()),
-        Box::new(hippos::security::rbac::SimpleAuthorizer::development()),
-        hippos::security::rate_limit::RateLimiter::development(),
+        Box::new(session_service) as Box<dyn hippos::services::session::SessionService>,
+        Box::new(turn_service) as Box<dyn hippos::services::turn::TurnService>,
+        Box::new(authenticator),
+        Box::new(authorizer),
+        rate_limiter,
+    )
+    .with_event_bus(event_bus)
+    .with_sse_connection_manager(1000)
+    .with_public_stats_endpoint(security_settings.enable_public_stats_endpoint)
+    .with_max_page_size(config.api.max_page_size)
+    .with_pattern_cache_capacity(config.patterns.cache_capacity)
+    .with_pattern_quality_thresholds(config.patterns.quality_thresholds.clone())
+    .with_metrics(observability_state.metrics.clone());
+    let app_state = with_lazy_services(
+        app_state,
+        &config,
+        index_service,
+        embedding_model,
+        turn_repository.clone(),
     );
-
-    // Initialize SSE ConnectionManager
-    app_state.init_sse_connection_manager(1000);
+    validate_embedding_model(&app_state).await?;
     info!("SSE ConnectionManager initialized");
 
     let app_state = Arc::new(app_state);
     info!("Application state created with SSE support");
 
-    // 创建可观测性状态并集成路由
-    let observability_state = Arc::new(ObservabilityState::new("0.1.0".to_string()));
+    spawn_active_session_refresh(
+        app_state.session_service.clone(),
+        observability_state.metrics.clone(),
+    );
+    spawn_storage_stats_refresh(
+        app_state.storage_stats_service(),
+        observability_state.metrics.clone(),
+    );
+    spawn_database_health_check(db_pool.clone(), observability_state.clone());
 
     // Create SSE router
     let sse_router = sse_server::create_sse_router(app_state.clone());
 
+    // Create WebSocket router
+    let ws_router = websocket::create_websocket_router(app_state.clone(), config.websocket.clone());
+
     // Create main API router
     let api_router = api::create_router((*app_state).clone());
 
     // Merge all routers
-    let router = create_observability_router(observability_state)
+    let router = create_observability_router(observability_state.clone())
         .merge(api_router)
-        .merge(sse_router);
+        .merge(sse_router)
+        .merge(ws_router);
+    let router = with_request_metrics(router, observability_state.clone());
 
-    info!("Combined router created with REST API + SSE MCP endpoints");
+    info!("Combined router created with REST API + SSE MCP + WebSocket endpoints");
 
-    let addr = format!("0.0.0.0:{}", port);
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    info!("Combined server listening on {}", addr);
+    let server_config = ServerConfig {
+        host: "0.0.0.0".to_string(),
+        port,
+        ..config.server.clone()
+    };
+    let listener = bind_listener(&server_config).await?;
+    info!("Combined server bound on port {}", port);
+    listener.serve(router, shutdown_signal()).await?;
 
-    axum::serve(listener, router).await?;
+    persist_metrics(&observability_state, &config);
 
     Ok(())
 }
+
+/// Security settings with the authenticator, authorizer and rate limiter that enforce them
+///
+/// When `HIPPOS_SECURITY_CONFIG` names a TOML settings file, the authenticator
+/// and rate limiter follow that file and pick up edits without a restart.
+/// Otherwise the development settings are used. The authorizer is built once
+/// from the settings' RBAC policy. API keys created at runtime are stored in
+/// the database.
+fn security_components(
+    db_pool: &SurrealPool,
+    config: &AppConfig,
+) -> Result<
+    (
+        SecuritySettings,
+        CombinedAuthenticator,
+        SimpleAuthorizer,
+        RateLimiter,
+    ),
+    Box<dyn std::error::Error>,
+> {
+    let api_key_repository = Arc::new(ApiKeyRepositoryImpl::new(db_pool.clone()));
+    let Ok(path) = std::env::var("HIPPOS_SECURITY_CONFIG") else {
+        let settings = if config.environment == "production" {
+            SecuritySettings::production()
+        } else {
+            SecuritySettings::development()
+        };
+        let authorizer = SimpleAuthorizer::from_config(&settings)?;
+        let rate_limiter = RateLimiter::from_security_settings(&settings);
+        return Ok((
+            settings,
+            CombinedAuthenticator::development().with_api_key_repository(api_key_repository),
+            authorizer,
+            rate_limiter,
+        ));
+    };
+
+    let settings = ReloadableSecuritySettings::load(path)?;
+    if let Err(e) = settings.watch() {
+        warn!("Security settings hot-reload disabled: {}", e);
+    }
+    info!(
+        "Security settings loaded from {}",
+        settings.path().display()
+    );
+
+    let current = (*settings.current()).clone();
+    let authorizer = SimpleAuthorizer::from_config(&current)?;
+    Ok((
+        current,
+        CombinedAuthenticator::from_reloadable(settings.clone())
+            .with_api_key_repository(api_key_repository),
+        authorizer,
+        RateLimiter::from_reloadable(settings),
+    ))
+}
+
+/// Restore metrics from the snapshot written on the previous shutdown
+fn restore_metrics(state: &ObservabilityState, config: &AppConfig) {
+    if let Some(dir) = &config.observability.metrics_snapshot_path {
+        match state.load_metrics_snapshot(dir) {
+            Ok(true) => info!("Metrics restored from snapshot in {}", dir.display()),
+            Ok(false) => {}
+            Err(e) => warn!("Failed to restore metrics snapshot: {}", e),
+        }
+    }
+}
+
+/// Persist metrics so counters continue across restarts
+fn persist_metrics(state: &ObservabilityState, config: &AppConfig) {
+    if let Some(dir) = &config.observability.metrics_snapshot_path {
+        match state.save_metrics_snapshot(dir) {
+            Ok(()) => info!("Metrics snapshot written to {}", dir.display()),
+            Err(e) => warn!("Failed to write metrics snapshot: {}", e),
+        }
+    }
+}
+
+/// Refresh `sessions_active` and the per-tenant `active_sessions_recent` gauge every 60 seconds
+fn spawn_active_session_refresh(
+    session_service: Arc<dyn SessionService>,
+    metrics: Arc<AppMetrics>,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+
+            let tenant_ids = match session_service.list_tenant_ids().await {
+                Ok(ids) => ids,
+                Err(e) => {
+                    warn!("Failed to list tenants for active session metrics: {}", e);
+                    continue;
+                }
+            };
+
+            let mut counts = Vec::with_capacity(tenant_ids.len());
+            for tenant_id in tenant_ids {
+                match session_service
+                    .count_active_sessions(&tenant_id, ACTIVE_SESSION_WINDOW_SECS)
+                    .await
+                {
+                    Ok(count) => counts.push((tenant_id, count)),
+                    Err(e) => warn!("Failed to count active sessions for {}: {}", tenant_id, e),
+                }
+            }
+            metrics.set_active_sessions_recent(counts);
+        }
+    });
+}
+
+/// Refresh the `table_record_count` gauge every five minutes
+fn spawn_storage_stats_refresh(stats_service: StorageStatsService, metrics: Arc<AppMetrics>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(300));
+        loop {
+            interval.tick().await;
+
+            match stats_service.gather().await {
+                Ok(stats) => metrics.set_table_record_counts(
+                    stats
+                        .tables
+                        .into_iter()
+                        .map(|t| (t.table_name, t.record_count)),
+                ),
+                Err(e) => warn!("Failed to gather storage stats: {}", e),
+            }
+        }
+    });
+}
+
+/// Ping the database every 10 seconds so `/health/ready` reflects its connectivity
+fn spawn_database_health_check(db_pool: SurrealPool, state: Arc<ObservabilityState>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(10));
+        loop {
+            interval.tick().await;
+
+            let result = db_pool.health_check().await;
+            if !result.healthy {
+                warn!("Database health check failed: {}", result.message);
+            }
+            state.add_health_check(result).await;
+        }
+    });
+}
+
+/// Wait for Ctrl+C or SIGTERM to start graceful shutdown
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    info!("Shutdown signal received, stopping server...");
+}
@@ -1,7 +1,7 @@
 //! MCP Server Module
 //!
 //! Provides a simplified MCP (Model Context Protocol) server that exposes
-//! search capabilities for the Hippos context management service.
+//! search and user profile capabilities for the Hippos context management service.
 //!
 //! Supports stdio transport for local MCP clients and SSE transport
 //! for remote MCP clients over HTTP.
@@ -11,6 +11,7 @@ pub mod sse_server;
 
 use crate::config::config::DatabaseConfig;
 use crate::index::create_embedding_model;
+use crate::models::profile_repository::ProfileRepositoryImpl;
 use crate::services::retrieval::create_retrieval_service;
 use crate::storage::repository::TurnRepository;
 use crate::storage::surrealdb::SurrealPool;
@@ -45,19 +46,20 @@ pub async fn run_mcp_server() -> Result<(), Box<dyn std::error::Error>> {
     info!("Initializing MCP server...");
 
     let db_pool = SurrealPool::new(DatabaseConfig::default()).await?;
-    let turn_repository = Arc::new(TurnRepository::new(db_pool.inner().await, db_pool.clone()));
+    let turn_repository = Arc::new(TurnRepository::new(db_pool.clone()));
     let embedding_config = crate::config::config::EmbeddingConfig {
         model_name: "all-MiniLM-L6-v2".into(),
         backend: "simple".into(),
         ..Default::default()
     };
     let embedding_model = create_embedding_model(&embedding_config, 384).await?;
-    let retrieval_service = create_retrieval_service(embedding_model, turn_repository);
+    let retrieval_service = create_retrieval_service(embedding_model, turn_repository, None);
     let retrieval_service_arc = Arc::from(retrieval_service);
+    let profile_repository = Arc::new(ProfileRepositoryImpl::new(db_pool.clone()));
 
     info!("MCP server starting with stdio transport...");
 
-    let mcp_server = HipposMcpServer::new(retrieval_service_arc)
+    let mcp_server = HipposMcpServer::new(retrieval_service_arc, profile_repository)
         .serve(stdio())
         .await
         .inspect_err(|e| {
@@ -1,8 +1,11 @@
 //! MCP Server Implementation
 //!
-//! Provides the HipposMcpServer with hippos_search and hippos_semantic_search tools.
+//! Provides the HipposMcpServer with hippos_search and hippos_semantic_search tools,
+//! plus hippos_get_profile and hippos_update_profile for user profiles.
 
 use crate::error::AppError;
+use crate::models::profile::Profile;
+use crate::models::profile_repository::ProfileRepository;
 use crate::services::RetrievalService;
 use rmcp::{
     ServerHandler,
@@ -16,6 +19,7 @@ use rmcp::{
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, error, info};
 
@@ -43,14 +47,19 @@ pub struct McpSearchResponse {
 #[derive(Clone)]
 pub struct HipposMcpServer {
     retrieval_service: Arc<dyn RetrievalService>,
+    profile_repository: Arc<dyn ProfileRepository + Send + Sync>,
     tool_router: rmcp::handler::server::tool::ToolRouter<Self>,
 }
 
 impl HipposMcpServer {
     /// Create new HipposMcpServer instance
-    pub fn new(retrieval_service: Arc<dyn RetrievalService>) -> Self {
+    pub fn new(
+        retrieval_service: Arc<dyn RetrievalService>,
+        profile_repository: Arc<dyn ProfileRepository + Send + Sync>,
+    ) -> Self {
         Self {
             retrieval_service,
+            profile_repository,
             tool_router: Self::tool_router(),
         }
     }
@@ -126,16 +135,17 @@ impl HipposMcpServer {
         session_id: String,
         query: String,
         limit: u32,
+        min_score: Option<f32>,
     ) -> Result<McpSearchResponse, AppError> {
         let start = std::time::Instant::now();
         debug!(
-            "Executing semantic search for session: {}, query: {}, limit: {}",
-            session_id, query, limit
+            "Executing semantic search for session: {}, query: {}, limit: {}, min_score: {:?}",
+            session_id, query, limit, min_score
         );
 
         let results = self
             .retrieval_service
-            .semantic_search(&session_id, &query, limit)
+            .semantic_search(&session_id, &query, limit, min_score)
             .await?;
 
         let took_ms = start.elapsed().as_millis() as u64;
@@ -150,6 +160,61 @@ impl HipposMcpServer {
     }
 }
 
+impl HipposMcpServer {
+    /// Load the profile of a user
+    async fn execute_get_profile(&self, user_id: &str) -> Result<Profile, AppError> {
+        debug!("Getting profile for user: {}", user_id);
+
+        self.profile_repository
+            .get_by_user_id(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Profile not found for user: {}", user_id)))
+    }
+
+    /// Update the profile of a user, creating it when it does not exist yet
+    async fn execute_update_profile(
+        &self,
+        params: HipposUpdateProfileParams,
+    ) -> Result<Profile, AppError> {
+        debug!("Updating profile for user: {}", params.user_id);
+
+        match self
+            .profile_repository
+            .get_by_user_id(&params.user_id)
+            .await?
+        {
+            Some(mut profile) => {
+                apply_profile_update(&mut profile, params.name, params.preferences);
+                self.profile_repository
+                    .update(&profile.id, &profile)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound(format!("Profile not found: {}", profile.id)))
+            }
+            None => {
+                let mut profile = Profile::new(&params.user_id);
+                apply_profile_update(&mut profile, params.name, params.preferences);
+                self.profile_repository.create(&profile).await
+            }
+        }
+    }
+}
+
+/// Apply the fields of an update request, recording each change in the profile history
+fn apply_profile_update(
+    profile: &mut Profile,
+    name: Option<String>,
+    preferences: Option<HashMap<String, serde_json::Value>>,
+) {
+    const REASON: Option<&str> = Some("Updated via MCP");
+
+    if let Some(name) = name {
+        profile.update_basic_info(Some(&name), None, None, REASON);
+    }
+    for (key, value) in preferences.unwrap_or_default() {
+        profile.add_preference(&key, value, REASON);
+    }
+}
+
 /// Tool parameters for hippos_search
 #[derive(Deserialize, JsonSchema)]
 pub struct HipposSearchParams {
@@ -164,6 +229,23 @@ pub struct HipposSemanticSearchParams {
     pub session_id: String,
     pub query: String,
     pub limit: Option<u32>,
+    /// Minimum cosine similarity (0.0-1.0) a result must reach
+    pub min_score: Option<f32>,
+}
+
+/// Tool parameters for hippos_get_profile
+#[derive(Deserialize, JsonSchema)]
+pub struct HipposGetProfileParams {
+    pub user_id: String,
+}
+
+/// Tool parameters for hippos_update_profile
+#[derive(Deserialize, JsonSchema)]
+pub struct HipposUpdateProfileParams {
+    pub user_id: String,
+    pub name: Option<String>,
+    /// Preference key-value pairs to set; existing keys are overwritten
+    pub preferences: Option<HashMap<String, serde_json::Value>>,
 }
 
 impl From<AppError> for ErrorData {
@@ -186,7 +268,7 @@ impl HipposMcpServer {
         let info = json!({
             "name": "hippos-search",
             "version": env!("CARGO_PKG_VERSION"),
-            "description": "Hippos Context Management Service - Search Only MCP Server"
+            "description": "Hippos Context Management Service - Search and Profile MCP Server"
         });
 
         let content = Content::json(info).map_err(|e| {
@@ -267,6 +349,7 @@ impl HipposMcpServer {
                 hippos_search_params.session_id,
                 hippos_search_params.query,
                 limit,
+                hippos_search_params.min_score,
             )
             .await
         {
@@ -282,6 +365,58 @@ impl HipposMcpServer {
             }
         }
     }
+
+    /// Get the profile of a user
+    #[tool(description = "Get the profile of a user")]
+    async fn hippos_get_profile(
+        &self,
+        params: Parameters<HipposGetProfileParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let user_id = params.0.user_id;
+
+        if user_id.trim().is_empty() {
+            return Err(ErrorData::invalid_params("user_id cannot be empty", None));
+        }
+
+        match self.execute_get_profile(&user_id).await {
+            Ok(profile) => {
+                let content = Content::json(json!(profile)).map_err(|e| {
+                    ErrorData::internal_error(format!("Failed to create JSON content: {}", e), None)
+                })?;
+                Ok(CallToolResult::success(vec![content]))
+            }
+            Err(e) => {
+                error!("Hippos get profile error: {}", e);
+                Err(ErrorData::from(e))
+            }
+        }
+    }
+
+    /// Update the name and preferences of a user profile
+    #[tool(description = "Update the name and preferences of a user profile")]
+    async fn hippos_update_profile(
+        &self,
+        params: Parameters<HipposUpdateProfileParams>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let update_params = params.0;
+
+        if update_params.user_id.trim().is_empty() {
+            return Err(ErrorData::invalid_params("user_id cannot be empty", None));
+        }
+
+        match self.execute_update_profile(update_params).await {
+            Ok(profile) => {
+                let content = Content::json(json!(profile)).map_err(|e| {
+                    ErrorData::internal_error(format!("Failed to create JSON content: {}", e), None)
+                })?;
+                Ok(CallToolResult::success(vec![content]))
+            }
+            Err(e) => {
+                error!("Hippos update profile error: {}", e);
+                Err(ErrorData::from(e))
+            }
+        }
+    }
 }
 
 #[tool_handler]
@@ -296,8 +431,25 @@ impl ServerHandler for HipposMcpServer {
                 ..Default::default()
             },
             instructions: Some(
-                "Hippos Context Management Service - Search Only MCP Server".to_string(),
+                "Hippos Context Management Service - Search and Profile MCP Server".to_string(),
             ),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_profile_update_records_changes() {
+        let mut profile = Profile::new("user_1");
+        let preferences = HashMap::from([("theme".to_string(), json!("dark"))]);
+
+        apply_profile_update(&mut profile, Some("Alice".to_string()), Some(preferences));
+
+        assert_eq!(profile.name.as_deref(), Some("Alice"));
+        assert_eq!(profile.preferences.get("theme"), Some(&json!("dark")));
+        assert_eq!(profile.version, 3);
+    }
+}
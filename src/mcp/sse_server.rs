@@ -3,35 +3,72 @@
 //! Custom Server-Sent Events (SSE) transport implementation for MCP protocol.
 //! Supports both standalone mode and merged with regular REST API.
 
-use crate::api::app_state::AppState;
-use crate::config::config::DatabaseConfig;
+use crate::api::app_state::{AppState, LazyService};
+use crate::config::config::{DatabaseConfig, PatternQualityThresholds};
+use crate::config::loader::default_config_path;
+use crate::error::AppError;
 use crate::index::create_embedding_model;
-use crate::models::turn::TurnMetadata;
+use crate::models::memory_repository::{MemoryRepository, MemoryRepositoryImpl};
+use crate::models::pattern_repository::PatternRepositoryImpl;
+use crate::models::turn::{Turn, TurnMetadata};
+use crate::observability::EventBus;
+use crate::observability::event_bus::is_visible_to;
+use crate::security::auth::Claims;
+use crate::security::middleware::authenticate_headers;
+use crate::services::pattern_manager::{DiscoveryMethod, PatternCache, PatternManager};
 use crate::services::retrieval::{RetrievalService, create_retrieval_service};
 use crate::services::session::SessionService;
 use crate::services::turn::TurnService;
 use crate::storage::repository::TurnRepository;
 use crate::storage::surrealdb::SurrealPool;
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::State,
+    http::HeaderMap,
     response::sse::{Event, KeepAlive, Sse},
     routing::{get, post},
 };
+use figment::{
+    Figment,
+    providers::{Format, Toml},
+};
 use futures_util::stream::{self, StreamExt};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::sync::broadcast;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
+/// Names of all MCP tools, in the order they are listed
+pub const MCP_TOOL_NAMES: [&str; 11] = [
+    "hippos_create_session",
+    "hippos_get_session",
+    "hippos_list_sessions",
+    "hippos_delete_session",
+    "hippos_add_turn",
+    "hippos_list_turns",
+    "hippos_get_turn",
+    "hippos_search",
+    "hippos_semantic_search",
+    "hippos_get_hot_memories",
+    "hippos_discover_patterns",
+];
+
 /// MCP Tool Configuration - Controls which tools are exposed
-#[derive(Debug, Clone)]
+///
+/// Read from the `[mcp.tools]` section of the config file; missing keys
+/// default to enabled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct McpToolConfig {
     // Session Management Tools
     pub enable_create_session: bool,
@@ -45,6 +82,10 @@ pub struct McpToolConfig {
     // Search Tools
     pub enable_search: bool,
     pub enable_semantic_search: bool,
+    // Memory Tools
+    pub enable_get_hot_memories: bool,
+    // Pattern Tools
+    pub enable_discover_patterns: bool,
 }
 
 impl Default for McpToolConfig {
@@ -60,10 +101,52 @@ impl Default for McpToolConfig {
             enable_get_turn: true,
             enable_search: true,
             enable_semantic_search: true,
+            enable_get_hot_memories: true,
+            enable_discover_patterns: true,
+        }
+    }
+}
+
+impl McpToolConfig {
+    /// Check whether the named tool is enabled (unknown tools are disabled)
+    pub fn is_enabled(&self, tool_name: &str) -> bool {
+        match tool_name {
+            "hippos_create_session" => self.enable_create_session,
+            "hippos_get_session" => self.enable_get_session,
+            "hippos_list_sessions" => self.enable_list_sessions,
+            "hippos_delete_session" => self.enable_delete_session,
+            "hippos_add_turn" => self.enable_add_turn,
+            "hippos_list_turns" => self.enable_list_turns,
+            "hippos_get_turn" => self.enable_get_turn,
+            "hippos_search" => self.enable_search,
+            "hippos_semantic_search" => self.enable_semantic_search,
+            "hippos_get_hot_memories" => self.enable_get_hot_memories,
+            "hippos_discover_patterns" => self.enable_discover_patterns,
+            _ => false,
         }
     }
+
+    /// Number of enabled tools
+    pub fn enabled_count(&self) -> usize {
+        MCP_TOOL_NAMES
+            .iter()
+            .filter(|name| self.is_enabled(name))
+            .count()
+    }
+
+    /// Load the `[mcp.tools]` section of a config file
+    pub fn load_from(path: &Path) -> crate::error::Result<Self> {
+        Figment::new()
+            .merge(Toml::file(path))
+            .focus("mcp.tools")
+            .extract()
+            .map_err(|e| AppError::Config(format!("Invalid [mcp.tools] config: {}", e)))
+    }
 }
 
+/// Tool flags shared between request handlers and the config file watcher
+pub type SharedMcpToolConfig = Arc<parking_lot::RwLock<McpToolConfig>>;
+
 /// SSE Server Configuration
 #[derive(Debug, Clone)]
 pub struct SseServerConfig {
@@ -73,7 +156,7 @@ pub struct SseServerConfig {
     pub message_path: String,
     pub max_connections: usize,
     pub heartbeat_interval: u64,
-    pub tools: McpToolConfig,
+    pub tools: SharedMcpToolConfig,
 }
 
 impl Default for SseServerConfig {
@@ -85,11 +168,89 @@ impl Default for SseServerConfig {
             message_path: "/mcp/message".to_string(),
             max_connections: 1000,
             heartbeat_interval: 30,
-            tools: McpToolConfig::default(),
+            tools: Arc::new(parking_lot::RwLock::new(McpToolConfig::default())),
         }
     }
 }
 
+impl SseServerConfig {
+    /// Returns this config with tool flags loaded from `path`
+    ///
+    /// Spawns a watcher that reloads the flags whenever the file changes, so
+    /// tools can be disabled without a restart. Only `[mcp.tools]` is
+    /// hot-reloaded; every other setting still requires a restart.
+    pub fn with_tool_config_file(self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match McpToolConfig::load_from(&path) {
+            Ok(tools) => *self.tools.write() = tools,
+            Err(e) => warn!(
+                "Failed to load MCP tool config from {}: {}",
+                path.display(),
+                e
+            ),
+        }
+        if let Err(e) = spawn_tool_config_watcher(path, self.tools.clone()) {
+            warn!("MCP tool config hot-reload disabled: {}", e);
+        }
+        self
+    }
+}
+
+/// Watch the config file and replace the shared tool flags when it changes
+///
+/// The parent directory is watched so that editors which save by replacing
+/// the file are picked up too.
+fn spawn_tool_config_watcher(path: PathBuf, tools: SharedMcpToolConfig) -> notify::Result<()> {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = tx.send(event);
+    })?;
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    tokio::spawn(async move {
+        // Keep the watcher alive for as long as events are being consumed
+        let _watcher = watcher;
+        while let Some(event) = rx.recv().await {
+            match event {
+                Ok(event) if is_config_change(&event, &path) => reload_tool_config(&path, &tools),
+                Ok(_) => {}
+                Err(e) => warn!("MCP tool config watcher error: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Check whether a file system event modifies or recreates the config file
+fn is_config_change(event: &notify::Event, path: &Path) -> bool {
+    matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+        && event
+            .paths
+            .iter()
+            .any(|p| p.file_name() == path.file_name())
+}
+
+/// Re-read the tool flags, keeping the current ones if the file is invalid
+fn reload_tool_config(path: &Path, tools: &SharedMcpToolConfig) {
+    match McpToolConfig::load_from(path) {
+        Ok(config) => {
+            let enabled = config.enabled_count();
+            *tools.write() = config;
+            info!("MCP tool config reloaded: {} tools enabled", enabled);
+        }
+        Err(e) => warn!(
+            "Failed to reload MCP tool config from {}: {}",
+            path.display(),
+            e
+        ),
+    }
+}
+
 /// SSE Connection Manager
 #[derive(Clone)]
 pub struct ConnectionManager {
@@ -101,12 +262,16 @@ pub struct ConnectionManager {
 
 impl ConnectionManager {
     pub fn new(max_connections: usize) -> Self {
-        let (tx, _) = broadcast::channel(1024);
+        Self::with_event_bus(max_connections, &EventBus::default())
+    }
+
+    /// Creates a manager that broadcasts the domain events published on `event_bus`
+    pub fn with_event_bus(max_connections: usize, event_bus: &EventBus) -> Self {
         Self {
             connections: Arc::new(RwLock::new(HashMap::new())),
             count: Arc::new(AtomicUsize::new(0)),
             max_connections,
-            tx,
+            tx: event_bus.sender(),
         }
     }
 
@@ -153,9 +318,13 @@ impl ConnectionManager {
 pub struct SseServerState {
     pub config: SseServerConfig,
     pub connection_manager: Arc<ConnectionManager>,
-    pub retrieval_service: Arc<dyn RetrievalService>,
+    pub retrieval_service: LazyService<dyn RetrievalService>,
     pub session_service: Arc<dyn SessionService>,
     pub turn_service: Arc<dyn TurnService>,
+    pub memory_repository: Arc<dyn MemoryRepository + Send + Sync>,
+    pub pattern_repository: Arc<PatternRepositoryImpl>,
+    pub pattern_cache: PatternCache,
+    pub pattern_quality_thresholds: PatternQualityThresholds,
 }
 
 impl From<(&AppState, &SseServerConfig)> for SseServerState {
@@ -163,17 +332,37 @@ impl From<(&AppState, &SseServerConfig)> for SseServerState {
         Self {
             config: config.clone(),
             connection_manager: app_state.connection_manager.clone()
-                .expect("ConnectionManager not initialized. Call app_state.init_sse_connection_manager() first."),
+                .expect("ConnectionManager not initialized. Build the state with AppState::with_sse_connection_manager()."),
             retrieval_service: app_state.retrieval_service.clone(),
             session_service: app_state.session_service.clone(),
             turn_service: app_state.turn_service.clone(),
+            memory_repository: app_state.memory_repository.clone(),
+            pattern_repository: app_state.pattern_repository.clone(),
+            pattern_cache: app_state.pattern_cache.clone(),
+            pattern_quality_thresholds: app_state.pattern_quality_thresholds.clone(),
         }
     }
 }
 
+/// Broadcast messages that carry no tenant
+///
+/// MCP SSE clients are not authenticated, so tenant-scoped domain events are
+/// dropped and only connection events are forwarded.
+fn public_events(
+    rx: broadcast::Receiver<String>,
+) -> impl futures_util::stream::Stream<Item = Result<String, BroadcastStreamRecvError>> + Unpin {
+    BroadcastStream::new(rx).filter(|msg| {
+        let public = msg.as_ref().is_ok_and(|msg| {
+            serde_json::from_str::<Value>(msg).is_ok_and(|event| is_visible_to(&event, None))
+        });
+        std::future::ready(public)
+    })
+}
+
 /// SSE event stream handler - uses AppState
 async fn sse_handler_app_state(
     State(state): State<Arc<AppState>>,
+    Extension(config): Extension<SseServerConfig>,
 ) -> Sse<impl futures_util::stream::Stream<Item = Result<Event, std::convert::Infallible>>> {
     let connection_manager = state
         .connection_manager
@@ -186,9 +375,8 @@ async fn sse_handler_app_state(
         .unwrap_or_else(|_| "unknown".to_string());
 
     let rx = connection_manager.subscribe();
-    let broadcast_stream = BroadcastStream::new(rx);
+    let broadcast_stream = public_events(rx);
 
-    let config = SseServerConfig::default();
     let heartbeat_interval = tokio::time::interval(Duration::from_secs(config.heartbeat_interval));
     let heartbeat_stream = IntervalStream::new(heartbeat_interval);
 
@@ -244,7 +432,7 @@ async fn sse_handler(
         .unwrap_or_else(|_| "unknown".to_string());
 
     let rx = state.connection_manager.subscribe();
-    let broadcast_stream = BroadcastStream::new(rx);
+    let broadcast_stream = public_events(rx);
 
     let heartbeat_interval =
         tokio::time::interval(Duration::from_secs(state.config.heartbeat_interval));
@@ -294,10 +482,15 @@ async fn sse_handler(
 /// Message handler for MCP JSON-RPC requests (uses AppState)
 async fn message_handler_app_state(
     State(state): State<Arc<AppState>>,
+    Extension(config): Extension<SseServerConfig>,
+    headers: HeaderMap,
     Json(request): Json<Value>,
 ) -> (axum::http::StatusCode, Json<Value>) {
-    let config = SseServerConfig::default();
-    let response = process_mcp_request_with_app(&state, &config, request).await;
+    // 凭证可选：只有需要用户身份的工具才要求认证
+    let caller = authenticate_headers(&headers, state.authenticator.as_ref())
+        .await
+        .ok();
+    let response = process_mcp_request_with_app(&state, &config, request, caller.as_ref()).await;
     let status = if response.get("type") == Some(&json!("error")) {
         axum::http::StatusCode::INTERNAL_SERVER_ERROR
     } else {
@@ -323,7 +516,7 @@ async fn message_handler(
 /// Build the tools list based on configuration
 fn build_tools_list(config: &SseServerConfig) -> Vec<Value> {
     let mut tools = Vec::new();
-    let tc = &config.tools;
+    let tc = config.tools.read();
 
     // Session Management Tools
     if tc.enable_create_session {
@@ -401,6 +594,7 @@ fn build_tools_list(config: &SseServerConfig) -> Vec<Value> {
                 "type": "object",
                 "properties": {
                     "session_id": { "type": "string" },
+                    "turn_ids": { "type": "array", "items": { "type": "string" }, "description": "Only return these turns" },
                     "page": { "type": "integer", "default": 1 },
                     "page_size": { "type": "integer", "default": 50 }
                 },
@@ -445,31 +639,229 @@ fn build_tools_list(config: &SseServerConfig) -> Vec<Value> {
                 "properties": {
                     "session_id": { "type": "string" },
                     "query": { "type": "string" },
-                    "limit": { "type": "integer", "default": 10 }
+                    "limit": { "type": "integer", "default": 10 },
+                    "min_score": {
+                        "type": "number",
+                        "minimum": 0.0,
+                        "maximum": 1.0,
+                        "description": "Minimum similarity score a result must reach"
+                    }
                 },
                 "required": ["session_id", "query"]
             }
         }));
     }
 
+    // Memory Tools
+    if tc.enable_get_hot_memories {
+        tools.push(json!({
+            "name": "hippos_get_hot_memories",
+            "description": "Get the most frequently accessed memories of the authenticated user (requires an Authorization or X-API-Key header)",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "user_id": { "type": "string" },
+                    "limit": { "type": "integer", "default": 50 }
+                },
+                "required": ["user_id"]
+            }
+        }));
+    }
+
+    // Pattern Tools
+    if tc.enable_discover_patterns {
+        tools.push(json!({
+            "name": "hippos_discover_patterns",
+            "description": "Discover patterns from a user's recent memories and pattern usage",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "user_id": { "type": "string" },
+                    "method": {
+                        "type": "string",
+                        "enum": ["memory_analysis", "error_pattern", "usage_pattern"]
+                    },
+                    "limit": { "type": "integer", "default": 10 }
+                },
+                "required": ["user_id", "method"]
+            }
+        }));
+    }
+
     tools
 }
 
+/// Execute the hippos_list_turns tool
+///
+/// When `turn_ids` is given the turns are fetched in a single batch query
+/// instead of listing the whole session.
+async fn call_list_turns(turn_service: &dyn TurnService, id: Value, arguments: &Value) -> Value {
+    let session_id = arguments
+        .get("session_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    if session_id.is_empty() {
+        return json!({ "type": "error", "id": id, "error": { "code": -32602, "message": "Missing session_id" } });
+    }
+
+    let turn_json = |t: &Turn| {
+        json!({
+            "id": t.id, "session_id": t.session_id, "turn_number": t.turn_number,
+            "content": t.raw_content, "created_at": t.metadata.timestamp.to_rfc3339()
+        })
+    };
+
+    if let Some(turn_ids) = arguments.get("turn_ids").and_then(|v| v.as_array()) {
+        let ids: Vec<&str> = turn_ids.iter().filter_map(|v| v.as_str()).collect();
+        return match turn_service.get_by_ids(&ids).await {
+            Ok(turns) => {
+                let mut results = Vec::new();
+                let mut missing = Vec::new();
+                for (turn_id, turn) in ids.iter().zip(turns) {
+                    match turn {
+                        Some(t) if t.session_id == session_id => results.push(turn_json(&t)),
+                        _ => missing.push(*turn_id),
+                    }
+                }
+                json!({ "type": "result", "id": id, "result": {
+                    "turns": results, "total": results.len(), "missing": missing
+                }})
+            }
+            Err(e) => {
+                json!({ "type": "error", "id": id, "error": { "code": -32603, "message": format!("Failed to list turns: {}", e) } })
+            }
+        };
+    }
+
+    match turn_service
+        .list_by_session(&session_id, Default::default())
+        .await
+    {
+        Ok(turns) => {
+            let results: Vec<_> = turns.iter().map(turn_json).collect();
+            json!({ "type": "result", "id": id, "result": {
+                "turns": results, "total": results.len()
+            }})
+        }
+        Err(e) => {
+            json!({ "type": "error", "id": id, "error": { "code": -32603, "message": format!("Failed to list turns: {}", e) } })
+        }
+    }
+}
+
+/// Execute the hippos_get_hot_memories tool
+///
+/// Only the authenticated `caller` may read their own hot memories.
+async fn call_get_hot_memories(
+    memory_repository: &(dyn MemoryRepository + Send + Sync),
+    id: Value,
+    arguments: &Value,
+    caller: Option<&Claims>,
+) -> Value {
+    let user_id = arguments
+        .get("user_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let limit = arguments
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(50)
+        .clamp(1, 100) as usize;
+
+    if user_id.is_empty() {
+        return json!({ "type": "error", "id": id, "error": { "code": -32602, "message": "Missing user_id" } });
+    }
+
+    // 与 REST 接口一致：只能读取认证用户自己的记忆
+    let Some(caller) = caller else {
+        return json!({ "type": "error", "id": id, "error": { "code": -32001, "message": "Authentication required" } });
+    };
+    if caller.sub != user_id {
+        return json!({ "type": "error", "id": id, "error": { "code": -32003, "message": "Access denied to memories of another user" } });
+    }
+
+    match memory_repository.list_hot(&user_id, limit).await {
+        Ok(memories) => {
+            let results: Vec<_> = memories
+                .iter()
+                .map(|m| {
+                    json!({
+                        "id": m.id, "memory_type": m.memory_type.to_string(), "gist": m.gist,
+                        "content": m.content, "importance": m.importance, "access_count": m.access_count,
+                        "accessed_at": m.accessed_at.to_rfc3339()
+                    })
+                })
+                .collect();
+            json!({ "type": "result", "id": id, "result": {
+                "memories": results, "total": results.len()
+            }})
+        }
+        Err(e) => {
+            json!({ "type": "error", "id": id, "error": { "code": -32603, "message": format!("Failed to get hot memories: {}", e) } })
+        }
+    }
+}
+
+/// Execute the hippos_discover_patterns tool
+async fn call_discover_patterns(
+    pattern_manager: &PatternManager,
+    id: Value,
+    arguments: &Value,
+) -> Value {
+    let user_id = arguments
+        .get("user_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let limit = arguments
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(10)
+        .clamp(1, 100) as u32;
+
+    if user_id.is_empty() {
+        return json!({ "type": "error", "id": id, "error": { "code": -32602, "message": "Missing user_id" } });
+    }
+
+    let method = match arguments.get("method").and_then(|v| v.as_str()) {
+        Some("memory_analysis") => DiscoveryMethod::MemoryAnalysis,
+        Some("error_pattern") => DiscoveryMethod::ErrorPattern,
+        Some("usage_pattern") => DiscoveryMethod::UsagePattern,
+        other => {
+            return json!({ "type": "error", "id": id, "error": { "code": -32602, "message": format!("Invalid method: {}", other.unwrap_or("")) } });
+        }
+    };
+
+    match pattern_manager.discover_patterns(&user_id, method, limit).await {
+        Ok(result) => {
+            let suggestions: Vec<_> = result
+                .suggested_patterns
+                .iter()
+                .map(|s| {
+                    json!({
+                        "name": s.name, "problem": s.problem,
+                        "pattern_type": s.pattern_type.to_string(), "confidence": s.confidence
+                    })
+                })
+                .collect();
+            json!({ "type": "result", "id": id, "result": {
+                "patterns_found": result.patterns.len(),
+                "suggested_patterns": suggestions,
+                "memories_analyzed": result.memories_analyzed
+            }})
+        }
+        Err(e) => {
+            json!({ "type": "error", "id": id, "error": { "code": -32603, "message": format!("Failed to discover patterns: {}", e) } })
+        }
+    }
+}
+
 /// Check if a tool is enabled based on configuration
 fn is_tool_enabled(config: &SseServerConfig, tool_name: &str) -> bool {
-    let tc = &config.tools;
-    match tool_name {
-        "hippos_create_session" => tc.enable_create_session,
-        "hippos_get_session" => tc.enable_get_session,
-        "hippos_list_sessions" => tc.enable_list_sessions,
-        "hippos_delete_session" => tc.enable_delete_session,
-        "hippos_add_turn" => tc.enable_add_turn,
-        "hippos_list_turns" => tc.enable_list_turns,
-        "hippos_get_turn" => tc.enable_get_turn,
-        "hippos_search" => tc.enable_search,
-        "hippos_semantic_search" => tc.enable_semantic_search,
-        _ => false,
-    }
+    config.tools.read().is_enabled(tool_name)
 }
 
 /// Process an MCP JSON-RPC request (uses AppState)
@@ -477,6 +869,7 @@ async fn process_mcp_request_with_app(
     state: &AppState,
     config: &SseServerConfig,
     request: Value,
+    caller: Option<&Claims>,
 ) -> Value {
     let id = request.get("id").cloned().unwrap_or(json!(null));
     let method = request
@@ -667,36 +1060,7 @@ async fn process_mcp_request_with_app(
                     }
                 }
                 "hippos_list_turns" => {
-                    let session_id = arguments
-                        .get("session_id")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string();
-
-                    if session_id.is_empty() {
-                        return json!({ "type": "error", "id": id, "error": { "code": -32602, "message": "Missing session_id" } });
-                    }
-
-                    match state
-                        .turn_service
-                        .list_by_session(&session_id, Default::default())
-                        .await
-                    {
-                        Ok(turns) => {
-                            let results: Vec<_> = turns.iter().map(|t| {
-                                json!({
-                                    "id": t.id, "session_id": t.session_id, "turn_number": t.turn_number,
-                                    "content": t.raw_content, "created_at": t.metadata.timestamp.to_rfc3339()
-                                })
-                            }).collect();
-                            json!({ "type": "result", "id": id, "result": {
-                                "turns": results, "total": results.len()
-                            }})
-                        }
-                        Err(e) => {
-                            json!({ "type": "error", "id": id, "error": { "code": -32603, "message": format!("Failed to list turns: {}", e) } })
-                        }
-                    }
+                    call_list_turns(state.turn_service.as_ref(), id, &arguments).await
                 }
                 "hippos_get_turn" => {
                     let turn_id = arguments
@@ -740,22 +1104,24 @@ async fn process_mcp_request_with_app(
                         .get("limit")
                         .and_then(|v| v.as_u64())
                         .unwrap_or(10) as u32;
+                    let min_score = arguments
+                        .get("min_score")
+                        .and_then(|v| v.as_f64())
+                        .map(|v| v as f32);
 
                     if session_id.is_empty() || query.is_empty() {
                         return json!({ "type": "error", "id": id, "error": { "code": -32602, "message": "Invalid params" } });
                     }
 
                     let is_semantic = tool_name == "hippos_semantic_search";
-                    let search_result = if is_semantic {
-                        state
-                            .retrieval_service
-                            .semantic_search(&session_id, &query, limit)
-                            .await
-                    } else {
-                        state
-                            .retrieval_service
-                            .hybrid_search(&session_id, &query, limit)
-                            .await
+                    let search_result = match state.retrieval_service.get().await {
+                        Ok(retrieval) if is_semantic => {
+                            retrieval
+                                .semantic_search(&session_id, &query, limit, min_score)
+                                .await
+                        }
+                        Ok(retrieval) => retrieval.hybrid_search(&session_id, &query, limit).await,
+                        Err(e) => Err(e),
                     };
 
                     match search_result {
@@ -781,6 +1147,16 @@ async fn process_mcp_request_with_app(
                         }
                     }
                 }
+                // Memory Tools
+                "hippos_get_hot_memories" => {
+                    call_get_hot_memories(state.memory_repository.as_ref(), id, &arguments, caller)
+                        .await
+                }
+                // Pattern Tools
+                "hippos_discover_patterns" => {
+                    let pattern_manager = state.pattern_manager();
+                    call_discover_patterns(&pattern_manager, id, &arguments).await
+                }
                 _ => {
                     json!({ "type": "error", "id": id, "error": { "code": -32601, "message": format!("Unknown tool: {}", tool_name) } })
                 }
@@ -984,36 +1360,7 @@ async fn process_mcp_request(state: &SseServerState, request: Value) -> Value {
                     }
                 }
                 "hippos_list_turns" => {
-                    let session_id = arguments
-                        .get("session_id")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("")
-                        .to_string();
-
-                    if session_id.is_empty() {
-                        return json!({ "type": "error", "id": id, "error": { "code": -32602, "message": "Missing session_id" } });
-                    }
-
-                    match state
-                        .turn_service
-                        .list_by_session(&session_id, Default::default())
-                        .await
-                    {
-                        Ok(turns) => {
-                            let results: Vec<_> = turns.iter().map(|t| {
-                                json!({
-                                    "id": t.id, "session_id": t.session_id, "turn_number": t.turn_number,
-                                    "content": t.raw_content, "created_at": t.metadata.timestamp.to_rfc3339()
-                                })
-                            }).collect();
-                            json!({ "type": "result", "id": id, "result": {
-                                "turns": results, "total": results.len()
-                            }})
-                        }
-                        Err(e) => {
-                            json!({ "type": "error", "id": id, "error": { "code": -32603, "message": format!("Failed to list turns: {}", e) } })
-                        }
-                    }
+                    call_list_turns(state.turn_service.as_ref(), id, &arguments).await
                 }
                 "hippos_get_turn" => {
                     let turn_id = arguments
@@ -1057,22 +1404,24 @@ async fn process_mcp_request(state: &SseServerState, request: Value) -> Value {
                         .get("limit")
                         .and_then(|v| v.as_u64())
                         .unwrap_or(10) as u32;
+                    let min_score = arguments
+                        .get("min_score")
+                        .and_then(|v| v.as_f64())
+                        .map(|v| v as f32);
 
                     if session_id.is_empty() || query.is_empty() {
                         return json!({ "type": "error", "id": id, "error": { "code": -32602, "message": "Invalid params" } });
                     }
 
                     let is_semantic = tool_name == "hippos_semantic_search";
-                    let search_result = if is_semantic {
-                        state
-                            .retrieval_service
-                            .semantic_search(&session_id, &query, limit)
-                            .await
-                    } else {
-                        state
-                            .retrieval_service
-                            .hybrid_search(&session_id, &query, limit)
-                            .await
+                    let search_result = match state.retrieval_service.get().await {
+                        Ok(retrieval) if is_semantic => {
+                            retrieval
+                                .semantic_search(&session_id, &query, limit, min_score)
+                                .await
+                        }
+                        Ok(retrieval) => retrieval.hybrid_search(&session_id, &query, limit).await,
+                        Err(e) => Err(e),
                     };
 
                     match search_result {
@@ -1098,6 +1447,22 @@ async fn process_mcp_request(state: &SseServerState, request: Value) -> Value {
                         }
                     }
                 }
+                // Memory Tools
+                // 独立模式没有认证器，无法确认调用者身份
+                "hippos_get_hot_memories" => {
+                    call_get_hot_memories(state.memory_repository.as_ref(), id, &arguments, None)
+                        .await
+                }
+                // Pattern Tools
+                "hippos_discover_patterns" => {
+                    let pattern_manager = PatternManager::new_basic(
+                        state.pattern_repository.clone(),
+                        state.memory_repository.clone(),
+                    )
+                    .with_cache(state.pattern_cache.clone())
+                    .with_quality_thresholds(state.pattern_quality_thresholds.clone());
+                    call_discover_patterns(&pattern_manager, id, &arguments).await
+                }
                 _ => {
                     json!({ "type": "error", "id": id, "error": { "code": -32601, "message": format!("Unknown tool: {}", tool_name) } })
                 }
@@ -1128,7 +1493,7 @@ async fn create_sse_server_state(
         ..Default::default()
     };
     let db_pool = SurrealPool::new(db_config).await?;
-    let turn_repository = Arc::new(TurnRepository::new(db_pool.inner().await, db_pool.clone()));
+    let turn_repository = Arc::new(TurnRepository::new(db_pool.clone()));
 
     let embedding_config = crate::config::config::EmbeddingConfig {
         model_name: "all-MiniLM-L6-v2".into(),
@@ -1137,34 +1502,44 @@ async fn create_sse_server_state(
     };
 
     let embedding_model = create_embedding_model(&embedding_config, 384).await?;
-    let retrieval_service = create_retrieval_service(embedding_model, turn_repository.clone());
+    let retrieval_service = create_retrieval_service(embedding_model, turn_repository.clone(), None);
 
     // For standalone mode, create session and turn service
     let session_repository = Arc::new(crate::storage::repository::SessionRepository::new(
         db_pool.clone(),
     ));
-    let session_service: Arc<dyn SessionService> =
-        Arc::new(crate::services::session::SessionServiceImpl::new(
+    let memory_repository: Arc<dyn MemoryRepository + Send + Sync> =
+        Arc::new(MemoryRepositoryImpl::new(db_pool.clone()));
+    let session_service: Arc<dyn SessionService> = Arc::new(
+        crate::services::session::SessionServiceImpl::new(
             session_repository.clone(),
             turn_repository.clone(),
-        ));
+        )
+        .with_memory_repository(memory_repository.clone()),
+    );
     let turn_service: Arc<dyn TurnService> = Arc::new(crate::services::turn::TurnServiceImpl::new(
         turn_repository,
         session_repository,
     ));
 
+    let pattern_repository = Arc::new(PatternRepositoryImpl::new(db_pool.clone()));
+
     Ok(SseServerState {
         config: config.clone(),
         connection_manager: Arc::new(ConnectionManager::new(config.max_connections)),
-        retrieval_service: Arc::from(retrieval_service),
+        retrieval_service: LazyService::ready("retrieval service", retrieval_service),
         session_service,
         turn_service,
+        memory_repository,
+        pattern_repository,
+        pattern_cache: PatternCache::default(),
+        pattern_quality_thresholds: PatternQualityThresholds::default(),
     })
 }
 
 /// Create SSE router that can be merged with existing AppState
 pub fn create_sse_router(app_state: Arc<AppState>) -> Router {
-    let config = SseServerConfig::default();
+    let config = SseServerConfig::default().with_tool_config_file(default_config_path());
 
     Router::new()
         .route(
@@ -1172,12 +1547,13 @@ pub fn create_sse_router(app_state: Arc<AppState>) -> Router {
             get(sse_handler_app_state),
         )
         .route(&config.message_path, post(message_handler_app_state))
+        .layer(Extension(config))
         .with_state(app_state)
 }
 
 /// Run the MCP SSE server (standalone mode)
 pub async fn run_sse_server(port: u16) -> Result<(), Box<dyn std::error::Error>> {
-    let config = SseServerConfig::default();
+    let config = SseServerConfig::default().with_tool_config_file(default_config_path());
     let state = create_sse_server_state(&config).await?;
     let state = Arc::new(state);
 
@@ -1196,3 +1572,94 @@ pub async fn run_sse_server(port: u16) -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_public_events_drops_tenant_events() {
+        let bus = EventBus::default();
+        let mut events = public_events(bus.subscribe());
+
+        bus.publish("session:created", "tenant_1", json!({ "name": "private" }));
+        bus.sender()
+            .send(json!({ "event": "connected", "id": "conn_1" }).to_string())
+            .unwrap();
+
+        let event: Value = serde_json::from_str(&events.next().await.unwrap().unwrap()).unwrap();
+        assert_eq!(event["event"], "connected");
+    }
+
+    #[tokio::test]
+    async fn test_get_hot_memories_requires_matching_caller() {
+        use crate::api::test_support::claims;
+        use crate::models::memory::{Memory, MemorySource, MemoryType};
+        use crate::storage::in_memory::InMemoryMemoryRepository;
+
+        let repo = InMemoryMemoryRepository::new();
+        let memory = Memory::new(
+            "user_1",
+            MemoryType::Semantic,
+            "likes rust",
+            MemorySource::Conversation,
+        );
+        repo.create(&memory).await.unwrap();
+        let arguments = json!({ "user_id": "user_1" });
+
+        let anonymous = call_get_hot_memories(&repo, json!(1), &arguments, None).await;
+        assert_eq!(anonymous["error"]["code"], -32001);
+
+        let mut other = claims("tenant_a", "user");
+        other.sub = "user_2".to_string();
+        let denied = call_get_hot_memories(&repo, json!(2), &arguments, Some(&other)).await;
+        assert_eq!(denied["error"]["code"], -32003);
+
+        let owner = claims("tenant_a", "user");
+        let response = call_get_hot_memories(&repo, json!(3), &arguments, Some(&owner)).await;
+        assert_eq!(response["type"], "result");
+        assert_eq!(response["result"]["total"], 1);
+    }
+
+    #[test]
+    fn test_tool_config_enabled_count() {
+        let mut tools = McpToolConfig::default();
+        assert_eq!(tools.enabled_count(), MCP_TOOL_NAMES.len());
+
+        tools.enable_delete_session = false;
+        assert!(!tools.is_enabled("hippos_delete_session"));
+        assert!(!tools.is_enabled("unknown_tool"));
+        assert_eq!(tools.enabled_count(), MCP_TOOL_NAMES.len() - 1);
+    }
+
+    #[test]
+    fn test_tool_config_load_from_file() {
+        let path = std::env::temp_dir().join(format!("hippos-mcp-{}.toml", Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "app_name = \"hippos\"\n\n[mcp.tools]\nenable_delete_session = false\n",
+        )
+        .unwrap();
+
+        let tools = McpToolConfig::load_from(&path).unwrap();
+        assert!(!tools.enable_delete_session);
+        assert!(tools.enable_create_session);
+
+        std::fs::write(&path, "app_name = \"hippos\"\n").unwrap();
+        assert_eq!(
+            McpToolConfig::load_from(&path).unwrap(),
+            McpToolConfig::default()
+        );
+
+        let tools = SseServerConfig::default().tools;
+        std::fs::write(&path, "[mcp.tools]\nenable_search = false\n").unwrap();
+        reload_tool_config(&path, &tools);
+        assert!(!tools.read().enable_search);
+
+        std::fs::write(&path, "[mcp.tools]\nenable_search = \"maybe\"\n").unwrap();
+        reload_tool_config(&path, &tools);
+        assert!(!tools.read().enable_search);
+
+        let _ = std::fs::remove_file(path);
+    }
+}
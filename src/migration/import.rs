@@ -3,9 +3,14 @@
 //! 将转换后的数据导入到 ArangoDB。
 //! 支持文档和边集合的批量导入。
 
-use crate::migration::{MigrationError, transform::*};
+use crate::migration::{ArangoDbConfig, MigrationConfig, MigrationError, transform::*};
+use futures_util::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+/// JSONL 导入时同时在途的批量请求上限
+const MAX_PARALLEL_BATCHES: usize = 4;
 
 /// 导入配置
 #[derive(Debug, Clone)]
@@ -69,9 +74,45 @@ pub struct ImportResult {
     pub success: bool,
     pub documents_created: usize,
     pub documents_updated: usize,
+    #[serde(default)]
+    pub documents_ignored: usize,
     pub errors: Vec<ImportError>,
 }
 
+/// JSONL 导入统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportStats {
+    /// 目标集合
+    pub collection: String,
+    /// 读取的文档数
+    pub documents_read: usize,
+    /// 新建的文档数
+    pub documents_created: usize,
+    /// 更新的文档数
+    pub documents_updated: usize,
+    /// 因已存在而跳过的文档数
+    pub documents_ignored: usize,
+    /// 发送的批次数
+    pub batches: usize,
+    /// 解析或导入错误
+    pub errors: Vec<MigrationError>,
+}
+
+impl ImportStats {
+    fn record(&mut self, result: ImportResult) {
+        self.documents_created += result.documents_created;
+        self.documents_updated += result.documents_updated;
+        self.documents_ignored += result.documents_ignored;
+        for error in result.errors {
+            self.errors.push(MigrationError::new(
+                "import_error",
+                &error.error_message,
+                error.document_key.as_deref(),
+            ));
+        }
+    }
+}
+
 /// 导入错误
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportError {
@@ -435,6 +476,7 @@ impl DataImporter {
             success: errors.is_empty(),
             documents_created: documents_created as usize,
             documents_updated: documents_updated as usize,
+            documents_ignored: 0,
             errors,
         })
     }
@@ -559,3 +601,251 @@ pub fn create_importer(source_dir: PathBuf) -> DataImporter {
 
     DataImporter::new(config)
 }
+
+/// 将 JSONL 导出文件导入 ArangoDB
+///
+/// 目标集合由文件名推导（`turns.jsonl` -> `{collection_prefix}turns`）。
+/// 每 `batch_size` 行组成一个批次，通过 `/_api/import` 批量导入，
+/// 最多 `MAX_PARALLEL_BATCHES` 个批次并行发送。已存在的文档会导致导入错误。
+pub async fn import_to_arangodb(
+    config: &ArangoDbConfig,
+    input_path: &Path,
+    batch_size: usize,
+) -> Result<ImportStats, String> {
+    import_jsonl(config, input_path, batch_size, false).await
+}
+
+/// 按迁移配置导入 JSONL 导出文件
+///
+/// 与 [`import_to_arangodb`] 相同，但使用 `MigrationConfig::batch_size`，
+/// 并在 `skip_existing` 开启时以 `onDuplicate=ignore` 跳过已存在的文档。
+pub async fn import_migration_file(
+    config: &MigrationConfig,
+    input_path: &Path,
+) -> Result<ImportStats, String> {
+    import_jsonl(
+        &config.arangodb,
+        input_path,
+        config.batch_size,
+        config.skip_existing,
+    )
+    .await
+}
+
+async fn import_jsonl(
+    config: &ArangoDbConfig,
+    input_path: &Path,
+    batch_size: usize,
+    skip_existing: bool,
+) -> Result<ImportStats, String> {
+    let collection = collection_for_file(config, input_path)?;
+    let file = std::fs::File::open(input_path)
+        .map_err(|e| format!("读取文件失败 {}: {}", input_path.display(), e))?;
+    let reader = std::io::BufReader::new(file);
+
+    // 所有批次共享同一个客户端（及其连接池）
+    let http_client = reqwest::Client::new();
+    let url = bulk_import_url(config, &collection, skip_existing);
+    let batch_size = batch_size.max(1);
+
+    let mut stats = ImportStats {
+        collection,
+        ..Default::default()
+    };
+    let mut in_flight = FuturesUnordered::new();
+    let mut batch = Vec::with_capacity(batch_size);
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("读取文件失败 {}: {}", input_path.display(), e))?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(document) => {
+                batch.push(document);
+                stats.documents_read += 1;
+            }
+            Err(e) => {
+                stats.errors.push(MigrationError::new(
+                    "parse_error",
+                    &format!("第 {} 行解析 JSON 失败: {}", index + 1, e),
+                    None,
+                ));
+                continue;
+            }
+        }
+
+        if batch.len() >= batch_size {
+            // 在途批次已满时，先等待任一批次完成
+            while in_flight.len() >= MAX_PARALLEL_BATCHES {
+                if let Some(result) = in_flight.next().await {
+                    stats.record(result?);
+                }
+            }
+            let documents = std::mem::replace(&mut batch, Vec::with_capacity(batch_size));
+            in_flight.push(post_bulk_import(&http_client, config, &url, documents));
+            stats.batches += 1;
+        }
+    }
+
+    if !batch.is_empty() {
+        in_flight.push(post_bulk_import(&http_client, config, &url, batch));
+        stats.batches += 1;
+    }
+
+    while let Some(result) = in_flight.next().await {
+        stats.record(result?);
+    }
+
+    println!(
+        "导入 {}: {} 个新建, {} 个跳过, {} 个错误",
+        stats.collection,
+        stats.documents_created,
+        stats.documents_ignored,
+        stats.errors.len()
+    );
+
+    Ok(stats)
+}
+
+/// 由导出文件名推导目标集合名
+fn collection_for_file(config: &ArangoDbConfig, input_path: &Path) -> Result<String, String> {
+    let stem = input_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| format!("无法从文件名推导集合: {}", input_path.display()))?;
+
+    if stem.starts_with(&config.collection_prefix) {
+        Ok(stem.to_string())
+    } else {
+        Ok(format!("{}{}", config.collection_prefix, stem))
+    }
+}
+
+fn bulk_import_url(config: &ArangoDbConfig, collection: &str, skip_existing: bool) -> String {
+    let mut url = format!(
+        "{}/_db/{}/_api/import?collection={}&type=list&details=true",
+        config.url.trim_end_matches('/'),
+        config.database,
+        collection
+    );
+    if skip_existing {
+        url.push_str("&onDuplicate=ignore");
+    }
+    url
+}
+
+/// 发送单个批次
+async fn post_bulk_import(
+    http_client: &reqwest::Client,
+    config: &ArangoDbConfig,
+    url: &str,
+    documents: Vec<serde_json::Value>,
+) -> Result<ImportResult, String> {
+    let response = http_client
+        .post(url)
+        .basic_auth(&config.username, Some(&config.password))
+        .json(&documents)
+        .send()
+        .await
+        .map_err(|e| format!("导入文档失败: {}", e))?;
+
+    if !response.status().is_success() {
+        let error_text = response
+            .text()
+            .await
+            .map_err(|e| format!("获取错误响应失败: {}", e))?;
+        return Err(format!("导入失败: {}", error_text));
+    }
+
+    let result: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("解析响应失败: {}", e))?;
+
+    Ok(parse_bulk_import_response(&result))
+}
+
+/// 解析 `/_api/import` 响应
+///
+/// 失败的文档只以 `details` 中的文本描述返回，没有错误码和文档键。
+fn parse_bulk_import_response(result: &serde_json::Value) -> ImportResult {
+    let count = |field: &str| result.get(field).and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+
+    let errors: Vec<ImportError> = result
+        .get("details")
+        .and_then(|v| v.as_array())
+        .map(|details| {
+            details
+                .iter()
+                .filter_map(|d| d.as_str())
+                .map(|message| ImportError {
+                    error_num: 0,
+                    error_message: message.to_string(),
+                    document_key: None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    ImportResult {
+        success: count("errors") == 0,
+        documents_created: count("created"),
+        documents_updated: count("updated"),
+        documents_ignored: count("ignored"),
+        errors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collection_for_file() {
+        let config = ArangoDbConfig::default();
+
+        assert_eq!(
+            collection_for_file(&config, Path::new("/tmp/export/turns.jsonl")).unwrap(),
+            "hippos_turns"
+        );
+        assert_eq!(
+            collection_for_file(&config, Path::new("hippos_sessions.jsonl")).unwrap(),
+            "hippos_sessions"
+        );
+    }
+
+    #[test]
+    fn test_bulk_import_url_skip_existing() {
+        let config = ArangoDbConfig::default();
+
+        let url = bulk_import_url(&config, "hippos_turns", false);
+        assert_eq!(
+            url,
+            "http://localhost:8529/_db/hippos/_api/import?collection=hippos_turns&type=list&details=true"
+        );
+        assert!(bulk_import_url(&config, "hippos_turns", true).ends_with("&onDuplicate=ignore"));
+    }
+
+    #[test]
+    fn test_parse_bulk_import_response() {
+        let response = serde_json::json!({
+            "error": false,
+            "created": 3,
+            "errors": 1,
+            "empty": 0,
+            "updated": 0,
+            "ignored": 2,
+            "details": ["at position 4: creating document failed with error 'illegal document key'"]
+        });
+
+        let result = parse_bulk_import_response(&response);
+        assert!(!result.success);
+        assert_eq!(result.documents_created, 3);
+        assert_eq!(result.documents_ignored, 2);
+        assert_eq!(result.errors.len(), 1);
+    }
+}
@@ -6,6 +6,7 @@
 pub mod export;
 pub mod import;
 pub mod transform;
+pub mod verify;
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -221,7 +222,7 @@ impl MigrationStats {
 }
 
 /// 运行完整迁移流程
-pub async fn run_full_migration(_config: MigrationConfig) -> Result<MigrationStats, String> {
+pub async fn run_full_migration(config: MigrationConfig) -> Result<MigrationStats, String> {
     use std::time::Instant;
 
     let progress = MigrationProgress::default();
@@ -240,6 +241,15 @@ pub async fn run_full_migration(_config: MigrationConfig) -> Result<MigrationSta
 
     // 4. 验证阶段
     println!("[4/4] 正在验证迁移结果...");
+    let verification_errors = verify::verify_migration(&progress, &config).await?;
+    if verification_errors.is_empty() {
+        println!("验证通过");
+    } else {
+        println!("发现 {} 处不一致:", verification_errors.len());
+        for error in &verification_errors {
+            println!("  - {}", error);
+        }
+    }
 
     // 计算统计
     let export_duration = export_start.elapsed().as_secs_f64();
@@ -0,0 +1,333 @@
+//! 迁移验证模块
+//!
+//! 导入完成后比对 Surrealdb 与 ArangoDB 中的数据，检查记录数量与引用完整性。
+//! 每一处不一致都记录为一个 `VerificationError`，不会在发现第一个问题时中止。
+
+use crate::config::config::DatabaseConfig;
+use crate::migration::{ArangoDbConfig, MigrationConfig, MigrationProgress, SurrealdbConfig};
+use crate::storage::repository::{execute_query, statement_rows};
+use crate::storage::surrealdb::SurrealPool;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 抽查的轮次数
+pub const SPOT_CHECK_TURNS: usize = 10;
+
+/// 最多报告的孤立索引记录数
+pub const MAX_ORPHAN_REPORTS: usize = 100;
+
+/// 验证问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationErrorKind {
+    /// 两个数据库中的记录数不一致
+    CountMismatch,
+    /// 轮次引用的会话在 ArangoDB 中不存在
+    MissingSession,
+    /// 索引记录引用的轮次在 ArangoDB 中不存在
+    OrphanIndexRecord,
+}
+
+impl fmt::Display for VerificationErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationErrorKind::CountMismatch => write!(f, "count_mismatch"),
+            VerificationErrorKind::MissingSession => write!(f, "missing_session"),
+            VerificationErrorKind::OrphanIndexRecord => write!(f, "orphan_index_record"),
+        }
+    }
+}
+
+/// 验证发现的不一致
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerificationError {
+    /// 问题类型
+    pub kind: VerificationErrorKind,
+    /// 问题描述
+    pub message: String,
+    /// 关联的记录键
+    pub record_id: Option<String>,
+}
+
+impl VerificationError {
+    pub fn new(kind: VerificationErrorKind, message: &str, record_id: Option<&str>) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+            record_id: record_id.map(|s| s.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.kind, self.message)
+    }
+}
+
+/// 验证迁移结果
+///
+/// 1. 比对两个数据库中的会话数与轮次数，并与 `progress` 记录的导入数比对；
+/// 2. 随机抽查 `SPOT_CHECK_TURNS` 个已导入轮次，确认其 `session_key` 指向的会话存在；
+/// 3. 查找引用不存在轮次的索引记录（最多报告 `MAX_ORPHAN_REPORTS` 条）。
+///
+/// 仅在无法连接或查询数据库时返回错误。
+pub async fn verify_migration(
+    progress: &MigrationProgress,
+    config: &MigrationConfig,
+) -> Result<Vec<VerificationError>, String> {
+    let source = SurrealPool::new(source_db_config(&config.surrealdb))
+        .await
+        .map_err(|e| format!("连接 Surrealdb 失败: {}", e))?;
+    let target = ArangoClient::new(&config.arangodb);
+    let mut errors = Vec::new();
+
+    // 1. 记录数比对
+    for (table, imported) in [
+        ("session", progress.sessions_imported),
+        ("turn", progress.turns_imported),
+    ] {
+        let source_count = surreal_count(&source, table).await?;
+        let target_count = target.count(&format!("{}s", table)).await?;
+        errors.extend(count_mismatches(
+            table,
+            source_count,
+            target_count,
+            imported,
+        ));
+    }
+
+    // 2. 抽查轮次的会话外键
+    let sampled = target
+        .query(
+            "FOR turn IN @@turns SORT RAND() LIMIT @limit \
+             RETURN { key: turn._key, session_key: turn.session_key, \
+             session_found: DOCUMENT(@sessions, turn.session_key) != null }",
+            serde_json::json!({
+                "@turns": target.collection("turns"),
+                "sessions": target.collection("sessions"),
+                "limit": SPOT_CHECK_TURNS,
+            }),
+        )
+        .await?;
+    errors.extend(missing_sessions(&sampled));
+
+    // 3. 孤立的索引记录
+    let orphans = target
+        .query(
+            "FOR record IN @@index_records \
+             FILTER DOCUMENT(@turns, record.turn_key) == null LIMIT @limit \
+             RETURN { key: record._key, turn_key: record.turn_key }",
+            serde_json::json!({
+                "@index_records": target.collection("index_records"),
+                "turns": target.collection("turns"),
+                "limit": MAX_ORPHAN_REPORTS,
+            }),
+        )
+        .await?;
+    errors.extend(orphan_index_records(&orphans));
+
+    Ok(errors)
+}
+
+fn source_db_config(config: &SurrealdbConfig) -> DatabaseConfig {
+    DatabaseConfig {
+        url: config.url.clone(),
+        namespace: config.namespace.clone(),
+        database: config.database.clone(),
+        username: config.username.clone(),
+        password: config.password.clone(),
+        ..Default::default()
+    }
+}
+
+/// 统计 Surrealdb 表的记录数
+async fn surreal_count(pool: &SurrealPool, table: &str) -> Result<u64, String> {
+    let query = format!("SELECT count() FROM {} GROUP ALL", table);
+    let rows = execute_query(pool, &query)
+        .await
+        .and_then(statement_rows)
+        .map_err(|e| format!("统计 Surrealdb 表 {} 失败: {}", table, e))?;
+
+    Ok(rows
+        .first()
+        .and_then(|row| row.get("count"))
+        .and_then(|count| count.as_u64())
+        .unwrap_or(0))
+}
+
+/// 比对记录数；`imported` 为 0 时视为未记录导入进度，不参与比对
+fn count_mismatches(
+    table: &str,
+    source_count: u64,
+    target_count: u64,
+    imported: usize,
+) -> Vec<VerificationError> {
+    let mut errors = Vec::new();
+    if source_count != target_count {
+        errors.push(VerificationError::new(
+            VerificationErrorKind::CountMismatch,
+            &format!(
+                "{} 数量不一致: Surrealdb {} 条, ArangoDB {} 条",
+                table, source_count, target_count
+            ),
+            None,
+        ));
+    }
+    if imported > 0 && imported as u64 != target_count {
+        errors.push(VerificationError::new(
+            VerificationErrorKind::CountMismatch,
+            &format!(
+                "{} 导入数与 ArangoDB 不一致: 导入 {} 条, ArangoDB {} 条",
+                table, imported, target_count
+            ),
+            None,
+        ));
+    }
+    errors
+}
+
+/// 抽查结果中会话不存在的轮次
+fn missing_sessions(rows: &[serde_json::Value]) -> Vec<VerificationError> {
+    rows.iter()
+        .filter(|row| row.get("session_found").and_then(|v| v.as_bool()) != Some(true))
+        .map(|row| {
+            let key = row.get("key").and_then(|v| v.as_str()).unwrap_or_default();
+            let session_key = row
+                .get("session_key")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            VerificationError::new(
+                VerificationErrorKind::MissingSession,
+                &format!("轮次 {} 引用的会话 {} 不存在", key, session_key),
+                Some(key),
+            )
+        })
+        .collect()
+}
+
+/// 引用不存在轮次的索引记录
+fn orphan_index_records(rows: &[serde_json::Value]) -> Vec<VerificationError> {
+    rows.iter()
+        .map(|row| {
+            let key = row.get("key").and_then(|v| v.as_str()).unwrap_or_default();
+            let turn_key = row
+                .get("turn_key")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            VerificationError::new(
+                VerificationErrorKind::OrphanIndexRecord,
+                &format!("索引记录 {} 引用的轮次 {} 不存在", key, turn_key),
+                Some(key),
+            )
+        })
+        .collect()
+}
+
+/// 执行 AQL 查询的最小 ArangoDB 客户端
+struct ArangoClient<'a> {
+    config: &'a ArangoDbConfig,
+    http_client: reqwest::Client,
+}
+
+impl<'a> ArangoClient<'a> {
+    fn new(config: &'a ArangoDbConfig) -> Self {
+        Self {
+            config,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    fn collection(&self, name: &str) -> String {
+        format!("{}{}", self.config.collection_prefix, name)
+    }
+
+    /// 统计集合的文档数
+    async fn count(&self, name: &str) -> Result<u64, String> {
+        let rows = self
+            .query(
+                "RETURN LENGTH(@@collection)",
+                serde_json::json!({ "@collection": self.collection(name) }),
+            )
+            .await?;
+        Ok(rows.first().and_then(|v| v.as_u64()).unwrap_or(0))
+    }
+
+    /// 通过 `/_api/cursor` 执行 AQL，返回第一批结果
+    async fn query(
+        &self,
+        query: &str,
+        bind_vars: serde_json::Value,
+    ) -> Result<Vec<serde_json::Value>, String> {
+        let url = format!(
+            "{}/_db/{}/_api/cursor",
+            self.config.url.trim_end_matches('/'),
+            self.config.database
+        );
+        let response = self
+            .http_client
+            .post(&url)
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .json(&serde_json::json!({ "query": query, "bindVars": bind_vars }))
+            .send()
+            .await
+            .map_err(|e| format!("执行 AQL 查询失败: {}", e))?;
+
+        let status = response.status();
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("解析响应失败: {}", e))?;
+        if !status.is_success() {
+            return Err(format!(
+                "AQL 查询失败: {}",
+                body.get("errorMessage").unwrap_or(&body)
+            ));
+        }
+
+        Ok(body
+            .get("result")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_mismatches() {
+        assert!(count_mismatches("turn", 10, 10, 10).is_empty());
+        assert!(count_mismatches("turn", 10, 10, 0).is_empty());
+
+        let errors = count_mismatches("turn", 10, 8, 9);
+        assert_eq!(errors.len(), 2);
+        assert!(
+            errors
+                .iter()
+                .all(|e| e.kind == VerificationErrorKind::CountMismatch)
+        );
+    }
+
+    #[test]
+    fn test_reference_checks() {
+        let sampled = vec![
+            serde_json::json!({ "key": "turn_1", "session_key": "s1", "session_found": true }),
+            serde_json::json!({ "key": "turn_2", "session_key": "s9", "session_found": false }),
+        ];
+        let errors = missing_sessions(&sampled);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, VerificationErrorKind::MissingSession);
+        assert_eq!(errors[0].record_id.as_deref(), Some("turn_2"));
+
+        let orphans = vec![serde_json::json!({ "key": "idx_1", "turn_key": "turn_9" })];
+        let errors = orphan_index_records(&orphans);
+        assert_eq!(errors[0].kind, VerificationErrorKind::OrphanIndexRecord);
+        assert_eq!(
+            errors[0].to_string(),
+            "[orphan_index_record] 索引记录 idx_1 引用的轮次 turn_9 不存在"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! API Key 仓储
+//!
+//! 持久化运行时创建的 API Key 记录，只保存密钥的 BLAKE3 哈希
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::error::Result;
+use crate::security::auth::ApiKeyRecord;
+use crate::storage::repository::{execute_query, statement_rows};
+use crate::storage::surrealdb::SurrealPool;
+
+/// API Key 仓储 trait
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    /// 保存新建的 API Key 记录
+    async fn create(&self, record: &ApiKeyRecord) -> Result<()>;
+
+    /// 根据密钥哈希获取记录
+    async fn get_by_hash(&self, key_hash: &blake3::Hash) -> Result<Option<ApiKeyRecord>>;
+
+    /// 仅当记录的哈希仍为 `old_hash` 时替换为 `record` 中的哈希与创建时间，返回是否替换
+    async fn replace_hash(&self, old_hash: &blake3::Hash, record: &ApiKeyRecord) -> Result<bool>;
+
+    /// 更新最近使用时间
+    async fn touch(&self, key_id: &str, used_at: DateTime<Utc>) -> Result<()>;
+
+    /// 删除租户的 API Key，不存在时返回 false
+    async fn delete(&self, tenant_id: &str, key_id: &str) -> Result<bool>;
+}
+
+/// API Key 仓储实现
+#[derive(Clone)]
+pub struct ApiKeyRepositoryImpl {
+    pool: SurrealPool,
+}
+
+impl ApiKeyRepositoryImpl {
+    pub fn new(pool: SurrealPool) -> Self {
+        Self { pool }
+    }
+
+    /// 执行查询并解析首条语句返回的记录
+    async fn query_records(&self, query: &str) -> Result<Vec<ApiKeyRecord>> {
+        let rows = statement_rows(execute_query(&self.pool, query).await?)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| match serde_json::from_value(row) {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    tracing::warn!("Failed to deserialize API key: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for ApiKeyRepositoryImpl {
+    async fn create(&self, record: &ApiKeyRecord) -> Result<()> {
+        let query = format!("CREATE api_key CONTENT {}", serde_json::to_string(record)?);
+        statement_rows(execute_query(&self.pool, &query).await?)?;
+        Ok(())
+    }
+
+    async fn get_by_hash(&self, key_hash: &blake3::Hash) -> Result<Option<ApiKeyRecord>> {
+        let query = format!(
+            "SELECT * FROM api_key WHERE key_hash = '{}' LIMIT 1",
+            key_hash.to_hex()
+        );
+        Ok(self.query_records(&query).await?.into_iter().next())
+    }
+
+    async fn replace_hash(&self, old_hash: &blake3::Hash, record: &ApiKeyRecord) -> Result<bool> {
+        let query = format!(
+            "UPDATE api_key SET key_hash = '{}', created_at = '{}', last_used_at = NONE WHERE key_id = '{}' AND key_hash = '{}' RETURN AFTER",
+            record.key_hash.to_hex(),
+            record.created_at.to_rfc3339(),
+            record.key_id.replace("'", "\\'"),
+            old_hash.to_hex()
+        );
+        Ok(!self.query_records(&query).await?.is_empty())
+    }
+
+    async fn touch(&self, key_id: &str, used_at: DateTime<Utc>) -> Result<()> {
+        let query = format!(
+            "UPDATE api_key SET last_used_at = '{}' WHERE key_id = '{}'",
+            used_at.to_rfc3339(),
+            key_id.replace("'", "\\'")
+        );
+        statement_rows(execute_query(&self.pool, &query).await?)?;
+        Ok(())
+    }
+
+    async fn delete(&self, tenant_id: &str, key_id: &str) -> Result<bool> {
+        let query = format!(
+            "DELETE api_key WHERE key_id = '{}' AND tenant_id = '{}' RETURN BEFORE",
+            key_id.replace("'", "\\'"),
+            tenant_id.replace("'", "\\'")
+        );
+        Ok(!statement_rows(execute_query(&self.pool, &query).await?)?.is_empty())
+    }
+}
@@ -162,6 +162,10 @@ pub enum RelationshipType {
     #[serde(rename = "similar_to")]
     SimilarTo,
 
+    /// 相关
+    #[serde(rename = "related_to")]
+    RelatedTo,
+
     /// 创建
     #[serde(rename = "created_by")]
     CreatedBy,
@@ -219,6 +223,7 @@ impl std::fmt::Display for RelationshipType {
             RelationshipType::References => write!(f, "references"),
             RelationshipType::ConflictsWith => write!(f, "conflicts_with"),
             RelationshipType::SimilarTo => write!(f, "similar_to"),
+            RelationshipType::RelatedTo => write!(f, "related_to"),
             RelationshipType::CreatedBy => write!(f, "created_by"),
             RelationshipType::Contains => write!(f, "contains"),
             RelationshipType::CompetesWith => write!(f, "competes_with"),
@@ -542,6 +547,7 @@ impl Relationship {
             RelationshipType::References => RelationshipType::ReferencedBy,
             RelationshipType::ConflictsWith => RelationshipType::ConflictsWith,
             RelationshipType::SimilarTo => RelationshipType::SimilarTo,
+            RelationshipType::RelatedTo => RelationshipType::RelatedTo,
             RelationshipType::CreatedBy => RelationshipType::Created,
             RelationshipType::Contains => RelationshipType::PartOf,
             RelationshipType::CompetesWith => RelationshipType::CompetesWith,
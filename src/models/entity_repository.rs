@@ -29,12 +29,27 @@ pub trait EntityRepository {
     /// 搜索实体
     async fn search_entities(&self, name: &str, entity_type: Option<&str>) -> Result<Vec<Entity>>;
 
+    /// 在租户内按名称或别名搜索实体（按出现频率降序）
+    async fn search_tenant_entities(
+        &self,
+        tenant_id: &str,
+        name: &str,
+        limit: usize,
+    ) -> Result<Vec<Entity>>;
+
     /// 创建关系
     async fn create_relationship(&self, relationship: &Relationship) -> Result<Relationship>;
 
     /// 根据 ID 获取关系
     async fn get_relationship_by_id(&self, id: &str) -> Result<Option<Relationship>>;
 
+    /// 更新关系的验证状态、强度和版本，关系不存在时返回 None
+    async fn update_relationship(
+        &self,
+        id: &str,
+        relationship: &Relationship,
+    ) -> Result<Option<Relationship>>;
+
     /// 删除关系
     async fn delete_relationship(&self, id: &str) -> Result<bool>;
 
@@ -47,6 +62,10 @@ pub trait EntityRepository {
     /// 获取图统计
     async fn get_graph_stats(&self) -> Result<GraphStats>;
 
+    /// 获取租户内的枢纽实体（按出现频率降序），并附带各自的关系数
+    async fn get_hub_entities(&self, tenant_id: &str, limit: usize)
+    -> Result<Vec<(Entity, usize)>>;
+
     /// 发现实体（根据名称）
     async fn discover_entity(&self, name: &str, entity_type: &str) -> Result<Option<Entity>>;
 }
@@ -263,20 +282,18 @@ impl EntityRepository for EntityRepositoryImpl {
     }
 
     async fn search_entities(&self, name: &str, entity_type: Option<&str>) -> Result<Vec<Entity>> {
-        let mut conditions = Vec::new();
-        conditions.push(format!("name CONTAINS '{}' OR aliases CONTAINS '{}'", name, name));
-
-        if let Some(etype) = entity_type {
-            conditions.push(format!("entity_type = '{}'", etype));
-        }
-
-        let where_clause = format!("WHERE {}", conditions.join(" AND "));
-
-        let query = format!(
-            "SELECT * FROM entity {} ORDER BY frequency DESC LIMIT 20",
-            where_clause
-        );
+        let query = entity_search_query(None, name, entity_type, 20);
+        let results = self.execute_query(&query).await?;
+        Ok(self.parse_entity_results(&results))
+    }
 
+    async fn search_tenant_entities(
+        &self,
+        tenant_id: &str,
+        name: &str,
+        limit: usize,
+    ) -> Result<Vec<Entity>> {
+        let query = entity_search_query(Some(tenant_id), name, None, limit);
         let results = self.execute_query(&query).await?;
         Ok(self.parse_entity_results(&results))
     }
@@ -328,6 +345,17 @@ impl EntityRepository for EntityRepositoryImpl {
         Ok(None)
     }
 
+    async fn update_relationship(
+        &self,
+        id: &str,
+        relationship: &Relationship,
+    ) -> Result<Option<Relationship>> {
+        let query = relationship_update_query(id, relationship);
+        let results = self.execute_query(&query).await?;
+
+        Ok(self.parse_relationship_results(&results).into_iter().next())
+    }
+
     async fn delete_relationship(&self, id: &str) -> Result<bool> {
         let query = format!("DELETE FROM relationship WHERE id = {}", id);
         let results = self.execute_query(&query).await?;
@@ -475,6 +503,37 @@ impl EntityRepository for EntityRepositoryImpl {
         })
     }
 
+    async fn get_hub_entities(
+        &self,
+        tenant_id: &str,
+        limit: usize,
+    ) -> Result<Vec<(Entity, usize)>> {
+        let entity_query = format!(
+            "SELECT * FROM entity WHERE tenant_id = '{}' ORDER BY frequency DESC LIMIT {}",
+            tenant_id, limit
+        );
+        let entity_results = self.execute_query(&entity_query).await?;
+        let entities = self.parse_entity_results(&entity_results);
+        if entities.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // 一次查询取回所有相关关系，再在内存中计数
+        let ids_str = entities
+            .iter()
+            .map(|e| e.id.as_str())
+            .collect::<Vec<_>>()
+            .join("','");
+        let rel_query = format!(
+            "SELECT * FROM relationship WHERE source_entity_id IN ['{}'] OR target_entity_id IN ['{}']",
+            ids_str, ids_str
+        );
+        let rel_results = self.execute_query(&rel_query).await?;
+        let relationships = self.parse_relationship_results(&rel_results);
+
+        Ok(count_relationships(entities, &relationships))
+    }
+
     async fn discover_entity(&self, name: &str, entity_type: &str) -> Result<Option<Entity>> {
         let query = format!(
             "SELECT * FROM entity WHERE name = '{}' AND entity_type = '{}' LIMIT 1",
@@ -502,3 +561,144 @@ impl EntityRepository for EntityRepositoryImpl {
         Ok(None)
     }
 }
+
+/// 构造关系更新语句（仅更新验证状态、强度、更新时间和版本）
+fn relationship_update_query(id: &str, relationship: &Relationship) -> String {
+    format!(
+        "UPDATE relationship SET verified = {}, strength = {}, updated_at = '{}', version = {} WHERE id = {}",
+        relationship.verified,
+        relationship.strength,
+        relationship.updated_at.to_rfc3339(),
+        relationship.version,
+        id
+    )
+}
+
+/// 统计每个实体参与的关系数（自环只计一次）
+fn count_relationships(
+    entities: Vec<Entity>,
+    relationships: &[Relationship],
+) -> Vec<(Entity, usize)> {
+    entities
+        .into_iter()
+        .map(|entity| {
+            let count = relationships
+                .iter()
+                .filter(|r| r.source_entity_id == entity.id || r.target_entity_id == entity.id)
+                .count();
+            (entity, count)
+        })
+        .collect()
+}
+
+/// 按名称或别名搜索实体的查询，可限定租户与实体类型
+fn entity_search_query(
+    tenant_id: Option<&str>,
+    name: &str,
+    entity_type: Option<&str>,
+    limit: usize,
+) -> String {
+    let name = name.replace("'", "\\'");
+    let mut conditions = vec![format!(
+        "(name CONTAINS '{}' OR aliases CONTAINS '{}')",
+        name, name
+    )];
+    if let Some(tenant_id) = tenant_id {
+        conditions.push(format!("tenant_id = '{}'", tenant_id.replace("'", "\\'")));
+    }
+    if let Some(etype) = entity_type {
+        conditions.push(format!("entity_type = '{}'", etype.replace("'", "\\'")));
+    }
+
+    format!(
+        "SELECT * FROM entity WHERE {} ORDER BY frequency DESC LIMIT {}",
+        conditions.join(" AND "),
+        limit
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::entity::{EntityType, RelationshipType};
+
+    #[test]
+    fn test_count_relationships() {
+        let rust = Entity::new("Rust", EntityType::Tool);
+        let alice = Entity::new("Alice", EntityType::Person);
+        let relationships = vec![
+            Relationship::new(&alice.id, &rust.id, RelationshipType::Uses, "m1"),
+            Relationship::new(&rust.id, "tokio", RelationshipType::DependsOn, "m2"),
+        ];
+
+        let counts = count_relationships(vec![rust.clone(), alice.clone()], &relationships);
+
+        assert_eq!(counts[0].0.id, rust.id);
+        assert_eq!(counts[0].1, 2);
+        assert_eq!(counts[1].0.id, alice.id);
+        assert_eq!(counts[1].1, 1);
+    }
+
+    #[test]
+    fn test_entity_search_query_scopes_tenant_and_escapes() {
+        let query = entity_search_query(Some("tenant_a"), "O'Brien", None, 10);
+        assert_eq!(
+            query,
+            "SELECT * FROM entity WHERE (name CONTAINS 'O\\'Brien' OR aliases CONTAINS 'O\\'Brien') \
+             AND tenant_id = 'tenant_a' ORDER BY frequency DESC LIMIT 10"
+        );
+
+        let query = entity_search_query(None, "Rust", Some("tool"), 20);
+        assert!(query.contains(
+            "(name CONTAINS 'Rust' OR aliases CONTAINS 'Rust') AND entity_type = 'tool'"
+        ));
+        assert!(!query.contains("tenant_id"));
+    }
+
+    #[test]
+    fn test_relationship_update_query() {
+        let mut relationship = Relationship::new("alice", "rust", RelationshipType::Uses, "m1");
+        relationship.strength = 0.8;
+        relationship.version = 3;
+        relationship.verify();
+
+        let query = relationship_update_query("relationship:abc", &relationship);
+
+        assert!(query.starts_with("UPDATE relationship SET verified = true, strength = 0.8"));
+        assert!(query.contains(&format!(
+            "updated_at = '{}'",
+            relationship.updated_at.to_rfc3339()
+        )));
+        assert!(query.ends_with("version = 3 WHERE id = relationship:abc"));
+    }
+
+    #[tokio::test]
+    async fn test_update_relationship_returns_updated_state() {
+        use crate::api::test_support::MockDatabase;
+
+        let db = MockDatabase::start().await;
+        let mut relationship = Relationship::new("alice", "rust", RelationshipType::Uses, "m1");
+        relationship.strength = 0.8;
+        relationship.version = 2;
+        relationship.verify();
+        db.respond("UPDATE relationship", serde_json::json!([relationship]))
+            .await;
+
+        let repo = EntityRepositoryImpl::new(db.pool());
+        let updated = repo
+            .update_relationship(&relationship.id, &relationship)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(updated.id, relationship.id);
+        assert!(updated.verified);
+        assert_eq!(updated.strength, 0.8);
+        assert_eq!(updated.version, relationship.version);
+        let queries = db.queries().await;
+        assert_eq!(
+            queries,
+            vec![relationship_update_query(&relationship.id, &relationship)]
+        );
+    }
+}
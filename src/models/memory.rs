@@ -132,7 +132,7 @@ pub struct Memory {
     /// 来源类型
     pub source: MemorySource,
 
-    /// 原始来源 ID（如对话 ID、文档 ID）
+    /// 原始来源 ID（对话记忆为产生它的轮次 ID，其他来源如文档 ID）
     pub source_id: Option<String>,
 
     /// === 关系字段 ===
@@ -158,6 +158,10 @@ pub struct Memory {
     /// 最后访问时间
     pub accessed_at: DateTime<Utc>,
 
+    /// 访问次数
+    #[serde(default)]
+    pub access_count: u32,
+
     /// 过期时间（可选）
     pub expires_at: Option<DateTime<Utc>>,
 
@@ -202,6 +206,7 @@ impl Memory {
             created_at: now,
             updated_at: now,
             accessed_at: now,
+            access_count: 0,
             expires_at: None,
             status: MemoryStatus::Active,
             version: 1,
@@ -489,6 +494,23 @@ mod tests {
         assert_eq!(memory.topics.len(), 1);
     }
 
+    #[test]
+    fn test_memory_access_count_defaults_to_zero() {
+        let memory = Memory::new(
+            "user_123",
+            MemoryType::Semantic,
+            "测试内容",
+            MemorySource::Conversation,
+        );
+        assert_eq!(memory.access_count, 0);
+
+        // 旧数据没有 access_count 字段时应反序列化为 0
+        let mut json = serde_json::to_value(&memory).unwrap();
+        json.as_object_mut().unwrap().remove("access_count");
+        let restored: Memory = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.access_count, 0);
+    }
+
     #[test]
     fn test_memory_query() {
         let query = MemoryQuery::new()
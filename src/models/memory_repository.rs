@@ -5,7 +5,7 @@
 use async_trait::async_trait;
 use std::marker::PhantomData;
 use crate::error::Result;
-use crate::models::memory::{Memory, MemoryQuery, MemoryStats};
+use crate::models::memory::{Memory, MemoryQuery, MemorySource, MemoryStats};
 use crate::storage::surrealdb::SurrealPool;
 
 /// Memory 仓储 trait
@@ -46,6 +46,32 @@ pub trait MemoryRepository {
 
     /// 获取记忆统计
     async fn get_stats(&self, user_id: &str) -> Result<MemoryStats>;
+
+    /// 访问计数加一并刷新访问时间
+    async fn increment_access_count(&self, id: &str) -> Result<()>;
+
+    /// 列出用户访问最频繁的记忆
+    async fn list_hot(&self, user_id: &str, limit: usize) -> Result<Vec<Memory>>;
+
+    /// 列出由指定来源（如轮次 ID）产生的记忆，按创建时间升序
+    async fn list_by_source_id(&self, source_id: &str) -> Result<Vec<Memory>>;
+
+    /// 删除由指定对话（会话）的轮次产生的所有记忆，返回删除数量
+    ///
+    /// 记忆通过 `source_id` 关联轮次，需在删除轮次之前调用。
+    /// 早期版本以会话 ID 作为 `source_id`，这类记录同样会被删除。
+    async fn delete_by_conversation(&self, session_id: &str) -> Result<u64>;
+
+    /// 按与查询向量的余弦相似度检索记忆
+    ///
+    /// 先按 `query` 的筛选条件过滤，再取相似度不低于 `min_similarity` 的 `(记忆, 相似度)`
+    /// 按相似度降序排列，最后按 `query` 的分页截取。没有嵌入向量或维度不一致的记忆不参与比较。
+    async fn search_by_embedding(
+        &self,
+        query_vector: &[f32],
+        query: &MemoryQuery,
+        min_similarity: f32,
+    ) -> Result<Vec<(Memory, f32)>>;
 }
 
 /// Memory 仓储实现
@@ -127,23 +153,10 @@ impl MemoryRepositoryImpl {
 impl MemoryRepository for MemoryRepositoryImpl {
     async fn create(&self, memory: &Memory) -> Result<Memory> {
         let memory = memory.clone();
-        let memory_json = serde_json::to_string(&memory).unwrap_or_else(|_| "{}".to_string());
+        let memory_json = serde_json::to_string(&memory)?;
 
-        let query = format!(
-            "CREATE memory SET id = '{}', tenant_id = '{}', user_id = '{}', memory_type = '{}', content = '{}', gist = '{}', embedding = {}, importance = {}, status = '{}', version = {}, created_at = '{}', updated_at = '{}'",
-            memory.id,
-            memory.tenant_id,
-            memory.user_id,
-            memory.memory_type,
-            memory.content.replace("'", "\\'"),
-            memory.gist.replace("'", "\\'"),
-            "[]", // embedding will be set separately
-            memory.importance,
-            memory.status,
-            memory.version,
-            memory.created_at.to_rfc3339(),
-            memory.updated_at.to_rfc3339(),
-        );
+        // 整条记录写入，保留来源、标签与向量等字段
+        let query = format!("CREATE memory CONTENT {}", memory_json);
 
         self.execute_query(&query).await?;
         Ok(memory)
@@ -157,12 +170,22 @@ impl MemoryRepository for MemoryRepositoryImpl {
             if let Some(json) = item.as_object() {
                 if let Some(result) = json.get("result").and_then(|r| r.as_array()) {
                     if let Some(memory_json) = result.first() {
-                        let memory = serde_json::from_value(memory_json.clone()).map_err(|e| {
+                        let mut memory: Memory = serde_json::from_value(memory_json.clone()).map_err(|e| {
                             crate::error::AppError::Database(format!(
                                 "Failed to deserialize memory: {}",
                                 e
                             ))
                         })?;
+
+                        // 读取即视为一次访问，计数失败不影响读取结果
+                        match self.increment_access_count(&memory.id).await {
+                            Ok(()) => {
+                                memory.access_count += 1;
+                                memory.mark_accessed();
+                            }
+                            Err(e) => tracing::warn!("Failed to increment access count for memory {}: {}", memory.id, e),
+                        }
+
                         return Ok(Some(memory));
                     }
                 }
@@ -239,16 +262,7 @@ impl MemoryRepository for MemoryRepositoryImpl {
         limit: usize,
         start: usize,
     ) -> Result<Vec<Memory>> {
-        let type_filter = match memory_type {
-            Some(t) => format!("AND memory_type = '{}'", t),
-            None => String::new(),
-        };
-
-        let query = format!(
-            "SELECT * FROM memory WHERE user_id = '{}' {} ORDER BY created_at DESC LIMIT {} START {}",
-            user_id, type_filter, limit, start
-        );
-
+        let query = list_by_user_query(user_id, memory_type, limit, start);
         let results = self.execute_query(&query).await?;
         Ok(self.parse_results(&results))
     }
@@ -276,58 +290,7 @@ impl MemoryRepository for MemoryRepositoryImpl {
     }
 
     async fn search(&self, query: &MemoryQuery) -> Result<Vec<Memory>> {
-        // 构建查询条件
-        let mut conditions = Vec::new();
-
-        if let Some(user_id) = &query.user_id {
-            conditions.push(format!("user_id = '{}'", user_id));
-        }
-
-        if !query.memory_types.is_empty() {
-            let types: Vec<String> = query
-                .memory_types
-                .iter()
-                .map(|t| format!("'{}'", format!("{}", t)))
-                .collect();
-            conditions.push(format!("memory_type IN [{}]", types.join(",")));
-        }
-
-        if !query.sources.is_empty() {
-            let sources: Vec<String> = query
-                .sources
-                .iter()
-                .map(|s| format!("'{}'", format!("{}", s)))
-                .collect();
-            conditions.push(format!("source IN [{}]", sources.join(",")));
-        }
-
-        if let Some(min_importance) = query.min_importance {
-            conditions.push(format!("importance >= {}", min_importance));
-        }
-
-        if !query.statuses.is_empty() {
-            let statuses: Vec<String> = query
-                .statuses
-                .iter()
-                .map(|s| format!("'{}'", format!("{}", s)))
-                .collect();
-            conditions.push(format!("status IN [{}]", statuses.join(",")));
-        }
-
-        let where_clause = if conditions.is_empty() {
-            String::new()
-        } else {
-            format!("WHERE {}", conditions.join(" AND "))
-        };
-
-        let limit = query.page_size;
-        let start = (query.page - 1) * query.page_size;
-
-        let sql = format!(
-            "SELECT * FROM memory {} ORDER BY created_at DESC LIMIT {} START {}",
-            where_clause, limit, start
-        );
-
+        let sql = build_search_query(query);
         let results = self.execute_query(&sql).await?;
         Ok(self.parse_results(&results))
     }
@@ -365,8 +328,62 @@ impl MemoryRepository for MemoryRepositoryImpl {
             storage_size_bytes: 0,
         })
     }
+
+    async fn increment_access_count(&self, id: &str) -> Result<()> {
+        self.execute_query(&access_count_query(id)).await?;
+        Ok(())
+    }
+
+    async fn list_hot(&self, user_id: &str, limit: usize) -> Result<Vec<Memory>> {
+        let query = format!(
+            "SELECT * FROM memory WHERE user_id = '{}' AND status = 'active' ORDER BY access_count DESC LIMIT {}",
+            user_id, limit
+        );
+        let results = self.execute_query(&query).await?;
+        Ok(self.parse_results(&results))
+    }
+
+    async fn list_by_source_id(&self, source_id: &str) -> Result<Vec<Memory>> {
+        let results = self.execute_query(&source_id_query(source_id)).await?;
+        Ok(self.parse_results(&results))
+    }
+
+    async fn delete_by_conversation(&self, session_id: &str) -> Result<u64> {
+        let results = self
+            .execute_query(&conversation_delete_query(session_id))
+            .await?;
+
+        let mut deleted = 0;
+        for item in &results {
+            if let Some(json) = item.as_object() {
+                if let Some(result) = json.get("result").and_then(|r| r.as_array()) {
+                    deleted += result.len() as u64;
+                }
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    async fn search_by_embedding(
+        &self,
+        query_vector: &[f32],
+        query: &MemoryQuery,
+        min_similarity: f32,
+    ) -> Result<Vec<(Memory, f32)>> {
+        let results = self
+            .execute_query(&embedding_candidates_query(query))
+            .await?;
+        Ok(rank_by_embedding(
+            query_vector,
+            self.parse_results(&results),
+            query,
+            min_similarity,
+        ))
+    }
 }
 
+
 impl MemoryRepositoryImpl {
     async fn count_by_type(&self, user_id: &str, memory_type: &str) -> Result<u64> {
         let query = format!(
@@ -455,4 +472,307 @@ impl MemoryRepositoryImpl {
 
         Ok(0)
     }
+
+}
+
+/// 将记忆查询条件转换为 SurrealQL
+fn build_search_query(query: &MemoryQuery) -> String {
+    let conditions = search_conditions(query);
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
+
+    let limit = query.page_size;
+    let start = query.page.saturating_sub(1) * query.page_size;
+
+    format!(
+        "SELECT * FROM memory {} ORDER BY created_at DESC LIMIT {} START {}",
+        where_clause, limit, start
+    )
+}
+
+/// 记忆查询的筛选条件（不含分页）
+fn search_conditions(query: &MemoryQuery) -> Vec<String> {
+    let mut conditions = Vec::new();
+
+    if let Some(user_id) = &query.user_id {
+        conditions.push(format!("user_id = '{}'", user_id.replace("'", "\\'")));
+    }
+
+    if !query.memory_types.is_empty() {
+        let types: Vec<String> = query
+            .memory_types
+            .iter()
+            .map(|t| format!("'{t}'"))
+            .collect();
+        conditions.push(format!("memory_type IN [{}]", types.join(",")));
+    }
+
+    if !query.tags.is_empty() {
+        let tags: Vec<String> = query.tags.iter().map(|t| format!("'{}'", t)).collect();
+        conditions.push(format!("tags CONTAINSANY [{}]", tags.join(",")));
+    }
+
+    if !query.sources.is_empty() {
+        let sources: Vec<String> = query
+            .sources
+            .iter()
+            .map(|s| format!("'{s}'"))
+            .collect();
+        conditions.push(format!("source IN [{}]", sources.join(",")));
+    }
+
+    if let Some(min_importance) = query.min_importance {
+        conditions.push(format!("importance >= {}", min_importance));
+    }
+
+    if !query.statuses.is_empty() {
+        let statuses: Vec<String> = query
+            .statuses
+            .iter()
+            .map(|s| format!("'{s}'"))
+            .collect();
+        conditions.push(format!("status IN [{}]", statuses.join(",")));
+    }
+
+    conditions
+}
+
+/// 按用户列出记忆，指定 `memory_type` 时追加类型过滤
+fn list_by_user_query(
+    user_id: &str,
+    memory_type: Option<&str>,
+    limit: usize,
+    start: usize,
+) -> String {
+    let user_id = user_id.replace("'", "\\'");
+    match memory_type {
+        Some(memory_type) => format!(
+            "SELECT * FROM memory WHERE user_id = '{}' AND memory_type = '{}' ORDER BY created_at DESC LIMIT {} START {}",
+            user_id,
+            memory_type.to_lowercase().replace("'", "\\'"),
+            limit,
+            start
+        ),
+        None => format!(
+            "SELECT * FROM memory WHERE user_id = '{}' ORDER BY created_at DESC LIMIT {} START {}",
+            user_id, limit, start
+        ),
+    }
+}
+
+/// 列出某个来源产生的全部记忆
+fn source_id_query(source_id: &str) -> String {
+    format!(
+        "SELECT * FROM memory WHERE source_id = '{}' ORDER BY created_at ASC",
+        source_id.replace("'", "\\'")
+    )
+}
+
+/// 列出满足筛选条件且带嵌入向量的全部记忆，供进程内计算相似度后再分页
+fn embedding_candidates_query(query: &MemoryQuery) -> String {
+    let mut conditions = search_conditions(query);
+    conditions.push("array::len(embedding ?? []) > 0".to_string());
+    format!("SELECT * FROM memory WHERE {}", conditions.join(" AND "))
+}
+
+/// 余弦相似度（维度不一致或零向量时为 0）
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+
+    dot / (norm_a * norm_b)
+}
+
+/// 按与查询向量的余弦相似度对记忆降序排序，过滤低于 `min_similarity` 的结果后按 `query` 分页
+pub(crate) fn rank_by_embedding(
+    query_vector: &[f32],
+    memories: Vec<Memory>,
+    query: &MemoryQuery,
+    min_similarity: f32,
+) -> Vec<(Memory, f32)> {
+    let mut scored: Vec<(Memory, f32)> = memories
+        .into_iter()
+        .filter_map(|memory| {
+            let embedding = memory.embedding.as_deref()?;
+            if embedding.is_empty() || embedding.len() != query_vector.len() {
+                return None;
+            }
+            let score = cosine_similarity(query_vector, embedding);
+            (score >= min_similarity).then_some((memory, score))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let start = query.page.saturating_sub(1) as usize * query.page_size as usize;
+    scored
+        .into_iter()
+        .skip(start)
+        .take(query.page_size as usize)
+        .collect()
+}
+
+/// 删除某个对话的轮次产生的全部记忆（不分页，避免遗留孤儿记录）
+///
+/// 同时匹配以轮次 ID 和以会话 ID（旧格式）作为 `source_id` 的记忆。
+fn conversation_delete_query(session_id: &str) -> String {
+    let session_id = session_id.replace("'", "\\'");
+    format!(
+        "DELETE FROM memory WHERE source = '{}' AND (source_id = '{}' OR source_id IN \
+         (SELECT VALUE record::id(id) FROM turn WHERE session_id = '{}')) RETURN BEFORE",
+        MemorySource::Conversation,
+        session_id,
+        session_id
+    )
+}
+
+/// 记忆 ID 转为转义后的记录 ID 字面量（`x` -> `memory:⟨x⟩`），防止 ID 中的 `⟩` 截断语句
+fn memory_record_id(id: &str) -> String {
+    let id = id.strip_prefix("memory:").unwrap_or(id);
+    let id = id
+        .strip_prefix('⟨')
+        .and_then(|id| id.strip_suffix('⟩'))
+        .unwrap_or(id);
+    format!("memory:⟨{}⟩", id.replace('\\', "\\\\").replace('⟩', "\\⟩"))
+}
+
+/// 记忆访问计数加一，直接定位记录而不是按 `id` 字段比较字符串
+fn access_count_query(id: &str) -> String {
+    format!(
+        "UPDATE {} SET access_count += 1, accessed_at = time::now() RETURN NONE",
+        memory_record_id(id)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_count_query_targets_record_id() {
+        assert_eq!(
+            access_count_query("mem_1"),
+            "UPDATE memory:⟨mem_1⟩ SET access_count += 1, accessed_at = time::now() RETURN NONE"
+        );
+        assert_eq!(
+            access_count_query("memory:⟨mem_1⟩"),
+            access_count_query("mem_1")
+        );
+        assert!(
+            access_count_query("x⟩; DELETE memory")
+                .starts_with("UPDATE memory:⟨x\\⟩; DELETE memory⟩")
+        );
+    }
+
+    #[test]
+    fn test_search_query_filters_tags() {
+        let query = MemoryQuery::new()
+            .for_user("user_1")
+            .with_tags(&["feature-x"])
+            .with_pagination(1, 20);
+
+        let sql = build_search_query(&query);
+        assert!(sql.contains("user_id = 'user_1' AND tags CONTAINSANY ['feature-x']"));
+    }
+
+    #[test]
+    fn test_search_query_filters_min_importance() {
+        let query = MemoryQuery {
+            min_importance: Some(0.7),
+            page: 1,
+            page_size: 50,
+            ..Default::default()
+        };
+
+        let sql = build_search_query(&query);
+        assert!(sql.contains("WHERE importance >= 0.7"));
+        assert!(sql.ends_with("LIMIT 50 START 0"));
+
+        let unfiltered = build_search_query(&MemoryQuery::default());
+        assert!(!unfiltered.contains("importance"));
+    }
+
+    #[test]
+    fn test_list_by_user_query_branches_on_memory_type() {
+        let sql = list_by_user_query("user_1", Some("episodic"), 10, 0);
+        assert!(sql.contains("WHERE user_id = 'user_1' AND memory_type = 'episodic' ORDER BY"));
+        assert!(sql.ends_with("LIMIT 10 START 0"));
+
+        let unfiltered = list_by_user_query("user_1", None, 10, 20);
+        assert!(unfiltered.contains("WHERE user_id = 'user_1' ORDER BY"));
+        assert!(!unfiltered.contains("memory_type"));
+        assert!(unfiltered.ends_with("LIMIT 10 START 20"));
+    }
+
+    #[test]
+    fn test_source_id_query_escapes_quotes() {
+        let sql = source_id_query("turn_1");
+        assert_eq!(
+            sql,
+            "SELECT * FROM memory WHERE source_id = 'turn_1' ORDER BY created_at ASC"
+        );
+        assert!(source_id_query("it's").contains("source_id = 'it\\'s'"));
+    }
+
+    #[test]
+    fn test_rank_by_embedding() {
+        let memory = |content: &str, embedding: Option<Vec<f32>>| {
+            let mut memory = Memory::new(
+                "user_1",
+                crate::models::memory::MemoryType::Semantic,
+                content,
+                MemorySource::Conversation,
+            );
+            memory.embedding = embedding;
+            memory
+        };
+        let memories = vec![
+            memory("orthogonal", Some(vec![0.0, 1.0])),
+            memory("close", Some(vec![0.9, 0.1])),
+            memory("missing", None),
+            memory("wrong dimension", Some(vec![1.0, 0.0, 0.0])),
+            memory("identical", Some(vec![1.0, 0.0])),
+        ];
+
+        let query = MemoryQuery::new().for_user("user_1").with_pagination(1, 10);
+        let ranked = rank_by_embedding(&[1.0, 0.0], memories.clone(), &query, 0.5);
+        let contents: Vec<&str> = ranked.iter().map(|(m, _)| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["identical", "close"]);
+        assert!((ranked[0].1 - 1.0).abs() < 1e-6);
+        assert!(ranked[0].1 > ranked[1].1);
+
+        // 分页在相似度排序之后进行
+        let second_page = MemoryQuery::new().with_pagination(2, 1);
+        let ranked = rank_by_embedding(&[1.0, 0.0], memories, &second_page, 0.5);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].0.content, "close");
+
+        let sql = embedding_candidates_query(&query.with_min_importance(0.5));
+        assert_eq!(
+            sql,
+            "SELECT * FROM memory WHERE user_id = 'user_1' AND importance >= 0.5 \
+             AND array::len(embedding ?? []) > 0"
+        );
+    }
+
+    #[test]
+    fn test_conversation_delete_query_removes_all_session_memories() {
+        let sql = conversation_delete_query("session_1");
+        assert!(sql.starts_with("DELETE FROM memory"));
+        assert!(sql.contains(
+            "source = 'conversation' AND (source_id = 'session_1' OR source_id IN \
+             (SELECT VALUE record::id(id) FROM turn WHERE session_id = 'session_1'))"
+        ));
+        assert!(!sql.contains("LIMIT"));
+    }
 }
@@ -3,6 +3,7 @@
 //! 定义 Hippos 的核心数据结构：Session, Turn, IndexRecord 等。
 //! 以及 AI 记忆系统的新模型：Memory, Profile, Pattern, Entity
 
+pub mod api_key_repository;
 pub mod entity;
 pub mod entity_repository;
 pub mod index_record;
@@ -14,6 +15,7 @@ pub mod pattern_repository;
 pub mod profile;
 pub mod profile_repository;
 pub mod session;
+pub mod snapshot;
 pub mod turn;
 
 pub use entity::*;
@@ -5,6 +5,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::config::config::PatternQualityThresholds;
+
 /// 模式类型枚举
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum PatternType {
@@ -41,6 +43,33 @@ impl std::fmt::Display for PatternType {
     }
 }
 
+/// 模式状态枚举
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum PatternStatus {
+    /// 活跃模式 - 可被检索和推荐
+    #[default]
+    #[serde(rename = "active")]
+    Active,
+
+    /// 已弃用 - 不再推荐，保留用于历史追溯
+    #[serde(rename = "deprecated")]
+    Deprecated,
+
+    /// 已归档 - 软删除
+    #[serde(rename = "archived")]
+    Archived,
+}
+
+impl std::fmt::Display for PatternStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatternStatus::Active => write!(f, "active"),
+            PatternStatus::Deprecated => write!(f, "deprecated"),
+            PatternStatus::Archived => write!(f, "archived"),
+        }
+    }
+}
+
 /// 问题模式
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pattern {
@@ -117,6 +146,10 @@ pub struct Pattern {
     /// 版本号
     pub version: u32,
 
+    /// 模式状态（旧记录缺省为 Active）
+    #[serde(default)]
+    pub status: PatternStatus,
+
     /// 祖先模式 ID（用于模式继承）
     pub parent_pattern_id: Option<String>,
 }
@@ -198,6 +231,9 @@ pub struct PatternQuery {
     /// 是否只返回公开模式
     pub public_only: bool,
 
+    /// 是否包含已弃用和已归档的模式（默认只返回活跃模式）
+    pub include_archived: bool,
+
     /// 分页
     pub page: u32,
     pub page_size: u32,
@@ -237,6 +273,7 @@ impl Pattern {
             is_public: false,
             confidence: 0.5,
             version: 1,
+            status: PatternStatus::Active,
             parent_pattern_id: None,
         }
     }
@@ -348,9 +385,16 @@ impl Pattern {
         }
     }
 
-    /// 是否为高质量模式
+    /// 是否为高质量模式（使用默认阈值）
     pub fn is_high_quality(&self) -> bool {
-        self.confidence >= 0.7 && self.success_rate() >= 0.7
+        self.meets_quality(&PatternQualityThresholds::default())
+    }
+
+    /// 是否满足给定的质量阈值
+    pub fn meets_quality(&self, thresholds: &PatternQualityThresholds) -> bool {
+        self.confidence >= thresholds.min_confidence
+            && self.success_rate() >= thresholds.min_success_rate
+            && self.usage_count >= thresholds.min_usage_count
     }
 
     /// 模式匹配度（检查触发条件）
@@ -366,6 +410,68 @@ impl Pattern {
             .map(|s| s.trim())
             .all(|keyword| input_lower.contains(keyword))
     }
+
+    /// 与另一个模式的相似度（0.0 - 1.0）
+    ///
+    /// 对 `name + problem + solution` 的小写文本取字符三元组集合，计算 Jaccard 相似度，
+    /// 用于发现功能相同的重复模式。
+    pub fn similarity_score(&self, other: &Pattern) -> f32 {
+        let a = self.trigrams();
+        let b = other.trigrams();
+        if a.is_empty() && b.is_empty() {
+            return 1.0;
+        }
+
+        let intersection = a.intersection(&b).count();
+        let union = a.union(&b).count();
+        intersection as f32 / union as f32
+    }
+
+    /// 导出为 CommonMark 文档
+    ///
+    /// 依次包含名称、类型、描述、问题、解决方案、详细解释、示例与标签，空的可选部分省略。
+    pub fn export_to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n**Type:** {}\n", self.name, self.pattern_type);
+        if !self.description.is_empty() {
+            markdown.push_str(&format!("\n{}\n", self.description));
+        }
+
+        markdown.push_str(&format!("\n## Problem\n\n{}\n", self.problem));
+        markdown.push_str(&format!("\n## Solution\n\n{}\n", self.solution));
+        if let Some(explanation) = &self.explanation {
+            markdown.push_str(&format!("\n## Explanation\n\n{}\n", explanation));
+        }
+
+        if !self.examples.is_empty() {
+            markdown.push_str("\n## Examples\n");
+            for (i, example) in self.examples.iter().enumerate() {
+                markdown.push_str(&format!(
+                    "\n### Example {}\n\n**Input:**\n\n{}\n\n**Output:**\n\n{}\n",
+                    i + 1,
+                    example.input,
+                    example.output
+                ));
+            }
+        }
+
+        if !self.tags.is_empty() {
+            markdown.push_str("\n## Tags\n\n");
+            for tag in &self.tags {
+                markdown.push_str(&format!("- {}\n", tag));
+            }
+        }
+
+        markdown
+    }
+
+    /// `name + problem + solution` 的字符三元组集合
+    fn trigrams(&self) -> std::collections::HashSet<[char; 3]> {
+        let text: Vec<char> = format!("{} {} {}", self.name, self.problem, self.solution)
+            .to_lowercase()
+            .chars()
+            .collect();
+        text.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+    }
 }
 
 /// 模式推荐结果
@@ -462,6 +568,27 @@ mod tests {
         assert!(!pattern.matches_trigger("学习 python"));
     }
 
+    #[test]
+    fn test_export_to_markdown() {
+        let mut pattern = Pattern::new(
+            "user_123",
+            PatternType::BestPractice,
+            "Connection Pooling",
+            "Connections are expensive",
+            "Reuse a fixed pool",
+        );
+        pattern.add_example("Open 100 connections", "Use a pool of 10", 0.9, None);
+        pattern.add_tag("database");
+
+        let markdown = pattern.export_to_markdown();
+        assert!(markdown.starts_with("# Connection Pooling\n\n**Type:** best_practice\n"));
+        assert!(markdown.contains("\n## Problem\n\nConnections are expensive\n"));
+        assert!(markdown.contains("\n## Solution\n\nReuse a fixed pool\n"));
+        assert!(markdown.contains("\n### Example 1\n\n**Input:**\n\nOpen 100 connections\n"));
+        assert!(markdown.ends_with("\n## Tags\n\n- database\n"));
+        assert!(!markdown.contains("## Explanation"));
+    }
+
     #[test]
     fn test_high_quality_pattern() {
         let mut pattern = Pattern::new(
@@ -483,4 +610,76 @@ mod tests {
         pattern.confidence = 0.5;
         assert!(!pattern.is_high_quality());
     }
+
+    #[test]
+    fn test_meets_quality_thresholds() {
+        let mut pattern = Pattern::new(
+            "user_123",
+            PatternType::BestPractice,
+            "模式",
+            "问题",
+            "解决方案",
+        );
+        pattern.confidence = 0.8;
+        pattern.record_usage("u1", "i", "o", 0.9, None, None);
+
+        let strict = PatternQualityThresholds {
+            min_confidence: 0.9,
+            ..Default::default()
+        };
+        assert!(!pattern.meets_quality(&strict));
+
+        let needs_usage = PatternQualityThresholds {
+            min_usage_count: 2,
+            ..Default::default()
+        };
+        assert!(!pattern.meets_quality(&needs_usage));
+
+        pattern.record_usage("u2", "i", "o", 0.9, None, None);
+        assert!(pattern.meets_quality(&needs_usage));
+    }
+
+    #[test]
+    fn test_pattern_similarity_score() {
+        let a = Pattern::new(
+            "user_123",
+            PatternType::ProblemSolution,
+            "Retry with backoff",
+            "Flaky network calls",
+            "Retry with exponential backoff",
+        );
+        let b = Pattern::new(
+            "user_456",
+            PatternType::BestPractice,
+            "retry with backoff",
+            "Flaky network calls",
+            "Retry with exponential backoff",
+        );
+        let c = Pattern::new(
+            "user_123",
+            PatternType::Workflow,
+            "部署流程",
+            "发布新版本",
+            "先构建镜像再滚动更新",
+        );
+
+        assert_eq!(a.similarity_score(&b), 1.0);
+        assert!(a.similarity_score(&c) < 0.1);
+        assert_eq!(a.similarity_score(&c), c.similarity_score(&a));
+    }
+
+    #[test]
+    fn test_pattern_status_defaults_to_active() {
+        let pattern = Pattern::new("user_123", PatternType::Skill, "模式", "问题", "解决方案");
+        assert_eq!(pattern.status, PatternStatus::Active);
+
+        let mut json = serde_json::to_value(&pattern).unwrap();
+        assert_eq!(json["status"], "active");
+
+        // 旧记录没有 status 字段时视为活跃
+        json.as_object_mut().unwrap().remove("status");
+        let legacy: Pattern = serde_json::from_value(json).unwrap();
+        assert_eq!(legacy.status, PatternStatus::Active);
+        assert_eq!(PatternStatus::Deprecated.to_string(), "deprecated");
+    }
 }
@@ -3,9 +3,12 @@
 //! 提供 Pattern 数据持久化服务
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::marker::PhantomData;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use crate::error::Result;
-use crate::models::pattern::{Pattern, PatternQuery, PatternStats, PatternUsage};
+use crate::models::pattern::{Pattern, PatternQuery, PatternStats, PatternStatus, PatternUsage};
 use crate::storage::surrealdb::SurrealPool;
 
 /// Pattern 仓储 trait
@@ -20,11 +23,19 @@ pub trait PatternRepository {
     /// 更新模式
     async fn update(&self, id: &str, pattern: &Pattern) -> Result<Option<Pattern>>;
 
-    /// 删除模式
+    /// 删除模式（物理删除）
     async fn delete(&self, id: &str) -> Result<bool>;
 
-    /// 列出模式
-    async fn list(&self, limit: usize, start: usize) -> Result<Vec<Pattern>>;
+    /// 设置模式状态（软删除、弃用、恢复）
+    async fn set_status(&self, id: &str, status: PatternStatus) -> Result<bool>;
+
+    /// 列出模式（默认只返回活跃模式）
+    async fn list(
+        &self,
+        limit: usize,
+        start: usize,
+        include_archived: bool,
+    ) -> Result<Vec<Pattern>>;
 
     /// 统计数量
     async fn count(&self) -> Result<u64>;
@@ -35,6 +46,19 @@ pub trait PatternRepository {
     /// 记录使用
     async fn record_usage(&self, pattern_id: &str, usage: &PatternUsage) -> Result<String>;
 
+    /// 列出最近的使用记录（按使用时间倒序，可选起始时间过滤）
+    async fn list_recent_usages(
+        &self,
+        limit: usize,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<PatternUsage>>;
+
+    /// 保存模式版本快照（在更新前调用，记录旧版本）
+    async fn save_version(&self, pattern: &Pattern) -> Result<()>;
+
+    /// 获取模式的历史版本快照
+    async fn get_version(&self, pattern_id: &str, version: u32) -> Result<Option<Pattern>>;
+
     /// 获取统计信息
     async fn get_stats(&self) -> Result<PatternStats>;
 
@@ -42,10 +66,15 @@ pub trait PatternRepository {
     async fn match_patterns(&self, input: &str, limit: u32) -> Result<Vec<Pattern>>;
 }
 
+/// `trigger` 字段全文检索的分析器与索引定义
+const PATTERN_SEARCH_INDEX_DDL: &str = "DEFINE ANALYZER IF NOT EXISTS pattern_analyzer TOKENIZERS class FILTERS lowercase; DEFINE INDEX IF NOT EXISTS pattern_trigger_idx ON TABLE pattern FIELDS trigger SEARCH ANALYZER pattern_analyzer BM25;";
+
 /// Pattern 仓储实现
 #[derive(Clone)]
 pub struct PatternRepositoryImpl {
     pool: SurrealPool,
+    /// `trigger` 全文索引是否已就绪（未就绪时匹配退回关键词扫描）
+    search_index_ready: Arc<AtomicBool>,
     _marker: PhantomData<Pattern>,
 }
 
@@ -53,10 +82,35 @@ impl PatternRepositoryImpl {
     pub fn new(pool: SurrealPool) -> Self {
         Self {
             pool,
+            search_index_ready: Arc::new(AtomicBool::new(false)),
             _marker: PhantomData,
         }
     }
 
+    /// 定义 `trigger` 字段的全文检索索引（启动时调用，可重复执行）
+    pub async fn ensure_search_index(&self) -> Result<()> {
+        let results = self.execute_query(PATTERN_SEARCH_INDEX_DDL).await?;
+        if let Some(error) = statement_error(&results) {
+            return Err(crate::error::AppError::Database(format!(
+                "Failed to define pattern search index: {}",
+                error
+            )));
+        }
+
+        self.search_index_ready.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// 使用全文索引匹配 `trigger`，按 BM25 得分排序
+    async fn match_patterns_full_text(&self, input: &str, limit: u32) -> Result<Vec<Pattern>> {
+        let sql = full_text_match_query(input, limit, Self::status_condition(false).as_deref());
+        let results = self.execute_query(&sql).await?;
+        if let Some(error) = statement_error(&results) {
+            return Err(crate::error::AppError::Database(error));
+        }
+        Ok(self.parse_results(&results))
+    }
+
     /// 执行 SurrealDB 查询
     async fn execute_query(&self, query: &str) -> Result<Vec<serde_json::Value>> {
         let config = self.pool.config();
@@ -98,6 +152,19 @@ impl PatternRepositoryImpl {
         Ok(results)
     }
 
+    /// 活跃状态过滤条件；没有 status 字段的旧记录视为活跃
+    fn status_condition(include_archived: bool) -> Option<String> {
+        if include_archived {
+            None
+        } else {
+            Some(format!(
+                "status NOT IN ['{}', '{}']",
+                PatternStatus::Deprecated,
+                PatternStatus::Archived
+            ))
+        }
+    }
+
     /// 从查询结果解析
     fn parse_results(&self, results: &[serde_json::Value]) -> Vec<Pattern> {
         let mut patterns = Vec::new();
@@ -115,6 +182,24 @@ impl PatternRepositoryImpl {
         }
         patterns
     }
+
+    /// 从查询结果解析使用记录
+    fn parse_usages(&self, results: &[serde_json::Value]) -> Vec<PatternUsage> {
+        let mut usages = Vec::new();
+        for item in results {
+            if let Some(json) = item.as_object() {
+                if let Some(result) = json.get("result").and_then(|r| r.as_array()) {
+                    for usage_json in result {
+                        match serde_json::from_value(usage_json.clone()) {
+                            Ok(usage) => usages.push(usage),
+                            Err(e) => tracing::warn!("Failed to deserialize pattern usage: {}", e),
+                        }
+                    }
+                }
+            }
+        }
+        usages
+    }
 }
 
 #[async_trait]
@@ -128,7 +213,7 @@ impl PatternRepository for PatternRepositoryImpl {
         let tags_json = serde_json::to_string(&pattern.tags).unwrap_or_else(|_| "[]".to_string());
 
         let query = format!(
-            "CREATE pattern SET id = '{}', tenant_id = '{}', pattern_type = '{}', name = '{}', description = '{}', trigger = '{}', context = '{}', problem = '{}', solution = '{}', explanation = {}, examples = {}, success_count = {}, failure_count = {}, avg_outcome = {}, tags = {}, created_by = '{}', created_at = '{}', updated_at = '{}', usage_count = {}, is_public = {}, confidence = {}, version = {}",
+            "CREATE pattern SET id = '{}', tenant_id = '{}', pattern_type = '{}', name = '{}', description = '{}', trigger = '{}', context = '{}', problem = '{}', solution = '{}', explanation = {}, examples = {}, success_count = {}, failure_count = {}, avg_outcome = {}, tags = {}, created_by = '{}', created_at = '{}', updated_at = '{}', usage_count = {}, is_public = {}, confidence = {}, version = {}, status = '{}'",
             pattern.id,
             pattern.tenant_id,
             pattern.pattern_type,
@@ -151,6 +236,7 @@ impl PatternRepository for PatternRepositoryImpl {
             pattern.is_public,
             pattern.confidence,
             pattern.version,
+            pattern.status,
         );
 
         self.execute_query(&query).await?;
@@ -189,7 +275,7 @@ impl PatternRepository for PatternRepositoryImpl {
         let tags_json = serde_json::to_string(&pattern.tags).unwrap_or_else(|_| "[]".to_string());
 
         let query = format!(
-            "UPDATE pattern SET name = '{}', description = '{}', trigger = '{}', context = '{}', problem = '{}', solution = '{}', explanation = {}, examples = {}, success_count = {}, failure_count = {}, avg_outcome = {}, tags = {}, updated_at = '{}', usage_count = {}, confidence = {}, version = {} WHERE id = '{}'",
+            "UPDATE pattern SET name = '{}', description = '{}', trigger = '{}', context = '{}', problem = '{}', solution = '{}', explanation = {}, examples = {}, success_count = {}, failure_count = {}, avg_outcome = {}, tags = {}, updated_at = '{}', usage_count = {}, confidence = {}, version = {}, status = '{}' WHERE id = '{}'",
             pattern.name.replace("'", "\\'"),
             pattern.description.replace("'", "\\'"),
             pattern.trigger.replace("'", "\\'"),
@@ -206,6 +292,7 @@ impl PatternRepository for PatternRepositoryImpl {
             pattern.usage_count,
             pattern.confidence,
             pattern.version,
+            pattern.status,
             id,
         );
 
@@ -228,10 +315,38 @@ impl PatternRepository for PatternRepositoryImpl {
         Ok(false)
     }
 
-    async fn list(&self, limit: usize, start: usize) -> Result<Vec<Pattern>> {
+    async fn set_status(&self, id: &str, status: PatternStatus) -> Result<bool> {
         let query = format!(
-            "SELECT * FROM pattern ORDER BY usage_count DESC LIMIT {} START {}",
-            limit, start
+            "UPDATE pattern SET status = '{}', updated_at = '{}' WHERE id = {}",
+            status,
+            Utc::now().to_rfc3339(),
+            id
+        );
+        let results = self.execute_query(&query).await?;
+
+        for item in &results {
+            if let Some(json) = item.as_object() {
+                if let Some(result) = json.get("result").and_then(|r| r.as_array()) {
+                    return Ok(!result.is_empty());
+                }
+            }
+        }
+
+        Ok(false)
+    }
+
+    async fn list(
+        &self,
+        limit: usize,
+        start: usize,
+        include_archived: bool,
+    ) -> Result<Vec<Pattern>> {
+        let where_clause = Self::status_condition(include_archived)
+            .map(|c| format!("WHERE {} ", c))
+            .unwrap_or_default();
+        let query = format!(
+            "SELECT * FROM pattern {}ORDER BY usage_count DESC LIMIT {} START {}",
+            where_clause, limit, start
         );
         let results = self.execute_query(&query).await?;
         Ok(self.parse_results(&results))
@@ -279,13 +394,21 @@ impl PatternRepository for PatternRepositoryImpl {
         }
 
         if let Some(keyword) = &query.keyword {
-            conditions.push(format!("name CONTAINS '{}' OR description CONTAINS '{}' OR problem CONTAINS '{}'", keyword, keyword, keyword));
+            conditions.push(format!("(name CONTAINS '{}' OR description CONTAINS '{}' OR problem CONTAINS '{}')", keyword, keyword, keyword));
+        }
+
+        if let Some(created_by) = &query.created_by {
+            conditions.push(format!("created_by = '{}'", created_by));
         }
 
         if query.public_only {
             conditions.push("is_public = true".to_string());
         }
 
+        if let Some(status) = Self::status_condition(query.include_archived) {
+            conditions.push(status);
+        }
+
         let where_clause = if conditions.is_empty() {
             String::new()
         } else {
@@ -337,6 +460,70 @@ impl PatternRepository for PatternRepositoryImpl {
         Ok(usage.id.clone())
     }
 
+    async fn list_recent_usages(
+        &self,
+        limit: usize,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<PatternUsage>> {
+        let where_clause = since
+            .map(|t| format!("WHERE used_at >= '{}'", t.to_rfc3339()))
+            .unwrap_or_default();
+
+        let query = format!(
+            "SELECT * FROM pattern_usage {} ORDER BY used_at DESC LIMIT {}",
+            where_clause, limit
+        );
+
+        let results = self.execute_query(&query).await?;
+        Ok(self.parse_usages(&results))
+    }
+
+    async fn save_version(&self, pattern: &Pattern) -> Result<()> {
+        let snapshot_json = serde_json::to_string(pattern).map_err(|e| {
+            crate::error::AppError::Database(format!("Failed to serialize pattern: {}", e))
+        })?;
+
+        let query = format!(
+            "DELETE pattern_version WHERE pattern_id = '{}' AND version = {}; CREATE pattern_version SET pattern_id = '{}', version = {}, snapshot = {}, created_at = '{}'",
+            pattern.id,
+            pattern.version,
+            pattern.id,
+            pattern.version,
+            snapshot_json,
+            Utc::now().to_rfc3339(),
+        );
+
+        self.execute_query(&query).await?;
+        Ok(())
+    }
+
+    async fn get_version(&self, pattern_id: &str, version: u32) -> Result<Option<Pattern>> {
+        let query = format!(
+            "SELECT snapshot FROM pattern_version WHERE pattern_id = '{}' AND version = {} LIMIT 1",
+            pattern_id, version
+        );
+        let results = self.execute_query(&query).await?;
+
+        for item in &results {
+            if let Some(snapshot) = item
+                .get("result")
+                .and_then(|r| r.as_array())
+                .and_then(|r| r.first())
+                .and_then(|v| v.get("snapshot"))
+            {
+                let pattern = serde_json::from_value(snapshot.clone()).map_err(|e| {
+                    crate::error::AppError::Database(format!(
+                        "Failed to deserialize pattern version: {}",
+                        e
+                    ))
+                })?;
+                return Ok(Some(pattern));
+            }
+        }
+
+        Ok(None)
+    }
+
     async fn get_stats(&self) -> Result<PatternStats> {
         let query = "SELECT count() FROM pattern GROUP ALL";
         let results = self.execute_query(&query).await?;
@@ -376,27 +563,110 @@ impl PatternRepository for PatternRepositoryImpl {
     }
 
     async fn match_patterns(&self, input: &str, limit: u32) -> Result<Vec<Pattern>> {
-        let input_lower = input.to_lowercase();
-        let keywords: Vec<&str> = input_lower.split_whitespace().collect();
+        if input.trim().is_empty() {
+            return Ok(Vec::new());
+        }
 
-        // 构建触发条件匹配查询
-        let conditions: Vec<String> = keywords
-            .iter()
-            .map(|k| format!("trigger CONTAINS '{}'", k))
-            .collect();
+        if self.search_index_ready.load(Ordering::SeqCst) {
+            match self.match_patterns_full_text(input, limit).await {
+                Ok(patterns) => return Ok(patterns),
+                Err(e) => tracing::warn!(
+                    "Full-text pattern match failed, falling back to keyword scan: {}",
+                    e
+                ),
+            }
+        }
 
-        let where_clause = if conditions.is_empty() {
-            String::new()
-        } else {
-            format!("WHERE {}", conditions.join(" OR "))
-        };
+        let sql = keyword_match_query(input, limit, Self::status_condition(false).as_deref());
+        let results = self.execute_query(&sql).await?;
+        Ok(self.parse_results(&results))
+    }
+}
 
-        let sql = format!(
-            "SELECT * FROM pattern {} ORDER BY usage_count DESC LIMIT {}",
-            where_clause, limit
+/// 返回 HTTP 查询结果中第一条失败语句的错误信息
+fn statement_error(results: &[serde_json::Value]) -> Option<String> {
+    results
+        .iter()
+        .find(|item| item.get("status").and_then(|s| s.as_str()) == Some("ERR"))
+        .map(|item| {
+            item.get("result")
+                .and_then(|r| r.as_str())
+                .unwrap_or("unknown error")
+                .to_string()
+        })
+}
+
+/// 构建全文检索匹配语句
+fn full_text_match_query(input: &str, limit: u32, status_condition: Option<&str>) -> String {
+    let status_filter = status_condition
+        .map(|c| format!(" AND {}", c))
+        .unwrap_or_default();
+    format!(
+        "SELECT *, search::score(1) AS score FROM pattern WHERE trigger @1@ '{}'{} ORDER BY score DESC LIMIT {}",
+        input.replace('\\', "\\\\").replace('\'', "\\'"),
+        status_filter,
+        limit
+    )
+}
+
+/// 构建关键词扫描匹配语句（全文索引不可用时的回退）
+fn keyword_match_query(input: &str, limit: u32, status_condition: Option<&str>) -> String {
+    let input_lower = input.to_lowercase();
+    let conditions: Vec<String> = input_lower
+        .split_whitespace()
+        .map(|k| format!("trigger CONTAINS '{}'", k.replace('\'', "\\'")))
+        .collect();
+
+    let mut where_parts = Vec::new();
+    if !conditions.is_empty() {
+        where_parts.push(format!("({})", conditions.join(" OR ")));
+    }
+    where_parts.extend(status_condition.map(str::to_string));
+    let where_clause = if where_parts.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", where_parts.join(" AND "))
+    };
+
+    format!(
+        "SELECT * FROM pattern {} ORDER BY usage_count DESC LIMIT {}",
+        where_clause, limit
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_text_match_query() {
+        let status = "status NOT IN ['deprecated', 'archived']";
+        let sql = full_text_match_query("tokio can't spawn", 5, Some(status));
+        assert!(sql.contains("WHERE trigger @1@ 'tokio can\\'t spawn' AND status NOT IN"));
+        assert!(sql.contains("ORDER BY score DESC LIMIT 5"));
+    }
+
+    #[test]
+    fn test_keyword_match_query() {
+        let sql = keyword_match_query("Tokio Spawn", 3, None);
+        assert_eq!(
+            sql,
+            "SELECT * FROM pattern WHERE (trigger CONTAINS 'tokio' OR trigger CONTAINS 'spawn') ORDER BY usage_count DESC LIMIT 3"
         );
+    }
 
-        let results = self.execute_query(&sql).await?;
-        Ok(self.parse_results(&results))
+    #[test]
+    fn test_statement_error() {
+        let ok = vec![serde_json::json!({ "status": "OK", "result": [] })];
+        assert_eq!(statement_error(&ok), None);
+
+        let failed = vec![
+            serde_json::json!({ "status": "OK", "result": null }),
+            serde_json::json!({ "status": "ERR", "result": "There was a problem" }),
+        ];
+        assert_eq!(
+            statement_error(&failed).as_deref(),
+            Some("There was a problem")
+        );
     }
 }
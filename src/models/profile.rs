@@ -6,6 +6,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::models::memory::{Memory, MemoryType};
+
 /// 用户画像
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
@@ -79,6 +81,14 @@ pub struct Profile {
 
     /// 变更历史
     pub change_history: Vec<ProfileChange>,
+
+    /// 从 Profile 类型记忆同步的次数
+    #[serde(default)]
+    pub sync_count: u64,
+
+    /// 最后一次从记忆同步的时间
+    #[serde(default)]
+    pub last_synced_at: Option<DateTime<Utc>>,
 }
 
 /// 用户告诉 Agent 的重要事实
@@ -262,6 +272,8 @@ impl Profile {
             last_verified: None,
             version: 1,
             change_history: Vec::new(),
+            sync_count: 0,
+            last_synced_at: None,
         }
     }
 
@@ -403,6 +415,58 @@ impl Profile {
         self.change_history.push(change);
     }
 
+    /// 将 Profile 类型记忆中的陈述合并到画像，返回实际同步的记忆数
+    pub fn merge_from_memories(&mut self, memories: &[Memory]) -> usize {
+        memories
+            .iter()
+            .filter(|memory| self.sync_from_memory(memory))
+            .count()
+    }
+
+    /// 从单条 Profile 类型记忆更新画像
+    ///
+    /// 识别 "prefers X"、"works at Y"、"expertise in Z" 形式的陈述，
+    /// 至少更新一个字段时累加 `sync_count` 并返回 `true`。
+    pub fn sync_from_memory(&mut self, memory: &Memory) -> bool {
+        if memory.memory_type != MemoryType::Profile {
+            return false;
+        }
+
+        let statements = parse_profile_statements(&memory.content);
+        if statements.is_empty() {
+            return false;
+        }
+
+        let reason = format!("synced from memory {}", memory.id);
+        for statement in statements {
+            match statement {
+                ProfileStatement::Preference(value) => {
+                    self.add_preference(&value, serde_json::Value::Bool(true), Some(&reason));
+                }
+                ProfileStatement::Organization(value) => {
+                    self.update_basic_info(None, None, Some(&value), Some(&reason));
+                }
+                ProfileStatement::Expertise(value) => {
+                    let fact = format!("Expertise in {}", value);
+                    if !self.facts.iter().any(|f| f.fact == fact) {
+                        self.add_fact(
+                            &fact,
+                            ProfileFactCategory::Technical,
+                            Some(&memory.id),
+                            memory.confidence,
+                        );
+                    }
+                }
+            }
+        }
+
+        let now = Utc::now();
+        self.sync_count += 1;
+        self.last_synced_at = Some(now);
+        self.updated_at = now;
+        true
+    }
+
     /// 获取验证的事实数量
     pub fn verified_facts_count(&self) -> usize {
         self.facts.iter().filter(|f| f.verified).count()
@@ -417,6 +481,51 @@ impl Profile {
     }
 }
 
+/// 从记忆内容中识别出的画像陈述
+#[derive(Debug, Clone, PartialEq)]
+enum ProfileStatement {
+    /// "prefers X"
+    Preference(String),
+    /// "works at Y"
+    Organization(String),
+    /// "expertise in Z"
+    Expertise(String),
+}
+
+/// 由陈述值构造画像陈述
+type StatementConstructor = fn(String) -> ProfileStatement;
+
+/// 陈述前缀与对应的画像字段
+const PROFILE_STATEMENT_MARKERS: [(&str, StatementConstructor); 3] = [
+    ("prefers ", ProfileStatement::Preference),
+    ("works at ", ProfileStatement::Organization),
+    ("expertise in ", ProfileStatement::Expertise),
+];
+
+/// 解析记忆内容中的画像陈述（不区分大小写，值截止到标点或 "and"）
+fn parse_profile_statements(content: &str) -> Vec<ProfileStatement> {
+    let lowercase = content.to_ascii_lowercase();
+    let mut statements = Vec::new();
+
+    for (marker, statement) in PROFILE_STATEMENT_MARKERS {
+        for (start, _) in lowercase.match_indices(marker) {
+            let rest = &content[start + marker.len()..];
+            let end = rest
+                .find(['.', ',', ';', '!', '?', '\n'])
+                .into_iter()
+                .chain(rest.to_ascii_lowercase().find(" and "))
+                .min()
+                .unwrap_or(rest.len());
+            let value = rest[..end].trim();
+            if !value.is_empty() {
+                statements.push(statement(value.to_string()));
+            }
+        }
+    }
+
+    statements
+}
+
 /// 画像对比结果（用于合并）
 #[derive(Debug, Clone)]
 pub struct ProfileComparison {
@@ -479,4 +588,63 @@ mod tests {
         let tech_facts = profile.get_facts_by_category(&ProfileFactCategory::Technical);
         assert_eq!(tech_facts.len(), 1);
     }
+
+    #[test]
+    fn test_parse_profile_statements() {
+        let statements = parse_profile_statements(
+            "User prefers dark mode. Works at Acme Corp; has expertise in Rust, Go",
+        );
+        assert_eq!(
+            statements,
+            vec![
+                ProfileStatement::Preference("dark mode".to_string()),
+                ProfileStatement::Organization("Acme Corp".to_string()),
+                ProfileStatement::Expertise("Rust".to_string()),
+            ]
+        );
+        assert!(parse_profile_statements("Nothing to see here").is_empty());
+    }
+
+    #[test]
+    fn test_merge_from_memories() {
+        use crate::models::memory::MemorySource;
+
+        let mut profile = Profile::new("user_123");
+        let memories = vec![
+            Memory::new(
+                "user_123",
+                MemoryType::Profile,
+                "User prefers dark mode",
+                MemorySource::Conversation,
+            ),
+            Memory::new(
+                "user_123",
+                MemoryType::Profile,
+                "Works at Acme and has expertise in Rust",
+                MemorySource::Conversation,
+            ),
+            Memory::new(
+                "user_123",
+                MemoryType::Episodic,
+                "User prefers tea",
+                MemorySource::Conversation,
+            ),
+        ];
+
+        assert_eq!(profile.merge_from_memories(&memories), 2);
+        assert_eq!(profile.sync_count, 2);
+        assert!(profile.last_synced_at.is_some());
+        assert_eq!(
+            profile.preferences.get("dark mode"),
+            Some(&serde_json::Value::Bool(true))
+        );
+        assert!(!profile.preferences.contains_key("tea"));
+        assert_eq!(profile.organization.as_deref(), Some("Acme"));
+        assert_eq!(
+            profile
+                .get_facts_by_category(&ProfileFactCategory::Technical)
+                .len(),
+            1
+        );
+    }
 }
@@ -32,6 +32,9 @@ pub trait ProfileRepository {
     /// 统计数量
     async fn count(&self) -> Result<u64>;
 
+    /// 统计指定用户的画像数量
+    async fn count_by_user(&self, user_id: &str) -> Result<u64>;
+
     /// 根据条件查询
     async fn search(&self, query: &ProfileQuery) -> Result<Vec<Profile>>;
 
@@ -128,7 +131,7 @@ impl ProfileRepository for ProfileRepositoryImpl {
             serde_json::to_string(&profile.change_history).unwrap_or_else(|_| "[]".to_string());
 
         let query = format!(
-            "CREATE profile SET id = '{}', tenant_id = '{}', user_id = '{}', name = {}, role = {}, organization = {}, location = {}, preferences = {}, communication_style = {}, technical_level = {}, language = {}, facts = {}, interests = {}, working_hours = {}, common_tasks = {}, tools_used = {}, created_at = '{}', updated_at = '{}', confidence = {}, version = {}, change_history = {}",
+            "CREATE profile SET id = '{}', tenant_id = '{}', user_id = '{}', name = {}, role = {}, organization = {}, location = {}, preferences = {}, communication_style = {}, technical_level = {}, language = {}, facts = {}, interests = {}, working_hours = {}, common_tasks = {}, tools_used = {}, created_at = '{}', updated_at = '{}', confidence = {}, version = {}, change_history = {}, sync_count = {}, last_synced_at = {}",
             profile.id,
             profile.tenant_id,
             profile.user_id,
@@ -150,6 +153,8 @@ impl ProfileRepository for ProfileRepositoryImpl {
             profile.confidence,
             profile.version,
             change_history_json,
+            profile.sync_count,
+            profile.last_synced_at.map(|t| format!("'{}'", t.to_rfc3339())).unwrap_or_else(|| "NONE".to_string()),
         );
 
         self.execute_query(&query).await?;
@@ -211,7 +216,7 @@ impl ProfileRepository for ProfileRepositoryImpl {
         let facts_json = serde_json::to_string(&profile.facts).unwrap_or_else(|_| "[]".to_string());
 
         let query = format!(
-            "UPDATE profile SET name = {}, role = {}, organization = {}, location = {}, preferences = {}, communication_style = {}, technical_level = {}, language = {}, facts = {}, interests = {}, working_hours = {}, common_tasks = {}, tools_used = {}, updated_at = '{}', confidence = {}, version = {} WHERE id = '{}'",
+            "UPDATE profile SET name = {}, role = {}, organization = {}, location = {}, preferences = {}, communication_style = {}, technical_level = {}, language = {}, facts = {}, interests = {}, working_hours = {}, common_tasks = {}, tools_used = {}, updated_at = '{}', confidence = {}, version = {}, sync_count = {}, last_synced_at = {} WHERE id = '{}'",
             profile.name.as_ref().map(|s| format!("'{}'", s.replace("'", "\\'"))).unwrap_or_else(|| "NONE".to_string()),
             profile.role.as_ref().map(|s| format!("'{}'", s.replace("'", "\\'"))).unwrap_or_else(|| "NONE".to_string()),
             profile.organization.as_ref().map(|s| format!("'{}'", s.replace("'", "\\'"))).unwrap_or_else(|| "NONE".to_string()),
@@ -228,6 +233,8 @@ impl ProfileRepository for ProfileRepositoryImpl {
             profile.updated_at.to_rfc3339(),
             profile.confidence,
             profile.version,
+            profile.sync_count,
+            profile.last_synced_at.map(|t| format!("'{}'", t.to_rfc3339())).unwrap_or_else(|| "NONE".to_string()),
             id,
         );
 
@@ -278,6 +285,19 @@ impl ProfileRepository for ProfileRepositoryImpl {
         Ok(0)
     }
 
+    async fn count_by_user(&self, user_id: &str) -> Result<u64> {
+        let query = format!(
+            "SELECT count() FROM profile WHERE user_id = '{}' GROUP ALL",
+            user_id.replace("'", "\\'")
+        );
+        let results = self.execute_query(&query).await?;
+
+        Ok(results
+            .iter()
+            .find_map(|item| item.get("result")?.as_array()?.first()?.get("count")?.as_u64())
+            .unwrap_or(0))
+    }
+
     async fn search(&self, query: &ProfileQuery) -> Result<Vec<Profile>> {
         let mut conditions = Vec::new();
 
@@ -164,6 +164,32 @@ impl Session {
     }
 }
 
+/// 带轮次统计的会话
+///
+/// 由单次查询得到：会话字段与 `turn_count`、`last_turn_at` 位于同一行。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionWithStats {
+    /// 会话
+    #[serde(flatten)]
+    pub session: Session,
+
+    /// 轮次数量
+    #[serde(default, deserialize_with = "deserialize_count")]
+    pub turn_count: u64,
+
+    /// 最后一轮的时间
+    #[serde(default)]
+    pub last_turn_at: Option<DateTime<Utc>>,
+}
+
+/// 子查询在没有轮次时返回 NONE，视为 0
+fn deserialize_count<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<u64>::deserialize(deserializer)?.unwrap_or(0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,6 +214,21 @@ mod tests {
         assert!(stats.last_indexed_at.is_none());
     }
 
+    #[test]
+    fn test_session_config_round_trip() {
+        // 旧记录没有 config 字段时使用默认配置
+        let json = r#"{"id": "session:abc123", "tenant_id": "test", "name": "test", "created_at": "2024-01-15T10:30:00Z", "last_active_at": "2024-01-15T10:30:00Z"}"#;
+        let session: Session = serde_json::from_str(json).unwrap();
+        assert_eq!(session.config.max_turns, 0);
+        assert_eq!(session.config.index_refresh_interval, 0);
+
+        let json = r#"{"id": "session:abc123", "tenant_id": "test", "name": "test", "created_at": "2024-01-15T10:30:00Z", "last_active_at": "2024-01-15T10:30:00Z", "config": {"max_turns": 50, "index_refresh_interval": 3000}}"#;
+        let session: Session = serde_json::from_str(json).unwrap();
+        assert_eq!(session.config.max_turns, 50);
+        assert_eq!(session.config.index_refresh_interval, 3000);
+        assert!(!session.config.auto_summarize);
+    }
+
     #[test]
     fn test_deserialize_id_with_plain_string() {
         let json = r#"{"id": "session:abc123", "tenant_id": "test", "name": "test", "created_at": "2024-01-15T10:30:00Z", "last_active_at": "2024-01-15T10:30:00Z", "status": "active", "config": {}, "stats": {}}"#;
@@ -319,4 +360,21 @@ mod tests {
         assert!(session.is_status("Paused"));
         assert!(!session.is_status("Active"));
     }
+
+    #[test]
+    fn test_deserialize_session_with_stats() {
+        let json = r#"{"id": "session:abc123", "tenant_id": "test", "name": "test", "created_at": "2024-01-15T10:30:00Z", "last_active_at": "2024-01-15T10:30:00Z", "turn_count": 3, "last_turn_at": "2024-01-15T11:00:00Z"}"#;
+        let stats: SessionWithStats = serde_json::from_str(json).unwrap();
+        assert_eq!(stats.session.id, "session:abc123");
+        assert_eq!(stats.turn_count, 3);
+        assert_eq!(
+            stats.last_turn_at,
+            Some(Utc.with_ymd_and_hms(2024, 1, 15, 11, 0, 0).unwrap())
+        );
+
+        let empty = r#"{"id": "session:abc123", "tenant_id": "test", "name": "test", "created_at": "2024-01-15T10:30:00Z", "last_active_at": "2024-01-15T10:30:00Z", "turn_count": null, "last_turn_at": null}"#;
+        let stats: SessionWithStats = serde_json::from_str(empty).unwrap();
+        assert_eq!(stats.turn_count, 0);
+        assert!(stats.last_turn_at.is_none());
+    }
 }
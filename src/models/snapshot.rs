@@ -0,0 +1,92 @@
+//! 会话快照模型
+//!
+//! 快照保存某一时刻会话的全部轮次（含脱水数据），用于在破坏性操作前建立检查点，
+//! 之后可将会话恢复到快照时的状态。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::models::turn::Turn;
+
+/// 会话快照
+///
+/// 创建后不再修改，对应 `session_snapshot` 表。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    /// 快照 ID
+    pub snapshot_id: String,
+    /// 所属会话 ID
+    pub session_id: String,
+    /// 创建时间
+    pub created_at: DateTime<Utc>,
+    /// 快照包含的轮次数量
+    pub turn_count: u64,
+    /// 快照数据（按 turn_number 升序的轮次数组）
+    pub snapshot_data: serde_json::Value,
+}
+
+impl SessionSnapshot {
+    /// 由会话当前的轮次创建快照
+    pub fn capture(session_id: &str, turns: &[Turn]) -> Result<Self> {
+        let mut turns = turns.to_vec();
+        turns.sort_by_key(|t| t.turn_number);
+
+        Ok(Self {
+            snapshot_id: format!("snapshot_{}", Uuid::new_v4()),
+            session_id: session_id.to_string(),
+            created_at: Utc::now(),
+            turn_count: turns.len() as u64,
+            snapshot_data: serde_json::to_value(&turns)?,
+        })
+    }
+
+    /// 还原快照中的轮次
+    pub fn turns(&self) -> Result<Vec<Turn>> {
+        Ok(serde_json::from_value(self.snapshot_data.clone())?)
+    }
+
+    /// 快照点：快照中最大的轮次编号（空快照为 0）
+    pub fn snapshot_point(&self) -> Result<u64> {
+        Ok(self
+            .turns()?
+            .iter()
+            .map(|t| t.turn_number)
+            .max()
+            .unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::turn::DehydratedData;
+
+    #[test]
+    fn test_capture_and_restore_turns() {
+        let mut dehydrated = Turn::new("session_1", 1, "Hello");
+        dehydrated.dehydrated = Some(DehydratedData {
+            gist: "greeting".to_string(),
+            ..Default::default()
+        });
+        let turns = vec![Turn::new("session_1", 2, "Hi there"), dehydrated];
+
+        let snapshot = SessionSnapshot::capture("session_1", &turns).unwrap();
+        assert!(snapshot.snapshot_id.starts_with("snapshot_"));
+        assert_eq!(snapshot.turn_count, 2);
+        assert_eq!(snapshot.snapshot_point().unwrap(), 2);
+
+        let restored = snapshot.turns().unwrap();
+        assert_eq!(restored[0].turn_number, 1);
+        assert_eq!(restored[0].dehydrated.as_ref().unwrap().gist, "greeting");
+        assert_eq!(restored[1].raw_content, "Hi there");
+    }
+
+    #[test]
+    fn test_empty_snapshot() {
+        let snapshot = SessionSnapshot::capture("session_1", &[]).unwrap();
+        assert_eq!(snapshot.turn_count, 0);
+        assert_eq!(snapshot.snapshot_point().unwrap(), 0);
+    }
+}
@@ -14,6 +14,28 @@ pub enum MessageType {
     System,
 }
 
+impl std::fmt::Display for MessageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageType::User => write!(f, "user"),
+            MessageType::Assistant => write!(f, "assistant"),
+            MessageType::System => write!(f, "system"),
+        }
+    }
+}
+
+impl MessageType {
+    /// 解析消息类型（不区分大小写），无法识别时返回 `None`
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "user" => Some(MessageType::User),
+            "assistant" => Some(MessageType::Assistant),
+            "system" => Some(MessageType::System),
+            _ => None,
+        }
+    }
+}
+
 /// 内容状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ContentStatus {
@@ -342,6 +364,17 @@ mod tests {
         assert!(system.contains("System") || system.contains("system"));
     }
 
+    #[test]
+    fn test_message_type_parse() {
+        assert_eq!(MessageType::parse("user"), Some(MessageType::User));
+        assert_eq!(
+            MessageType::parse("Assistant"),
+            Some(MessageType::Assistant)
+        );
+        assert_eq!(MessageType::parse("SYSTEM"), Some(MessageType::System));
+        assert_eq!(MessageType::parse("tool"), None);
+    }
+
     #[test]
     fn test_content_status_serialization() {
         let pending = serde_json::to_string(&ContentStatus::Pending).unwrap();
@@ -0,0 +1,126 @@
+//! 领域事件总线
+//!
+//! 服务在存储操作完成后发布类型化的领域事件（如 `session:created`），
+//! 事件序列化为 JSON 后转发到 `ConnectionManager` 的广播通道，由 WebSocket / SSE 订阅者接收。
+//! 每个事件都带有所属租户，订阅端须用 `is_visible_to` 过滤，避免跨租户泄露。
+
+use chrono::Utc;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// 事件广播通道容量（与 `ConnectionManager` 一致）
+pub const EVENT_BUS_CAPACITY: usize = 1024;
+
+/// 会话创建事件
+pub const SESSION_CREATED: &str = "session:created";
+/// 轮次创建事件
+pub const TURN_CREATED: &str = "turn:created";
+/// 记忆创建事件
+pub const MEMORY_CREATED: &str = "memory:created";
+
+/// 领域事件总线
+///
+/// 事件格式为 `{"event": <类型>, "tenant_id": <租户>, "data": <载荷>, "timestamp": <RFC 3339>}`，
+/// WebSocket 转发时以 `event` 字段作为订阅主题。
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<String>,
+}
+
+impl EventBus {
+    /// 基于已有的广播通道创建
+    pub fn new(tx: broadcast::Sender<String>) -> Self {
+        Self { tx }
+    }
+
+    /// 底层广播通道的发送端
+    pub fn sender(&self) -> broadcast::Sender<String> {
+        self.tx.clone()
+    }
+
+    /// 订阅事件
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.tx.subscribe()
+    }
+
+    /// 发布属于 `tenant_id` 的事件
+    ///
+    /// 没有订阅者或载荷序列化失败时仅记录日志，不影响调用方的业务流程。
+    pub fn publish<E: Serialize>(&self, event_type: &str, tenant_id: &str, payload: E) {
+        let data = match serde_json::to_value(payload) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::warn!("Failed to serialize {} event: {}", event_type, e);
+                return;
+            }
+        };
+        let event = serde_json::json!({
+            "event": event_type,
+            "tenant_id": tenant_id,
+            "data": data,
+            "timestamp": Utc::now().to_rfc3339(),
+        });
+
+        if self.tx.send(event.to_string()).is_err() {
+            tracing::trace!("No subscribers for {} event", event_type);
+        }
+    }
+}
+
+/// 事件能否转发给属于 `tenant_id` 的连接
+///
+/// 带租户的领域事件只转发给同一租户的连接；未认证的连接（`None`）
+/// 只能收到不带租户的连接事件（如 `connected`）。
+pub fn is_visible_to(event: &serde_json::Value, tenant_id: Option<&str>) -> bool {
+    match event.get("tenant_id") {
+        None | Some(serde_json::Value::Null) => true,
+        Some(event_tenant) => tenant_id.is_some_and(|tenant_id| event_tenant == tenant_id),
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        let (tx, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self::new(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_forwards_json_event() {
+        let bus = EventBus::default();
+        let mut rx = bus.subscribe();
+
+        bus.publish(
+            SESSION_CREATED,
+            "tenant_1",
+            serde_json::json!({"id": "session_1"}),
+        );
+
+        let event: serde_json::Value = serde_json::from_str(&rx.recv().await.unwrap()).unwrap();
+        assert_eq!(event["event"], SESSION_CREATED);
+        assert_eq!(event["tenant_id"], "tenant_1");
+        assert_eq!(event["data"]["id"], "session_1");
+        assert!(event["timestamp"].is_string());
+    }
+
+    #[test]
+    fn test_publish_without_subscribers() {
+        EventBus::default().publish(TURN_CREATED, "tenant_1", "turn_1");
+    }
+
+    #[test]
+    fn test_is_visible_to_filters_other_tenants() {
+        let event = serde_json::json!({"event": TURN_CREATED, "tenant_id": "tenant_1"});
+        assert!(is_visible_to(&event, Some("tenant_1")));
+        assert!(!is_visible_to(&event, Some("tenant_2")));
+        assert!(!is_visible_to(&event, None));
+
+        let connected = serde_json::json!({"event": "connected", "id": "conn_1"});
+        assert!(is_visible_to(&connected, None));
+        assert!(is_visible_to(&connected, Some("tenant_2")));
+    }
+}
@@ -0,0 +1,256 @@
+//! 服务层日志上下文
+//!
+//! 为服务方法附加租户 / 会话 / 轮次信息，使错误日志可以直接定位到具体资源。
+//! 嵌套的服务调用只在最外层记录一次错误。
+
+use std::fmt::Display;
+use std::future::Future;
+
+use tracing::field::Empty;
+use tracing::{Instrument, Level, Span};
+
+use crate::error::AppError;
+
+tokio::task_local! {
+    /// 当前最外层服务调用的 span；存在时说明处于嵌套调用中
+    static OUTERMOST_SPAN: Span;
+}
+
+/// 服务错误的日志级别
+pub trait LogSeverity {
+    fn log_level(&self) -> Level {
+        Level::ERROR
+    }
+}
+
+/// 资源不存在记为 debug，其余客户端错误记为 warn，服务端错误记为 error
+impl LogSeverity for AppError {
+    fn log_level(&self) -> Level {
+        let (status, _): (u16, String) = self.into();
+        match status {
+            404 => Level::DEBUG,
+            400..=499 => Level::WARN,
+            _ => Level::ERROR,
+        }
+    }
+}
+
+impl LogSeverity for String {}
+
+/// 服务调用的日志上下文
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogContext {
+    /// 租户 ID（仅知道会话 ID 时为空，可在读取会话后通过 `record_tenant` 补充）
+    pub tenant_id: Option<String>,
+    /// 会话 ID
+    pub session_id: Option<String>,
+    /// 轮次 ID
+    pub turn_id: Option<String>,
+}
+
+impl LogContext {
+    /// 创建租户级上下文
+    pub fn new(tenant_id: impl Into<String>) -> Self {
+        Self {
+            tenant_id: Some(tenant_id.into()),
+            session_id: None,
+            turn_id: None,
+        }
+    }
+
+    /// 创建仅已知会话 ID 的上下文
+    ///
+    /// 租户在读取会话之后才知道，届时调用 `record_tenant` 写入 span。
+    pub fn for_session(session_id: impl Into<String>) -> Self {
+        Self::default().with_session(session_id)
+    }
+
+    /// 设置会话 ID
+    pub fn with_session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// 设置轮次 ID
+    pub fn with_turn(mut self, turn_id: impl Into<String>) -> Self {
+        self.turn_id = Some(turn_id.into());
+        self
+    }
+
+    /// 创建携带上下文字段的 span
+    pub fn span(&self, operation: &'static str) -> Span {
+        let span = tracing::error_span!(
+            "service",
+            operation,
+            tenant_id = Empty,
+            session_id = Empty,
+            turn_id = Empty
+        );
+        self.record(&span);
+        span
+    }
+
+    /// 将上下文字段写入已有 span
+    pub fn record(&self, span: &Span) {
+        if let Some(tenant_id) = &self.tenant_id {
+            span.record("tenant_id", tenant_id.as_str());
+        }
+        if let Some(session_id) = &self.session_id {
+            span.record("session_id", session_id.as_str());
+        }
+        if let Some(turn_id) = &self.turn_id {
+            span.record("turn_id", turn_id.as_str());
+        }
+    }
+
+    /// 为当前最外层服务调用补充租户 ID（不在服务调用中时忽略）
+    pub fn record_tenant(tenant_id: &str) {
+        let _ = OUTERMOST_SPAN.try_with(|span| {
+            span.record("tenant_id", tenant_id);
+        });
+    }
+
+    /// 记录一次失败的服务调用，上下文字段由所在的 span 提供
+    pub fn log_error(operation: &str, error: &(impl Display + LogSeverity)) {
+        match error.log_level() {
+            Level::ERROR => tracing::error!(error = %error, operation, "operation failed"),
+            Level::WARN => tracing::warn!(error = %error, operation, "operation failed"),
+            _ => tracing::debug!(error = %error, operation, "operation failed"),
+        }
+    }
+
+    /// 在上下文 span 内执行 `fut`
+    ///
+    /// 只有最外层调用在返回错误前记录日志，嵌套调用直接把错误交给外层。
+    pub async fn run<T, E, F>(&self, operation: &'static str, fut: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: Display + LogSeverity,
+    {
+        let span = self.span(operation);
+        if OUTERMOST_SPAN.try_with(|_| ()).is_ok() {
+            return fut.instrument(span).await;
+        }
+
+        let logged = async { fut.await.inspect_err(|e| Self::log_error(operation, e)) };
+        OUTERMOST_SPAN
+            .scope(span.clone(), logged.instrument(span))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+
+    /// 记录事件级别与写入的 `tenant_id` 字段
+    #[derive(Clone, Default)]
+    struct Captured {
+        levels: Arc<Mutex<Vec<Level>>>,
+        tenants: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Visit for Captured {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "tenant_id" {
+                self.tenants.lock().unwrap().push(value.to_string());
+            }
+        }
+
+        fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Captured {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            self.levels.lock().unwrap().push(*event.metadata().level());
+        }
+
+        fn on_record(
+            &self,
+            _span: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            _ctx: Context<'_, S>,
+        ) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    fn capture() -> (Captured, tracing::subscriber::DefaultGuard) {
+        let captured = Captured::default();
+        let subscriber = tracing_subscriber::registry().with(captured.clone());
+        (captured, tracing::subscriber::set_default(subscriber))
+    }
+
+    #[test]
+    fn test_log_context_builders() {
+        let ctx = LogContext::new("tenant_1")
+            .with_session("session_1")
+            .with_turn("turn_1");
+
+        assert_eq!(ctx.tenant_id.as_deref(), Some("tenant_1"));
+        assert_eq!(ctx.session_id.as_deref(), Some("session_1"));
+        assert_eq!(ctx.turn_id.as_deref(), Some("turn_1"));
+
+        let ctx = LogContext::for_session("session_2");
+        assert!(ctx.tenant_id.is_none());
+        assert_eq!(ctx.session_id.as_deref(), Some("session_2"));
+        assert!(ctx.turn_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_run_passes_result_through() {
+        let ctx = LogContext::new("tenant_1");
+
+        let ok: Result<u32, String> = ctx.run("test.ok", async { Ok(7) }).await;
+        assert_eq!(ok, Ok(7));
+
+        let err: Result<u32, String> = ctx.run("test.err", async { Err("boom".to_string()) }).await;
+        assert_eq!(err, Err("boom".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_nested_run_logs_error_once() {
+        let (captured, _guard) = capture();
+        let outer = LogContext::for_session("session_1");
+
+        let result: Result<(), AppError> = outer
+            .run("test.outer", async {
+                LogContext::record_tenant("tenant_1");
+                LogContext::for_session("session_1")
+                    .run("test.inner", async {
+                        Err(AppError::Database("boom".to_string()))
+                    })
+                    .await
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(*captured.levels.lock().unwrap(), vec![Level::ERROR]);
+        assert_eq!(*captured.tenants.lock().unwrap(), vec!["tenant_1"]);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_log_below_error() {
+        let (captured, _guard) = capture();
+        let ctx = LogContext::new("tenant_1");
+
+        let _: Result<(), AppError> = ctx
+            .run("test.not_found", async {
+                Err(AppError::NotFound("missing".to_string()))
+            })
+            .await;
+        let _: Result<(), AppError> = ctx
+            .run("test.validation", async {
+                Err(AppError::Validation("bad".to_string()))
+            })
+            .await;
+
+        assert_eq!(
+            *captured.levels.lock().unwrap(),
+            vec![Level::DEBUG, Level::WARN]
+        );
+    }
+}
@@ -2,36 +2,252 @@
 //!
 //! 提供 Prometheus 指标、结构化日志和健康检查。
 
+pub mod event_bus;
+pub mod log_context;
+#[cfg(feature = "otel")]
+pub mod otel;
+
+pub use event_bus::EventBus;
+pub use log_context::LogContext;
+#[cfg(feature = "otel")]
+pub use otel::OtelGuard as TracingGuard;
+
 use axum::{Json, Router, response::IntoResponse, routing::get};
 
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicI64, AtomicU64, AtomicUsize, Ordering};
 use tokio::sync::Mutex;
 
 // ===== Simple Metrics (using atomics for zero-dep implementation) =====
 
+/// `turns_total` 的 `message_type` 标签值
+pub const TURN_MESSAGE_TYPES: [&str; 3] = ["user", "assistant", "system"];
+
+/// `memories_total` 的 `memory_type` 标签值
+pub const MEMORY_TYPES: [&str; 4] = ["episodic", "semantic", "procedural", "profile"];
+
+/// `patterns_total` 的 `pattern_type` 标签值
+pub const PATTERN_TYPES: [&str; 5] = [
+    "problem_solution",
+    "workflow",
+    "best_practice",
+    "common_error",
+    "skill",
+];
+
+/// `active_sessions_recent` 统计的活跃窗口（秒）
+pub const ACTIVE_SESSION_WINDOW_SECS: u64 = 300;
+
+/// 延迟直方图的默认桶边界（毫秒）
+pub const DEFAULT_LATENCY_BUCKETS_MS: [f64; 8] =
+    [5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0];
+
+/// 覆盖延迟直方图桶边界的环境变量（逗号分隔的毫秒值）
+pub const METRICS_BUCKETS_ENV: &str = "HIPPOS_METRICS_BUCKETS";
+
+/// 延迟直方图
+///
+/// 每个桶保存 `(上界毫秒, 累计计数)`，即延迟不超过上界的观测次数，
+/// 与 Prometheus `_bucket{le="..."}` 的语义一致。
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    pub buckets: Vec<(f64, AtomicU64)>,
+    /// 观测值总和（毫秒）
+    pub sum: AtomicU64,
+    pub count: AtomicU64,
+}
+
+impl Default for LatencyHistogram {
+    /// 使用 `HIPPOS_METRICS_BUCKETS` 指定的桶边界，未设置或无效时使用默认边界
+    fn default() -> Self {
+        Self::new(&latency_buckets_from_env())
+    }
+}
+
+impl LatencyHistogram {
+    /// 以给定桶边界（毫秒）创建直方图，边界会被排序并去重
+    pub fn new(bounds_ms: &[f64]) -> Self {
+        let mut bounds: Vec<f64> = bounds_ms.to_vec();
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+
+        Self {
+            buckets: bounds
+                .into_iter()
+                .map(|bound| (bound, AtomicU64::new(0)))
+                .collect(),
+            sum: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    /// 记录一次耗时
+    pub fn observe(&self, duration_ms: u64) {
+        for (bound, count) in &self.buckets {
+            if duration_ms as f64 <= *bound {
+                count.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        self.sum.fetch_add(duration_ms, Ordering::SeqCst);
+        self.count.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// 导出直方图快照
+    pub fn to_snapshot(&self) -> HistogramSnapshot {
+        HistogramSnapshot {
+            buckets: self
+                .buckets
+                .iter()
+                .map(|(bound, count)| HistogramBucket {
+                    le_ms: *bound,
+                    count: count.load(Ordering::SeqCst),
+                })
+                .collect(),
+            sum_ms: self.sum.load(Ordering::SeqCst),
+            count: self.count.load(Ordering::SeqCst),
+        }
+    }
+
+    /// 从快照恢复，桶边界与当前配置不一致时只恢复总和与次数
+    pub fn restore_from_snapshot(&self, snapshot: &HistogramSnapshot) {
+        let same_bounds = snapshot.buckets.len() == self.buckets.len()
+            && snapshot
+                .buckets
+                .iter()
+                .zip(&self.buckets)
+                .all(|(saved, (bound, _))| saved.le_ms == *bound);
+        if same_bounds {
+            for (saved, (_, count)) in snapshot.buckets.iter().zip(&self.buckets) {
+                count.store(saved.count, Ordering::SeqCst);
+            }
+        } else if !snapshot.buckets.is_empty() {
+            tracing::warn!("Latency histogram buckets changed; bucket counts not restored");
+        }
+        self.sum.store(snapshot.sum_ms, Ordering::SeqCst);
+        self.count.store(snapshot.count, Ordering::SeqCst);
+    }
+
+    /// 以 Prometheus 格式输出（单位换算为秒）
+    fn write_prometheus(&self, output: &mut String, name: &str, help: &str) {
+        let _ = writeln!(output, "# HELP {} {}", name, help);
+        let _ = writeln!(output, "# TYPE {} histogram", name);
+        for (bound, count) in &self.buckets {
+            let _ = writeln!(
+                output,
+                "{}_bucket{{le=\"{}\"}} {}",
+                name,
+                bound / 1000.0,
+                count.load(Ordering::SeqCst)
+            );
+        }
+        let count = self.count.load(Ordering::SeqCst);
+        let _ = writeln!(output, "{}_bucket{{le=\"+Inf\"}} {}", name, count);
+        let _ = writeln!(
+            output,
+            "{}_sum {}",
+            name,
+            self.sum.load(Ordering::SeqCst) as f64 / 1000.0
+        );
+        let _ = writeln!(output, "{}_count {}", name, count);
+    }
+}
+
+/// 解析桶边界配置（逗号分隔的毫秒值），存在无效值时返回 `None`
+fn parse_latency_buckets(value: &str) -> Option<Vec<f64>> {
+    let bounds: Vec<f64> = value
+        .split(',')
+        .map(|part| part.trim().parse::<f64>().ok())
+        .collect::<Option<_>>()?;
+    let valid = !bounds.is_empty() && bounds.iter().all(|b| b.is_finite() && *b > 0.0);
+    valid.then_some(bounds)
+}
+
+/// 从 `HIPPOS_METRICS_BUCKETS` 读取桶边界
+fn latency_buckets_from_env() -> Vec<f64> {
+    match std::env::var(METRICS_BUCKETS_ENV) {
+        Ok(value) => parse_latency_buckets(&value).unwrap_or_else(|| {
+            tracing::warn!(
+                "Invalid {}={:?}, using default latency buckets",
+                METRICS_BUCKETS_ENV,
+                value
+            );
+            DEFAULT_LATENCY_BUCKETS_MS.to_vec()
+        }),
+        Err(_) => DEFAULT_LATENCY_BUCKETS_MS.to_vec(),
+    }
+}
+
+/// 直方图的单个桶
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistogramBucket {
+    /// 桶上界（毫秒）
+    pub le_ms: f64,
+    /// 不超过上界的观测次数（累计）
+    pub count: u64,
+}
+
+/// 直方图快照
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistogramSnapshot {
+    pub buckets: Vec<HistogramBucket>,
+    pub sum_ms: u64,
+    pub count: u64,
+}
+
 /// 简单应用指标
 #[derive(Clone, Default)]
 pub struct AppMetrics {
     pub http_requests_total: Arc<AtomicU64>,
-    pub http_request_duration_sum: Arc<AtomicU64>,
+    /// 按 (请求方法, 状态码) 计数的 HTTP 请求
+    pub http_requests_by_method: Arc<DashMap<(String, u16), AtomicU64>>,
+    /// 按状态码计数的 HTTP 错误响应（状态码 >= 400）
+    pub http_errors_by_status: Arc<DashMap<u16, AtomicU64>>,
+    /// HTTP 请求耗时直方图
+    pub http_request_duration: Arc<LatencyHistogram>,
     pub active_connections: Arc<AtomicUsize>,
-    pub sessions_active: Arc<AtomicUsize>,
-    pub sessions_archived: Arc<AtomicUsize>,
+    /// 有符号存储，归档/删除时递减不会下溢
+    pub sessions_active: Arc<AtomicI64>,
+    pub sessions_archived: Arc<AtomicI64>,
+    /// 各租户最近 `ACTIVE_SESSION_WINDOW_SECS` 秒内活跃的会话数量（由后台任务定期刷新）
+    pub active_sessions_recent: Arc<parking_lot::RwLock<BTreeMap<String, u64>>>,
     pub turns_total: Arc<AtomicU64>,
     pub search_requests_total: Arc<AtomicU64>,
     pub search_latency_sum: Arc<AtomicU64>,
     pub errors_total: Arc<AtomicU64>,
+    /// 模式缓存命中次数
+    pub cache_hits_total: Arc<AtomicU64>,
+    /// 按 `TURN_MESSAGE_TYPES` 下标计数的轮次
+    pub turns_by_type: Arc<[AtomicU64; TURN_MESSAGE_TYPES.len()]>,
+    /// 按 `MEMORY_TYPES` 下标计数的记忆
+    pub memories_by_type: Arc<[AtomicU64; MEMORY_TYPES.len()]>,
+    /// 按 `PATTERN_TYPES` 下标计数的模式
+    pub patterns_by_type: Arc<[AtomicU64; PATTERN_TYPES.len()]>,
+    /// 各表记录数量（由存储统计刷新）
+    pub table_record_counts: Arc<parking_lot::RwLock<BTreeMap<String, u64>>>,
 }
 
 impl AppMetrics {
     /// 记录 HTTP 请求
-    pub fn record_http_request(&self, duration_ms: u64) {
+    pub fn record_http_request(&self, method: &str, status: u16, duration_ms: u64) {
         self.http_requests_total.fetch_add(1, Ordering::SeqCst);
-        self.http_request_duration_sum
-            .fetch_add(duration_ms, Ordering::SeqCst);
+        self.http_request_duration.observe(duration_ms);
+        self.http_requests_by_method
+            .entry((method.to_uppercase(), status))
+            .or_default()
+            .fetch_add(1, Ordering::SeqCst);
+        if status >= 400 {
+            self.http_errors_by_status
+                .entry(status)
+                .or_default()
+                .fetch_add(1, Ordering::SeqCst);
+        }
     }
 
     /// 记录活跃连接
@@ -40,17 +256,27 @@ impl AppMetrics {
             .fetch_add(delta as usize, Ordering::SeqCst);
     }
 
-    /// 记录会话变化
+    /// 记录会话变化（`delta` 可为负数）
     pub fn record_session(&self, status: &str, delta: isize) {
-        let _ = match status {
-            "active" => self
-                .sessions_active
-                .fetch_add(delta as usize, Ordering::SeqCst),
-            "archived" => self
-                .sessions_archived
-                .fetch_add(delta as usize, Ordering::SeqCst),
+        let gauge = match status {
+            "active" => &self.sessions_active,
+            "archived" => &self.sessions_archived,
             _ => return,
         };
+        gauge.fetch_add(delta as i64, Ordering::SeqCst);
+    }
+
+    /// 更新各租户最近活跃会话数量，并以合计值刷新 `sessions_active`
+    pub fn set_active_sessions_recent(&self, counts: impl IntoIterator<Item = (String, u64)>) {
+        let counts: BTreeMap<_, _> = counts.into_iter().collect();
+        let total: u64 = counts.values().sum();
+        self.sessions_active.store(total as i64, Ordering::SeqCst);
+        *self.active_sessions_recent.write() = counts;
+    }
+
+    /// 更新各表记录数量
+    pub fn set_table_record_counts(&self, counts: impl IntoIterator<Item = (String, u64)>) {
+        *self.table_record_counts.write() = counts.into_iter().collect();
     }
 
     /// 记录搜索请求
@@ -65,17 +291,108 @@ impl AppMetrics {
         self.errors_total.fetch_add(1, Ordering::SeqCst);
     }
 
+    /// 记录模式缓存命中
+    pub fn record_cache_hit(&self) {
+        self.cache_hits_total.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// 记录创建的轮次（同时计入 `turns_total`）
+    pub fn record_turn_created(&self, message_type: &str) {
+        self.turns_total.fetch_add(1, Ordering::SeqCst);
+        increment_labelled(&*self.turns_by_type, &TURN_MESSAGE_TYPES, message_type);
+    }
+
+    /// 记录创建的记忆
+    pub fn record_memory_created(&self, memory_type: &str) {
+        increment_labelled(&*self.memories_by_type, &MEMORY_TYPES, memory_type);
+    }
+
+    /// 记录创建的模式
+    pub fn record_pattern_created(&self, pattern_type: &str) {
+        increment_labelled(&*self.patterns_by_type, &PATTERN_TYPES, pattern_type);
+    }
+
+    /// 导出指标快照
+    pub fn to_snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            http_requests_total: self.http_requests_total.load(Ordering::SeqCst),
+            http_request_duration: self.http_request_duration.to_snapshot(),
+            sessions_active: self.sessions_active.load(Ordering::SeqCst),
+            sessions_archived: self.sessions_archived.load(Ordering::SeqCst),
+            turns_total: self.turns_total.load(Ordering::SeqCst),
+            search_requests_total: self.search_requests_total.load(Ordering::SeqCst),
+            search_latency_sum: self.search_latency_sum.load(Ordering::SeqCst),
+            errors_total: self.errors_total.load(Ordering::SeqCst),
+            cache_hits_total: self.cache_hits_total.load(Ordering::SeqCst),
+            turns_by_type: load_labelled(&*self.turns_by_type, &TURN_MESSAGE_TYPES),
+            memories_by_type: load_labelled(&*self.memories_by_type, &MEMORY_TYPES),
+            patterns_by_type: load_labelled(&*self.patterns_by_type, &PATTERN_TYPES),
+        }
+    }
+
+    /// 从快照恢复指标
+    pub fn restore_from_snapshot(&self, snapshot: MetricsSnapshot) {
+        self.http_requests_total
+            .store(snapshot.http_requests_total, Ordering::SeqCst);
+        self.http_request_duration
+            .restore_from_snapshot(&snapshot.http_request_duration);
+        self.sessions_active
+            .store(snapshot.sessions_active, Ordering::SeqCst);
+        self.sessions_archived
+            .store(snapshot.sessions_archived, Ordering::SeqCst);
+        self.turns_total.store(snapshot.turns_total, Ordering::SeqCst);
+        self.search_requests_total
+            .store(snapshot.search_requests_total, Ordering::SeqCst);
+        self.search_latency_sum
+            .store(snapshot.search_latency_sum, Ordering::SeqCst);
+        self.errors_total
+            .store(snapshot.errors_total, Ordering::SeqCst);
+        self.cache_hits_total
+            .store(snapshot.cache_hits_total, Ordering::SeqCst);
+        store_labelled(
+            &*self.turns_by_type,
+            &TURN_MESSAGE_TYPES,
+            &snapshot.turns_by_type,
+        );
+        store_labelled(
+            &*self.memories_by_type,
+            &MEMORY_TYPES,
+            &snapshot.memories_by_type,
+        );
+        store_labelled(
+            &*self.patterns_by_type,
+            &PATTERN_TYPES,
+            &snapshot.patterns_by_type,
+        );
+    }
+
     /// 生成 Prometheus 格式指标
     pub fn gather(&self) -> String {
-        format!(
-            r#"# HELP http_requests_total Total HTTP requests
-# TYPE http_requests_total counter
-http_requests_total {}
-# HELP http_request_duration_seconds HTTP request duration in seconds
-# TYPE http_request_duration_seconds histogram
-http_request_duration_seconds_sum {}
-http_request_duration_seconds_count {}
-# HELP active_connections Active HTTP connections
+        let mut output = String::new();
+        let _ = writeln!(output, "# HELP http_requests_total Total HTTP requests");
+        let _ = writeln!(output, "# TYPE http_requests_total counter");
+        let requests: BTreeMap<_, _> = self
+            .http_requests_by_method
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().load(Ordering::SeqCst)))
+            .collect();
+        for ((method, status), count) in requests {
+            let _ = writeln!(
+                output,
+                "http_requests_total{{method=\"{}\",status=\"{}\"}} {}",
+                method, status, count
+            );
+        }
+
+        self.http_request_duration.write_prometheus(
+            &mut output,
+            "http_request_duration_seconds",
+            "HTTP request duration in seconds",
+        );
+
+        let _ = write!(
+            output,
+            r#"# HELP active_connections Active HTTP connections
 # TYPE active_connections gauge
 active_connections {}
 # HELP sessions_active Active sessions
@@ -84,9 +401,6 @@ sessions_active {}
 # HELP sessions_archived Archived sessions
 # TYPE sessions_archived gauge
 sessions_archived {}
-# HELP turns_total Total turns
-# TYPE turns_total counter
-turns_total {}
 # HELP search_requests_total Total search requests
 # TYPE search_requests_total counter
 search_requests_total {}
@@ -97,22 +411,182 @@ search_latency_seconds_count {}
 # HELP errors_total Total errors
 # TYPE errors_total counter
 errors_total {}
+# HELP cache_hits_total Pattern cache hits
+# TYPE cache_hits_total counter
+cache_hits_total {}
 "#,
-            self.http_requests_total.load(Ordering::SeqCst),
-            self.http_request_duration_sum.load(Ordering::SeqCst) as f64 / 1000.0,
-            self.http_requests_total.load(Ordering::SeqCst),
             self.active_connections.load(Ordering::SeqCst),
             self.sessions_active.load(Ordering::SeqCst),
             self.sessions_archived.load(Ordering::SeqCst),
-            self.turns_total.load(Ordering::SeqCst),
             self.search_requests_total.load(Ordering::SeqCst),
             self.search_latency_sum.load(Ordering::SeqCst) as f64 / 1000.0,
             self.search_requests_total.load(Ordering::SeqCst),
             self.errors_total.load(Ordering::SeqCst),
-        )
+            self.cache_hits_total.load(Ordering::SeqCst),
+        );
+
+        let errors: BTreeMap<_, _> = self
+            .http_errors_by_status
+            .iter()
+            .map(|entry| (*entry.key(), entry.value().load(Ordering::SeqCst)))
+            .collect();
+        let _ = writeln!(
+            output,
+            "# HELP http_errors_total HTTP responses with an error status"
+        );
+        let _ = writeln!(output, "# TYPE http_errors_total counter");
+        for (status, count) in errors {
+            let _ = writeln!(
+                output,
+                "http_errors_total{{status=\"{}\"}} {}",
+                status, count
+            );
+        }
+
+        let active_sessions_recent = self.active_sessions_recent.read();
+        if !active_sessions_recent.is_empty() {
+            let _ = writeln!(
+                output,
+                "# HELP active_sessions_recent Sessions active within the recent window per tenant"
+            );
+            let _ = writeln!(output, "# TYPE active_sessions_recent gauge");
+            for (tenant_id, count) in active_sessions_recent.iter() {
+                let _ = writeln!(
+                    output,
+                    "active_sessions_recent{{tenant_id=\"{}\",window=\"{}m\"}} {}",
+                    escape_label_value(tenant_id),
+                    ACTIVE_SESSION_WINDOW_SECS / 60,
+                    count
+                );
+            }
+        }
+
+        write_labelled(
+            &mut output,
+            "turns_total",
+            "Total turns by message type",
+            "message_type",
+            &TURN_MESSAGE_TYPES,
+            &*self.turns_by_type,
+        );
+        write_labelled(
+            &mut output,
+            "memories_total",
+            "Total memories created by memory type",
+            "memory_type",
+            &MEMORY_TYPES,
+            &*self.memories_by_type,
+        );
+        write_labelled(
+            &mut output,
+            "patterns_total",
+            "Total patterns created by pattern type",
+            "pattern_type",
+            &PATTERN_TYPES,
+            &*self.patterns_by_type,
+        );
+
+        let table_record_counts = self.table_record_counts.read();
+        if !table_record_counts.is_empty() {
+            let _ = writeln!(
+                output,
+                "# HELP table_record_count Records stored per database table"
+            );
+            let _ = writeln!(output, "# TYPE table_record_count gauge");
+            for (table, count) in table_record_counts.iter() {
+                let _ = writeln!(
+                    output,
+                    "table_record_count{{table=\"{}\"}} {}",
+                    escape_label_value(table),
+                    count
+                );
+            }
+        }
+
+        output
+    }
+}
+
+/// 按 Prometheus 文本格式转义标签值中的反斜杠、双引号和换行
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// 按标签值递增对应计数器，未知标签值只记录调试日志
+fn increment_labelled(counters: &[AtomicU64], labels: &[&str], value: &str) {
+    match labels.iter().position(|l| l.eq_ignore_ascii_case(value)) {
+        Some(index) => {
+            counters[index].fetch_add(1, Ordering::SeqCst);
+        }
+        None => tracing::debug!("Ignoring metric for unknown type: {}", value),
+    }
+}
+
+fn load_labelled(counters: &[AtomicU64], labels: &[&str]) -> BTreeMap<String, u64> {
+    labels
+        .iter()
+        .zip(counters)
+        .map(|(label, counter)| (label.to_string(), counter.load(Ordering::SeqCst)))
+        .collect()
+}
+
+fn store_labelled(counters: &[AtomicU64], labels: &[&str], values: &BTreeMap<String, u64>) {
+    for (label, counter) in labels.iter().zip(counters) {
+        counter.store(values.get(*label).copied().unwrap_or(0), Ordering::SeqCst);
     }
 }
 
+/// 输出带单个标签的计数器族
+fn write_labelled(
+    output: &mut String,
+    name: &str,
+    help: &str,
+    label_name: &str,
+    labels: &[&str],
+    counters: &[AtomicU64],
+) {
+    let _ = writeln!(output, "# HELP {} {}", name, help);
+    let _ = writeln!(output, "# TYPE {} counter", name);
+    for (label, counter) in labels.iter().zip(counters) {
+        let _ = writeln!(
+            output,
+            "{}{{{}=\"{}\"}} {}",
+            name,
+            label_name,
+            label,
+            counter.load(Ordering::SeqCst)
+        );
+    }
+}
+
+/// 指标快照
+///
+/// 在关闭时写入磁盘，启动时恢复，避免重启后计数器归零。
+/// `active_connections` 只反映当前进程的连接，不包含在快照中。
+/// 按方法和状态码分组的 HTTP 计数器也不包含在快照中，重启后从零开始。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsSnapshot {
+    pub http_requests_total: u64,
+    pub http_request_duration: HistogramSnapshot,
+    pub sessions_active: i64,
+    pub sessions_archived: i64,
+    pub turns_total: u64,
+    pub search_requests_total: u64,
+    pub search_latency_sum: u64,
+    pub errors_total: u64,
+    pub cache_hits_total: u64,
+    pub turns_by_type: BTreeMap<String, u64>,
+    pub memories_by_type: BTreeMap<String, u64>,
+    pub patterns_by_type: BTreeMap<String, u64>,
+}
+
+/// 指标快照文件名
+pub const METRICS_SNAPSHOT_FILE: &str = "metrics_snapshot.json";
+
 // ===== Health Check =====
 
 /// 健康检查状态
@@ -165,14 +639,43 @@ impl ObservabilityState {
     }
 
     /// 添加健康检查结果
+    ///
+    /// 同名检查只保留最新结果，使就绪检查反映依赖的当前状态。
     pub async fn add_health_check(&self, result: HealthCheckResult) {
         let mut checks = self.health_checks.lock().await;
+        if let Some(existing) = checks.iter_mut().find(|c| c.name == result.name) {
+            *existing = result;
+            return;
+        }
         checks.push(result);
         if checks.len() > 10 {
             checks.remove(0);
         }
     }
 
+    /// 将当前指标写入 `{dir}/metrics_snapshot.json`
+    pub fn save_metrics_snapshot(&self, dir: &Path) -> crate::error::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let json = serde_json::to_string_pretty(&self.metrics.to_snapshot())?;
+        std::fs::write(dir.join(METRICS_SNAPSHOT_FILE), json)?;
+        Ok(())
+    }
+
+    /// 从 `{dir}/metrics_snapshot.json` 恢复指标
+    ///
+    /// 文件不存在时返回 `Ok(false)`。
+    pub fn load_metrics_snapshot(&self, dir: &Path) -> crate::error::Result<bool> {
+        let path = dir.join(METRICS_SNAPSHOT_FILE);
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let json = std::fs::read_to_string(path)?;
+        let snapshot: MetricsSnapshot = serde_json::from_str(&json)?;
+        self.metrics.restore_from_snapshot(snapshot);
+        Ok(true)
+    }
+
     /// 获取应用正常运行时间
     pub fn uptime_seconds(&self) -> f64 {
         (Utc::now() - self.start_time).num_seconds() as f64
@@ -244,6 +747,15 @@ pub async fn metrics(state: axum::extract::State<Arc<ObservabilityState>>) -> im
     (axum::http::StatusCode::OK, output)
 }
 
+/// 延迟直方图端点（JSON）
+pub async fn metrics_histogram(
+    state: axum::extract::State<Arc<ObservabilityState>>,
+) -> impl IntoResponse {
+    Json(serde_json::json!({
+        "http_request_duration": state.metrics.http_request_duration.to_snapshot(),
+    }))
+}
+
 /// 版本信息端点
 pub async fn version(state: axum::extract::State<Arc<ObservabilityState>>) -> impl IntoResponse {
     Json(serde_json::json!({
@@ -260,23 +772,92 @@ pub fn create_observability_router(state: Arc<ObservabilityState>) -> Router {
         .route("/health/live", get(liveness))
         .route("/health/ready", get(readiness))
         .route("/metrics", get(metrics))
+        .route("/metrics/histogram", get(metrics_histogram))
         .route("/version", get(version))
         .with_state(state)
 }
 
 // ===== Structured Logging =====
 
-/// 初始化结构化日志
-pub fn init_tracing(service_name: &str) {
-    let env_filter = std::env::var("RUST_LOG").unwrap_or_else(|_| format!("info,{}", service_name));
+/// 未设置 `RUST_LOG` 时的日志过滤规则
+const DEFAULT_LOG_FILTER: &str = "info";
+
+/// `init_tracing` 返回的 guard；未启用 `otel` 特性时不持有任何资源
+#[cfg(not(feature = "otel"))]
+#[derive(Debug)]
+pub struct TracingGuard;
 
-    let subscriber = tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
+fn env_filter() -> tracing_subscriber::EnvFilter {
+    tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new(DEFAULT_LOG_FILTER))
+}
+
+fn fmt_layer<S>() -> tracing_subscriber::fmt::Layer<S>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    tracing_subscriber::fmt::layer()
         .with_target(true)
         .with_line_number(true)
-        .finish();
+}
+
+/// 初始化结构化日志
+///
+/// 启用 `otel` 特性且设置了 `HIPPOS_OTEL_ENDPOINT` 时同时挂载 OTLP 导出层；返回的 guard
+/// 需持有到进程退出，释放时刷新尚未发送的 span。
+#[cfg(feature = "otel")]
+pub fn init_tracing(service_name: &str) -> Option<TracingGuard> {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let endpoint = otel::otel_endpoint();
+    let provider = endpoint
+        .as_deref()
+        .map(|endpoint| otel::init_tracer_provider(endpoint, service_name));
+    let otel_layer = match &provider {
+        Some(Ok(provider)) => Some(otel::layer(provider, service_name)),
+        _ => None,
+    };
+
+    let subscriber = tracing_subscriber::registry()
+        .with(env_filter())
+        .with(fmt_layer())
+        .with(otel_layer);
+
+    tracing::subscriber::set_global_default(subscriber).expect("Failed to set tracing subscriber");
+
+    match provider {
+        Some(Ok(provider)) => {
+            tracing::info!(
+                "Exporting traces to {}",
+                endpoint.as_deref().unwrap_or_default()
+            );
+            Some(otel::OtelGuard::new(provider))
+        }
+        Some(Err(e)) => {
+            tracing::warn!("OpenTelemetry export disabled: {}", e);
+            None
+        }
+        None => None,
+    }
+}
+
+/// 初始化结构化日志
+#[cfg(not(feature = "otel"))]
+pub fn init_tracing(_service_name: &str) -> Option<TracingGuard> {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    let subscriber = tracing_subscriber::registry()
+        .with(env_filter())
+        .with(fmt_layer());
 
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set tracing subscriber");
+
+    if std::env::var_os("HIPPOS_OTEL_ENDPOINT").is_some() {
+        tracing::warn!(
+            "HIPPOS_OTEL_ENDPOINT is set but hippos was built without the `otel` feature"
+        );
+    }
+    None
 }
 
 // ===== Request Metrics Middleware =====
@@ -288,18 +869,28 @@ pub async fn metrics_middleware(
     state: Arc<ObservabilityState>,
 ) -> Result<axum::response::Response, std::convert::Infallible> {
     let start = std::time::Instant::now();
+    let method = req.method().as_str().to_string();
 
     state.metrics.record_connection(1);
 
     let response = next.run(req).await;
 
     let duration_ms = start.elapsed().as_millis() as u64;
-    state.metrics.record_http_request(duration_ms);
+    state
+        .metrics
+        .record_http_request(&method, response.status().as_u16(), duration_ms);
     state.metrics.record_connection(-1);
 
     Ok(response)
 }
 
+/// 为路由挂载 `metrics_middleware`，使所有请求计入 `http_requests_total` 等 HTTP 指标
+pub fn with_request_metrics(router: Router, state: Arc<ObservabilityState>) -> Router {
+    router.layer(axum::middleware::from_fn(move |req, next| {
+        metrics_middleware(req, next, state.clone())
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -307,16 +898,201 @@ mod tests {
     #[test]
     fn test_metrics_gather() {
         let metrics = AppMetrics::default();
-        metrics.record_http_request(100);
+        metrics.record_http_request("GET", 200, 100);
         metrics.record_connection(1);
         metrics.record_search(50);
         metrics.record_error();
+        metrics.record_cache_hit();
 
         let output = metrics.gather();
-        assert!(output.contains("http_requests_total 1"));
+        assert!(output.contains("http_requests_total{method=\"GET\",status=\"200\"} 1"));
         assert!(output.contains("active_connections 1"));
         assert!(output.contains("search_requests_total 1"));
         assert!(output.contains("errors_total 1"));
+        assert!(output.contains("cache_hits_total 1"));
+    }
+
+    #[test]
+    fn test_default_log_filter_stops_at_info() {
+        let filter = tracing_subscriber::EnvFilter::new(DEFAULT_LOG_FILTER);
+        assert_eq!(
+            filter.max_level_hint(),
+            Some(tracing::level_filters::LevelFilter::INFO)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_metrics_exported_for_routed_requests() {
+        use tower::ServiceExt;
+
+        let state = Arc::new(ObservabilityState::new("test".to_string()));
+        let router =
+            with_request_metrics(create_observability_router(state.clone()), state.clone());
+
+        let request = axum::http::Request::builder()
+            .uri("/version")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        router.oneshot(request).await.unwrap();
+
+        let output = state.metrics.gather();
+        assert!(output.contains("http_requests_total{method=\"GET\",status=\"200\"} 1"));
+        assert_eq!(state.metrics.http_requests_total.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_add_health_check_keeps_latest_per_name() {
+        let state = ObservabilityState::new("test".to_string());
+        let check = |healthy| HealthCheckResult {
+            name: "database".to_string(),
+            healthy,
+            message: String::new(),
+            latency_ms: 1,
+        };
+
+        state.add_health_check(check(false)).await;
+        state.add_health_check(check(true)).await;
+
+        let checks = state.health_checks.lock().await;
+        assert_eq!(checks.len(), 1);
+        assert!(checks[0].healthy);
+    }
+
+    #[test]
+    fn test_metrics_gather_active_sessions_recent() {
+        let metrics = AppMetrics::default();
+        metrics.set_active_sessions_recent([
+            ("tenant_1".to_string(), 3),
+            ("acme \"eu\"\\west\n".to_string(), 2),
+        ]);
+
+        let output = metrics.gather();
+        assert!(output.contains("sessions_active 5\n"));
+        assert!(output.contains("# TYPE active_sessions_recent gauge"));
+        assert!(output.contains("active_sessions_recent{tenant_id=\"tenant_1\",window=\"5m\"} 3"));
+        assert!(output.contains(
+            "active_sessions_recent{tenant_id=\"acme \\\"eu\\\"\\\\west\\n\",window=\"5m\"} 2"
+        ));
+    }
+
+    #[test]
+    fn test_metrics_gather_http_labels() {
+        let metrics = AppMetrics::default();
+        metrics.record_http_request("GET", 200, 10);
+        metrics.record_http_request("get", 200, 10);
+        metrics.record_http_request("POST", 500, 10);
+        metrics.record_http_request("POST", 404, 10);
+        metrics.record_http_request("POST", 500, 10);
+
+        let output = metrics.gather();
+        assert!(output.contains("http_requests_total{method=\"GET\",status=\"200\"} 2"));
+        assert!(output.contains("http_requests_total{method=\"POST\",status=\"500\"} 2"));
+        assert!(output.contains("http_requests_total{method=\"POST\",status=\"404\"} 1"));
+        assert!(output.contains("http_errors_total{status=\"404\"} 1"));
+        assert!(output.contains("http_errors_total{status=\"500\"} 2"));
+        assert!(!output.contains("http_errors_total{status=\"200\"}"));
+        assert!(output.contains("http_request_duration_seconds_count 5"));
+        assert_eq!(output.matches("# TYPE http_requests_total").count(), 1);
+    }
+
+    #[test]
+    fn test_latency_histogram_buckets() {
+        let metrics = AppMetrics {
+            http_request_duration: Arc::new(LatencyHistogram::new(&[50.0, 10.0, 100.0])),
+            ..Default::default()
+        };
+        for duration_ms in [5, 10, 42, 70, 2000] {
+            metrics.record_http_request("GET", 200, duration_ms);
+        }
+
+        let output = metrics.gather();
+        assert!(output.contains("http_request_duration_seconds_bucket{le=\"0.01\"} 2"));
+        assert!(output.contains("http_request_duration_seconds_bucket{le=\"0.05\"} 3"));
+        assert!(output.contains("http_request_duration_seconds_bucket{le=\"0.1\"} 4"));
+        assert!(output.contains("http_request_duration_seconds_bucket{le=\"+Inf\"} 5"));
+        assert!(output.contains("http_request_duration_seconds_sum 2.127"));
+        assert!(output.contains("http_request_duration_seconds_count 5"));
+
+        let snapshot = metrics.http_request_duration.to_snapshot();
+        let restored = LatencyHistogram::new(&[10.0, 50.0, 100.0]);
+        restored.restore_from_snapshot(&snapshot);
+        assert_eq!(restored.to_snapshot(), snapshot);
+    }
+
+    #[test]
+    fn test_parse_latency_buckets() {
+        assert_eq!(
+            parse_latency_buckets("5, 10,25"),
+            Some(vec![5.0, 10.0, 25.0])
+        );
+        assert_eq!(parse_latency_buckets("5,abc"), None);
+        assert_eq!(parse_latency_buckets("0,10"), None);
+        assert_eq!(parse_latency_buckets(""), None);
+    }
+
+    #[test]
+    fn test_metrics_gather_table_record_counts() {
+        let metrics = AppMetrics::default();
+        assert!(!metrics.gather().contains("table_record_count"));
+
+        metrics.set_table_record_counts([("session".to_string(), 4), ("turn".to_string(), 12)]);
+        let output = metrics.gather();
+        assert!(output.contains("# TYPE table_record_count gauge"));
+        assert!(output.contains("table_record_count{table=\"session\"} 4"));
+        assert!(output.contains("table_record_count{table=\"turn\"} 12"));
+    }
+
+    #[test]
+    fn test_metrics_gather_labels_created_types() {
+        let metrics = AppMetrics::default();
+        metrics.record_turn_created("user");
+        metrics.record_turn_created("assistant");
+        metrics.record_turn_created("Assistant");
+        metrics.record_turn_created("unknown");
+        metrics.record_memory_created("semantic");
+        metrics.record_pattern_created("workflow");
+
+        let output = metrics.gather();
+        assert!(output.contains("turns_total{message_type=\"user\"} 1"));
+        assert!(output.contains("turns_total{message_type=\"assistant\"} 2"));
+        assert!(output.contains("turns_total{message_type=\"system\"} 0"));
+        assert!(output.contains("memories_total{memory_type=\"semantic\"} 1"));
+        assert!(output.contains("patterns_total{pattern_type=\"workflow\"} 1"));
+        assert_eq!(metrics.turns_total.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_metrics_snapshot_roundtrip() {
+        let metrics = AppMetrics::default();
+        metrics.record_http_request("GET", 200, 100);
+        metrics.record_search(50);
+        metrics.record_error();
+        metrics.record_turn_created("assistant");
+
+        let snapshot = metrics.to_snapshot();
+        assert_eq!(snapshot.http_requests_total, 1);
+        assert_eq!(snapshot.search_latency_sum, 50);
+        assert_eq!(snapshot.turns_by_type["assistant"], 1);
+
+        let restored = AppMetrics::default();
+        restored.restore_from_snapshot(snapshot.clone());
+        assert_eq!(restored.to_snapshot(), snapshot);
+    }
+
+    #[test]
+    fn test_metrics_snapshot_file() {
+        let dir = std::env::temp_dir().join(format!("hippos-metrics-{}", uuid::Uuid::new_v4()));
+        let state = ObservabilityState::new("test".to_string());
+        assert!(!state.load_metrics_snapshot(&dir).unwrap());
+
+        state.metrics.record_error();
+        state.save_metrics_snapshot(&dir).unwrap();
+
+        let restored = ObservabilityState::new("test".to_string());
+        assert!(restored.load_metrics_snapshot(&dir).unwrap());
+        assert_eq!(restored.metrics.to_snapshot().errors_total, 1);
+
+        let _ = std::fs::remove_dir_all(dir);
     }
 
     #[test]
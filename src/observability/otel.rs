@@ -0,0 +1,106 @@
+//! OpenTelemetry 链路追踪
+//!
+//! 设置 `HIPPOS_OTEL_ENDPOINT` 后，`init_tracing` 会额外挂载 OTLP（gRPC）导出层，
+//! 将服务调用的 span 发送到该地址；变量为空时使用 `DEFAULT_OTEL_ENDPOINT`。
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
+use tracing_opentelemetry::OpenTelemetryLayer;
+
+use crate::error::{AppError, Result};
+
+/// 启用 OTLP 导出的环境变量
+pub const OTEL_ENDPOINT_ENV: &str = "HIPPOS_OTEL_ENDPOINT";
+
+/// 默认 OTLP gRPC 接收地址
+pub const DEFAULT_OTEL_ENDPOINT: &str = "http://localhost:4317";
+
+/// 从环境变量读取 OTLP 接收地址，未设置时返回 `None`（不启用导出）
+pub fn otel_endpoint() -> Option<String> {
+    resolve_endpoint(std::env::var(OTEL_ENDPOINT_ENV).ok())
+}
+
+/// 变量未设置时不启用；设置为空字符串时使用默认地址
+fn resolve_endpoint(value: Option<String>) -> Option<String> {
+    value.map(|endpoint| {
+        let endpoint = endpoint.trim();
+        if endpoint.is_empty() {
+            DEFAULT_OTEL_ENDPOINT.to_string()
+        } else {
+            endpoint.to_string()
+        }
+    })
+}
+
+/// 创建向 `endpoint` 批量导出 span 的 tracer provider
+///
+/// 需要在 tokio 运行时内调用；进程退出前应调用 `shutdown` 刷新未发送的 span。
+pub fn init_tracer_provider(endpoint: &str, service_name: &str) -> Result<SdkTracerProvider> {
+    let exporter = SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| AppError::Config(format!("Failed to build OTLP exporter: {}", e)))?;
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        )
+        .build();
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    Ok(provider)
+}
+
+/// 持有 tracer provider，释放时刷新并关闭导出
+#[derive(Debug)]
+pub struct OtelGuard {
+    provider: SdkTracerProvider,
+}
+
+impl OtelGuard {
+    pub fn new(provider: SdkTracerProvider) -> Self {
+        Self { provider }
+    }
+}
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to shut down OpenTelemetry tracer provider: {}", e);
+        }
+    }
+}
+
+/// 将 `tracing` span 转发到 provider 的订阅层
+pub fn layer<S>(
+    provider: &SdkTracerProvider,
+    service_name: &str,
+) -> OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(service_name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_endpoint() {
+        assert_eq!(resolve_endpoint(None), None);
+        assert_eq!(
+            resolve_endpoint(Some(String::new())).as_deref(),
+            Some(DEFAULT_OTEL_ENDPOINT)
+        );
+        assert_eq!(
+            resolve_endpoint(Some("http://collector:4317".to_string())).as_deref(),
+            Some("http://collector:4317")
+        );
+    }
+}
@@ -2,8 +2,34 @@
 //!
 //! Security-related configuration settings.
 
+use figment::{
+    Figment,
+    providers::{Format, Toml},
+};
+use notify::{EventKind, RecursiveMode, Watcher};
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::error::{AppError, Result};
+use crate::security::rate_limit::RateLimitMode;
+
+/// Algorithm used to verify incoming JWTs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "UPPERCASE")]
+pub enum JwtAlgorithm {
+    /// HMAC with the shared `jwt_secret`
+    #[default]
+    Hs256,
+    /// RSA signature verified with a PEM encoded public key
+    Rs256 {
+        /// PEM encoded RSA public key
+        public_key_pem: String,
+    },
+}
 
 /// Extended security configuration for the security layer
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -17,6 +43,8 @@ pub struct SecuritySettings {
     pub jwt_audience: String,
     /// JWT expiry time in seconds
     pub jwt_expiry_seconds: u64,
+    /// JWT signature algorithm (defaults to HS256 with `jwt_secret`)
+    pub jwt_algorithm: JwtAlgorithm,
     /// Valid API keys (map key -> tenant_id)
     pub api_keys: HashSet<String>,
     /// Rate limit requests per minute
@@ -27,6 +55,8 @@ pub struct SecuritySettings {
     pub rate_limit_burst_size: u32,
     /// Enable rate limiting
     pub rate_limit_enabled: bool,
+    /// How requests are grouped into quotas (per client, per tenant or sliding window)
+    pub rate_limit_mode: RateLimitMode,
     /// Enable API key authentication
     pub api_key_auth_enabled: bool,
     /// Enable JWT authentication
@@ -39,6 +69,10 @@ pub struct SecuritySettings {
     pub validation_enabled: bool,
     /// Enable security headers
     pub security_headers_enabled: bool,
+    /// Embedded RBAC policy (TOML); the built-in policy is used when unset
+    pub rbac_policy: Option<String>,
+    /// Serve `GET /api/v1/patterns/stats` without authentication
+    pub enable_public_stats_endpoint: bool,
 }
 
 impl SecuritySettings {
@@ -52,17 +86,21 @@ impl SecuritySettings {
             jwt_issuer: "hippos".to_string(),
             jwt_audience: "hippos-api".to_string(),
             jwt_expiry_seconds: 3600,
+            jwt_algorithm: JwtAlgorithm::Hs256,
             api_keys,
             rate_limit_requests_per_minute: 60,
             rate_limit_requests_per_hour: 1000,
             rate_limit_burst_size: 10,
             rate_limit_enabled: false,
+            rate_limit_mode: RateLimitMode::PerClient,
             api_key_auth_enabled: true,
             jwt_auth_enabled: true,
             cors_allowed_origins: vec!["http://localhost:3000".to_string()],
             max_request_size: 10 * 1024 * 1024,
             validation_enabled: true,
             security_headers_enabled: true,
+            rbac_policy: None,
+            enable_public_stats_endpoint: false,
         }
     }
 
@@ -70,6 +108,7 @@ impl SecuritySettings {
     pub fn production() -> Self {
         let mut settings = Self::development();
         settings.rate_limit_enabled = true;
+        settings.rate_limit_mode = RateLimitMode::PerTenant;
         settings.security_headers_enabled = true;
         settings
     }
@@ -78,4 +117,160 @@ impl SecuritySettings {
     pub fn has_jwt_secret(&self) -> bool {
         !self.jwt_secret.is_empty()
     }
+
+    /// Load settings from a TOML file whose top-level keys are the settings fields
+    ///
+    /// Missing fields take their default values; a missing file is an error so
+    /// that a deleted file never silently resets the settings. A rate limit mode
+    /// that fails [`RateLimitMode::validate`] is rejected as well.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Err(AppError::Config(format!(
+                "Security settings file not found: {}",
+                path.display()
+            )));
+        }
+        let settings: Self = Figment::new()
+            .merge(Toml::file(path))
+            .extract()
+            .map_err(|e| AppError::Config(format!("Invalid security settings: {}", e)))?;
+        settings.rate_limit_mode.validate()?;
+        Ok(settings)
+    }
+}
+
+/// Security settings backed by a config file that can be reloaded at runtime
+///
+/// Clones share the same settings, so a reload is seen by every component
+/// holding a clone. Readers take a cheap snapshot with [`current`] on each
+/// request instead of keeping the settings they were built with.
+///
+/// [`current`]: ReloadableSecuritySettings::current
+#[derive(Debug, Clone)]
+pub struct ReloadableSecuritySettings {
+    path: PathBuf,
+    current: Arc<RwLock<Arc<SecuritySettings>>>,
+}
+
+impl ReloadableSecuritySettings {
+    /// Wrap settings that are reloaded from `path`
+    pub fn new(path: impl Into<PathBuf>, settings: SecuritySettings) -> Self {
+        Self {
+            path: path.into(),
+            current: Arc::new(RwLock::new(Arc::new(settings))),
+        }
+    }
+
+    /// Load the initial settings from `path`
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let settings = SecuritySettings::load_from(&path)?;
+        Ok(Self::new(path, settings))
+    }
+
+    /// Path of the backing config file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Snapshot of the current settings
+    pub fn current(&self) -> Arc<SecuritySettings> {
+        self.current.read().clone()
+    }
+
+    /// Re-read the config file and atomically replace the current settings
+    ///
+    /// The current settings are kept if the file cannot be loaded.
+    pub fn reload(&self) -> Result<()> {
+        let settings = SecuritySettings::load_from(&self.path)?;
+        *self.current.write() = Arc::new(settings);
+        info!("Security settings reloaded from {}", self.path.display());
+        Ok(())
+    }
+
+    /// Spawn a background task that reloads the settings whenever the file changes
+    ///
+    /// The parent directory is watched so that editors which save by replacing
+    /// the file are picked up too. Must be called inside a Tokio runtime.
+    pub fn watch(&self) -> notify::Result<()> {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })?;
+        let dir = self
+            .path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+        let settings = self.clone();
+        tokio::spawn(async move {
+            // Keep the watcher alive for as long as events are being consumed
+            let _watcher = watcher;
+            while let Some(event) = rx.recv().await {
+                match event {
+                    Ok(event) if is_settings_change(&event, &settings.path) => {
+                        if let Err(e) = settings.reload() {
+                            warn!(
+                                "Failed to reload security settings from {}: {}",
+                                settings.path.display(),
+                                e
+                            );
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Security settings watcher error: {}", e),
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// Check whether a file system event modifies or recreates the settings file
+fn is_settings_change(event: &notify::Event, path: &Path) -> bool {
+    matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+        && event
+            .paths
+            .iter()
+            .any(|p| p.file_name() == path.file_name())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reload_replaces_settings_and_keeps_them_on_error() {
+        let path =
+            std::env::temp_dir().join(format!("hippos-security-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "rate_limit_requests_per_minute = 5\napi_keys = [\"key-a\"]\n",
+        )
+        .unwrap();
+
+        let settings = ReloadableSecuritySettings::load(&path).unwrap();
+        let shared = settings.clone();
+        assert_eq!(settings.current().rate_limit_requests_per_minute, 5);
+        assert!(settings.current().api_keys.contains("key-a"));
+
+        std::fs::write(
+            &path,
+            "rate_limit_requests_per_minute = 10\napi_keys = [\"key-b\"]\n",
+        )
+        .unwrap();
+        settings.reload().unwrap();
+        assert_eq!(shared.current().rate_limit_requests_per_minute, 10);
+        assert!(shared.current().api_keys.contains("key-b"));
+
+        // An invalid or deleted file keeps the last good settings
+        std::fs::write(&path, "rate_limit_requests_per_minute = \"many\"\n").unwrap();
+        assert!(settings.reload().is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(settings.reload().is_err());
+        assert_eq!(shared.current().rate_limit_requests_per_minute, 10);
+    }
 }
@@ -5,9 +5,9 @@
 use axum::{
     body::Body,
     extract::Request,
-    http::{Method, StatusCode, header},
+    http::{HeaderMap, Method, StatusCode, header},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use chrono::{DateTime, Utc};
 use std::result::Result as StdResult;
@@ -37,38 +37,64 @@ impl RequestClaimsExt for Request<Body> {
 }
 
 /// Authentication middleware
+///
+/// Missing or invalid credentials are rejected with `AppError::Unauthorized` (401).
 pub async fn auth_middleware(
     req: Request<Body>,
     next: Next,
     authenticator: Arc<dyn Authenticator>,
-) -> StdResult<Response, StatusCode> {
-    let credentials = extract_credentials(&req);
+) -> StdResult<Response, AppError> {
+    let claims = authenticate_headers(req.headers(), authenticator.as_ref()).await?;
 
-    match authenticator.authenticate(&credentials).await {
-        Ok(_token) => {
-            let claims = authenticator
-                .validate_token(&_token.token)
-                .await
-                .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let mut req = req;
+    req.set_claims(claims);
 
-            let mut req = req;
-            req.set_claims(claims);
+    Ok(next.run(req).await)
+}
 
-            Ok(next.run(req).await)
-        }
-        Err(e) => {
-            let status = match e {
-                AppError::Authentication(_) => StatusCode::UNAUTHORIZED,
-                _ => StatusCode::INTERNAL_SERVER_ERROR,
-            };
-            Err(status)
-        }
+/// Authenticate the credentials carried by request headers
+///
+/// Fails with `AppError::Unauthorized` when no credentials are present or
+/// they are rejected.
+pub async fn authenticate_headers(
+    headers: &HeaderMap,
+    authenticator: &dyn Authenticator,
+) -> StdResult<Claims, AppError> {
+    authenticate_credentials(&extract_credentials(headers), authenticator).await
+}
+
+/// Authenticate an API key or JWT and return the caller's claims
+///
+/// Fails with `AppError::Unauthorized` when `credentials` is empty or rejected.
+pub async fn authenticate_credentials(
+    credentials: &Credentials,
+    authenticator: &dyn Authenticator,
+) -> StdResult<Claims, AppError> {
+    if credentials.api_key.is_none() && credentials.jwt_token.is_none() {
+        return Err(AppError::Unauthorized("Missing credentials".to_string()));
+    }
+
+    let token = authenticator
+        .authenticate(credentials)
+        .await
+        .map_err(into_unauthorized)?;
+    authenticator
+        .validate_token(&token.token)
+        .await
+        .map_err(into_unauthorized)
+}
+
+/// Map authentication failures to `Unauthorized`, keeping other errors as-is
+fn into_unauthorized(e: AppError) -> AppError {
+    match e {
+        AppError::Authentication(msg) => AppError::Unauthorized(msg),
+        other => other,
     }
 }
 
 /// Extract credentials from request headers
-fn extract_credentials(req: &Request<Body>) -> Credentials {
-    let auth_header = req.headers().get(header::AUTHORIZATION);
+fn extract_credentials(headers: &HeaderMap) -> Credentials {
+    let auth_header = headers.get(header::AUTHORIZATION);
 
     if let Some(auth) = auth_header {
         if let Ok(auth_str) = auth.to_str() {
@@ -76,7 +102,7 @@ fn extract_credentials(req: &Request<Body>) -> Credentials {
         }
     }
 
-    if let Some(api_key) = req.headers().get("X-API-Key") {
+    if let Some(api_key) = headers.get("X-API-Key") {
         if let Ok(key) = api_key.to_str() {
             return Credentials::new(Some(key.to_string()), None);
         }
@@ -86,21 +112,29 @@ fn extract_credentials(req: &Request<Body>) -> Credentials {
 }
 
 /// Authorization middleware
+///
+/// Requests without claims are rejected with `AppError::Unauthorized` (401),
+/// failed permission checks with `AppError::Forbidden` (403).
 pub async fn authorize_middleware(
     req: Request<Body>,
     next: Next,
     authorizer: Arc<dyn Authorizer>,
     resource: ResourceType,
     action: ActionType,
-) -> StdResult<Response, StatusCode> {
-    let claims = req.claims().ok_or(StatusCode::UNAUTHORIZED)?;
+) -> StdResult<Response, AppError> {
+    let claims = req
+        .claims()
+        .ok_or_else(|| AppError::Unauthorized("Missing credentials".to_string()))?;
 
     let permission = Permission::new(resource.clone(), action.clone());
 
     if authorizer.check_permission(claims, &permission).await {
         Ok(next.run(req).await)
     } else {
-        Err(StatusCode::FORBIDDEN)
+        Err(AppError::Forbidden(format!(
+            "Missing permission {}:{}",
+            resource, action
+        )))
     }
 }
 
@@ -111,8 +145,12 @@ pub async fn rate_limit_middleware(
     rate_limiter: Arc<RateLimiter>,
 ) -> StdResult<Response, StatusCode> {
     let client = RateLimitMiddleware::extract_client_id(&req, req.claims());
+    let tenant_id = RateLimitMiddleware::extract_tenant_id(&req, req.claims());
 
-    match rate_limiter.check_rate_limit(&client).await {
+    match rate_limiter
+        .check_request(&client, tenant_id.as_deref())
+        .await
+    {
         RateLimitResult::Allowed => {
             let response = next.run(req).await;
             Ok(response)
@@ -413,7 +451,11 @@ impl MiddlewareBuilder {
             let authenticator = self.app_state.authenticator.clone();
             middleware.push(Box::new(move |req, next| {
                 let auth = authenticator.clone();
-                Box::pin(async move { auth_middleware(req, next, auth).await })
+                Box::pin(async move {
+                    Ok(auth_middleware(req, next, auth)
+                        .await
+                        .unwrap_or_else(IntoResponse::into_response))
+                })
             }));
         }
 
@@ -426,7 +468,11 @@ impl MiddlewareBuilder {
                 let authz = authorizer.clone();
                 let res = resource.clone();
                 let act = action.clone();
-                Box::pin(async move { authorize_middleware(req, next, authz, res, act).await })
+                Box::pin(async move {
+                    Ok(authorize_middleware(req, next, authz, res, act)
+                        .await
+                        .unwrap_or_else(IntoResponse::into_response))
+                })
             }));
         }
 
@@ -14,8 +14,8 @@ pub mod rate_limit;
 pub mod rbac;
 pub mod validation;
 
-pub use auth::{ApiKeyAuth, AuthToken, Authenticator, Credentials, JwtAuth, TokenType};
-pub use config::SecuritySettings;
-pub use rate_limit::{RateLimitConfig, RateLimitResult, RateLimiter};
+pub use auth::{ApiKeyAuth, ApiKeyRecord, AuthToken, Authenticator, Credentials, JwtAuth, TokenType};
+pub use config::{ReloadableSecuritySettings, SecuritySettings};
+pub use rate_limit::{RateLimitConfig, RateLimitMode, RateLimitResult, RateLimiter};
 pub use rbac::{ActionType, Authorizer, Permission, ResourceType, Role};
 pub use validation::{RequestValidator, ValidatedRequest};
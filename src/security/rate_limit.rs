@@ -4,11 +4,26 @@
 
 use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Instant;
 use tokio::sync::RwLock;
 
+use crate::error::{AppError, Result};
+use crate::security::config::{ReloadableSecuritySettings, SecuritySettings};
+
+/// Interval between sweeps that evict stale tenant buckets
+const TENANT_BUCKET_EVICTION_INTERVAL_SECS: u64 = 60;
+
+/// Per-tenant request counters: tenant ID -> (requests in window, window start)
+type TenantBuckets = DashMap<String, (AtomicU64, Instant)>;
+
+/// Per-client request timestamps inside the sliding window, oldest first
+type SlidingWindows = DashMap<String, VecDeque<Instant>>;
+
 /// Rate limit configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -66,6 +81,45 @@ impl RateLimitConfig {
     }
 }
 
+/// How requests are grouped into rate limit quotas
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitMode {
+    /// Quota per client (JWT subject, API key or IP)
+    #[default]
+    PerClient,
+    /// Fixed-window quota per authenticated tenant
+    ///
+    /// Anonymous requests have no tenant and fall back to the per-client quota,
+    /// keyed by client IP.
+    PerTenant,
+    /// Quota per client counted over the trailing `window_secs`
+    ///
+    /// Unlike the fixed window, a client cannot spend a full quota at the end
+    /// of one window and another at the start of the next.
+    SlidingWindow {
+        /// Length of the trailing window in seconds
+        window_secs: u64,
+        /// Maximum requests allowed within the window
+        max_requests: u64,
+    },
+}
+
+impl RateLimitMode {
+    /// Reject mode parameters that would disable limiting
+    ///
+    /// A zero-length sliding window prunes every timestamp before counting,
+    /// so it would allow unlimited requests.
+    pub fn validate(&self) -> Result<()> {
+        if let RateLimitMode::SlidingWindow { window_secs: 0, .. } = self {
+            return Err(AppError::Config(
+                "Sliding window rate limit requires window_secs > 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// Rate limit result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RateLimitResult {
@@ -149,8 +203,16 @@ pub struct RateLimiter {
     config: RateLimitConfig,
     /// Request history (client -> timestamps)
     request_history: Arc<RwLock<HashMap<String, Vec<DateTime<Utc>>>>>,
+    /// Quota grouping mode
+    mode: RateLimitMode,
+    /// Fixed-window request counters used in `PerTenant` mode
+    tenant_buckets: Arc<TenantBuckets>,
+    /// Request timestamps used in `SlidingWindow` mode
+    sliding_windows: Arc<SlidingWindows>,
     /// Whether rate limiting is enabled
     enabled: bool,
+    /// Hot-reloaded settings; when set, limits and the enabled flag are read from them
+    settings: Option<ReloadableSecuritySettings>,
 }
 
 impl RateLimiter {
@@ -159,8 +221,102 @@ impl RateLimiter {
         Self {
             config,
             request_history: Arc::new(RwLock::new(HashMap::new())),
+            mode: RateLimitMode::PerClient,
+            tenant_buckets: Arc::new(DashMap::new()),
+            sliding_windows: Arc::new(DashMap::new()),
             enabled,
+            settings: None,
+        }
+    }
+
+    /// Create a rate limiter whose quotas are tracked per tenant ID
+    ///
+    /// When called inside a Tokio runtime, a background task evicts stale
+    /// tenant buckets every 60 seconds until the limiter is dropped.
+    pub fn per_tenant(config: RateLimitConfig, enabled: bool) -> Self {
+        let mut limiter = Self::new(config, enabled);
+        limiter.set_mode(RateLimitMode::PerTenant);
+        limiter
+    }
+
+    /// Create a rate limiter that allows at most `max_requests` per client
+    /// within any `window_secs` span
+    ///
+    /// When called inside a Tokio runtime, a background task evicts idle
+    /// clients every 60 seconds until the limiter is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window_secs` is zero.
+    pub fn sliding_window(
+        config: RateLimitConfig,
+        enabled: bool,
+        window_secs: u64,
+        max_requests: u64,
+    ) -> Self {
+        assert!(window_secs > 0, "sliding window requires window_secs > 0");
+        let mut limiter = Self::new(config, enabled);
+        limiter.set_mode(RateLimitMode::SlidingWindow {
+            window_secs,
+            max_requests,
+        });
+        limiter
+    }
+
+    /// Quota grouping mode
+    pub fn mode(&self) -> RateLimitMode {
+        self.mode
+    }
+
+    /// Switch the quota mode and start the eviction task it needs
+    fn set_mode(&mut self, mode: RateLimitMode) {
+        self.mode = mode;
+        if tokio::runtime::Handle::try_current().is_err() {
+            return;
         }
+        match mode {
+            RateLimitMode::PerClient => {}
+            RateLimitMode::PerTenant => self.spawn_tenant_bucket_eviction(),
+            RateLimitMode::SlidingWindow { window_secs, .. } => {
+                self.spawn_sliding_window_eviction(window_secs)
+            }
+        }
+    }
+
+    /// Spawn the background task that evicts stale tenant buckets
+    fn spawn_tenant_bucket_eviction(&self) {
+        let buckets: Weak<TenantBuckets> = Arc::downgrade(&self.tenant_buckets);
+        let window = std::time::Duration::from_secs(self.config.window_size_seconds);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                TENANT_BUCKET_EVICTION_INTERVAL_SECS,
+            ));
+            loop {
+                interval.tick().await;
+                let Some(buckets) = buckets.upgrade() else {
+                    break;
+                };
+                evict_stale_buckets(&buckets, window);
+            }
+        });
+    }
+
+    /// Spawn the background task that evicts clients idle for a whole window
+    fn spawn_sliding_window_eviction(&self, window_secs: u64) {
+        let windows: Weak<SlidingWindows> = Arc::downgrade(&self.sliding_windows);
+        let window = std::time::Duration::from_secs(window_secs);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                TENANT_BUCKET_EVICTION_INTERVAL_SECS,
+            ));
+            loop {
+                interval.tick().await;
+                let Some(windows) = windows.upgrade() else {
+                    break;
+                };
+                evict_idle_windows(&windows, window, Instant::now());
+            }
+        });
     }
 
     /// Create development rate limiter
@@ -180,19 +336,63 @@ impl RateLimiter {
         burst_size: u32,
         enabled: bool,
     ) -> Self {
-        let config = RateLimitConfig {
-            requests_per_minute,
-            requests_per_hour,
-            requests_per_day: requests_per_hour * 24,
-            burst_size,
-            ..Default::default()
-        };
+        let config = config_from_limits(requests_per_minute, requests_per_hour, burst_size);
         Self::new(config, enabled)
     }
 
+    /// Create a rate limiter from fixed security settings, including the quota mode
+    pub fn from_security_settings(settings: &SecuritySettings) -> Self {
+        let mut limiter = Self::from_settings(
+            settings.rate_limit_requests_per_minute,
+            settings.rate_limit_requests_per_hour,
+            settings.rate_limit_burst_size,
+            settings.rate_limit_enabled,
+        );
+        limiter.set_mode(settings.rate_limit_mode);
+        limiter
+    }
+
+    /// Create a rate limiter that follows hot-reloaded security settings
+    ///
+    /// The limits and the enabled flag are read from `settings` on every
+    /// request, so a reload takes effect without rebuilding the limiter. The
+    /// quota mode is fixed when the limiter is created.
+    pub fn from_reloadable(settings: ReloadableSecuritySettings) -> Self {
+        let current = settings.current();
+        let mut limiter = Self::from_settings(
+            current.rate_limit_requests_per_minute,
+            current.rate_limit_requests_per_hour,
+            current.rate_limit_burst_size,
+            current.rate_limit_enabled,
+        );
+        limiter.set_mode(current.rate_limit_mode);
+        limiter.settings = Some(settings);
+        limiter
+    }
+
+    /// Limits and enabled flag for the current request
+    fn current_config(&self) -> (RateLimitConfig, bool) {
+        match &self.settings {
+            Some(settings) => {
+                let current = settings.current();
+                let config = RateLimitConfig {
+                    window_size_seconds: self.config.window_size_seconds,
+                    ..config_from_limits(
+                        current.rate_limit_requests_per_minute,
+                        current.rate_limit_requests_per_hour,
+                        current.rate_limit_burst_size,
+                    )
+                };
+                (config, current.rate_limit_enabled)
+            }
+            None => (self.config.clone(), self.enabled),
+        }
+    }
+
     /// Check rate limit for a client
     pub async fn check_rate_limit(&self, client: &RateLimitClient) -> RateLimitResult {
-        if !self.enabled {
+        let (config, enabled) = self.current_config();
+        if !enabled {
             return RateLimitResult::Allowed;
         }
 
@@ -217,13 +417,13 @@ impl RateLimiter {
                 .map(|v| v.iter().filter(|t| **t > hour_cutoff).count())
                 .unwrap_or(0);
 
-            let reset_at = now + Duration::seconds(self.config.window_size_seconds as i64);
+            let reset_at = now + Duration::seconds(config.window_size_seconds as i64);
 
-            if minute_count >= self.config.requests_per_minute as usize {
+            if minute_count >= config.requests_per_minute as usize {
                 return RateLimitResult::Limited {
                     retry_after: 60,
                     limit: RateLimitInfo {
-                        limit: self.config.requests_per_minute,
+                        limit: config.requests_per_minute,
                         remaining: 0,
                         reset_at,
                         window: "minute".to_string(),
@@ -231,11 +431,11 @@ impl RateLimiter {
                 };
             }
 
-            if hour_count >= self.config.requests_per_hour as usize {
+            if hour_count >= config.requests_per_hour as usize {
                 return RateLimitResult::Limited {
                     retry_after: 3600,
                     limit: RateLimitInfo {
-                        limit: self.config.requests_per_hour,
+                        limit: config.requests_per_hour,
                         remaining: 0,
                         reset_at,
                         window: "hour".to_string(),
@@ -255,13 +455,13 @@ impl RateLimiter {
                 .push(now);
         }
 
-        let remaining = self.config.requests_per_minute as i32 - recent_requests.0 as i32;
+        let remaining = config.requests_per_minute as i32 - recent_requests.0 as i32;
 
         RateLimitResult::AllowedWithInfo {
             remaining: remaining as u32,
             reset_at: recent_requests.2,
             limit: RateLimitInfo {
-                limit: self.config.requests_per_minute,
+                limit: config.requests_per_minute,
                 remaining: remaining as u32,
                 reset_at: recent_requests.2,
                 window: "minute".to_string(),
@@ -269,9 +469,153 @@ impl RateLimiter {
         }
     }
 
+    /// Check the rate limit for a request, using the tenant quota in `PerTenant` mode
+    ///
+    /// Requests without a tenant ID fall back to the per-client quota.
+    pub async fn check_request(
+        &self,
+        client: &RateLimitClient,
+        tenant_id: Option<&str>,
+    ) -> RateLimitResult {
+        match (self.mode, tenant_id) {
+            (RateLimitMode::PerTenant, Some(tenant_id)) => self.check_tenant_rate_limit(tenant_id),
+            (
+                RateLimitMode::SlidingWindow {
+                    window_secs,
+                    max_requests,
+                },
+                _,
+            ) => self.check_sliding_window(client, window_secs, max_requests),
+            _ => self.check_rate_limit(client).await,
+        }
+    }
+
+    /// Check and count a request against the client's sliding-window quota
+    pub fn check_sliding_window(
+        &self,
+        client: &RateLimitClient,
+        window_secs: u64,
+        max_requests: u64,
+    ) -> RateLimitResult {
+        if !self.current_config().1 {
+            return RateLimitResult::Allowed;
+        }
+
+        self.check_sliding_window_at(
+            client.as_str(),
+            std::time::Duration::from_secs(window_secs),
+            max_requests,
+            Instant::now(),
+        )
+    }
+
+    /// Sliding-window check as of `now`: prune timestamps older than the
+    /// window, then allow and record the request if the rest fit the quota
+    fn check_sliding_window_at(
+        &self,
+        client_id: &str,
+        window: std::time::Duration,
+        max_requests: u64,
+        now: Instant,
+    ) -> RateLimitResult {
+        let mut timestamps = self
+            .sliding_windows
+            .entry(client_id.to_string())
+            .or_default();
+        while timestamps
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) >= window)
+        {
+            timestamps.pop_front();
+        }
+
+        let limit = u32::try_from(max_requests).unwrap_or(u32::MAX);
+        let count = timestamps.len() as u64;
+        if count >= max_requests {
+            // Quota frees up once the oldest request slides out of the window
+            let retry_in = timestamps
+                .front()
+                .map(|oldest| window.saturating_sub(now.saturating_duration_since(*oldest)))
+                .unwrap_or(window);
+            return RateLimitResult::Limited {
+                retry_after: retry_in.as_secs().max(1),
+                limit: RateLimitInfo {
+                    limit,
+                    remaining: 0,
+                    reset_at: Utc::now()
+                        + Duration::from_std(retry_in).unwrap_or_else(|_| Duration::zero()),
+                    window: "sliding".to_string(),
+                },
+            };
+        }
+        timestamps.push_back(now);
+
+        let remaining = u32::try_from(max_requests - count - 1).unwrap_or(u32::MAX);
+        let reset_at = Utc::now() + Duration::from_std(window).unwrap_or_else(|_| Duration::zero());
+        RateLimitResult::AllowedWithInfo {
+            remaining,
+            reset_at,
+            limit: RateLimitInfo {
+                limit,
+                remaining,
+                reset_at,
+                window: "sliding".to_string(),
+            },
+        }
+    }
+
+    /// Check and count a request against the tenant's per-window quota
+    pub fn check_tenant_rate_limit(&self, tenant_id: &str) -> RateLimitResult {
+        let (config, enabled) = self.current_config();
+        if !enabled {
+            return RateLimitResult::Allowed;
+        }
+
+        let window = std::time::Duration::from_secs(config.window_size_seconds);
+        let limit = config.requests_per_minute;
+        let mut bucket = self
+            .tenant_buckets
+            .entry(tenant_id.to_string())
+            .or_insert_with(|| (AtomicU64::new(0), Instant::now()));
+
+        let elapsed = bucket.1.elapsed();
+        if elapsed >= window {
+            *bucket = (AtomicU64::new(0), Instant::now());
+        }
+        let window_remaining = window.saturating_sub(bucket.1.elapsed());
+        let reset_at =
+            Utc::now() + Duration::from_std(window_remaining).unwrap_or_else(|_| Duration::zero());
+
+        let count = bucket.0.load(Ordering::Relaxed);
+        if count >= limit as u64 {
+            return RateLimitResult::Limited {
+                retry_after: window_remaining.as_secs().max(1),
+                limit: RateLimitInfo {
+                    limit,
+                    remaining: 0,
+                    reset_at,
+                    window: "minute".to_string(),
+                },
+            };
+        }
+        bucket.0.fetch_add(1, Ordering::Relaxed);
+
+        let remaining = limit.saturating_sub(count as u32 + 1);
+        RateLimitResult::AllowedWithInfo {
+            remaining,
+            reset_at,
+            limit: RateLimitInfo {
+                limit,
+                remaining,
+                reset_at,
+                window: "minute".to_string(),
+            },
+        }
+    }
+
     /// Record a request for a client
     pub async fn record_request(&self, client: &RateLimitClient) {
-        if !self.enabled {
+        if !self.current_config().1 {
             return;
         }
 
@@ -287,6 +631,7 @@ impl RateLimiter {
 
     /// Get current usage stats for a client
     pub async fn get_usage_stats(&self, client: &RateLimitClient) -> Vec<RateLimitInfo> {
+        let (config, _) = self.current_config();
         let client_id = client.as_str();
         let now = Utc::now();
         let history = self.request_history.read().await;
@@ -312,29 +657,22 @@ impl RateLimiter {
 
         vec![
             RateLimitInfo {
-                limit: self.config.requests_per_minute,
-                remaining: self
-                    .config
+                limit: config.requests_per_minute,
+                remaining: config
                     .requests_per_minute
                     .saturating_sub(minute_count as u32),
                 reset_at: now + Duration::minutes(1),
                 window: "minute".to_string(),
             },
             RateLimitInfo {
-                limit: self.config.requests_per_hour,
-                remaining: self
-                    .config
-                    .requests_per_hour
-                    .saturating_sub(hour_count as u32),
+                limit: config.requests_per_hour,
+                remaining: config.requests_per_hour.saturating_sub(hour_count as u32),
                 reset_at: now + Duration::hours(1),
                 window: "hour".to_string(),
             },
             RateLimitInfo {
-                limit: self.config.requests_per_day,
-                remaining: self
-                    .config
-                    .requests_per_day
-                    .saturating_sub(day_count as u32),
+                limit: config.requests_per_day,
+                remaining: config.requests_per_day.saturating_sub(day_count as u32),
                 reset_at: now + Duration::days(1),
                 window: "day".to_string(),
             },
@@ -352,9 +690,40 @@ impl RateLimiter {
     pub async fn clear_all(&self) {
         let mut history = self.request_history.write().await;
         history.clear();
+        self.tenant_buckets.clear();
+        self.sliding_windows.clear();
+    }
+}
+
+/// Rate limit config for the per-minute and per-hour limits in the security settings
+fn config_from_limits(
+    requests_per_minute: u32,
+    requests_per_hour: u32,
+    burst_size: u32,
+) -> RateLimitConfig {
+    RateLimitConfig {
+        requests_per_minute,
+        requests_per_hour,
+        requests_per_day: requests_per_hour * 24,
+        burst_size,
+        ..Default::default()
     }
 }
 
+/// Remove tenant buckets whose window ended before the last sweep
+fn evict_stale_buckets(buckets: &TenantBuckets, window: std::time::Duration) {
+    buckets.retain(|_, (_, window_start)| window_start.elapsed() < window);
+}
+
+/// Remove clients whose most recent request fell out of the window
+fn evict_idle_windows(windows: &SlidingWindows, window: std::time::Duration, now: Instant) {
+    windows.retain(|_, timestamps| {
+        timestamps
+            .back()
+            .is_some_and(|t| now.saturating_duration_since(*t) < window)
+    });
+}
+
 /// Async trait for rate limiters (allows custom implementations)
 #[async_trait]
 pub trait AsyncRateLimiter: Send + Sync {
@@ -385,7 +754,23 @@ impl AsyncRateLimiter for RateLimiter {
 pub struct RateLimitMiddleware;
 
 impl RateLimitMiddleware {
+    /// Extract the tenant ID used for per-tenant limits
+    ///
+    /// Only authenticated requests have a tenant, taken from their claims.
+    /// Client-supplied headers such as `X-Tenant-Id` are ignored, so anonymous
+    /// callers cannot spread their traffic over many tenant budgets.
+    pub fn extract_tenant_id<B>(
+        _req: &axum::http::Request<B>,
+        claims: Option<&crate::security::auth::Claims>,
+    ) -> Option<String> {
+        claims.map(|claims| claims.tenant_id.clone())
+    }
+
     /// Extract client identifier from request
+    ///
+    /// Authenticated requests are keyed by their subject. Anonymous requests
+    /// are keyed by client IP; unverified credential headers are ignored so
+    /// they cannot be rotated to obtain fresh quotas.
     pub fn extract_client_id<B>(
         req: &axum::http::Request<B>,
         claims: Option<&crate::security::auth::Claims>,
@@ -395,24 +780,7 @@ impl RateLimitMiddleware {
             return RateLimitClient::from_jwt_subject(&claims.sub);
         }
 
-        // Fall back to API key header
-        if let Some(api_key) = req.headers().get("X-API-Key") {
-            if let Ok(key) = api_key.to_str() {
-                return RateLimitClient::from_api_key(key);
-            }
-        }
-
-        // Fall back to Authorization header
-        if let Some(auth) = req.headers().get("Authorization") {
-            if let Ok(auth_str) = auth.to_str() {
-                if auth_str.starts_with("ApiKey ") {
-                    let key = &auth_str[7..];
-                    return RateLimitClient::from_api_key(key);
-                }
-            }
-        }
-
-        // Finally, use IP address
+        // Otherwise use the client IP address
         if let Some(ip) = req.headers().get("X-Forwarded-For") {
             if let Ok(ip_str) = ip.to_str() {
                 return RateLimitClient::from_ip(ip_str.split(',').next().unwrap_or(ip_str).trim());
@@ -425,12 +793,272 @@ impl RateLimitMiddleware {
             }
         }
 
-        // Use peer address if available (try extensions first)
+        // Use the peer address recorded by the server
+        if let Some(axum::extract::ConnectInfo(peer)) = req
+            .extensions()
+            .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+        {
+            return RateLimitClient::from_ip(&peer.ip().to_string());
+        }
         if let Some(peer) = req.extensions().get::<std::net::SocketAddr>() {
-            return RateLimitClient::from_ip(&peer.to_string());
+            return RateLimitClient::from_ip(&peer.ip().to_string());
+        }
+
+        // Requests without any address share one anonymous quota
+        RateLimitClient::Custom("anonymous".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tenant_config(requests_per_minute: u32) -> RateLimitConfig {
+        RateLimitConfig {
+            requests_per_minute,
+            ..RateLimitConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_per_tenant_quota_is_isolated() {
+        let limiter = RateLimiter::per_tenant(tenant_config(2), true);
+        assert_eq!(limiter.mode(), RateLimitMode::PerTenant);
+        let client = RateLimitClient::from_ip("10.0.0.1");
+
+        for _ in 0..2 {
+            assert!(matches!(
+                limiter.check_request(&client, Some("tenant_a")).await,
+                RateLimitResult::AllowedWithInfo { .. }
+            ));
         }
+        assert!(matches!(
+            limiter.check_request(&client, Some("tenant_a")).await,
+            RateLimitResult::Limited { .. }
+        ));
+
+        // A busy tenant does not consume another tenant's quota
+        assert!(matches!(
+            limiter.check_request(&client, Some("tenant_b")).await,
+            RateLimitResult::AllowedWithInfo { remaining: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_evict_stale_buckets() {
+        let buckets = TenantBuckets::new();
+        buckets.insert("fresh".to_string(), (AtomicU64::new(1), Instant::now()));
+        buckets.insert(
+            "stale".to_string(),
+            (
+                AtomicU64::new(1),
+                Instant::now() - std::time::Duration::from_secs(120),
+            ),
+        );
+
+        evict_stale_buckets(&buckets, std::time::Duration::from_secs(60));
+        assert!(buckets.contains_key("fresh"));
+        assert!(!buckets.contains_key("stale"));
+    }
+
+    #[test]
+    fn test_sliding_window_prevents_burst_at_window_boundary() {
+        let window = std::time::Duration::from_secs(60);
+        let start = Instant::now();
+        let at = |secs: u64| start + std::time::Duration::from_secs(secs);
+
+        // A fixed window resets its counter at the boundary, so a full quota
+        // spent just before it is followed by another full quota just after
+        let fixed = RateLimiter::per_tenant(tenant_config(2), true);
+        fixed.tenant_buckets.insert(
+            "tenant_a".to_string(),
+            (AtomicU64::new(2), Instant::now() - window),
+        );
+        assert!(matches!(
+            fixed.check_tenant_rate_limit("tenant_a"),
+            RateLimitResult::AllowedWithInfo { remaining: 1, .. }
+        ));
+
+        // The sliding window still counts the requests from one second ago
+        let sliding = RateLimiter::sliding_window(RateLimitConfig::default(), true, 60, 2);
+        for secs in [59, 59] {
+            assert!(matches!(
+                sliding.check_sliding_window_at("client", window, 2, at(secs)),
+                RateLimitResult::AllowedWithInfo { .. }
+            ));
+        }
+        assert!(matches!(
+            sliding.check_sliding_window_at("client", window, 2, at(61)),
+            RateLimitResult::Limited {
+                retry_after: 58,
+                ..
+            }
+        ));
+
+        // Quota returns once the burst has slid out of the window
+        assert!(matches!(
+            sliding.check_sliding_window_at("client", window, 2, at(119)),
+            RateLimitResult::AllowedWithInfo { remaining: 1, .. }
+        ));
+
+        evict_idle_windows(&sliding.sliding_windows, window, at(200));
+        assert!(sliding.sliding_windows.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_mode_from_settings() {
+        let path =
+            std::env::temp_dir().join(format!("hippos-security-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "rate_limit_enabled = true\n[rate_limit_mode.sliding_window]\nwindow_secs = 60\nmax_requests = 1\n",
+        )
+        .unwrap();
+        let limiter =
+            RateLimiter::from_reloadable(ReloadableSecuritySettings::load(&path).unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            limiter.mode(),
+            RateLimitMode::SlidingWindow {
+                window_secs: 60,
+                max_requests: 1
+            }
+        );
+
+        let client = RateLimitClient::from_ip("10.0.0.1");
+        assert!(matches!(
+            limiter.check_request(&client, Some("tenant_a")).await,
+            RateLimitResult::AllowedWithInfo { remaining: 0, .. }
+        ));
+        assert!(matches!(
+            limiter.check_request(&client, Some("tenant_a")).await,
+            RateLimitResult::Limited { .. }
+        ));
+    }
+
+    #[test]
+    fn test_sliding_window_rejects_zero_window() {
+        let path =
+            std::env::temp_dir().join(format!("hippos-security-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "[rate_limit_mode.sliding_window]\nwindow_secs = 0\nmax_requests = 10\n",
+        )
+        .unwrap();
+        let result = ReloadableSecuritySettings::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(AppError::Config(_))));
+
+        let panic = std::panic::catch_unwind(|| {
+            RateLimiter::sliding_window(RateLimitConfig::default(), true, 0, 10)
+        });
+        assert!(panic.is_err());
+    }
+
+    #[test]
+    fn test_extract_tenant_id_ignores_header_for_anonymous_requests() {
+        let req = axum::http::Request::builder()
+            .header("X-Tenant-Id", "tenant_a")
+            .body(())
+            .unwrap();
+        assert_eq!(RateLimitMiddleware::extract_tenant_id(&req, None), None);
+    }
+
+    #[test]
+    fn test_extract_client_id_keys_anonymous_requests_by_ip() {
+        let peer = std::net::SocketAddr::from(([10, 0, 0, 7], 40000));
+        let rotating_key = |key: &str| {
+            let mut req = axum::http::Request::builder()
+                .header("X-API-Key", key)
+                .header("X-Tenant-Id", key)
+                .body(())
+                .unwrap();
+            req.extensions_mut()
+                .insert(axum::extract::ConnectInfo(peer));
+            RateLimitMiddleware::extract_client_id(&req, None)
+        };
+
+        assert_eq!(rotating_key("key-1").as_str(), "10.0.0.7");
+        assert_eq!(rotating_key("key-2").as_str(), "10.0.0.7");
+
+        let req = axum::http::Request::builder().body(()).unwrap();
+        assert_eq!(
+            RateLimitMiddleware::extract_client_id(&req, None).as_str(),
+            RateLimitMiddleware::extract_client_id(&req, None).as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_production_settings_build_per_tenant_limiter() {
+        let limiter = RateLimiter::from_security_settings(&SecuritySettings::production());
+        assert_eq!(limiter.mode(), RateLimitMode::PerTenant);
+        assert_eq!(
+            RateLimiter::from_security_settings(&SecuritySettings::development()).mode(),
+            RateLimitMode::PerClient
+        );
+    }
+
+    #[test]
+    fn test_extract_tenant_id_prefers_claims_over_header() {
+        let claims = crate::security::auth::Claims::new(
+            "user_1".to_string(),
+            "tenant_a".to_string(),
+            "user".to_string(),
+            3600,
+            "hippos".to_string(),
+            "hippos-api".to_string(),
+        );
+        let req = axum::http::Request::builder()
+            .header("X-Tenant-Id", "tenant_b")
+            .body(())
+            .unwrap();
+
+        assert_eq!(
+            RateLimitMiddleware::extract_tenant_id(&req, Some(&claims)),
+            Some("tenant_a".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reloaded_settings_apply_to_next_request() {
+        let path =
+            std::env::temp_dir().join(format!("hippos-security-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "rate_limit_enabled = true\nrate_limit_requests_per_minute = 1\nrate_limit_requests_per_hour = 100\n",
+        )
+        .unwrap();
+        let settings = ReloadableSecuritySettings::load(&path).unwrap();
+        let limiter = RateLimiter::from_reloadable(settings.clone());
+        let client = RateLimitClient::from_ip("10.0.0.1");
+
+        assert!(matches!(
+            limiter.check_rate_limit(&client).await,
+            RateLimitResult::AllowedWithInfo { .. }
+        ));
+        assert!(matches!(
+            limiter.check_rate_limit(&client).await,
+            RateLimitResult::Limited { .. }
+        ));
+
+        std::fs::write(
+            &path,
+            "rate_limit_enabled = true\nrate_limit_requests_per_minute = 5\nrate_limit_requests_per_hour = 100\n",
+        )
+        .unwrap();
+        settings.reload().unwrap();
+        assert!(matches!(
+            limiter.check_rate_limit(&client).await,
+            RateLimitResult::AllowedWithInfo { remaining: 4, .. }
+        ));
+
+        std::fs::write(&path, "rate_limit_enabled = false\n").unwrap();
+        settings.reload().unwrap();
+        assert!(matches!(
+            limiter.check_rate_limit(&client).await,
+            RateLimitResult::Allowed
+        ));
 
-        // Generate a random client ID as last resort
-        RateLimitClient::Custom(format!("unknown-{}", uuid::Uuid::new_v4()))
+        std::fs::remove_file(&path).unwrap();
     }
 }
@@ -4,7 +4,15 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
+use std::path::Path;
+
+use crate::error::{AppError, Result};
+use crate::security::config::SecuritySettings;
+
+/// Built-in policy used when no policy is configured
+pub const DEFAULT_RBAC_POLICY: &str = include_str!("../../policies.toml");
 
 /// Role enumeration for access control
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -15,6 +23,10 @@ pub enum Role {
     TenantAdmin,
     /// Regular user
     User,
+    /// Read and write access to sessions and turns
+    ReadWrite,
+    /// MCP agent clients
+    Mcp,
     /// Read-only access
     ReadOnly,
 }
@@ -25,6 +37,8 @@ impl fmt::Display for Role {
             Role::Admin => write!(f, "admin"),
             Role::TenantAdmin => write!(f, "tenant_admin"),
             Role::User => write!(f, "user"),
+            Role::ReadWrite => write!(f, "read_write"),
+            Role::Mcp => write!(f, "mcp"),
             Role::ReadOnly => write!(f, "read_only"),
         }
     }
@@ -33,12 +47,19 @@ impl fmt::Display for Role {
 impl Role {
     /// Convert from string to Role
     pub fn from_string(s: &str) -> Self {
+        Self::from_name(s).unwrap_or(Role::User)
+    }
+
+    /// Parse a role name, returning `None` for unknown roles
+    pub fn from_name(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
-            "admin" => Role::Admin,
-            "tenant_admin" | "tenantadmin" => Role::TenantAdmin,
-            "user" => Role::User,
-            "read_only" | "readonly" | "read" => Role::ReadOnly,
-            _ => Role::User,
+            "admin" => Some(Role::Admin),
+            "tenant_admin" | "tenantadmin" => Some(Role::TenantAdmin),
+            "user" => Some(Role::User),
+            "read_write" | "readwrite" => Some(Role::ReadWrite),
+            "mcp" => Some(Role::Mcp),
+            "read_only" | "readonly" | "read" => Some(Role::ReadOnly),
+            _ => None,
         }
     }
 
@@ -51,6 +72,17 @@ impl Role {
     pub fn is_elevated(&self) -> bool {
         matches!(self, Role::Admin | Role::TenantAdmin)
     }
+
+    /// Roles whose permissions this role inherits
+    ///
+    /// Inheritance forms a DAG: `admin -> read_write -> read_only` and `admin -> user`.
+    pub fn inherited_roles(&self) -> &'static [Role] {
+        match self {
+            Role::Admin => &[Role::ReadWrite, Role::User],
+            Role::ReadWrite => &[Role::ReadOnly],
+            _ => &[],
+        }
+    }
 }
 
 /// Resource types that can be protected
@@ -83,6 +115,21 @@ impl fmt::Display for ResourceType {
     }
 }
 
+impl ResourceType {
+    /// Parse a resource name, returning `None` for unknown resources
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "session" => Some(ResourceType::Session),
+            "turn" => Some(ResourceType::Turn),
+            "index" => Some(ResourceType::Index),
+            "system" => Some(ResourceType::System),
+            "user" => Some(ResourceType::User),
+            "all" | "*" => Some(ResourceType::All),
+            _ => None,
+        }
+    }
+}
+
 /// Action types that can be performed on resources
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ActionType {
@@ -116,6 +163,22 @@ impl fmt::Display for ActionType {
     }
 }
 
+impl ActionType {
+    /// Parse an action name, returning `None` for unknown actions
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "create" => Some(ActionType::Create),
+            "read" => Some(ActionType::Read),
+            "update" => Some(ActionType::Update),
+            "delete" => Some(ActionType::Delete),
+            "search" => Some(ActionType::Search),
+            "manage" => Some(ActionType::Manage),
+            "all" | "*" => Some(ActionType::All),
+            _ => None,
+        }
+    }
+}
+
 /// Permission definition combining resource and action
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Permission {
@@ -182,7 +245,7 @@ pub fn get_default_permissions(role: &Role) -> Vec<Permission> {
             // Read-only on system
             Permission::new(ResourceType::System, ActionType::Read),
         ],
-        Role::User => vec![
+        Role::User | Role::ReadWrite => vec![
             // CRUD on own sessions and turns
             Permission::new(ResourceType::Session, ActionType::Create),
             Permission::new(ResourceType::Session, ActionType::Read),
@@ -197,6 +260,15 @@ pub fn get_default_permissions(role: &Role) -> Vec<Permission> {
             Permission::new(ResourceType::Index, ActionType::Read),
             // No system access
         ],
+        Role::Mcp => vec![
+            // Agents record and read conversations and search them
+            Permission::new(ResourceType::Session, ActionType::Create),
+            Permission::new(ResourceType::Session, ActionType::Read),
+            Permission::new(ResourceType::Turn, ActionType::Create),
+            Permission::new(ResourceType::Turn, ActionType::Read),
+            Permission::new(ResourceType::Index, ActionType::Read),
+            Permission::new(ResourceType::Index, ActionType::Search),
+        ],
         Role::ReadOnly => vec![
             // Read-only access
             Permission::new(ResourceType::Session, ActionType::Read),
@@ -244,12 +316,63 @@ impl SimpleAuthorizer {
                 get_default_permissions(&Role::TenantAdmin),
             ),
             (Role::User, get_default_permissions(&Role::User)),
+            (Role::ReadWrite, get_default_permissions(&Role::ReadWrite)),
+            (Role::Mcp, get_default_permissions(&Role::Mcp)),
             (Role::ReadOnly, get_default_permissions(&Role::ReadOnly)),
         ];
 
         Self { permissions }
     }
 
+    /// Create an authorizer from a TOML policy file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let policy = std::fs::read_to_string(path).map_err(|e| {
+            AppError::Config(format!(
+                "Failed to read RBAC policy {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::from_policy_str(&policy)
+    }
+
+    /// Create an authorizer from `SecuritySettings::rbac_policy`,
+    /// falling back to the built-in policy when none is set
+    pub fn from_config(settings: &SecuritySettings) -> Result<Self> {
+        Self::from_policy_str(
+            settings
+                .rbac_policy
+                .as_deref()
+                .unwrap_or(DEFAULT_RBAC_POLICY),
+        )
+    }
+
+    /// Create an authorizer from a TOML policy string
+    ///
+    /// Roles missing from the policy are granted no permissions
+    /// (except `admin`, which always has full access).
+    pub fn from_policy_str(policy: &str) -> Result<Self> {
+        let policy: PolicyFile = toml::from_str(policy)
+            .map_err(|e| AppError::Config(format!("Invalid RBAC policy: {}", e)))?;
+
+        let mut permissions = Vec::with_capacity(policy.roles.len());
+        for (name, role_policy) in policy.roles {
+            let role = Role::from_name(&name).ok_or_else(|| {
+                AppError::Config(format!("Unknown role in RBAC policy: {}", name))
+            })?;
+
+            let role_permissions = role_policy
+                .permissions
+                .into_iter()
+                .map(|p| p.into_permission(&name))
+                .collect::<Result<Vec<_>>>()?;
+
+            permissions.push((role, role_permissions));
+        }
+
+        Ok(Self { permissions })
+    }
+
     /// Create development authorizer
     pub fn development() -> Self {
         Self::new()
@@ -261,6 +384,85 @@ impl SimpleAuthorizer {
         self.permissions.push((role, permissions));
         self
     }
+
+    /// Permissions granted directly to a role, without inheritance
+    ///
+    /// Roles missing from the policy get none, except `admin`, which falls back
+    /// to its default full access.
+    fn direct_permissions(&self, role: &Role) -> Vec<Permission> {
+        match self.permissions.iter().find(|(r, _)| r == role) {
+            Some((_, perms)) => perms.clone(),
+            None if role.is_admin() => get_default_permissions(role),
+            None => Vec::new(),
+        }
+    }
+
+    /// Permissions granted to a role, including those of every role it inherits from
+    fn effective_permissions(&self, role: &Role) -> Vec<Permission> {
+        let mut pending = vec![role.clone()];
+        let mut visited = Vec::new();
+        let mut permissions: Vec<Permission> = Vec::new();
+
+        while let Some(role) = pending.pop() {
+            if visited.contains(&role) {
+                continue;
+            }
+
+            for permission in self.direct_permissions(&role) {
+                if !permissions.contains(&permission) {
+                    permissions.push(permission);
+                }
+            }
+
+            pending.extend(role.inherited_roles().iter().cloned());
+            visited.push(role);
+        }
+
+        permissions
+    }
+}
+
+/// RBAC policy file layout
+#[derive(Debug, Deserialize)]
+struct PolicyFile {
+    #[serde(default)]
+    roles: HashMap<String, RolePolicy>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RolePolicy {
+    #[serde(default)]
+    permissions: Vec<PolicyPermission>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolicyPermission {
+    resource: String,
+    action: String,
+    resource_id: Option<String>,
+}
+
+impl PolicyPermission {
+    fn into_permission(self, role: &str) -> Result<Permission> {
+        let resource = ResourceType::from_name(&self.resource).ok_or_else(|| {
+            AppError::Config(format!(
+                "Unknown resource '{}' for role {}",
+                self.resource, role
+            ))
+        })?;
+        let action = ActionType::from_name(&self.action).ok_or_else(|| {
+            AppError::Config(format!(
+                "Unknown action '{}' for role {}",
+                self.action, role
+            ))
+        })?;
+
+        Ok(Permission {
+            resource,
+            action,
+            resource_id: self.resource_id,
+        })
+    }
 }
 
 impl Default for SimpleAuthorizer {
@@ -274,29 +476,14 @@ impl Authorizer for SimpleAuthorizer {
     async fn check_permission(&self, claims: &Claims, permission: &Permission) -> bool {
         let role = Role::from_string(&claims.role);
 
-        // Admin has all permissions
-        if role.is_admin() {
-            return true;
-        }
-
-        // Check role permissions
-        if let Some((_, perms)) = self.permissions.iter().find(|(r, _)| *r == role) {
-            for perm in perms {
-                if perm.matches(permission) {
-                    return true;
-                }
-            }
-        }
-
-        false
+        // Check role permissions, including inherited roles
+        self.effective_permissions(&role)
+            .iter()
+            .any(|perm| perm.matches(permission))
     }
 
     async fn get_role_permissions(&self, role: &Role) -> Vec<Permission> {
-        if let Some((_, perms)) = self.permissions.iter().find(|(r, _)| *r == *role) {
-            perms.clone()
-        } else {
-            get_default_permissions(role)
-        }
+        self.effective_permissions(role)
     }
 
     async fn can_access_resource(
@@ -341,3 +528,191 @@ impl ClaimsExt for Claims {
         self.is_admin() || self.tenant_id() == tenant_id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(role: &str) -> Claims {
+        Claims::new(
+            "user_1".to_string(),
+            "tenant_1".to_string(),
+            role.to_string(),
+            3600,
+            "hippos".to_string(),
+            "hippos-api".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_default_policy_roles() {
+        let authorizer = SimpleAuthorizer::from_config(&SecuritySettings::default()).unwrap();
+        let write_turn = Permission::new(ResourceType::Turn, ActionType::Create);
+        let delete_turn = Permission::new(ResourceType::Turn, ActionType::Delete);
+
+        assert!(
+            authorizer
+                .check_permission(&claims("read_write"), &delete_turn)
+                .await
+        );
+        assert!(
+            authorizer
+                .check_permission(&claims("mcp"), &write_turn)
+                .await
+        );
+        assert!(
+            !authorizer
+                .check_permission(&claims("mcp"), &delete_turn)
+                .await
+        );
+        assert!(
+            !authorizer
+                .check_permission(&claims("read_only"), &write_turn)
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_policy_from_str_replaces_defaults() {
+        let policy = r#"
+            [roles.read_only]
+            permissions = [{ resource = "session", action = "read" }]
+        "#;
+        let authorizer = SimpleAuthorizer::from_policy_str(policy).unwrap();
+
+        let read_session = Permission::new(ResourceType::Session, ActionType::Read);
+        let search_index = Permission::new(ResourceType::Index, ActionType::Search);
+        assert!(
+            authorizer
+                .check_permission(&claims("read_only"), &read_session)
+                .await
+        );
+        assert!(
+            !authorizer
+                .check_permission(&claims("read_only"), &search_index)
+                .await
+        );
+        assert!(
+            !authorizer
+                .check_permission(&claims("user"), &read_session)
+                .await
+        );
+    }
+
+    #[test]
+    fn test_policy_rejects_unknown_names() {
+        let unknown_role = r#"
+            [roles.superuser]
+            permissions = []
+        "#;
+        assert!(matches!(
+            SimpleAuthorizer::from_policy_str(unknown_role),
+            Err(AppError::Config(_))
+        ));
+
+        let unknown_action = r#"
+            [roles.user]
+            permissions = [{ resource = "session", action = "fly" }]
+        "#;
+        assert!(matches!(
+            SimpleAuthorizer::from_policy_str(unknown_action),
+            Err(AppError::Config(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_role_inheritance() {
+        let authorizer = SimpleAuthorizer::new()
+            .with_permissions(
+                Role::ReadOnly,
+                vec![Permission::new(ResourceType::System, ActionType::Read)],
+            )
+            .with_permissions(Role::ReadWrite, Vec::new());
+
+        let read_system = Permission::new(ResourceType::System, ActionType::Read);
+        assert!(
+            authorizer
+                .check_permission(&claims("read_write"), &read_system)
+                .await
+        );
+        assert!(
+            authorizer
+                .check_permission(&claims("read_only"), &read_system)
+                .await
+        );
+        assert!(
+            !authorizer
+                .check_permission(&claims("user"), &read_system)
+                .await
+        );
+        assert!(
+            !authorizer
+                .check_permission(&claims("mcp"), &read_system)
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_admin_inherits_user_only_permission() {
+        let user_only =
+            Permission::new_with_id(ResourceType::System, ActionType::Read, "status".to_string());
+        let authorizer = SimpleAuthorizer::new()
+            .with_permissions(Role::Admin, Vec::new())
+            .with_permissions(Role::User, vec![user_only.clone()]);
+
+        assert!(
+            authorizer
+                .check_permission(&claims("admin"), &user_only)
+                .await
+        );
+        assert!(
+            authorizer
+                .check_permission(&claims("user"), &user_only)
+                .await
+        );
+        assert!(
+            !authorizer
+                .check_permission(&claims("read_write"), &user_only)
+                .await
+        );
+
+        let admin_permissions = authorizer.get_role_permissions(&Role::Admin).await;
+        assert!(admin_permissions.contains(&user_only));
+        // Defaults of read_write and read_only are collected along the chain too
+        let delete_turn = Permission::new(ResourceType::Turn, ActionType::Delete);
+        assert!(admin_permissions.contains(&delete_turn));
+        assert!(
+            !authorizer
+                .get_role_permissions(&Role::User)
+                .await
+                .contains(&Permission::new(ResourceType::System, ActionType::Manage))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_admin_missing_from_policy_keeps_full_access() {
+        let policy = r#"
+            [roles.user]
+            permissions = [{ resource = "session", action = "read" }]
+        "#;
+        let authorizer = SimpleAuthorizer::from_policy_str(policy).unwrap();
+        let manage_system = Permission::new(ResourceType::System, ActionType::Manage);
+
+        assert!(
+            authorizer
+                .check_permission(&claims("admin"), &manage_system)
+                .await
+        );
+        assert!(
+            !authorizer
+                .check_permission(&claims("user"), &manage_system)
+                .await
+        );
+        assert!(authorizer.get_role_permissions(&Role::Mcp).await.is_empty());
+    }
+
+    #[test]
+    fn test_from_file_missing() {
+        assert!(SimpleAuthorizer::from_file(Path::new("/nonexistent/policies.toml")).is_err());
+    }
+}
@@ -353,10 +353,54 @@ pub mod validators {
         validator.validate_length("content", content, Some(1), Some(100_000))
     }
 
+    /// Shortest accepted search query (after trimming)
+    pub const MIN_SEARCH_QUERY_LEN: usize = 2;
+
+    /// Longest accepted search query
+    pub const MAX_SEARCH_QUERY_LEN: usize = 1000;
+
+    /// Longest accepted session ID
+    pub const MAX_SESSION_ID_LEN: usize = 255;
+
     /// Validate search query
+    ///
+    /// Rejects queries that are too short or long to be useful, and purely
+    /// numeric queries, which only waste embedding computation.
     pub fn validate_search_query(query: &str) -> ValidationResult<()> {
-        let validator = RequestValidator::new();
-        validator.validate_length("query", query, Some(1), Some(1000))
+        let trimmed_len = query.trim().chars().count();
+        let is_numeric = query.chars().all(|c| c.is_numeric() || c.is_whitespace());
+        if trimmed_len < MIN_SEARCH_QUERY_LEN
+            || query.chars().count() > MAX_SEARCH_QUERY_LEN
+            || is_numeric
+        {
+            return Err(ValidationError::Custom {
+                field: "query".to_string(),
+                message: format!(
+                    "Query must be {}-{} characters and not purely numeric",
+                    MIN_SEARCH_QUERY_LEN, MAX_SEARCH_QUERY_LEN
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Validate session ID: non-empty, bounded, without whitespace or control characters
+    pub fn validate_session_id(session_id: &str) -> ValidationResult<()> {
+        if session_id.is_empty()
+            || session_id.chars().count() > MAX_SESSION_ID_LEN
+            || session_id
+                .chars()
+                .any(|c| c.is_whitespace() || c.is_control())
+        {
+            return Err(ValidationError::Custom {
+                field: "session_id".to_string(),
+                message: format!(
+                    "Session ID must be 1-{} characters without whitespace",
+                    MAX_SESSION_ID_LEN
+                ),
+            });
+        }
+        Ok(())
     }
 
     /// Validate pagination parameters
@@ -380,3 +424,61 @@ pub mod validators {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::validators::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_validate_search_query() {
+        assert!(validate_search_query("").is_err());
+        assert!(validate_search_query("1").is_err());
+        assert!(validate_search_query("12345").is_err());
+        assert!(validate_search_query("  a  ").is_err());
+        assert!(validate_search_query(&"a".repeat(MAX_SEARCH_QUERY_LEN + 1)).is_err());
+
+        assert!(validate_search_query("ok").is_ok());
+        assert!(validate_search_query("error 404").is_ok());
+        assert!(validate_search_query(&"a".repeat(MAX_SEARCH_QUERY_LEN)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_session_id() {
+        assert!(validate_session_id("session_1").is_ok());
+        assert!(validate_session_id("").is_err());
+        assert!(validate_session_id("has space").is_err());
+        assert!(validate_session_id(&"s".repeat(MAX_SESSION_ID_LEN + 1)).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_search_query_validator_never_panics(query in any::<String>()) {
+            let _ = validate_search_query(&query);
+        }
+
+        #[test]
+        fn prop_numeric_queries_rejected(query in "[0-9 ]{0,50}") {
+            prop_assert!(validate_search_query(&query).is_err());
+        }
+
+        #[test]
+        fn prop_short_queries_rejected(query in "\\s*\\PC?\\s*") {
+            prop_assert!(validate_search_query(&query).is_err());
+        }
+
+        #[test]
+        fn prop_word_queries_accepted(query in "[a-z][a-z0-9 ]{1,998}[a-z]") {
+            prop_assert!(validate_search_query(&query).is_ok());
+        }
+
+        #[test]
+        fn prop_accepted_queries_meet_rules(query in any::<String>()) {
+            if validate_search_query(&query).is_ok() {
+                prop_assert!(query.trim().chars().count() >= MIN_SEARCH_QUERY_LEN);
+                prop_assert!(query.chars().count() <= MAX_SEARCH_QUERY_LEN);
+                prop_assert!(!query.chars().all(char::is_numeric));
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! 上下文压缩服务
+//!
+//! 会话超出模型上下文窗口时，将较早的多个轮次压缩为一个系统轮次：
+//! 每个轮次优先使用脱水摘要，再抽取其中最长的若干句子。
+
+use crate::error::{AppError, Result};
+use crate::models::turn::{MessageType, Turn};
+
+/// 每个轮次保留的句子数
+pub const DEFAULT_SENTENCES_PER_TURN: usize = 3;
+
+/// 合成轮次 `metadata.custom` 中记录被压缩轮次编号范围的键
+pub const COMPRESSED_RANGE_KEY: &str = "compressed_range";
+
+/// 上下文压缩器
+#[derive(Debug, Clone)]
+pub struct ContextCompressor {
+    sentences_per_turn: usize,
+}
+
+impl ContextCompressor {
+    pub fn new() -> Self {
+        Self {
+            sentences_per_turn: DEFAULT_SENTENCES_PER_TURN,
+        }
+    }
+
+    /// 设置每个轮次保留的句子数
+    pub fn with_sentences_per_turn(mut self, sentences_per_turn: usize) -> Self {
+        self.sentences_per_turn = sentences_per_turn.max(1);
+        self
+    }
+
+    /// 将同一会话的多个轮次压缩为一个合成的系统轮次
+    ///
+    /// 轮次按编号升序处理，每个轮次输出一行 `[Turn N] ...`。合成轮次沿用最早轮次的编号，
+    /// 由调用方负责写入并删除原轮次。
+    pub fn compress_turns(&self, turns: &[Turn]) -> Result<Turn> {
+        let mut sorted: Vec<&Turn> = turns.iter().collect();
+        sorted.sort_by_key(|turn| turn.turn_number);
+
+        let (first, last) = match (sorted.first(), sorted.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => {
+                return Err(AppError::Validation("No turns to compress".to_string()));
+            }
+        };
+        if sorted
+            .iter()
+            .any(|turn| turn.session_id != first.session_id)
+        {
+            return Err(AppError::Validation(
+                "Cannot compress turns from different sessions".to_string(),
+            ));
+        }
+
+        let content = sorted
+            .iter()
+            .map(|turn| {
+                let text = turn
+                    .dehydrated
+                    .as_ref()
+                    .map(|dehydrated| dehydrated.gist.as_str())
+                    .filter(|gist| !gist.trim().is_empty())
+                    .unwrap_or(&turn.raw_content);
+                format!(
+                    "[Turn {}] {}",
+                    turn.turn_number,
+                    select_sentences(text, self.sentences_per_turn)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut compressed = Turn::new(&first.session_id, first.turn_number, &content);
+        compressed.metadata.message_type = MessageType::System;
+        compressed.metadata.custom.insert(
+            COMPRESSED_RANGE_KEY.to_string(),
+            format!("{}-{}", first.turn_number, last.turn_number),
+        );
+        Ok(compressed)
+    }
+}
+
+impl Default for ContextCompressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 选出最长的 `count` 个句子，按原文顺序以空格拼接
+fn select_sentences(text: &str, count: usize) -> String {
+    let sentences = split_sentences(text);
+
+    let mut ranked: Vec<usize> = (0..sentences.len()).collect();
+    ranked.sort_by_key(|&i| std::cmp::Reverse(sentences[i].chars().count()));
+    ranked.truncate(count);
+    ranked.sort_unstable();
+
+    ranked
+        .into_iter()
+        .map(|i| sentences[i])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 按中英文句末标点与换行切分句子（保留标点）
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if matches!(c, '.' | '!' | '?' | '。' | '！' | '？' | '\n') {
+            let end = i + c.len_utf8();
+            sentences.push(text[start..end].trim());
+            start = end;
+        }
+    }
+    sentences.push(text[start..].trim());
+
+    sentences.retain(|sentence| !sentence.is_empty());
+    sentences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::turn::DehydratedData;
+
+    #[test]
+    fn test_select_sentences_keeps_longest_in_order() {
+        let text = "Short. This is the longest sentence here! Mid length one? Tiny. Another long sentence.";
+        assert_eq!(
+            select_sentences(text, 3),
+            "This is the longest sentence here! Mid length one? Another long sentence."
+        );
+        assert_eq!(
+            select_sentences("第一句。第二句比较长！", 1),
+            "第二句比较长！"
+        );
+    }
+
+    #[test]
+    fn test_compress_turns_prefers_gists() {
+        let mut dehydrated = Turn::new("session_1", 2, "raw content that should be skipped.");
+        dehydrated.dehydrated = Some(DehydratedData {
+            gist: "User asked about lifetimes.".to_string(),
+            ..Default::default()
+        });
+        let turns = vec![dehydrated, Turn::new("session_1", 1, "Hello there.")];
+
+        let compressed = ContextCompressor::new().compress_turns(&turns).unwrap();
+        assert_eq!(compressed.session_id, "session_1");
+        assert_eq!(compressed.turn_number, 1);
+        assert_eq!(compressed.metadata.message_type, MessageType::System);
+        assert_eq!(
+            compressed.raw_content,
+            "[Turn 1] Hello there.\n[Turn 2] User asked about lifetimes."
+        );
+        assert_eq!(compressed.metadata.custom[COMPRESSED_RANGE_KEY], "1-2");
+    }
+
+    #[test]
+    fn test_compress_turns_rejects_invalid_input() {
+        let compressor = ContextCompressor::new();
+        assert!(matches!(
+            compressor.compress_turns(&[]),
+            Err(AppError::Validation(_))
+        ));
+
+        let turns = vec![
+            Turn::new("session_1", 1, "a"),
+            Turn::new("session_2", 2, "b"),
+        ];
+        assert!(matches!(
+            compressor.compress_turns(&turns),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
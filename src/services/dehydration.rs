@@ -1,21 +1,155 @@
 //! 脱水服务
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 
-use crate::error::Result;
+use crate::error::{AppError, Result};
 use crate::models::turn::{DehydratedData, Turn};
+use crate::storage::repository::TurnStore;
+
+/// 读取会话轮次时每页的轮次数
+const TURN_PAGE_SIZE: usize = 100;
+
+/// 每个 Token 约占的字节数（与 `Turn::estimated_tokens` 一致）
+const BYTES_PER_TOKEN: usize = 4;
+
+/// 批量脱水时保留完整摘要的最近轮次数
+const DETAILED_GIST_TURNS: usize = 10;
+
+/// 批量脱水时较早轮次摘要的最大字符数
+const CONDENSED_GIST_CHARS: usize = 80;
+
+/// 会话的 Token 预算估算
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenEstimate {
+    /// 使用脱水摘要组装上下文的 Token 数（未脱水的轮次按原文计算）
+    pub total_gist_tokens: usize,
+    /// 使用原文组装上下文的 Token 数
+    pub total_raw_tokens: usize,
+    /// 摘要 Token 与原文 Token 之比
+    pub dehydrated_ratio: f32,
+}
+
+impl TokenEstimate {
+    /// 根据轮次估算 Token 数
+    pub fn from_turns(turns: &[Turn]) -> Self {
+        let mut estimate = Self::default();
+        for turn in turns {
+            let raw_tokens = turn.estimated_tokens() as usize;
+            estimate.total_raw_tokens += raw_tokens;
+            estimate.total_gist_tokens += match &turn.dehydrated {
+                Some(dehydrated) => dehydrated.gist.len() / BYTES_PER_TOKEN,
+                None => raw_tokens,
+            };
+        }
+
+        if estimate.total_raw_tokens > 0 {
+            estimate.dehydrated_ratio =
+                estimate.total_gist_tokens as f32 / estimate.total_raw_tokens as f32;
+        }
+
+        estimate
+    }
+}
+
+/// 会话批量脱水报告
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DehydrationReport {
+    /// 本次脱水的轮次数
+    pub processed: usize,
+    /// 已脱水而跳过的轮次数
+    pub skipped: usize,
+    /// 脱水失败的轮次数
+    pub failed: usize,
+}
 
 #[async_trait]
 pub trait DehydrationService: Send + Sync {
     async fn generate_summary(&self, content: &str) -> Result<DehydratedData>;
     async fn extract_keywords(&self, content: &str) -> Result<Vec<String>>;
     async fn extract_topics(&self, content: &str) -> Result<Vec<String>>;
+    /// 估算会话组装为 LLM 上下文时消耗的 Token 数
+    async fn estimate_token_count(&self, session_id: &str) -> Result<TokenEstimate>;
+
+    /// 为会话中所有未脱水的轮次生成并保存脱水数据
+    ///
+    /// 按轮次编号从新到旧处理：最近 `DETAILED_GIST_TURNS` 个轮次保留完整摘要，
+    /// 更早的轮次摘要压缩到 `CONDENSED_GIST_CHARS` 个字符以内。
+    async fn dehydrate_session(
+        &self,
+        session_id: &str,
+        turn_repository: &dyn TurnStore,
+    ) -> Result<DehydrationReport> {
+        let mut turns = load_session_turns(turn_repository, session_id).await?;
+        let report = self.dehydrate_turns(&mut turns, turn_repository).await?;
+        if report.processed + report.failed > 0 {
+            tracing::info!(
+                "Dehydrated session {}: {} processed, {} skipped, {} failed",
+                session_id,
+                report.processed,
+                report.skipped,
+                report.failed
+            );
+        }
+        Ok(report)
+    }
+
+    /// 只为给定轮次中未脱水的轮次生成并保存脱水数据，成功保存后同步写回 `turns`
+    ///
+    /// 摘要长度规则与 `dehydrate_session` 相同，按给定轮次中的新旧排名计算。
+    async fn dehydrate_turns(
+        &self,
+        turns: &mut [Turn],
+        turn_repository: &dyn TurnStore,
+    ) -> Result<DehydrationReport> {
+        let total = turns.len();
+        let mut pending: Vec<&mut Turn> = turns
+            .iter_mut()
+            .filter(|turn| turn.dehydrated.is_none())
+            .collect();
+
+        let mut report = DehydrationReport {
+            skipped: total - pending.len(),
+            ..Default::default()
+        };
+
+        pending.sort_by_key(|turn| std::cmp::Reverse(turn.turn_number));
+        for (rank, turn) in pending.into_iter().enumerate() {
+            let mut summary = match self.generate_summary(&turn.raw_content).await {
+                Ok(summary) => summary,
+                Err(e) => {
+                    tracing::warn!("Failed to dehydrate turn {}: {}", turn.id, e);
+                    report.failed += 1;
+                    continue;
+                }
+            };
+            if rank >= DETAILED_GIST_TURNS {
+                summary.gist = condense_gist(&summary.gist, CONDENSED_GIST_CHARS);
+            }
+
+            match turn_repository.set_dehydrated(&turn.id, &summary).await {
+                Ok(()) => {
+                    turn.dehydrated = Some(summary);
+                    report.processed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to save dehydrated turn {}: {}", turn.id, e);
+                    report.failed += 1;
+                }
+            }
+        }
+
+        Ok(report)
+    }
 }
 
 pub struct SimpleDehydrationService {
     max_gist_length: usize,
     max_topics: usize,
     max_tags: usize,
+    turn_repository: Option<Arc<dyn TurnStore>>,
 }
 
 impl SimpleDehydrationService {
@@ -24,9 +158,16 @@ impl SimpleDehydrationService {
             max_gist_length,
             max_topics,
             max_tags,
+            turn_repository: None,
         }
     }
 
+    /// 设置轮次仓储（`estimate_token_count` 需要）
+    pub fn with_turn_repository(mut self, turn_repository: Arc<dyn TurnStore>) -> Self {
+        self.turn_repository = Some(turn_repository);
+        self
+    }
+
     fn clean_text(&self, text: &str) -> String {
         text.lines()
             .map(|line| line.trim())
@@ -159,6 +300,44 @@ impl DehydrationService for SimpleDehydrationService {
         let keywords = self.extract_basic_keywords(&cleaned);
         Ok(self.classify_topics(&cleaned, &keywords))
     }
+
+    async fn estimate_token_count(&self, session_id: &str) -> Result<TokenEstimate> {
+        let turn_repository = self.turn_repository.as_ref().ok_or_else(|| {
+            AppError::Config("Dehydration service has no turn repository configured".to_string())
+        })?;
+
+        let turns = load_session_turns(turn_repository.as_ref(), session_id).await?;
+        Ok(TokenEstimate::from_turns(&turns))
+    }
+}
+
+/// 分页读取会话的全部轮次
+async fn load_session_turns(
+    turn_repository: &dyn TurnStore,
+    session_id: &str,
+) -> Result<Vec<Turn>> {
+    let mut turns = Vec::new();
+    loop {
+        let page = turn_repository
+            .list_by_session(session_id, TURN_PAGE_SIZE, turns.len())
+            .await?;
+        let page_len = page.len();
+        turns.extend(page);
+        if page_len < TURN_PAGE_SIZE {
+            break;
+        }
+    }
+
+    Ok(turns)
+}
+
+/// 将摘要压缩到 `max_chars` 个字符以内（超出时以 "..." 结尾）
+fn condense_gist(gist: &str, max_chars: usize) -> String {
+    if gist.chars().count() <= max_chars {
+        return gist.to_string();
+    }
+    let gist = gist.strip_suffix("...").unwrap_or(gist);
+    gist.chars().take(max_chars).collect::<String>() + "..."
 }
 
 pub fn create_dehydration_service(
@@ -220,4 +399,96 @@ mod tests {
 
         assert!(topics.contains(&"AI".to_string()));
     }
+
+    #[test]
+    fn test_token_estimate_falls_back_to_raw_content() {
+        let mut dehydrated = Turn::new("session_1", 1, &"a".repeat(400));
+        dehydrated.dehydrated = Some(DehydratedData {
+            gist: "b".repeat(40),
+            topics: vec![],
+            tags: vec![],
+            embedding: None,
+            generated_at: chrono::Utc::now(),
+            generator: None,
+        });
+        let raw = Turn::new("session_1", 2, &"c".repeat(200));
+
+        let estimate = TokenEstimate::from_turns(&[dehydrated, raw]);
+        assert_eq!(estimate.total_raw_tokens, 150);
+        assert_eq!(estimate.total_gist_tokens, 60);
+        assert!((estimate.dehydrated_ratio - 0.4).abs() < 1e-6);
+
+        assert_eq!(TokenEstimate::from_turns(&[]), TokenEstimate::default());
+    }
+
+    #[test]
+    fn test_condense_gist() {
+        assert_eq!(condense_gist("short gist", 80), "short gist");
+        assert_eq!(condense_gist("abcdef...", 80), "abcdef...");
+        assert_eq!(condense_gist("数据库连接池配置", 3), "数据库...");
+        assert_eq!(condense_gist(&"a".repeat(200), 80).chars().count(), 83);
+    }
+
+    #[tokio::test]
+    async fn test_dehydrate_session_condenses_older_turns() {
+        use crate::storage::in_memory::InMemoryRepository;
+        use crate::storage::repository::Repository;
+
+        let turns = InMemoryRepository::<Turn>::new();
+        let content = "Rust ownership makes memory management explicit. ".repeat(4);
+        for turn_number in 1..=12 {
+            turns
+                .create(&Turn::new("session_1", turn_number, &content))
+                .await
+                .unwrap();
+        }
+        let mut done = Turn::new("session_1", 13, &content);
+        done.dehydrated = Some(DehydratedData {
+            gist: "already dehydrated".to_string(),
+            ..Default::default()
+        });
+        turns.create(&done).await.unwrap();
+
+        let service = SimpleDehydrationService::new(500, 5, 10);
+        let report = service
+            .dehydrate_session("session_1", &turns)
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            DehydrationReport {
+                processed: 12,
+                skipped: 1,
+                failed: 0,
+            }
+        );
+
+        // 最近 10 个未脱水的轮次（3..=12）保留完整摘要，更早的轮次被压缩
+        let stored = turns.list_by_session("session_1", 20, 0).await.unwrap();
+        let gist_chars: Vec<usize> = stored
+            .iter()
+            .map(|t| t.dehydrated.as_ref().unwrap().gist.chars().count())
+            .collect();
+        assert!(
+            gist_chars[..2]
+                .iter()
+                .all(|&n| n <= CONDENSED_GIST_CHARS + 3)
+        );
+        assert!(
+            gist_chars[2..12]
+                .iter()
+                .all(|&n| n > CONDENSED_GIST_CHARS + 3)
+        );
+        assert_eq!(
+            stored[12].dehydrated.as_ref().unwrap().gist,
+            "already dehydrated"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_estimate_token_count_requires_turn_repository() {
+        let service = SimpleDehydrationService::new(100, 5, 10);
+        let result = service.estimate_token_count("session_1").await;
+        assert!(matches!(result, Err(AppError::Config(_))));
+    }
 }
@@ -9,7 +9,9 @@
 
 use std::sync::Arc;
 use chrono::{DateTime, Utc};
+use futures_util::stream::{self, FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
 use crate::error::Result;
 use crate::models::entity::{
     Entity, EntityType, Relationship, RelationshipType,
@@ -43,6 +45,9 @@ pub struct EntityUpdates {
     pub confidence: Option<f32>,
 }
 
+/// Default number of texts processed concurrently by `discover_entities_batch`
+pub const DEFAULT_DISCOVERY_CONCURRENCY: usize = 4;
+
 /// Discovery result containing discovered entities and relationships
 #[derive(Debug, Clone, Default)]
 pub struct DiscoveryResult {
@@ -59,6 +64,31 @@ pub struct DiscoveryResult {
     pub processing_time_ms: u64,
 }
 
+/// Number of repository calls `bulk_import` keeps in flight at once
+pub const BULK_IMPORT_CONCURRENCY: usize = 16;
+
+/// Bulk import result counting the outcome of each input item
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BulkImportResult {
+    /// Entities created
+    pub successful: usize,
+
+    /// Entities skipped because an entity with the same name already exists
+    pub skipped: usize,
+
+    /// Entities that failed to persist
+    pub failed: usize,
+
+    /// Relationships created
+    pub relationships_created: usize,
+
+    /// Relationships skipped because an endpoint entity is missing
+    pub relationships_skipped: usize,
+
+    /// Relationships that failed to persist
+    pub relationships_failed: usize,
+}
+
 /// Graph traversal result with paths
 #[derive(Debug, Clone)]
 pub struct GraphTraversalResult {
@@ -323,6 +353,184 @@ impl EntityManager {
         Ok(result)
     }
 
+    /// Discover entities from multiple texts concurrently
+    ///
+    /// Each item is a `(text, source_memory_id)` pair. At most `concurrency`
+    /// discoveries run at once and results keep the order of `items`.
+    pub async fn discover_entities_batch(
+        &self,
+        items: Vec<(&str, &str)>,
+        concurrency: usize,
+    ) -> Result<Vec<DiscoveryResult>> {
+        let semaphore = Semaphore::new(concurrency.max(1));
+        let semaphore = &semaphore;
+        let total = items.len();
+
+        let mut pending: FuturesUnordered<_> = items
+            .into_iter()
+            .enumerate()
+            .map(|(index, (text, source_memory_id))| async move {
+                let _permit = semaphore.acquire().await.ok();
+                (index, self.discover_entities(text, source_memory_id).await)
+            })
+            .collect();
+
+        let mut results: Vec<Option<DiscoveryResult>> = vec![None; total];
+        while let Some((index, result)) = pending.next().await {
+            results[index] = Some(result?);
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Bulk import entities and relationships
+    ///
+    /// Entities whose name already exists, either in the repository or earlier
+    /// in the batch, are skipped and relationships pointing at them are
+    /// redirected to the existing entity. Relationships are created only after
+    /// all entities are processed and both endpoints are confirmed present.
+    /// Up to `BULK_IMPORT_CONCURRENCY` repository calls run at once.
+    pub async fn bulk_import(
+        &self,
+        entities: Vec<Entity>,
+        relationships: Vec<Relationship>,
+    ) -> Result<BulkImportResult> {
+        tracing::info!(
+            "Bulk importing {} entities and {} relationships",
+            entities.len(),
+            relationships.len()
+        );
+
+        let mut result = BulkImportResult::default();
+        let mut redirects: std::collections::HashMap<String, String> =
+            std::collections::HashMap::new();
+        let mut batch_names: std::collections::HashMap<String, String> =
+            std::collections::HashMap::new();
+        let mut to_create = Vec::with_capacity(entities.len());
+
+        for entity in entities {
+            if let Some(first_id) = batch_names.get(&entity.name) {
+                redirects.insert(entity.id, first_id.clone());
+                result.skipped += 1;
+            } else {
+                batch_names.insert(entity.name.clone(), entity.id.clone());
+                to_create.push(entity);
+            }
+        }
+
+        let mut present = std::collections::HashSet::new();
+        let mut created = stream::iter(to_create)
+            .map(|entity| async move {
+                let outcome = match self.entity_repo.discover_entity(&entity.name, "all").await {
+                    Ok(Some(existing)) => Ok((existing.id, false)),
+                    Ok(None) => self
+                        .entity_repo
+                        .create_entity(&entity)
+                        .await
+                        .map(|created| (created.id, true)),
+                    Err(e) => Err(e),
+                };
+                (entity, outcome)
+            })
+            .buffer_unordered(BULK_IMPORT_CONCURRENCY);
+
+        while let Some((entity, outcome)) = created.next().await {
+            match outcome {
+                Ok((id, is_new)) => {
+                    if is_new {
+                        result.successful += 1;
+                    } else {
+                        result.skipped += 1;
+                    }
+                    if id != entity.id {
+                        redirects.insert(entity.id, id.clone());
+                    }
+                    present.insert(id);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to import entity '{}': {}", entity.name, e);
+                    result.failed += 1;
+                }
+            }
+        }
+        drop(created);
+
+        let resolve = |id: &str| {
+            let mut id = id;
+            // Batch duplicates may point at an entity that was itself redirected
+            for _ in 0..2 {
+                match redirects.get(id) {
+                    Some(target) => id = target.as_str(),
+                    None => break,
+                }
+            }
+            id.to_string()
+        };
+        let relationships: Vec<Relationship> = relationships
+            .into_iter()
+            .map(|mut relationship| {
+                relationship.source_entity_id = resolve(&relationship.source_entity_id);
+                relationship.target_entity_id = resolve(&relationship.target_entity_id);
+                relationship
+            })
+            .collect();
+
+        let unknown: std::collections::HashSet<String> = relationships
+            .iter()
+            .flat_map(|r| [&r.source_entity_id, &r.target_entity_id])
+            .filter(|id| !present.contains(*id))
+            .cloned()
+            .collect();
+        let mut lookups = stream::iter(unknown)
+            .map(|id| async move {
+                let found = self.entity_repo.get_entity_by_id(&id).await;
+                (id, found)
+            })
+            .buffer_unordered(BULK_IMPORT_CONCURRENCY);
+
+        while let Some((id, found)) = lookups.next().await {
+            match found {
+                Ok(Some(_)) => {
+                    present.insert(id);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Failed to look up entity {}: {}", id, e),
+            }
+        }
+        drop(lookups);
+
+        let (ready, missing): (Vec<_>, Vec<_>) = relationships.into_iter().partition(|r| {
+            present.contains(&r.source_entity_id) && present.contains(&r.target_entity_id)
+        });
+        result.relationships_skipped = missing.len();
+
+        let mut outcomes = stream::iter(ready)
+            .map(|relationship| async move {
+                self.entity_repo.create_relationship(&relationship).await
+            })
+            .buffer_unordered(BULK_IMPORT_CONCURRENCY);
+
+        while let Some(outcome) = outcomes.next().await {
+            match outcome {
+                Ok(_) => result.relationships_created += 1,
+                Err(e) => {
+                    tracing::warn!("Failed to import relationship: {}", e);
+                    result.relationships_failed += 1;
+                }
+            }
+        }
+
+        tracing::info!(
+            "Bulk import finished: {} created, {} skipped, {} failed entities; {} relationships created",
+            result.successful,
+            result.skipped,
+            result.failed,
+            result.relationships_created
+        );
+
+        Ok(result)
+    }
+
     /// Merge entities (disambiguation)
     ///
     /// Combines a source entity into a target entity, resolving conflicts.
@@ -833,6 +1041,9 @@ mod tests {
     #[async_trait]
     impl EntityRepository for MockEntityRepository {
         async fn create_entity(&self, entity: &Entity) -> Result<Entity> {
+            if entity.name == "Broken" {
+                return Err(crate::error::AppError::Database("write failed".to_string()));
+            }
             Ok(entity.clone())
         }
 
@@ -868,7 +1079,12 @@ mod tests {
             Ok(relationship.clone())
         }
 
-        async fn get_relationship_by_id(&self, _id: &str) -> Result<Option<Relationship>> {
+        async fn get_relationship_by_id(&self, id: &str) -> Result<Option<Relationship>> {
+            if id == "existing_relationship" {
+                let relationship =
+                    Relationship::new("entity_a", "entity_b", RelationshipType::Uses, "memory_1");
+                return Ok(Some(relationship));
+            }
             Ok(None)
         }
 
@@ -908,6 +1124,14 @@ mod tests {
             })
         }
 
+        async fn get_hub_entities(
+            &self,
+            _tenant_id: &str,
+            _limit: usize,
+        ) -> Result<Vec<(Entity, usize)>> {
+            Ok(vec![])
+        }
+
         async fn discover_entity(&self, name: &str, _entity_type: &str) -> Result<Option<Entity>> {
             if name == "Existing" {
                 let entity = Entity::new("Existing Entity", EntityType::Person);
@@ -929,6 +1153,62 @@ mod tests {
         assert_eq!(result.entity_type, EntityType::Person);
     }
 
+    #[tokio::test]
+    async fn test_discover_entities_batch_preserves_order() {
+        let repo = Arc::new(MockEntityRepository);
+        let manager = EntityManager::new(repo);
+
+        let items = vec![
+            ("Alice met Existing", "memory_1"),
+            ("nothing here", "memory_2"),
+            ("Hippos Project", "memory_3"),
+        ];
+        let results = manager
+            .discover_entities_batch(items, DEFAULT_DISCOVERY_CONCURRENCY)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].entities[0].name, "Alice");
+        assert_eq!(results[0].existing_entities.len(), 1);
+        assert!(results[1].entities.is_empty());
+        assert_eq!(results[2].entities[0].name, "Hippos Project");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_import() {
+        let repo = Arc::new(MockEntityRepository);
+        let manager = EntityManager::new(repo);
+
+        let alice = Entity::new("Alice", EntityType::Person);
+        let alice_again = Entity::new("Alice", EntityType::Person);
+        let existing = Entity::new("Existing", EntityType::Person);
+        let broken = Entity::new("Broken", EntityType::Tool);
+        let relationships = vec![
+            Relationship::new(&alice_again.id, &existing.id, RelationshipType::Knows, "memory_1"),
+            Relationship::new(&alice.id, "existing_entity", RelationshipType::Knows, "memory_1"),
+            Relationship::new(&alice.id, &broken.id, RelationshipType::Uses, "memory_1"),
+            Relationship::new(&alice.id, "missing_entity", RelationshipType::Knows, "memory_1"),
+        ];
+
+        let result = manager
+            .bulk_import(vec![alice, alice_again, existing, broken], relationships)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            BulkImportResult {
+                successful: 1,
+                skipped: 2,
+                failed: 1,
+                relationships_created: 2,
+                relationships_skipped: 2,
+                relationships_failed: 0,
+            }
+        );
+    }
+
     #[tokio::test]
     async fn test_get_entity_existing() {
         let repo = Arc::new(MockEntityRepository);
@@ -1017,4 +1297,16 @@ mod tests {
         let result = manager.increment_frequency("existing_entity").await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_verify_relationship() {
+        let repo = Arc::new(MockEntityRepository);
+        let manager = EntityManager::new(repo);
+
+        assert!(manager.verify_relationship("existing_relationship").await.unwrap());
+        assert!(matches!(
+            manager.verify_relationship("missing_relationship").await,
+            Err(crate::error::AppError::NotFound(_))
+        ));
+    }
 }
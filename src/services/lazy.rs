@@ -0,0 +1,135 @@
+//! 按需创建的服务槽位
+//!
+//! 加载嵌入模型等服务代价较高，`LazyService` 让它们在首次使用时才创建，
+//! `AppState` 与各业务服务可共享同一个槽位。
+
+use crate::error::{AppError, Result};
+use futures_util::future::BoxFuture;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// Async initialiser for a service that is created on first use
+pub type ServiceInitializer<T> = Arc<dyn Fn() -> BoxFuture<'static, Result<Box<T>>> + Send + Sync>;
+
+/// A service that is only instantiated the first time it is requested
+///
+/// Clones share the same cell, so the initialiser runs at most once for every
+/// holder of the slot (a failed initialisation is retried on the next request).
+pub struct LazyService<T: ?Sized> {
+    name: &'static str,
+    cell: Arc<OnceCell<Arc<T>>>,
+    init: Option<ServiceInitializer<T>>,
+}
+
+impl<T: ?Sized> Clone for LazyService<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            cell: self.cell.clone(),
+            init: self.init.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized> LazyService<T> {
+    /// Creates a service slot with no initialiser
+    pub fn unconfigured(name: &'static str) -> Self {
+        Self {
+            name,
+            cell: Arc::new(OnceCell::new()),
+            init: None,
+        }
+    }
+
+    /// Creates a service slot holding an already-built service
+    pub fn ready(name: &'static str, service: Box<T>) -> Self {
+        Self {
+            name,
+            cell: Arc::new(OnceCell::new_with(Some(Arc::from(service)))),
+            init: None,
+        }
+    }
+
+    /// Creates a service slot that runs `init` on first use
+    pub fn new<F, Fut>(name: &'static str, init: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Box<T>>> + Send + 'static,
+    {
+        Self {
+            name,
+            cell: Arc::new(OnceCell::new()),
+            init: Some(Arc::new(move || Box::pin(init()))),
+        }
+    }
+
+    /// Whether the service has already been created
+    pub fn is_initialized(&self) -> bool {
+        self.cell.initialized()
+    }
+
+    /// Returns the service only if it has already been created
+    ///
+    /// Used for maintenance (e.g. cascading deletes into the in-memory index)
+    /// that has nothing to do while the service was never built.
+    pub fn get_initialized(&self) -> Option<&T> {
+        self.cell.get().map(|service| service.as_ref())
+    }
+
+    /// Returns the service, running the initialiser if needed
+    pub async fn get(&self) -> Result<&T> {
+        Ok(self.cell_value().await?.as_ref())
+    }
+
+    /// Returns a shared handle to the service, running the initialiser if needed
+    pub async fn get_shared(&self) -> Result<Arc<T>> {
+        Ok(self.cell_value().await?.clone())
+    }
+
+    async fn cell_value(&self) -> Result<&Arc<T>> {
+        self.cell
+            .get_or_try_init(|| async {
+                let init = self
+                    .init
+                    .as_ref()
+                    .ok_or_else(|| AppError::Config(format!("{} is not configured", self.name)))?;
+                tracing::info!("Initializing {} on first use", self.name);
+                init().await.map(Arc::from)
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_lazy_service_initializes_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let service: LazyService<str> = LazyService::new("test service", move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { Ok(Box::<str>::from("ready")) }
+        });
+
+        assert!(!service.is_initialized());
+        assert!(service.get_initialized().is_none());
+        assert_eq!(service.get().await.unwrap(), "ready");
+
+        let shared = service.clone();
+        assert_eq!(shared.get().await.unwrap(), "ready");
+        assert!(service.is_initialized());
+        assert_eq!(service.get_initialized(), Some("ready"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_lazy_service_unconfigured() {
+        let service: LazyService<str> = LazyService::unconfigured("test service");
+        assert!(matches!(service.get().await, Err(AppError::Config(_))));
+        assert!(!service.is_initialized());
+    }
+}
@@ -4,12 +4,17 @@
 //! summarization, entity extraction, and relationship building.
 
 use std::sync::Arc;
-use crate::error::Result;
+use crate::error::{AppError, Result};
 use crate::models::entity::{Entity, EntityType, Relationship, RelationshipType};
-use crate::models::memory::{Memory, MemorySource, MemoryType};
+use crate::models::memory::{Memory, MemoryQuery, MemorySource, MemoryStatus, MemoryType};
 use crate::models::memory_repository::MemoryRepository;
+use crate::models::turn::{MessageType, Turn};
 use crate::models::entity_repository::EntityRepository;
+use crate::observability::event_bus::MEMORY_CREATED;
+use crate::observability::{AppMetrics, EventBus};
 use crate::services::dehydration::DehydrationService;
+use crate::services::memory_integrator::MemoryIntegrator;
+use crate::services::profile::ProfileService;
 
 /// MemoryBuilder Service
 ///
@@ -20,9 +25,13 @@ use crate::services::dehydration::DehydrationService;
 /// - Integrates with dehydration service for summarization
 #[derive(Clone)]
 pub struct MemoryBuilder {
-    memory_repo: Arc<dyn MemoryRepository>,
-    entity_repo: Arc<dyn EntityRepository>,
+    memory_repo: Arc<dyn MemoryRepository + Send + Sync>,
+    entity_repo: Arc<dyn EntityRepository + Send + Sync>,
     dehydration_service: Arc<dyn DehydrationService>,
+    integrator: Option<Arc<MemoryIntegrator>>,
+    metrics: Option<Arc<AppMetrics>>,
+    event_bus: Option<EventBus>,
+    profile_service: Option<Arc<dyn ProfileService>>,
     min_importance: f32,
     max_importance: f32,
 }
@@ -30,19 +39,73 @@ pub struct MemoryBuilder {
 impl MemoryBuilder {
     /// Create a new MemoryBuilder
     pub fn new(
-        memory_repo: Arc<dyn MemoryRepository>,
-        entity_repo: Arc<dyn EntityRepository>,
+        memory_repo: Arc<dyn MemoryRepository + Send + Sync>,
+        entity_repo: Arc<dyn EntityRepository + Send + Sync>,
         dehydration_service: Arc<dyn DehydrationService>,
     ) -> Self {
         Self {
             memory_repo,
             entity_repo,
             dehydration_service,
+            integrator: None,
+            metrics: None,
+            event_bus: None,
+            profile_service: None,
             min_importance: 0.0,
             max_importance: 1.0,
         }
     }
 
+    /// Attach a memory integrator used to link new memories to known entities
+    pub fn with_integrator(mut self, integrator: Arc<MemoryIntegrator>) -> Self {
+        self.integrator = Some(integrator);
+        self
+    }
+
+    /// Attach application metrics so created memories are counted by type
+    pub fn with_metrics(mut self, metrics: Arc<AppMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Attach an event bus so created memories are published as `memory:created`
+    pub fn with_event_bus(mut self, event_bus: EventBus) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// Attach a profile service so profile memories update the user's profile
+    pub fn with_profile_service(mut self, profile_service: Arc<dyn ProfileService>) -> Self {
+        self.profile_service = Some(profile_service);
+        self
+    }
+
+    fn record_memory_created(&self, memory: &Memory) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_memory_created(&memory.memory_type.to_string());
+        }
+        if let Some(event_bus) = &self.event_bus {
+            event_bus.publish(
+                MEMORY_CREATED,
+                &memory.tenant_id,
+                serde_json::json!({
+                    "id": memory.id,
+                    "user_id": memory.user_id,
+                    "memory_type": memory.memory_type.to_string(),
+                    "importance": memory.importance,
+                }),
+            );
+        }
+    }
+
+    async fn sync_profile(&self, memory: &Memory) {
+        if let Some(profile_service) = &self.profile_service
+            && let Err(e) = profile_service.sync_from_memory(memory).await
+        {
+            tracing::warn!("Failed to sync profile from memory {}: {}", memory.id, e);
+        }
+    }
+
     /// Build memory from raw content
     ///
     /// This is the main entry point for creating a new memory:
@@ -51,12 +114,40 @@ impl MemoryBuilder {
     /// 3. Generate gist/summary using dehydration service
     /// 4. Extract entities and relationships
     /// 5. Create and store memory
+    /// 6. Link memory to known entities (when an integrator is attached)
     pub async fn build_memory(
         &self,
         user_id: &str,
         content: &str,
         memory_type: MemoryType,
         source: MemorySource,
+    ) -> Result<Memory> {
+        let memory = self
+            .build_memory_unlinked(user_id, content, memory_type, source)
+            .await?;
+
+        // Step 8: Link memory to known entities mentioned in its content
+        if let Some(integrator) = &self.integrator {
+            if let Err(e) = integrator
+                .link_memory_to_entities(std::slice::from_ref(&memory))
+                .await
+            {
+                tracing::warn!("Failed to link memory {} to entities: {}", memory.id, e);
+            }
+        }
+
+        tracing::info!("Memory built successfully: {}", memory.id);
+
+        Ok(memory)
+    }
+
+    /// Build and store a memory without linking it to known entities
+    async fn build_memory_unlinked(
+        &self,
+        user_id: &str,
+        content: &str,
+        memory_type: MemoryType,
+        source: MemorySource,
     ) -> Result<Memory> {
         tracing::info!("Building memory for user: {}, type: {:?}", user_id, memory_type);
 
@@ -81,11 +172,23 @@ impl MemoryBuilder {
             memory.add_topic(topic);
         }
 
+        // Add topics that distinguish this memory from the user's other memories
+        match self.extract_topics(content, user_id).await {
+            Ok(topics) => {
+                for topic in &topics {
+                    memory.add_topic(topic);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to extract topics for user {}: {}", user_id, e),
+        }
+
         // Step 4: Extract entities from content
         let entities = self.extract_entities(content, &memory.id).await?;
 
         // Step 5: Store memory first to get the ID
         let created_memory = self.memory_repo.create(&memory).await?;
+        self.record_memory_created(&created_memory);
+        self.sync_profile(&created_memory).await;
 
         // Step 6: Save extracted entities
         for mut entity in entities {
@@ -106,8 +209,6 @@ impl MemoryBuilder {
             }
         }
 
-        tracing::info!("Memory built successfully: {}", created_memory.id);
-
         Ok(created_memory)
     }
 
@@ -200,6 +301,23 @@ impl MemoryBuilder {
         Ok(dehydrated.gist)
     }
 
+    /// Extract the main topics of `content` relative to the user's memories
+    ///
+    /// Scores each term by TF-IDF: term frequency in `content` against the
+    /// inverse document frequency across the gists of the user's most recent
+    /// memories. Returns up to five lowercase, deduplicated terms.
+    pub async fn extract_topics(&self, content: &str, user_id: &str) -> Result<Vec<String>> {
+        let gists: Vec<String> = self
+            .memory_repo
+            .list_by_user(user_id, None, TOPIC_CORPUS_SIZE, 0)
+            .await?
+            .into_iter()
+            .map(|memory| memory.gist)
+            .collect();
+
+        Ok(tfidf_topics(content, &gists, MAX_EXTRACTED_TOPICS))
+    }
+
     /// Extract entities from content
     ///
     /// Performs simple NER-like extraction:
@@ -497,20 +615,312 @@ impl MemoryBuilder {
         let mut memories = Vec::new();
 
         for (content, memory_type, source) in items {
-            match self.build_memory(user_id, content, memory_type, source).await {
+            match self
+                .build_memory_unlinked(user_id, content, memory_type, source)
+                .await
+            {
                 Ok(memory) => memories.push(memory),
                 Err(e) => tracing::error!("Failed to build memory: {}", e),
             }
         }
 
+        // Link the whole batch to known entities concurrently
+        if let Some(integrator) = &self.integrator
+            && let Err(e) = integrator.link_memory_to_entities(&memories).await
+        {
+            tracing::warn!("Failed to link memories to entities: {}", e);
+        }
+
         Ok(memories)
     }
+
+    /// Build a memory from a slice of conversation turns without storing it
+    ///
+    /// User and assistant turns are concatenated in turn order; system turns
+    /// are skipped. The gist is the first sentence of the conversation, the
+    /// type is episodic when the text mentions a specific event and semantic
+    /// otherwise, and importance weighs turn-number recency (0.4) against
+    /// content length (0.6). `source_id` is the ID of the latest turn, the one
+    /// that completed the exchange.
+    pub fn build_from_turns(&self, turns: &[Turn], user_id: &str) -> Result<Memory> {
+        let mut turns: Vec<&Turn> = turns
+            .iter()
+            .filter(|t| t.metadata.message_type != MessageType::System)
+            .collect();
+        turns.sort_by_key(|t| t.turn_number);
+
+        let content = turns
+            .iter()
+            .map(|t| t.raw_content.trim())
+            .filter(|c| !c.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if content.is_empty() {
+            return Err(AppError::Validation(
+                "Cannot build a memory from turns without user or assistant content".to_string(),
+            ));
+        }
+
+        let memory_type = detect_turn_memory_type(&content);
+        let mut memory = Memory::new(user_id, memory_type, &content, MemorySource::Conversation);
+        memory.gist = first_sentence(&content);
+        memory.source_id = turns.last().map(|t| t.id.clone());
+        memory.importance = turn_importance(&turns, &content)
+            .clamp(self.min_importance, self.max_importance);
+        for topic in tfidf_topics(&content, &[], MAX_EXTRACTED_TOPICS) {
+            memory.add_topic(&topic);
+        }
+
+        Ok(memory)
+    }
+
+    /// Consolidate episodic memories about a topic into one semantic memory
+    ///
+    /// Collects the user's active episodic memories tagged with `topic`. When at
+    /// least `min_memories` are found, a semantic memory built from their gists is
+    /// stored, each source memory gets it as `parent_id` and is archived.
+    /// Returns `None` when there is not enough material to consolidate.
+    pub async fn consolidate_memories(
+        &self,
+        user_id: &str,
+        topic: &str,
+        min_memories: usize,
+    ) -> Result<Option<Memory>> {
+        let sources = self.find_episodic_memories(user_id, topic).await?;
+        if sources.len() < min_memories.max(1) {
+            tracing::debug!(
+                "Not consolidating topic {} for user {}: {} memories found",
+                topic,
+                user_id,
+                sources.len()
+            );
+            return Ok(None);
+        }
+
+        let mut consolidated = merge_episodic_memories(user_id, topic, &sources);
+        let dehydrated = self
+            .dehydration_service
+            .generate_summary(&consolidated.content)
+            .await?;
+        consolidated.gist = dehydrated.gist;
+        consolidated.keywords = dehydrated.tags;
+
+        let created = self.memory_repo.create(&consolidated).await?;
+        self.record_memory_created(&created);
+
+        for mut source in sources {
+            source.parent_id = Some(created.id.clone());
+            source.archive();
+            self.memory_repo.update(&source.id, &source).await?;
+        }
+
+        tracing::info!(
+            "Consolidated {} memories about {} into {}",
+            created.related_ids.len(),
+            topic,
+            created.id
+        );
+
+        Ok(Some(created))
+    }
+
+    /// Find all active episodic memories of a user tagged with `topic`, oldest first
+    async fn find_episodic_memories(&self, user_id: &str, topic: &str) -> Result<Vec<Memory>> {
+        // 标签以小写存储（见 `Memory::add_tag`）
+        let tag = topic.to_lowercase();
+        let mut query = MemoryQuery::new()
+            .for_user(user_id)
+            .with_types(&[MemoryType::Episodic])
+            .with_tags(&[tag.as_str()]);
+        query.statuses = vec![MemoryStatus::Active];
+
+        let mut memories = Vec::new();
+        let mut page = 1;
+        loop {
+            query = query.with_pagination(page, CONSOLIDATION_PAGE_SIZE);
+            let batch = self.memory_repo.search(&query).await?;
+            let fetched = batch.len();
+            memories.extend(batch);
+            if fetched < CONSOLIDATION_PAGE_SIZE as usize {
+                break;
+            }
+            page += 1;
+        }
+
+        memories.sort_by_key(|m| m.created_at);
+        Ok(memories)
+    }
+}
+
+/// Build the semantic memory that replaces a set of episodic memories
+///
+/// `sources` are expected in chronological order.
+fn merge_episodic_memories(user_id: &str, topic: &str, sources: &[Memory]) -> Memory {
+    let content = sources
+        .iter()
+        .map(|m| {
+            if m.gist.is_empty() {
+                m.content.as_str()
+            } else {
+                m.gist.as_str()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut memory = Memory::new(
+        user_id,
+        MemoryType::Semantic,
+        &content,
+        MemorySource::Conversation,
+    );
+    if let Some(first) = sources.first() {
+        memory.tenant_id = first.tenant_id.clone();
+        memory.source = first.source.clone();
+    }
+    memory.importance = sources
+        .iter()
+        .map(|m| m.importance)
+        .fold(memory.importance, f32::max);
+    memory.add_tag(topic);
+    memory.add_topic(topic);
+    for source in sources {
+        memory.add_related(&source.id);
+        for topic in &source.topics {
+            memory.add_topic(topic);
+        }
+    }
+
+    memory
+}
+
+/// Maximum length of a gist extracted by `first_sentence`, in characters
+const MAX_TURN_GIST_CHARS: usize = 200;
+
+/// Content length at which the length component of turn importance saturates
+const TURN_IMPORTANCE_FULL_LENGTH_CHARS: usize = 1000;
+
+/// Phrases suggesting that a conversation recounts a specific event
+const EPISODIC_KEYWORDS: [&str; 16] = [
+    "yesterday", "today", "tonight", "this morning", "last week", "last month", "remember when",
+    "happened", "went to", "met with", "昨天", "今天", "刚才", "上周", "发生", "去了",
+];
+
+/// Classify conversation text as episodic when it mentions an event, semantic otherwise
+fn detect_turn_memory_type(content: &str) -> MemoryType {
+    let content_lower = content.to_lowercase();
+    if EPISODIC_KEYWORDS.iter().any(|k| content_lower.contains(k)) {
+        MemoryType::Episodic
+    } else {
+        MemoryType::Semantic
+    }
+}
+
+/// Extract the first sentence of `content`, capped at `MAX_TURN_GIST_CHARS`
+fn first_sentence(content: &str) -> String {
+    let end = content
+        .char_indices()
+        .find(|(_, c)| matches!(c, '.' | '!' | '?' | '。' | '！' | '？' | '\n'))
+        .map(|(i, c)| if c == '\n' { i } else { i + c.len_utf8() })
+        .unwrap_or(content.len());
+
+    content[..end]
+        .trim()
+        .chars()
+        .take(MAX_TURN_GIST_CHARS)
+        .collect()
+}
+
+/// Importance of a memory built from `turns`, in [0, 1]
+///
+/// Recency is the mean turn number relative to the latest one, so memories
+/// drawn from later in a conversation score higher. Length saturates at
+/// `TURN_IMPORTANCE_FULL_LENGTH_CHARS`.
+fn turn_importance(turns: &[&Turn], content: &str) -> f32 {
+    let latest = turns.iter().map(|t| t.turn_number).max().unwrap_or(0);
+    let recency = if latest == 0 {
+        1.0
+    } else {
+        let total: u64 = turns.iter().map(|t| t.turn_number).sum();
+        total as f32 / turns.len() as f32 / latest as f32
+    };
+    let length =
+        (content.chars().count() as f32 / TURN_IMPORTANCE_FULL_LENGTH_CHARS as f32).min(1.0);
+
+    0.4 * recency + 0.6 * length
+}
+
+/// Number of recent memories whose gists form the TF-IDF corpus
+const TOPIC_CORPUS_SIZE: usize = 100;
+
+/// Maximum number of topics extracted from a memory's content
+const MAX_EXTRACTED_TOPICS: usize = 5;
+
+/// Words that never make useful topics
+const TOPIC_STOP_WORDS: [&str; 32] = [
+    "the", "and", "for", "with", "that", "this", "from", "are", "was", "were", "been", "have",
+    "has", "had", "but", "not", "you", "your", "our", "they", "them", "their", "its", "into",
+    "about", "will", "would", "can", "could", "should", "what", "which",
+];
+
+/// Split text into lowercase candidate topic terms
+pub(crate) fn topic_terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .map(|word| word.trim_matches('-').to_lowercase())
+        .filter(|word| {
+            word.chars().count() > 2
+                && !word.chars().all(|c| c.is_numeric())
+                && !TOPIC_STOP_WORDS.contains(&word.as_str())
+        })
+        .collect()
+}
+
+/// Rank the terms of `content` by TF-IDF against `corpus` and keep the top `limit`
+///
+/// Uses smoothed IDF (`ln((1 + n) / (1 + df)) + 1`) so terms unseen in the
+/// corpus rank highest. Ties are broken alphabetically.
+fn tfidf_topics(content: &str, corpus: &[String], limit: usize) -> Vec<String> {
+    let terms = topic_terms(content);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let mut term_counts: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    for term in &terms {
+        *term_counts.entry(term.as_str()).or_insert(0) += 1;
+    }
+
+    let documents: Vec<std::collections::HashSet<String>> = corpus
+        .iter()
+        .map(|doc| topic_terms(doc).into_iter().collect())
+        .collect();
+    let total_docs = documents.len() as f64;
+
+    let mut scored: Vec<(&str, f64)> = term_counts
+        .into_iter()
+        .map(|(term, count)| {
+            let doc_freq = documents.iter().filter(|doc| doc.contains(term)).count() as f64;
+            let tf = count as f64 / terms.len() as f64;
+            let idf = ((1.0 + total_docs) / (1.0 + doc_freq)).ln() + 1.0;
+            (term, tf * idf)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(term, _)| term.to_string())
+        .collect()
 }
 
+/// Page size used when collecting memories to consolidate
+const CONSOLIDATION_PAGE_SIZE: u32 = 100;
+
 /// Create a MemoryBuilder service
 pub fn create_memory_builder(
-    memory_repo: Arc<dyn MemoryRepository>,
-    entity_repo: Arc<dyn EntityRepository>,
+    memory_repo: Arc<dyn MemoryRepository + Send + Sync>,
+    entity_repo: Arc<dyn EntityRepository + Send + Sync>,
     dehydration_service: Arc<dyn DehydrationService>,
 ) -> MemoryBuilder {
     MemoryBuilder::new(memory_repo, entity_repo, dehydration_service)
@@ -521,7 +931,7 @@ mod tests {
     use super::*;
     use crate::models::memory_repository::MemoryRepository;
     use crate::models::entity_repository::EntityRepository;
-    use crate::services::dehydration::DehydrationService;
+    use crate::services::dehydration::{DehydrationService, TokenEstimate};
     use crate::models::turn::DehydratedData;
     use async_trait::async_trait;
 
@@ -548,6 +958,10 @@ mod tests {
         async fn extract_topics(&self, content: &str) -> Result<Vec<String>> {
             Ok(vec!["test".to_string()])
         }
+
+        async fn estimate_token_count(&self, _session_id: &str) -> Result<TokenEstimate> {
+            Ok(TokenEstimate::default())
+        }
     }
 
     #[derive(Clone)]
@@ -612,6 +1026,31 @@ mod tests {
                 storage_size_bytes: 0,
             })
         }
+
+        async fn increment_access_count(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn list_hot(&self, _user_id: &str, _limit: usize) -> Result<Vec<Memory>> {
+            Ok(vec![])
+        }
+
+        async fn list_by_source_id(&self, _source_id: &str) -> Result<Vec<Memory>> {
+            Ok(vec![])
+        }
+
+        async fn delete_by_conversation(&self, _session_id: &str) -> Result<u64> {
+            Ok(0)
+        }
+
+        async fn search_by_embedding(
+            &self,
+            _query_vector: &[f32],
+            _query: &MemoryQuery,
+            _min_similarity: f32,
+        ) -> Result<Vec<(Memory, f32)>> {
+            Ok(vec![])
+        }
     }
 
     #[derive(Clone)]
@@ -643,6 +1082,15 @@ mod tests {
             Ok(vec![])
         }
 
+        async fn search_tenant_entities(
+            &self,
+            _tenant_id: &str,
+            _name: &str,
+            _limit: usize,
+        ) -> Result<Vec<Entity>> {
+            Ok(vec![])
+        }
+
         async fn create_relationship(&self, relationship: &Relationship) -> Result<Relationship> {
             Ok(relationship.clone())
         }
@@ -651,6 +1099,14 @@ mod tests {
             Ok(None)
         }
 
+        async fn update_relationship(
+            &self,
+            _id: &str,
+            relationship: &Relationship,
+        ) -> Result<Option<Relationship>> {
+            Ok(Some(relationship.clone()))
+        }
+
         async fn delete_relationship(&self, _id: &str) -> Result<bool> {
             Ok(true)
         }
@@ -686,11 +1142,103 @@ mod tests {
             })
         }
 
+        async fn get_hub_entities(
+            &self,
+            _tenant_id: &str,
+            _limit: usize,
+        ) -> Result<Vec<(Entity, usize)>> {
+            Ok(vec![])
+        }
+
         async fn discover_entity(&self, _name: &str, _entity_type: &str) -> Result<Option<Entity>> {
             Ok(None)
         }
     }
 
+    #[test]
+    fn test_merge_episodic_memories() {
+        let mut monday = Memory::new(
+            "user_123",
+            MemoryType::Episodic,
+            "User worked on feature X on Monday",
+            MemorySource::Conversation,
+        );
+        monday.importance = 0.8;
+        let mut tuesday = Memory::new(
+            "user_123",
+            MemoryType::Episodic,
+            "User continued feature X on Tuesday",
+            MemorySource::Conversation,
+        );
+        tuesday.gist = "Continued feature X".to_string();
+
+        let merged =
+            merge_episodic_memories("user_123", "feature-x", &[monday.clone(), tuesday.clone()]);
+
+        assert_eq!(merged.memory_type, MemoryType::Semantic);
+        assert_eq!(
+            merged.content,
+            "User worked on feature X on Monday\nContinued feature X"
+        );
+        assert_eq!(merged.related_ids, vec![monday.id, tuesday.id]);
+        assert_eq!(merged.tags, vec!["feature-x".to_string()]);
+        assert!((merged.importance - 0.8).abs() < f32::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_consolidate_memories_requires_min_memories() {
+        let builder = MemoryBuilder::new(
+            Arc::new(MockMemoryRepository),
+            Arc::new(MockEntityRepository),
+            Arc::new(MockDehydrationService),
+        );
+
+        let result = builder
+            .consolidate_memories("user_123", "feature-x", 2)
+            .await
+            .unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_build_from_turns() {
+        let builder = MemoryBuilder::new(
+            Arc::new(MockMemoryRepository),
+            Arc::new(MockEntityRepository),
+            Arc::new(MockDehydrationService),
+        );
+
+        let mut system = Turn::new("session_1", 1, "You are a helpful assistant.");
+        system.metadata.message_type = MessageType::System;
+        let mut reply = Turn::new("session_1", 3, "Rust ownership moves values by default.");
+        reply.metadata.message_type = MessageType::Assistant;
+        let reply_id = reply.id.clone();
+        let turns = vec![
+            reply,
+            Turn::new("session_1", 2, "How does Rust ownership work? I keep hitting errors."),
+            system,
+        ];
+
+        let memory = builder.build_from_turns(&turns, "user_123").unwrap();
+        assert_eq!(
+            memory.content,
+            "How does Rust ownership work? I keep hitting errors.\nRust ownership moves values by default."
+        );
+        assert_eq!(memory.gist, "How does Rust ownership work?");
+        assert_eq!(memory.memory_type, MemoryType::Semantic);
+        assert_eq!(memory.source_id, Some(reply_id));
+        assert!(memory.topics.contains(&"ownership".to_string()));
+        // Recency (2.5 / 3) weighted 0.4, length (92 / 1000) weighted 0.6
+        assert!((memory.importance - (0.4 * 2.5 / 3.0 + 0.6 * 0.092)).abs() < 1e-4);
+
+        let turns = vec![Turn::new("session_1", 1, "Yesterday I went to the Rust meetup")];
+        let memory = builder.build_from_turns(&turns, "user_123").unwrap();
+        assert_eq!(memory.memory_type, MemoryType::Episodic);
+        assert_eq!(memory.gist, "Yesterday I went to the Rust meetup");
+
+        assert!(builder.build_from_turns(&[], "user_123").is_err());
+    }
+
     #[tokio::test]
     async fn test_calculate_importance() {
         let memory_repo = Arc::new(MockMemoryRepository);
@@ -793,6 +1341,27 @@ mod tests {
         assert!(!entities.is_empty());
     }
 
+    #[test]
+    fn test_tfidf_topics() {
+        let corpus = vec![
+            "Discussed the project deadline".to_string(),
+            "Project kickoff meeting notes".to_string(),
+            "Reviewed the project budget".to_string(),
+        ];
+
+        let topics = tfidf_topics(
+            "The Kubernetes migration project: Kubernetes cluster upgrade",
+            &corpus,
+            3,
+        );
+        assert_eq!(topics[0], "kubernetes");
+        assert!(!topics.contains(&"project".to_string()));
+        assert!(!topics.contains(&"the".to_string()));
+        assert_eq!(topics.len(), 3);
+
+        assert!(tfidf_topics("a to of", &corpus, 5).is_empty());
+    }
+
     #[tokio::test]
     async fn test_similarity_calculation() {
         let memory_repo = Arc::new(MockMemoryRepository);
@@ -5,13 +5,17 @@
 //! and relationship updates for the memory system.
 
 use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::time::{interval, Duration};
 use tracing::{debug, error, info};
 
 use crate::{
+    error::Result,
     models::{
+        entity::{EntityType, Relationship, RelationshipType},
+        entity_repository::EntityRepository,
         memory::{Memory, MemoryStatus, MemoryType},
         memory_repository::MemoryRepository,
         MemoryQuery,
@@ -19,6 +23,12 @@ use crate::{
     services::memory_recall::MemoryRecall,
 };
 
+/// Default number of memories linked to entities concurrently
+pub const DEFAULT_LINK_CONCURRENCY: usize = 4;
+
+/// Maximum number of entities matched per noun phrase
+const ENTITY_MATCHES_PER_PHRASE: usize = 20;
+
 /// Configuration for memory integration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryIntegrationConfig {
@@ -128,6 +138,7 @@ pub struct MemoryIntegrator {
     config: MemoryIntegrationConfig,
     memory_repo: Arc<dyn MemoryRepository + Send + Sync>,
     memory_recall: Arc<MemoryRecall>,
+    entity_repo: Option<Arc<dyn EntityRepository + Send + Sync>>,
     stats: Arc<tokio::sync::Mutex<IntegrationStats>>,
 }
 
@@ -142,10 +153,43 @@ impl MemoryIntegrator {
             config: config.unwrap_or_default(),
             memory_repo,
             memory_recall,
+            entity_repo: None,
             stats: Arc::new(tokio::sync::Mutex::new(IntegrationStats::default())),
         }
     }
 
+    /// Attach an entity repository used to link memories to known entities
+    pub fn with_entity_repo(mut self, entity_repo: Arc<dyn EntityRepository + Send + Sync>) -> Self {
+        self.entity_repo = Some(entity_repo);
+        self
+    }
+
+    /// Link memories to the known entities they mention
+    ///
+    /// Extracts noun phrases from each memory's content, looks each one up
+    /// among the memory tenant's entities and creates a relationship from the
+    /// memory's source (e.g. the session) to every matched entity: `PartOf`
+    /// for projects, organizations and events, `RelatedTo` otherwise.
+    /// At most `DEFAULT_LINK_CONCURRENCY` memories are linked at once; the
+    /// created relationships are returned per memory, in the order of `memories`.
+    pub async fn link_memory_to_entities(
+        &self,
+        memories: &[Memory],
+    ) -> Result<Vec<Vec<Relationship>>> {
+        let Some(entity_repo) = &self.entity_repo else {
+            return Ok(vec![Vec::new(); memories.len()]);
+        };
+
+        let links: Vec<_> = memories
+            .iter()
+            .map(|memory| link_to_entities(entity_repo.as_ref(), memory))
+            .collect();
+        stream::iter(links)
+            .buffered(DEFAULT_LINK_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+
     /// Calculate similarity between two memories
     fn calculate_similarity(&self, m1: &Memory, m2: &Memory) -> f32 {
         // If both have embeddings, use cosine similarity
@@ -643,7 +687,113 @@ impl Clone for MemoryIntegrator {
             config: self.config.clone(),
             memory_repo: self.memory_repo.clone(),
             memory_recall: self.memory_recall.clone(),
+            entity_repo: self.entity_repo.clone(),
             stats: self.stats.clone(),
         }
     }
 }
+
+/// Link one memory to the entities it mentions, see `MemoryIntegrator::link_memory_to_entities`
+async fn link_to_entities(
+    entity_repo: &(dyn EntityRepository + Send + Sync),
+    memory: &Memory,
+) -> Result<Vec<Relationship>> {
+    let source_id = memory.source_id.as_deref().unwrap_or(&memory.id);
+    let mut linked: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut relationships = Vec::new();
+
+    for phrase in extract_noun_phrases(&memory.content) {
+        let entities = entity_repo
+            .search_tenant_entities(&memory.tenant_id, &phrase, ENTITY_MATCHES_PER_PHRASE)
+            .await?;
+
+        for entity in entities {
+            if entity.tenant_id != memory.tenant_id || !linked.insert(entity.id.clone()) {
+                continue;
+            }
+
+            let mut relationship = Relationship::new(
+                source_id,
+                &entity.id,
+                memory_link_type(&entity.entity_type),
+                &memory.id,
+            );
+            relationship.tenant_id = memory.tenant_id.clone();
+            relationship.context = Some(format!("Mentioned in memory: {}", phrase));
+
+            relationships.push(entity_repo.create_relationship(&relationship).await?);
+        }
+    }
+
+    debug!(
+        "Linked memory {} to {} entities",
+        memory.id,
+        relationships.len()
+    );
+
+    Ok(relationships)
+}
+
+/// Relationship type linking a memory's source to an entity it mentions
+///
+/// The source is part of the projects, organizations and events it mentions
+/// and merely related to everything else.
+fn memory_link_type(entity_type: &EntityType) -> RelationshipType {
+    match entity_type {
+        EntityType::Project | EntityType::Organization | EntityType::Event => {
+            RelationshipType::PartOf
+        }
+        _ => RelationshipType::RelatedTo,
+    }
+}
+
+/// Extract candidate noun phrases (capitalized word sequences) from content
+fn extract_noun_phrases(content: &str) -> Vec<String> {
+    const STOPWORDS: [&str; 8] = ["The", "This", "That", "These", "Those", "It", "We", "They"];
+
+    let mut phrases: Vec<String> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    fn flush(current: &mut Vec<&str>, phrases: &mut Vec<String>) {
+        if !current.is_empty() {
+            let phrase = current.join(" ");
+            if !phrases.contains(&phrase) {
+                phrases.push(phrase);
+            }
+            current.clear();
+        }
+    }
+
+    for word in content.split_whitespace() {
+        let trimmed = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let capitalized = trimmed.chars().next().is_some_and(|c| c.is_uppercase())
+            && trimmed.chars().count() >= 2
+            && !STOPWORDS.contains(&trimmed);
+
+        if capitalized {
+            current.push(trimmed);
+        } else {
+            flush(&mut current, &mut phrases);
+        }
+
+        // Punctuation after a word ends the phrase
+        if capitalized && word.ends_with(|c: char| !c.is_alphanumeric()) {
+            flush(&mut current, &mut phrases);
+        }
+    }
+    flush(&mut current, &mut phrases);
+
+    phrases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_noun_phrases() {
+        let phrases =
+            extract_noun_phrases("I worked on the Hippos project with Alice. The New York office helped.");
+        assert_eq!(phrases, vec!["Hippos", "Alice", "New York"]);
+    }
+}
@@ -10,12 +10,27 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
-use crate::error::Result;
-use crate::models::memory::{Memory, MemoryQuery, MemoryStats, MemoryType};
+use crate::error::{AppError, Result};
+use crate::index::EmbeddingModel;
+use crate::models::memory::{
+    Memory, MemoryQuery, MemorySource, MemoryStats, MemoryStatus, MemoryType,
+};
 use crate::models::memory_repository::MemoryRepository;
 use crate::models::profile_repository::ProfileRepository;
+use crate::models::turn::Turn;
+use crate::services::memory_builder::topic_terms;
+use crate::storage::repository::TurnStore;
 use crate::storage::surrealdb::SurrealPool;
 
+/// 按时间窗口召回时允许的最大跨度（天）
+pub const MAX_RECALL_RANGE_DAYS: i64 = 90;
+
+/// 按时间窗口召回时最多参与排序的轮次数量，窗口内轮次超出时返回校验错误
+pub const TIME_RANGE_RECALL_CANDIDATES: usize = 1000;
+
+/// 向量语义搜索时保留结果的最低余弦相似度
+const MIN_SEMANTIC_SIMILARITY: f32 = 0.3;
+
 /// RRF 融合权重配置
 #[derive(Debug, Clone)]
 pub struct RrfWeights {
@@ -77,6 +92,10 @@ pub struct SearchOptions {
     pub memory_types: Vec<String>,
     pub include_archived: bool,
     pub rrf_weights: RrfWeights,
+    /// 是否为结果附带来源轮次的原始内容
+    pub include_raw_content: bool,
+    /// 是否使用嵌入向量做语义搜索（需要配置嵌入模型，否则退回关键词匹配）
+    pub use_semantic: bool,
 }
 
 impl SearchOptions {
@@ -118,10 +137,20 @@ impl SearchOptions {
         self.rrf_weights = weights;
         self
     }
+
+    pub fn include_raw_content(mut self, include: bool) -> Self {
+        self.include_raw_content = include;
+        self
+    }
+
+    pub fn use_semantic(mut self, use_semantic: bool) -> Self {
+        self.use_semantic = use_semantic;
+        self
+    }
 }
 
 /// 搜索结果项
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SearchResultItem {
     pub memory: Memory,
     pub combined_score: f32,
@@ -132,6 +161,8 @@ pub struct SearchResultItem {
     pub rank_temporal: Option<u32>,
     pub rank_context: Option<u32>,
     pub match_reasons: Vec<String>,
+    /// 来源轮次的原始内容（仅在 `include_raw_content` 时填充）
+    pub raw_content: Option<String>,
 }
 
 /// 记忆召回服务
@@ -140,6 +171,9 @@ pub struct MemoryRecall {
     pool: SurrealPool,
     memory_repo: Arc<dyn MemoryRepository + Send + Sync>,
     profile_repo: Arc<dyn ProfileRepository + Send + Sync>,
+    turn_repo: Option<Arc<dyn TurnStore>>,
+    /// 嵌入模型（可选，用于为缺少嵌入向量的记忆即时计算）
+    embedding_model: Option<Arc<dyn EmbeddingModel>>,
 }
 
 impl MemoryRecall {
@@ -153,9 +187,23 @@ impl MemoryRecall {
             pool,
             memory_repo,
             profile_repo,
+            turn_repo: None,
+            embedding_model: None,
         }
     }
 
+    /// 设置轮次仓储（用于附带原始内容）
+    pub fn with_turn_repository(mut self, turn_repo: Arc<dyn TurnStore>) -> Self {
+        self.turn_repo = Some(turn_repo);
+        self
+    }
+
+    /// 设置嵌入模型（用于相似记忆检索）
+    pub fn with_embedding_model(mut self, embedding_model: Arc<dyn EmbeddingModel>) -> Self {
+        self.embedding_model = Some(embedding_model);
+        self
+    }
+
     /// 获取数据库连接池
     pub fn pool(&self) -> &SurrealPool {
         &self.pool
@@ -211,6 +259,27 @@ pub trait MemoryRecallService: Send + Sync {
 
     /// 获取记忆统计
     async fn get_memory_stats(&self, user_id: &str) -> Result<MemoryStats>;
+
+    /// 查找与指定记忆语义相似的记忆
+    ///
+    /// 优先复用该记忆已存储的嵌入向量；候选记忆只按其已存储的嵌入向量比较，不会重新计算。
+    async fn find_similar_memories(
+        &self,
+        memory_id: &str,
+        user_id: &str,
+        limit: u32,
+    ) -> Result<Vec<SearchResultItem>>;
+
+    /// 召回 `[start, end)` 时间窗口内的对话轮次，按 TF-IDF 相关度排序
+    ///
+    /// 窗口内轮次超过 `TIME_RANGE_RECALL_CANDIDATES` 时返回校验错误，而不是只对部分轮次排序。
+    async fn recall_by_time_range(
+        &self,
+        user_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<SearchResultItem>>;
 }
 
 #[async_trait]
@@ -233,7 +302,7 @@ impl MemoryRecallService for MemoryRecall {
         )?;
 
         // 使用 RRF 融合结果
-        let fused_results = Self::rrf_fusion(
+        let mut fused_results = Self::rrf_fusion(
             semantic_results,
             temporal_results,
             context_results,
@@ -241,6 +310,10 @@ impl MemoryRecallService for MemoryRecall {
             limit,
         );
 
+        if options.include_raw_content {
+            self.attach_raw_content(&mut fused_results).await?;
+        }
+
         Ok(fused_results)
     }
 
@@ -251,7 +324,7 @@ impl MemoryRecallService for MemoryRecall {
         query: &str,
         limit: u32,
     ) -> Result<Vec<SearchResultItem>> {
-        let options = SearchOptions::new().with_limit(limit);
+        let options = SearchOptions::new().with_limit(limit).use_semantic(true);
         self.semantic_search_internal(user_id, query, limit as usize, &options)
             .await
     }
@@ -296,9 +369,98 @@ impl MemoryRecallService for MemoryRecall {
     async fn get_memory_stats(&self, user_id: &str) -> Result<MemoryStats> {
         self.memory_repo.get_stats(user_id).await
     }
+
+    /// 查找相似记忆
+    async fn find_similar_memories(
+        &self,
+        memory_id: &str,
+        user_id: &str,
+        limit: u32,
+    ) -> Result<Vec<SearchResultItem>> {
+        let memory = self
+            .memory_repo
+            .get_by_id(memory_id)
+            .await?
+            .filter(|m| m.user_id == user_id)
+            .ok_or_else(|| AppError::NotFound(format!("Memory not found: {}", memory_id)))?;
+
+        let embedding = match stored_embedding(&memory) {
+            Some(embedding) => embedding.to_vec(),
+            None => {
+                let model = self.embedding_model.as_ref().ok_or_else(|| {
+                    AppError::Embedding(format!(
+                        "Memory {} has no stored embedding and no embedding model is available",
+                        memory_id
+                    ))
+                })?;
+                model.encode(embedding_text(&memory)).await?
+            }
+        };
+
+        // 多取一条，排除记忆自身后仍能填满 `limit`
+        let query = MemoryQuery::new()
+            .for_user(user_id)
+            .with_pagination(1, limit.saturating_add(1));
+        let mut scored: Vec<(Memory, f32)> = self
+            .memory_repo
+            .search_by_embedding(&embedding, &query, f32::NEG_INFINITY)
+            .await?
+            .into_iter()
+            .filter(|(m, _)| m.id != memory.id)
+            .collect();
+        scored.truncate(limit as usize);
+
+        Ok(similarity_results(scored))
+    }
+
+    /// 按时间窗口召回
+    async fn recall_by_time_range(
+        &self,
+        user_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<SearchResultItem>> {
+        validate_recall_range(start, end)?;
+        let turn_repo = self.turn_repo.as_ref().ok_or_else(|| {
+            AppError::Config("Memory recall has no turn repository configured".to_string())
+        })?;
+
+        // 多读取一个轮次，用于判断窗口是否超出排序上限
+        let turns = turn_repo
+            .list_by_user_and_time_range(user_id, start, end, TIME_RANGE_RECALL_CANDIDATES + 1)
+            .await?;
+        if turns.len() > TIME_RANGE_RECALL_CANDIDATES {
+            return Err(AppError::Validation(format!(
+                "Time range contains more than {} turns; narrow the range",
+                TIME_RANGE_RECALL_CANDIDATES
+            )));
+        }
+        Ok(rank_turns_by_tfidf(user_id, turns, limit))
+    }
 }
 
 impl MemoryRecall {
+    /// 批量读取结果的来源轮次（`source_id`）并附带其原始内容
+    async fn attach_raw_content(&self, results: &mut [SearchResultItem]) -> Result<()> {
+        let turn_repo = self.turn_repo.as_ref().ok_or_else(|| {
+            AppError::Config("Memory recall has no turn repository configured".to_string())
+        })?;
+
+        let sources = source_turn_ids(results);
+        if sources.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<&str> = sources.iter().map(|(_, id)| id.as_str()).collect();
+        let turns = turn_repo.get_turns_by_ids(&ids).await?;
+
+        for ((i, _), turn) in sources.into_iter().zip(turns) {
+            results[i].raw_content = turn.map(|turn| turn.raw_content);
+        }
+        Ok(())
+    }
+
     /// 内部语义搜索实现
     async fn semantic_search_internal(
         &self,
@@ -307,32 +469,18 @@ impl MemoryRecall {
         limit: usize,
         options: &SearchOptions,
     ) -> Result<Vec<SearchResultItem>> {
-        // 构建查询
-        let mut memory_query = MemoryQuery::new()
-            .for_user(user_id)
-            .with_pagination(1, limit as u32);
-
-        // 应用过滤条件
-        if let Some(min_importance) = options.min_importance {
-            memory_query = memory_query.with_min_importance(min_importance);
-        }
-
-        if !options.memory_types.is_empty() {
-            // 将字符串类型转换为 MemoryType 枚举
-            let types: Vec<MemoryType> = options
-                .memory_types
-                .iter()
-                .filter_map(|s| match s.to_lowercase().as_str() {
-                    "episodic" => Some(MemoryType::Episodic),
-                    "semantic" => Some(MemoryType::Semantic),
-                    "procedural" => Some(MemoryType::Procedural),
-                    "profile" => Some(MemoryType::Profile),
-                    _ => None,
-                })
-                .collect();
-            if !types.is_empty() {
-                memory_query = memory_query.with_types(&types);
-            }
+        let memory_query = recall_query(user_id, options).with_pagination(1, limit as u32);
+
+        // 配置了嵌入模型时按向量相似度检索，过滤条件在仓储内先于分页应用
+        if options.use_semantic
+            && let Some(model) = &self.embedding_model
+        {
+            let query_vector = model.encode(query).await?;
+            let scored = self
+                .memory_repo
+                .search_by_embedding(&query_vector, &memory_query, MIN_SEMANTIC_SIMILARITY)
+                .await?;
+            return Ok(similarity_results(scored));
         }
 
         // 搜索记忆
@@ -414,6 +562,7 @@ impl MemoryRecall {
                 rank_temporal: None,
                 rank_context: None,
                 match_reasons,
+                raw_content: None,
             });
         }
 
@@ -485,6 +634,7 @@ impl MemoryRecall {
                 rank_temporal: Some(rank as u32 + 1),
                 rank_context: None,
                 match_reasons: vec!["temporal_proximity".to_string()],
+                raw_content: None,
             });
         }
 
@@ -604,6 +754,7 @@ impl MemoryRecall {
                     rank_temporal: None,
                     rank_context: Some(rank as u32 + 1),
                     match_reasons,
+                    raw_content: None,
                 });
             }
         }
@@ -651,6 +802,7 @@ impl MemoryRecall {
                     rank_temporal: None,
                     rank_context: None,
                     match_reasons: reasons,
+                    raw_content: None,
                 },
             );
         }
@@ -688,6 +840,7 @@ impl MemoryRecall {
                         rank_temporal: Some(rank as u32 + 1),
                         rank_context: None,
                         match_reasons: reasons,
+                        raw_content: None,
                     },
                 );
             }
@@ -730,6 +883,7 @@ impl MemoryRecall {
                         rank_temporal: None,
                         rank_context: Some(rank as u32 + 1),
                         match_reasons: reasons,
+                        raw_content: None,
                     },
                 );
             }
@@ -746,6 +900,167 @@ impl MemoryRecall {
     }
 }
 
+/// 记忆已存储的嵌入向量（空数组视为缺失）
+fn stored_embedding(memory: &Memory) -> Option<&[f32]> {
+    memory.embedding.as_deref().filter(|e| !e.is_empty())
+}
+
+/// 计算嵌入时使用的文本：优先摘要，摘要为空时使用原文
+fn embedding_text(memory: &Memory) -> &str {
+    if memory.gist.is_empty() {
+        &memory.content
+    } else {
+        &memory.gist
+    }
+}
+
+/// 按搜索选项构造记忆查询的筛选条件（类型、最低重要性、是否包含归档），不含分页
+fn recall_query(user_id: &str, options: &SearchOptions) -> MemoryQuery {
+    let types: Vec<MemoryType> = options
+        .memory_types
+        .iter()
+        .filter_map(|s| match s.to_lowercase().as_str() {
+            "episodic" => Some(MemoryType::Episodic),
+            "semantic" => Some(MemoryType::Semantic),
+            "procedural" => Some(MemoryType::Procedural),
+            "profile" => Some(MemoryType::Profile),
+            _ => None,
+        })
+        .collect();
+
+    let mut query = MemoryQuery::new().for_user(user_id).with_types(&types);
+    query.min_importance = options.min_importance;
+    query.statuses = if options.include_archived {
+        vec![MemoryStatus::Active, MemoryStatus::Archived]
+    } else {
+        vec![MemoryStatus::Active]
+    };
+    query
+}
+
+/// 将按相似度降序排列的 `(记忆, 相似度)` 转为语义搜索结果
+fn similarity_results(scored: Vec<(Memory, f32)>) -> Vec<SearchResultItem> {
+    scored
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (memory, score))| SearchResultItem {
+            memory,
+            combined_score: score,
+            semantic_score: Some(score),
+            temporal_score: 0.0,
+            context_score: None,
+            rank_semantic: Some(rank as u32 + 1),
+            rank_temporal: None,
+            rank_context: None,
+            match_reasons: vec!["embedding_similarity".to_string()],
+            raw_content: None,
+        })
+        .collect()
+}
+
+/// 收集带来源 ID 的结果下标及其来源轮次 ID
+fn source_turn_ids(results: &[SearchResultItem]) -> Vec<(usize, String)> {
+    results
+        .iter()
+        .enumerate()
+        .filter_map(|(i, r)| r.memory.source_id.clone().map(|id| (i, id)))
+        .collect()
+}
+
+/// 检查时间窗口：`start` 必须早于 `end`，且跨度不超过 `MAX_RECALL_RANGE_DAYS` 天
+fn validate_recall_range(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<()> {
+    if start >= end {
+        return Err(AppError::Validation(
+            "Time range start must be before end".to_string(),
+        ));
+    }
+    if end - start > chrono::Duration::days(MAX_RECALL_RANGE_DAYS) {
+        return Err(AppError::Validation(format!(
+            "Time range must not exceed {} days",
+            MAX_RECALL_RANGE_DAYS
+        )));
+    }
+
+    Ok(())
+}
+
+/// 以窗口内轮次为语料计算各轮次 `raw_content` 的 TF-IDF 分数，返回前 `limit` 个结果
+///
+/// 轮次分数为其各词 TF-IDF 权重之和（平滑 IDF），窗口内越独特的内容分数越高，
+/// 并归一化到 `[0, 1]`。分数相同时较新的轮次优先。
+fn rank_turns_by_tfidf(user_id: &str, turns: Vec<Turn>, limit: usize) -> Vec<SearchResultItem> {
+    let documents: Vec<Vec<String>> = turns.iter().map(|t| topic_terms(&t.raw_content)).collect();
+    let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+    for terms in &documents {
+        let unique: std::collections::HashSet<&str> = terms.iter().map(String::as_str).collect();
+        for term in unique {
+            *doc_freq.entry(term).or_insert(0) += 1;
+        }
+    }
+
+    let total_docs = documents.len() as f64;
+    let scores: Vec<f64> = documents
+        .iter()
+        .map(|terms| {
+            terms
+                .iter()
+                .map(|term| {
+                    let idf =
+                        ((1.0 + total_docs) / (1.0 + doc_freq[term.as_str()] as f64)).ln() + 1.0;
+                    idf / terms.len() as f64
+                })
+                .sum()
+        })
+        .collect();
+    let max_score = scores.iter().cloned().fold(0.0_f64, f64::max);
+
+    let mut ranked: Vec<(Turn, f32)> = turns
+        .into_iter()
+        .zip(scores)
+        .map(|(turn, score)| {
+            let normalized = if max_score > 0.0 {
+                score / max_score
+            } else {
+                0.0
+            };
+            (turn, normalized as f32)
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.1.total_cmp(&a.1)
+            .then_with(|| b.0.metadata.timestamp.cmp(&a.0.metadata.timestamp))
+    });
+
+    ranked
+        .into_iter()
+        .take(limit)
+        .enumerate()
+        .map(|(rank, (turn, score))| {
+            let mut memory = Memory::new(
+                user_id,
+                MemoryType::Episodic,
+                &turn.raw_content,
+                MemorySource::Conversation,
+            );
+            memory.source_id = Some(turn.id);
+            memory.created_at = turn.metadata.timestamp;
+
+            SearchResultItem {
+                memory,
+                combined_score: score,
+                semantic_score: None,
+                temporal_score: 1.0,
+                context_score: Some(score),
+                rank_semantic: None,
+                rank_temporal: None,
+                rank_context: Some(rank as u32 + 1),
+                match_reasons: vec!["time_range".to_string(), "tfidf_relevance".to_string()],
+                raw_content: Some(turn.raw_content),
+            }
+        })
+        .collect()
+}
+
 /// 创建 MemoryRecall 服务
 pub fn create_memory_recall_service(
     pool: SurrealPool,
@@ -776,6 +1091,38 @@ mod tests {
         assert!(range.end.is_some());
     }
 
+    #[test]
+    fn test_validate_recall_range() {
+        let start = Utc::now();
+        assert!(validate_recall_range(start, start + chrono::Duration::days(1)).is_ok());
+        assert!(validate_recall_range(start, start + chrono::Duration::days(90)).is_ok());
+        assert!(validate_recall_range(start, start).is_err());
+        assert!(validate_recall_range(start, start - chrono::Duration::hours(1)).is_err());
+        assert!(validate_recall_range(start, start + chrono::Duration::days(91)).is_err());
+    }
+
+    #[test]
+    fn test_rank_turns_by_tfidf() {
+        let turns = vec![
+            Turn::new("session_1", 1, "deploy the service deploy"),
+            Turn::new("session_1", 2, "deploy the service"),
+            Turn::new("session_1", 3, "kubernetes ingress certificate rotation"),
+        ];
+        let distinctive_id = turns[2].id.clone();
+
+        let results = rank_turns_by_tfidf("user_1", turns, 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].memory.source_id.as_deref(),
+            Some(distinctive_id.as_str())
+        );
+        assert_eq!(results[0].combined_score, 1.0);
+        assert!(results[1].combined_score < 1.0);
+        assert_eq!(results[0].memory.user_id, "user_1");
+        assert_eq!(results[0].rank_context, Some(1));
+        assert!(rank_turns_by_tfidf("user_1", Vec::new(), 5).is_empty());
+    }
+
     #[test]
     fn test_time_range_today() {
         let range = TimeRange::today();
@@ -790,13 +1137,15 @@ mod tests {
             .with_offset(5)
             .with_min_importance(0.7)
             .with_memory_types(&["episodic", "semantic"])
-            .include_archived(true);
+            .include_archived(true)
+            .use_semantic(true);
 
         assert_eq!(options.limit, 10);
         assert_eq!(options.offset, 5);
         assert_eq!(options.min_importance, Some(0.7));
         assert_eq!(options.memory_types.len(), 2);
         assert!(options.include_archived);
+        assert!(options.use_semantic);
     }
 
     #[test]
@@ -833,6 +1182,7 @@ mod tests {
             rank_temporal: None,
             rank_context: None,
             match_reasons: vec!["semantic".to_string()],
+            raw_content: None,
         };
 
         let temporal_item = SearchResultItem {
@@ -845,6 +1195,7 @@ mod tests {
             rank_temporal: Some(1),
             rank_context: None,
             match_reasons: vec!["temporal".to_string()],
+            raw_content: None,
         };
 
         let weights = RrfWeights::default();
@@ -865,4 +1216,214 @@ mod tests {
         assert!(results[0].match_reasons.contains(&"semantic".to_string()));
         assert!(results[0].match_reasons.contains(&"temporal".to_string()));
     }
+
+    #[test]
+    fn test_raw_content_serialized_as_null_by_default() {
+        let mut memory = Memory::new(
+            "user_123",
+            MemoryType::Episodic,
+            "Test content",
+            MemorySource::Conversation,
+        );
+        memory.source_id = Some("turn_1".to_string());
+
+        let item = SearchResultItem {
+            memory: memory.clone(),
+            combined_score: 0.5,
+            semantic_score: None,
+            temporal_score: 0.0,
+            context_score: None,
+            rank_semantic: None,
+            rank_temporal: None,
+            rank_context: None,
+            match_reasons: Vec::new(),
+            raw_content: None,
+        };
+        let json = serde_json::to_value(&item).unwrap();
+        assert!(json["raw_content"].is_null());
+
+        let without_source = SearchResultItem {
+            memory: Memory::new(
+                "user_123",
+                MemoryType::Episodic,
+                "No source",
+                MemorySource::Conversation,
+            ),
+            ..item.clone()
+        };
+        assert_eq!(
+            source_turn_ids(&[without_source, item]),
+            vec![(1, "turn_1".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_ranks_by_embedding_after_filters() {
+        use crate::api::test_support::MockDatabase;
+        use crate::index::embedding::SimpleEmbeddingModel;
+        use crate::models::profile_repository::ProfileRepositoryImpl;
+        use crate::storage::in_memory::InMemoryMemoryRepository;
+
+        let mut model = SimpleEmbeddingModel::new(3);
+        model.add_word_embedding("rust", &[1.0, 0.0, 0.0]);
+        model.add_word_embedding("async", &[0.0, 1.0, 0.0]);
+        model.add_word_embedding("tuning", &[0.0, 0.0, 1.0]);
+        let model = Arc::new(model);
+
+        let repo = Arc::new(InMemoryMemoryRepository::new());
+        let mut created = Vec::new();
+        for (content, importance, status) in [
+            ("rust async", 0.9, MemoryStatus::Active),
+            ("rust async", 0.9, MemoryStatus::Archived),
+            ("rust async tuning", 0.1, MemoryStatus::Active),
+        ] {
+            let mut memory = Memory::new(
+                "user_1",
+                MemoryType::Semantic,
+                content,
+                MemorySource::Conversation,
+            );
+            memory.importance = importance;
+            memory.status = status;
+            memory.embedding = Some(model.encode(content).await.unwrap());
+            created.push(repo.create(&memory).await.unwrap());
+        }
+        let (exact, unimportant) = (&created[0], &created[2]);
+
+        let db = MockDatabase::start().await;
+        let recall = MemoryRecall::new(
+            db.pool(),
+            repo.clone(),
+            Arc::new(ProfileRepositoryImpl::new(db.pool())),
+        )
+        .with_embedding_model(model);
+
+        let results = recall
+            .semantic_search("user_1", "rust async", 10)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.memory.id.as_str()).collect();
+        assert_eq!(ids, vec![exact.id.as_str(), unimportant.id.as_str()]);
+        assert_eq!(results[0].rank_semantic, Some(1));
+        assert!(results[0].combined_score > results[1].combined_score);
+        assert_eq!(results[0].match_reasons, vec!["embedding_similarity"]);
+
+        // 重要性过滤先于分页：最相似但不够重要的记忆不会占掉唯一的名额
+        let options = SearchOptions::new()
+            .with_limit(1)
+            .with_min_importance(0.5)
+            .use_semantic(true);
+        let page = recall
+            .semantic_search_internal("user_1", "rust async tuning", 1, &options)
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].memory.id, exact.id);
+    }
+
+    #[tokio::test]
+    async fn test_recall_by_time_range_rejects_windows_over_the_cap() {
+        use crate::api::test_support::MockDatabase;
+        use crate::models::profile_repository::ProfileRepositoryImpl;
+        use crate::storage::in_memory::{InMemoryMemoryRepository, InMemoryRepository};
+        use crate::storage::repository::Repository;
+
+        let turns = Arc::new(InMemoryRepository::<Turn>::new());
+        let now = Utc::now();
+        for n in 0..=TIME_RANGE_RECALL_CANDIDATES as u64 {
+            let mut turn = Turn::new("session_1", n, "deploy the service");
+            turn.metadata.user_id = Some("user_1".to_string());
+            turn.metadata.timestamp = now - chrono::Duration::seconds(n as i64);
+            turns.create(&turn).await.unwrap();
+        }
+
+        let db = MockDatabase::start().await;
+        let recall = MemoryRecall::new(
+            db.pool(),
+            Arc::new(InMemoryMemoryRepository::new()),
+            Arc::new(ProfileRepositoryImpl::new(db.pool())),
+        )
+        .with_turn_repository(turns);
+
+        let start = now - chrono::Duration::hours(1);
+        let end = now + chrono::Duration::seconds(1);
+        let err = recall
+            .recall_by_time_range("user_1", start, end, 10)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::Validation(_)));
+
+        // 去掉最早的一个轮次后窗口恰好在上限内
+        let start = now - chrono::Duration::seconds(TIME_RANGE_RECALL_CANDIDATES as i64 - 1);
+        let results = recall
+            .recall_by_time_range("user_1", start, end, 10)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_find_similar_memories_uses_stored_embeddings() {
+        use crate::api::test_support::MockDatabase;
+        use crate::models::profile_repository::ProfileRepositoryImpl;
+        use crate::storage::in_memory::InMemoryMemoryRepository;
+
+        let repo = Arc::new(InMemoryMemoryRepository::new());
+        let mut created = Vec::new();
+        for (content, embedding) in [
+            ("target", Some(vec![1.0, 0.0])),
+            ("close", Some(vec![0.9, 0.1])),
+            ("far", Some(vec![0.0, 1.0])),
+            ("unembedded", None),
+        ] {
+            let mut memory = Memory::new(
+                "user_1",
+                MemoryType::Semantic,
+                content,
+                MemorySource::Conversation,
+            );
+            memory.embedding = embedding;
+            created.push(repo.create(&memory).await.unwrap());
+        }
+
+        // 未配置嵌入模型：候选记忆只能按已存储的向量比较
+        let db = MockDatabase::start().await;
+        let recall = MemoryRecall::new(
+            db.pool(),
+            repo.clone(),
+            Arc::new(ProfileRepositoryImpl::new(db.pool())),
+        );
+
+        let results = recall
+            .find_similar_memories(&created[0].id, "user_1", 10)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.memory.id.as_str()).collect();
+        assert_eq!(ids, vec![created[1].id.as_str(), created[2].id.as_str()]);
+
+        let results = recall
+            .find_similar_memories(&created[0].id, "user_1", 1)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory.id, created[1].id);
+    }
+
+    #[test]
+    fn test_stored_embedding_treats_empty_as_missing() {
+        let mut memory = Memory::new(
+            "user_123",
+            MemoryType::Semantic,
+            "content",
+            MemorySource::Conversation,
+        );
+        assert!(stored_embedding(&memory).is_none());
+
+        memory.embedding = Some(Vec::new());
+        assert!(stored_embedding(&memory).is_none());
+
+        memory.embedding = Some(vec![0.5, 0.5]);
+        assert_eq!(stored_embedding(&memory), Some(&[0.5, 0.5][..]));
+        assert_eq!(embedding_text(&memory), "content");
+    }
 }
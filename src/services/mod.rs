@@ -1,16 +1,24 @@
 //! 服务模块
 
+pub mod compressor;
 pub mod dehydration;
+pub mod lazy;
 pub mod memory_builder;
 pub mod memory_integrator;
 pub mod memory_recall;
 pub mod pattern_manager;
 pub mod performance;
+pub mod profile;
 pub mod retrieval;
 pub mod session;
+pub mod snapshot;
 pub mod turn;
 
-pub use dehydration::{DehydrationService, create_dehydration_service};
+pub use compressor::ContextCompressor;
+pub use dehydration::{
+    DehydrationReport, DehydrationService, TokenEstimate, create_dehydration_service,
+};
+pub use lazy::LazyService;
 pub use memory_builder::{MemoryBuilder, create_memory_builder};
 pub use memory_recall::{MemoryRecall, MemoryRecallService, create_memory_recall_service, SearchOptions, SearchResultItem, TimeRange, RrfWeights};
 pub use pattern_manager::{
@@ -18,6 +26,11 @@ pub use pattern_manager::{
     DiscoveryMethod, PatternSuggestion, OutcomeRecord, PatternCreateRequest,
     PatternGenerator, create_pattern_manager, create_pattern_manager_basic,
 };
+pub use profile::{ProfileService, ProfileServiceImpl};
 pub use retrieval::{RetrievalService, create_retrieval_service};
 pub use session::{Pagination, SessionQuery, SessionService, create_session_service};
-pub use turn::{BatchCreateResult, TurnGroup, TurnQuery, TurnService, create_turn_service};
+pub use snapshot::{SessionSnapshotService, SessionSnapshotServiceImpl, SnapshotRestoreResult};
+pub use turn::{
+    BatchCreateResult, ContextWindow, ConversationPair, TruncationStrategy, TurnGroup, TurnQuery,
+    TurnService, create_turn_service,
+};
@@ -6,16 +6,44 @@
 //! - Recommendations based on user context
 //! - Integration with memory repository for context-aware pattern discovery
 
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 use async_trait::async_trait;
 use chrono::Utc;
-use crate::error::Result;
+use lru::LruCache;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::config::config::PatternQualityThresholds;
+use crate::error::{AppError, Result};
 use crate::models::pattern::{
-    Pattern, PatternType, PatternQuery, PatternStats, PatternUsage,
+    Pattern, PatternType, PatternQuery, PatternStats, PatternStatus, PatternUsage,
 };
 use crate::models::memory::{Memory, MemoryQuery};
 use crate::models::pattern_repository::PatternRepository;
 use crate::models::memory_repository::MemoryRepository;
+use crate::observability::AppMetrics;
+
+/// Number of recent usages inspected by usage pattern discovery
+const USAGE_ANALYSIS_WINDOW: usize = 500;
+
+/// Word n-gram size used to group usage inputs
+const USAGE_NGRAM_SIZE: usize = 2;
+
+/// Minimum number of distinct patterns an n-gram must appear in
+const USAGE_NGRAM_MIN_PATTERNS: usize = 3;
+
+/// Schema version written by `export_to_json`; newer versions are rejected on import
+pub const PATTERN_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Page size used when streaming patterns out of the repository
+const PATTERN_EXPORT_PAGE_SIZE: u32 = 100;
+
+/// Number of patterns kept by `PatternCache::default()`
+pub const DEFAULT_PATTERN_CACHE_CAPACITY: usize = 500;
+
+/// Similarity above which auto-discovery treats a generated pattern as a duplicate
+pub const DEFAULT_PATTERN_DEDUP_THRESHOLD: f32 = 0.85;
 
 /// Pattern updates input
 #[derive(Debug, Clone, Default)]
@@ -51,6 +79,47 @@ pub struct PatternUpdates {
     pub confidence: Option<f32>,
 }
 
+/// A single field that differs between two pattern versions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Name of the changed field
+    pub field_name: String,
+
+    /// Value in the old version (removed tags for list fields)
+    pub old_value: Option<String>,
+
+    /// Value in the new version (added tags for list fields)
+    pub new_value: Option<String>,
+}
+
+/// Field-level differences between two pattern versions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PatternDiff {
+    /// Fields whose values differ
+    pub changed_fields: Vec<FieldChange>,
+}
+
+/// Result of importing a pattern library
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportResult {
+    /// Number of patterns created
+    pub imported: usize,
+
+    /// Number of patterns skipped because a pattern with the same name exists
+    pub skipped: usize,
+
+    /// Errors for patterns that failed to import
+    pub errors: Vec<String>,
+}
+
+/// Pattern library document read by `import_from_json`
+#[derive(Debug, Deserialize)]
+struct PatternLibrary {
+    schema_version: u32,
+    #[serde(default)]
+    patterns: Vec<Pattern>,
+}
+
 /// Pattern recommendation result
 #[derive(Debug, Clone)]
 pub struct PatternRecommendation {
@@ -203,6 +272,64 @@ pub struct OutcomeRecord {
     pub context: Option<String>,
 }
 
+/// LRU cache of patterns keyed by ID
+///
+/// Clones share the same underlying cache, so one instance can be handed to
+/// every `PatternManager` built for a request.
+#[derive(Clone)]
+pub struct PatternCache {
+    inner: Arc<Mutex<LruCache<String, Pattern>>>,
+    capacity: usize,
+}
+
+impl PatternCache {
+    /// Create a cache holding at most `capacity` patterns (at least one)
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let size = NonZeroUsize::new(capacity).expect("capacity is at least one");
+        Self {
+            inner: Arc::new(Mutex::new(LruCache::new(size))),
+            capacity,
+        }
+    }
+
+    /// Maximum number of cached patterns
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of cached patterns
+    pub fn len(&self) -> usize {
+        self.inner.lock().len()
+    }
+
+    /// Whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().is_empty()
+    }
+
+    /// Look up a pattern, marking it as recently used
+    pub fn get(&self, pattern_id: &str) -> Option<Pattern> {
+        self.inner.lock().get(pattern_id).cloned()
+    }
+
+    /// Cache a pattern, evicting the least recently used one when full
+    pub fn insert(&self, pattern: Pattern) {
+        self.inner.lock().put(pattern.id.clone(), pattern);
+    }
+
+    /// Drop a pattern from the cache
+    pub fn invalidate(&self, pattern_id: &str) {
+        self.inner.lock().pop(pattern_id);
+    }
+}
+
+impl Default for PatternCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PATTERN_CACHE_CAPACITY)
+    }
+}
+
 /// Pattern Manager Service
 ///
 /// Orchestrates pattern operations with business logic:
@@ -214,34 +341,91 @@ pub struct OutcomeRecord {
 /// - Auto-generates patterns from high-importance memories using AI
 #[derive(Clone)]
 pub struct PatternManager {
-    pattern_repo: Arc<dyn PatternRepository>,
-    memory_repo: Arc<dyn MemoryRepository>,
+    pattern_repo: Arc<dyn PatternRepository + Send + Sync>,
+    memory_repo: Arc<dyn MemoryRepository + Send + Sync>,
     /// Optional AI generator for pattern extraction
     ai_generator: Option<Arc<dyn PatternGenerator>>,
+    /// Thresholds a pattern must meet to count as high quality
+    quality_thresholds: PatternQualityThresholds,
+    /// Patterns cached by ID in front of the repository
+    cache: PatternCache,
+    /// Optional metrics recording cache hits
+    metrics: Option<Arc<AppMetrics>>,
+    /// Similarity above which auto-discovery skips a generated pattern
+    dedup_threshold: f32,
 }
 
 impl PatternManager {
     /// Create a new PatternManager with optional AI generator
     pub fn new(
-        pattern_repo: Arc<dyn PatternRepository>,
-        memory_repo: Arc<dyn MemoryRepository>,
+        pattern_repo: Arc<dyn PatternRepository + Send + Sync>,
+        memory_repo: Arc<dyn MemoryRepository + Send + Sync>,
         ai_generator: Option<Arc<dyn PatternGenerator>>,
     ) -> Self {
         Self {
             pattern_repo,
             memory_repo,
             ai_generator,
+            quality_thresholds: PatternQualityThresholds::default(),
+            cache: PatternCache::default(),
+            metrics: None,
+            dedup_threshold: DEFAULT_PATTERN_DEDUP_THRESHOLD,
         }
     }
 
     /// Create a new PatternManager without AI generator
     pub fn new_basic(
-        pattern_repo: Arc<dyn PatternRepository>,
-        memory_repo: Arc<dyn MemoryRepository>,
+        pattern_repo: Arc<dyn PatternRepository + Send + Sync>,
+        memory_repo: Arc<dyn MemoryRepository + Send + Sync>,
     ) -> Self {
         Self::new(pattern_repo, memory_repo, None)
     }
 
+    /// Use custom quality thresholds (see `config.patterns.quality_thresholds`)
+    pub fn with_quality_thresholds(mut self, thresholds: PatternQualityThresholds) -> Self {
+        self.quality_thresholds = thresholds;
+        self
+    }
+
+    /// Use a shared pattern cache (see `config.patterns.cache_capacity`)
+    pub fn with_cache(mut self, cache: PatternCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Record cache hits into the given metrics
+    pub fn with_metrics(mut self, metrics: Arc<AppMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Use a custom similarity threshold for auto-discovery deduplication
+    pub fn with_dedup_threshold(mut self, dedup_threshold: f32) -> Self {
+        self.dedup_threshold = dedup_threshold;
+        self
+    }
+
+    /// Find a pattern in `existing` that `candidate` duplicates
+    ///
+    /// A pattern is a duplicate when its trigger matches (ignoring case) or its
+    /// `similarity_score` exceeds the dedup threshold.
+    fn find_duplicate<'a>(
+        &self,
+        candidate: &Pattern,
+        existing: &'a [Pattern],
+    ) -> Option<&'a Pattern> {
+        let trigger = candidate.trigger.trim().to_lowercase();
+        existing.iter().find(|pattern| {
+            (!trigger.is_empty() && pattern.trigger.trim().to_lowercase() == trigger)
+                || candidate.similarity_score(pattern) > self.dedup_threshold
+        })
+    }
+
+    /// Whether a pattern meets this manager's quality thresholds
+    pub fn assess_quality(&self, pattern: &Pattern) -> bool {
+        pattern.meets_quality(&self.quality_thresholds)
+    }
+
     /// Create a new pattern
     ///
     /// Creates a pattern with the given parameters and stores it in the repository.
@@ -274,7 +458,19 @@ impl PatternManager {
     /// Returns the full pattern details if found.
     pub async fn get_pattern(&self, pattern_id: &str) -> Result<Option<Pattern>> {
         tracing::debug!("Getting pattern: {}", pattern_id);
-        self.pattern_repo.get_by_id(pattern_id).await
+
+        if let Some(pattern) = self.cache.get(pattern_id) {
+            if let Some(metrics) = &self.metrics {
+                metrics.record_cache_hit();
+            }
+            return Ok(Some(pattern));
+        }
+
+        let pattern = self.pattern_repo.get_by_id(pattern_id).await?;
+        if let Some(pattern) = &pattern {
+            self.cache.insert(pattern.clone());
+        }
+        Ok(pattern)
     }
 
     /// Update a pattern
@@ -295,6 +491,7 @@ impl PatternManager {
             .ok_or_else(|| {
                 crate::error::AppError::NotFound(format!("Pattern not found: {}", pattern_id))
             })?;
+        let previous = pattern.clone();
 
         // Apply updates
         if let Some(name) = &updates.name {
@@ -328,11 +525,17 @@ impl PatternManager {
             pattern.confidence = *confidence;
         }
 
+        // Keep a snapshot of the previous version for history and diffs
+        self.pattern_repo.save_version(&previous).await?;
+
         pattern.updated_at = Utc::now();
         pattern.version += 1;
 
-        // Save updated pattern
-        self.pattern_repo.update(pattern_id, &pattern).await
+        // Save updated pattern, then drop the cached copy so readers that
+        // raced the write cannot leave a stale entry behind
+        let updated = self.pattern_repo.update(pattern_id, &pattern).await?;
+        self.cache.invalidate(pattern_id);
+        Ok(updated)
     }
 
     /// Record an outcome for a pattern
@@ -367,8 +570,9 @@ impl PatternManager {
             context: record.context.clone(),
         };
 
-        // Record usage in repository
+        // Record usage in repository (this also updates the pattern's statistics)
         let usage_id = self.pattern_repo.record_usage(pattern_id, &usage).await?;
+        self.cache.invalidate(pattern_id);
 
         Ok(usage_id)
     }
@@ -494,7 +698,7 @@ impl PatternManager {
         }
 
         // Score based on pattern quality
-        if pattern.is_high_quality() {
+        if self.assess_quality(pattern) {
             score += 0.2;
             reasons.push("High quality pattern (high confidence + success rate)".to_string());
         }
@@ -541,7 +745,11 @@ impl PatternManager {
     pub async fn match_patterns(&self, input: &str, limit: u32) -> Result<Vec<Pattern>> {
         tracing::info!("Matching patterns against input (limit: {})", limit);
 
-        self.pattern_repo.match_patterns(input, limit).await
+        let patterns = self.pattern_repo.match_patterns(input, limit).await?;
+        for pattern in &patterns {
+            self.cache.insert(pattern.clone());
+        }
+        Ok(patterns)
     }
 
     /// Discover new patterns from memories
@@ -574,7 +782,7 @@ impl PatternManager {
                 self.analyze_memories_for_patterns(&memories, limit as usize)
             }
             DiscoveryMethod::UsagePattern => {
-                self.analyze_usage_patterns(&memories, limit as usize)
+                self.analyze_usage_patterns(limit as usize).await?
             }
             DiscoveryMethod::ErrorPattern => {
                 self.analyze_error_patterns(&memories, limit as usize)
@@ -652,15 +860,70 @@ impl PatternManager {
         (patterns, confidence, suggestions)
     }
 
-    /// Analyze usage patterns from memories
-    fn analyze_usage_patterns(
+    /// Analyze usage patterns from recent pattern usages
+    ///
+    /// Groups recent usage inputs by word n-gram and suggests a new pattern
+    /// for every n-gram that shows up across at least
+    /// `USAGE_NGRAM_MIN_PATTERNS` distinct patterns.
+    async fn analyze_usage_patterns(
         &self,
-        _memories: &[Memory],
-        _limit: usize,
-    ) -> (Vec<Pattern>, f32, Vec<PatternSuggestion>) {
-        // Placeholder: In a real implementation, this would analyze
-        // usage patterns from memory content
-        (Vec::new(), 0.0, Vec::new())
+        limit: usize,
+    ) -> Result<(Vec<Pattern>, f32, Vec<PatternSuggestion>)> {
+        let usages = self
+            .pattern_repo
+            .list_recent_usages(USAGE_ANALYSIS_WINDOW, None)
+            .await?;
+
+        // n-gram -> distinct pattern ids
+        let mut ngram_patterns: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut all_patterns: HashSet<&str> = HashSet::new();
+
+        for usage in &usages {
+            all_patterns.insert(usage.pattern_id.as_str());
+            for ngram in input_ngrams(&usage.input, USAGE_NGRAM_SIZE) {
+                ngram_patterns
+                    .entry(ngram)
+                    .or_default()
+                    .insert(usage.pattern_id.clone());
+            }
+        }
+
+        let mut common: Vec<(String, HashSet<String>)> = ngram_patterns
+            .into_iter()
+            .filter(|(_, ids)| ids.len() >= USAGE_NGRAM_MIN_PATTERNS)
+            .collect();
+
+        // Most widely shared first, ties broken alphabetically for stable output
+        common.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+
+        let suggestions: Vec<PatternSuggestion> = common
+            .into_iter()
+            .take(limit)
+            .map(|(ngram, ids)| {
+                let mut ids: Vec<String> = ids.into_iter().collect();
+                ids.sort();
+                PatternSuggestion {
+                    name: format!("{} pattern", ngram),
+                    problem: format!("Recurring input: {}", ngram),
+                    solution: String::new(),
+                    pattern_type: PatternType::Workflow,
+                    confidence: (ids.len() as f32 / all_patterns.len() as f32).min(1.0),
+                    evidence: vec![format!(
+                        "Input \"{}\" used across {} patterns: {}",
+                        ngram,
+                        ids.len(),
+                        ids.join(", ")
+                    )],
+                }
+            })
+            .collect();
+
+        let confidence = suggestions
+            .iter()
+            .map(|s| s.confidence)
+            .fold(0.0_f32, f32::max);
+
+        Ok((Vec::new(), confidence, suggestions))
     }
 
     /// Analyze error patterns from memories
@@ -734,11 +997,43 @@ impl PatternManager {
 
     /// Delete a pattern
     ///
-    /// Removes a pattern from the system.
+    /// Soft-deletes the pattern by marking it archived; the record and its
+    /// version history are kept for auditing.
     pub async fn delete_pattern(&self, pattern_id: &str) -> Result<bool> {
-        tracing::info!("Deleting pattern: {}", pattern_id);
+        tracing::info!("Archiving pattern: {}", pattern_id);
+
+        let changed = self
+            .pattern_repo
+            .set_status(pattern_id, PatternStatus::Archived)
+            .await?;
+        self.cache.invalidate(pattern_id);
+        Ok(changed)
+    }
+
+    /// Deprecate a pattern
+    ///
+    /// Deprecated patterns are no longer returned or recommended by default.
+    pub async fn deprecate_pattern(&self, pattern_id: &str) -> Result<bool> {
+        tracing::info!("Deprecating pattern: {}", pattern_id);
 
-        self.pattern_repo.delete(pattern_id).await
+        let changed = self
+            .pattern_repo
+            .set_status(pattern_id, PatternStatus::Deprecated)
+            .await?;
+        self.cache.invalidate(pattern_id);
+        Ok(changed)
+    }
+
+    /// Restore a deprecated or archived pattern to active
+    pub async fn restore_pattern(&self, pattern_id: &str) -> Result<bool> {
+        tracing::info!("Restoring pattern: {}", pattern_id);
+
+        let changed = self
+            .pattern_repo
+            .set_status(pattern_id, PatternStatus::Active)
+            .await?;
+        self.cache.invalidate(pattern_id);
+        Ok(changed)
     }
 
     /// Add an example to a pattern
@@ -767,7 +1062,9 @@ impl PatternManager {
         pattern.add_example(input, output, outcome, source_memory_id);
 
         // Save updated pattern
-        self.pattern_repo.update(pattern_id, &pattern).await
+        let updated = self.pattern_repo.update(pattern_id, &pattern).await?;
+        self.cache.invalidate(pattern_id);
+        Ok(updated)
     }
 
     /// List patterns with pagination
@@ -777,10 +1074,11 @@ impl PatternManager {
         &self,
         limit: usize,
         start: usize,
+        include_archived: bool,
     ) -> Result<Vec<Pattern>> {
         tracing::debug!("Listing patterns (limit: {}, start: {})", limit, start);
 
-        self.pattern_repo.list(limit, start).await
+        self.pattern_repo.list(limit, start, include_archived).await
     }
 
     /// Auto-generate patterns from high-importance memories
@@ -790,6 +1088,18 @@ impl PatternManager {
     pub async fn auto_generate_from_memories(
         &self,
         min_importance: f32,
+    ) -> Result<Vec<String>> {
+        self.generate_from_memories(min_importance, Vec::new()).await
+    }
+
+    /// Generate patterns from high-importance memories, skipping duplicates
+    ///
+    /// A generated pattern is not persisted when it duplicates one of `existing`
+    /// or a pattern created earlier in the same run (see `find_duplicate`).
+    async fn generate_from_memories(
+        &self,
+        min_importance: f32,
+        mut existing: Vec<Pattern>,
     ) -> Result<Vec<String>> {
         tracing::info!(
             "Auto-generating patterns from memories with importance >= {}",
@@ -834,6 +1144,19 @@ impl PatternManager {
             // Generate pattern from memory
             match generator.generate_from_memory(&memory).await {
                 Ok(request) => {
+                    let mut candidate = request.to_pattern(&memory.user_id);
+                    candidate.trigger = request.trigger.clone();
+                    if let Some(duplicate) = self.find_duplicate(&candidate, &existing) {
+                        tracing::debug!(
+                            "Skipping pattern '{}' from memory {}: duplicates pattern {}",
+                            candidate.name,
+                            memory.id,
+                            duplicate.id
+                        );
+                        seen_patterns.insert(pattern_key);
+                        continue;
+                    }
+
                     // Create the pattern
                     let pattern = self
                         .create_pattern(
@@ -873,6 +1196,7 @@ impl PatternManager {
                         pattern.name,
                         memory.id
                     );
+                    existing.push(candidate);
                 }
                 Err(e) => {
                     tracing::error!(
@@ -1124,19 +1448,15 @@ impl PatternManager {
         let existing_patterns = self
             .pattern_repo
             .search(&PatternQuery {
+                include_archived: true,
                 page: 1,
                 page_size: 1000,
                 ..Default::default()
             })
             .await?;
 
-        let existing_triggers: std::collections::HashSet<String> = existing_patterns
-            .iter()
-            .map(|p| p.trigger.to_lowercase())
-            .collect();
-
         // Generate patterns from high-importance memories
-        let created_ids = self.auto_generate_from_memories(0.7).await?;
+        let created_ids = self.generate_from_memories(0.7, existing_patterns).await?;
 
         // Count truly new patterns (not duplicates)
         let new_count = created_ids.len() as u32;
@@ -1155,12 +1475,201 @@ impl PatternManager {
     pub async fn count_patterns(&self) -> Result<u64> {
         self.pattern_repo.count().await
     }
+
+    /// Export patterns as a JSON pattern library
+    ///
+    /// Exports all patterns, or only those created by `created_by`. Patterns are
+    /// fetched page by page and serialized straight into the output buffer.
+    pub async fn export_to_json(&self, created_by: Option<&str>) -> Result<String> {
+        tracing::info!("Exporting patterns (created_by: {:?})", created_by);
+
+        let mut out = Vec::new();
+        out.extend_from_slice(
+            format!(
+                "{{\"schema_version\":{},\"exported_at\":{},\"patterns\":[",
+                PATTERN_EXPORT_SCHEMA_VERSION,
+                serde_json::to_string(&Utc::now())?
+            )
+            .as_bytes(),
+        );
+
+        let mut exported = 0usize;
+        let mut page = 1;
+        loop {
+            let patterns = self
+                .pattern_repo
+                .search(&PatternQuery {
+                    created_by: created_by.map(str::to_string),
+                    include_archived: true,
+                    page,
+                    page_size: PATTERN_EXPORT_PAGE_SIZE,
+                    ..Default::default()
+                })
+                .await?;
+
+            for pattern in &patterns {
+                if exported > 0 {
+                    out.push(b',');
+                }
+                pattern.serialize(&mut serde_json::Serializer::new(&mut out))?;
+                exported += 1;
+            }
+
+            if patterns.len() < PATTERN_EXPORT_PAGE_SIZE as usize {
+                break;
+            }
+            page += 1;
+        }
+
+        out.extend_from_slice(b"]}");
+        tracing::info!("Exported {} patterns", exported);
+
+        String::from_utf8(out).map_err(|e| AppError::Serialization(e.to_string()))
+    }
+
+    /// Import patterns from a JSON pattern library
+    ///
+    /// Imported patterns get fresh IDs and are owned by `imported_by`. Patterns whose
+    /// name already exists (or appears earlier in the same library) are skipped.
+    pub async fn import_from_json(&self, json: &str, imported_by: &str) -> Result<ImportResult> {
+        let library: PatternLibrary = serde_json::from_str(json)?;
+        if library.schema_version > PATTERN_EXPORT_SCHEMA_VERSION {
+            return Err(AppError::Validation(format!(
+                "Unsupported pattern library schema version {} (max {})",
+                library.schema_version, PATTERN_EXPORT_SCHEMA_VERSION
+            )));
+        }
+
+        tracing::info!(
+            "Importing {} patterns for user: {}",
+            library.patterns.len(),
+            imported_by
+        );
+
+        let mut existing_names = HashSet::new();
+        let mut page = 1;
+        loop {
+            let patterns = self
+                .pattern_repo
+                .search(&PatternQuery {
+                    include_archived: true,
+                    page,
+                    page_size: PATTERN_EXPORT_PAGE_SIZE,
+                    ..Default::default()
+                })
+                .await?;
+            let fetched = patterns.len();
+            existing_names.extend(patterns.into_iter().map(|p| p.name));
+
+            if fetched < PATTERN_EXPORT_PAGE_SIZE as usize {
+                break;
+            }
+            page += 1;
+        }
+
+        let mut result = ImportResult::default();
+        let now = Utc::now();
+        for mut pattern in library.patterns {
+            if !existing_names.insert(pattern.name.clone()) {
+                result.skipped += 1;
+                continue;
+            }
+
+            pattern.id = uuid::Uuid::new_v4().to_string();
+            pattern.created_by = imported_by.to_string();
+            pattern.created_at = now;
+            pattern.updated_at = now;
+
+            match self.pattern_repo.create(&pattern).await {
+                Ok(_) => result.imported += 1,
+                Err(e) => result
+                    .errors
+                    .push(format!("Failed to import pattern '{}': {}", pattern.name, e)),
+            }
+        }
+
+        tracing::info!(
+            "Pattern import complete: {} imported, {} skipped, {} failed",
+            result.imported,
+            result.skipped,
+            result.errors.len()
+        );
+
+        Ok(result)
+    }
+
+    /// Compute the differences between two versions of a pattern
+    ///
+    /// Text fields are compared by value. Tags are compared as sets:
+    /// `old_value` lists removed tags and `new_value` lists added tags.
+    pub fn get_pattern_diff(old: &Pattern, new: &Pattern) -> PatternDiff {
+        let mut changed_fields = Vec::new();
+
+        let text_fields = [
+            ("pattern_type", Some(old.pattern_type.to_string()), Some(new.pattern_type.to_string())),
+            ("name", Some(old.name.clone()), Some(new.name.clone())),
+            ("description", Some(old.description.clone()), Some(new.description.clone())),
+            ("trigger", Some(old.trigger.clone()), Some(new.trigger.clone())),
+            ("context", Some(old.context.clone()), Some(new.context.clone())),
+            ("problem", Some(old.problem.clone()), Some(new.problem.clone())),
+            ("solution", Some(old.solution.clone()), Some(new.solution.clone())),
+            ("explanation", old.explanation.clone(), new.explanation.clone()),
+        ];
+
+        for (field_name, old_value, new_value) in text_fields {
+            if old_value != new_value {
+                changed_fields.push(FieldChange {
+                    field_name: field_name.to_string(),
+                    old_value,
+                    new_value,
+                });
+            }
+        }
+
+        let old_tags: HashSet<&String> = old.tags.iter().collect();
+        let new_tags: HashSet<&String> = new.tags.iter().collect();
+        let join_sorted = |tags: Vec<&&String>| -> Option<String> {
+            if tags.is_empty() {
+                return None;
+            }
+            let mut tags: Vec<&str> = tags.into_iter().map(|t| t.as_str()).collect();
+            tags.sort_unstable();
+            Some(tags.join(", "))
+        };
+        let removed = join_sorted(old_tags.difference(&new_tags).collect());
+        let added = join_sorted(new_tags.difference(&old_tags).collect());
+
+        if removed.is_some() || added.is_some() {
+            changed_fields.push(FieldChange {
+                field_name: "tags".to_string(),
+                old_value: removed,
+                new_value: added,
+            });
+        }
+
+        PatternDiff { changed_fields }
+    }
+}
+
+/// Split an input into lowercase word n-grams
+fn input_ngrams(input: &str, n: usize) -> Vec<String> {
+    let words: Vec<String> = input
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    if n == 0 || words.len() < n {
+        return Vec::new();
+    }
+
+    words.windows(n).map(|w| w.join(" ")).collect()
 }
 
 /// Create a PatternManager service with optional AI generator
 pub fn create_pattern_manager(
-    pattern_repo: Arc<dyn PatternRepository>,
-    memory_repo: Arc<dyn MemoryRepository>,
+    pattern_repo: Arc<dyn PatternRepository + Send + Sync>,
+    memory_repo: Arc<dyn MemoryRepository + Send + Sync>,
     ai_generator: Option<Arc<dyn PatternGenerator>>,
 ) -> PatternManager {
     PatternManager::new(pattern_repo, memory_repo, ai_generator)
@@ -1168,8 +1677,8 @@ pub fn create_pattern_manager(
 
 /// Create a basic PatternManager without AI generator
 pub fn create_pattern_manager_basic(
-    pattern_repo: Arc<dyn PatternRepository>,
-    memory_repo: Arc<dyn MemoryRepository>,
+    pattern_repo: Arc<dyn PatternRepository + Send + Sync>,
+    memory_repo: Arc<dyn MemoryRepository + Send + Sync>,
 ) -> PatternManager {
     PatternManager::new_basic(pattern_repo, memory_repo)
 }
@@ -1213,7 +1722,16 @@ mod tests {
             Ok(true)
         }
 
-        async fn list(&self, _limit: usize, _start: usize) -> Result<Vec<Pattern>> {
+        async fn set_status(&self, id: &str, _status: PatternStatus) -> Result<bool> {
+            Ok(id == "existing_pattern")
+        }
+
+        async fn list(
+            &self,
+            _limit: usize,
+            _start: usize,
+            _include_archived: bool,
+        ) -> Result<Vec<Pattern>> {
             Ok(vec![])
         }
 
@@ -1240,6 +1758,39 @@ mod tests {
             Ok("usage_123".to_string())
         }
 
+        async fn list_recent_usages(
+            &self,
+            _limit: usize,
+            _since: Option<chrono::DateTime<Utc>>,
+        ) -> Result<Vec<PatternUsage>> {
+            let usage = |pattern_id: &str, input: &str| PatternUsage {
+                id: format!("usage_{}", pattern_id),
+                pattern_id: pattern_id.to_string(),
+                user_id: "user_123".to_string(),
+                input: input.to_string(),
+                output: String::new(),
+                outcome: 1.0,
+                feedback: None,
+                used_at: Utc::now(),
+                context: None,
+            };
+            Ok(vec![
+                usage("pattern_a", "How to fix borrow checker error"),
+                usage("pattern_b", "Borrow checker complains about lifetimes"),
+                usage("pattern_c", "Rust borrow checker in async code"),
+                usage("pattern_c", "Deploy with docker compose"),
+                usage("pattern_d", "docker compose networking"),
+            ])
+        }
+
+        async fn save_version(&self, _pattern: &Pattern) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get_version(&self, _pattern_id: &str, _version: u32) -> Result<Option<Pattern>> {
+            Ok(None)
+        }
+
         async fn get_stats(&self) -> Result<PatternStats> {
             Ok(PatternStats {
                 total_count: 10,
@@ -1333,6 +1884,7 @@ mod tests {
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
                 accessed_at: Utc::now(),
+                access_count: 0,
                 expires_at: None,
                 status: crate::models::memory::MemoryStatus::Active,
                 version: 1,
@@ -1356,6 +1908,58 @@ mod tests {
                 storage_size_bytes: 1024,
             })
         }
+
+        async fn increment_access_count(&self, _id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn list_hot(&self, _user_id: &str, _limit: usize) -> Result<Vec<Memory>> {
+            Ok(vec![])
+        }
+
+        async fn list_by_source_id(&self, _source_id: &str) -> Result<Vec<Memory>> {
+            Ok(vec![])
+        }
+
+        async fn delete_by_conversation(&self, _session_id: &str) -> Result<u64> {
+            Ok(0)
+        }
+
+        async fn search_by_embedding(
+            &self,
+            _query_vector: &[f32],
+            _query: &MemoryQuery,
+            _min_similarity: f32,
+        ) -> Result<Vec<(Memory, f32)>> {
+            Ok(vec![])
+        }
+    }
+
+    #[test]
+    fn test_assess_quality_uses_configured_thresholds() {
+        let manager = PatternManager::new_basic(
+            Arc::new(MockPatternRepository),
+            Arc::new(MockMemoryRepository),
+        );
+        let mut pattern = Pattern::new(
+            "user_123",
+            PatternType::BestPractice,
+            "Retry with backoff",
+            "Flaky network",
+            "Retry with exponential backoff",
+        );
+        pattern.confidence = 0.8;
+        assert!(!manager.assess_quality(&pattern)); // default success rate is 0.5
+
+        pattern.success_count = 9;
+        pattern.failure_count = 1;
+        assert!(manager.assess_quality(&pattern));
+
+        let manager = manager.with_quality_thresholds(PatternQualityThresholds {
+            min_usage_count: 20,
+            ..Default::default()
+        });
+        assert!(!manager.assess_quality(&pattern));
     }
 
     #[tokio::test]
@@ -1494,6 +2098,32 @@ mod tests {
         assert_eq!(result.method, DiscoveryMethod::MemoryAnalysis);
     }
 
+    #[tokio::test]
+    async fn test_discover_usage_patterns() {
+        let pattern_repo = Arc::new(MockPatternRepository);
+        let memory_repo = Arc::new(MockMemoryRepository);
+        let manager = PatternManager::new_basic(pattern_repo, memory_repo);
+
+        let result = manager
+            .discover_patterns("user_123", DiscoveryMethod::UsagePattern, 5)
+            .await
+            .unwrap();
+
+        // "borrow checker" spans 3 patterns, "docker compose" only 2
+        assert_eq!(result.suggested_patterns.len(), 1);
+        assert_eq!(result.suggested_patterns[0].name, "borrow checker pattern");
+        assert!((result.confidence - 0.75).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_input_ngrams() {
+        assert_eq!(
+            input_ngrams("Fix the Borrow-checker", 2),
+            vec!["fix the", "the borrow", "borrow checker"]
+        );
+        assert!(input_ngrams("single", 2).is_empty());
+    }
+
     #[tokio::test]
     async fn test_get_pattern_stats() {
         let pattern_repo = Arc::new(MockPatternRepository);
@@ -1518,6 +2148,79 @@ mod tests {
         assert!(result.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_deprecate_and_restore_pattern() {
+        let pattern_repo = Arc::new(MockPatternRepository);
+        let memory_repo = Arc::new(MockMemoryRepository);
+        let manager = PatternManager::new_basic(pattern_repo, memory_repo);
+
+        assert!(manager.deprecate_pattern("existing_pattern").await.unwrap());
+        assert!(manager.restore_pattern("existing_pattern").await.unwrap());
+        assert!(!manager.deprecate_pattern("missing_pattern").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_get_pattern_uses_cache() {
+        use std::sync::atomic::Ordering;
+
+        let pattern_repo = Arc::new(MockPatternRepository);
+        let memory_repo = Arc::new(MockMemoryRepository);
+        let metrics = Arc::new(AppMetrics::default());
+        let cache = PatternCache::new(10);
+        let manager = PatternManager::new_basic(pattern_repo, memory_repo)
+            .with_cache(cache.clone())
+            .with_metrics(metrics.clone());
+        let hits = || metrics.cache_hits_total.load(Ordering::SeqCst);
+        let id = "existing_pattern";
+
+        assert!(manager.get_pattern(id).await.unwrap().is_some());
+        assert_eq!(hits(), 0);
+        assert!(manager.get_pattern(id).await.unwrap().is_some());
+        assert_eq!(hits(), 1);
+
+        // Missing patterns are not cached
+        let missing = manager.get_pattern("missing_pattern").await.unwrap();
+        assert!(missing.is_none());
+        assert_eq!(cache.len(), 1);
+
+        let updates = PatternUpdates {
+            name: Some("Renamed".to_string()),
+            ..Default::default()
+        };
+        manager.update_pattern(id, &updates).await.unwrap();
+        assert!(cache.get(id).is_none());
+
+        manager.get_pattern(id).await.unwrap();
+        assert_eq!(hits(), 1);
+        manager.delete_pattern(id).await.unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_pattern_cache_evicts_least_recently_used() {
+        let cache = PatternCache::new(2);
+        assert_eq!(cache.capacity(), 2);
+        let default_capacity = PatternCache::default().capacity();
+        assert_eq!(default_capacity, DEFAULT_PATTERN_CACHE_CAPACITY);
+
+        for id in ["a", "b"] {
+            let mut pattern = Pattern::new("user", PatternType::Skill, id, "problem", "solution");
+            pattern.id = id.to_string();
+            cache.insert(pattern);
+        }
+        // Touch "a" so that "b" becomes the eviction candidate
+        assert!(cache.get("a").is_some());
+
+        let mut pattern = Pattern::new("user", PatternType::Skill, "c", "problem", "solution");
+        pattern.id = "c".to_string();
+        cache.insert(pattern);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+    }
+
     #[tokio::test]
     async fn test_add_example() {
         let pattern_repo = Arc::new(MockPatternRepository);
@@ -1537,7 +2240,7 @@ mod tests {
         let memory_repo = Arc::new(MockMemoryRepository);
         let manager = PatternManager::new_basic(pattern_repo, memory_repo);
 
-        let patterns = manager.list_patterns(10, 0).await.unwrap();
+        let patterns = manager.list_patterns(10, 0, false).await.unwrap();
 
         assert!(patterns.is_empty());
     }
@@ -1607,6 +2310,7 @@ mod tests {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             accessed_at: Utc::now(),
+            access_count: 0,
             expires_at: None,
             status: crate::models::memory::MemoryStatus::Active,
             version: 1,
@@ -1719,6 +2423,52 @@ mod tests {
         assert_eq!(pattern.pattern_type, PatternType::ProblemSolution);
     }
 
+    #[test]
+    fn test_find_duplicate_uses_dedup_threshold() {
+        let manager = PatternManager::new_basic(
+            Arc::new(MockPatternRepository),
+            Arc::new(MockMemoryRepository),
+        );
+        let existing = vec![Pattern::new(
+            "user_123",
+            PatternType::BestPractice,
+            "Retry with backoff",
+            "Flaky network calls",
+            "Retry with exponential backoff",
+        )];
+
+        let near_copy = Pattern::new(
+            "user_456",
+            PatternType::BestPractice,
+            "Retry with backoff",
+            "Flaky network calls",
+            "Retry with exponential backoff!",
+        );
+        assert!(manager.find_duplicate(&near_copy, &existing).is_some());
+
+        let unrelated = Pattern::new(
+            "user_456",
+            PatternType::Workflow,
+            "Release checklist",
+            "Shipping a new version",
+            "Tag, build, then deploy",
+        );
+        assert!(manager.find_duplicate(&unrelated, &existing).is_none());
+
+        let mut same_trigger = unrelated.clone();
+        same_trigger.trigger = "network".to_string();
+        let mut existing_with_trigger = existing.clone();
+        existing_with_trigger[0].trigger = "Network".to_string();
+        assert!(
+            manager
+                .find_duplicate(&same_trigger, &existing_with_trigger)
+                .is_some()
+        );
+
+        let strict = manager.with_dedup_threshold(1.0);
+        assert!(strict.find_duplicate(&near_copy, &existing).is_none());
+    }
+
     #[tokio::test]
     async fn test_auto_discover_patterns_no_ai_generator() {
         let pattern_repo = Arc::new(MockPatternRepository);
@@ -1749,4 +2499,87 @@ mod tests {
             || words.contains(&"brown".to_string())
             || words.contains(&"jumps".to_string()));
     }
+
+    #[test]
+    fn test_get_pattern_diff() {
+        let mut old = Pattern::new(
+            "user_123",
+            PatternType::ProblemSolution,
+            "Test Pattern",
+            "Test Problem",
+            "Test Solution",
+        );
+        old.tags = vec!["rust".to_string(), "async".to_string()];
+
+        let mut new = old.clone();
+        new.solution = "Better Solution".to_string();
+        new.explanation = Some("Why it works".to_string());
+        new.tags = vec!["rust".to_string(), "tokio".to_string()];
+        new.version += 1;
+
+        let diff = PatternManager::get_pattern_diff(&old, &new);
+        let fields: Vec<&str> = diff
+            .changed_fields
+            .iter()
+            .map(|c| c.field_name.as_str())
+            .collect();
+        assert_eq!(fields, vec!["solution", "explanation", "tags"]);
+
+        let explanation = &diff.changed_fields[1];
+        assert_eq!(explanation.old_value, None);
+        assert_eq!(explanation.new_value.as_deref(), Some("Why it works"));
+
+        let tags = &diff.changed_fields[2];
+        assert_eq!(tags.old_value.as_deref(), Some("async"));
+        assert_eq!(tags.new_value.as_deref(), Some("tokio"));
+
+        assert!(PatternManager::get_pattern_diff(&old, &old).changed_fields.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let pattern_repo = Arc::new(MockPatternRepository);
+        let memory_repo = Arc::new(MockMemoryRepository);
+        let manager = PatternManager::new_basic(pattern_repo, memory_repo);
+
+        let json = manager.export_to_json(Some("user_123")).await.unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["schema_version"], PATTERN_EXPORT_SCHEMA_VERSION);
+        assert_eq!(value["patterns"].as_array().unwrap().len(), 1);
+        assert_eq!(value["patterns"][0]["name"], "Test Pattern");
+
+        // The exported pattern already exists by name
+        let result = manager.import_from_json(&json, "user_456").await.unwrap();
+        assert_eq!(result.imported, 0);
+        assert_eq!(result.skipped, 1);
+
+        let mut library = value;
+        let mut renamed = library["patterns"][0].clone();
+        renamed["name"] = serde_json::json!("Shared Pattern");
+        library["patterns"] = serde_json::json!([renamed.clone(), renamed]);
+
+        let result = manager
+            .import_from_json(&library.to_string(), "user_456")
+            .await
+            .unwrap();
+        assert_eq!(result.imported, 1);
+        assert_eq!(result.skipped, 1);
+        assert!(result.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_newer_schema() {
+        let pattern_repo = Arc::new(MockPatternRepository);
+        let memory_repo = Arc::new(MockMemoryRepository);
+        let manager = PatternManager::new_basic(pattern_repo, memory_repo);
+
+        let json = format!(
+            "{{\"schema_version\":{},\"patterns\":[]}}",
+            PATTERN_EXPORT_SCHEMA_VERSION + 1
+        );
+        assert!(matches!(
+            manager.import_from_json(&json, "user_456").await,
+            Err(AppError::Validation(_))
+        ));
+    }
 }
@@ -0,0 +1,371 @@
+//! 画像服务
+//!
+//! 提供用户画像的 CRUD 操作、去重合并，以及将 Profile 类型记忆同步到用户画像。
+
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::error::{AppError, Result};
+use crate::models::memory::{Memory, MemoryType};
+use crate::models::profile::{Profile, ProfileQuery};
+use crate::models::profile_repository::ProfileRepository;
+use crate::observability::LogContext;
+use crate::services::session::Pagination;
+
+/// 画像服务 trait
+#[async_trait]
+pub trait ProfileService: Send + Sync {
+    /// 创建画像
+    async fn create(&self, profile: &Profile) -> Result<Profile>;
+
+    /// 根据 ID 获取画像
+    async fn get_by_id(&self, id: &str) -> Result<Option<Profile>>;
+
+    /// 根据用户 ID 获取画像
+    async fn get_by_user_id(&self, user_id: &str) -> Result<Option<Profile>>;
+
+    /// 更新画像
+    async fn update(&self, profile: &Profile) -> Result<Profile>;
+
+    /// 删除画像
+    async fn delete(&self, id: &str) -> Result<bool>;
+
+    /// 分页列出用户的画像
+    async fn list_by_user(&self, user_id: &str, pagination: Pagination) -> Result<Vec<Profile>>;
+
+    /// 统计用户的画像数量
+    async fn count_by_user(&self, user_id: &str) -> Result<u64>;
+
+    /// 合并画像（去重）
+    ///
+    /// 将 `secondary_id` 的兴趣、偏好和事实并入 `primary_id`（按字符串去重，
+    /// 偏好冲突时保留主画像的值），随后删除次画像。
+    async fn merge_profiles(&self, primary_id: &str, secondary_id: &str) -> Result<Profile>;
+
+    /// 从 Profile 类型记忆更新所属用户的画像（画像不存在时创建）
+    async fn sync_from_memory(&self, memory: &Memory) -> Result<()>;
+}
+
+/// 画像服务实现
+pub struct ProfileServiceImpl {
+    repository: Arc<dyn ProfileRepository + Send + Sync>,
+}
+
+impl ProfileServiceImpl {
+    /// 创建新的服务实例
+    pub fn new(repository: Arc<dyn ProfileRepository + Send + Sync>) -> Self {
+        Self { repository }
+    }
+}
+
+#[async_trait]
+impl ProfileService for ProfileServiceImpl {
+    async fn create(&self, profile: &Profile) -> Result<Profile> {
+        self.repository.create(profile).await
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<Option<Profile>> {
+        self.repository.get_by_id(id).await
+    }
+
+    async fn get_by_user_id(&self, user_id: &str) -> Result<Option<Profile>> {
+        self.repository.get_by_user_id(user_id).await
+    }
+
+    async fn update(&self, profile: &Profile) -> Result<Profile> {
+        self.repository
+            .update(&profile.id, profile)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Profile not found: {}", profile.id)))
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool> {
+        self.repository.delete(id).await
+    }
+
+    async fn list_by_user(&self, user_id: &str, pagination: Pagination) -> Result<Vec<Profile>> {
+        let query = ProfileQuery {
+            user_id: Some(user_id.to_string()),
+            page: pagination.page.max(1) as u32,
+            page_size: pagination.page_size as u32,
+            ..Default::default()
+        };
+        self.repository.search(&query).await
+    }
+
+    async fn count_by_user(&self, user_id: &str) -> Result<u64> {
+        self.repository.count_by_user(user_id).await
+    }
+
+    async fn merge_profiles(&self, primary_id: &str, secondary_id: &str) -> Result<Profile> {
+        if primary_id == secondary_id {
+            return Err(AppError::Validation(
+                "Cannot merge a profile into itself".to_string(),
+            ));
+        }
+
+        let mut primary = self
+            .get_by_id(primary_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Profile not found: {}", primary_id)))?;
+        let secondary = self
+            .get_by_id(secondary_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Profile not found: {}", secondary_id)))?;
+
+        if primary.tenant_id != secondary.tenant_id {
+            return Err(AppError::Forbidden(
+                "Cannot merge profiles across tenants".to_string(),
+            ));
+        }
+
+        LogContext::new(primary.tenant_id.clone())
+            .run("profile.merge_profiles", async {
+                merge_profile_fields(&mut primary, secondary);
+                let merged = self.update(&primary).await?;
+                self.repository.delete(secondary_id).await?;
+
+                Ok(merged)
+            })
+            .await
+    }
+
+    async fn sync_from_memory(&self, memory: &Memory) -> Result<()> {
+        if memory.memory_type != MemoryType::Profile {
+            return Ok(());
+        }
+
+        match self.repository.get_by_user_id(&memory.user_id).await? {
+            Some(mut profile) => {
+                if profile.sync_from_memory(memory) {
+                    self.repository.update(&profile.id, &profile).await?;
+                }
+            }
+            None => {
+                let mut profile = Profile::new(&memory.user_id);
+                profile.tenant_id = memory.tenant_id.clone();
+                if profile.sync_from_memory(memory) {
+                    self.repository.create(&profile).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 将次画像的兴趣、偏好和事实并入主画像
+///
+/// 兴趣和事实按字符串去重并保持原有顺序；偏好只补充主画像中缺失的键。
+fn merge_profile_fields(primary: &mut Profile, secondary: Profile) {
+    for interest in secondary.interests {
+        if !primary.interests.contains(&interest) {
+            primary.interests.push(interest);
+        }
+    }
+
+    for (key, value) in secondary.preferences {
+        primary.preferences.entry(key).or_insert(value);
+    }
+
+    for fact in secondary.facts {
+        if !primary.facts.iter().any(|f| f.fact == fact.fact) {
+            primary.facts.push(fact);
+        }
+    }
+
+    primary.updated_at = Utc::now();
+    primary.version += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::memory::MemorySource;
+    use crate::models::profile::{ProfileComparison, ProfileFactCategory};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MockProfileRepository {
+        profiles: Mutex<Vec<Profile>>,
+    }
+
+    #[async_trait]
+    impl ProfileRepository for MockProfileRepository {
+        async fn create(&self, profile: &Profile) -> Result<Profile> {
+            self.profiles.lock().unwrap().push(profile.clone());
+            Ok(profile.clone())
+        }
+
+        async fn get_by_id(&self, id: &str) -> Result<Option<Profile>> {
+            let profiles = self.profiles.lock().unwrap();
+            Ok(profiles.iter().find(|p| p.id == id).cloned())
+        }
+
+        async fn get_by_user_id(&self, user_id: &str) -> Result<Option<Profile>> {
+            let profiles = self.profiles.lock().unwrap();
+            Ok(profiles.iter().find(|p| p.user_id == user_id).cloned())
+        }
+
+        async fn update(&self, id: &str, profile: &Profile) -> Result<Option<Profile>> {
+            let mut profiles = self.profiles.lock().unwrap();
+            let existing = profiles.iter_mut().find(|p| p.id == id);
+            Ok(existing.map(|p| {
+                *p = profile.clone();
+                profile.clone()
+            }))
+        }
+
+        async fn delete(&self, id: &str) -> Result<bool> {
+            let mut profiles = self.profiles.lock().unwrap();
+            let before = profiles.len();
+            profiles.retain(|p| p.id != id);
+            Ok(profiles.len() < before)
+        }
+
+        async fn list(&self, _limit: usize, _start: usize) -> Result<Vec<Profile>> {
+            Ok(self.profiles.lock().unwrap().clone())
+        }
+
+        async fn count(&self) -> Result<u64> {
+            Ok(self.profiles.lock().unwrap().len() as u64)
+        }
+
+        async fn count_by_user(&self, user_id: &str) -> Result<u64> {
+            let profiles = self.profiles.lock().unwrap();
+            Ok(profiles.iter().filter(|p| p.user_id == user_id).count() as u64)
+        }
+
+        async fn search(&self, _query: &ProfileQuery) -> Result<Vec<Profile>> {
+            Ok(Vec::new())
+        }
+
+        async fn merge(
+            &self,
+            _target_id: &str,
+            _source_id: &str,
+            _strategy: &str,
+        ) -> Result<ProfileComparison> {
+            Ok(ProfileComparison {
+                added_facts: Vec::new(),
+                conflicting_facts: Vec::new(),
+                consistent_values: Vec::new(),
+            })
+        }
+    }
+
+    fn profile_memory(content: &str) -> Memory {
+        Memory::new(
+            "user_1",
+            MemoryType::Profile,
+            content,
+            MemorySource::Conversation,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_sync_from_memory_creates_and_updates_profile() {
+        let repository = Arc::new(MockProfileRepository::default());
+        let service = ProfileServiceImpl::new(repository.clone());
+
+        service
+            .sync_from_memory(&profile_memory("User prefers dark mode"))
+            .await
+            .unwrap();
+        service
+            .sync_from_memory(&profile_memory("Works at Acme"))
+            .await
+            .unwrap();
+
+        let profile = repository.get_by_user_id("user_1").await.unwrap().unwrap();
+        assert_eq!(profile.sync_count, 2);
+        assert_eq!(profile.organization.as_deref(), Some("Acme"));
+        assert!(profile.preferences.contains_key("dark mode"));
+    }
+
+    #[tokio::test]
+    async fn test_sync_ignores_other_memory_types() {
+        let repository = Arc::new(MockProfileRepository::default());
+        let service = ProfileServiceImpl::new(repository.clone());
+
+        let memory = Memory::new(
+            "user_1",
+            MemoryType::Semantic,
+            "User prefers dark mode",
+            MemorySource::Conversation,
+        );
+        service.sync_from_memory(&memory).await.unwrap();
+
+        assert!(repository.get_by_user_id("user_1").await.unwrap().is_none());
+    }
+
+    fn profile_with(user_id: &str, interests: &[&str], facts: &[&str]) -> Profile {
+        let mut profile = Profile::new(user_id);
+        for interest in interests {
+            profile.add_interest(interest);
+        }
+        for fact in facts {
+            profile.add_fact(fact, ProfileFactCategory::Personal, None, 0.9);
+        }
+        profile
+    }
+
+    #[tokio::test]
+    async fn test_merge_profiles_dedups_and_deletes_secondary() {
+        let repository = Arc::new(MockProfileRepository::default());
+        let service = ProfileServiceImpl::new(repository.clone());
+
+        let mut primary = profile_with("user_1", &["rust", "go"], &["Lives in Berlin"]);
+        primary.add_preference("theme", serde_json::json!("dark"), None);
+        let mut secondary = profile_with(
+            "user_1",
+            &["go", "python"],
+            &["Lives in Berlin", "Has a cat"],
+        );
+        secondary.add_preference("theme", serde_json::json!("light"), None);
+        secondary.add_preference("editor", serde_json::json!("vim"), None);
+        service.create(&primary).await.unwrap();
+        service.create(&secondary).await.unwrap();
+
+        let merged = service
+            .merge_profiles(&primary.id, &secondary.id)
+            .await
+            .unwrap();
+
+        assert_eq!(merged.interests, vec!["rust", "go", "python"]);
+        let facts: Vec<&str> = merged.facts.iter().map(|f| f.fact.as_str()).collect();
+        assert_eq!(facts, vec!["Lives in Berlin", "Has a cat"]);
+        assert_eq!(merged.preferences["theme"], serde_json::json!("dark"));
+        assert_eq!(merged.preferences["editor"], serde_json::json!("vim"));
+        assert_eq!(merged.version, primary.version + 1);
+        assert!(service.get_by_id(&secondary.id).await.unwrap().is_none());
+        assert_eq!(service.count_by_user("user_1").await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_merge_profiles_rejects_invalid_pairs() {
+        let repository = Arc::new(MockProfileRepository::default());
+        let service = ProfileServiceImpl::new(repository.clone());
+
+        let primary = Profile::new("user_1");
+        let mut other_tenant = Profile::new("user_1");
+        other_tenant.tenant_id = "tenant_2".to_string();
+        service.create(&primary).await.unwrap();
+        service.create(&other_tenant).await.unwrap();
+
+        assert!(matches!(
+            service.merge_profiles(&primary.id, &primary.id).await,
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            service.merge_profiles(&primary.id, "missing").await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            service.merge_profiles(&primary.id, &other_tenant.id).await,
+            Err(AppError::Forbidden(_))
+        ));
+        assert_eq!(service.count_by_user("user_1").await.unwrap(), 2);
+    }
+}
@@ -596,6 +596,10 @@ mod tests {
             Ok(0)
         }
 
+        async fn count_by_user(&self, _user_id: &str) -> Result<u64> {
+            Ok(0)
+        }
+
         async fn search(&self, _query: &crate::models::profile::ProfileQuery) -> Result<Vec<Profile>> {
             Ok(vec![])
         }
@@ -8,7 +8,7 @@ use std::sync::Arc;
 use crate::error::{AppError, Result};
 use crate::index::{IndexService, SearchOptions, SearchResult};
 use crate::models::turn::Turn;
-use crate::storage::repository::{Repository, TurnRepository};
+use crate::storage::repository::TurnStore;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProgressiveIndex {
@@ -24,11 +24,13 @@ pub struct ProgressiveIndex {
 #[async_trait]
 pub trait RetrievalService: Send + Sync {
     async fn list_recent(&self, session_id: &str, limit: u32) -> Result<Vec<ProgressiveIndex>>;
+    /// 语义检索，`min_score` 为 Some 时过滤掉相似度低于该值的结果
     async fn semantic_search(
         &self,
         session_id: &str,
         query: &str,
         limit: u32,
+        min_score: Option<f32>,
     ) -> Result<Vec<SearchResult>>;
     async fn hybrid_search(
         &self,
@@ -41,11 +43,11 @@ pub trait RetrievalService: Send + Sync {
 
 pub struct RetrievalServiceImpl {
     index_service: Box<dyn IndexService>,
-    turn_repository: Arc<TurnRepository>,
+    turn_repository: Arc<dyn TurnStore>,
 }
 
 impl RetrievalServiceImpl {
-    pub fn new(index_service: Box<dyn IndexService>, turn_repository: Arc<TurnRepository>) -> Self {
+    pub fn new(index_service: Box<dyn IndexService>, turn_repository: Arc<dyn TurnStore>) -> Self {
         Self {
             index_service,
             turn_repository,
@@ -78,11 +80,16 @@ impl RetrievalService for RetrievalServiceImpl {
         Ok(progressive_indices)
     }
 
+    #[tracing::instrument(
+        skip(self, query),
+        fields(service.name = "RetrievalService", session.id = session_id)
+    )]
     async fn semantic_search(
         &self,
         session_id: &str,
         query: &str,
         limit: u32,
+        min_score: Option<f32>,
     ) -> Result<Vec<SearchResult>> {
         self.index_service
             .search_indices(
@@ -94,7 +101,7 @@ impl RetrievalService for RetrievalServiceImpl {
                     use_semantic: true,
                     use_full_text: false,
                     use_hybrid: false,
-                    threshold: None,
+                    threshold: min_score,
                 },
             )
             .await
@@ -139,11 +146,12 @@ impl RetrievalService for RetrievalServiceImpl {
 
 pub fn create_retrieval_service(
     embedding_model: Box<dyn crate::index::EmbeddingModel>,
-    turn_repository: Arc<TurnRepository>,
+    turn_repository: Arc<dyn TurnStore>,
+    hnsw: Option<crate::index::HnswConfig>,
 ) -> Box<dyn RetrievalService> {
     use crate::index::{create_full_text_index, create_unified_index_service, create_vector_index};
 
-    let vector_index = create_vector_index(None, false);
+    let vector_index = create_vector_index(None, hnsw);
     let full_text_index = create_full_text_index(None, false);
     let index_service =
         create_unified_index_service(vector_index, full_text_index, embedding_model);
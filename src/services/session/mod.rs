@@ -8,8 +8,16 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::error::{AppError, Result};
-use crate::models::session::Session;
-use crate::storage::repository::{Repository, SessionRepository, TurnRepository};
+use crate::index::IndexService;
+use crate::models::memory_repository::MemoryRepository;
+use crate::models::session::{Session, SessionConfig, SessionWithStats};
+use crate::observability::event_bus::SESSION_CREATED;
+use crate::observability::{AppMetrics, EventBus, LogContext};
+use crate::services::lazy::LazyService;
+use crate::storage::repository::{SessionStore, TurnStore};
+
+/// 默认每页数量
+pub const DEFAULT_PAGE_SIZE: usize = 20;
 
 /// 分页参数
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -36,6 +44,29 @@ impl Pagination {
     pub fn is_valid(&self) -> bool {
         self.page > 0 && self.page_size > 0
     }
+
+    /// 默认分页参数（第 1 页，每页 20 条）
+    pub fn default_page_size() -> Self {
+        Self::new(1, DEFAULT_PAGE_SIZE)
+    }
+
+    /// 由查询参数构造分页，缺省字段取默认值
+    pub fn from_query(page: Option<usize>, page_size: Option<usize>) -> Self {
+        let defaults = Self::default_page_size();
+        Self::new(
+            page.unwrap_or(defaults.page),
+            page_size.unwrap_or(defaults.page_size),
+        )
+    }
+
+    /// 将页码限制为至少 1，每页数量限制在 `[min_page_size, max_page_size]` 内
+    pub fn clamp(self, min_page_size: usize, max_page_size: usize) -> Self {
+        let max_page_size = max_page_size.max(min_page_size);
+        Self {
+            page: self.page.max(1),
+            page_size: self.page_size.clamp(min_page_size, max_page_size),
+        }
+    }
 }
 
 /// 会话列表查询参数
@@ -57,17 +88,29 @@ pub trait SessionService: Send + Sync {
     /// 根据 ID 获取会话
     async fn get_by_id(&self, id: &str) -> Result<Option<Session>>;
 
+    /// 获取会话及其轮次数量和最后一轮时间（单次数据库往返）
+    async fn get_session_with_stats(&self, session_id: &str) -> Result<Option<SessionWithStats>>;
+
     /// 更新会话
     async fn update(&self, session: &Session) -> Result<Session>;
 
+    /// 仅更新会话配置
+    async fn update_config(&self, id: &str, config: SessionConfig) -> Result<Session>;
+
     /// 删除会话
     async fn delete(&self, id: &str) -> Result<bool>;
 
     /// 列出会话
     async fn list(&self, tenant_id: &str, query: SessionQuery) -> Result<Vec<Session>>;
 
-    /// 统计会话数量
-    async fn count(&self, tenant_id: &str) -> Result<u64>;
+    /// 统计会话数量，指定 `status` 时只统计该状态的会话
+    async fn count(&self, tenant_id: &str, status: Option<&str>) -> Result<u64>;
+
+    /// 统计最近 `active_within_secs` 秒内活跃的会话数量
+    async fn count_active_sessions(&self, tenant_id: &str, active_within_secs: u64) -> Result<u64>;
+
+    /// 列出存在会话的所有租户 ID
+    async fn list_tenant_ids(&self) -> Result<Vec<String>>;
 
     /// 归档会话
     async fn archive(&self, id: &str, reason: Option<String>) -> Result<Session>;
@@ -75,156 +118,473 @@ pub trait SessionService: Send + Sync {
     /// 恢复会话
     async fn restore(&self, id: &str, new_name: Option<String>) -> Result<Session>;
 
+    /// 合并会话
+    ///
+    /// 将 `secondary_id` 的轮次接在 `primary_id` 最大轮次之后重新编号并移入主会话，
+    /// 随后删除次会话。合并后的会话保留主会话的 ID 和元数据。
+    async fn merge_sessions(
+        &self,
+        primary_id: &str,
+        secondary_id: &str,
+        new_name: Option<String>,
+    ) -> Result<Session>;
+
     /// 验证会话访问权限
     async fn validate_access(&self, session_id: &str, user_id: &str) -> Result<bool>;
 }
 
 /// 会话服务实现
 pub struct SessionServiceImpl {
-    repository: Arc<SessionRepository>,
-    turn_repository: Arc<TurnRepository>,
+    repository: Arc<dyn SessionStore>,
+    turn_repository: Arc<dyn TurnStore>,
+    /// 记忆仓储（可选，配置后删除会话时级联删除其对话记忆）
+    memory_repository: Option<Arc<dyn MemoryRepository + Send + Sync>>,
+    /// 索引服务（可选，配置后删除、合并会话时同步维护其轮次的索引条目）
+    index_service: Option<LazyService<dyn IndexService>>,
+    /// 应用指标（可选，配置后维护 `sessions_active` / `sessions_archived`）
+    metrics: Option<Arc<AppMetrics>>,
+    /// 事件总线（可选，配置后创建会话时发布 `session:created`）
+    event_bus: Option<EventBus>,
 }
 
 impl SessionServiceImpl {
     /// 创建新的服务实例
-    pub fn new(repository: Arc<SessionRepository>, turn_repository: Arc<TurnRepository>) -> Self {
+    pub fn new(repository: Arc<dyn SessionStore>, turn_repository: Arc<dyn TurnStore>) -> Self {
         Self {
             repository,
             turn_repository,
+            memory_repository: None,
+            index_service: None,
+            metrics: None,
+            event_bus: None,
+        }
+    }
+
+    /// 设置记忆仓储
+    pub fn with_memory_repository(
+        mut self,
+        memory_repository: Arc<dyn MemoryRepository + Send + Sync>,
+    ) -> Self {
+        self.memory_repository = Some(memory_repository);
+        self
+    }
+
+    /// 设置索引服务
+    pub fn with_index_service(mut self, index_service: LazyService<dyn IndexService>) -> Self {
+        self.index_service = Some(index_service);
+        self
+    }
+
+    /// 设置应用指标
+    pub fn with_metrics(mut self, metrics: Arc<AppMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// 设置事件总线
+    pub fn with_event_bus(mut self, event_bus: EventBus) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// 已创建的索引服务（尚未创建时内存索引中没有条目，无需维护）
+    fn built_index(&self) -> Option<&dyn IndexService> {
+        self.index_service.as_ref()?.get_initialized()
+    }
+
+    /// 重建移入 `session_id` 的轮次（编号 `[first_turn, last_turn]`）的索引条目
+    ///
+    /// 索引条目记录了轮次所属的会话与编号，移动后需删除旧条目并按新位置写入；
+    /// 原本未被索引的轮次保持未索引。失败只记录警告，不影响合并结果。
+    async fn reindex_moved_turns(&self, session_id: &str, first_turn: u64, last_turn: u64) {
+        let Some(index) = self.built_index() else {
+            return;
+        };
+        let turns = match self
+            .turn_repository
+            .list_by_turn_range(session_id, first_turn, last_turn)
+            .await
+        {
+            Ok(turns) => turns,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load moved turns of session {}: {}",
+                    session_id,
+                    e
+                );
+                return;
+            }
+        };
+        for turn in &turns {
+            match index.delete_index(&turn.id).await {
+                Ok(true) => {
+                    if let Err(e) = index.index_turn(turn).await {
+                        tracing::warn!("Failed to reindex moved turn {}: {}", turn.id, e);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!("Failed to delete index entries for turn {}: {}", turn.id, e)
+                }
+            }
         }
     }
+
+    /// 按会话状态调整会话数量指标
+    fn record_session_metric(&self, status: &str, delta: isize) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_session(session_metric_label(status), delta);
+        }
+    }
+}
+
+/// 会话状态对应的指标标签
+fn session_metric_label(status: &str) -> &'static str {
+    if status == "Archived" {
+        "archived"
+    } else {
+        "active"
+    }
 }
 
 /// 注意：移除了 Default 实现，因为无法在没有数据库连接的情况下创建 Repository
-/// 测试时，请使用 SessionServiceImpl::new() 传入适当的 Arc<SessionRepository>
+/// 测试时，请使用 SessionServiceImpl::new() 传入内存仓储（见 `RepositorySet::in_memory`）
 
 #[async_trait]
 impl SessionService for SessionServiceImpl {
+    #[tracing::instrument(
+        skip(self),
+        fields(service.name = "SessionService", session.id = tracing::field::Empty)
+    )]
     async fn create(&self, tenant_id: &str, name: &str) -> Result<Session> {
-        // 检查同名 Session 是否已存在
-        let existing = self
-            .repository
-            .list_by_tenant(tenant_id, 10, 0)
-            .await
-            .map_err(|e| AppError::Database(e.to_string()))?;
+        let span = tracing::Span::current();
+        LogContext::new(tenant_id)
+            .run("session.create", async {
+                // 检查同名 Session 是否已存在
+                let existing = self
+                    .repository
+                    .list_by_tenant(tenant_id, 10, 0)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
 
-        if existing.iter().any(|s| s.name == name) {
-            return Err(AppError::Validation(
-                "Session with this name already exists".to_string(),
-            ));
-        }
+                if existing.iter().any(|s| s.name == name) {
+                    return Err(AppError::Validation(
+                        "Session with this name already exists".to_string(),
+                    ));
+                }
 
-        let session = Session::new(tenant_id, name);
-        self.repository
-            .create(&session)
+                let session = Session::new(tenant_id, name);
+                let created = self
+                    .repository
+                    .create(&session)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                span.record("session.id", created.id.as_str());
+                self.record_session_metric(&created.status, 1);
+                if let Some(event_bus) = &self.event_bus {
+                    event_bus.publish(
+                        SESSION_CREATED,
+                        &created.tenant_id,
+                        serde_json::json!({
+                            "id": created.id,
+                            "tenant_id": created.tenant_id,
+                            "name": created.name,
+                        }),
+                    );
+                }
+                Ok(created)
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
     async fn get_by_id(&self, id: &str) -> Result<Option<Session>> {
-        self.repository
-            .get_by_id(id)
+        LogContext::for_session(id)
+            .run("session.get_by_id", async {
+                let session = self
+                    .repository
+                    .get_by_id(id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                if let Some(session) = &session {
+                    LogContext::record_tenant(&session.tenant_id);
+                }
+                Ok(session)
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
-    async fn update(&self, session: &Session) -> Result<Session> {
-        self.repository
-            .update(&session.id, session)
+    async fn get_session_with_stats(&self, session_id: &str) -> Result<Option<SessionWithStats>> {
+        LogContext::for_session(session_id)
+            .run("session.get_session_with_stats", async {
+                self.repository
+                    .get_with_stats(session_id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))?
-            .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", session.id)))
     }
 
-    async fn delete(&self, id: &str) -> Result<bool> {
-        // 1. 验证 Session 存在
-        self.get_by_id(id)
-            .await?
-            .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
-
-        // 2. 删除所有关联的 Turn（级联删除，使用 while 循环处理大量数据）
-        const BATCH_SIZE: usize = 100;
-        let mut offset = 0usize;
-
-        loop {
-            let turns = self
-                .turn_repository
-                .list_by_session(id, BATCH_SIZE, offset)
-                .await
-                .map_err(|e| AppError::Database(e.to_string()))?;
+    async fn update(&self, session: &Session) -> Result<Session> {
+        LogContext::new(&session.tenant_id)
+            .with_session(&session.id)
+            .run("session.update", async {
+                self.repository
+                    .update(&session.id, session)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?
+                    .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", session.id)))
+            })
+            .await
+    }
 
-            if turns.is_empty() {
-                break;
-            }
+    async fn update_config(&self, id: &str, config: SessionConfig) -> Result<Session> {
+        LogContext::for_session(id)
+            .run("session.update_config", async {
+                let mut session = self
+                    .get_by_id(id)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
 
-            for turn in &turns {
-                self.turn_repository
-                    .delete(&turn.id)
+                self.repository
+                    .update_config(id, &config)
                     .await
                     .map_err(|e| AppError::Database(e.to_string()))?;
-            }
 
-            if turns.len() < BATCH_SIZE {
-                break;
-            }
+                session.config = config;
+                Ok(session)
+            })
+            .await
+    }
 
-            offset += turns.len();
-        }
+    async fn delete(&self, id: &str) -> Result<bool> {
+        LogContext::for_session(id)
+            .run("session.delete", async {
+                // 1. 验证 Session 存在
+                let session = self
+                    .get_by_id(id)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+                // 2. 级联删除该会话的轮次产生的记忆（需在删除轮次之前）
+                let mut deleted_memories = 0;
+                if let Some(memory_repository) = &self.memory_repository {
+                    deleted_memories = memory_repository
+                        .delete_by_conversation(id)
+                        .await
+                        .map_err(|e| AppError::Database(e.to_string()))?;
+                }
+
+                // 3. 级联删除所有关联的 Turn（单条语句，不分页）
+                let deleted_turns = self
+                    .turn_repository
+                    .bulk_delete_by_session(id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
 
-        // 3. 删除 Session
-        self.repository
-            .delete(id)
+                // 4. 删除这些轮次的索引条目（索引失败不影响删除结果）
+                let mut deleted_indices = 0;
+                if let Some(index) = self.built_index() {
+                    match index.delete_session_indices(id).await {
+                        Ok(count) => deleted_indices = count,
+                        Err(e) => tracing::warn!(
+                            "Failed to delete index entries for session {}: {}",
+                            id,
+                            e
+                        ),
+                    }
+                }
+
+                tracing::info!(
+                    "Deleted session {}: {} turns, {} memories, {} index entries",
+                    id,
+                    deleted_turns,
+                    deleted_memories,
+                    deleted_indices
+                );
+
+                // 5. 删除 Session
+                let deleted = self
+                    .repository
+                    .delete(id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                if deleted {
+                    self.record_session_metric(&session.status, -1);
+                }
+                Ok(deleted)
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
     async fn list(&self, tenant_id: &str, query: SessionQuery) -> Result<Vec<Session>> {
-        let offset = query.pagination.offset();
-        let limit = query.pagination.page_size;
-        self.repository
-            .list_by_tenant(tenant_id, limit, offset)
+        LogContext::new(tenant_id)
+            .run("session.list", async {
+                let offset = query.pagination.offset();
+                let limit = query.pagination.page_size;
+                self.repository
+                    .list_by_tenant_and_status(tenant_id, query.status.as_deref(), limit, offset)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
-    async fn count(&self, tenant_id: &str) -> Result<u64> {
-        self.repository
-            .count_by_tenant(tenant_id)
+    async fn count(&self, tenant_id: &str, status: Option<&str>) -> Result<u64> {
+        LogContext::new(tenant_id)
+            .run("session.count", async {
+                self.repository
+                    .count_by_tenant_and_status(tenant_id, status)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
-    async fn archive(&self, id: &str, _reason: Option<String>) -> Result<Session> {
-        let mut session = self
-            .get_by_id(id)
-            .await?
-            .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+    async fn count_active_sessions(&self, tenant_id: &str, active_within_secs: u64) -> Result<u64> {
+        LogContext::new(tenant_id)
+            .run("session.count_active_sessions", async {
+                self.repository
+                    .count_active(tenant_id, active_within_secs)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
+            .await
+    }
 
-        if session.status == "Archived" {
-            return Ok(session);
-        }
+    async fn list_tenant_ids(&self) -> Result<Vec<String>> {
+        LogContext::default()
+            .run("session.list_tenant_ids", async {
+                self.repository
+                    .list_tenant_ids()
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
+            .await
+    }
 
-        session.status = "Archived".to_string();
-        self.update(&session).await
+    async fn archive(&self, id: &str, _reason: Option<String>) -> Result<Session> {
+        LogContext::for_session(id)
+            .run("session.archive", async {
+                let mut session = self
+                    .get_by_id(id)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+                if session.status == "Archived" {
+                    return Ok(session);
+                }
+
+                let previous_status =
+                    std::mem::replace(&mut session.status, "Archived".to_string());
+                let archived = self.update(&session).await?;
+                self.record_session_metric(&previous_status, -1);
+                self.record_session_metric(&archived.status, 1);
+                Ok(archived)
+            })
+            .await
     }
 
     async fn restore(&self, id: &str, new_name: Option<String>) -> Result<Session> {
-        let mut session = self
-            .get_by_id(id)
-            .await?
-            .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
-
-        if session.status != "Archived" {
-            return Err(AppError::Validation(
-                "Only archived sessions can be restored".to_string(),
-            ));
-        }
+        LogContext::for_session(id)
+            .run("session.restore", async {
+                let mut session = self
+                    .get_by_id(id)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", id)))?;
+
+                if session.status != "Archived" {
+                    return Err(AppError::Validation(
+                        "Only archived sessions can be restored".to_string(),
+                    ));
+                }
+
+                session.status = "Active".to_string();
+                if let Some(name) = new_name {
+                    session.name = name;
+                }
+                let restored = self.update(&session).await?;
+                self.record_session_metric("Archived", -1);
+                self.record_session_metric(&restored.status, 1);
+                Ok(restored)
+            })
+            .await
+    }
 
-        session.status = "Active".to_string();
-        if let Some(name) = new_name {
-            session.name = name;
-        }
-        self.update(&session).await
+    async fn merge_sessions(
+        &self,
+        primary_id: &str,
+        secondary_id: &str,
+        new_name: Option<String>,
+    ) -> Result<Session> {
+        LogContext::for_session(primary_id)
+            .run("session.merge_sessions", async {
+                let primary = self.get_by_id(primary_id).await?.ok_or_else(|| {
+                    AppError::NotFound(format!("Session not found: {}", primary_id))
+                })?;
+                let secondary = self.get_by_id(secondary_id).await?.ok_or_else(|| {
+                    AppError::NotFound(format!("Session not found: {}", secondary_id))
+                })?;
+                validate_merge(&primary, &secondary)?;
+
+                // 1. 次会话轮次接在主会话最大轮次之后重新编号（单个事务）
+                let max_turn = self
+                    .turn_repository
+                    .get_max_turn_number(primary_id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                let moved_turns = self
+                    .turn_repository
+                    .move_turns_to_session(secondary_id, primary_id, max_turn + 1)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                if moved_turns > 0 {
+                    self.reindex_moved_turns(primary_id, max_turn + 1, max_turn + moved_turns)
+                        .await;
+                }
+
+                // 2. 在数据库端累加主会话统计，避免写回整行覆盖并发修改
+                self.repository
+                    .adjust_turn_count(primary_id, moved_turns as i64)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                self.repository
+                    .add_stats(
+                        primary_id,
+                        secondary.stats.total_tokens,
+                        secondary.stats.storage_size,
+                        secondary.last_active_at,
+                    )
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                if let Some(name) = new_name {
+                    self.repository
+                        .update_name(primary_id, &name)
+                        .await
+                        .map_err(|e| AppError::Database(e.to_string()))?;
+                }
+                let merged = self.get_by_id(primary_id).await?.ok_or_else(|| {
+                    AppError::NotFound(format!("Session not found: {}", primary_id))
+                })?;
+
+                // 3. 删除次会话（轮次已全部移出）
+                let deleted = self
+                    .repository
+                    .delete(secondary_id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                if deleted {
+                    self.record_session_metric(&secondary.status, -1);
+                }
+
+                tracing::info!(
+                    "Merged session {} into {}: {} turns moved",
+                    secondary_id,
+                    primary_id,
+                    moved_turns
+                );
+                Ok(merged)
+            })
+            .await
     }
 
     async fn validate_access(&self, session_id: &str, _user_id: &str) -> Result<bool> {
@@ -232,6 +592,31 @@ impl SessionService for SessionServiceImpl {
     }
 }
 
+/// 检查两个会话能否合并：不能是同一会话、均未归档且属于同一租户
+fn validate_merge(primary: &Session, secondary: &Session) -> Result<()> {
+    if primary.id == secondary.id {
+        return Err(AppError::Validation(
+            "Cannot merge a session into itself".to_string(),
+        ));
+    }
+    if let Some(archived) = [primary, secondary]
+        .into_iter()
+        .find(|s| s.status == "Archived")
+    {
+        return Err(AppError::Validation(format!(
+            "Archived session cannot be merged: {}",
+            archived.id
+        )));
+    }
+    if primary.tenant_id != secondary.tenant_id {
+        return Err(AppError::Forbidden(
+            "Sessions belong to different tenants".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// 会话归档信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionArchiveInfo {
@@ -243,8 +628,8 @@ pub struct SessionArchiveInfo {
 
 /// 创建会话服务
 pub fn create_session_service(
-    repository: Arc<SessionRepository>,
-    turn_repository: Arc<TurnRepository>,
+    repository: Arc<dyn SessionStore>,
+    turn_repository: Arc<dyn TurnStore>,
 ) -> Box<dyn SessionService> {
     Box::new(SessionServiceImpl::new(repository, turn_repository))
 }
@@ -278,6 +663,26 @@ mod tests {
         assert!(pagination.is_valid());
     }
 
+    #[test]
+    fn test_pagination_clamp() {
+        let pagination = Pagination::new(0, 0).clamp(1, 200);
+        assert_eq!(pagination.page, 1);
+        assert_eq!(pagination.page_size, 1);
+        assert!(pagination.is_valid());
+
+        let pagination = Pagination::new(3, 5000).clamp(1, 200);
+        assert_eq!(pagination.page, 3);
+        assert_eq!(pagination.page_size, 200);
+
+        let pagination = Pagination::from_query(None, None);
+        assert_eq!(pagination.page, 1);
+        assert_eq!(pagination.page_size, DEFAULT_PAGE_SIZE);
+        assert_eq!(
+            pagination.page_size,
+            Pagination::default_page_size().page_size
+        );
+    }
+
     #[tokio::test]
     async fn test_session_create() {
         let session = Session::new("tenant_1", "Test Session");
@@ -285,4 +690,271 @@ mod tests {
         assert_eq!(session.name, "Test Session");
         assert_eq!(session.status, "Active");
     }
+
+    #[tokio::test]
+    async fn test_count_applies_status_filter() {
+        use crate::storage::factory::RepositorySet;
+
+        let repos = RepositorySet::in_memory();
+        for (tenant_id, status) in [
+            ("tenant_1", "Active"),
+            ("tenant_1", "Archived"),
+            ("tenant_1", "Archived"),
+            ("tenant_2", "Archived"),
+        ] {
+            let mut session = Session::new(tenant_id, "session");
+            session.status = status.to_string();
+            repos.sessions.create(&session).await.unwrap();
+        }
+        let service = SessionServiceImpl::new(repos.sessions.clone(), repos.turns.clone());
+
+        assert_eq!(service.count("tenant_1", None).await.unwrap(), 3);
+        assert_eq!(
+            service.count("tenant_1", Some("archived")).await.unwrap(),
+            2
+        );
+        let query = SessionQuery {
+            pagination: Pagination::new(1, 1),
+            status: Some("archived".to_string()),
+        };
+        assert_eq!(service.list("tenant_1", query).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_cascades_turns_memories_and_indices() {
+        use crate::index::embedding::SimpleEmbeddingModel;
+        use crate::index::{UnifiedIndexService, create_full_text_index, create_vector_index};
+        use crate::models::memory::{Memory, MemorySource, MemoryType};
+        use crate::models::turn::Turn;
+        use crate::storage::factory::RepositorySet;
+
+        let repos = RepositorySet::in_memory();
+        let index: LazyService<dyn IndexService> = LazyService::ready(
+            "index service",
+            Box::new(UnifiedIndexService::new(
+                create_vector_index(None, None),
+                create_full_text_index(None, false),
+                Box::new(SimpleEmbeddingModel::new(384)),
+            )),
+        );
+        let service = SessionServiceImpl::new(repos.sessions.clone(), repos.turns.clone())
+            .with_memory_repository(repos.memories.clone())
+            .with_index_service(index.clone());
+
+        let session = service.create("tenant_1", "chat").await.unwrap();
+        let other = service.create("tenant_1", "other").await.unwrap();
+        for (session_id, turn_number) in [(&session.id, 1), (&session.id, 2), (&other.id, 1)] {
+            let turn = repos
+                .turns
+                .create(&Turn::new(session_id, turn_number, "hello world"))
+                .await
+                .unwrap();
+            index.get().await.unwrap().index_turn(&turn).await.unwrap();
+            let mut memory = Memory::new(
+                "user_1",
+                MemoryType::Episodic,
+                "hello world",
+                MemorySource::Conversation,
+            );
+            memory.source_id = Some(turn.id.clone());
+            repos.memories.create(&memory).await.unwrap();
+        }
+
+        assert!(service.delete(&session.id).await.unwrap());
+
+        let index = index.get().await.unwrap();
+        assert_eq!(repos.turns.count_by_session(&session.id).await.unwrap(), 0);
+        assert!(
+            index
+                .list_indices(&session.id, 10, 0)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(repos.memories.count().await.unwrap(), 1);
+        assert_eq!(repos.turns.count_by_session(&other.id).await.unwrap(), 1);
+        assert_eq!(index.list_indices(&other.id, 10, 0).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_merge_reindexes_moved_turns() {
+        use crate::index::embedding::SimpleEmbeddingModel;
+        use crate::index::{UnifiedIndexService, create_full_text_index, create_vector_index};
+        use crate::models::turn::Turn;
+        use crate::storage::factory::RepositorySet;
+
+        let repos = RepositorySet::in_memory();
+        let index: LazyService<dyn IndexService> = LazyService::ready(
+            "index service",
+            Box::new(UnifiedIndexService::new(
+                create_vector_index(None, None),
+                create_full_text_index(None, false),
+                Box::new(SimpleEmbeddingModel::new(384)),
+            )),
+        );
+        let service = SessionServiceImpl::new(repos.sessions.clone(), repos.turns.clone())
+            .with_index_service(index.clone());
+
+        let primary = service.create("tenant_1", "primary").await.unwrap();
+        let secondary = service.create("tenant_1", "secondary").await.unwrap();
+        let kept = repos
+            .turns
+            .create(&Turn::new(&primary.id, 1, "primary turn"))
+            .await
+            .unwrap();
+        let moved = repos
+            .turns
+            .create(&Turn::new(&secondary.id, 1, "secondary turn"))
+            .await
+            .unwrap();
+        let unindexed = repos
+            .turns
+            .create(&Turn::new(&secondary.id, 2, "never indexed"))
+            .await
+            .unwrap();
+        let index_service = index.get().await.unwrap();
+        index_service.index_turn(&kept).await.unwrap();
+        index_service.index_turn(&moved).await.unwrap();
+
+        service
+            .merge_sessions(&primary.id, &secondary.id, None)
+            .await
+            .unwrap();
+
+        assert!(
+            index_service
+                .list_indices(&secondary.id, 10, 0)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let records = index_service
+            .list_indices(&primary.id, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        let moved_record = records
+            .iter()
+            .find(|record| record.turn_id == moved.id)
+            .unwrap();
+        assert_eq!(moved_record.session_id, primary.id);
+        assert_eq!(moved_record.turn_number, 2);
+        assert!(records.iter().all(|record| record.turn_id != unindexed.id));
+    }
+
+    #[tokio::test]
+    async fn test_merge_counts_moved_turns_and_renames() {
+        use crate::models::turn::Turn;
+        use crate::storage::factory::RepositorySet;
+
+        let repos = RepositorySet::in_memory();
+        let service = SessionServiceImpl::new(repos.sessions.clone(), repos.turns.clone());
+        let primary = service.create("tenant_1", "primary").await.unwrap();
+        let mut secondary = service.create("tenant_1", "secondary").await.unwrap();
+        secondary.stats.total_tokens = 40;
+        secondary.stats.storage_size = 512;
+        repos
+            .sessions
+            .update(&secondary.id, &secondary)
+            .await
+            .unwrap();
+        // 次会话的 total_turns 为 0，与实际轮次数不一致
+        for turn_number in 1..=2 {
+            repos
+                .turns
+                .create(&Turn::new(&secondary.id, turn_number, "hello"))
+                .await
+                .unwrap();
+        }
+
+        let merged = service
+            .merge_sessions(&primary.id, &secondary.id, Some("merged".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(merged.stats.total_turns, 2);
+        assert_eq!(merged.stats.total_tokens, 40);
+        assert_eq!(merged.stats.storage_size, 512);
+        assert_eq!(merged.name, "merged");
+        assert_eq!(merged.status, primary.status);
+        assert!(service.get_by_id(&secondary.id).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_validate_merge() {
+        let primary = Session::new("tenant_1", "Primary");
+        let secondary = Session::new("tenant_1", "Secondary");
+        assert!(validate_merge(&primary, &secondary).is_ok());
+        assert!(validate_merge(&primary, &primary).is_err());
+
+        let mut archived = secondary.clone();
+        archived.status = "Archived".to_string();
+        assert!(matches!(
+            validate_merge(&primary, &archived),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            validate_merge(&archived, &primary),
+            Err(AppError::Validation(_))
+        ));
+
+        let other_tenant = Session::new("tenant_2", "Other");
+        assert!(matches!(
+            validate_merge(&primary, &other_tenant),
+            Err(AppError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_session_metric_lifecycle() {
+        use crate::storage::factory::RepositorySet;
+
+        enum Step {
+            Create,
+            Archive,
+            Restore,
+            Delete,
+        }
+
+        let repos = RepositorySet::in_memory();
+        let metrics = Arc::new(AppMetrics::default());
+        let service = SessionServiceImpl::new(repos.sessions.clone(), repos.turns.clone())
+            .with_metrics(metrics.clone());
+
+        // (操作, 预期活跃数, 预期归档数)
+        let steps = [
+            (Step::Create, 1, 0),
+            (Step::Create, 2, 0),
+            (Step::Archive, 1, 1),
+            (Step::Restore, 2, 0),
+            (Step::Archive, 1, 1),
+            (Step::Delete, 1, 0),
+            (Step::Delete, 0, 0),
+        ];
+        let mut ids: Vec<String> = Vec::new();
+        for (step, active, archived) in steps {
+            match step {
+                Step::Create => {
+                    let name = format!("chat {}", ids.len());
+                    ids.push(service.create("tenant_1", &name).await.unwrap().id);
+                }
+                Step::Archive => {
+                    service.archive(&ids[0], None).await.unwrap();
+                }
+                Step::Restore => {
+                    service.restore(&ids[0], None).await.unwrap();
+                }
+                Step::Delete => assert!(service.delete(&ids.remove(0)).await.unwrap()),
+            }
+            let snapshot = metrics.to_snapshot();
+            assert_eq!(
+                (snapshot.sessions_active, snapshot.sessions_archived),
+                (active, archived)
+            );
+        }
+
+        let output = metrics.gather();
+        assert!(output.contains("sessions_active 0"));
+        assert!(output.contains("sessions_archived 0"));
+    }
 }
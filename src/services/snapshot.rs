@@ -0,0 +1,178 @@
+//! 会话快照服务
+//!
+//! 在破坏性操作（如“总结并清空”）前为会话建立不可变快照，并支持将会话恢复到快照时的状态。
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::error::{AppError, Result};
+use crate::models::snapshot::SessionSnapshot;
+use crate::models::turn::Turn;
+use crate::observability::LogContext;
+use crate::storage::repository::TurnStore;
+use crate::storage::surrealdb::SurrealPool;
+
+/// 快照恢复结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotRestoreResult {
+    /// 快照 ID
+    pub snapshot_id: String,
+    /// 会话 ID
+    pub session_id: String,
+    /// 重新写入的快照轮次数量
+    pub turns_restored: usize,
+    /// 删除的快照点之后创建的轮次数量
+    pub turns_discarded: usize,
+}
+
+/// 会话快照服务 trait
+#[async_trait]
+pub trait SessionSnapshotService: Send + Sync {
+    /// 为会话当前的全部轮次创建快照
+    async fn create_snapshot(&self, session_id: &str) -> Result<SessionSnapshot>;
+
+    /// 获取会话的指定快照
+    async fn get_snapshot(
+        &self,
+        session_id: &str,
+        snapshot_id: &str,
+    ) -> Result<Option<SessionSnapshot>>;
+
+    /// 将会话恢复到快照时的状态
+    async fn restore_snapshot(
+        &self,
+        session_id: &str,
+        snapshot_id: &str,
+    ) -> Result<SnapshotRestoreResult>;
+}
+
+/// 会话快照服务实现
+pub struct SessionSnapshotServiceImpl {
+    pool: SurrealPool,
+    turn_repository: Arc<dyn TurnStore>,
+}
+
+impl SessionSnapshotServiceImpl {
+    /// 创建新的服务实例
+    pub fn new(pool: SurrealPool, turn_repository: Arc<dyn TurnStore>) -> Self {
+        Self {
+            pool,
+            turn_repository,
+        }
+    }
+
+    /// 获取会话的全部轮次（按 turn_number 升序）
+    async fn session_turns(&self, session_id: &str) -> Result<Vec<Turn>> {
+        let total = self.turn_repository.count_by_session(session_id).await?;
+        self.turn_repository
+            .list_by_session(session_id, total as usize, 0)
+            .await
+    }
+}
+
+/// 快照点之后创建的轮次数量
+fn count_turns_after(turns: &[Turn], snapshot_point: u64) -> usize {
+    turns
+        .iter()
+        .filter(|t| t.turn_number > snapshot_point)
+        .count()
+}
+
+#[async_trait]
+impl SessionSnapshotService for SessionSnapshotServiceImpl {
+    async fn create_snapshot(&self, session_id: &str) -> Result<SessionSnapshot> {
+        LogContext::for_session(session_id)
+            .run("snapshot.create", async {
+                let turns = self.session_turns(session_id).await?;
+                let snapshot = SessionSnapshot::capture(session_id, &turns)?;
+
+                self.pool
+                    .inner()
+                    .await
+                    .query("CREATE type::thing('session_snapshot', $id) CONTENT $snapshot")
+                    .bind(("id", snapshot.snapshot_id.clone()))
+                    .bind(("snapshot", serde_json::to_value(&snapshot)?))
+                    .await?
+                    .check()?;
+
+                Ok(snapshot)
+            })
+            .await
+    }
+
+    async fn get_snapshot(
+        &self,
+        session_id: &str,
+        snapshot_id: &str,
+    ) -> Result<Option<SessionSnapshot>> {
+        let mut response = self
+            .pool
+            .inner()
+            .await
+            .query(
+                "SELECT * OMIT id FROM session_snapshot \
+                 WHERE snapshot_id = $id AND session_id = $sid LIMIT 1",
+            )
+            .bind(("id", snapshot_id.to_string()))
+            .bind(("sid", session_id.to_string()))
+            .await?;
+        let results: Vec<serde_json::Value> = response.take(0)?;
+
+        results
+            .into_iter()
+            .next()
+            .map(|json| {
+                serde_json::from_value(json).map_err(|e| {
+                    AppError::Database(format!("Failed to deserialize snapshot: {}", e))
+                })
+            })
+            .transpose()
+    }
+
+    async fn restore_snapshot(
+        &self,
+        session_id: &str,
+        snapshot_id: &str,
+    ) -> Result<SnapshotRestoreResult> {
+        LogContext::for_session(session_id)
+            .run("snapshot.restore", async {
+                let snapshot = self
+                    .get_snapshot(session_id, snapshot_id)
+                    .await?
+                    .ok_or_else(|| {
+                        AppError::NotFound(format!("Snapshot not found: {}", snapshot_id))
+                    })?;
+                let turns = snapshot.turns()?;
+                let snapshot_point = snapshot.snapshot_point()?;
+
+                let current = self.session_turns(session_id).await?;
+                self.turn_repository
+                    .restore_session_turns(session_id, snapshot_point, &turns)
+                    .await?;
+
+                Ok(SnapshotRestoreResult {
+                    snapshot_id: snapshot.snapshot_id,
+                    session_id: session_id.to_string(),
+                    turns_restored: turns.len(),
+                    turns_discarded: count_turns_after(&current, snapshot_point),
+                })
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_turns_after() {
+        let turns: Vec<Turn> = (1..=5)
+            .map(|n| Turn::new("session_1", n, "content"))
+            .collect();
+        assert_eq!(count_turns_after(&turns, 3), 2);
+        assert_eq!(count_turns_after(&turns, 5), 0);
+        assert_eq!(count_turns_after(&turns, 0), 5);
+    }
+}
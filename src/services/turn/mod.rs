@@ -4,11 +4,24 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::error::{AppError, Result};
+use crate::index::IndexService;
 use crate::models::turn::{MessageType, Turn, TurnMetadata};
-use crate::storage::repository::{Repository, SessionRepository, TurnRepository};
+use crate::observability::event_bus::TURN_CREATED;
+use crate::observability::{AppMetrics, EventBus, LogContext};
+use crate::services::compressor::ContextCompressor;
+use crate::services::lazy::LazyService;
+use crate::services::memory_builder::topic_terms;
+use crate::storage::repository::{SessionStore, TurnStore};
+
+/// 批量删除轮次时每批删除的数量
+const DELETE_BATCH_SIZE: usize = 100;
+
+/// 构建上下文窗口时最多考虑的最近轮次数
+pub const CONTEXT_WINDOW_MAX_TURNS: usize = 1000;
 
 /// 批量创建结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +50,55 @@ pub struct TurnGroup {
     pub turn_ids: Vec<String>,
 }
 
+impl TurnGroup {
+    /// 将分组展开为对话对，`turns` 为按轮次 ID 索引的轮次
+    ///
+    /// 每个用户轮次开启一个对话对；助手轮次作为最近一次提问的回复，首个回复之后的
+    /// 回复依次计入 `additional_assistant_turns`。分组中的系统轮次附加到其后的第一个
+    /// 对话对。没有用户轮次的分组（纯助手或纯系统分组）不产生对话对。
+    pub fn into_conversation_pairs(self, turns: &HashMap<String, Turn>) -> Vec<ConversationPair> {
+        let mut pairs: Vec<ConversationPair> = Vec::new();
+        let mut pending_system = None;
+
+        for turn in self.turn_ids.iter().filter_map(|id| turns.get(id)) {
+            match turn.metadata.message_type {
+                MessageType::User => pairs.push(ConversationPair {
+                    user_turn: turn.clone(),
+                    assistant_turn: None,
+                    additional_assistant_turns: Vec::new(),
+                    system_turn: pending_system.take(),
+                }),
+                MessageType::Assistant => {
+                    if let Some(pair) = pairs.last_mut() {
+                        if pair.assistant_turn.is_none() {
+                            pair.assistant_turn = Some(turn.clone());
+                        } else {
+                            pair.additional_assistant_turns.push(turn.clone());
+                        }
+                    }
+                }
+                MessageType::System => pending_system = Some(turn.clone()),
+            }
+        }
+
+        pairs
+    }
+}
+
+/// 对话对（一次用户提问及其回复，可直接组装为 LLM 对话输入）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationPair {
+    /// 用户轮次
+    pub user_turn: Turn,
+    /// 助手回复（尚未回复时为空）
+    pub assistant_turn: Option<Turn>,
+    /// 同一提问的其余助手回复（按 turn_number 升序）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_assistant_turns: Vec<Turn>,
+    /// 提问前生效的系统消息
+    pub system_turn: Option<Turn>,
+}
+
 /// 轮次分组类型
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TurnGroupType {
@@ -62,6 +124,148 @@ pub struct TurnQuery {
     pub message_type: Option<String>,
 }
 
+/// 会话最近轮次窗口（按 turn_number 升序，可直接作为 LLM 对话输入）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationWindow {
+    /// 窗口内的轮次
+    pub turns: Vec<Turn>,
+    /// 会话轮次总数
+    pub total_turns: u64,
+    /// 窗口起始轮次编号（会话为空时为 0）
+    pub window_start_turn: u64,
+}
+
+impl ConversationWindow {
+    /// 由按 turn_number 降序排列的最近轮次构建窗口
+    fn from_recent(mut recent: Vec<Turn>, total_turns: u64, include_system: bool) -> Self {
+        recent.reverse();
+        let window_start_turn = recent.first().map(|t| t.turn_number).unwrap_or(0);
+        if !include_system {
+            recent.retain(|t| t.metadata.message_type != MessageType::System);
+        }
+
+        Self {
+            turns: recent,
+            total_turns,
+            window_start_turn,
+        }
+    }
+}
+
+/// 上下文窗口超出 token 预算时的裁剪策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// 从最新轮次开始累加，丢弃放不下的更早轮次
+    #[default]
+    Oldest,
+    /// 始终优先保留最新轮次，其余按与最新轮次的词项重合度挑选，丢弃相关度最低的轮次
+    LeastRelevant,
+}
+
+/// 压缩会话早期轮次的结果
+#[derive(Debug, Clone)]
+pub struct CompressedTurns {
+    /// 替代原轮次的合成系统轮次
+    pub turn: Turn,
+    /// 被压缩的轮次数
+    pub turns_compressed: usize,
+    /// 删除的原轮次数
+    pub deleted: u64,
+}
+
+/// 符合 token 预算的上下文窗口（按 turn_number 升序）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextWindow {
+    /// 窗口内的轮次
+    pub turns: Vec<Turn>,
+    /// 窗口内轮次的估算 token 总数
+    pub tokens_used: u64,
+    /// 未放入窗口的轮次数
+    pub turns_truncated: u64,
+}
+
+impl ContextWindow {
+    /// 由按 turn_number 降序排列的最近轮次挑选不超过 `max_tokens` 的轮次
+    fn fit(
+        recent: Vec<Turn>,
+        total_turns: u64,
+        max_tokens: u64,
+        strategy: TruncationStrategy,
+    ) -> Self {
+        let mut tokens_used = 0;
+        let mut turns = Vec::new();
+
+        match strategy {
+            TruncationStrategy::Oldest => {
+                for turn in recent {
+                    let tokens = approximate_tokens(&turn.raw_content);
+                    if tokens_used + tokens > max_tokens {
+                        break;
+                    }
+                    tokens_used += tokens;
+                    turns.push(turn);
+                }
+            }
+            TruncationStrategy::LeastRelevant => {
+                for turn in rank_by_relevance(recent) {
+                    let tokens = approximate_tokens(&turn.raw_content);
+                    if tokens_used + tokens <= max_tokens {
+                        tokens_used += tokens;
+                        turns.push(turn);
+                    }
+                }
+            }
+        }
+
+        turns.sort_by_key(|t| t.turn_number);
+        Self {
+            turns_truncated: total_turns.saturating_sub(turns.len() as u64),
+            turns,
+            tokens_used,
+        }
+    }
+}
+
+/// 估算文本的 token 数：`ceil(字符数 / 4)`
+pub fn approximate_tokens(text: &str) -> u64 {
+    (text.chars().count() as u64).div_ceil(4)
+}
+
+/// 将按 turn_number 降序排列的轮次按相关度排序
+///
+/// 最新轮次排在首位，其余轮次按与最新轮次词项集合的 Jaccard 相似度降序排列，
+/// 相似度相同时较新的轮次在前。
+fn rank_by_relevance(mut recent: Vec<Turn>) -> Vec<Turn> {
+    if recent.is_empty() {
+        return recent;
+    }
+
+    let latest = recent.remove(0);
+    let latest_terms: HashSet<String> = topic_terms(&latest.raw_content).into_iter().collect();
+    let mut scored: Vec<(f32, Turn)> = recent
+        .into_iter()
+        .map(|turn| {
+            let terms: HashSet<String> = topic_terms(&turn.raw_content).into_iter().collect();
+            let union = terms.union(&latest_terms).count();
+            let score = if union == 0 {
+                0.0
+            } else {
+                terms.intersection(&latest_terms).count() as f32 / union as f32
+            };
+            (score, turn)
+        })
+        .collect();
+    scored.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| b.1.turn_number.cmp(&a.1.turn_number))
+    });
+
+    std::iter::once(latest)
+        .chain(scored.into_iter().map(|(_, turn)| turn))
+        .collect()
+}
+
 /// 轮次服务 trait
 #[async_trait]
 pub trait TurnService: Send + Sync {
@@ -76,18 +280,53 @@ pub trait TurnService: Send + Sync {
     /// 根据 ID 获取轮次
     async fn get_by_id(&self, id: &str) -> Result<Option<Turn>>;
 
+    /// 批量获取轮次，结果与输入顺序一致，不存在的 ID 对应 `None`
+    async fn get_by_ids(&self, ids: &[&str]) -> Result<Vec<Option<Turn>>>;
+
+    /// 根据会话内的轮次编号获取轮次
+    async fn get_by_turn_number(&self, session_id: &str, turn_number: u64) -> Result<Option<Turn>>;
+
     /// 更新轮次
     async fn update(&self, turn: &Turn) -> Result<Turn>;
 
     /// 删除轮次
     async fn delete(&self, id: &str) -> Result<bool>;
 
+    /// 删除会话中轮次编号小于 `before_turn` 的全部轮次（每批 100 条），返回删除数量
+    async fn delete_turns_before(&self, session_id: &str, before_turn: u64) -> Result<usize>;
+
+    /// 将会话中轮次编号小于 `before_turn` 的轮次压缩为一个合成的系统轮次
+    ///
+    /// 合成轮次替换原轮次的索引条目，并同步调整会话的轮次数。
+    async fn compress_turns_before(
+        &self,
+        session_id: &str,
+        before_turn: u64,
+    ) -> Result<CompressedTurns>;
+
+    /// 按轮次编号范围获取会话的轮次（闭区间，按轮次编号升序）
+    ///
+    /// `start_turn` 不能大于 `end_turn`，且两者都不能超过会话当前的最大轮次编号。
+    async fn get_turns_in_range(
+        &self,
+        session_id: &str,
+        start_turn: u64,
+        end_turn: u64,
+    ) -> Result<Vec<Turn>>;
+
     /// 列出会话的所有轮次
     async fn list_by_session(&self, session_id: &str, query: TurnQuery) -> Result<Vec<Turn>>;
 
     /// 统计会话的轮次数量
     async fn count_by_session(&self, session_id: &str) -> Result<u64>;
 
+    /// 统计会话中 `message_type` 类型（未指定时为全部）的轮次数量
+    async fn count_by_session_filtered(
+        &self,
+        session_id: &str,
+        message_type: Option<&str>,
+    ) -> Result<u64>;
+
     /// 获取下一个轮次编号
     async fn get_next_turn_number(&self, session_id: &str) -> Result<u64>;
 
@@ -100,116 +339,440 @@ pub trait TurnService: Send + Sync {
 
     /// 识别轮次分组
     async fn identify_turn_groups(&self, session_id: &str) -> Result<Vec<TurnGroup>>;
+
+    /// 获取会话的对话对列表（按轮次顺序）
+    async fn get_conversation_pairs(&self, session_id: &str) -> Result<Vec<ConversationPair>>;
+
+    /// 获取会话最近 `window_size` 个轮次组成的对话窗口
+    ///
+    /// `include_system` 为 false 时过滤掉系统消息。
+    async fn get_conversation_window(
+        &self,
+        session_id: &str,
+        window_size: u32,
+        include_system: bool,
+    ) -> Result<ConversationWindow>;
+
+    /// 获取符合 token 预算的上下文窗口
+    ///
+    /// 从最近 `CONTEXT_WINDOW_MAX_TURNS` 个轮次中按 `strategy` 挑选，
+    /// token 数按 `ceil(字符数 / 4)` 估算。
+    async fn get_context_window(
+        &self,
+        session_id: &str,
+        max_tokens: u32,
+        strategy: TruncationStrategy,
+    ) -> Result<ContextWindow>;
 }
 
 /// 轮次服务实现
 pub struct TurnServiceImpl {
-    repository: Arc<TurnRepository>,
-    session_repository: Arc<SessionRepository>,
+    repository: Arc<dyn TurnStore>,
+    session_repository: Arc<dyn SessionStore>,
+    index_service: Option<LazyService<dyn IndexService>>,
+    metrics: Option<Arc<AppMetrics>>,
+    event_bus: Option<EventBus>,
 }
 
 impl TurnServiceImpl {
     /// 创建新的服务实例
-    pub fn new(
-        repository: Arc<TurnRepository>,
-        session_repository: Arc<SessionRepository>,
-    ) -> Self {
+    pub fn new(repository: Arc<dyn TurnStore>, session_repository: Arc<dyn SessionStore>) -> Self {
         Self {
             repository,
             session_repository,
+            index_service: None,
+            metrics: None,
+            event_bus: None,
+        }
+    }
+
+    /// 设置索引服务，删除轮次时同步删除其索引条目
+    pub fn with_index_service(mut self, index_service: LazyService<dyn IndexService>) -> Self {
+        self.index_service = Some(index_service);
+        self
+    }
+
+    /// 设置应用指标，创建轮次后按消息类型计数
+    pub fn with_metrics(mut self, metrics: Arc<AppMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// 设置事件总线，创建轮次后发布 `turn:created`
+    pub fn with_event_bus(mut self, event_bus: EventBus) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    /// 删除已删除轮次的索引条目（索引服务尚未创建时内存索引中没有条目）
+    async fn delete_turn_indices(&self, turn_ids: &[String]) {
+        let Some(index) = self
+            .index_service
+            .as_ref()
+            .and_then(|s| s.get_initialized())
+        else {
+            return;
+        };
+        for turn_id in turn_ids {
+            if let Err(e) = index.delete_index(turn_id).await {
+                tracing::warn!("Failed to delete index entries for turn {}: {}", turn_id, e);
+            }
+        }
+    }
+
+    /// 按增减量原子更新会话的 `total_turns` 统计（失败只记录警告，不影响轮次操作）
+    async fn adjust_session_turn_count(&self, session_id: &str, delta: i64) {
+        if let Err(e) = self
+            .session_repository
+            .adjust_turn_count(session_id, delta)
+            .await
+        {
+            tracing::warn!(
+                "Failed to update turn count of session {}: {}",
+                session_id,
+                e
+            );
         }
     }
 }
 
 /// 注意：移除了 Default 实现，因为无法在没有数据库连接的情况下创建 Repository
-/// 测试时，请使用 TurnServiceImpl::new() 传入适当的 Arc<TurnRepository>
+/// 测试时，请使用 TurnServiceImpl::new() 传入内存仓储（见 `RepositorySet::in_memory`）
 
 #[async_trait]
 impl TurnService for TurnServiceImpl {
+    #[tracing::instrument(
+        skip(self, content, metadata),
+        fields(
+            service.name = "TurnService",
+            session.id = session_id,
+            turn.id = tracing::field::Empty
+        )
+    )]
     async fn create(
         &self,
         session_id: &str,
         content: &str,
         metadata: Option<TurnMetadata>,
     ) -> Result<Turn> {
-        // 验证 Session 存在
-        self.session_repository
-            .get_by_id(session_id)
-            .await
-            .map_err(|e| AppError::Database(e.to_string()))?
-            .ok_or_else(|| AppError::NotFound(format!("Session not found: {}", session_id)))?;
+        let span = tracing::Span::current();
+        LogContext::for_session(session_id)
+            .run("turn.create", async {
+                // 验证 Session 存在
+                let session = self
+                    .session_repository
+                    .get_by_id(session_id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?
+                    .ok_or_else(|| {
+                        AppError::NotFound(format!("Session not found: {}", session_id))
+                    })?;
+                LogContext::record_tenant(&session.tenant_id);
 
-        let turn_number = self.get_next_turn_number(session_id).await?;
-        let mut turn = Turn::new(session_id, turn_number, content);
-        if let Some(md) = metadata {
-            turn.metadata = md;
-        }
-        self.repository
-            .create(&turn)
+                let turn_number = self.get_next_turn_number(session_id).await?;
+                let mut turn = Turn::new(session_id, turn_number, content);
+                if let Some(md) = metadata {
+                    turn.metadata = md;
+                }
+                let created = self
+                    .repository
+                    .create(&turn)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                span.record("turn.id", created.id.as_str());
+                self.adjust_session_turn_count(session_id, 1).await;
+
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_turn_created(&created.metadata.message_type.to_string());
+                }
+                if let Some(event_bus) = &self.event_bus {
+                    event_bus.publish(
+                        TURN_CREATED,
+                        &session.tenant_id,
+                        serde_json::json!({
+                            "id": created.id,
+                            "session_id": created.session_id,
+                            "turn_number": created.turn_number,
+                            "message_type": created.metadata.message_type.to_string(),
+                        }),
+                    );
+                }
+                Ok(created)
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
     async fn get_by_id(&self, id: &str) -> Result<Option<Turn>> {
-        self.repository
-            .get_by_id(id)
+        LogContext::default()
+            .with_turn(id)
+            .run("turn.get_by_id", async {
+                self.repository
+                    .get_by_id(id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
+            .await
+    }
+
+    async fn get_by_ids(&self, ids: &[&str]) -> Result<Vec<Option<Turn>>> {
+        LogContext::default()
+            .run("turn.get_by_ids", async {
+                self.repository
+                    .get_turns_by_ids(ids)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
+            .await
+    }
+
+    async fn get_by_turn_number(&self, session_id: &str, turn_number: u64) -> Result<Option<Turn>> {
+        LogContext::for_session(session_id)
+            .run("turn.get_by_turn_number", async {
+                // 轮次编号从 1 开始；删除过轮次的会话编号不连续，因此直接按编号查询
+                if turn_number == 0 {
+                    return Ok(None);
+                }
+
+                self.repository
+                    .get_by_turn_number(session_id, turn_number)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
     async fn update(&self, turn: &Turn) -> Result<Turn> {
-        self.repository
-            .update(&turn.id, turn)
+        LogContext::for_session(&turn.session_id)
+            .with_turn(&turn.id)
+            .run("turn.update", async {
+                self.repository
+                    .update(&turn.id, turn)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?
+                    .ok_or_else(|| AppError::NotFound(format!("Turn not found: {}", turn.id)))
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))?
-            .ok_or_else(|| AppError::NotFound(format!("Turn not found: {}", turn.id)))
     }
 
     async fn delete(&self, id: &str) -> Result<bool> {
-        self.repository
-            .delete(id)
+        LogContext::default()
+            .with_turn(id)
+            .run("turn.delete", async {
+                let Some(turn) = self
+                    .repository
+                    .get_by_id(id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?
+                else {
+                    return Ok(false);
+                };
+                let deleted = self
+                    .repository
+                    .delete(id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                if deleted {
+                    self.delete_turn_indices(std::slice::from_ref(&turn.id))
+                        .await;
+                    self.adjust_session_turn_count(&turn.session_id, -1).await;
+                }
+                Ok(deleted)
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
-    async fn list_by_session(&self, session_id: &str, query: TurnQuery) -> Result<Vec<Turn>> {
-        // 检查页码是否越界
-        let total = self
-            .count_by_session(session_id)
+    async fn delete_turns_before(&self, session_id: &str, before_turn: u64) -> Result<usize> {
+        LogContext::for_session(session_id)
+            .run("turn.delete_turns_before", async {
+                let mut deleted = 0;
+                loop {
+                    let batch = self
+                        .repository
+                        .delete_batch_before(session_id, before_turn, DELETE_BATCH_SIZE)
+                        .await
+                        .map_err(|e| AppError::Database(e.to_string()))?;
+                    self.delete_turn_indices(&batch).await;
+                    deleted += batch.len();
+                    if batch.len() < DELETE_BATCH_SIZE {
+                        break;
+                    }
+                }
+
+                if deleted > 0 {
+                    self.adjust_session_turn_count(session_id, -(deleted as i64))
+                        .await;
+                }
+
+                tracing::info!(
+                    "Deleted {} turns before turn {} in session {}",
+                    deleted,
+                    before_turn,
+                    session_id
+                );
+                Ok(deleted)
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))?;
-
-        if query.page > 0 {
-            let max_page = (total as f64 / query.page_size as f64).ceil() as usize;
-            if max_page > 0 && query.page > max_page {
-                return Err(AppError::Validation(format!(
-                    "Page {} exceeds maximum page {} (total: {} items)",
-                    query.page, max_page, total
-                )));
-            }
-        }
+    }
+
+    async fn compress_turns_before(
+        &self,
+        session_id: &str,
+        before_turn: u64,
+    ) -> Result<CompressedTurns> {
+        LogContext::for_session(session_id)
+            .run("turn.compress_turns_before", async {
+                let turns = match before_turn.checked_sub(1) {
+                    Some(end_turn) => self
+                        .repository
+                        .list_by_turn_range(session_id, 0, end_turn)
+                        .await
+                        .map_err(|e| AppError::Database(e.to_string()))?,
+                    None => Vec::new(),
+                };
+                if turns.is_empty() {
+                    return Err(AppError::Validation(format!(
+                        "No turns before turn {} to compress",
+                        before_turn
+                    )));
+                }
 
-        let offset = (query.page.saturating_sub(1)) * query.page_size;
-        let limit = query.page_size;
-        self.repository
-            .list_by_session(session_id, limit, offset)
+                // 先写入合成轮次，再按 ID 删除原轮次，失败时不会丢失内容
+                let compressed = ContextCompressor::new().compress_turns(&turns)?;
+                let compressed = self
+                    .repository
+                    .create(&compressed)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                let turn_ids: Vec<String> = turns.iter().map(|turn| turn.id.clone()).collect();
+                let id_refs: Vec<&str> = turn_ids.iter().map(String::as_str).collect();
+                let deleted = self
+                    .repository
+                    .delete_by_ids(&id_refs)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+
+                self.delete_turn_indices(&turn_ids).await;
+                if let Some(index) = self
+                    .index_service
+                    .as_ref()
+                    .and_then(|s| s.get_initialized())
+                    && let Err(e) = index.index_turn(&compressed).await
+                {
+                    tracing::warn!("Failed to index compressed turn {}: {}", compressed.id, e);
+                }
+                self.adjust_session_turn_count(session_id, 1 - deleted as i64)
+                    .await;
+
+                Ok(CompressedTurns {
+                    turn: compressed,
+                    turns_compressed: turns.len(),
+                    deleted,
+                })
+            })
+            .await
+    }
+
+    async fn get_turns_in_range(
+        &self,
+        session_id: &str,
+        start_turn: u64,
+        end_turn: u64,
+    ) -> Result<Vec<Turn>> {
+        LogContext::for_session(session_id)
+            .run("turn.get_turns_in_range", async {
+                let max_turn = self
+                    .repository
+                    .get_max_turn_number(session_id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                validate_turn_range(start_turn, end_turn, max_turn)?;
+
+                self.repository
+                    .list_by_turn_range(session_id, start_turn, end_turn)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
+            .await
+    }
+
+    async fn list_by_session(&self, session_id: &str, query: TurnQuery) -> Result<Vec<Turn>> {
+        LogContext::for_session(session_id)
+            .run("turn.list_by_session", async {
+                let message_type = parse_message_type_filter(query.message_type.as_deref())?;
+
+                // 检查页码是否越界
+                let total = self
+                    .repository
+                    .count_by_session_filtered(session_id, message_type.clone())
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+
+                if query.page > 0 {
+                    let max_page = (total as f64 / query.page_size as f64).ceil() as usize;
+                    if max_page > 0 && query.page > max_page {
+                        return Err(AppError::Validation(format!(
+                            "Page {} exceeds maximum page {} (total: {} items)",
+                            query.page, max_page, total
+                        )));
+                    }
+                }
+
+                let offset = (query.page.saturating_sub(1)) * query.page_size;
+                let limit = query.page_size;
+                match message_type {
+                    Some(message_type) => {
+                        self.repository
+                            .list_by_session_filtered(session_id, Some(message_type), limit, offset)
+                            .await
+                    }
+                    None => {
+                        self.repository
+                            .list_by_session(session_id, limit, offset)
+                            .await
+                    }
+                }
+                .map_err(|e| AppError::Database(e.to_string()))
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
     async fn count_by_session(&self, session_id: &str) -> Result<u64> {
-        self.repository
-            .count_by_session(session_id)
+        LogContext::for_session(session_id)
+            .run("turn.count_by_session", async {
+                self.repository
+                    .count_by_session(session_id)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
+            .await
+    }
+
+    async fn count_by_session_filtered(
+        &self,
+        session_id: &str,
+        message_type: Option<&str>,
+    ) -> Result<u64> {
+        LogContext::for_session(session_id)
+            .run("turn.count_by_session_filtered", async {
+                let message_type = parse_message_type_filter(message_type)?;
+                self.repository
+                    .count_by_session_filtered(session_id, message_type)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
             .await
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
     async fn get_next_turn_number(&self, session_id: &str) -> Result<u64> {
-        self.repository
-            .get_max_turn_number(session_id)
+        LogContext::for_session(session_id)
+            .run("turn.get_next_turn_number", async {
+                self.repository
+                    .get_max_turn_number(session_id)
+                    .await
+                    .map(|n| n + 1)
+                    .map_err(|e| AppError::Database(e.to_string()))
+            })
             .await
-            .map(|n| n + 1)
-            .map_err(|e| AppError::Database(e.to_string()))
     }
 
     async fn create_batch(
@@ -244,62 +807,202 @@ impl TurnService for TurnServiceImpl {
     }
 
     async fn identify_turn_groups(&self, session_id: &str) -> Result<Vec<TurnGroup>> {
-        let session_turns = self
-            .repository
-            .list_by_session(session_id, 1000, 0)
-            .await
-            .map_err(|e| AppError::Database(e.to_string()))?;
+        LogContext::for_session(session_id)
+            .run("turn.identify_turn_groups", async {
+                let session_turns = self
+                    .repository
+                    .list_by_session(session_id, 1000, 0)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
 
-        let mut groups = Vec::new();
-        let mut current_group: Option<TurnGroup> = None;
+                let mut groups = Vec::new();
+                let mut current_group: Option<TurnGroup> = None;
 
-        for turn in session_turns {
-            let group_type = match turn.metadata.message_type {
-                MessageType::User => TurnGroupType::User,
-                MessageType::Assistant => TurnGroupType::Assistant,
-                MessageType::System => TurnGroupType::System,
-            };
+                for turn in session_turns {
+                    let group_type = match turn.metadata.message_type {
+                        MessageType::User => TurnGroupType::User,
+                        MessageType::Assistant => TurnGroupType::Assistant,
+                        MessageType::System => TurnGroupType::System,
+                    };
 
-            if let Some(ref mut group) = current_group {
-                if group.group_type == group_type
-                    || (group.group_type == TurnGroupType::User
-                        && group_type == TurnGroupType::Assistant)
-                {
-                    group.end_turn = turn.turn_number;
-                    group.turn_ids.push(turn.id);
-                } else {
-                    groups.push(current_group.take().unwrap());
-                    current_group = Some(TurnGroup {
-                        group_id: format!("group_{}", turn.turn_number),
-                        start_turn: turn.turn_number,
-                        end_turn: turn.turn_number,
-                        group_type,
-                        turn_ids: vec![turn.id],
-                    });
+                    if let Some(ref mut group) = current_group {
+                        let answers_user = group_type == TurnGroupType::Assistant
+                            && matches!(
+                                group.group_type,
+                                TurnGroupType::User | TurnGroupType::Mixed
+                            );
+                        if group.group_type == group_type || answers_user {
+                            if answers_user {
+                                group.group_type = TurnGroupType::Mixed;
+                            }
+                            group.end_turn = turn.turn_number;
+                            group.turn_ids.push(turn.id);
+                        } else {
+                            groups.push(current_group.take().unwrap());
+                            current_group = Some(TurnGroup {
+                                group_id: format!("group_{}", turn.turn_number),
+                                start_turn: turn.turn_number,
+                                end_turn: turn.turn_number,
+                                group_type,
+                                turn_ids: vec![turn.id],
+                            });
+                        }
+                    } else {
+                        current_group = Some(TurnGroup {
+                            group_id: format!("group_{}", turn.turn_number),
+                            start_turn: turn.turn_number,
+                            end_turn: turn.turn_number,
+                            group_type,
+                            turn_ids: vec![turn.id],
+                        });
+                    }
                 }
-            } else {
-                current_group = Some(TurnGroup {
-                    group_id: format!("group_{}", turn.turn_number),
-                    start_turn: turn.turn_number,
-                    end_turn: turn.turn_number,
-                    group_type,
-                    turn_ids: vec![turn.id],
-                });
-            }
-        }
 
-        if let Some(group) = current_group {
-            groups.push(group);
+                if let Some(group) = current_group {
+                    groups.push(group);
+                }
+
+                Ok(groups)
+            })
+            .await
+    }
+
+    async fn get_conversation_pairs(&self, session_id: &str) -> Result<Vec<ConversationPair>> {
+        LogContext::for_session(session_id)
+            .run("turn.get_conversation_pairs", async {
+                let groups = self.identify_turn_groups(session_id).await?;
+                let ids: Vec<&str> = groups
+                    .iter()
+                    .flat_map(|g| g.turn_ids.iter().map(String::as_str))
+                    .collect();
+                let turns: HashMap<String, Turn> = self
+                    .get_by_ids(&ids)
+                    .await?
+                    .into_iter()
+                    .flatten()
+                    .map(|t| (t.id.clone(), t))
+                    .collect();
+
+                Ok(assemble_conversation_pairs(groups, &turns))
+            })
+            .await
+    }
+
+    async fn get_conversation_window(
+        &self,
+        session_id: &str,
+        window_size: u32,
+        include_system: bool,
+    ) -> Result<ConversationWindow> {
+        LogContext::for_session(session_id)
+            .run("turn.get_conversation_window", async {
+                let total_turns = self.count_by_session(session_id).await?;
+                let recent = self
+                    .repository
+                    .list_recent_by_session(session_id, window_size as usize)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+
+                Ok(ConversationWindow::from_recent(
+                    recent,
+                    total_turns,
+                    include_system,
+                ))
+            })
+            .await
+    }
+
+    async fn get_context_window(
+        &self,
+        session_id: &str,
+        max_tokens: u32,
+        strategy: TruncationStrategy,
+    ) -> Result<ContextWindow> {
+        LogContext::for_session(session_id)
+            .run("turn.get_context_window", async {
+                let total_turns = self.count_by_session(session_id).await?;
+                let recent = self
+                    .repository
+                    .list_recent_by_session(session_id, CONTEXT_WINDOW_MAX_TURNS)
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+
+                Ok(ContextWindow::fit(
+                    recent,
+                    total_turns,
+                    max_tokens as u64,
+                    strategy,
+                ))
+            })
+            .await
+    }
+}
+
+/// 校验轮次编号范围：起点不大于终点，且终点不超过会话当前的最大轮次编号
+fn validate_turn_range(start_turn: u64, end_turn: u64, max_turn: u64) -> Result<()> {
+    if start_turn > end_turn {
+        return Err(AppError::Validation(format!(
+            "start_turn {} must not exceed end_turn {}",
+            start_turn, end_turn
+        )));
+    }
+    if end_turn > max_turn {
+        return Err(AppError::Validation(format!(
+            "Turn range {}-{} exceeds the session's current max turn number {}",
+            start_turn, end_turn, max_turn
+        )));
+    }
+    Ok(())
+}
+
+/// 解析消息类型过滤参数（user / assistant / system）
+fn parse_message_type_filter(message_type: Option<&str>) -> Result<Option<MessageType>> {
+    message_type
+        .map(|message_type| {
+            MessageType::parse(message_type).ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Invalid message_type: {} (expected user, assistant or system)",
+                    message_type
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// 由轮次分组组装对话对，系统分组的消息附加到其后的第一个对话对
+fn assemble_conversation_pairs(
+    groups: Vec<TurnGroup>,
+    turns: &HashMap<String, Turn>,
+) -> Vec<ConversationPair> {
+    let mut pairs = Vec::new();
+    let mut pending_system: Option<Turn> = None;
+
+    for group in groups {
+        if group.group_type == TurnGroupType::System {
+            pending_system = group
+                .turn_ids
+                .iter()
+                .rev()
+                .find_map(|id| turns.get(id).cloned());
+            continue;
         }
 
-        Ok(groups)
+        let mut group_pairs = group.into_conversation_pairs(turns);
+        if let Some(first) = group_pairs.first_mut()
+            && first.system_turn.is_none()
+        {
+            first.system_turn = pending_system.take();
+        }
+        pairs.extend(group_pairs);
     }
+
+    pairs
 }
 
 /// 创建轮次服务
 pub fn create_turn_service(
-    repository: Arc<TurnRepository>,
-    session_repository: Arc<SessionRepository>,
+    repository: Arc<dyn TurnStore>,
+    session_repository: Arc<dyn SessionStore>,
 ) -> Box<dyn TurnService> {
     Box::new(TurnServiceImpl::new(repository, session_repository))
 }
@@ -307,8 +1010,219 @@ pub fn create_turn_service(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::session::Session;
     use crate::models::turn::{MessageType, Turn};
 
+    #[test]
+    fn test_validate_turn_range() {
+        assert!(validate_turn_range(50, 75, 100).is_ok());
+        assert!(validate_turn_range(3, 3, 3).is_ok());
+        assert!(matches!(
+            validate_turn_range(75, 50, 100),
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            validate_turn_range(50, 101, 100),
+            Err(AppError::Validation(_))
+        ));
+        assert!(validate_turn_range(1, 1, 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delete_turns_before_keeps_boundary_turn() {
+        use crate::index::embedding::SimpleEmbeddingModel;
+        use crate::index::{UnifiedIndexService, create_full_text_index, create_vector_index};
+        use crate::storage::factory::RepositorySet;
+
+        let repos = RepositorySet::in_memory();
+        let index: LazyService<dyn IndexService> = LazyService::ready(
+            "index service",
+            Box::new(UnifiedIndexService::new(
+                create_vector_index(None, None),
+                create_full_text_index(None, false),
+                Box::new(SimpleEmbeddingModel::new(384)),
+            )),
+        );
+        let service = TurnServiceImpl::new(repos.turns.clone(), repos.sessions.clone())
+            .with_index_service(index.clone());
+        let session = repos
+            .sessions
+            .create(&Session::new("tenant_1", "chat"))
+            .await
+            .unwrap();
+
+        let mut turns = Vec::new();
+        for content in ["one", "two", "three", "four"] {
+            let turn = service.create(&session.id, content, None).await.unwrap();
+            index.get().await.unwrap().index_turn(&turn).await.unwrap();
+            turns.push(turn);
+        }
+
+        assert_eq!(
+            service.delete_turns_before(&session.id, 3).await.unwrap(),
+            2
+        );
+
+        let remaining: Vec<u64> = repos
+            .turns
+            .list_by_session(&session.id, 10, 0)
+            .await
+            .unwrap()
+            .iter()
+            .map(|turn| turn.turn_number)
+            .collect();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.contains(&3) && remaining.contains(&4));
+
+        let mut indexed: Vec<String> = index
+            .get()
+            .await
+            .unwrap()
+            .list_indices(&session.id, 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|record| record.turn_id)
+            .collect();
+        indexed.sort();
+        let mut expected = vec![turns[2].id.clone(), turns[3].id.clone()];
+        expected.sort();
+        assert_eq!(indexed, expected);
+
+        let session = repos
+            .sessions
+            .get_by_id(&session.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.stats.total_turns, 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_keeps_archived_session_status() {
+        use crate::storage::factory::RepositorySet;
+
+        let repos = RepositorySet::in_memory();
+        let service = TurnServiceImpl::new(repos.turns.clone(), repos.sessions.clone());
+        let mut session = repos
+            .sessions
+            .create(&Session::new("tenant_1", "chat"))
+            .await
+            .unwrap();
+        session.status = "Archived".to_string();
+        repos.sessions.update(&session.id, &session).await.unwrap();
+
+        service
+            .create(&session.id, "late reply", None)
+            .await
+            .unwrap();
+
+        let stored = repos
+            .sessions
+            .get_by_id(&session.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.status, "Archived");
+        assert_eq!(stored.description, None);
+        assert_eq!(stored.stats.total_turns, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_by_turn_number_after_deleting_earlier_turns() {
+        use crate::storage::factory::RepositorySet;
+
+        let repos = RepositorySet::in_memory();
+        let service = TurnServiceImpl::new(repos.turns.clone(), repos.sessions.clone());
+        let session = repos
+            .sessions
+            .create(&Session::new("tenant_1", "chat"))
+            .await
+            .unwrap();
+        for content in ["one", "two", "three", "four"] {
+            service.create(&session.id, content, None).await.unwrap();
+        }
+        service.delete_turns_before(&session.id, 3).await.unwrap();
+
+        // 剩余轮次编号为 3、4，轮次数量为 2
+        let turn = service
+            .get_by_turn_number(&session.id, 4)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(turn.raw_content, "four");
+        assert!(
+            service
+                .get_by_turn_number(&session.id, 1)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            service
+                .get_by_turn_number(&session.id, 0)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            service
+                .get_by_turn_number(&session.id, 5)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_count_by_session_filtered_counts_beyond_page() {
+        use crate::storage::factory::RepositorySet;
+
+        let repos = RepositorySet::in_memory();
+        let service = TurnServiceImpl::new(repos.turns.clone(), repos.sessions.clone());
+        let message_types = [
+            MessageType::User,
+            MessageType::Assistant,
+            MessageType::User,
+            MessageType::Assistant,
+            MessageType::User,
+        ];
+        for (turn_number, message_type) in (1..).zip(message_types) {
+            let mut turn = Turn::new("session_1", turn_number, "content");
+            turn.metadata.message_type = message_type;
+            repos.turns.create(&turn).await.unwrap();
+        }
+
+        let query = TurnQuery {
+            page: 2,
+            page_size: 2,
+            message_type: Some("user".to_string()),
+        };
+        let page = service.list_by_session("session_1", query).await.unwrap();
+        let numbers: Vec<u64> = page.iter().map(|t| t.turn_number).collect();
+        assert_eq!(numbers, vec![5]);
+        assert_eq!(
+            service
+                .count_by_session_filtered("session_1", Some("user"))
+                .await
+                .unwrap(),
+            3
+        );
+        assert_eq!(
+            service
+                .count_by_session_filtered("session_1", None)
+                .await
+                .unwrap(),
+            5
+        );
+        assert!(matches!(
+            service
+                .count_by_session_filtered("session_1", Some("tool"))
+                .await,
+            Err(AppError::Validation(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_turn_create() {
         let turn = Turn::new("session_1", 1, "Hello, world!");
@@ -341,4 +1255,176 @@ mod tests {
         };
         assert_eq!(group.turn_ids.len(), 2);
     }
+
+    fn turn_with_type(id: &str, turn_number: u64, message_type: MessageType) -> Turn {
+        let mut turn = Turn::new("session_1", turn_number, id);
+        turn.id = id.to_string();
+        turn.metadata.message_type = message_type;
+        turn
+    }
+
+    fn group(group_type: TurnGroupType, ids: &[&str]) -> TurnGroup {
+        TurnGroup {
+            group_id: format!("group_{}", ids[0]),
+            start_turn: 0,
+            end_turn: 0,
+            group_type,
+            turn_ids: ids.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_into_conversation_pairs() {
+        let turns: HashMap<String, Turn> = [
+            turn_with_type("u1", 1, MessageType::User),
+            turn_with_type("a1", 2, MessageType::Assistant),
+        ]
+        .into_iter()
+        .map(|t| (t.id.clone(), t))
+        .collect();
+
+        let pairs = group(TurnGroupType::Mixed, &["u1", "a1"]).into_conversation_pairs(&turns);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].user_turn.id, "u1");
+        assert_eq!(pairs[0].assistant_turn.as_ref().unwrap().id, "a1");
+        assert!(pairs[0].additional_assistant_turns.is_empty());
+        assert!(pairs[0].system_turn.is_none());
+
+        assert!(
+            group(TurnGroupType::Assistant, &["a1"])
+                .into_conversation_pairs(&turns)
+                .is_empty()
+        );
+        assert!(
+            group(TurnGroupType::User, &["missing"])
+                .into_conversation_pairs(&turns)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_mixed_group_keeps_every_turn() {
+        let turns: HashMap<String, Turn> = [
+            turn_with_type("u1", 1, MessageType::User),
+            turn_with_type("u2", 2, MessageType::User),
+            turn_with_type("a1", 3, MessageType::Assistant),
+            turn_with_type("a2", 4, MessageType::Assistant),
+            turn_with_type("a3", 5, MessageType::Assistant),
+        ]
+        .into_iter()
+        .map(|t| (t.id.clone(), t))
+        .collect();
+
+        let pairs = group(TurnGroupType::Mixed, &["u1", "u2", "a1", "a2", "a3"])
+            .into_conversation_pairs(&turns);
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].user_turn.id, "u1");
+        assert!(pairs[0].assistant_turn.is_none());
+        assert_eq!(pairs[1].user_turn.id, "u2");
+        assert_eq!(pairs[1].assistant_turn.as_ref().unwrap().id, "a1");
+        let additional: Vec<&str> = pairs[1]
+            .additional_assistant_turns
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(additional, vec!["a2", "a3"]);
+    }
+
+    #[test]
+    fn test_assemble_conversation_pairs_attaches_system_turn() {
+        let turns: HashMap<String, Turn> = [
+            turn_with_type("s1", 1, MessageType::System),
+            turn_with_type("u1", 2, MessageType::User),
+            turn_with_type("a1", 3, MessageType::Assistant),
+            turn_with_type("u2", 4, MessageType::User),
+        ]
+        .into_iter()
+        .map(|t| (t.id.clone(), t))
+        .collect();
+        let groups = vec![
+            group(TurnGroupType::System, &["s1"]),
+            group(TurnGroupType::Mixed, &["u1", "a1"]),
+            group(TurnGroupType::User, &["u2"]),
+        ];
+
+        let pairs = assemble_conversation_pairs(groups, &turns);
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].system_turn.as_ref().unwrap().id, "s1");
+        assert_eq!(pairs[0].assistant_turn.as_ref().unwrap().id, "a1");
+        assert_eq!(pairs[1].user_turn.id, "u2");
+        assert!(pairs[1].assistant_turn.is_none());
+        assert!(pairs[1].system_turn.is_none());
+    }
+
+    #[test]
+    fn test_conversation_window_from_recent() {
+        let mut system = Turn::new("session_1", 3, "You are helpful");
+        system.metadata.message_type = MessageType::System;
+        let recent = vec![
+            Turn::new("session_1", 4, "Hi"),
+            system,
+            Turn::new("session_1", 2, "Hello"),
+        ];
+
+        let window = ConversationWindow::from_recent(recent.clone(), 4, true);
+        let numbers: Vec<u64> = window.turns.iter().map(|t| t.turn_number).collect();
+        assert_eq!(numbers, vec![2, 3, 4]);
+        assert_eq!(window.total_turns, 4);
+        assert_eq!(window.window_start_turn, 2);
+
+        let window = ConversationWindow::from_recent(recent, 4, false);
+        let numbers: Vec<u64> = window.turns.iter().map(|t| t.turn_number).collect();
+        assert_eq!(numbers, vec![2, 4]);
+        assert_eq!(window.window_start_turn, 2);
+
+        let window = ConversationWindow::from_recent(Vec::new(), 0, true);
+        assert!(window.turns.is_empty());
+        assert_eq!(window.window_start_turn, 0);
+    }
+
+    #[test]
+    fn test_approximate_tokens() {
+        assert_eq!(approximate_tokens(""), 0);
+        assert_eq!(approximate_tokens("abcd"), 1);
+        assert_eq!(approximate_tokens("abcde"), 2);
+        assert_eq!(approximate_tokens("你好世界啊"), 2);
+    }
+
+    #[test]
+    fn test_context_window_oldest() {
+        let recent = vec![
+            Turn::new("session_1", 4, &"a".repeat(40)),
+            Turn::new("session_1", 3, &"b".repeat(40)),
+            Turn::new("session_1", 2, &"c".repeat(40)),
+            Turn::new("session_1", 1, &"d".repeat(4)),
+        ];
+
+        let window = ContextWindow::fit(recent, 4, 25, TruncationStrategy::Oldest);
+        let numbers: Vec<u64> = window.turns.iter().map(|t| t.turn_number).collect();
+        assert_eq!(numbers, vec![3, 4]);
+        assert_eq!(window.tokens_used, 20);
+        assert_eq!(window.turns_truncated, 2);
+    }
+
+    #[test]
+    fn test_context_window_least_relevant() {
+        let recent = vec![
+            Turn::new("session_1", 4, "deploy the rust service"),
+            Turn::new("session_1", 3, "what about lunch today"),
+            Turn::new("session_1", 2, "rust service deploy failed"),
+            Turn::new("session_1", 1, "hello there friend"),
+        ];
+        let budget = approximate_tokens("deploy the rust service")
+            + approximate_tokens("rust service deploy failed");
+
+        let window = ContextWindow::fit(recent, 4, budget, TruncationStrategy::LeastRelevant);
+        let numbers: Vec<u64> = window.turns.iter().map(|t| t.turn_number).collect();
+        assert_eq!(numbers, vec![2, 4]);
+        assert_eq!(window.tokens_used, budget);
+        assert_eq!(window.turns_truncated, 2);
+
+        let window = ContextWindow::fit(Vec::new(), 0, 100, TruncationStrategy::LeastRelevant);
+        assert!(window.turns.is_empty());
+        assert_eq!(window.turns_truncated, 0);
+    }
 }
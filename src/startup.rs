@@ -0,0 +1,192 @@
+//! 服务启动模块
+//!
+//! 根据服务器配置绑定 HTTP 或 HTTPS 监听器，并统一处理优雅关闭。
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use axum::Router;
+use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+use tokio::net::TcpListener;
+use tracing::info;
+
+use crate::config::config::ServerConfig;
+use crate::error::{AppError, Result};
+
+/// HTTPS 优雅关闭时等待现有连接结束的最长时间
+const TLS_SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// 已绑定的监听器
+pub enum ListenerKind {
+    /// 明文 HTTP
+    Plain(TcpListener),
+    /// HTTPS (rustls)
+    Tls(axum_server::Server<RustlsAcceptor>),
+}
+
+impl ListenerKind {
+    /// 是否为 HTTPS 监听器
+    pub fn is_tls(&self) -> bool {
+        matches!(self, ListenerKind::Tls(_))
+    }
+
+    /// 启动服务，直到 `shutdown` 完成后优雅关闭
+    pub async fn serve<F>(self, router: Router, shutdown: F) -> std::io::Result<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        match self {
+            ListenerKind::Plain(listener) => {
+                axum::serve(
+                    listener,
+                    router.into_make_service_with_connect_info::<SocketAddr>(),
+                )
+                .with_graceful_shutdown(shutdown)
+                .await
+            }
+            ListenerKind::Tls(server) => {
+                let handle = axum_server::Handle::new();
+                let shutdown_handle = handle.clone();
+                tokio::spawn(async move {
+                    shutdown.await;
+                    shutdown_handle.graceful_shutdown(Some(TLS_SHUTDOWN_GRACE));
+                });
+
+                server
+                    .handle(handle)
+                    .serve(router.into_make_service_with_connect_info::<SocketAddr>())
+                    .await
+            }
+        }
+    }
+}
+
+/// 根据服务器配置绑定监听器
+///
+/// `tls_enabled = true` 时从 `tls.cert_path` / `tls.key_path` 加载 PEM 证书并返回 HTTPS 监听器，
+/// 否则返回明文 TCP 监听器。
+pub async fn bind_listener(config: &ServerConfig) -> Result<ListenerKind> {
+    let addr = format!("{}:{}", config.host, config.port);
+
+    if !config.tls_enabled {
+        let listener = TcpListener::bind(&addr).await?;
+        info!("Listening on http://{}", addr);
+        return Ok(ListenerKind::Plain(listener));
+    }
+
+    let tls = config.tls.as_ref().ok_or_else(|| {
+        AppError::Config("server.tls must be set when tls_enabled is true".to_string())
+    })?;
+
+    let rustls_config = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+        .await
+        .map_err(|e| {
+            AppError::Config(format!(
+                "Failed to load TLS certificate {} / key {}: {}",
+                tls.cert_path.display(),
+                tls.key_path.display(),
+                e
+            ))
+        })?;
+
+    let listener = std::net::TcpListener::bind(&addr)?;
+    listener.set_nonblocking(true)?;
+    info!("Listening on https://{}", addr);
+
+    Ok(ListenerKind::Tls(axum_server::from_tcp_rustls(
+        listener,
+        rustls_config,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config::TlsConfig;
+    use axum::routing::get;
+
+    fn free_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    #[tokio::test]
+    async fn test_bind_listener_plain() {
+        let config = ServerConfig {
+            host: "127.0.0.1".into(),
+            port: 0,
+            ..Default::default()
+        };
+
+        let listener = bind_listener(&config).await.unwrap();
+        assert!(!listener.is_tls());
+    }
+
+    #[tokio::test]
+    async fn test_bind_listener_tls_requires_certs() {
+        let config = ServerConfig {
+            host: "127.0.0.1".into(),
+            port: 0,
+            tls_enabled: true,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            bind_listener(&config).await,
+            Err(AppError::Config(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_serve_https_with_self_signed_cert() {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let dir = std::env::temp_dir().join(format!("hippos-tls-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        std::fs::write(&cert_path, cert.cert.pem()).unwrap();
+        std::fs::write(&key_path, cert.key_pair.serialize_pem()).unwrap();
+
+        let port = free_port();
+        let config = ServerConfig {
+            host: "127.0.0.1".into(),
+            port,
+            tls_enabled: true,
+            tls: Some(TlsConfig {
+                cert_path,
+                key_path,
+            }),
+            ..Default::default()
+        };
+
+        let listener = bind_listener(&config).await.unwrap();
+        assert!(listener.is_tls());
+
+        let router = Router::new().route("/ping", get(|| async { "pong" }));
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(listener.serve(router, async {
+            let _ = stop_rx.await;
+        }));
+
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .build()
+            .unwrap();
+        let response = client
+            .get(format!("https://127.0.0.1:{}/ping", port))
+            .send()
+            .await
+            .unwrap();
+
+        assert!(response.status().is_success());
+        assert_eq!(response.text().await.unwrap(), "pong");
+
+        stop_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
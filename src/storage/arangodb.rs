@@ -438,7 +438,7 @@ pub async fn create_arango_storage(config: &DatabaseConfig) -> Result<ArangoStor
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::config::{DatabaseConfig, DatabaseType};
+    use crate::config::config::{CompressionConfig, DatabaseConfig, DatabaseType};
     use serde::{Deserialize, Serialize};
 
     #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -462,6 +462,9 @@ mod tests {
             connection_timeout: 30,
             idle_timeout: 300,
             collection_prefix: "custom_".into(),
+            max_retries: 3,
+            base_delay_ms: 100,
+            compression: CompressionConfig::default(),
         };
 
         let arango_config = ArangoConfig::from(db_config);
@@ -487,6 +490,9 @@ mod tests {
             connection_timeout: 30,
             idle_timeout: 300,
             collection_prefix: "".into(),
+            max_retries: 3,
+            base_delay_ms: 100,
+            compression: CompressionConfig::default(),
         };
 
         let arango_config = ArangoConfig::from(db_config);
@@ -508,6 +514,9 @@ mod tests {
             connection_timeout: 30,
             idle_timeout: 300,
             collection_prefix: "test_".into(),
+            max_retries: 3,
+            base_delay_ms: 100,
+            compression: CompressionConfig::default(),
         };
 
         let arango_config = ArangoConfig::from(db_config);
@@ -538,6 +547,9 @@ mod tests {
             connection_timeout: 30,
             idle_timeout: 300,
             collection_prefix: "test_".into(),
+            max_retries: 3,
+            base_delay_ms: 100,
+            compression: CompressionConfig::default(),
         };
 
         let arango_config = ArangoConfig::from(db_config);
@@ -563,6 +575,9 @@ mod tests {
             connection_timeout: 30,
             idle_timeout: 300,
             collection_prefix: "".into(),
+            max_retries: 3,
+            base_delay_ms: 100,
+            compression: CompressionConfig::default(),
         };
 
         let arango_config = ArangoConfig::from(db_config);
@@ -7,6 +7,7 @@ use std::marker::PhantomData;
 
 use crate::error::{AppError, Result};
 use crate::models::index_record::IndexRecord;
+use crate::models::memory::Memory;
 use crate::models::session::Session;
 use crate::models::turn::Turn;
 use crate::storage::arangodb::ArangoStorage;
@@ -330,3 +331,47 @@ impl ArangoRepository<IndexRecord> for ArangoIndexRecordRepository {
             .map_err(AppError::Database)
     }
 }
+
+/// 记忆仓储实现
+#[derive(Clone)]
+pub struct ArangoMemoryRepository {
+    storage: ArangoStorage,
+    _marker: PhantomData<Memory>,
+}
+
+impl ArangoMemoryRepository {
+    pub fn new(storage: ArangoStorage) -> Self {
+        Self {
+            storage,
+            _marker: PhantomData,
+        }
+    }
+
+    /// 按用户列出记忆，指定 `memory_type` 时追加类型过滤
+    pub async fn list_by_user(
+        &self,
+        user_id: &str,
+        memory_type: Option<&str>,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<Memory>> {
+        let user_id = user_id.replace("'", "\\'");
+        let query = match memory_type {
+            Some(memory_type) => format!(
+                "FOR m IN memories FILTER m.user_id == '{}' AND m.memory_type == '{}' SORT m.created_at DESC LIMIT {}, {} RETURN m",
+                user_id,
+                memory_type.to_lowercase().replace("'", "\\'"),
+                start,
+                limit
+            ),
+            None => format!(
+                "FOR m IN memories FILTER m.user_id == '{}' SORT m.created_at DESC LIMIT {}, {} RETURN m",
+                user_id, start, limit
+            ),
+        };
+        self.storage
+            .aql::<Memory>(&query)
+            .await
+            .map_err(AppError::Database)
+    }
+}
@@ -0,0 +1,96 @@
+//! 轮次内容压缩
+//!
+//! 超长的 `raw_content` 以 gzip 压缩并 base64 编码后存入 `compressed_content` 字段，
+//! 读取时还原，服务层看到的始终是原始文本。
+
+use std::io::{Read, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::config::config::CompressionConfig;
+use crate::error::{AppError, Result};
+
+/// 记录中 `compression` 字段的 gzip 取值
+pub const GZIP: &str = "gzip";
+
+/// 判断内容是否需要压缩
+pub fn should_compress(config: &CompressionConfig, content: &str) -> bool {
+    config.enabled && content.len() > config.min_size_bytes
+}
+
+/// gzip 压缩并 base64 编码
+pub fn compress(content: &str) -> Result<String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(content.as_bytes())?;
+    Ok(STANDARD.encode(encoder.finish()?))
+}
+
+/// base64 解码并 gzip 解压
+pub fn decompress(encoded: &str) -> Result<String> {
+    let bytes = STANDARD
+        .decode(encoded)
+        .map_err(|e| AppError::Database(format!("Invalid compressed content: {}", e)))?;
+    let mut content = String::new();
+    GzDecoder::new(bytes.as_slice()).read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// 还原轮次记录中的压缩内容，将原文写回 `raw_content`
+pub fn inflate_turn_record(record: &mut serde_json::Value) -> Result<()> {
+    if record.get("compression").and_then(|v| v.as_str()) != Some(GZIP) {
+        return Ok(());
+    }
+
+    let encoded = record
+        .get("compressed_content")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| AppError::Database("Compressed turn has no content".to_string()))?;
+    let content = decompress(encoded)?;
+    record["raw_content"] = serde_json::Value::String(content);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress_roundtrip() {
+        let content = "long pasted document ".repeat(100);
+        let encoded = compress(&content).unwrap();
+        assert_ne!(encoded, content);
+        assert_eq!(decompress(&encoded).unwrap(), content);
+    }
+
+    #[test]
+    fn test_should_compress_respects_threshold() {
+        let mut config = CompressionConfig {
+            enabled: true,
+            min_size_bytes: 10,
+        };
+        assert!(!should_compress(&config, "short"));
+        assert!(should_compress(&config, "longer than ten bytes"));
+
+        config.enabled = false;
+        assert!(!should_compress(&config, "longer than ten bytes"));
+    }
+
+    #[test]
+    fn test_inflate_turn_record() {
+        let mut record = serde_json::json!({
+            "raw_content": "",
+            "compressed_content": compress("hello").unwrap(),
+            "compression": GZIP,
+        });
+        inflate_turn_record(&mut record).unwrap();
+        assert_eq!(record["raw_content"], "hello");
+
+        let mut plain = serde_json::json!({ "raw_content": "plain" });
+        inflate_turn_record(&mut plain).unwrap();
+        assert_eq!(plain["raw_content"], "plain");
+    }
+}
@@ -4,8 +4,18 @@
 
 use crate::config::config::{DatabaseConfig, DatabaseType};
 use crate::error::{AppError, Result};
+use crate::models::memory_repository::MemoryRepository;
+use crate::models::turn::Turn;
+use crate::storage::in_memory::{
+    InMemoryMemoryRepository, InMemoryRepository, InMemorySessionRepository,
+};
+use crate::storage::repository::{SessionStore, TurnStore};
 use std::sync::Arc;
 
+#[cfg(feature = "surrealdb")]
+use crate::models::memory_repository::MemoryRepositoryImpl;
+#[cfg(feature = "surrealdb")]
+use crate::storage::repository::{SessionRepository, TurnRepository};
 #[cfg(feature = "surrealdb")]
 use crate::storage::surrealdb::SurrealPool;
 
@@ -87,6 +97,72 @@ impl StorageFactory {
     }
 }
 
+/// SurrealDB 仓储驱动
+pub const SURREALDB_DRIVER: &str = "surrealdb";
+
+/// 内存仓储驱动（无需数据库实例，用于测试）
+pub const MEMORY_DRIVER: &str = "memory";
+
+/// 仓储集合
+#[derive(Clone)]
+pub struct RepositorySet {
+    /// 会话仓储
+    pub sessions: Arc<dyn SessionStore>,
+    /// 轮次仓储
+    pub turns: Arc<dyn TurnStore>,
+    /// 记忆仓储
+    pub memories: Arc<dyn MemoryRepository + Send + Sync>,
+}
+
+impl RepositorySet {
+    /// 基于 `HashMap` 的内存仓储集合
+    pub fn in_memory() -> Self {
+        let turns = InMemoryRepository::<Turn>::new();
+        Self {
+            sessions: Arc::new(InMemorySessionRepository::new(turns.clone())),
+            memories: Arc::new(InMemoryMemoryRepository::with_turns(turns.clone())),
+            turns: Arc::new(turns),
+        }
+    }
+
+    /// 基于 SurrealDB 连接池的仓储集合
+    #[cfg(feature = "surrealdb")]
+    pub fn surrealdb(pool: SurrealPool) -> Self {
+        Self {
+            sessions: Arc::new(SessionRepository::new(pool.clone())),
+            turns: Arc::new(TurnRepository::new(pool.clone())),
+            memories: Arc::new(MemoryRepositoryImpl::new(pool)),
+        }
+    }
+}
+
+/// 仓储工厂
+pub struct RepositoryFactory;
+
+impl RepositoryFactory {
+    /// 根据驱动名称创建仓储集合
+    ///
+    /// - `surrealdb`：按 `config` 建立连接池
+    /// - `memory`：内存仓储，忽略 `config`
+    #[cfg_attr(not(feature = "surrealdb"), allow(unused_variables))]
+    pub async fn create(driver: &str, config: &DatabaseConfig) -> Result<RepositorySet> {
+        match driver.to_lowercase().as_str() {
+            MEMORY_DRIVER => Ok(RepositorySet::in_memory()),
+            #[cfg(feature = "surrealdb")]
+            SURREALDB_DRIVER => {
+                let pool = SurrealPool::new(config.clone())
+                    .await
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                Ok(RepositorySet::surrealdb(pool))
+            }
+            _ => Err(AppError::Config(format!(
+                "Unsupported repository driver: {} (expected '{}' or '{}')",
+                driver, SURREALDB_DRIVER, MEMORY_DRIVER
+            ))),
+        }
+    }
+}
+
 #[cfg(feature = "arangodb")]
 impl StorageInstance {
     pub fn as_arangodb(&self) -> Option<&ArangoStorage> {
@@ -113,3 +189,74 @@ impl StorageInstance {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::session::Session;
+
+    #[tokio::test]
+    async fn test_create_memory_driver() {
+        let repos = RepositoryFactory::create("memory", &DatabaseConfig::default())
+            .await
+            .unwrap();
+
+        let session = repos
+            .sessions
+            .create(&Session::new("tenant_1", "chat"))
+            .await
+            .unwrap();
+        repos
+            .turns
+            .create(&Turn::new(&session.id, 1, "hello"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repos
+                .sessions
+                .list_by_tenant("tenant_1", 10, 0)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            repos
+                .sessions
+                .list_by_tenant("tenant_2", 10, 0)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(repos.turns.count_by_session(&session.id).await.unwrap(), 1);
+        assert_eq!(repos.memories.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_services_over_memory_driver() {
+        use crate::services::session::{SessionService, SessionServiceImpl};
+        use crate::services::turn::{TurnService, TurnServiceImpl};
+
+        let repos = RepositorySet::in_memory();
+        let sessions = SessionServiceImpl::new(repos.sessions.clone(), repos.turns.clone())
+            .with_memory_repository(repos.memories.clone());
+        let turns = TurnServiceImpl::new(repos.turns.clone(), repos.sessions.clone());
+
+        let session = sessions.create("tenant_1", "chat").await.unwrap();
+        let first = turns.create(&session.id, "hello", None).await.unwrap();
+        let second = turns.create(&session.id, "world", None).await.unwrap();
+        assert_eq!((first.turn_number, second.turn_number), (1, 2));
+        assert_eq!(turns.count_by_session(&session.id).await.unwrap(), 2);
+
+        assert!(sessions.delete(&session.id).await.unwrap());
+        assert!(sessions.get_by_id(&session.id).await.unwrap().is_none());
+        assert_eq!(repos.turns.count_by_session(&session.id).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_create_unknown_driver() {
+        let result = RepositoryFactory::create("redis", &DatabaseConfig::default()).await;
+        assert!(matches!(result, Err(AppError::Config(_))));
+    }
+}
@@ -0,0 +1,902 @@
+//! 内存仓储
+//!
+//! 基于 `HashMap` 的仓储实现，供集成测试在没有 SurrealDB 实例时使用。
+//! 数据仅保存在进程内，克隆的仓储共享同一份数据。
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::{AppError, Result};
+use crate::models::memory::{
+    Memory, MemoryQuery, MemorySource, MemoryStats, MemoryStatus, MemoryType,
+};
+use crate::models::memory_repository::{MemoryRepository, rank_by_embedding};
+use crate::models::session::{Session, SessionConfig, SessionWithStats};
+use crate::models::turn::{DehydratedData, MessageType, Turn};
+use crate::storage::repository::{Repository, SessionStore, TurnStore};
+
+/// 可保存在 `InMemoryRepository` 中的记录
+pub trait InMemoryRecord: Clone + Send + Sync + 'static {
+    /// 记录 ID
+    fn record_id(&self) -> &str;
+
+    /// 列表查询的排序方式
+    fn list_order(a: &Self, b: &Self) -> Ordering;
+
+    /// 所属租户（用于 `list_by_tenant`）
+    fn tenant_id(&self) -> Option<&str> {
+        None
+    }
+
+    /// 所属会话（用于 `list_by_session`）
+    fn session_id(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl InMemoryRecord for Session {
+    fn record_id(&self) -> &str {
+        &self.id
+    }
+
+    /// 按创建时间降序
+    fn list_order(a: &Self, b: &Self) -> Ordering {
+        b.created_at.cmp(&a.created_at)
+    }
+
+    fn tenant_id(&self) -> Option<&str> {
+        Some(&self.tenant_id)
+    }
+}
+
+impl InMemoryRecord for Turn {
+    fn record_id(&self) -> &str {
+        &self.id
+    }
+
+    /// 按会话、turn_number 升序
+    fn list_order(a: &Self, b: &Self) -> Ordering {
+        (&a.session_id, a.turn_number).cmp(&(&b.session_id, b.turn_number))
+    }
+
+    fn session_id(&self) -> Option<&str> {
+        Some(&self.session_id)
+    }
+}
+
+/// 通用内存仓储
+#[derive(Clone)]
+pub struct InMemoryRepository<T> {
+    records: Arc<RwLock<HashMap<String, T>>>,
+}
+
+impl<T> Default for InMemoryRepository<T> {
+    fn default() -> Self {
+        Self {
+            records: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
+
+impl<T: InMemoryRecord> InMemoryRepository<T> {
+    /// 创建空仓储
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 按 `list_order` 排序后分页返回满足条件的记录
+    fn select(&self, filter: impl Fn(&T) -> bool, limit: usize, start: usize) -> Vec<T> {
+        let mut records: Vec<T> = self
+            .records
+            .read()
+            .values()
+            .filter(|r| filter(r))
+            .cloned()
+            .collect();
+        records.sort_by(T::list_order);
+        records.into_iter().skip(start).take(limit).collect()
+    }
+
+    /// 统计满足条件的记录
+    fn count_where(&self, filter: impl Fn(&T) -> bool) -> u64 {
+        self.records.read().values().filter(|r| filter(r)).count() as u64
+    }
+}
+
+#[async_trait]
+impl<T: InMemoryRecord> Repository<T> for InMemoryRepository<T> {
+    async fn create(&self, entity: &T) -> Result<T> {
+        let mut records = self.records.write();
+        if records.contains_key(entity.record_id()) {
+            return Err(AppError::Conflict(format!(
+                "Record already exists: {}",
+                entity.record_id()
+            )));
+        }
+        records.insert(entity.record_id().to_string(), entity.clone());
+        Ok(entity.clone())
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<Option<T>> {
+        Ok(self.records.read().get(id).cloned())
+    }
+
+    async fn update(&self, id: &str, entity: &T) -> Result<Option<T>> {
+        Ok(self.records.write().get_mut(id).map(|record| {
+            *record = entity.clone();
+            record.clone()
+        }))
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool> {
+        Ok(self.records.write().remove(id).is_some())
+    }
+
+    async fn list(&self, limit: usize, start: usize) -> Result<Vec<T>> {
+        Ok(self.select(|_| true, limit, start))
+    }
+
+    async fn count(&self) -> Result<u64> {
+        Ok(self.records.read().len() as u64)
+    }
+
+    async fn list_by_tenant(&self, tenant_id: &str, limit: usize, start: usize) -> Result<Vec<T>> {
+        Ok(self.select(|r| r.tenant_id() == Some(tenant_id), limit, start))
+    }
+
+    async fn count_by_tenant(&self, tenant_id: &str) -> Result<u64> {
+        Ok(self.count_where(|r| r.tenant_id() == Some(tenant_id)))
+    }
+
+    async fn list_by_session(
+        &self,
+        session_id: &str,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<T>> {
+        Ok(self.select(|r| r.session_id() == Some(session_id), limit, start))
+    }
+
+    async fn count_by_session(&self, session_id: &str) -> Result<u64> {
+        Ok(self.count_where(|r| r.session_id() == Some(session_id)))
+    }
+}
+
+/// 内存会话仓储
+///
+/// 与轮次仓储共享轮次数据，`get_with_stats` 据此统计会话的轮次。
+#[derive(Clone, Default)]
+pub struct InMemorySessionRepository {
+    sessions: InMemoryRepository<Session>,
+    turns: InMemoryRepository<Turn>,
+}
+
+impl InMemorySessionRepository {
+    /// 创建空仓储，轮次统计基于 `turns`
+    pub fn new(turns: InMemoryRepository<Turn>) -> Self {
+        Self {
+            sessions: InMemoryRepository::new(),
+            turns,
+        }
+    }
+}
+
+#[async_trait]
+impl Repository<Session> for InMemorySessionRepository {
+    async fn create(&self, entity: &Session) -> Result<Session> {
+        self.sessions.create(entity).await
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<Option<Session>> {
+        self.sessions.get_by_id(id).await
+    }
+
+    async fn update(&self, id: &str, entity: &Session) -> Result<Option<Session>> {
+        self.sessions.update(id, entity).await
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool> {
+        self.sessions.delete(id).await
+    }
+
+    async fn list(&self, limit: usize, start: usize) -> Result<Vec<Session>> {
+        self.sessions.list(limit, start).await
+    }
+
+    async fn count(&self) -> Result<u64> {
+        self.sessions.count().await
+    }
+
+    async fn list_by_tenant(
+        &self,
+        tenant_id: &str,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<Session>> {
+        self.sessions.list_by_tenant(tenant_id, limit, start).await
+    }
+
+    async fn count_by_tenant(&self, tenant_id: &str) -> Result<u64> {
+        self.sessions.count_by_tenant(tenant_id).await
+    }
+}
+
+#[async_trait]
+impl SessionStore for InMemorySessionRepository {
+    async fn get_with_stats(&self, id: &str) -> Result<Option<SessionWithStats>> {
+        let Some(session) = self.sessions.get_by_id(id).await? else {
+            return Ok(None);
+        };
+        let turns = self.turns.select(|t| t.session_id == id, usize::MAX, 0);
+
+        Ok(Some(SessionWithStats {
+            session,
+            turn_count: turns.len() as u64,
+            last_turn_at: turns.last().map(|t| t.metadata.timestamp),
+        }))
+    }
+
+    async fn update_config(&self, id: &str, config: &SessionConfig) -> Result<()> {
+        if let Some(session) = self.sessions.records.write().get_mut(id) {
+            session.config = config.clone();
+        }
+        Ok(())
+    }
+
+    async fn adjust_turn_count(&self, id: &str, delta: i64) -> Result<()> {
+        if let Some(session) = self.sessions.records.write().get_mut(id) {
+            session.stats.total_turns = session.stats.total_turns.saturating_add_signed(delta);
+        }
+        Ok(())
+    }
+
+    async fn add_stats(
+        &self,
+        id: &str,
+        total_tokens: u64,
+        storage_size: u64,
+        last_active_at: DateTime<Utc>,
+    ) -> Result<()> {
+        if let Some(session) = self.sessions.records.write().get_mut(id) {
+            session.stats.total_tokens += total_tokens;
+            session.stats.storage_size += storage_size;
+            session.last_active_at = session.last_active_at.max(last_active_at);
+        }
+        Ok(())
+    }
+
+    async fn update_name(&self, id: &str, name: &str) -> Result<()> {
+        if let Some(session) = self.sessions.records.write().get_mut(id) {
+            session.name = name.to_string();
+        }
+        Ok(())
+    }
+
+    async fn count_active(&self, tenant_id: &str, active_within_secs: u64) -> Result<u64> {
+        let since = Utc::now() - chrono::Duration::seconds(active_within_secs as i64);
+        Ok(self.sessions.count_where(|s| {
+            s.tenant_id == tenant_id
+                && s.last_active_at > since
+                && s.status.eq_ignore_ascii_case("active")
+        }))
+    }
+
+    async fn list_by_tenant_and_status(
+        &self,
+        tenant_id: &str,
+        status: Option<&str>,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<Session>> {
+        Ok(self.sessions.select(
+            |s| {
+                s.tenant_id == tenant_id
+                    && status.is_none_or(|status| s.status.eq_ignore_ascii_case(status))
+            },
+            limit,
+            start,
+        ))
+    }
+
+    async fn count_by_tenant_and_status(
+        &self,
+        tenant_id: &str,
+        status: Option<&str>,
+    ) -> Result<u64> {
+        Ok(self.sessions.count_where(|s| {
+            s.tenant_id == tenant_id
+                && status.is_none_or(|status| s.status.eq_ignore_ascii_case(status))
+        }))
+    }
+
+    async fn list_tenant_ids(&self) -> Result<Vec<String>> {
+        let mut tenant_ids: Vec<String> = self
+            .sessions
+            .records
+            .read()
+            .values()
+            .map(|s| s.tenant_id.clone())
+            .collect();
+        tenant_ids.sort();
+        tenant_ids.dedup();
+        Ok(tenant_ids)
+    }
+}
+
+#[async_trait]
+impl TurnStore for InMemoryRepository<Turn> {
+    async fn list_by_session_filtered(
+        &self,
+        session_id: &str,
+        message_type: Option<MessageType>,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<Turn>> {
+        Ok(self.select(
+            |t| {
+                t.session_id == session_id
+                    && message_type
+                        .as_ref()
+                        .is_none_or(|m| &t.metadata.message_type == m)
+            },
+            limit,
+            start,
+        ))
+    }
+
+    async fn count_by_session_filtered(
+        &self,
+        session_id: &str,
+        message_type: Option<MessageType>,
+    ) -> Result<u64> {
+        Ok(self.count_where(|t| {
+            t.session_id == session_id
+                && message_type
+                    .as_ref()
+                    .is_none_or(|m| &t.metadata.message_type == m)
+        }))
+    }
+
+    async fn get_max_turn_number(&self, session_id: &str) -> Result<u64> {
+        Ok(self
+            .records
+            .read()
+            .values()
+            .filter(|t| t.session_id == session_id)
+            .map(|t| t.turn_number)
+            .max()
+            .unwrap_or(0))
+    }
+
+    async fn get_by_turn_number(&self, session_id: &str, turn_number: u64) -> Result<Option<Turn>> {
+        Ok(self
+            .records
+            .read()
+            .values()
+            .find(|t| t.session_id == session_id && t.turn_number == turn_number)
+            .cloned())
+    }
+
+    async fn get_turns_by_ids(&self, ids: &[&str]) -> Result<Vec<Option<Turn>>> {
+        let records = self.records.read();
+        Ok(ids.iter().map(|id| records.get(*id).cloned()).collect())
+    }
+
+    async fn list_recent_by_session(&self, session_id: &str, limit: usize) -> Result<Vec<Turn>> {
+        let mut turns = self.select(|t| t.session_id == session_id, usize::MAX, 0);
+        turns.reverse();
+        turns.truncate(limit);
+        Ok(turns)
+    }
+
+    async fn list_by_turn_range(
+        &self,
+        session_id: &str,
+        start_turn: u64,
+        end_turn: u64,
+    ) -> Result<Vec<Turn>> {
+        Ok(self.select(
+            |t| t.session_id == session_id && (start_turn..=end_turn).contains(&t.turn_number),
+            usize::MAX,
+            0,
+        ))
+    }
+
+    async fn list_by_user_and_time_range(
+        &self,
+        user_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Turn>> {
+        let mut turns = self.select(
+            |t| {
+                t.metadata.user_id.as_deref() == Some(user_id)
+                    && t.metadata.timestamp >= start
+                    && t.metadata.timestamp < end
+            },
+            usize::MAX,
+            0,
+        );
+        turns.sort_by(|a, b| b.metadata.timestamp.cmp(&a.metadata.timestamp));
+        turns.truncate(limit);
+        Ok(turns)
+    }
+
+    async fn bulk_delete_by_session(&self, session_id: &str) -> Result<u64> {
+        let mut records = self.records.write();
+        let before = records.len();
+        records.retain(|_, t| t.session_id != session_id);
+        Ok((before - records.len()) as u64)
+    }
+
+    async fn set_dehydrated(&self, turn_id: &str, dehydrated: &DehydratedData) -> Result<()> {
+        if let Some(turn) = self.records.write().get_mut(turn_id) {
+            turn.dehydrated = Some(dehydrated.clone());
+        }
+        Ok(())
+    }
+
+    async fn delete_batch_before(
+        &self,
+        session_id: &str,
+        before_turn: u64,
+        batch_size: usize,
+    ) -> Result<Vec<String>> {
+        let ids: Vec<String> = self
+            .select(
+                |t| t.session_id == session_id && t.turn_number < before_turn,
+                batch_size,
+                0,
+            )
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        let mut records = self.records.write();
+        Ok(ids
+            .into_iter()
+            .filter(|id| records.remove(id).is_some())
+            .collect())
+    }
+
+    async fn delete_by_ids(&self, ids: &[&str]) -> Result<u64> {
+        let mut records = self.records.write();
+        Ok(ids
+            .iter()
+            .filter(|id| records.remove(**id).is_some())
+            .count() as u64)
+    }
+
+    async fn restore_session_turns(
+        &self,
+        session_id: &str,
+        snapshot_point: u64,
+        turns: &[Turn],
+    ) -> Result<()> {
+        let mut records = self.records.write();
+        records.retain(|id, t| {
+            t.session_id != session_id
+                || (t.turn_number <= snapshot_point && !turns.iter().any(|s| &s.id == id))
+        });
+        for turn in turns {
+            records.insert(turn.id.clone(), turn.clone());
+        }
+        Ok(())
+    }
+
+    async fn move_turns_to_session(
+        &self,
+        from_session_id: &str,
+        to_session_id: &str,
+        first_turn_number: u64,
+    ) -> Result<u64> {
+        let moved = self.select(|t| t.session_id == from_session_id, usize::MAX, 0);
+        let mut records = self.records.write();
+        for (turn_number, turn) in (first_turn_number..).zip(&moved) {
+            if let Some(record) = records.get_mut(&turn.id) {
+                record.session_id = to_session_id.to_string();
+                record.turn_number = turn_number;
+            }
+        }
+        Ok(moved.len() as u64)
+    }
+}
+
+/// 内存记忆仓储
+///
+/// 过滤、排序与 `MemoryRepositoryImpl` 的 SurrealQL 查询保持一致。
+#[derive(Clone, Default)]
+pub struct InMemoryMemoryRepository {
+    memories: InMemoryRepository<Memory>,
+    /// 轮次数据（`delete_by_conversation` 据此找到会话的轮次）
+    turns: InMemoryRepository<Turn>,
+}
+
+impl InMemoryRecord for Memory {
+    fn record_id(&self) -> &str {
+        &self.id
+    }
+
+    /// 按创建时间降序
+    fn list_order(a: &Self, b: &Self) -> Ordering {
+        b.created_at.cmp(&a.created_at)
+    }
+
+    fn tenant_id(&self) -> Option<&str> {
+        Some(&self.tenant_id)
+    }
+}
+
+impl InMemoryMemoryRepository {
+    /// 创建空仓储
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建空仓储，与 `turns` 共享轮次数据
+    pub fn with_turns(turns: InMemoryRepository<Turn>) -> Self {
+        Self {
+            memories: InMemoryRepository::new(),
+            turns,
+        }
+    }
+}
+
+/// 记忆是否满足搜索条件
+fn matches_query(memory: &Memory, query: &MemoryQuery) -> bool {
+    query.user_id.as_ref().is_none_or(|u| &memory.user_id == u)
+        && (query.memory_types.is_empty() || query.memory_types.contains(&memory.memory_type))
+        && (query.tags.is_empty() || query.tags.iter().any(|t| memory.tags.contains(t)))
+        && (query.topics.is_empty() || query.topics.iter().any(|t| memory.topics.contains(t)))
+        && (query.sources.is_empty() || query.sources.contains(&memory.source))
+        && (query.statuses.is_empty() || query.statuses.contains(&memory.status))
+        && query
+            .min_importance
+            .is_none_or(|min| memory.importance >= min)
+        && query.created_after.is_none_or(|t| memory.created_at >= t)
+        && query.created_before.is_none_or(|t| memory.created_at <= t)
+        && query.keyword.as_ref().is_none_or(|k| {
+            let keyword = k.to_lowercase();
+            memory.content.to_lowercase().contains(&keyword)
+                || memory.gist.to_lowercase().contains(&keyword)
+        })
+}
+
+#[async_trait]
+impl MemoryRepository for InMemoryMemoryRepository {
+    async fn create(&self, memory: &Memory) -> Result<Memory> {
+        self.memories.create(memory).await
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<Option<Memory>> {
+        // 读取即视为一次访问
+        Ok(self.memories.records.write().get_mut(id).map(|memory| {
+            memory.access_count += 1;
+            memory.mark_accessed();
+            memory.clone()
+        }))
+    }
+
+    async fn update(&self, id: &str, memory: &Memory) -> Result<Option<Memory>> {
+        self.memories.update(id, memory).await
+    }
+
+    async fn delete(&self, id: &str) -> Result<bool> {
+        self.memories.delete(id).await
+    }
+
+    async fn list(&self, limit: usize, start: usize) -> Result<Vec<Memory>> {
+        self.memories.list(limit, start).await
+    }
+
+    async fn count(&self) -> Result<u64> {
+        self.memories.count().await
+    }
+
+    async fn list_by_user(
+        &self,
+        user_id: &str,
+        memory_type: Option<&str>,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<Memory>> {
+        let memory_type = memory_type.map(str::to_lowercase);
+        Ok(self.memories.select(
+            |m| {
+                m.user_id == user_id
+                    && memory_type
+                        .as_ref()
+                        .is_none_or(|t| &m.memory_type.to_string() == t)
+            },
+            limit,
+            start,
+        ))
+    }
+
+    async fn count_by_user(&self, user_id: &str) -> Result<u64> {
+        Ok(self.memories.count_where(|m| m.user_id == user_id))
+    }
+
+    async fn search(&self, query: &MemoryQuery) -> Result<Vec<Memory>> {
+        let start = query.page.saturating_sub(1) as usize * query.page_size as usize;
+        Ok(self
+            .memories
+            .select(|m| matches_query(m, query), query.page_size as usize, start))
+    }
+
+    async fn get_stats(&self, user_id: &str) -> Result<MemoryStats> {
+        let records = self.memories.records.read();
+        let memories: Vec<&Memory> = records.values().filter(|m| m.user_id == user_id).collect();
+        let count_type =
+            |t: MemoryType| memories.iter().filter(|m| m.memory_type == t).count() as u64;
+
+        let total_count = memories.len() as u64;
+        let active_count = memories
+            .iter()
+            .filter(|m| m.status == MemoryStatus::Active)
+            .count() as u64;
+        let avg_importance = if memories.is_empty() {
+            0.0
+        } else {
+            memories.iter().map(|m| m.importance).sum::<f32>() / memories.len() as f32
+        };
+
+        Ok(MemoryStats {
+            user_id: user_id.to_string(),
+            total_count,
+            episodic_count: count_type(MemoryType::Episodic),
+            semantic_count: count_type(MemoryType::Semantic),
+            procedural_count: count_type(MemoryType::Procedural),
+            profile_count: count_type(MemoryType::Profile),
+            active_count,
+            archived_count: total_count.saturating_sub(active_count),
+            avg_importance,
+            high_importance_count: memories.iter().filter(|m| m.importance > 0.7).count() as u64,
+            storage_size_bytes: 0,
+        })
+    }
+
+    async fn increment_access_count(&self, id: &str) -> Result<()> {
+        if let Some(memory) = self.memories.records.write().get_mut(id) {
+            memory.access_count += 1;
+            memory.mark_accessed();
+        }
+        Ok(())
+    }
+
+    async fn list_hot(&self, user_id: &str, limit: usize) -> Result<Vec<Memory>> {
+        let mut memories = self.memories.select(
+            |m| m.user_id == user_id && m.status == MemoryStatus::Active,
+            usize::MAX,
+            0,
+        );
+        memories.sort_by_key(|m| std::cmp::Reverse(m.access_count));
+        memories.truncate(limit);
+        Ok(memories)
+    }
+
+    async fn list_by_source_id(&self, source_id: &str) -> Result<Vec<Memory>> {
+        let mut memories =
+            self.memories
+                .select(|m| m.source_id.as_deref() == Some(source_id), usize::MAX, 0);
+        memories.sort_by_key(|m| m.created_at);
+        Ok(memories)
+    }
+
+    async fn delete_by_conversation(&self, session_id: &str) -> Result<u64> {
+        let turn_ids: Vec<String> = self
+            .turns
+            .select(|t| t.session_id == session_id, usize::MAX, 0)
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+
+        let mut memories = self.memories.records.write();
+        let before = memories.len();
+        memories.retain(|_, m| {
+            m.source != MemorySource::Conversation
+                || !m
+                    .source_id
+                    .as_ref()
+                    .is_some_and(|id| id == session_id || turn_ids.contains(id))
+        });
+        Ok((before - memories.len()) as u64)
+    }
+
+    async fn search_by_embedding(
+        &self,
+        query_vector: &[f32],
+        query: &MemoryQuery,
+        min_similarity: f32,
+    ) -> Result<Vec<(Memory, f32)>> {
+        let memories = self
+            .memories
+            .select(|m| matches_query(m, query), usize::MAX, 0);
+        Ok(rank_by_embedding(
+            query_vector,
+            memories,
+            query,
+            min_similarity,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_memory_repository_crud() {
+        let repo = InMemoryRepository::<Turn>::new();
+        for n in [2, 1, 3] {
+            repo.create(&Turn::new("session_1", n, "hello"))
+                .await
+                .unwrap();
+        }
+        let other = repo.create(&Turn::new("session_2", 1, "hi")).await.unwrap();
+
+        let turns = repo.list_by_session("session_1", 10, 0).await.unwrap();
+        let numbers: Vec<u64> = turns.iter().map(|t| t.turn_number).collect();
+        assert_eq!(numbers, vec![1, 2, 3]);
+        assert_eq!(
+            repo.list_by_session("session_1", 1, 1).await.unwrap()[0].turn_number,
+            2
+        );
+        assert_eq!(repo.count_by_session("session_1").await.unwrap(), 3);
+        assert!(matches!(
+            repo.create(&other).await,
+            Err(AppError::Conflict(_))
+        ));
+
+        assert!(repo.delete(&other.id).await.unwrap());
+        assert!(repo.get_by_id(&other.id).await.unwrap().is_none());
+        assert!(repo.update(&other.id, &other).await.unwrap().is_none());
+        assert_eq!(repo.count().await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_memory_repository() {
+        let repo = InMemoryMemoryRepository::new();
+        let mut important = Memory::new(
+            "user_1",
+            MemoryType::Semantic,
+            "Rust ownership rules",
+            MemorySource::Conversation,
+        );
+        important.importance = 0.9;
+        let mut minor = Memory::new(
+            "user_1",
+            MemoryType::Episodic,
+            "Had lunch",
+            MemorySource::Conversation,
+        );
+        minor.importance = 0.3;
+        repo.create(&important).await.unwrap();
+        repo.create(&minor).await.unwrap();
+        repo.create(&Memory::new(
+            "user_2",
+            MemoryType::Semantic,
+            "Rust lifetimes",
+            MemorySource::Research,
+        ))
+        .await
+        .unwrap();
+
+        let mut query = MemoryQuery::new().for_user("user_1");
+        query.keyword = Some("rust".to_string());
+        query.page = 1;
+        query.page_size = 10;
+        let found = repo.search(&query).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, important.id);
+
+        let by_type = repo
+            .list_by_user("user_1", Some("Episodic"), 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(by_type.len(), 1);
+
+        repo.get_by_id(&minor.id).await.unwrap();
+        let hot = repo.list_hot("user_1", 1).await.unwrap();
+        assert_eq!(hot[0].id, minor.id);
+
+        let mut embedded = important.clone();
+        embedded.embedding = Some(vec![1.0, 0.0]);
+        repo.update(&important.id, &embedded).await.unwrap();
+        let similar = repo
+            .search_by_embedding(
+                &[1.0, 0.1],
+                &MemoryQuery::new().for_user("user_1").with_pagination(1, 5),
+                0.5,
+            )
+            .await
+            .unwrap();
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].0.id, important.id);
+        assert!(
+            repo.search_by_embedding(
+                &[1.0, 0.1],
+                &MemoryQuery::new().for_user("user_2").with_pagination(1, 5),
+                0.5,
+            )
+            .await
+            .unwrap()
+            .is_empty()
+        );
+
+        let stats = repo.get_stats("user_1").await.unwrap();
+        assert_eq!(stats.total_count, 2);
+        assert_eq!(stats.semantic_count, 1);
+        assert_eq!(stats.high_importance_count, 1);
+        assert!((stats.avg_importance - 0.6).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_by_min_importance() {
+        let repo = InMemoryMemoryRepository::new();
+        let mut ids = Vec::new();
+        for importance in [0.2, 0.7, 0.9] {
+            let mut memory = Memory::new(
+                "user_1",
+                MemoryType::Semantic,
+                "content",
+                MemorySource::Conversation,
+            );
+            memory.importance = importance;
+            ids.push(repo.create(&memory).await.unwrap().id);
+        }
+
+        let query = MemoryQuery::new()
+            .for_user("user_1")
+            .with_min_importance(0.7)
+            .with_pagination(1, 10);
+        let mut found: Vec<String> = repo
+            .search(&query)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+        found.sort();
+        let mut expected = ids[1..].to_vec();
+        expected.sort();
+        // 阈值本身包含在内
+        assert_eq!(found, expected);
+
+        let unfiltered = MemoryQuery::new().for_user("user_1").with_pagination(1, 10);
+        assert_eq!(repo.search(&unfiltered).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_delete_by_conversation_matches_turn_and_legacy_session_ids() {
+        let turns = InMemoryRepository::<Turn>::new();
+        let turn = turns
+            .create(&Turn::new("session_1", 1, "hello"))
+            .await
+            .unwrap();
+        let repo = InMemoryMemoryRepository::with_turns(turns);
+
+        let memory = |source_id: &str| {
+            let mut memory = Memory::new(
+                "user_1",
+                MemoryType::Episodic,
+                "content",
+                MemorySource::Conversation,
+            );
+            memory.source_id = Some(source_id.to_string());
+            memory
+        };
+        repo.create(&memory(&turn.id)).await.unwrap();
+        repo.create(&memory("session_1")).await.unwrap();
+        let other = repo.create(&memory("session_2")).await.unwrap();
+
+        assert_eq!(repo.delete_by_conversation("session_1").await.unwrap(), 2);
+        let remaining = repo.list_by_user("user_1", None, 10, 0).await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, other.id);
+    }
+}
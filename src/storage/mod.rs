@@ -17,4 +17,11 @@ pub mod repository;
 #[cfg(not(feature = "surrealdb"))]
 pub mod repository;
 
+#[cfg(feature = "surrealdb")]
+pub mod stats;
+
+pub mod compression;
+
+pub mod in_memory;
+
 pub mod factory;
@@ -1,11 +1,13 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::marker::PhantomData;
 use surrealdb::{Surreal, engine::any::Any};
 
 use crate::error::Result;
 use crate::models::index_record::IndexRecord;
-use crate::models::session::Session;
-use crate::models::turn::Turn;
+use crate::models::session::{Session, SessionConfig, SessionWithStats};
+use crate::models::turn::{DehydratedData, MessageType, Turn};
+use crate::storage::compression;
 use crate::storage::surrealdb::SurrealPool;
 
 /// 仓储 trait
@@ -55,6 +57,217 @@ pub trait Repository<T: Clone + Send + Sync> {
     }
 }
 
+/// 会话仓储（`Repository<Session>` 之外的会话查询）
+#[async_trait]
+pub trait SessionStore: Repository<Session> + Send + Sync {
+    /// 获取会话及其轮次统计（单次查询）
+    async fn get_with_stats(&self, id: &str) -> Result<Option<SessionWithStats>>;
+
+    /// 仅更新会话配置，不修改其他字段
+    async fn update_config(&self, id: &str, config: &SessionConfig) -> Result<()>;
+
+    /// 原子地按 `delta` 增减会话的 `stats.total_turns`（不低于 0），不修改其他字段
+    async fn adjust_turn_count(&self, id: &str, delta: i64) -> Result<()>;
+
+    /// 原子地累加会话的 `stats.total_tokens` 与 `stats.storage_size`，
+    /// 并将 `last_active_at` 推进到不早于给定时间，不修改其他字段
+    async fn add_stats(
+        &self,
+        id: &str,
+        total_tokens: u64,
+        storage_size: u64,
+        last_active_at: DateTime<Utc>,
+    ) -> Result<()>;
+
+    /// 仅更新会话名称，不修改其他字段
+    async fn update_name(&self, id: &str, name: &str) -> Result<()>;
+
+    /// 统计租户在最近 `active_within_secs` 秒内活跃的会话数量
+    async fn count_active(&self, tenant_id: &str, active_within_secs: u64) -> Result<u64>;
+
+    /// 列出租户的会话，指定 `status` 时只返回该状态（不区分大小写）的会话
+    async fn list_by_tenant_and_status(
+        &self,
+        tenant_id: &str,
+        status: Option<&str>,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<Session>>;
+
+    /// 统计租户的会话数量，指定 `status` 时只统计该状态（不区分大小写）的会话
+    async fn count_by_tenant_and_status(
+        &self,
+        tenant_id: &str,
+        status: Option<&str>,
+    ) -> Result<u64>;
+
+    /// 列出存在会话的所有租户 ID
+    async fn list_tenant_ids(&self) -> Result<Vec<String>>;
+}
+
+/// 轮次仓储（`Repository<Turn>` 之外的轮次查询与批量操作）
+#[async_trait]
+pub trait TurnStore: Repository<Turn> + Send + Sync {
+    /// 分页获取会话的轮次（按 turn_number 升序），可按消息类型过滤
+    async fn list_by_session_filtered(
+        &self,
+        session_id: &str,
+        message_type: Option<MessageType>,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<Turn>>;
+
+    /// 统计会话的轮次数量，可按消息类型过滤
+    async fn count_by_session_filtered(
+        &self,
+        session_id: &str,
+        message_type: Option<MessageType>,
+    ) -> Result<u64>;
+
+    /// 获取指定会话的最大 turn_number
+    async fn get_max_turn_number(&self, session_id: &str) -> Result<u64>;
+
+    /// 根据会话内的 turn_number 获取轮次
+    async fn get_by_turn_number(&self, session_id: &str, turn_number: u64) -> Result<Option<Turn>>;
+
+    /// 批量获取轮次，返回结果与输入 `ids` 顺序一致，不存在的 ID 对应 `None`
+    async fn get_turns_by_ids(&self, ids: &[&str]) -> Result<Vec<Option<Turn>>>;
+
+    /// 获取会话最近的 `limit` 个轮次（按 turn_number 降序）
+    async fn list_recent_by_session(&self, session_id: &str, limit: usize) -> Result<Vec<Turn>>;
+
+    /// 获取会话内 turn_number 位于 `[start_turn, end_turn]` 的轮次（按 turn_number 升序）
+    async fn list_by_turn_range(
+        &self,
+        session_id: &str,
+        start_turn: u64,
+        end_turn: u64,
+    ) -> Result<Vec<Turn>>;
+
+    /// 获取用户在 `[start, end)` 时间窗口内的轮次（按 `metadata.timestamp` 降序，最多 `limit` 个）
+    async fn list_by_user_and_time_range(
+        &self,
+        user_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Turn>>;
+
+    /// 删除会话下的所有轮次，返回删除数量
+    async fn bulk_delete_by_session(&self, session_id: &str) -> Result<u64>;
+
+    /// 写入轮次的脱水数据
+    async fn set_dehydrated(&self, turn_id: &str, dehydrated: &DehydratedData) -> Result<()>;
+
+    /// 删除会话中 turn_number 小于 `before_turn` 的最早 `batch_size` 个轮次，返回被删除轮次的 ID
+    async fn delete_batch_before(
+        &self,
+        session_id: &str,
+        before_turn: u64,
+        batch_size: usize,
+    ) -> Result<Vec<String>>;
+
+    /// 按 ID 批量删除轮次，返回删除数量
+    async fn delete_by_ids(&self, ids: &[&str]) -> Result<u64>;
+
+    /// 将会话轮次恢复为给定的快照轮次
+    ///
+    /// 删除 `snapshot_point` 之后创建的轮次以及快照中的同 ID 轮次，再重新写入快照轮次。
+    async fn restore_session_turns(
+        &self,
+        session_id: &str,
+        snapshot_point: u64,
+        turns: &[Turn],
+    ) -> Result<()>;
+
+    /// 将会话的全部轮次移动到另一个会话，返回移动数量
+    ///
+    /// 轮次按原 turn_number 升序依次重新编号为 `first_turn_number`、`first_turn_number + 1`……
+    async fn move_turns_to_session(
+        &self,
+        from_session_id: &str,
+        to_session_id: &str,
+        first_turn_number: u64,
+    ) -> Result<u64>;
+}
+
+/// 发送 SQL 请求，瞬时连接错误按 `database.max_retries` / `database.base_delay_ms` 重试
+async fn send_with_retry(pool: &SurrealPool, url: &str, query: &str) -> Result<reqwest::Response> {
+    let config = pool.config();
+    SurrealPool::with_retry(
+        || {
+            let request = pool
+                .http_client()
+                .post(url)
+                .header("surreal-ns", &config.namespace)
+                .header("surreal-db", &config.database)
+                .header("Accept", "application/json")
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .basic_auth(&config.username, Some(&config.password))
+                .body(query.to_string());
+            Box::pin(async move {
+                request.send().await.map_err(|e| {
+                    crate::error::AppError::Database(format!("HTTP request failed: {}", e))
+                })
+            })
+        },
+        config.max_retries,
+        config.base_delay_ms,
+    )
+    .await
+}
+
+/// 通过 HTTP 执行 SurrealDB 查询
+pub(crate) async fn execute_query(
+    pool: &SurrealPool,
+    query: &str,
+) -> Result<Vec<serde_json::Value>> {
+    let config = pool.config();
+    let url = format!(
+        "{}/sql",
+        config.url.replace("ws://", "http://").replace("/rpc", "")
+    );
+
+    tracing::debug!(
+        "Sending HTTP request to SurrealDB: url={}, query={}",
+        url,
+        query
+    );
+
+    let response = send_with_retry(pool, &url, query).await?;
+
+    if !response.status().is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(crate::error::AppError::Database(format!(
+            "SurrealDB error: {}",
+            error_text
+        )));
+    }
+
+    let response_text = response.text().await.unwrap_or_default();
+    serde_json::from_str(&response_text)
+        .map_err(|e| crate::error::AppError::Database(format!("Failed to parse response: {}", e)))
+}
+
+/// 取出首条语句的结果行，任一语句执行失败（`status` 为 `ERR`）时返回错误
+pub(crate) fn statement_rows(results: Vec<serde_json::Value>) -> Result<Vec<serde_json::Value>> {
+    if let Some(failed) = results
+        .iter()
+        .find(|item| item.get("status").and_then(|s| s.as_str()) == Some("ERR"))
+    {
+        return Err(crate::error::AppError::Database(format!(
+            "SurrealDB statement failed: {}",
+            failed.get("result").unwrap_or(&serde_json::Value::Null)
+        )));
+    }
+
+    Ok(results
+        .into_iter()
+        .next()
+        .and_then(|item| item.get("result").and_then(|r| r.as_array()).cloned())
+        .unwrap_or_default())
+}
+
 /// 会话仓储实现
 #[derive(Clone)]
 pub struct SessionRepository {
@@ -71,6 +284,149 @@ impl SessionRepository {
     }
 }
 
+#[async_trait]
+impl SessionStore for SessionRepository {
+    /// 获取会话及其轮次统计（单次查询）
+    async fn get_with_stats(&self, id: &str) -> Result<Option<SessionWithStats>> {
+        let query = format!(
+            "SELECT *, \
+             (SELECT count() FROM turn WHERE session_id = '{id}' GROUP ALL)[0].count AS turn_count, \
+             (SELECT metadata.timestamp AS timestamp FROM turn WHERE session_id = '{id}' ORDER BY turn_number DESC LIMIT 1)[0].timestamp AS last_turn_at \
+             FROM session WHERE id = {id}",
+            id = id
+        );
+        let rows = statement_rows(execute_query(&self.pool, &query).await?)?;
+
+        rows.into_iter()
+            .next()
+            .map(|row| {
+                serde_json::from_value(row).map_err(|e| {
+                    crate::error::AppError::Database(format!(
+                        "Failed to deserialize session stats: {}",
+                        e
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// 仅更新会话配置，不修改其他字段
+    async fn update_config(&self, id: &str, config: &SessionConfig) -> Result<()> {
+        let query = format!(
+            "UPDATE session SET config = {} WHERE id = {}",
+            serde_json::to_string(config)?,
+            id
+        );
+        execute_query(&self.pool, &query).await?;
+        Ok(())
+    }
+
+    async fn adjust_turn_count(&self, id: &str, delta: i64) -> Result<()> {
+        execute_query(&self.pool, &adjust_turn_count_query(id, delta)).await?;
+        Ok(())
+    }
+
+    async fn add_stats(
+        &self,
+        id: &str,
+        total_tokens: u64,
+        storage_size: u64,
+        last_active_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let query = add_stats_query(id, total_tokens, storage_size, last_active_at);
+        execute_query(&self.pool, &query).await?;
+        Ok(())
+    }
+
+    /// 仅更新会话名称，不修改其他字段
+    async fn update_name(&self, id: &str, name: &str) -> Result<()> {
+        let query = format!(
+            "UPDATE session SET name = {} WHERE id = {}",
+            serde_json::to_string(name)?,
+            id
+        );
+        execute_query(&self.pool, &query).await?;
+        Ok(())
+    }
+
+    /// 统计租户在最近 `active_within_secs` 秒内活跃的会话数量
+    async fn count_active(&self, tenant_id: &str, active_within_secs: u64) -> Result<u64> {
+        let query = format!(
+            "SELECT count() FROM session WHERE tenant_id = '{}' \
+             AND <datetime> last_active_at > time::now() - duration::from::secs({}) \
+             AND string::lowercase(status) = 'active' GROUP ALL",
+            tenant_id, active_within_secs
+        );
+        let results = execute_query(&self.pool, &query).await?;
+
+        Ok(results
+            .iter()
+            .filter_map(|item| item.get("result").and_then(|r| r.as_array()))
+            .filter_map(|rows| rows.first())
+            .find_map(|row| row.get("count").and_then(|v| v.as_u64()))
+            .unwrap_or(0))
+    }
+
+    /// 列出租户的会话，指定 `status` 时只返回该状态（不区分大小写）的会话
+    async fn list_by_tenant_and_status(
+        &self,
+        tenant_id: &str,
+        status: Option<&str>,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<Session>> {
+        let query = list_by_tenant_query(tenant_id, status, limit, start);
+        let results = execute_query(&self.pool, &query).await?;
+
+        let mut sessions = Vec::new();
+        for item in &results {
+            if let Some(result) = item.get("result").and_then(|r| r.as_array()) {
+                for session_json in result {
+                    match serde_json::from_value(session_json.clone()) {
+                        Ok(session) => sessions.push(session),
+                        Err(e) => tracing::warn!("Failed to deserialize session: {}", e),
+                    }
+                }
+            }
+        }
+
+        Ok(sessions)
+    }
+
+    /// 统计租户的会话数量，指定 `status` 时只统计该状态（不区分大小写）的会话
+    async fn count_by_tenant_and_status(
+        &self,
+        tenant_id: &str,
+        status: Option<&str>,
+    ) -> Result<u64> {
+        let query = count_by_tenant_query(tenant_id, status);
+        let rows = statement_rows(execute_query(&self.pool, &query).await?)?;
+
+        Ok(rows
+            .first()
+            .and_then(|row| row.get("count"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0))
+    }
+
+    /// 列出存在会话的所有租户 ID
+    async fn list_tenant_ids(&self) -> Result<Vec<String>> {
+        let results = execute_query(
+            &self.pool,
+            "SELECT tenant_id FROM session GROUP BY tenant_id",
+        )
+        .await?;
+
+        Ok(results
+            .iter()
+            .filter_map(|item| item.get("result").and_then(|r| r.as_array()))
+            .flatten()
+            .filter_map(|row| row.get("tenant_id").and_then(|v| v.as_str()))
+            .map(str::to_string)
+            .collect())
+    }
+}
+
 #[async_trait]
 impl Repository<Session> for SessionRepository {
     async fn create(&self, session: &Session) -> Result<Session> {
@@ -88,8 +444,11 @@ impl Repository<Session> for SessionRepository {
             None => "NONE".to_string(),
         };
 
+        let config_str = serde_json::to_string(&session.config)?;
+        let stats_str = serde_json::to_string(&session.stats)?;
+
         let query = format!(
-            "CREATE session SET tenant_id = '{}', name = '{}', description = {}, created_at = '{}', last_active_at = '{}', status = '{}', metadata = {}",
+            "CREATE session SET tenant_id = '{}', name = '{}', description = {}, created_at = '{}', last_active_at = '{}', status = '{}', metadata = {}, config = {}, stats = {}",
             session.tenant_id,
             session.name,
             description_str,
@@ -97,6 +456,8 @@ impl Repository<Session> for SessionRepository {
             session.last_active_at.to_rfc3339(),
             session.status,
             metadata_str,
+            config_str,
+            stats_str,
         );
 
         // Execute via HTTP to avoid SDK serialization issues
@@ -112,19 +473,7 @@ impl Repository<Session> for SessionRepository {
             query
         );
 
-        let response = self
-            .pool
-            .http_client()
-            .post(&url)
-            .header("surreal-ns", &config.namespace)
-            .header("surreal-db", &config.database)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .basic_auth(&config.username, Some(&config.password))
-            .body(query.clone())
-            .send()
-            .await
-            .map_err(|e| crate::error::AppError::Database(format!("HTTP request failed: {}", e)))?;
+        let response = send_with_retry(&self.pool, &url, &query).await?;
 
         tracing::debug!("SurrealDB response status: {}", response.status());
 
@@ -156,19 +505,7 @@ impl Repository<Session> for SessionRepository {
             query
         );
 
-        let response = self
-            .pool
-            .http_client()
-            .post(&url)
-            .header("surreal-ns", &config.namespace)
-            .header("surreal-db", &config.database)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .basic_auth(&config.username, Some(&config.password))
-            .body(query.clone())
-            .send()
-            .await
-            .map_err(|e| crate::error::AppError::Database(format!("HTTP request failed: {}", e)))?;
+        let response = send_with_retry(&self.pool, &url, &query).await?;
 
         tracing::debug!("SurrealDB response status: {}", response.status());
 
@@ -209,12 +546,14 @@ impl Repository<Session> for SessionRepository {
     async fn update(&self, id: &str, session: &Session) -> Result<Option<Session>> {
         let session = session.clone();
         let query = format!(
-            "UPDATE session SET tenant_id = '{}', name = '{}', description = '{}', last_active_at = '{}', status = '{}' WHERE id = {}",
+            "UPDATE session SET tenant_id = '{}', name = '{}', description = '{}', last_active_at = '{}', status = '{}', config = {}, stats = {} WHERE id = {}",
             session.tenant_id,
             session.name,
             session.description.clone().unwrap_or_default(),
             session.last_active_at.to_rfc3339(),
             session.status,
+            serde_json::to_string(&session.config)?,
+            serde_json::to_string(&session.stats)?,
             id,
         );
 
@@ -231,19 +570,7 @@ impl Repository<Session> for SessionRepository {
             query
         );
 
-        let response = self
-            .pool
-            .http_client()
-            .post(&url)
-            .header("surreal-ns", &config.namespace)
-            .header("surreal-db", &config.database)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .basic_auth(&config.username, Some(&config.password))
-            .body(query.clone())
-            .send()
-            .await
-            .map_err(|e| crate::error::AppError::Database(format!("HTTP request failed: {}", e)))?;
+        let response = send_with_retry(&self.pool, &url, &query).await?;
 
         tracing::debug!("SurrealDB response status: {}", response.status());
 
@@ -274,19 +601,7 @@ impl Repository<Session> for SessionRepository {
             query
         );
 
-        let response = self
-            .pool
-            .http_client()
-            .post(&url)
-            .header("surreal-ns", &config.namespace)
-            .header("surreal-db", &config.database)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .basic_auth(&config.username, Some(&config.password))
-            .body(query.clone())
-            .send()
-            .await
-            .map_err(|e| crate::error::AppError::Database(format!("HTTP request failed: {}", e)))?;
+        let response = send_with_retry(&self.pool, &url, &query).await?;
 
         tracing::debug!("SurrealDB response status: {}", response.status());
 
@@ -334,19 +649,7 @@ impl Repository<Session> for SessionRepository {
             query
         );
 
-        let response = self
-            .pool
-            .http_client()
-            .post(&url)
-            .header("surreal-ns", &config.namespace)
-            .header("surreal-db", &config.database)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .basic_auth(&config.username, Some(&config.password))
-            .body(query.clone())
-            .send()
-            .await
-            .map_err(|e| crate::error::AppError::Database(format!("HTTP request failed: {}", e)))?;
+        let response = send_with_retry(&self.pool, &url, &query).await?;
 
         tracing::debug!("SurrealDB response status: {}", response.status());
 
@@ -406,19 +709,7 @@ impl Repository<Session> for SessionRepository {
             query
         );
 
-        let response = self
-            .pool
-            .http_client()
-            .post(&url)
-            .header("surreal-ns", &config.namespace)
-            .header("surreal-db", &config.database)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .basic_auth(&config.username, Some(&config.password))
-            .body(query)
-            .send()
-            .await
-            .map_err(|e| crate::error::AppError::Database(format!("HTTP request failed: {}", e)))?;
+        let response = send_with_retry(&self.pool, &url, query).await?;
 
         tracing::debug!("SurrealDB response status: {}", response.status());
 
@@ -451,6 +742,16 @@ impl Repository<Session> for SessionRepository {
         Ok(0)
     }
 
+    async fn list_by_tenant(
+        &self,
+        tenant_id: &str,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<Session>> {
+        self.list_by_tenant_and_status(tenant_id, None, limit, start)
+            .await
+    }
+
     async fn count_by_tenant(&self, tenant_id: &str) -> Result<u64> {
         let query = format!(
             "SELECT count() FROM session WHERE tenant_id = '{}' GROUP ALL",
@@ -470,19 +771,7 @@ impl Repository<Session> for SessionRepository {
             query
         );
 
-        let response = self
-            .pool
-            .http_client()
-            .post(&url)
-            .header("surreal-ns", &config.namespace)
-            .header("surreal-db", &config.database)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .basic_auth(&config.username, Some(&config.password))
-            .body(query.clone())
-            .send()
-            .await
-            .map_err(|e| crate::error::AppError::Database(format!("HTTP request failed: {}", e)))?;
+        let response = send_with_retry(&self.pool, &url, &query).await?;
 
         tracing::debug!("SurrealDB response status: {}", response.status());
 
@@ -516,31 +805,198 @@ impl Repository<Session> for SessionRepository {
     }
 }
 
+/// 按租户过滤会话的条件，指定 `status` 时追加状态过滤
+fn tenant_status_condition(tenant_id: &str, status: Option<&str>) -> String {
+    let tenant_id = tenant_id.replace("'", "\\'");
+    match status {
+        Some(status) => format!(
+            "tenant_id = '{}' AND string::lowercase(status) = '{}'",
+            tenant_id,
+            status.to_lowercase().replace("'", "\\'")
+        ),
+        None => format!("tenant_id = '{}'", tenant_id),
+    }
+}
+
+/// 按租户列出会话，指定 `status` 时追加状态过滤
+fn list_by_tenant_query(
+    tenant_id: &str,
+    status: Option<&str>,
+    limit: usize,
+    start: usize,
+) -> String {
+    format!(
+        "SELECT * FROM session WHERE {} ORDER BY created_at DESC LIMIT {} START {}",
+        tenant_status_condition(tenant_id, status),
+        limit,
+        start
+    )
+}
+
+/// 统计租户的会话数量，与 `list_by_tenant_query` 使用相同的过滤条件
+fn count_by_tenant_query(tenant_id: &str, status: Option<&str>) -> String {
+    format!(
+        "SELECT count() FROM session WHERE {} GROUP ALL",
+        tenant_status_condition(tenant_id, status)
+    )
+}
+
+/// 在数据库端增减会话轮次数，避免读取-修改-写回整行覆盖并发修改
+fn adjust_turn_count_query(id: &str, delta: i64) -> String {
+    format!(
+        "UPDATE session SET stats.total_turns = math::max([stats.total_turns + {}, 0]) WHERE id = {}",
+        delta, id
+    )
+}
+
+/// 在数据库端累加会话的 Token 数与存储大小，`last_active_at` 只前进不后退
+fn add_stats_query(
+    id: &str,
+    total_tokens: u64,
+    storage_size: u64,
+    last_active_at: DateTime<Utc>,
+) -> String {
+    let last_active_at = last_active_at.to_rfc3339();
+    format!(
+        "UPDATE session SET stats.total_tokens = stats.total_tokens + {}, \
+         stats.storage_size = stats.storage_size + {}, \
+         last_active_at = IF <datetime> last_active_at < <datetime> '{}' THEN '{}' ELSE last_active_at END \
+         WHERE id = {}",
+        total_tokens, storage_size, last_active_at, last_active_at, id
+    )
+}
+
+/// 按会话过滤轮次的条件，`message_type` 存储为变体名（如 `'User'`）
+fn session_turn_condition(session_id: &str, message_type: Option<&MessageType>) -> String {
+    let filter = match message_type {
+        Some(MessageType::User) => " AND metadata.message_type = 'User'",
+        Some(MessageType::Assistant) => " AND metadata.message_type = 'Assistant'",
+        Some(MessageType::System) => " AND metadata.message_type = 'System'",
+        None => "",
+    };
+    format!(
+        "session_id = '{}'{}",
+        session_id.replace("'", "\\'"),
+        filter
+    )
+}
+
+/// 生成按会话分页查询轮次的语句
+fn list_by_session_query(
+    session_id: &str,
+    message_type: Option<&MessageType>,
+    limit: usize,
+    start: usize,
+) -> String {
+    format!(
+        "SELECT * FROM turn WHERE {} ORDER BY turn_number ASC LIMIT {} START {}",
+        session_turn_condition(session_id, message_type),
+        limit,
+        start
+    )
+}
+
+/// 统计会话轮次数量，与 `list_by_session_query` 使用相同的过滤条件
+fn count_by_session_query(session_id: &str, message_type: Option<&MessageType>) -> String {
+    format!(
+        "SELECT count() FROM turn WHERE {} GROUP ALL",
+        session_turn_condition(session_id, message_type)
+    )
+}
+
 /// 轮次仓储实现
 #[derive(Clone)]
 pub struct TurnRepository {
-    db: Surreal<Any>,
     pool: SurrealPool,
     _marker: PhantomData<Turn>,
 }
 
 impl TurnRepository {
-    pub fn new(db: Surreal<Any>, pool: SurrealPool) -> Self {
+    pub fn new(pool: SurrealPool) -> Self {
         Self {
-            db,
             pool,
             _marker: PhantomData,
         }
     }
 
+    /// 执行单条语句并返回其结果行
+    async fn query_rows(&self, query: &str) -> Result<Vec<serde_json::Value>> {
+        statement_rows(execute_query(&self.pool, query).await?)
+    }
+
+    /// 执行查询并反序列化为轮次，无法解析的记录会被跳过
+    async fn query_turns(&self, query: &str) -> Result<Vec<Turn>> {
+        let mut turns = Vec::new();
+        for json in self.query_rows(query).await? {
+            match turn_from_record(json) {
+                Ok(turn) => turns.push(turn),
+                Err(e) => tracing::warn!("Skipping turn record: {}", e),
+            }
+        }
+
+        Ok(turns)
+    }
+
+    /// 执行计数查询（`SELECT count() ... GROUP ALL`）
+    async fn query_count(&self, query: &str) -> Result<u64> {
+        Ok(self
+            .query_rows(query)
+            .await?
+            .first()
+            .and_then(|row| row.get("count"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0))
+    }
+
+    /// 生成 `raw_content` 的 SET 片段，启用压缩且超过阈值时改写 `compressed_content`
+    fn content_assignments(&self, raw_content: &str) -> Result<String> {
+        let config = &self.pool.config().compression;
+        if compression::should_compress(config, raw_content) {
+            return Ok(format!(
+                "raw_content = '', compressed_content = '{}', compression = '{}'",
+                compression::compress(raw_content)?,
+                compression::GZIP
+            ));
+        }
+
+        Ok(format!(
+            "raw_content = '{}', compressed_content = NONE, compression = NONE",
+            raw_content.replace("'", "\\'")
+        ))
+    }
+}
+
+#[async_trait]
+impl TurnStore for TurnRepository {
+    /// 分页获取会话的轮次（按 turn_number 升序），可按消息类型过滤
+    async fn list_by_session_filtered(
+        &self,
+        session_id: &str,
+        message_type: Option<MessageType>,
+        limit: usize,
+        start: usize,
+    ) -> Result<Vec<Turn>> {
+        let query = list_by_session_query(session_id, message_type.as_ref(), limit, start);
+        self.query_turns(&query).await
+    }
+
+    /// 统计会话的轮次数量，可按消息类型过滤
+    async fn count_by_session_filtered(
+        &self,
+        session_id: &str,
+        message_type: Option<MessageType>,
+    ) -> Result<u64> {
+        let query = count_by_session_query(session_id, message_type.as_ref());
+        self.query_count(&query).await
+    }
+
     /// 获取指定会话的最大 turn_number
-    pub async fn get_max_turn_number(&self, session_id: &str) -> Result<u64> {
+    async fn get_max_turn_number(&self, session_id: &str) -> Result<u64> {
         let query = format!(
             "SELECT turn_number FROM turn WHERE session_id = '{}' ORDER BY turn_number DESC LIMIT 1",
             session_id
         );
-        let mut response = self.db.query(query).await?;
-        let results: Vec<serde_json::Value> = response.take(0)?;
+        let results = self.query_rows(&query).await?;
 
         if let Some(json) = results.first() {
             if let Some(turn_number) = json.get("turn_number").and_then(|v| v.as_u64()) {
@@ -551,6 +1007,236 @@ impl TurnRepository {
         Ok(0)
     }
 
+    /// 根据会话内的 turn_number 获取轮次
+    async fn get_by_turn_number(&self, session_id: &str, turn_number: u64) -> Result<Option<Turn>> {
+        let query = format!(
+            "SELECT * FROM turn WHERE session_id = '{}' AND turn_number = {} LIMIT 1",
+            session_id.replace("'", "\\'"),
+            turn_number
+        );
+        let results = self.query_rows(&query).await?;
+
+        if let Some(json) = results.first() {
+            return turn_from_record(json.clone()).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    /// 批量获取轮次（单次查询）
+    ///
+    /// 返回结果与输入 `ids` 顺序一致，不存在的 ID 对应 `None`。
+    async fn get_turns_by_ids(&self, ids: &[&str]) -> Result<Vec<Option<Turn>>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let records: Vec<String> = ids.iter().map(|id| turn_record_id(id)).collect();
+        let query = format!("SELECT * FROM turn WHERE id IN [{}]", records.join(", "));
+        let found: std::collections::HashMap<_, _> = self
+            .query_turns(&query)
+            .await?
+            .into_iter()
+            .map(|turn| (normalize_turn_id(&turn.id).to_string(), turn))
+            .collect();
+
+        Ok(ids
+            .iter()
+            .map(|id| found.get(normalize_turn_id(id)).cloned())
+            .collect())
+    }
+
+    /// 获取会话最近的 `limit` 个轮次（按 turn_number 降序）
+    async fn list_recent_by_session(&self, session_id: &str, limit: usize) -> Result<Vec<Turn>> {
+        let query = format!(
+            "SELECT * FROM turn WHERE session_id = '{}' ORDER BY turn_number DESC LIMIT {}",
+            session_id.replace("'", "\\'"),
+            limit
+        );
+        self.query_turns(&query).await
+    }
+
+    /// 获取会话内 turn_number 位于 `[start_turn, end_turn]` 的轮次（按 turn_number 升序）
+    async fn list_by_turn_range(
+        &self,
+        session_id: &str,
+        start_turn: u64,
+        end_turn: u64,
+    ) -> Result<Vec<Turn>> {
+        let query = format!(
+            "SELECT * FROM turn WHERE session_id = '{}' AND turn_number >= {} AND turn_number <= {} ORDER BY turn_number ASC",
+            session_id.replace("'", "\\'"),
+            start_turn,
+            end_turn
+        );
+        self.query_turns(&query).await
+    }
+
+    /// 获取用户在 `[start, end)` 时间窗口内的轮次（按 `metadata.timestamp` 降序，最多 `limit` 个）
+    async fn list_by_user_and_time_range(
+        &self,
+        user_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<Turn>> {
+        let query = format!(
+            "SELECT * FROM turn WHERE metadata.user_id = '{}' \
+             AND <datetime> metadata.timestamp >= <datetime> '{}' \
+             AND <datetime> metadata.timestamp < <datetime> '{}' \
+             ORDER BY metadata.timestamp DESC LIMIT {}",
+            user_id.replace("'", "\\'"),
+            start.to_rfc3339(),
+            end.to_rfc3339(),
+            limit
+        );
+        self.query_turns(&query).await
+    }
+
+    /// 删除会话下的所有轮次（单条语句），返回删除数量
+    async fn bulk_delete_by_session(&self, session_id: &str) -> Result<u64> {
+        let query = format!(
+            "DELETE FROM turn WHERE session_id = '{}' RETURN BEFORE",
+            session_id.replace("'", "\\'")
+        );
+        let results = self.query_rows(&query).await?;
+
+        Ok(results.len() as u64)
+    }
+
+    /// 写入轮次的脱水数据
+    async fn set_dehydrated(&self, turn_id: &str, dehydrated: &DehydratedData) -> Result<()> {
+        let query = format!(
+            "UPDATE {} SET dehydrated = {} RETURN NONE",
+            turn_record_id(turn_id),
+            serde_json::to_string(dehydrated)?
+        );
+        self.query_rows(&query).await?;
+        Ok(())
+    }
+
+    /// 删除会话中 turn_number 小于 `before_turn` 的最早 `batch_size` 个轮次，返回被删除轮次的 ID
+    async fn delete_batch_before(
+        &self,
+        session_id: &str,
+        before_turn: u64,
+        batch_size: usize,
+    ) -> Result<Vec<String>> {
+        let query = format!(
+            "SELECT VALUE id FROM turn WHERE session_id = '{}' AND turn_number < {} ORDER BY turn_number ASC LIMIT {}",
+            session_id.replace("'", "\\'"),
+            before_turn,
+            batch_size
+        );
+        let rows = self.query_rows(&query).await?;
+        let ids: Vec<String> = rows
+            .iter()
+            .filter_map(|id| id.as_str())
+            .map(|id| normalize_turn_id(id).to_string())
+            .collect();
+        if ids.is_empty() {
+            return Ok(ids);
+        }
+
+        let records: Vec<String> = ids.iter().map(|id| turn_record_id(id)).collect();
+        let query = format!(
+            "DELETE FROM turn WHERE id IN [{}] RETURN BEFORE",
+            records.join(", ")
+        );
+        let deleted = self.query_rows(&query).await?;
+        Ok(deleted
+            .iter()
+            .filter_map(|row| row.get("id")?.as_str())
+            .map(|id| normalize_turn_id(id).to_string())
+            .collect())
+    }
+
+    /// 按 ID 批量删除轮次（单条语句），返回删除数量
+    async fn delete_by_ids(&self, ids: &[&str]) -> Result<u64> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let ids: Vec<String> = ids.iter().map(|id| turn_record_id(id)).collect();
+        let query = format!(
+            "DELETE FROM turn WHERE id IN [{}] RETURN BEFORE",
+            ids.join(", ")
+        );
+        Ok(self.query_rows(&query).await?.len() as u64)
+    }
+
+    /// 将会话轮次恢复为给定的快照轮次（单个事务）
+    ///
+    /// 删除 `snapshot_point` 之后创建的轮次以及快照中的同 ID 轮次，再重新写入快照轮次，
+    /// 脱水数据和内容状态一并恢复。
+    async fn restore_session_turns(
+        &self,
+        session_id: &str,
+        snapshot_point: u64,
+        turns: &[Turn],
+    ) -> Result<()> {
+        let session_id = session_id.replace("'", "\\'");
+        let ids: Vec<String> = turns.iter().map(|t| turn_record_id(&t.id)).collect();
+
+        let mut statements = vec![
+            "BEGIN TRANSACTION".to_string(),
+            format!(
+                "DELETE FROM turn WHERE session_id = '{}' AND (turn_number > {} OR id IN [{}])",
+                session_id,
+                snapshot_point,
+                ids.join(", ")
+            ),
+        ];
+        for turn in turns {
+            statements.push(format!(
+                "CREATE turn SET id = '{}', session_id = '{}', turn_number = {}, {}, metadata = {}, dehydrated = {}, status = {}",
+                normalize_turn_id(&turn.id),
+                session_id,
+                turn.turn_number,
+                self.content_assignments(&turn.raw_content)?,
+                serde_json::to_string(&turn.metadata)?,
+                serde_json::to_string(&turn.dehydrated)?,
+                serde_json::to_string(&turn.status)?,
+            ));
+        }
+        statements.push("COMMIT TRANSACTION".to_string());
+
+        let results = execute_query(&self.pool, &statements.join(";\n")).await?;
+        statement_rows(results)?;
+        Ok(())
+    }
+
+    /// 将会话的全部轮次移动到另一个会话（单个事务），返回移动数量
+    ///
+    /// 轮次按原 turn_number 升序依次重新编号为 `first_turn_number`、`first_turn_number + 1`……
+    async fn move_turns_to_session(
+        &self,
+        from_session_id: &str,
+        to_session_id: &str,
+        first_turn_number: u64,
+    ) -> Result<u64> {
+        let query = format!(
+            "SELECT id, turn_number FROM turn WHERE session_id = '{}' ORDER BY turn_number ASC",
+            from_session_id.replace("'", "\\'")
+        );
+        let ids: Vec<String> = self
+            .query_rows(&query)
+            .await?
+            .iter()
+            .filter_map(|row| row.get("id").and_then(|id| id.as_str()))
+            .map(|id| normalize_turn_id(id).to_string())
+            .collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let script = move_turns_script(&ids, to_session_id, first_turn_number);
+        statement_rows(execute_query(&self.pool, &script).await?)?;
+        Ok(ids.len() as u64)
+    }
+}
+
+impl TurnRepository {
     /// 在事务中创建 turn 并返回分配的 turn_number
     pub async fn create_with_turn_number(&self, session_id: &str, turn: &Turn) -> Result<Turn> {
         let max_turn = self.get_max_turn_number(session_id).await?;
@@ -565,6 +1251,47 @@ impl TurnRepository {
     }
 }
 
+/// 生成将轮次移动到 `to_session_id` 并从 `first_turn_number` 起连续编号的事务脚本
+fn move_turns_script(ids: &[String], to_session_id: &str, first_turn_number: u64) -> String {
+    let to_session_id = to_session_id.replace("'", "\\'");
+    let mut statements = vec!["BEGIN TRANSACTION".to_string()];
+    for (turn_number, id) in (first_turn_number..).zip(ids) {
+        statements.push(format!(
+            "UPDATE {} SET session_id = '{}', turn_number = {} RETURN NONE",
+            turn_record_id(id),
+            to_session_id,
+            turn_number
+        ));
+    }
+    statements.push("COMMIT TRANSACTION".to_string());
+    statements.join(";\n")
+}
+
+/// 反序列化轮次记录，压缩存储的内容会先还原到 `raw_content`
+fn turn_from_record(mut json: serde_json::Value) -> Result<Turn> {
+    compression::inflate_turn_record(&mut json)?;
+    serde_json::from_value(json)
+        .map_err(|e| crate::error::AppError::Database(format!("Failed to deserialize turn: {}", e)))
+}
+
+/// 去掉 SurrealDB 记录 ID 的表前缀和尖括号（`turn:⟨x⟩` -> `x`）
+fn normalize_turn_id(id: &str) -> &str {
+    let id = id.strip_prefix("turn:").unwrap_or(id);
+    id.strip_prefix('⟨')
+        .and_then(|id| id.strip_suffix('⟩'))
+        .unwrap_or(id)
+}
+
+/// 轮次 ID 转为转义后的记录 ID 字面量（`x` -> `turn:⟨x⟩`），防止 ID 中的 `⟩` 截断语句
+fn turn_record_id(id: &str) -> String {
+    format!(
+        "turn:⟨{}⟩",
+        normalize_turn_id(id)
+            .replace('\\', "\\\\")
+            .replace('⟩', "\\⟩")
+    )
+}
+
 #[async_trait]
 impl Repository<Turn> for TurnRepository {
     async fn create(&self, turn: &Turn) -> Result<Turn> {
@@ -575,15 +1302,15 @@ impl Repository<Turn> for TurnRepository {
             serde_json::to_string(&turn.metadata).unwrap_or_else(|_| "{}".to_string());
 
         let query = format!(
-            "CREATE turn SET id = '{}', session_id = '{}', turn_number = {}, raw_content = '{}', metadata = {}",
+            "CREATE turn SET id = '{}', session_id = '{}', turn_number = {}, {}, metadata = {}",
             turn.id,
             turn.session_id,
             turn.turn_number,
-            turn.raw_content.replace("'", "\\'"),
+            self.content_assignments(&turn.raw_content)?,
             metadata_json,
         );
 
-        let _ = self.db.query(query).await?;
+        self.query_rows(&query).await?;
 
         // Return the input turn (with ID we provided)
         Ok(turn)
@@ -591,14 +1318,10 @@ impl Repository<Turn> for TurnRepository {
 
     async fn get_by_id(&self, id: &str) -> Result<Option<Turn>> {
         let query = format!("SELECT * FROM turn WHERE id = {}", id);
-        let mut response = self.db.query(query).await?;
-        let results: Vec<serde_json::Value> = response.take(0)?;
+        let results = self.query_rows(&query).await?;
 
         if let Some(json) = results.first() {
-            let turn = serde_json::from_value(json.clone()).map_err(|e| {
-                crate::error::AppError::Database(format!("Failed to deserialize turn: {}", e))
-            })?;
-            return Ok(Some(turn));
+            return turn_from_record(json.clone()).map(Some);
         }
 
         Ok(None)
@@ -610,50 +1333,21 @@ impl Repository<Turn> for TurnRepository {
             serde_json::to_string(&turn.metadata).unwrap_or_else(|_| "{}".to_string());
 
         let query = format!(
-            "UPDATE turn SET raw_content = '{}', metadata = {} WHERE id = {}",
-            turn.raw_content.replace("'", "\\'"),
+            "UPDATE turn SET {}, metadata = {} WHERE id = {}",
+            self.content_assignments(&turn.raw_content)?,
             metadata_json,
             id,
         );
-
-        // Use HTTP API to avoid SDK serialization issues
-        let config = self.pool.config();
-        let url = format!(
-            "{}/sql",
-            config.url.replace("ws://", "http://").replace("/rpc", "")
-        );
-
-        let response = self
-            .pool
-            .http_client()
-            .post(&url)
-            .header("surreal-ns", &config.namespace)
-            .header("surreal-db", &config.database)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/x-www-form-urlencoded")
-            .basic_auth(&config.username, Some(&config.password))
-            .body(query)
-            .send()
-            .await
-            .map_err(|e| crate::error::AppError::Database(format!("HTTP request failed: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(crate::error::AppError::Database(format!(
-                "SurrealDB error: {}",
-                error_text
-            )));
-        }
+        self.query_rows(&query).await?;
 
         Ok(Some(turn))
     }
 
     async fn delete(&self, id: &str) -> Result<bool> {
         let query = format!("DELETE FROM turn WHERE id = {}", id);
-        let mut response = self.db.query(query).await?;
-        let results: Vec<serde_json::Value> = response.take(0)?;
+        let results = self.query_rows(&query).await?;
 
-        Ok(results.len() > 0)
+        Ok(!results.is_empty())
     }
 
     async fn list(&self, limit: usize, start: usize) -> Result<Vec<Turn>> {
@@ -661,32 +1355,11 @@ impl Repository<Turn> for TurnRepository {
             "SELECT * FROM turn ORDER BY created_at DESC LIMIT {} START {}",
             limit, start
         );
-        let mut response = self.db.query(query).await?;
-        let results: Vec<serde_json::Value> = response.take(0)?;
-
-        let mut turns = Vec::new();
-        for json in results {
-            match serde_json::from_value(json) {
-                Ok(turn) => turns.push(turn),
-                Err(e) => tracing::warn!("Failed to deserialize turn: {}", e),
-            }
-        }
-
-        Ok(turns)
+        self.query_turns(&query).await
     }
 
     async fn count(&self) -> Result<u64> {
-        let query = "SELECT count() FROM turn GROUP ALL";
-        let mut response = self.db.query(query).await?;
-        let results: Vec<serde_json::Value> = response.take(0)?;
-
-        if let Some(json) = results.first() {
-            if let Some(count) = json.get("count").and_then(|v| v.as_u64()) {
-                return Ok(count);
-            }
-        }
-
-        Ok(0)
+        self.query_count("SELECT count() FROM turn GROUP ALL").await
     }
 
     async fn list_by_session(
@@ -695,39 +1368,13 @@ impl Repository<Turn> for TurnRepository {
         limit: usize,
         start: usize,
     ) -> Result<Vec<Turn>> {
-        let query = format!(
-            "SELECT * FROM turn WHERE session_id = '{}' ORDER BY turn_number ASC LIMIT {} START {}",
-            session_id, limit, start
-        );
-        let mut response = self.db.query(query).await?;
-        let results: Vec<serde_json::Value> = response.take(0)?;
-
-        let mut turns = Vec::new();
-        for json in results {
-            match serde_json::from_value(json) {
-                Ok(turn) => turns.push(turn),
-                Err(e) => tracing::warn!("Failed to deserialize turn: {}", e),
-            }
-        }
-
-        Ok(turns)
+        self.list_by_session_filtered(session_id, None, limit, start)
+            .await
     }
 
     async fn count_by_session(&self, session_id: &str) -> Result<u64> {
-        let query = format!(
-            "SELECT count() FROM turn WHERE session_id = '{}' GROUP ALL",
-            session_id
-        );
-        let mut response = self.db.query(query).await?;
-        let results: Vec<serde_json::Value> = response.take(0)?;
-
-        if let Some(json) = results.first() {
-            if let Some(count) = json.get("count").and_then(|v| v.as_u64()) {
-                return Ok(count);
-            }
-        }
-
-        Ok(0)
+        self.query_count(&count_by_session_query(session_id, None))
+            .await
     }
 }
 
@@ -887,3 +1534,126 @@ impl Repository<IndexRecord> for IndexRecordRepository {
         Ok(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_normalize_turn_id() {
+        assert_eq!(normalize_turn_id("turn_abc"), "turn_abc");
+        assert_eq!(normalize_turn_id("turn:turn_abc"), "turn_abc");
+        assert_eq!(
+            normalize_turn_id("turn:⟨turn_session:1_abc⟩"),
+            "turn_session:1_abc"
+        );
+    }
+
+    #[test]
+    fn test_turn_record_id_escapes_closing_bracket() {
+        assert_eq!(turn_record_id("turn:⟨turn_abc⟩"), "turn:⟨turn_abc⟩");
+        assert_eq!(
+            turn_record_id("x⟩]; DELETE turn; --"),
+            "turn:⟨x\\⟩]; DELETE turn; --⟩"
+        );
+    }
+
+    #[test]
+    fn test_adjust_turn_count_query_only_touches_turn_count() {
+        let query = adjust_turn_count_query("session:abc", -3);
+        assert_eq!(
+            query,
+            "UPDATE session SET stats.total_turns = math::max([stats.total_turns + -3, 0]) WHERE id = session:abc"
+        );
+    }
+
+    #[test]
+    fn test_add_stats_query_only_touches_stats_and_activity() {
+        let at = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        let query = add_stats_query("session:abc", 120, 4096, at);
+        assert!(
+            query.starts_with("UPDATE session SET stats.total_tokens = stats.total_tokens + 120, ")
+        );
+        assert!(query.contains("stats.storage_size = stats.storage_size + 4096, "));
+        assert!(query.contains(
+            "IF <datetime> last_active_at < <datetime> '2024-01-02T03:04:05+00:00' THEN '2024-01-02T03:04:05+00:00'"
+        ));
+        assert!(query.ends_with("WHERE id = session:abc"));
+        assert!(!query.contains("total_turns"));
+        assert!(!query.contains("name"));
+    }
+
+    #[test]
+    fn test_list_by_tenant_query() {
+        let query = list_by_tenant_query("tenant_1", None, 20, 40);
+        assert!(query.contains("tenant_id = 'tenant_1'"));
+        assert!(!query.contains("status"));
+        assert!(query.ends_with("LIMIT 20 START 40"));
+
+        let query = list_by_tenant_query("tenant_1", Some("Archived"), 20, 0);
+        assert!(query.contains("string::lowercase(status) = 'archived'"));
+    }
+
+    #[test]
+    fn test_count_by_tenant_query_applies_status_filter() {
+        assert_eq!(
+            count_by_tenant_query("tenant_1", None),
+            "SELECT count() FROM session WHERE tenant_id = 'tenant_1' GROUP ALL"
+        );
+        assert_eq!(
+            count_by_tenant_query("tenant_1", Some("Archived")),
+            "SELECT count() FROM session WHERE tenant_id = 'tenant_1' AND string::lowercase(status) = 'archived' GROUP ALL"
+        );
+    }
+
+    #[test]
+    fn test_list_by_session_query() {
+        let query = list_by_session_query("session_1", None, 20, 40);
+        assert!(query.contains("session_id = 'session_1'"));
+        assert!(!query.contains("message_type"));
+        assert!(query.ends_with("ORDER BY turn_number ASC LIMIT 20 START 40"));
+
+        let query = list_by_session_query("session_1", Some(&MessageType::Assistant), 20, 0);
+        assert!(query.contains("session_id = 'session_1' AND metadata.message_type = 'Assistant'"));
+
+        assert_eq!(
+            count_by_session_query("session_1", Some(&MessageType::User)),
+            "SELECT count() FROM turn WHERE session_id = 'session_1' AND metadata.message_type = 'User' GROUP ALL"
+        );
+    }
+
+    #[test]
+    fn test_move_turns_script() {
+        let ids = vec!["turn_a".to_string(), "turn_b".to_string()];
+        let script = move_turns_script(&ids, "session_1", 6);
+        let statements: Vec<&str> = script.split(";\n").collect();
+
+        assert_eq!(statements.len(), 4);
+        assert_eq!(statements[0], "BEGIN TRANSACTION");
+        assert_eq!(
+            statements[1],
+            "UPDATE turn:⟨turn_a⟩ SET session_id = 'session_1', turn_number = 6 RETURN NONE"
+        );
+        assert!(
+            statements[2].contains("turn:⟨turn_b⟩") && statements[2].contains("turn_number = 7")
+        );
+        assert_eq!(statements[3], "COMMIT TRANSACTION");
+    }
+
+    #[test]
+    fn test_statement_rows() {
+        let results = serde_json::json!([
+            {"status": "OK", "result": [{"count": 3}], "time": "1ms"},
+            {"status": "OK", "result": [], "time": "1ms"}
+        ]);
+        let rows = statement_rows(serde_json::from_value(results).unwrap()).unwrap();
+        assert_eq!(rows, vec![serde_json::json!({"count": 3})]);
+
+        let failed = serde_json::json!([
+            {"status": "OK", "result": [], "time": "1ms"},
+            {"status": "ERR", "result": "Database record already exists", "time": "1ms"}
+        ]);
+        assert!(statement_rows(serde_json::from_value(failed).unwrap()).is_err());
+    }
+}
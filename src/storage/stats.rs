@@ -0,0 +1,207 @@
+//! 存储统计
+//!
+//! 统计 SurrealDB 各表的记录数量，并通过抽样估算平均记录大小，供运维监控使用。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, Result};
+use crate::storage::repository::execute_query;
+use crate::storage::surrealdb::SurrealPool;
+
+/// 参与统计的表
+pub const STATS_TABLES: [&str; 11] = [
+    "session",
+    "turn",
+    "session_snapshot",
+    "memory",
+    "entity",
+    "relationship",
+    "pattern",
+    "pattern_version",
+    "pattern_usage",
+    "profile",
+    "index_record",
+];
+
+/// 估算平均记录大小时每张表抽样的记录数
+pub const SAMPLE_SIZE: usize = 10;
+
+/// 单张表的存储统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableStats {
+    /// 表名
+    pub table_name: String,
+    /// 记录数量
+    pub record_count: u64,
+    /// 估算的表大小（字节）：记录数量 × 平均记录大小
+    pub estimated_bytes: u64,
+    /// 抽样记录序列化后的平均长度（字节）
+    pub avg_record_size_bytes: u64,
+}
+
+/// 存储统计结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageStats {
+    /// 各表统计（顺序与 `STATS_TABLES` 一致）
+    pub tables: Vec<TableStats>,
+    /// 所有表的估算大小之和（字节）
+    pub total_estimated_bytes: u64,
+    /// 统计时间
+    pub gathered_at: DateTime<Utc>,
+}
+
+impl StorageStats {
+    /// 由各表统计汇总
+    pub fn new(tables: Vec<TableStats>) -> Self {
+        Self {
+            total_estimated_bytes: tables.iter().map(|t| t.estimated_bytes).sum(),
+            tables,
+            gathered_at: Utc::now(),
+        }
+    }
+}
+
+/// 存储统计服务
+#[derive(Clone)]
+pub struct StorageStatsService {
+    pool: SurrealPool,
+}
+
+impl StorageStatsService {
+    /// 创建新的服务实例
+    pub fn new(pool: SurrealPool) -> Self {
+        Self { pool }
+    }
+
+    /// 统计所有表（单次请求）
+    ///
+    /// 每张表执行两条语句：`count()` 计数，以及随机抽样 `SAMPLE_SIZE` 条记录
+    /// 计算 `string::len(<string> $this)`。
+    pub async fn gather(&self) -> Result<StorageStats> {
+        let results = execute_query(&self.pool, &stats_query()).await?;
+        parse_stats(&results).map(StorageStats::new)
+    }
+}
+
+/// 生成所有表的统计语句
+fn stats_query() -> String {
+    STATS_TABLES
+        .iter()
+        .map(|table| {
+            format!(
+                "SELECT count() FROM {table} GROUP ALL;\n\
+                 SELECT VALUE string::len(<string> $this) FROM {table} ORDER BY RAND() LIMIT {SAMPLE_SIZE};"
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 解析统计语句的结果，每张表对应两条语句的结果
+fn parse_stats(results: &[serde_json::Value]) -> Result<Vec<TableStats>> {
+    STATS_TABLES
+        .iter()
+        .enumerate()
+        .map(|(i, table)| {
+            let record_count = statement_result(results, 2 * i, table)?
+                .first()
+                .and_then(|row| row.get("count"))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0);
+            let sizes: Vec<u64> = statement_result(results, 2 * i + 1, table)?
+                .iter()
+                .filter_map(|v| v.as_u64())
+                .collect();
+            let avg_record_size_bytes = if sizes.is_empty() {
+                0
+            } else {
+                sizes.iter().sum::<u64>() / sizes.len() as u64
+            };
+
+            Ok(TableStats {
+                table_name: table.to_string(),
+                record_count,
+                estimated_bytes: record_count * avg_record_size_bytes,
+                avg_record_size_bytes,
+            })
+        })
+        .collect()
+}
+
+/// 取出第 `index` 条语句的结果行，语句失败时返回错误
+fn statement_result<'a>(
+    results: &'a [serde_json::Value],
+    index: usize,
+    table: &str,
+) -> Result<&'a [serde_json::Value]> {
+    let statement = results.get(index).ok_or_else(|| {
+        AppError::Database(format!("Missing storage stats result for table {}", table))
+    })?;
+    if statement.get("status").and_then(|s| s.as_str()) == Some("ERR") {
+        return Err(AppError::Database(format!(
+            "Failed to gather storage stats for table {}: {}",
+            table,
+            statement.get("result").unwrap_or(&serde_json::Value::Null)
+        )));
+    }
+
+    Ok(statement
+        .get("result")
+        .and_then(|r| r.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or(&[]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ok(result: serde_json::Value) -> serde_json::Value {
+        json!({"status": "OK", "result": result, "time": "1ms"})
+    }
+
+    #[test]
+    fn test_stats_query_covers_all_tables() {
+        let query = stats_query();
+        for table in STATS_TABLES {
+            assert!(query.contains(&format!("SELECT count() FROM {} GROUP ALL", table)));
+        }
+        assert!(query.contains("LIMIT 10"));
+    }
+
+    #[test]
+    fn test_parse_stats() {
+        let mut results = vec![ok(json!([{"count": 4}])), ok(json!([100, 200, 300]))];
+        for _ in 1..STATS_TABLES.len() {
+            results.push(ok(json!([])));
+            results.push(ok(json!([])));
+        }
+
+        let stats = StorageStats::new(parse_stats(&results).unwrap());
+        assert_eq!(stats.tables.len(), STATS_TABLES.len());
+        assert_eq!(
+            stats.tables[0],
+            TableStats {
+                table_name: "session".to_string(),
+                record_count: 4,
+                estimated_bytes: 800,
+                avg_record_size_bytes: 200,
+            }
+        );
+        assert_eq!(stats.tables[1].record_count, 0);
+        assert_eq!(stats.tables[1].avg_record_size_bytes, 0);
+        assert_eq!(stats.total_estimated_bytes, 800);
+    }
+
+    #[test]
+    fn test_parse_stats_reports_failed_statement() {
+        let results = vec![
+            ok(json!([{"count": 4}])),
+            json!({"status": "ERR", "result": "Parse error", "time": "1ms"}),
+        ];
+        assert!(parse_stats(&results).is_err());
+        assert!(parse_stats(&[]).is_err());
+    }
+}
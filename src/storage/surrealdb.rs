@@ -1,6 +1,11 @@
 use crate::config::config::DatabaseConfig;
+use crate::error::{AppError, Result};
+use crate::observability::HealthCheckResult;
+use crate::storage::repository::{execute_query, statement_rows};
+use futures_util::future::BoxFuture;
 use reqwest;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use surrealdb::{
     Surreal,
     engine::any::{Any, connect},
@@ -8,6 +13,9 @@ use surrealdb::{
 };
 use tokio::sync::Mutex;
 
+/// 数据库健康检查的超时时间，超时视为不健康
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// SurrealDB 连接池
 #[derive(Clone)]
 pub struct SurrealPool {
@@ -21,7 +29,7 @@ pub struct SurrealPool {
 
 impl SurrealPool {
     /// 创建新的连接池
-    pub async fn new(config: DatabaseConfig) -> Result<Self, surrealdb::Error> {
+    pub async fn new(config: DatabaseConfig) -> std::result::Result<Self, surrealdb::Error> {
         let db: Surreal<Any> = connect(&config.url).await?;
 
         // 认证
@@ -46,6 +54,16 @@ impl SurrealPool {
         })
     }
 
+    /// 不建立 SDK 连接、仅通过 HTTP 执行查询的连接池（测试用）
+    #[cfg(test)]
+    pub(crate) fn detached(config: DatabaseConfig) -> Self {
+        Self {
+            db: Arc::new(Mutex::new(None)),
+            config,
+            http_client: Arc::new(reqwest::Client::new()),
+        }
+    }
+
     /// 获取连接
     pub async fn get(&self) -> SurrealPoolConn {
         SurrealPoolConn { pool: self.clone() }
@@ -67,11 +85,179 @@ impl SurrealPool {
         &self.config
     }
 
+    /// 数据库健康检查
+    ///
+    /// 通过 HTTP API 执行 `SELECT 1` 并测量往返延迟，
+    /// `HEALTH_CHECK_TIMEOUT` 内成功返回时视为健康。
+    pub async fn health_check(&self) -> HealthCheckResult {
+        let started = Instant::now();
+        let outcome = tokio::time::timeout(HEALTH_CHECK_TIMEOUT, execute_query(self, "SELECT 1"))
+            .await
+            .map_err(|_| {
+                AppError::Timeout(format!(
+                    "No response within {}ms",
+                    HEALTH_CHECK_TIMEOUT.as_millis()
+                ))
+            })
+            .and_then(|results| statement_rows(results?));
+
+        database_health(outcome.map(|_| ()), started.elapsed().as_millis() as u64)
+    }
+
     /// 关闭连接
     pub async fn close(&self) {
         let mut guard = self.db.lock().await;
         *guard = None;
     }
+
+    /// 执行 `f`，遇到瞬时连接错误时按指数退避重试
+    ///
+    /// 仅重试消息中包含 "connection refused" / "timeout" 的 `AppError::Database`，
+    /// 第 n 次重试前等待 `base_delay_ms * 2^n` 毫秒；重试耗尽后返回最后一次错误。
+    pub async fn with_retry<'a, F, T>(f: F, max_retries: u32, base_delay_ms: u64) -> Result<T>
+    where
+        F: Fn() -> BoxFuture<'a, Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Err(e) if attempt < max_retries && is_transient(&e) => {
+                    let delay = retry_delay(base_delay_ms, attempt);
+                    tracing::warn!(
+                        "Transient database error (attempt {}/{}), retrying in {:?}: {}",
+                        attempt + 1,
+                        max_retries,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// 在事务中执行 `f`
+    ///
+    /// `f` 通过 [`SurrealTransactionGuard`] 添加语句并调用 `commit()` 提交；
+    /// 未提交即返回（包括返回错误）时，句柄被丢弃，语句全部回滚。
+    pub async fn transaction<'a, F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(SurrealTransactionGuard) -> BoxFuture<'a, Result<T>>,
+    {
+        f(SurrealTransactionGuard::new(self.inner().await)).await
+    }
+}
+
+/// 事务句柄
+///
+/// SDK 不支持跨请求的交互式事务，语句先缓存在句柄中，`commit()` 时包裹在
+/// `BEGIN TRANSACTION; ... COMMIT TRANSACTION;` 中一次性发送；
+/// 未提交就被丢弃时等同于 `ROLLBACK`，缓存的语句不会执行。
+pub struct SurrealTransactionGuard {
+    db: Surreal<Any>,
+    statements: Vec<String>,
+    committed: bool,
+}
+
+impl SurrealTransactionGuard {
+    fn new(db: Surreal<Any>) -> Self {
+        Self {
+            db,
+            statements: Vec::new(),
+            committed: false,
+        }
+    }
+
+    /// 添加一条语句
+    pub fn query(&mut self, statement: impl Into<String>) -> &mut Self {
+        self.statements.push(statement.into());
+        self
+    }
+
+    /// 已添加的语句数量
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    /// 是否未添加任何语句
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// 生成完整的事务脚本
+    fn script(&self) -> String {
+        let mut script = String::from("BEGIN TRANSACTION;\n");
+        for statement in &self.statements {
+            script.push_str(statement.trim().trim_end_matches(';'));
+            script.push_str(";\n");
+        }
+        script.push_str("COMMIT TRANSACTION;");
+        script
+    }
+
+    /// 提交事务，返回各语句的结果
+    ///
+    /// 任一语句失败时整个事务回滚并返回错误。
+    pub async fn commit(mut self) -> Result<surrealdb::Response> {
+        self.committed = true;
+        if self.statements.is_empty() {
+            return self.db.query("RETURN NONE").await.map_err(AppError::from);
+        }
+
+        let response = self.db.query(self.script()).await?;
+        Ok(response.check()?)
+    }
+
+    /// 显式回滚，丢弃所有语句
+    pub fn rollback(mut self) {
+        self.statements.clear();
+    }
+}
+
+impl Drop for SurrealTransactionGuard {
+    fn drop(&mut self) {
+        if !self.committed && !self.statements.is_empty() {
+            tracing::warn!(
+                "Transaction dropped without commit, rolled back {} statement(s)",
+                self.statements.len()
+            );
+        }
+    }
+}
+
+/// 是否为可重试的瞬时连接错误
+fn is_transient(error: &AppError) -> bool {
+    match error {
+        AppError::Database(message) => {
+            let message = message.to_lowercase();
+            message.contains("connection refused")
+                || message.contains("timeout")
+                || message.contains("timed out")
+        }
+        _ => false,
+    }
+}
+
+/// 由 `SELECT 1` 的执行结果生成数据库健康检查结果
+fn database_health(outcome: Result<()>, latency_ms: u64) -> HealthCheckResult {
+    let (healthy, message) = match outcome {
+        Ok(()) => (true, "Database reachable".to_string()),
+        Err(e) => (false, e.to_string()),
+    };
+    HealthCheckResult {
+        name: "database".to_string(),
+        healthy,
+        message,
+        latency_ms,
+    }
+}
+
+/// 第 `attempt` 次重试前的等待时间
+fn retry_delay(base_delay_ms: u64, attempt: u32) -> Duration {
+    let factor = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    Duration::from_millis(base_delay_ms.saturating_mul(factor))
 }
 
 /// 连接包装器
@@ -95,3 +281,105 @@ impl SurrealPoolConn {
         self.pool.config()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_with_retry_recovers_from_transient_errors() {
+        let calls = AtomicU32::new(0);
+
+        let result = SurrealPool::with_retry(
+            || {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move {
+                    if call < 2 {
+                        Err(AppError::Database(
+                            "tcp connect error: Connection refused".into(),
+                        ))
+                    } else {
+                        Ok(call)
+                    }
+                })
+            },
+            3,
+            1,
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_gives_up() {
+        let calls = AtomicU32::new(0);
+
+        let result: Result<()> = SurrealPool::with_retry(
+            || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Err(AppError::Database("request timeout".into())) })
+            },
+            2,
+            1,
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Database(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // 非瞬时错误不重试
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<()> = SurrealPool::with_retry(
+            || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Err(AppError::Database("SurrealDB error: parse".into())) })
+            },
+            2,
+            1,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_database_health() {
+        let healthy = database_health(Ok(()), 12);
+        assert_eq!(healthy.name, "database");
+        assert!(healthy.healthy);
+        assert_eq!(healthy.latency_ms, 12);
+
+        let unhealthy = database_health(Err(AppError::Timeout("No response".into())), 2000);
+        assert!(!unhealthy.healthy);
+        assert!(unhealthy.message.contains("No response"));
+    }
+
+    #[tokio::test]
+    async fn test_transaction_guard_script() {
+        let mut guard = SurrealTransactionGuard::new(Surreal::init());
+        assert!(guard.is_empty());
+
+        guard
+            .query("DELETE FROM turn WHERE session_id = 'session_1';")
+            .query("DELETE session:session_1");
+        assert_eq!(guard.len(), 2);
+        assert_eq!(
+            guard.script(),
+            "BEGIN TRANSACTION;\n\
+             DELETE FROM turn WHERE session_id = 'session_1';\n\
+             DELETE session:session_1;\n\
+             COMMIT TRANSACTION;"
+        );
+
+        guard.rollback();
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(100, 0), Duration::from_millis(100));
+        assert_eq!(retry_delay(100, 3), Duration::from_millis(800));
+        assert_eq!(retry_delay(100, 80), Duration::from_millis(u64::MAX));
+    }
+}
@@ -4,27 +4,81 @@
 //! Supports topic-based subscriptions for memory, profile, pattern, and entity events.
 
 use axum::{
-    Extension,
+    Extension, Router,
+    extract::Query,
     extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    http::HeaderMap,
     response::Response,
+    routing::get,
 };
 use futures_util::{SinkExt, StreamExt};
 use futures_util::stream::{SplitSink, SplitStream};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::api::app_state::AppState;
+use crate::config::config::WebSocketConfig;
+use crate::error::AppError;
+use crate::observability::event_bus::is_visible_to;
+use crate::security::auth::Credentials;
+use crate::security::middleware::{authenticate_credentials, authenticate_headers};
 
 pub mod subscription;
 
+/// Wire encoding used for messages sent to a connection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Encoding {
+    /// JSON text frames (the default)
+    #[default]
+    #[serde(rename = "json")]
+    Json,
+    /// MessagePack binary frames
+    #[serde(rename = "msgpack")]
+    MessagePack,
+}
+
 /// WebSocket message types for subscription control
+///
+/// A subscribe message may carry `"encoding": "msgpack"` to switch the
+/// connection's outgoing messages to MessagePack binary frames.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SubscriptionMessage {
     pub action: String,
+    #[serde(default)]
     pub topics: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<Encoding>,
+}
+
+impl SubscriptionMessage {
+    /// Decodes a text frame as JSON
+    fn from_text(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| format!("Invalid message format: {}", e))
+    }
+
+    /// Decodes a binary frame, as MessagePack when it starts with a map header
+    /// and as UTF-8 JSON otherwise
+    fn from_binary(bytes: &[u8]) -> Result<Self, String> {
+        match bytes.first() {
+            Some(&byte) if is_msgpack_map_header(byte) => rmp_serde::from_slice(bytes)
+                .map_err(|e| format!("Invalid MessagePack message: {}", e)),
+            _ => {
+                let text = std::str::from_utf8(bytes)
+                    .map_err(|e| format!("Invalid message format: {}", e))?;
+                Self::from_text(text)
+            }
+        }
+    }
+}
+
+/// Whether `byte` is a MessagePack map header (fixmap, map16 or map32)
+fn is_msgpack_map_header(byte: u8) -> bool {
+    matches!(byte, 0x80..=0x8f | 0xde | 0xdf)
 }
 
 /// WebSocket message types for broadcasting events
@@ -35,10 +89,25 @@ pub struct WebSocketMessage {
     pub data: serde_json::Value,
 }
 
+impl WebSocketMessage {
+    /// Serialises the message into a frame of the given encoding
+    fn encode(&self, encoding: Encoding) -> Result<Message, String> {
+        match encoding {
+            Encoding::Json => serde_json::to_string(self)
+                .map(Message::Text)
+                .map_err(|e| format!("JSON error: {}", e)),
+            Encoding::MessagePack => rmp_serde::to_vec_named(self)
+                .map(Message::Binary)
+                .map_err(|e| format!("MessagePack error: {}", e)),
+        }
+    }
+}
+
 /// WebSocket connection state with topic subscriptions
 struct WebSocketConnection {
     id: String,
     subscriptions: HashSet<String>,
+    encoding: Encoding,
     sender: SplitSink<WebSocket, Message>,
 }
 
@@ -49,21 +118,21 @@ impl WebSocketConnection {
         Self {
             id,
             subscriptions: HashSet::new(),
+            encoding: Encoding::default(),
             sender,
         }
     }
 
+    /// Encodes and sends a message using the connection's negotiated encoding
+    async fn send_message(&mut self, message: &WebSocketMessage) -> Result<(), String> {
+        let frame = message.encode(self.encoding)?;
+        self.sender.send(frame).await.map_err(|e| e.to_string())
+    }
+
     fn matches_topic(&self, topic: &str) -> bool {
-        self.subscriptions.iter().any(|pattern| {
-            if pattern.ends_with(":*") {
-                let prefix = &pattern[..pattern.len() - 2];
-                topic.starts_with(prefix)
-                    && topic.len() > prefix.len()
-                    && topic.as_bytes()[prefix.len()] == b':'
-            } else {
-                pattern == topic
-            }
-        })
+        self.subscriptions
+            .iter()
+            .any(|pattern| topic_matches(pattern, topic))
     }
 
     fn subscribe(&mut self, topic: String) {
@@ -75,16 +144,58 @@ impl WebSocketConnection {
     }
 }
 
+/// Create the `/ws` router with the state and heartbeat settings the handler reads
+pub fn create_websocket_router(app_state: Arc<AppState>, config: WebSocketConfig) -> Router {
+    Router::new()
+        .route("/ws", get(ws_handler))
+        .layer(Extension(app_state))
+        .layer(Extension(config))
+}
+
+/// Credentials passed as query parameters of the upgrade request
+///
+/// Browsers cannot set headers on a WebSocket handshake, so `?token=<jwt>`
+/// or `?api_key=<key>` is accepted in place of the usual auth headers.
+#[derive(Debug, Default, Deserialize)]
+pub struct WsAuthQuery {
+    pub token: Option<String>,
+    pub api_key: Option<String>,
+}
+
 /// WebSocket handler using Axum's WebSocket support
+///
+/// The upgrade request is authenticated from its query parameters or, when
+/// none are given, its `Authorization` / `X-API-Key` headers; unauthenticated
+/// upgrades are rejected with 401. Heartbeat settings come from an optional
+/// `Extension<WebSocketConfig>`; the defaults are used when none is
+/// registered. Domain events are only forwarded to connections of the
+/// event's tenant.
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
     Extension(state): Extension<Arc<AppState>>,
-) -> Response {
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+    config: Option<Extension<WebSocketConfig>>,
+    Query(query): Query<WsAuthQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let authenticator = state.authenticator.as_ref();
+    let claims = if query.token.is_some() || query.api_key.is_some() {
+        let credentials = Credentials::new(query.api_key, query.token);
+        authenticate_credentials(&credentials, authenticator).await?
+    } else {
+        authenticate_headers(&headers, authenticator).await?
+    };
+
+    let config = config.map(|Extension(c)| c).unwrap_or_default();
+    Ok(ws.on_upgrade(|socket| handle_socket(socket, state, config, claims.tenant_id)))
 }
 
 /// Handle the WebSocket connection
-async fn handle_socket(ws: WebSocket, state: Arc<AppState>) {
+async fn handle_socket(
+    ws: WebSocket,
+    state: Arc<AppState>,
+    config: WebSocketConfig,
+    tenant_id: String,
+) {
     let (sender, receiver) = ws.split();
     let connection_id = uuid::Uuid::new_v4().to_string();
 
@@ -108,11 +219,7 @@ async fn handle_socket(ws: WebSocket, state: Arc<AppState>) {
             "timestamp": chrono::Utc::now().to_rfc3339()
         }),
     };
-    if let Err(e) = ws_connection
-        .sender
-        .send(Message::Text(serde_json::to_string(&init_event).unwrap()))
-        .await
-    {
+    if let Err(e) = ws_connection.send_message(&init_event).await {
         error!("Failed to send init event: {}", e);
         return;
     }
@@ -125,16 +232,31 @@ async fn handle_socket(ws: WebSocket, state: Arc<AppState>) {
 
     let receive_conn = connection.clone();
     let forward_conn = connection.clone();
+    let ping_conn = connection.clone();
+    let watchdog_conn = connection.clone();
 
     // Clone connection_id before moving into tasks
     let connection_id_for_receive = connection_id.clone();
     let connection_id_for_forward = connection_id.clone();
 
-    // Use join instead of spawn to avoid Send bound issues with parking_lot Mutex
-    tokio::join! {
-        handle_receive(receiver, connection_id_for_receive, receive_conn),
-        handle_forward(rx, connection_id_for_forward, forward_conn)
-    };
+    // Milliseconds since `started` at which the last pong was received
+    let started = Instant::now();
+    let last_pong = Arc::new(AtomicU64::new(0));
+
+    // Use select instead of spawn to avoid Send bound issues with parking_lot Mutex.
+    // The connection ends as soon as any task finishes, including a heartbeat timeout.
+    tokio::select! {
+        _ = handle_receive(
+            receiver,
+            connection_id_for_receive,
+            receive_conn,
+            last_pong.clone(),
+            started,
+        ) => {}
+        _ = handle_forward(rx, connection_id_for_forward, forward_conn, tenant_id) => {}
+        _ = send_pings(ping_conn, config.ping_interval()) => {}
+        _ = watch_heartbeat(watchdog_conn, &connection_id, last_pong, started, &config) => {}
+    }
 
     connection_manager.remove_connection(&connection_id).await;
     debug!("WebSocket connection closed: {}", connection_id);
@@ -145,14 +267,32 @@ async fn handle_receive(
     mut receiver: SplitStream<WebSocket>,
     connection_id: String,
     connection: Arc<tokio::sync::Mutex<WebSocketConnection>>,
+    last_pong: Arc<AtomicU64>,
+    started: Instant,
 ) {
     while let Some(msg) = receiver.next().await {
         match msg {
+            Ok(Message::Pong(_)) => {
+                last_pong.store(started.elapsed().as_millis() as u64, Ordering::Relaxed);
+            }
             Ok(Message::Text(text)) => {
-                if let Err(e) = process_message(&text, &connection_id, &connection).await {
+                let result = match SubscriptionMessage::from_text(&text) {
+                    Ok(msg) => process_message(msg, &connection_id, &connection).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
                     error!("Failed to process message: {}", e);
                 }
             }
+            Ok(Message::Binary(bytes)) => {
+                let result = match SubscriptionMessage::from_binary(&bytes) {
+                    Ok(msg) => process_message(msg, &connection_id, &connection).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    error!("Failed to process binary message: {}", e);
+                }
+            }
             Ok(Message::Close(_)) => {
                 debug!("Client initiated close for {}", connection_id);
                 break;
@@ -168,17 +308,18 @@ async fn handle_receive(
 
 /// Process incoming subscription messages
 async fn process_message(
-    text: &str,
+    msg: SubscriptionMessage,
     connection_id: &str,
     connection: &Arc<tokio::sync::Mutex<WebSocketConnection>>,
 ) -> Result<(), String> {
-    let msg: SubscriptionMessage =
-        serde_json::from_str(text).map_err(|e| format!("Invalid message format: {}", e))?;
-
     let mut conn = connection.lock().await;
 
     match msg.action.as_str() {
         "subscribe" => {
+            if let Some(encoding) = msg.encoding {
+                conn.encoding = encoding;
+                debug!("{} switched to {:?} encoding", connection_id, encoding);
+            }
             for topic in msg.topics.clone() {
                 conn.subscribe(topic);
                 debug!("{} subscribed to topic", connection_id);
@@ -206,10 +347,9 @@ async fn process_message(
                     "timestamp": chrono::Utc::now().to_rfc3339()
                 }),
             };
-            let text = serde_json::to_string(&pong).map_err(|e| format!("JSON error: {}", e))?;
             // Release lock before awaiting
             drop(conn);
-            send_message_to_connection(text, connection.clone()).await;
+            send_message_to_connection(&pong, connection.clone()).await;
         }
         _ => {
             let error_msg = WebSocketMessage {
@@ -219,11 +359,9 @@ async fn process_message(
                     "message": format!("Unknown action: {}", msg.action)
                 }),
             };
-            let text =
-                serde_json::to_string(&error_msg).map_err(|e| format!("JSON error: {}", e))?;
             // Release lock before awaiting
             drop(conn);
-            send_message_to_connection(text, connection.clone()).await;
+            send_message_to_connection(&error_msg, connection.clone()).await;
         }
     }
 
@@ -232,16 +370,10 @@ async fn process_message(
 
 /// Send a message to the WebSocket connection (releases lock first)
 async fn send_message_to_connection(
-    text: String,
+    message: &WebSocketMessage,
     connection: Arc<tokio::sync::Mutex<WebSocketConnection>>,
 ) {
-    if let Err(e) = connection
-        .lock()
-        .await
-        .sender
-        .send(Message::Text(text))
-        .await
-    {
+    if let Err(e) = connection.lock().await.send_message(message).await {
         error!("Failed to send message: {}", e);
     }
 }
@@ -261,13 +393,7 @@ async fn send_confirmation_async(
         }),
     };
 
-    if let Err(e) = connection
-        .lock()
-        .await
-        .sender
-        .send(Message::Text(serde_json::to_string(&confirmation).unwrap()))
-        .await
-    {
+    if let Err(e) = connection.lock().await.send_message(&confirmation).await {
         error!("Failed to send confirmation: {}", e);
     }
 }
@@ -287,20 +413,17 @@ async fn send_confirmation(
         }),
     };
 
-    if let Err(e) = conn
-        .sender
-        .send(Message::Text(serde_json::to_string(&confirmation).unwrap()))
-        .await
-    {
+    if let Err(e) = conn.send_message(&confirmation).await {
         error!("Failed to send confirmation: {}", e);
     }
 }
 
-/// Forward broadcast events to WebSocket connections
+/// Forward broadcast events of the connection's tenant to WebSocket connections
 async fn handle_forward(
     mut rx: broadcast::Receiver<String>,
     connection_id: String,
     connection: Arc<tokio::sync::Mutex<WebSocketConnection>>,
+    tenant_id: String,
 ) {
     while let Ok(event_str) = rx.recv().await {
         let event: serde_json::Value = match serde_json::from_str(&event_str) {
@@ -310,6 +433,9 @@ async fn handle_forward(
                 continue;
             }
         };
+        if !is_visible_to(&event, Some(&tenant_id)) {
+            continue;
+        }
 
         let topic = event
             .get("event")
@@ -329,11 +455,7 @@ async fn handle_forward(
             };
 
             // Acquire lock, send message, and release lock within the same await point
-            if let Err(e) = connection.lock().await
-                .sender
-                .send(Message::Text(serde_json::to_string(&message).unwrap()))
-                .await
-            {
+            if let Err(e) = connection.lock().await.send_message(&message).await {
                 error!("Failed to forward event to {}: {}", connection_id, e);
                 break;
             }
@@ -341,14 +463,304 @@ async fn handle_forward(
     }
 }
 
+/// Send a `Ping` frame every `ping_interval` (never returns when the interval is zero)
+async fn send_pings(
+    connection: Arc<tokio::sync::Mutex<WebSocketConnection>>,
+    ping_interval: Duration,
+) {
+    if ping_interval.is_zero() {
+        return std::future::pending().await;
+    }
+
+    let mut interval = tokio::time::interval(ping_interval);
+    // The first tick completes immediately
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        if let Err(e) = connection
+            .lock()
+            .await
+            .sender
+            .send(Message::Ping(vec![]))
+            .await
+        {
+            error!("Failed to send ping: {}", e);
+            return;
+        }
+    }
+}
+
+/// Close the connection once no pong has arrived within `pong_timeout`
+async fn watch_heartbeat(
+    connection: Arc<tokio::sync::Mutex<WebSocketConnection>>,
+    connection_id: &str,
+    last_pong: Arc<AtomicU64>,
+    started: Instant,
+    config: &WebSocketConfig,
+) {
+    let ping_interval = config.ping_interval();
+    if ping_interval.is_zero() {
+        return std::future::pending().await;
+    }
+
+    let pong_timeout = config.pong_timeout();
+    let mut interval = tokio::time::interval(
+        ping_interval
+            .min(pong_timeout)
+            .max(Duration::from_millis(100)),
+    );
+
+    loop {
+        interval.tick().await;
+
+        let now_ms = started.elapsed().as_millis() as u64;
+        if is_pong_overdue(last_pong.load(Ordering::Relaxed), now_ms, pong_timeout) {
+            warn!(
+                "WebSocket {} missed heartbeat for {:?}, closing",
+                connection_id, pong_timeout
+            );
+            let _ = connection
+                .lock()
+                .await
+                .sender
+                .send(Message::Close(None))
+                .await;
+            return;
+        }
+    }
+}
+
+/// Whether more than `pong_timeout` has passed since the last pong
+fn is_pong_overdue(last_pong_ms: u64, now_ms: u64, pong_timeout: Duration) -> bool {
+    now_ms.saturating_sub(last_pong_ms) > pong_timeout.as_millis() as u64
+}
+
+/// Whether a subscription pattern matches a topic
+///
+/// - `"memory:*"` matches every topic in the `memory` namespace (`memory:created`,
+///   `memory:updated`, ...) but nothing outside it.
+/// - `"**"` matches every topic in every namespace. A bare `"*"` is accepted as an
+///   alias for older clients.
+/// - Any other pattern must equal the topic exactly.
+fn topic_matches(pattern: &str, topic: &str) -> bool {
+    if pattern == "**" || pattern == "*" {
+        return true;
+    }
+
+    match pattern.strip_suffix(":*") {
+        Some(prefix) => topic
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with(':')),
+        None => pattern == topic,
+    }
+}
+
 /// Topics that can be subscribed to
 pub mod topics {
     pub const MEMORY_CREATED: &str = "memory:created";
     pub const MEMORY_UPDATED: &str = "memory:updated";
     pub const MEMORY_DELETED: &str = "memory:deleted";
+    /// Every topic in the `memory` namespace
     pub const MEMORY_ALL: &str = "memory:*";
     pub const PROFILE_UPDATED: &str = "profile:updated";
     pub const PATTERN_CREATED: &str = "pattern:created";
     pub const ENTITY_CREATED: &str = "entity:created";
-    pub const ALL_EVENTS: &str = "*";
+    /// Every topic in every namespace
+    pub const ALL_EVENTS: &str = "**";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Next JSON text frame received on `socket`
+    async fn next_json<S>(socket: &mut S) -> serde_json::Value
+    where
+        S: futures_util::Stream<
+                Item = Result<
+                    tokio_tungstenite::tungstenite::Message,
+                    tokio_tungstenite::tungstenite::Error,
+                >,
+            > + Unpin,
+    {
+        loop {
+            let frame = tokio::time::timeout(Duration::from_secs(5), socket.next())
+                .await
+                .expect("timed out waiting for a message")
+                .unwrap()
+                .unwrap();
+            if let tokio_tungstenite::tungstenite::Message::Text(text) = frame {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authenticated_subscriber_receives_turn_created() {
+        use crate::api::test_support::MockDatabase;
+        use crate::models::entity_repository::EntityRepositoryImpl;
+        use crate::models::pattern_repository::PatternRepositoryImpl;
+        use crate::models::profile_repository::ProfileRepositoryImpl;
+        use crate::models::session::Session;
+        use crate::observability::EventBus;
+        use crate::services::session::SessionServiceImpl;
+        use crate::services::turn::TurnServiceImpl;
+        use crate::storage::factory::RepositorySet;
+        use tokio_tungstenite::{connect_async, tungstenite};
+
+        let db = MockDatabase::start().await;
+        let repos = RepositorySet::in_memory();
+        let event_bus = EventBus::default();
+        let session_service = SessionServiceImpl::new(repos.sessions.clone(), repos.turns.clone());
+        let turn_service = TurnServiceImpl::new(repos.turns.clone(), repos.sessions.clone())
+            .with_event_bus(event_bus.clone());
+        let state = AppState::development(
+            db.pool(),
+            repos.sessions.clone(),
+            repos.turns.clone(),
+            repos.memories.clone(),
+            PatternRepositoryImpl::new(db.pool()),
+            EntityRepositoryImpl::new(db.pool()),
+            ProfileRepositoryImpl::new(db.pool()),
+            Box::new(session_service),
+            Box::new(turn_service),
+        )
+        .with_event_bus(event_bus)
+        .with_sse_connection_manager(10);
+        let state = Arc::new(state);
+        let router = crate::api::create_router((*state).clone()).merge(create_websocket_router(
+            state.clone(),
+            WebSocketConfig::default(),
+        ));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        match connect_async(format!("ws://{}/ws", addr)).await {
+            Err(tungstenite::Error::Http(response)) => assert_eq!(response.status(), 401),
+            other => panic!(
+                "unauthenticated upgrade was not rejected: {:?}",
+                other.map(|_| ())
+            ),
+        }
+
+        // `dev-api-key` belongs to `dev-tenant` in the development authenticator
+        let (mut socket, _) = connect_async(format!("ws://{}/ws?api_key=dev-api-key", addr))
+            .await
+            .unwrap();
+        assert_eq!(next_json(&mut socket).await["type"], "connected");
+
+        socket
+            .send(tungstenite::Message::Text(
+                r#"{"action":"subscribe","topics":["turn:*"]}"#.to_string(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(next_json(&mut socket).await["type"], "subscribed");
+
+        let other = repos
+            .sessions
+            .create(&Session::new("other-tenant", "chat"))
+            .await
+            .unwrap();
+        let own = repos
+            .sessions
+            .create(&Session::new("dev-tenant", "chat"))
+            .await
+            .unwrap();
+        state
+            .turn_service
+            .create(&other.id, "hidden", None)
+            .await
+            .unwrap();
+        let turn = state
+            .turn_service
+            .create(&own.id, "hello", None)
+            .await
+            .unwrap();
+
+        let event = next_json(&mut socket).await;
+        assert_eq!(event["type"], "event");
+        assert_eq!(event["topic"], "turn:created");
+        assert_eq!(event["data"]["tenant_id"], "dev-tenant");
+        assert_eq!(event["data"]["data"]["id"], turn.id.as_str());
+    }
+
+    #[test]
+    fn test_is_pong_overdue() {
+        let timeout = Duration::from_secs(90);
+        assert!(!is_pong_overdue(0, 90_000, timeout));
+        assert!(is_pong_overdue(0, 90_001, timeout));
+        assert!(!is_pong_overdue(60_000, 120_000, timeout));
+        // Clock never goes backwards, but a late store must not underflow
+        assert!(!is_pong_overdue(5_000, 4_000, timeout));
+    }
+
+    #[test]
+    fn test_topic_matches_namespace_wildcard() {
+        assert!(topic_matches(topics::MEMORY_ALL, topics::MEMORY_CREATED));
+        assert!(topic_matches(topics::MEMORY_ALL, topics::MEMORY_DELETED));
+        assert!(!topic_matches(topics::MEMORY_ALL, topics::PROFILE_UPDATED));
+        assert!(!topic_matches(topics::MEMORY_ALL, "memory"));
+        assert!(!topic_matches(topics::MEMORY_ALL, "memoryx:created"));
+        assert!(topic_matches("memory:created", "memory:created"));
+        assert!(!topic_matches("memory:created", "memory:updated"));
+    }
+
+    #[test]
+    fn test_topic_matches_all_events() {
+        for topic in [
+            topics::MEMORY_CREATED,
+            topics::PROFILE_UPDATED,
+            topics::PATTERN_CREATED,
+            "entity:relationship:created",
+        ] {
+            assert!(topic_matches(topics::ALL_EVENTS, topic));
+            assert!(topic_matches("*", topic));
+        }
+    }
+
+    #[test]
+    fn test_subscription_message_decoding() {
+        let msg = SubscriptionMessage::from_text(
+            r#"{"action": "subscribe", "topics": ["memory:*"], "encoding": "msgpack"}"#,
+        )
+        .unwrap();
+        assert_eq!(msg.encoding, Some(Encoding::MessagePack));
+
+        let bytes = rmp_serde::to_vec_named(&msg).unwrap();
+        assert!(is_msgpack_map_header(bytes[0]));
+        let decoded = SubscriptionMessage::from_binary(&bytes).unwrap();
+        assert_eq!(decoded.action, "subscribe");
+        assert_eq!(decoded.topics, vec!["memory:*".to_string()]);
+        assert_eq!(decoded.encoding, Some(Encoding::MessagePack));
+
+        // JSON sent in a binary frame is still accepted
+        let decoded = SubscriptionMessage::from_binary(br#"{"action": "ping"}"#).unwrap();
+        assert_eq!(decoded.action, "ping");
+        assert!(decoded.topics.is_empty());
+        assert_eq!(decoded.encoding, None);
+    }
+
+    #[test]
+    fn test_websocket_message_encoding() {
+        let message = WebSocketMessage {
+            r#type: "event".to_string(),
+            topic: "memory:created".to_string(),
+            data: serde_json::json!({ "id": "mem_1", "importance": 0.8 }),
+        };
+
+        let Message::Text(text) = message.encode(Encoding::Json).unwrap() else {
+            panic!("expected a text frame");
+        };
+        let Message::Binary(bytes) = message.encode(Encoding::MessagePack).unwrap() else {
+            panic!("expected a binary frame");
+        };
+        assert!(bytes.len() < text.len());
+
+        let decoded: WebSocketMessage = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(decoded.topic, "memory:created");
+        assert_eq!(decoded.data["id"], "mem_1");
+    }
 }